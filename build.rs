@@ -105,8 +105,10 @@ fn main() {
     println!("cargo:rerun-if-changed=app.rc");
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=build_support/model_catalog.rs");
+    println!("cargo:rerun-if-changed=build_support/model_catalog/pricing.rs");
     println!("cargo:rerun-if-changed=build_support/model_catalog_validation.rs");
     println!("cargo:rerun-if-changed=build_support/model_catalog_validation/presentation.rs");
+    println!("cargo:rerun-if-changed=build_support/model_catalog_validation/pricing.rs");
     println!("cargo:rerun-if-changed=build_support/model_catalog_validation/vision.rs");
     println!("cargo:rerun-if-changed=build_support/creation_runtime_delivery.rs");
     println!("cargo:rerun-if-changed=build_support/delivery_channel.rs");
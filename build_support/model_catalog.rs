@@ -1,6 +1,8 @@
 use std::fs;
 use std::path::Path;
 
+#[path = "model_catalog/pricing.rs"]
+mod pricing;
 #[path = "model_catalog_validation.rs"]
 mod validation;
 
@@ -136,6 +138,8 @@ pub(crate) fn generate(manifest_path: &Path, output_path: &Path) {
     lines.push("}".to_string());
     lines.push(String::new());
 
+    pricing::push_pricing_lookups(&mut lines, &manifest);
+
     lines.push(
         "pub fn generated_ordinary_reasoning_policy(provider: &str, api_model: &str) -> OrdinaryReasoningPolicy {"
             .to_string(),
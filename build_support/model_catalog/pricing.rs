@@ -0,0 +1,66 @@
+//! List-price lookups generated from Live endpoints and model profiles.
+
+use super::{manifest_object, rust_string};
+
+pub(super) fn push_pricing_lookups(lines: &mut Vec<String>, manifest: &serde_json::Value) {
+    lines.push(
+        "pub fn generated_endpoint_pricing(api_model: &str) -> Option<TokenPricing> {".to_string(),
+    );
+    lines.push("    match api_model {".to_string());
+    for (endpoint, metadata) in manifest_object(manifest, "endpoints") {
+        if let Some(pricing) = token_pricing_literal(metadata) {
+            lines.push(format!(
+                "        {} => Some({pricing}),",
+                rust_string(endpoint)
+            ));
+        }
+    }
+    lines.push("        _ => None,".to_string());
+    lines.push("    }".to_string());
+    lines.push("}".to_string());
+    lines.push(String::new());
+
+    lines.push(
+        "pub fn generated_model_pricing(provider: &str, api_model: &str) -> Option<TokenPricing> {"
+            .to_string(),
+    );
+    lines.push("    match (provider, api_model) {".to_string());
+    for (profile_key, profile) in manifest_object(manifest, "model_profiles") {
+        let Some(pricing) = token_pricing_literal(profile) else {
+            continue;
+        };
+        let (provider, api_model) = profile_key
+            .split_once(':')
+            .expect("model profile keys must be provider:api_model");
+        lines.push(format!(
+            "        ({}, {}) => Some({pricing}),",
+            rust_string(provider),
+            rust_string(api_model),
+        ));
+    }
+    lines.push("        _ => None,".to_string());
+    lines.push("    }".to_string());
+    lines.push("}".to_string());
+    lines.push(String::new());
+}
+
+/// `TokenPricing` literal for an entry's `pricing_usd_per_million_tokens`.
+fn token_pricing_literal(entry: &serde_json::Value) -> Option<String> {
+    let pricing = entry
+        .get("pricing_usd_per_million_tokens")
+        .and_then(serde_json::Value::as_object)?;
+    let rate = |key: &str| {
+        pricing
+            .get(key)
+            .and_then(serde_json::Value::as_f64)
+            .unwrap_or_else(|| panic!("pricing key {key:?} must be a number"))
+    };
+    Some(format!(
+        "TokenPricing {{ input_text: {:?}, input_audio: {:?}, input_image: {:?}, output_text: {:?}, output_audio: {:?} }}",
+        rate("input_text"),
+        rate("input_audio"),
+        rate("input_image"),
+        rate("output_text"),
+        rate("output_audio"),
+    ))
+}
@@ -2,6 +2,8 @@ use std::collections::{HashMap, HashSet};
 
 #[path = "model_catalog_validation/presentation.rs"]
 mod presentation;
+#[path = "model_catalog_validation/pricing.rs"]
+mod pricing;
 #[path = "model_catalog_validation/vision.rs"]
 mod vision;
 
@@ -44,6 +46,7 @@ pub(super) fn validate(manifest: &serde_json::Value) {
         used_profiles.insert(profile_key.clone());
         validate_presentation(model, profile);
         validate_reasoning_policy(id, provider, profile);
+        pricing::validate_pricing(&profile_key, profile);
         for language in ["name_vi", "name_ko", "name_en"] {
             let name =
                 presentation::localized_name(model, profile, presentation_variants, language);
@@ -173,6 +176,7 @@ fn validate_presentation(
         "search_tool_enabled_by_default",
         "intelligence_tier",
         "reasoning_policy",
+        "pricing_usd_per_million_tokens",
     ] {
         assert!(
             model.get(field).is_none(),
@@ -412,6 +416,7 @@ fn validate_endpoints(manifest: &serde_json::Value, models: &[serde_json::Value]
             );
         }
         validate_live_profile(endpoint, metadata);
+        pricing::validate_pricing(endpoint, metadata);
     }
 
     let forbidden = |endpoint: &str| {
@@ -0,0 +1,36 @@
+//! List prices used for session cost accounting and cost-aware routing, on
+//! Live endpoints and on model profiles alike.
+
+pub(super) fn validate_pricing(
+    endpoint: &str,
+    metadata: &serde_json::Map<String, serde_json::Value>,
+) {
+    const RATES: [&str; 5] = [
+        "input_text",
+        "input_audio",
+        "input_image",
+        "output_text",
+        "output_audio",
+    ];
+    let Some(pricing) = metadata.get("pricing_usd_per_million_tokens") else {
+        return;
+    };
+    let pricing = pricing.as_object().unwrap_or_else(|| {
+        panic!("pricing_usd_per_million_tokens for {endpoint} must be an object")
+    });
+    for key in pricing.keys() {
+        assert!(
+            RATES.contains(&key.as_str()),
+            "unknown pricing key {key:?} for {endpoint}"
+        );
+    }
+    for key in RATES {
+        assert!(
+            pricing
+                .get(key)
+                .and_then(serde_json::Value::as_f64)
+                .is_some_and(|rate| rate.is_finite() && rate >= 0.0),
+            "pricing {key} for {endpoint} must be a non-negative number"
+        );
+    }
+}
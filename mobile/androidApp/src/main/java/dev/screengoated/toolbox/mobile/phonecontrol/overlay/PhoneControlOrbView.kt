@@ -166,6 +166,7 @@ internal class PhoneControlOrbView(
         .use { it.readText() }
         .replace("/*FONT_CSS*/", PHONE_CONTROL_ORB_RENDERER_CSS)
         .replace("/*CMD_PLACEHOLDER*/", "")
+        .replace("/*UNDO_LABEL*/", "")
 
     private fun applyVisual(
         next: PhoneControlOverlayVisual,
@@ -212,6 +212,9 @@ internal object PhoneControlToolRegistry {
             "android_app_api",
             PhoneControlHandler.EDIT_TEXT_FILE_STRUCTURE,
         ),
+        unavailable("read_office_file", "file_resource_access", "android_app_api"),
+        unavailable("edit_office_file", "file_resource_access", "android_app_api"),
+        unavailable("undo_file_edit", "file_resource_access", "android_app_api"),
         realWithProviders(
             "run_command",
             "command_execution",
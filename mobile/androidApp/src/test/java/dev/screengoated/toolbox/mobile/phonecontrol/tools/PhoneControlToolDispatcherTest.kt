@@ -24,7 +24,7 @@ class PhoneControlToolDispatcherTest {
     @Test
     fun everyGeneratedCatalogToolDispatchesExactlyOrReturnsTypedUnavailable() = runTest {
         val generatedNames = generatedCatalogNames()
-        assertEquals(62, generatedNames.size)
+        assertEquals(65, generatedNames.size)
         assertEquals(generatedNames.size, generatedNames.toSet().size)
         assertEquals(generatedNames, PhoneControlToolRegistry.specs.map { it.name })
 
@@ -9,7 +9,7 @@ const PROMPT_CORE: &str =
 const TOOL_CATALOG: &str =
     include_str!("../../../src/overlay/computer_control/phone_control_catalog.json");
 const PLATFORM_DEVICE_TOKEN: &str = "{{PLATFORM_DEVICE}}";
-const STATIC_TOOL_COUNT: usize = 62;
+const STATIC_TOOL_COUNT: usize = 65;
 const CONTROLLER_RULES: &str = "ROUTING: highest-fidelity evidence. Accessible: observe, then act on current @id. Pixel-only: vision targets/marks. Prefer direct browser/system/file/integration providers. Raw input needs known focus/effect. Change route after typed failure.";
 const SESSION_RULES: &str = "Interpret communicative intent, not grammatical form. If the requested outcome is too uncertain to choose an effect safely, ask one concise clarification and do not act.";
 
@@ -10,7 +10,7 @@ from typing import Any
 
 
 SCHEMA_VERSION = 1
-STATIC_DECLARATION_COUNT = 62
+STATIC_DECLARATION_COUNT = 65
 ASSET_PATH = "phone_control/catalog.json"
 PROMPT_ASSET_PATH = "phone_control/prompt_core.txt"
 AUTHORITY_ASSET_PATH = "phone_control/authority-matrix.json"
@@ -48,7 +48,7 @@ FORBIDDEN_NAME_TERMS = {
 PROFILE_FIELDS = (
     "name_vi", "name_ko", "name_en", "quota_vi", "quota_ko", "quota_en",
     "supports_search", "search_tool_enabled_by_default", "intelligence_tier",
-    "reasoning_policy",
+    "reasoning_policy", "pricing_usd_per_million_tokens",
 )
 
 
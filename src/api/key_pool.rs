@@ -0,0 +1,301 @@
+//! Rotation across several API keys for one provider.
+//!
+//! `Config::api_key_pool` holds extra keys; with the primary key they form one
+//! pool per provider. `provider_credentials::resolve` leases the next usable
+//! key on every call and remembers, on the calling thread, which key it handed
+//! out. A rate-limit or billing failure then benches that key alone, and the
+//! provider's models enter their own cooldown only once every key is benched.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::config::types::ApiKeyRotation;
+
+pub(crate) const PRIMARY_LABEL: &str = "primary";
+
+/// Providers whose credential can rotate, by their environment override.
+const POOLED_CREDENTIALS: &[(&str, &str)] = &[
+    ("GROQ_API_KEY", "groq"),
+    ("GEMINI_API_KEY", "google"),
+    ("OPENROUTER_API_KEY", "openrouter"),
+    ("NVIDIA_API_KEY", "nvidia"),
+];
+
+struct PoolKey {
+    label: String,
+    key: String,
+    requests: u64,
+    rate_limits: u64,
+    last_limited: Option<Instant>,
+    benched_until: Option<Instant>,
+}
+
+impl PoolKey {
+    fn ready(&self, now: Instant) -> bool {
+        self.benched_until.is_none_or(|until| until <= now)
+    }
+}
+
+struct Pool {
+    /// The primary key as saved, so a caller passing some other key is left alone.
+    primary: String,
+    keys: Vec<PoolKey>,
+    next: usize,
+}
+
+impl Pool {
+    fn pick(&self, rotation: ApiKeyRotation, now: Instant) -> usize {
+        let count = self.keys.len();
+        let mut ready = (0..count)
+            .map(|offset| (self.next + offset) % count)
+            .filter(|index| self.keys[*index].ready(now));
+        let choice = match rotation {
+            ApiKeyRotation::RoundRobin => ready.next(),
+            // `None` sorts first, so a key that was never limited wins; ties
+            // keep the round-robin order.
+            ApiKeyRotation::LeastRecentlyLimited => {
+                ready.min_by_key(|index| self.keys[*index].last_limited)
+            }
+        };
+        // With every key benched, send the one that reopens first; its failure
+        // is what lets the model cooldown take over.
+        choice.unwrap_or_else(|| {
+            (0..count)
+                .min_by_key(|index| self.keys[*index].benched_until)
+                .unwrap_or(0)
+        })
+    }
+}
+
+/// Per-key counters for the usage statistics page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct KeyUsage {
+    pub(crate) label: String,
+    pub(crate) requests: u64,
+    pub(crate) rate_limits: u64,
+    pub(crate) cooling: Option<Duration>,
+}
+
+#[derive(Default)]
+struct Pools {
+    rotation: ApiKeyRotation,
+    providers: HashMap<String, Pool>,
+}
+
+impl Pools {
+    /// Rebuilds every pool from the settings. A key keeps its counters and
+    /// cooldown while its label and value are unchanged.
+    fn configure(&mut self, config: &Config) {
+        self.rotation = config.api_key_rotation;
+        let mut previous = std::mem::take(&mut self.providers);
+        let primaries = [
+            ("groq", &config.api_key),
+            ("google", &config.gemini_api_key),
+            ("openrouter", &config.openrouter_api_key),
+            ("nvidia", &config.nvidia_api_key),
+        ];
+        for (provider, primary) in primaries {
+            let extra = config
+                .api_key_pool
+                .iter()
+                .filter(|entry| entry.provider == provider && !entry.key.trim().is_empty());
+            let entries = std::iter::once((PRIMARY_LABEL, primary.as_str()))
+                .chain(extra.map(|entry| (entry.label.trim(), entry.key.as_str())));
+            let mut old = previous
+                .remove(provider)
+                .map(|pool| pool.keys)
+                .unwrap_or_default();
+            let mut keys: Vec<PoolKey> = Vec::new();
+            for (index, (label, key)) in entries.enumerate() {
+                let key = key.trim();
+                if key.is_empty() {
+                    continue;
+                }
+                let mut label = if label.is_empty() {
+                    format!("key {index}")
+                } else {
+                    label.to_string()
+                };
+                if keys.iter().any(|existing| existing.label == label) {
+                    label = format!("{label} #{index}");
+                }
+                let state = match old
+                    .iter()
+                    .position(|existing| existing.label == label && existing.key == key)
+                {
+                    Some(position) => old.swap_remove(position),
+                    None => PoolKey {
+                        label,
+                        key: key.to_string(),
+                        requests: 0,
+                        rate_limits: 0,
+                        last_limited: None,
+                        benched_until: None,
+                    },
+                };
+                keys.push(state);
+            }
+            // A lone primary key needs no pool; it resolves exactly as before.
+            if keys.iter().any(|key| key.label != PRIMARY_LABEL) {
+                self.providers.insert(
+                    provider.to_string(),
+                    Pool {
+                        primary: primary.trim().to_string(),
+                        keys,
+                        next: 0,
+                    },
+                );
+            }
+        }
+    }
+
+    /// The next key for `provider` as `(label, key)`, when `saved` is the
+    /// pool's primary key or empty.
+    fn lease(&mut self, provider: &str, saved: &str, now: Instant) -> Option<(String, String)> {
+        let rotation = self.rotation;
+        let pool = self.providers.get_mut(provider)?;
+        let saved = saved.trim();
+        if !saved.is_empty() && saved != pool.primary {
+            return None;
+        }
+        let index = pool.pick(rotation, now);
+        pool.next = (index + 1) % pool.keys.len();
+        let key = &mut pool.keys[index];
+        key.requests += 1;
+        Some((key.label.clone(), key.key.clone()))
+    }
+
+    /// Benches one key and reports whether another key is ready right now.
+    fn bench(&mut self, provider: &str, label: &str, cooldown: Duration, now: Instant) -> bool {
+        let Some(pool) = self.providers.get_mut(provider) else {
+            return false;
+        };
+        let Some(key) = pool.keys.iter_mut().find(|key| key.label == label) else {
+            return false;
+        };
+        key.rate_limits += 1;
+        key.last_limited = Some(now);
+        key.benched_until = Some(now + cooldown);
+        pool.keys
+            .iter()
+            .any(|key| key.label != label && key.ready(now))
+    }
+
+    fn usage(&self, provider: &str, now: Instant) -> Vec<KeyUsage> {
+        let Some(pool) = self.providers.get(provider) else {
+            return Vec::new();
+        };
+        pool.keys
+            .iter()
+            .map(|key| KeyUsage {
+                label: key.label.clone(),
+                requests: key.requests,
+                rate_limits: key.rate_limits,
+                cooling: key
+                    .benched_until
+                    .filter(|until| *until > now)
+                    .map(|until| until.saturating_duration_since(now)),
+            })
+            .collect()
+    }
+}
+
+static POOLS: LazyLock<Mutex<Pools>> = LazyLock::new(|| Mutex::new(Pools::default()));
+
+thread_local! {
+    /// Provider → label of the key this thread was last given.
+    static LEASES: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+fn pools() -> MutexGuard<'static, Pools> {
+    POOLS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The pool name for a retry-chain provider; Gemini Live shares Google's keys.
+pub(crate) fn pool_provider(provider: &str) -> &str {
+    match provider {
+        "gemini-live" => "google",
+        other => other,
+    }
+}
+
+pub(crate) fn configure(config: &Config) {
+    pools().configure(config);
+}
+
+fn pooled_provider(env_name: &str) -> Option<&'static str> {
+    POOLED_CREDENTIALS
+        .iter()
+        .find(|(name, _)| *name == env_name)
+        .map(|(_, provider)| *provider)
+}
+
+/// Leases a pooled key for the credential named by `env_name`. Without one,
+/// the thread's earlier lease is dropped, so a failure of this request cannot
+/// bench a key it never used.
+pub(super) fn lease(env_name: &str, saved: &str) -> Option<String> {
+    let provider = pooled_provider(env_name)?;
+    let Some((label, key)) = pools().lease(provider, saved, Instant::now()) else {
+        forget(env_name);
+        return None;
+    };
+    LEASES.with_borrow_mut(|leases| leases.insert(provider.to_string(), label));
+    Some(key)
+}
+
+/// Drops this thread's lease for the credential named by `env_name`.
+pub(super) fn forget(env_name: &str) {
+    if let Some(provider) = pooled_provider(env_name) {
+        LEASES.with_borrow_mut(|leases| leases.remove(provider));
+    }
+}
+
+/// Whether the credential named by `env_name` has any pooled key.
+pub(super) fn has_keys(env_name: &str) -> bool {
+    pooled_provider(env_name).is_some_and(|provider| pools().providers.contains_key(provider))
+}
+
+/// Label of the key this thread last leased for `provider`.
+pub(crate) fn leased_label(provider: &str) -> Option<String> {
+    let provider = pool_provider(provider);
+    if !pools().providers.contains_key(provider) {
+        return None;
+    }
+    LEASES.with_borrow(|leases| leases.get(provider).cloned())
+}
+
+/// Labels of every key in `provider`'s pool; empty without a pool.
+pub(crate) fn labels(provider: &str) -> Vec<String> {
+    pools()
+        .providers
+        .get(pool_provider(provider))
+        .map(|pool| pool.keys.iter().map(|key| key.label.clone()).collect())
+        .unwrap_or_default()
+}
+
+/// Benches the key this thread last leased for `provider`. Returns true when
+/// another key is ready, so the same model can be retried at once.
+pub(crate) fn bench_leased(provider: &str, cooldown: Duration) -> bool {
+    let provider = pool_provider(provider);
+    let Some(label) = leased_label(provider) else {
+        return false;
+    };
+    let another_ready = pools().bench(provider, &label, cooldown, Instant::now());
+    crate::log_info!(
+        "[key-pool] {provider} key '{label}' benched for {}s; another key ready: {another_ready}",
+        cooldown.as_secs()
+    );
+    another_ready
+}
+
+pub(crate) fn usage(provider: &str) -> Vec<KeyUsage> {
+    pools().usage(pool_provider(provider), Instant::now())
+}
+
+#[cfg(test)]
+mod tests;
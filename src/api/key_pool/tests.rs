@@ -0,0 +1,142 @@
+use super::*;
+use crate::config::types::PooledApiKey;
+
+fn config(rotation: ApiKeyRotation, extra: &[(&str, &str)]) -> Config {
+    Config {
+        api_key: "gsk-primary".to_string(),
+        api_key_pool: extra
+            .iter()
+            .map(|(label, key)| PooledApiKey {
+                id: label.to_string(),
+                provider: "groq".to_string(),
+                label: label.to_string(),
+                key: key.to_string(),
+            })
+            .collect(),
+        api_key_rotation: rotation,
+        ..Config::default()
+    }
+}
+
+fn pools(rotation: ApiKeyRotation, extra: &[(&str, &str)]) -> Pools {
+    let mut pools = Pools::default();
+    pools.configure(&config(rotation, extra));
+    pools
+}
+
+fn lease_label(pools: &mut Pools, now: Instant) -> String {
+    pools.lease("groq", "gsk-primary", now).unwrap().0
+}
+
+#[test]
+fn lone_primary_key_makes_no_pool() {
+    let mut pools = pools(ApiKeyRotation::RoundRobin, &[("spare", "  ")]);
+    assert!(pools.providers.is_empty());
+    assert_eq!(pools.lease("groq", "gsk-primary", Instant::now()), None);
+}
+
+#[test]
+fn round_robin_takes_keys_in_turn_and_skips_benched_ones() {
+    let now = Instant::now();
+    let mut pools = pools(
+        ApiKeyRotation::RoundRobin,
+        &[("team", "gsk-team"), ("spare", "gsk-spare")],
+    );
+    let order: Vec<String> = (0..4).map(|_| lease_label(&mut pools, now)).collect();
+    assert_eq!(order, ["primary", "team", "spare", "primary"]);
+
+    assert!(pools.bench("groq", "team", Duration::from_secs(60), now));
+    assert_eq!(lease_label(&mut pools, now), "spare");
+    assert_eq!(lease_label(&mut pools, now), "primary");
+    assert_eq!(
+        lease_label(&mut pools, now + Duration::from_secs(61)),
+        "team"
+    );
+}
+
+#[test]
+fn least_recently_limited_prefers_keys_that_were_never_limited() {
+    let now = Instant::now();
+    let mut pools = pools(
+        ApiKeyRotation::LeastRecentlyLimited,
+        &[("team", "gsk-team"), ("spare", "gsk-spare")],
+    );
+    pools.bench("groq", "primary", Duration::from_secs(1), now);
+    pools.bench(
+        "groq",
+        "team",
+        Duration::from_secs(1),
+        now + Duration::from_secs(1),
+    );
+    let later = now + Duration::from_secs(5);
+    assert_eq!(lease_label(&mut pools, later), "spare");
+    assert_eq!(lease_label(&mut pools, later), "spare");
+
+    pools.bench("groq", "spare", Duration::from_secs(1), later);
+    let latest = later + Duration::from_secs(5);
+    assert_eq!(lease_label(&mut pools, latest), "primary");
+}
+
+#[test]
+fn bench_reports_whether_another_key_is_ready() {
+    let now = Instant::now();
+    let mut pools = pools(ApiKeyRotation::RoundRobin, &[("team", "gsk-team")]);
+    assert!(pools.bench("groq", "primary", Duration::from_secs(30), now));
+    assert!(!pools.bench("groq", "team", Duration::from_secs(60), now));
+    assert!(!pools.bench("groq", "unknown", Duration::from_secs(60), now));
+
+    // Every key benched: the one that reopens first is sent.
+    assert_eq!(lease_label(&mut pools, now), "primary");
+}
+
+#[test]
+fn reconfiguring_keeps_the_state_of_unchanged_keys() {
+    let now = Instant::now();
+    let mut pools = pools(ApiKeyRotation::RoundRobin, &[("team", "gsk-team")]);
+    lease_label(&mut pools, now);
+    pools.bench("groq", "team", Duration::from_secs(60), now);
+
+    pools.configure(&config(
+        ApiKeyRotation::RoundRobin,
+        &[("team", "gsk-team"), ("", "gsk-new")],
+    ));
+    let usage = pools.usage("groq", now);
+    let labels: Vec<&str> = usage.iter().map(|key| key.label.as_str()).collect();
+    assert_eq!(labels, ["primary", "team", "key 2"]);
+    assert_eq!(usage[0].requests, 1);
+    assert_eq!(usage[1].rate_limits, 1);
+    assert_eq!(usage[1].cooling, Some(Duration::from_secs(60)));
+
+    pools.configure(&config(
+        ApiKeyRotation::RoundRobin,
+        &[("team", "gsk-rotated")],
+    ));
+    let usage = pools.usage("groq", now);
+    assert_eq!(usage[1].rate_limits, 0);
+    assert_eq!(usage[1].cooling, None);
+}
+
+#[test]
+fn duplicate_labels_are_made_distinct() {
+    let pools = pools(
+        ApiKeyRotation::RoundRobin,
+        &[("team", "gsk-a"), ("team", "gsk-b")],
+    );
+    let labels: Vec<String> = pools
+        .usage("groq", Instant::now())
+        .into_iter()
+        .map(|key| key.label)
+        .collect();
+    assert_eq!(labels, ["primary", "team", "team #2"]);
+}
+
+#[test]
+fn a_key_other_than_the_saved_primary_is_not_replaced() {
+    let mut pools = pools(ApiKeyRotation::RoundRobin, &[("team", "gsk-team")]);
+    let now = Instant::now();
+    assert_eq!(pools.lease("groq", "gsk-someone-else", now), None);
+    assert_eq!(
+        pools.lease("groq", "", now),
+        Some(("primary".to_string(), "gsk-primary".to_string()))
+    );
+}
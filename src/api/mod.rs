@@ -5,6 +5,7 @@ pub mod gemini_generate;
 pub mod gemini_live;
 mod gemini_schema;
 pub mod groq;
+pub(crate) mod key_pool;
 pub mod ollama;
 pub mod openai_compat;
 pub(crate) mod provider_credentials;
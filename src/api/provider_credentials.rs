@@ -1,22 +1,81 @@
 #[cfg(test)]
 use std::cell::RefCell;
 
+use crate::config::Config;
+
 #[cfg(test)]
 thread_local! {
     static OVERRIDE: RefCell<Option<(String, String)>> = const { RefCell::new(None) };
 }
 
+/// The credential for one provider: a non-empty environment variable wins,
+/// then a key leased from the provider's pool (see `key_pool`), then `saved`.
 pub(crate) fn resolve(env_name: &str, saved: &str) -> String {
     #[cfg(test)]
-    if let Some(value) = OVERRIDE.with_borrow(|entry| {
+    if let Some(value) = overridden(env_name) {
+        return value;
+    }
+    let environment = std::env::var(env_name).ok();
+    if environment
+        .as_deref()
+        .is_none_or(|value| value.trim().is_empty())
+    {
+        if let Some(key) = super::key_pool::lease(env_name, saved) {
+            return key;
+        }
+    } else {
+        super::key_pool::forget(env_name);
+    }
+    resolve_value(environment, saved)
+}
+
+/// Like [`resolve`], reading the saved key from the running app's settings.
+/// Call it only on the path that sends the request, so a pooled key is
+/// leased, and counted, for the provider that actually serves it.
+pub(crate) fn resolve_from_app(env_name: &str, saved: fn(&Config) -> &str) -> String {
+    let saved = crate::APP
+        .lock()
+        .ok()
+        .map(|app| saved(&app.config).to_string())
+        .unwrap_or_default();
+    resolve(env_name, &saved)
+}
+
+/// The Groq and Gemini keys for one request to `provider`. Only the provider
+/// being called leases from its pool; the other key is passed as saved, so a
+/// failure benches the key that request used.
+pub(crate) fn for_attempt(provider: &str, config: &Config) -> (String, String) {
+    match super::key_pool::pool_provider(provider) {
+        "groq" => (
+            resolve("GROQ_API_KEY", &config.api_key),
+            config.gemini_api_key.clone(),
+        ),
+        "google" => (
+            config.api_key.clone(),
+            resolve("GEMINI_API_KEY", &config.gemini_api_key),
+        ),
+        _ => (config.api_key.clone(), config.gemini_api_key.clone()),
+    }
+}
+
+/// Whether a credential is available, without leasing a pooled key.
+pub(crate) fn present(env_name: &str, saved: &str) -> bool {
+    #[cfg(test)]
+    if let Some(value) = overridden(env_name) {
+        return !value.trim().is_empty();
+    }
+    super::key_pool::has_keys(env_name)
+        || !resolve_value(std::env::var(env_name).ok(), saved).is_empty()
+}
+
+#[cfg(test)]
+fn overridden(env_name: &str) -> Option<String> {
+    OVERRIDE.with_borrow(|entry| {
         entry
             .as_ref()
             .filter(|(name, _)| name == env_name)
             .map(|(_, value)| value.clone())
-    }) {
-        return value;
-    }
-    resolve_value(std::env::var(env_name).ok(), saved)
+    })
 }
 
 #[cfg(test)]
@@ -46,7 +105,8 @@ fn resolve_value(environment: Option<String>, saved: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{resolve, resolve_value, with_override};
+    use super::{for_attempt, resolve, resolve_value, with_override};
+    use crate::config::Config;
 
     #[test]
     fn nonempty_environment_value_has_priority() {
@@ -71,4 +131,29 @@ mod tests {
         assert_eq!(during, "rotated");
         assert_eq!(resolve("SGT_TEST_CREDENTIAL", "saved"), before);
     }
+
+    #[test]
+    fn an_attempt_resolves_only_the_called_providers_key() {
+        let config = Config {
+            api_key: "saved-groq".to_string(),
+            gemini_api_key: "saved-gemini".to_string(),
+            ..Config::default()
+        };
+        with_override("GROQ_API_KEY", "resolved-groq", || {
+            assert_eq!(
+                for_attempt("groq", &config),
+                ("resolved-groq".to_string(), "saved-gemini".to_string())
+            );
+            assert_eq!(
+                for_attempt("openrouter", &config),
+                ("saved-groq".to_string(), "saved-gemini".to_string())
+            );
+        });
+        with_override("GEMINI_API_KEY", "resolved-gemini", || {
+            assert_eq!(
+                for_attempt("gemini-live", &config),
+                ("saved-groq".to_string(), "resolved-gemini".to_string())
+            );
+        });
+    }
 }
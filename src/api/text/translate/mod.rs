@@ -63,36 +63,6 @@ where
         target_language,
     } = request;
 
-    let saved_openrouter_api_key = crate::APP
-        .lock()
-        .ok()
-        .and_then(|app| {
-            let config = app.config.clone();
-            if config.openrouter_api_key.is_empty() {
-                None
-            } else {
-                Some(config.openrouter_api_key.clone())
-            }
-        })
-        .unwrap_or_default();
-    let openrouter_api_key =
-        crate::api::provider_credentials::resolve("OPENROUTER_API_KEY", &saved_openrouter_api_key);
-
-    let saved_nvidia_api_key = crate::APP
-        .lock()
-        .ok()
-        .and_then(|app| {
-            let config = app.config.clone();
-            if config.nvidia_api_key.is_empty() {
-                None
-            } else {
-                Some(config.nvidia_api_key.clone())
-            }
-        })
-        .unwrap_or_default();
-    let nvidia_api_key =
-        crate::api::provider_credentials::resolve("NVIDIA_API_KEY", &saved_nvidia_api_key);
-
     let full_content;
     let prompt = format!("{}\n\n{}", instruction, text);
     let transport = TranslateTransportOptions {
@@ -209,6 +179,10 @@ where
         }
         Some(Provider::Nvidia) => {
             // --- NVIDIA NIM API ---
+            let nvidia_api_key =
+                crate::api::provider_credentials::resolve_from_app("NVIDIA_API_KEY", |config| {
+                    &config.nvidia_api_key
+                });
             full_content = translate_nvidia(
                 &nvidia_api_key,
                 &model,
@@ -220,6 +194,10 @@ where
         }
         Some(Provider::OpenRouter) => {
             // --- OPENROUTER API ---
+            let openrouter_api_key = crate::api::provider_credentials::resolve_from_app(
+                "OPENROUTER_API_KEY",
+                |config| &config.openrouter_api_key,
+            );
             full_content = translate_openrouter(
                 &openrouter_api_key,
                 &model,
@@ -165,21 +165,6 @@ where
         request_timeout,
     } = request;
 
-    let saved_openrouter_key = crate::APP
-        .lock()
-        .ok()
-        .map(|app| app.config.openrouter_api_key.clone())
-        .unwrap_or_default();
-    let openrouter_api_key =
-        super::provider_credentials::resolve("OPENROUTER_API_KEY", &saved_openrouter_key);
-
-    let saved_nvidia_key = crate::APP
-        .lock()
-        .ok()
-        .map(|app| app.config.nvidia_api_key.clone())
-        .unwrap_or_default();
-    let nvidia_api_key = super::provider_credentials::resolve("NVIDIA_API_KEY", &saved_nvidia_key);
-
     let prepare_started = Instant::now();
     let prepared_image = prepare_image_payload(
         provider.as_str(),
@@ -357,6 +342,10 @@ where
         )?;
     } else if Provider::from_wire(&provider) == Some(Provider::Nvidia) {
         // --- NVIDIA NIM API ---
+        let nvidia_api_key =
+            super::provider_credentials::resolve_from_app("NVIDIA_API_KEY", |config| {
+                &config.nvidia_api_key
+            });
         if nvidia_api_key.trim().is_empty() {
             return Err(anyhow::anyhow!("NO_API_KEY:nvidia"));
         }
@@ -395,6 +384,10 @@ where
         )?;
     } else if Provider::from_wire(&provider) == Some(Provider::OpenRouter) {
         // --- OPENROUTER API ---
+        let openrouter_api_key =
+            super::provider_credentials::resolve_from_app("OPENROUTER_API_KEY", |config| {
+                &config.openrouter_api_key
+            });
         if openrouter_api_key.trim().is_empty() {
             return Err(anyhow::anyhow!("NO_API_KEY:openrouter"));
         }
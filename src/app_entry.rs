@@ -41,6 +41,7 @@ pub(crate) fn run() -> eframe::Result<()> {
     }
 
     let startup_args = StartupArgs::collect();
+    crate::retry_model_chain::keep_state_in(&crate::paths::app_config_dir());
     if let Some(exit_code) = headless::run_pre_boot(&startup_args) {
         std::process::exit(exit_code);
     }
@@ -101,6 +102,7 @@ pub(crate) fn run() -> eframe::Result<()> {
     if let Err(error) = crate::component_registry::resume_pending_removals() {
         crate::log_info!("[Components] Pending removal maintenance failed: {error}");
     }
+    crate::component_registry::storage::enforce_quota_in_background();
     if let Err(error) = crate::component_registry::external_tools::reconcile_interrupted_installs()
     {
         crate::log_info!("[Components] External-tool staging maintenance failed: {error}");
@@ -129,6 +131,7 @@ pub(crate) fn run() -> eframe::Result<()> {
 
     if !isolated_ui_test {
         std::thread::spawn(crate::hotkey::run_hotkey_listener);
+        std::thread::spawn(crate::overlay::computer_control::run_trigger_scheduler);
     }
 
     crate::api::tts::init_tts();
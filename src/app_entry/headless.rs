@@ -13,6 +13,16 @@ const UIA_TASK_FLAG: &str = "--cc-uia-task";
 const MCP_TEST_FLAG: &str = "--cc-mcp-test";
 const SYSTEM_QUERY_TEST_FLAG: &str = "--cc-system-query-test";
 const TASK_TRACE_FLAG: &str = "--cc-task-trace";
+const TRACE_REPLAY_FLAG: &str = "--cc-trace-replay";
+const ROUTINE_SAVE_FLAG: &str = "--cc-routine-save";
+const ROUTINE_LIST_FLAG: &str = "--cc-routine-list";
+const ROUTINE_RUN_FLAG: &str = "--cc-routine-run";
+const TEXT_TASK_FLAG: &str = "--cc-text-task";
+const TRIGGER_LIST_FLAG: &str = "--cc-trigger-list";
+const TRIGGER_FIRE_FLAG: &str = "--cc-trigger-fire";
+const GOLDEN_EVAL_FLAG: &str = "--golden-eval";
+const COMPONENT_IMPORT_FLAG: &str = "--component-import";
+const COMPONENT_EXPORT_FLAG: &str = "--component-export";
 
 const POST_UNPACK_MODE_FLAGS: &[&str] = &[
     GT_NARRATION_TEST_FLAG,
@@ -27,6 +37,16 @@ const POST_UNPACK_MODE_FLAGS: &[&str] = &[
     MCP_TEST_FLAG,
     SYSTEM_QUERY_TEST_FLAG,
     TASK_TRACE_FLAG,
+    TRACE_REPLAY_FLAG,
+    ROUTINE_SAVE_FLAG,
+    ROUTINE_LIST_FLAG,
+    ROUTINE_RUN_FLAG,
+    TEXT_TASK_FLAG,
+    TRIGGER_LIST_FLAG,
+    TRIGGER_FIRE_FLAG,
+    GOLDEN_EVAL_FLAG,
+    COMPONENT_IMPORT_FLAG,
+    COMPONENT_EXPORT_FLAG,
 ];
 
 pub(crate) fn is_requested(args: &StartupArgs) -> bool {
@@ -197,6 +217,158 @@ pub(crate) fn run_after_bootstrap(args: &StartupArgs) -> Option<i32> {
         ));
     }
 
+    if args.has(TRACE_REPLAY_FLAG) {
+        let Some(trace) = args.value(TRACE_REPLAY_FLAG) else {
+            eprintln!(
+                "[trace-replay] ERROR: {TRACE_REPLAY_FLAG} needs a session events.jsonl path"
+            );
+            return Some(1);
+        };
+        let policy = args.value("--cc-policy");
+        let report = args.value("--cc-replay-report");
+        return Some(report_result(
+            crate::overlay::computer_control::run_trace_replay_cli(
+                &trace,
+                policy.as_deref(),
+                report.as_deref(),
+            ),
+            "trace-replay",
+        ));
+    }
+
+    if args.has(ROUTINE_SAVE_FLAG) {
+        let (Some(trace), Some(name)) = (
+            args.value(ROUTINE_SAVE_FLAG),
+            args.value("--cc-routine-name"),
+        ) else {
+            eprintln!(
+                "[routine] ERROR: {ROUTINE_SAVE_FLAG} needs an events.jsonl path and --cc-routine-name"
+            );
+            return Some(1);
+        };
+        let description = args.value("--cc-routine-description");
+        return Some(report_result(
+            crate::overlay::computer_control::run_routine_save_cli(
+                &trace,
+                &name,
+                description.as_deref(),
+            ),
+            "routine",
+        ));
+    }
+
+    if args.has(ROUTINE_LIST_FLAG) {
+        return Some(report_result(
+            crate::overlay::computer_control::run_routine_list_cli(),
+            "routine",
+        ));
+    }
+
+    if args.has(ROUTINE_RUN_FLAG) {
+        let Some(name) = args.value(ROUTINE_RUN_FLAG) else {
+            eprintln!("[routine] ERROR: {ROUTINE_RUN_FLAG} needs a routine name");
+            return Some(1);
+        };
+        let params_json = args.value("--cc-routine-params-json");
+        return Some(report_result(
+            crate::overlay::computer_control::run_routine_cli(&name, params_json.as_deref()),
+            "routine",
+        ));
+    }
+
+    if args.has(TEXT_TASK_FLAG) {
+        let Some(task) = args.value(TEXT_TASK_FLAG) else {
+            eprintln!("[cc-text] ERROR: {TEXT_TASK_FLAG} needs a task");
+            return Some(1);
+        };
+        let provider = args.value("--cc-text-provider");
+        let model = args.value("--cc-text-model");
+        let endpoint = args.value("--cc-text-endpoint");
+        let scripted = args.value("--cc-text-scripted");
+        return Some(report_result(
+            crate::overlay::computer_control::run_text_task_cli(
+                &task,
+                provider.as_deref(),
+                model.as_deref(),
+                endpoint.as_deref(),
+                scripted.as_deref(),
+            ),
+            "cc-text",
+        ));
+    }
+
+    if args.has(TRIGGER_LIST_FLAG) {
+        return Some(report_result(
+            crate::overlay::computer_control::run_trigger_list_cli(),
+            "trigger",
+        ));
+    }
+
+    if args.has(TRIGGER_FIRE_FLAG) {
+        let Some(name) = args.value(TRIGGER_FIRE_FLAG) else {
+            eprintln!("[trigger] ERROR: {TRIGGER_FIRE_FLAG} needs a trigger name");
+            return Some(1);
+        };
+        return Some(report_result(
+            crate::overlay::computer_control::run_trigger_fire_cli(&name),
+            "trigger",
+        ));
+    }
+
+    if args.has(GOLDEN_EVAL_FLAG) {
+        let Some(folder) = args.value(GOLDEN_EVAL_FLAG) else {
+            eprintln!("[golden-eval] ERROR: {GOLDEN_EVAL_FLAG} needs a golden-set folder");
+            return Some(1);
+        };
+        let list = |flag: &str| -> Vec<String> {
+            args.value(flag)
+                .map(|raw| {
+                    raw.split(',')
+                        .map(str::trim)
+                        .filter(|id| !id.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        return Some(report_result(
+            crate::golden_eval::run_cli(crate::golden_eval::EvalOptions {
+                folder: folder.into(),
+                preset_ids: list("--golden-eval-preset"),
+                model_ids: list("--golden-eval-model"),
+                judge_model: args.value("--golden-eval-judge"),
+                output: args.value("--golden-eval-out").map(Into::into),
+            }),
+            "golden-eval",
+        ));
+    }
+
+    if args.has(COMPONENT_IMPORT_FLAG) {
+        let Some(source) = args.value(COMPONENT_IMPORT_FLAG) else {
+            eprintln!(
+                "[components] ERROR: {COMPONENT_IMPORT_FLAG} needs a bundle directory or .zip"
+            );
+            return Some(1);
+        };
+        return Some(report_result(
+            crate::component_registry::sideload::run_import_cli(&source),
+            "components",
+        ));
+    }
+
+    if args.has(COMPONENT_EXPORT_FLAG) {
+        let Some(destination) = args.value(COMPONENT_EXPORT_FLAG) else {
+            eprintln!(
+                "[components] ERROR: {COMPONENT_EXPORT_FLAG} needs a new directory or .zip path"
+            );
+            return Some(1);
+        };
+        return Some(report_result(
+            crate::component_registry::sideload::run_export_cli(&destination),
+            "components",
+        ));
+    }
+
     super::replay::run(args)
 }
 
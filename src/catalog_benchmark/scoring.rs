@@ -1,4 +1,5 @@
-use unicode_normalization::UnicodeNormalization;
+use crate::text_metrics::normalize;
+pub use crate::text_metrics::{ocr_similarity, term_coverage, text_similarity};
 
 #[derive(Debug)]
 pub struct CoordinateScore {
@@ -58,38 +59,6 @@ pub fn transcription(response: &str) -> String {
         .to_string()
 }
 
-pub fn text_similarity(actual: &str, expected: &str) -> f64 {
-    let actual: Vec<char> = normalize(actual).chars().collect();
-    let expected: Vec<char> = normalize(expected).chars().collect();
-    character_similarity(&actual, &expected)
-}
-
-pub fn ocr_similarity(actual: &str, expected: &str) -> f64 {
-    let actual: Vec<char> = normalize_ocr(actual).chars().collect();
-    let expected: Vec<char> = normalize_ocr(expected).chars().collect();
-    character_similarity(&actual, &expected)
-}
-
-fn character_similarity(actual: &[char], expected: &[char]) -> f64 {
-    if actual.is_empty() && expected.is_empty() {
-        return 1.0;
-    }
-    let distance = levenshtein(actual, expected);
-    1.0 - distance as f64 / actual.len().max(expected.len()) as f64
-}
-
-pub fn term_coverage(actual: &str, terms: &[String]) -> f64 {
-    if terms.is_empty() {
-        return 1.0;
-    }
-    let actual = normalize(actual);
-    let found = terms
-        .iter()
-        .filter(|term| actual.contains(&normalize(term)))
-        .count();
-    found as f64 / terms.len() as f64
-}
-
 pub fn exact_coverage(actual: &str, fragments: &[String]) -> f64 {
     if fragments.is_empty() {
         return 1.0;
@@ -144,59 +113,6 @@ pub fn line_count_matches(actual: &str, expected: Option<usize>) -> f64 {
     f64::from(actual.lines().count() == expected)
 }
 
-fn normalize(value: &str) -> String {
-    let mut normalized = String::new();
-    let mut pending_space = false;
-    for character in value.nfkc().flat_map(char::to_lowercase) {
-        if character.is_alphanumeric() {
-            if pending_space && !normalized.is_empty() {
-                normalized.push(' ');
-            }
-            normalized.push(character);
-            pending_space = false;
-        } else {
-            pending_space = true;
-        }
-    }
-    normalized
-}
-
-fn normalize_ocr(value: &str) -> String {
-    let mut normalized = String::new();
-    let mut pending_space = false;
-    for character in value.nfkc() {
-        if character.is_whitespace() {
-            pending_space = true;
-            continue;
-        }
-        if pending_space && !normalized.is_empty() {
-            normalized.push(' ');
-        }
-        normalized.push(match character {
-            '‘' | '’' => '\'',
-            '“' | '”' => '"',
-            other => other,
-        });
-        pending_space = false;
-    }
-    normalized
-}
-
-fn levenshtein(left: &[char], right: &[char]) -> usize {
-    let mut previous: Vec<usize> = (0..=right.len()).collect();
-    let mut current = vec![0; right.len() + 1];
-    for (left_index, left_char) in left.iter().enumerate() {
-        current[0] = left_index + 1;
-        for (right_index, right_char) in right.iter().enumerate() {
-            current[right_index + 1] = (previous[right_index + 1] + 1)
-                .min(current[right_index] + 1)
-                .min(previous[right_index] + usize::from(left_char != right_char));
-        }
-        std::mem::swap(&mut previous, &mut current);
-    }
-    previous[right.len()]
-}
-
 fn parse_json_object(response: &str) -> Option<serde_json::Value> {
     serde_json::from_str(response.trim()).ok().or_else(|| {
         let start = response.find('{')?;
@@ -1,11 +1,10 @@
 use std::collections::HashSet;
-use std::io::{Read as _, Write as _};
+use std::io::Write as _;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{LazyLock, Mutex};
-use std::time::Duration;
 
-use anyhow::{Context, Result, anyhow, bail};
+use anyhow::{Result, anyhow, bail};
 
 use super::super::staging;
 use super::{
@@ -17,7 +16,6 @@ use crate::component_registry::receipt::{file_matches, is_reparse_point};
 
 static INSTALL_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
 static INSTALL_SEQUENCE: AtomicU64 = AtomicU64::new(0);
-const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10 * 60);
 
 pub(super) fn ensure(
     delivery: &'static EngineDelivery,
@@ -100,55 +98,15 @@ fn download(
     cancelled: &AtomicBool,
     on_progress: impl Fn(u64, u64),
 ) -> Result<()> {
-    let request = crate::api::client::UREQ_DOWNLOAD_AGENT
-        .get(delivery.download_url)
-        .config()
-        .https_only(true)
-        .timeout_global(Some(DOWNLOAD_TIMEOUT))
-        .build();
-    let response = request
-        .header("User-Agent", "ScreenGoatedToolbox")
-        .call()
-        .context("Computer Control engine download failed")?;
-    if response
-        .headers()
-        .get("content-length")
-        .and_then(|value| value.to_str().ok())
-        .and_then(|value| value.parse::<u64>().ok())
-        .is_some_and(|size| size != delivery.size_bytes)
-    {
-        bail!("Computer Control engine download size does not match this build");
-    }
-    let mut reader = response.into_body().into_reader();
-    let mut output = std::fs::OpenOptions::new()
-        .create_new(true)
-        .write(true)
-        .open(target)?;
-    let mut buffer = [0_u8; 128 * 1024];
-    let mut downloaded = 0_u64;
-    loop {
-        if cancelled.load(Ordering::Relaxed) {
-            bail!("Computer Control engine download was cancelled");
-        }
-        let read = reader.read(&mut buffer)?;
-        if read == 0 {
-            break;
-        }
-        downloaded = downloaded
-            .checked_add(read as u64)
-            .ok_or_else(|| anyhow!("Computer Control engine download is too large"))?;
-        if downloaded > delivery.size_bytes {
-            bail!("Computer Control engine download exceeds its exact size");
-        }
-        output.write_all(&buffer[..read])?;
-        on_progress(downloaded, delivery.size_bytes);
-    }
-    if downloaded != delivery.size_bytes {
-        bail!("Computer Control engine download is incomplete");
-    }
-    output.flush()?;
-    output.sync_all()?;
-    Ok(())
+    let download = crate::component_registry::transfer::Download {
+        label: "Computer Control engine",
+        url: delivery.download_url,
+        size_bytes: delivery.size_bytes,
+        sha256: delivery.sha256,
+    };
+    crate::component_registry::transfer::fetch(&download, target, cancelled, |downloaded| {
+        on_progress(downloaded, delivery.size_bytes)
+    })
 }
 
 fn validate_archive(delivery: &EngineDelivery, path: &Path) -> Result<()> {
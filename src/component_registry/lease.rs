@@ -30,11 +30,14 @@ pub(crate) fn acquire(id: &str) -> Result<ComponentLease> {
         anyhow::bail!("component removal is pending");
     }
     *state.counts.entry(id.to_string()).or_default() += 1;
+    drop(state);
+    super::usage::touch(id);
     Ok(ComponentLease { id: id.to_string() })
 }
 
 impl Drop for ComponentLease {
     fn drop(&mut self) {
+        super::usage::touch(&self.id);
         let owns_removal = {
             let mut state = LEASES.lock().unwrap_or_else(|value| value.into_inner());
             let Some(count) = state.counts.get_mut(&self.id) else {
@@ -102,6 +105,34 @@ pub(super) fn reserve_removal(id: &str) -> bool {
     }
 }
 
+/// Reserves removal of `id` only when nothing holds it, leaving no pending
+/// removal behind when it is busy.
+#[cfg(not(feature = "recorder-worker"))]
+pub(super) fn reserve_idle_removal(id: &str) -> bool {
+    let mut state = LEASES.lock().unwrap_or_else(|value| value.into_inner());
+    if in_use_locked(&state, id) {
+        return false;
+    }
+    state.pending_removals.insert(id.to_string());
+    state.removals_in_progress.insert(id.to_string());
+    true
+}
+
+#[cfg(not(feature = "recorder-worker"))]
+pub(super) fn in_use(id: &str) -> bool {
+    in_use_locked(
+        &LEASES.lock().unwrap_or_else(|value| value.into_inner()),
+        id,
+    )
+}
+
+#[cfg(not(feature = "recorder-worker"))]
+fn in_use_locked(state: &LeaseState, id: &str) -> bool {
+    state.counts.get(id).copied().unwrap_or_default() != 0
+        || state.pending_removals.contains(id)
+        || state.removals_in_progress.contains(id)
+}
+
 pub(super) fn finish_removal(id: &str, clear_pending: bool) {
     let mut state = LEASES.lock().unwrap_or_else(|value| value.into_inner());
     state.removals_in_progress.remove(id);
@@ -149,4 +180,18 @@ mod tests {
         drop(mutation);
         assert!(acquire(id).is_ok());
     }
+
+    #[test]
+    #[cfg(not(feature = "recorder-worker"))]
+    fn idle_removal_skips_leased_components_without_queueing_them() {
+        let id = "test-idle-removal";
+        let lease = acquire(id).unwrap();
+        assert!(!reserve_idle_removal(id));
+        assert!(!removal_pending(id));
+        drop(lease);
+        assert!(reserve_idle_removal(id));
+        assert!(acquire(id).is_err());
+        finish_removal(id, true);
+        assert!(!in_use(id));
+    }
 }
@@ -75,13 +75,7 @@ fn install_delivery(
 ) -> Result<()> {
     let (archive, staging_root) = working_paths(delivery.id, delivery.version)?;
     let result = (|| {
-        download(
-            delivery.download_url,
-            delivery.size_bytes,
-            &archive,
-            cancelled,
-            &on_progress,
-        )?;
+        download(delivery, &archive, cancelled, &on_progress)?;
         verify_file(&archive, delivery.size_bytes, delivery.sha256)?;
         extract_delivery(&archive, &staging_root, delivery)?;
         publish(
@@ -121,54 +115,20 @@ fn working_paths(id: &str, version: &str) -> Result<(PathBuf, PathBuf)> {
 }
 
 fn download(
-    url: &str,
-    expected_size: u64,
+    delivery: &LocalAsrDelivery,
     target: &Path,
     cancelled: &std::sync::atomic::AtomicBool,
     on_progress: &impl Fn(u64, u64),
 ) -> Result<()> {
-    let response = crate::api::client::UREQ_DOWNLOAD_AGENT
-        .get(url)
-        .header("User-Agent", "ScreenGoatedToolbox")
-        .call()
-        .map_err(|error| anyhow!("local ASR component download failed: {error}"))?;
-    if response
-        .headers()
-        .get("content-length")
-        .and_then(|value| value.to_str().ok())
-        .and_then(|value| value.parse::<u64>().ok())
-        .is_some_and(|size| size != expected_size)
-    {
-        bail!("local ASR component download size does not match this build");
-    }
-    let mut reader = response.into_body().into_reader();
-    let mut output = fs::OpenOptions::new()
-        .create_new(true)
-        .write(true)
-        .open(target)?;
-    let mut buffer = [0_u8; 128 * 1024];
-    let mut total = 0_u64;
-    loop {
-        if cancelled.load(std::sync::atomic::Ordering::Relaxed) {
-            bail!("local ASR component download cancelled");
-        }
-        let read = reader.read(&mut buffer)?;
-        if read == 0 {
-            break;
-        }
-        total = total
-            .checked_add(read as u64)
-            .filter(|total| *total <= expected_size)
-            .ok_or_else(|| anyhow!("local ASR component download exceeds its limit"))?;
-        output.write_all(&buffer[..read])?;
-        on_progress(total, expected_size);
-    }
-    if total != expected_size {
-        bail!("local ASR component download is incomplete");
-    }
-    output.flush()?;
-    output.sync_all()?;
-    Ok(())
+    let download = super::super::transfer::Download {
+        label: "local ASR component",
+        url: delivery.download_url,
+        size_bytes: delivery.size_bytes,
+        sha256: delivery.sha256,
+    };
+    super::super::transfer::fetch(&download, target, cancelled, |downloaded| {
+        on_progress(downloaded, delivery.size_bytes)
+    })
 }
 
 fn extract_delivery(
@@ -20,9 +20,15 @@ mod removal;
 #[cfg(not(feature = "recorder-worker"))]
 pub(crate) mod screen_text_detector;
 #[cfg(not(feature = "recorder-worker"))]
+pub(crate) mod sideload;
+#[cfg(not(feature = "recorder-worker"))]
 mod staging;
 #[cfg(not(feature = "recorder-worker"))]
+pub(crate) mod storage;
+mod transfer;
+#[cfg(not(feature = "recorder-worker"))]
 pub(crate) mod update_catalog;
+mod usage;
 pub(crate) mod vc_runtime;
 #[cfg(not(feature = "recorder-worker"))]
 pub(crate) mod web_assets;
@@ -178,59 +178,13 @@ fn download(
     cancelled: &AtomicBool,
     on_progress: &impl Fn(u64),
 ) -> Result<()> {
-    if cancelled.load(Ordering::Relaxed) {
-        bail!("model download cancelled");
-    }
-    let response = crate::api::client::UREQ_DOWNLOAD_AGENT
-        .get(url)
-        .header("User-Agent", "ScreenGoatedToolbox")
-        .call()
-        .map_err(|error| anyhow!("model download failed: {error}"))?;
-    if response
-        .headers()
-        .get("content-length")
-        .and_then(|value| value.to_str().ok())
-        .and_then(|value| value.parse::<u64>().ok())
-        .is_some_and(|size| size != expected_size)
-    {
-        bail!("model download size does not match this build");
-    }
-    let mut reader = response.into_body().into_reader();
-    let mut options = std::fs::OpenOptions::new();
-    options.write(true).create_new(true);
-    #[cfg(windows)]
-    {
-        use std::os::windows::fs::OpenOptionsExt as _;
-        options.share_mode(0);
-    }
-    let mut output = options.open(target)?;
-    let mut buffer = [0_u8; 256 * 1024];
-    let mut downloaded = 0_u64;
-    let mut hasher = Sha256::new();
-    loop {
-        if cancelled.load(Ordering::Relaxed) {
-            bail!("model download cancelled");
-        }
-        let read = reader.read(&mut buffer)?;
-        if read == 0 {
-            break;
-        }
-        downloaded = downloaded
-            .checked_add(read as u64)
-            .filter(|size| *size <= expected_size)
-            .ok_or_else(|| anyhow!("model download exceeds its pinned size"))?;
-        output.write_all(&buffer[..read])?;
-        hasher.update(&buffer[..read]);
-        on_progress(downloaded);
-    }
-    if downloaded != expected_size
-        || !format!("{:x}", hasher.finalize()).eq_ignore_ascii_case(expected_sha256)
-    {
-        bail!("model download is incomplete");
-    }
-    output.flush()?;
-    output.sync_all()?;
-    Ok(())
+    let download = super::super::transfer::Download {
+        label: "model",
+        url,
+        size_bytes: expected_size,
+        sha256: expected_sha256,
+    };
+    super::super::transfer::fetch(&download, target, cancelled, on_progress)
 }
 
 fn extract_archive(
@@ -1,11 +1,10 @@
-use std::io::{Read as _, Write as _};
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{LazyLock, Mutex};
 
 use anyhow::{Result, anyhow, bail};
 
-use super::super::receipt::{ComponentReceipt, OwnedComponentFile, file_matches};
+use super::super::receipt::{ComponentReceipt, file_matches};
 use super::{
     ARCHITECTURE, COMPONENT_ID, QwenRuntimeArchive, QwenRuntimeDelivery, VC_COMPONENT_ID, archive,
     delivery, owned_file, validate_exact_tree, validate_install, version_root,
@@ -120,60 +119,15 @@ fn download_archive(
     progress_total: u64,
     on_progress: &impl Fn(u64, u64),
 ) -> Result<()> {
-    if cancel.load(Ordering::Relaxed) {
-        bail!("Download cancelled");
-    }
-    let response = crate::api::client::UREQ_DOWNLOAD_AGENT
-        .get(archive.url)
-        .header("User-Agent", "ScreenGoatedToolbox")
-        .call()
-        .map_err(|error| anyhow!("Qwen3 runtime download failed: {error}"))?;
-    let headers = response.headers();
-    let content_length = headers
-        .get("content-length")
-        .and_then(|value| value.to_str().ok())
-        .and_then(|value| value.parse::<u64>().ok());
-    if content_length != Some(archive.size_bytes) {
-        bail!("Qwen3 runtime download size does not match this build");
-    }
-    let mut reader = response.into_body().into_reader();
-    let mut output = std::fs::OpenOptions::new()
-        .write(true)
-        .create_new(true)
-        .open(target)?;
-    let mut buffer = [0_u8; 256 * 1024];
-    let mut downloaded = 0_u64;
-    loop {
-        if cancel.load(Ordering::Relaxed) {
-            bail!("Download cancelled");
-        }
-        let read = reader.read(&mut buffer)?;
-        if read == 0 {
-            break;
-        }
-        downloaded = downloaded
-            .checked_add(read as u64)
-            .ok_or_else(|| anyhow!("Qwen3 runtime download is too large"))?;
-        if downloaded > archive.size_bytes {
-            bail!("Qwen3 runtime download exceeds its pinned size");
-        }
-        output.write_all(&buffer[..read])?;
-        on_progress(progress_offset + downloaded, progress_total);
-    }
-    if downloaded != archive.size_bytes {
-        bail!("Qwen3 runtime download is incomplete");
-    }
-    output.flush()?;
-    output.sync_all()?;
-    let expected = OwnedComponentFile {
-        path: PathBuf::from("archive.download"),
+    let download = super::super::transfer::Download {
+        label: "Qwen3 runtime",
+        url: archive.url,
         size_bytes: archive.size_bytes,
-        sha256: archive.sha256.to_string(),
+        sha256: archive.sha256,
     };
-    if !file_matches(target, &expected)? {
-        bail!("Qwen3 runtime archive checksum mismatch");
-    }
-    Ok(())
+    super::super::transfer::fetch(&download, target, cancel, |downloaded| {
+        on_progress(progress_offset + downloaded, progress_total)
+    })
 }
 
 fn adopt_legacy(
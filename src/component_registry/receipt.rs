@@ -89,6 +89,10 @@ pub(crate) fn write_receipt(root: &Path, receipt: &ComponentReceipt) -> Result<(
         bail!("component receipt root is not a regular directory");
     }
     crate::atomic_json::write_json_atomic(&root.join(RECEIPT_NAME), receipt)?;
+    // A fresh install counts as a use, so it is the last eviction candidate.
+    super::usage::touch(&receipt.id);
+    #[cfg(all(not(test), not(feature = "recorder-worker")))]
+    super::storage::enforce_quota_in_background();
     Ok(())
 }
 
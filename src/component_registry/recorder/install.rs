@@ -1,11 +1,11 @@
 use std::collections::HashSet;
-use std::io::{Read as _, Write as _};
+use std::io::Write as _;
 use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 use std::sync::Mutex;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
-use anyhow::{Context, Result, anyhow, bail};
+use anyhow::{Result, anyhow, bail};
 
 use super::{
     MAX_COMPONENT_FILES, RecorderDelivery, WORKER_ID, owned_file, receipt, recovery, staging,
@@ -145,50 +145,15 @@ fn download(
     cancelled: &AtomicBool,
     on_progress: impl Fn(u64, u64),
 ) -> Result<()> {
-    let response = crate::api::client::UREQ_DOWNLOAD_AGENT
-        .get(delivery.download_url)
-        .header("User-Agent", "ScreenGoatedToolbox")
-        .call()
-        .with_context(|| format!("{} download failed", delivery.id))?;
-    if response
-        .headers()
-        .get("content-length")
-        .and_then(|value| value.to_str().ok())
-        .and_then(|value| value.parse::<u64>().ok())
-        .is_some_and(|size| size != delivery.size_bytes)
-    {
-        bail!("recorder component download size does not match this build");
-    }
-    let mut reader = response.into_body().into_reader();
-    let mut output = std::fs::OpenOptions::new()
-        .create_new(true)
-        .write(true)
-        .open(target)?;
-    let mut buffer = [0_u8; 128 * 1024];
-    let mut downloaded = 0_u64;
-    loop {
-        if cancelled.load(Ordering::Relaxed) {
-            bail!("recorder component download was cancelled");
-        }
-        let read = reader.read(&mut buffer)?;
-        if read == 0 {
-            break;
-        }
-        downloaded = downloaded
-            .checked_add(read as u64)
-            .ok_or_else(|| anyhow!("recorder component download is too large"))?;
-        if downloaded > delivery.size_bytes {
-            bail!("recorder component download exceeds its exact size");
-        }
-        output.write_all(&buffer[..read])?;
-        on_progress(downloaded, delivery.size_bytes);
-    }
-    if downloaded != delivery.size_bytes {
-        bail!("recorder component download is incomplete");
-    }
-    output.flush()?;
-    output.sync_all()?;
-    Ok(())
+    let download = crate::component_registry::transfer::Download {
+        label: "recorder component",
+        url: delivery.download_url,
+        size_bytes: delivery.size_bytes,
+        sha256: delivery.sha256,
+    };
+    crate::component_registry::transfer::fetch(&download, target, cancelled, |downloaded| {
+        on_progress(downloaded, delivery.size_bytes)
+    })
 }
 
 fn validate_archive(delivery: &RecorderDelivery, path: &Path) -> Result<()> {
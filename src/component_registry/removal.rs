@@ -89,6 +89,9 @@ pub(super) fn run_reserved_removal(id: &str) -> Result<RemovalOutcome> {
             | RemovalOutcome::PreservedModified(_)
     );
     super::models::invalidate_status(id);
+    if outcome == RemovalOutcome::Removed {
+        let _ = super::usage::forget(id);
+    }
     super::lease::finish_removal(id, finished);
     Ok(outcome)
 }
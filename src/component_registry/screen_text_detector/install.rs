@@ -3,9 +3,8 @@ use std::io::{Read as _, Seek as _, SeekFrom, Write as _};
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{LazyLock, Mutex};
-use std::time::Duration;
 
-use anyhow::{Context, Result, anyhow, bail};
+use anyhow::{Result, anyhow, bail};
 
 use super::{
     DetectorDelivery, ID, MAX_COMPONENT_FILES, receipt, validate_exact_tree, validate_install,
@@ -17,7 +16,6 @@ use crate::component_registry::staging;
 
 static INSTALL_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
 static INSTALL_SEQUENCE: AtomicU64 = AtomicU64::new(0);
-const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(10 * 60);
 
 pub(super) fn ensure(
     delivery: &'static DetectorDelivery,
@@ -100,53 +98,15 @@ fn download(
     cancelled: &AtomicBool,
     on_progress: impl Fn(u64, u64),
 ) -> Result<()> {
-    let request = crate::api::client::UREQ_DOWNLOAD_AGENT
-        .get(delivery.download_url)
-        .config()
-        .https_only(true)
-        .timeout_global(Some(DOWNLOAD_TIMEOUT))
-        .build();
-    let response = request
-        .header("User-Agent", "ScreenGoatedToolbox")
-        .call()
-        .context("Screen Translate detector download failed")?;
-    if response
-        .headers()
-        .get("content-length")
-        .and_then(|value| value.to_str().ok())
-        .and_then(|value| value.parse::<u64>().ok())
-        .is_some_and(|size| size != delivery.size_bytes)
-    {
-        bail!("Screen Translate detector download size does not match this build");
-    }
-    let mut reader = response.into_body().into_reader();
-    let mut output = std::fs::OpenOptions::new()
-        .create_new(true)
-        .write(true)
-        .open(target)?;
-    let mut buffer = [0_u8; 128 * 1024];
-    let mut downloaded = 0_u64;
-    loop {
-        if cancelled.load(Ordering::Relaxed) {
-            bail!("Screen Translate detector download was cancelled");
-        }
-        let read = reader.read(&mut buffer)?;
-        if read == 0 {
-            break;
-        }
-        downloaded = downloaded
-            .checked_add(read as u64)
-            .filter(|size| *size <= delivery.size_bytes)
-            .ok_or_else(|| anyhow!("Screen Translate detector download exceeds its size"))?;
-        output.write_all(&buffer[..read])?;
-        on_progress(downloaded, delivery.size_bytes);
-    }
-    if downloaded != delivery.size_bytes {
-        bail!("Screen Translate detector download is incomplete");
-    }
-    output.flush()?;
-    output.sync_all()?;
-    Ok(())
+    let download = crate::component_registry::transfer::Download {
+        label: "Screen Translate detector",
+        url: delivery.download_url,
+        size_bytes: delivery.size_bytes,
+        sha256: delivery.sha256,
+    };
+    crate::component_registry::transfer::fetch(&download, target, cancelled, |downloaded| {
+        on_progress(downloaded, delivery.size_bytes)
+    })
 }
 
 fn validate_archive(delivery: &DetectorDelivery, path: &Path) -> Result<()> {
@@ -0,0 +1,397 @@
+//! Offline import and export of installed components for air-gapped machines.
+//!
+//! A bundle is a directory, or a zip of one, holding the signed component
+//! catalog and its signature as published plus `components/<id>/<version>/`
+//! trees exactly as installed, receipts included. Nothing in a bundle is
+//! trusted: the catalog must verify against the built-in key, each receipt
+//! must equal what that catalog pins for its component, and each file must
+//! match its pinned size and SHA-256 before the version root is published.
+
+use std::io::{Read as _, Write as _};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::{Context, Result, anyhow, bail};
+use sha2::{Digest as _, Sha256};
+
+use super::receipt::{self, ComponentReceipt, OwnedComponentFile, RECEIPT_NAME};
+use super::staging;
+use super::update_catalog::{self, PinnedComponent};
+
+mod archive;
+#[cfg(test)]
+mod tests;
+
+pub(super) const COMPONENTS_DIR: &str = "components";
+const CATALOG_PREFIX: &str = "sgt-component-catalog-v";
+const MAX_BUNDLE_COMPONENTS: usize = 256;
+static IMPORT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Default)]
+pub(crate) struct ImportSummary {
+    pub(crate) catalog_sequence: Option<u64>,
+    pub(crate) installed: Vec<String>,
+    pub(crate) already_installed: Vec<String>,
+    pub(crate) rejected: Vec<(String, String)>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ExportSummary {
+    pub(crate) exported: Vec<String>,
+    pub(crate) skipped: Vec<(String, String)>,
+}
+
+pub(crate) fn run_import_cli(source: &str) -> Result<()> {
+    let summary = import_bundle(Path::new(source))?;
+    if let Some(sequence) = summary.catalog_sequence {
+        println!("[components] signed catalog {sequence} adopted");
+    }
+    for id in &summary.installed {
+        println!("[components] installed {id}");
+    }
+    for id in &summary.already_installed {
+        println!("[components] already installed {id}");
+    }
+    for (id, reason) in &summary.rejected {
+        eprintln!("[components] rejected {id}: {reason}");
+    }
+    if !summary.rejected.is_empty() {
+        bail!("{} component(s) were rejected", summary.rejected.len());
+    }
+    Ok(())
+}
+
+pub(crate) fn run_export_cli(destination: &str) -> Result<()> {
+    let summary = export_installed(Path::new(destination))?;
+    for id in &summary.exported {
+        println!("[components] exported {id}");
+    }
+    for (id, reason) in &summary.skipped {
+        println!("[components] skipped {id}: {reason}");
+    }
+    println!("[components] bundle written to {destination}");
+    Ok(())
+}
+
+/// Adopts the bundle's catalog, then publishes every component version it
+/// carries that the catalog pins and this machine lacks.
+pub(crate) fn import_bundle(source: &Path) -> Result<ImportSummary> {
+    let metadata = std::fs::symlink_metadata(source)
+        .with_context(|| format!("component bundle '{}' is missing", source.display()))?;
+    if metadata.is_dir() && !receipt::is_reparse_point(&metadata) {
+        return import_directory(source);
+    }
+    if !metadata.is_file() || receipt::is_reparse_point(&metadata) {
+        bail!("component bundle is neither a directory nor an archive");
+    }
+    let scratch = scratch_root("import")?;
+    let result = archive::extract(source, &scratch).and_then(|()| import_directory(&scratch));
+    // The scratch tree is ours alone; nothing in it outlives the import.
+    let _ = std::fs::remove_dir_all(&scratch);
+    result
+}
+
+fn import_directory(root: &Path) -> Result<ImportSummary> {
+    let mut summary = ImportSummary {
+        catalog_sequence: adopt_catalog(root)?,
+        ..ImportSummary::default()
+    };
+    let components = root.join(COMPONENTS_DIR);
+    for (id, version) in bundle_versions(&components)? {
+        let label = format!("{id} {version}");
+        match install_version(&components.join(&id).join(&version), &id, &version) {
+            Ok(true) => summary.installed.push(label),
+            Ok(false) => summary.already_installed.push(label),
+            Err(error) => summary.rejected.push((label, format!("{error:#}"))),
+        }
+    }
+    if summary.installed.is_empty()
+        && summary.already_installed.is_empty()
+        && summary.rejected.is_empty()
+    {
+        bail!("component bundle carries no components");
+    }
+    Ok(summary)
+}
+
+/// Verifies and caches the bundle's catalog, or falls back to one already
+/// cached here when the bundle carries none.
+fn adopt_catalog(root: &Path) -> Result<Option<u64>> {
+    let mut adopted = None;
+    for entry in std::fs::read_dir(root)? {
+        let entry = entry?;
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        if !name.starts_with(CATALOG_PREFIX) || !name.ends_with(".json") {
+            continue;
+        }
+        let catalog = read_bounded(&entry.path(), 2 * 1024 * 1024)?;
+        let signature = read_bounded(&entry.path().with_extension("sig"), 64)
+            .with_context(|| format!("{name} has no signature beside it"))?;
+        let sequence = update_catalog::import_signed(&name, &catalog, &signature)
+            .with_context(|| format!("{name} failed verification"))?;
+        adopted = adopted.max(Some(sequence));
+    }
+    if adopted.is_none() {
+        update_catalog::load_cached()?;
+    }
+    Ok(adopted)
+}
+
+fn bundle_versions(components: &Path) -> Result<Vec<(String, String)>> {
+    let mut versions = Vec::new();
+    for id in regular_directories(components)? {
+        for version in regular_directories(&components.join(&id))? {
+            versions.push((id.clone(), version));
+            if versions.len() > MAX_BUNDLE_COMPONENTS {
+                bail!("component bundle carries too many components");
+            }
+        }
+    }
+    versions.sort();
+    Ok(versions)
+}
+
+/// Child directories named like registry identifiers; anything else is not
+/// part of the layout and is ignored.
+fn regular_directories(parent: &Path) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    let entries = match std::fs::read_dir(parent) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(names),
+        Err(error) => return Err(error.into()),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let metadata = std::fs::symlink_metadata(entry.path())?;
+        if !metadata.is_dir() || receipt::is_reparse_point(&metadata) {
+            continue;
+        }
+        if let Some(name) = entry.file_name().to_str()
+            && !name.starts_with('.')
+            && super::validate_identifier(name).is_ok()
+        {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
+
+/// Returns false when the version is already installed; its owner keeps
+/// checking that copy's integrity as usual.
+fn install_version(source: &Path, id: &str, version: &str) -> Result<bool> {
+    let receipt = ComponentReceipt::read(&source.join(RECEIPT_NAME))?;
+    if receipt.id != id || receipt.version != version {
+        bail!("receipt names a different component");
+    }
+    let pinned = update_catalog::pinned_component(id, version)
+        .ok_or_else(|| anyhow!("the signed catalog does not pin this version"))?;
+    if !matches_pinned(&receipt, &pinned) {
+        bail!("receipt differs from what the signed catalog pins");
+    }
+
+    let _mutation = super::acquire_mutation_guard()?;
+    if super::validate_version_root(id, version).is_ok() {
+        return Ok(false);
+    }
+    let _lease = super::acquire(id)?;
+    let stage = scratch_root(&format!("{id}-{version}"))?;
+    let result = (|| {
+        for file in &receipt.files {
+            let from = receipt::resolve_owned_path(source, &file.path)?;
+            let to = staging::prepare_target(&stage, &file.path)?;
+            copy_verified(&from, &to, file)?;
+        }
+        super::write_receipt(&stage, &receipt)?;
+        let parent = super::ensure_component_parent(id)?;
+        let target = super::component_version_root(id, version)?;
+        if target.parent() != Some(parent.as_path()) || target.exists() {
+            bail!("component install target is invalid or already exists");
+        }
+        std::fs::rename(&stage, &target)?;
+        super::validate_version_root(id, version)?;
+        Ok(true)
+    })();
+    if stage.exists() {
+        let owned = receipt
+            .files
+            .iter()
+            .map(|file| file.path.clone())
+            .chain(std::iter::once(PathBuf::from(RECEIPT_NAME)))
+            .collect::<Vec<_>>();
+        let _ = staging::cleanup_owned(&stage, &owned);
+    }
+    result
+}
+
+pub(super) fn matches_pinned(receipt: &ComponentReceipt, pinned: &PinnedComponent) -> bool {
+    let sorted_files = |files: &[OwnedComponentFile]| {
+        let mut files = files
+            .iter()
+            .map(|file| {
+                (
+                    file.path.clone(),
+                    file.size_bytes,
+                    file.sha256.to_ascii_lowercase(),
+                )
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    };
+    let sorted_dependencies = |dependencies: &[String]| {
+        let mut dependencies = dependencies.to_vec();
+        dependencies.sort();
+        dependencies
+    };
+    sorted_files(&receipt.files) == sorted_files(&pinned.files)
+        && sorted_dependencies(&receipt.dependencies) == sorted_dependencies(&pinned.dependencies)
+}
+
+/// Copies one file, refusing it unless it has exactly the pinned size and
+/// digest. The target must not exist yet.
+pub(super) fn copy_verified(from: &Path, to: &Path, expected: &OwnedComponentFile) -> Result<()> {
+    let metadata = std::fs::symlink_metadata(from)
+        .with_context(|| format!("{} is missing", expected.path.display()))?;
+    if !metadata.is_file()
+        || receipt::is_reparse_point(&metadata)
+        || metadata.len() != expected.size_bytes
+    {
+        bail!("{} does not match its pinned size", expected.path.display());
+    }
+    let mut input = std::fs::File::open(from)?;
+    let mut output = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(to)?;
+    let mut hasher = Sha256::new();
+    let mut copied = 0_u64;
+    let mut buffer = [0_u8; 256 * 1024];
+    loop {
+        let read = input.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        copied = copied
+            .checked_add(read as u64)
+            .filter(|size| *size <= expected.size_bytes)
+            .ok_or_else(|| anyhow!("{} grew while copying", expected.path.display()))?;
+        output.write_all(&buffer[..read])?;
+        hasher.update(&buffer[..read]);
+    }
+    output.flush()?;
+    output.sync_all()?;
+    if copied != expected.size_bytes
+        || !format!("{:x}", hasher.finalize()).eq_ignore_ascii_case(&expected.sha256)
+    {
+        drop(output);
+        let _ = std::fs::remove_file(to);
+        bail!(
+            "{} does not match its pinned SHA-256",
+            expected.path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Writes every intact installed version the signed catalog pins, with that
+/// catalog, to a new directory or `.zip`.
+pub(crate) fn export_installed(destination: &Path) -> Result<ExportSummary> {
+    if destination.exists() {
+        bail!("{} already exists", destination.display());
+    }
+    update_catalog::load_cached()?;
+    let catalog = update_catalog::signed_catalog_files()?.ok_or_else(|| {
+        anyhow!("no signed component catalog is cached; refresh once online or import a bundle")
+    })?;
+
+    let mut sink = archive::Sink::create(destination)?;
+    let result = (|| {
+        sink.add_bytes(&catalog.name, &catalog.catalog)?;
+        sink.add_bytes(
+            &format!("{}.sig", catalog.name.trim_end_matches(".json")),
+            &catalog.signature,
+        )?;
+        let mut summary = ExportSummary::default();
+        let root = super::components_root();
+        for id in regular_directories(&root)? {
+            for version in regular_directories(&root.join(&id))? {
+                let label = format!("{id} {version}");
+                match export_version(&mut sink, &id, &version) {
+                    Ok(true) => summary.exported.push(label),
+                    Ok(false) => {}
+                    Err(error) => summary.skipped.push((label, format!("{error:#}"))),
+                }
+            }
+        }
+        if summary.exported.is_empty() {
+            bail!("no installed component matches the signed catalog");
+        }
+        Ok(summary)
+    })();
+    match result {
+        Ok(summary) => {
+            sink.finish()?;
+            Ok(summary)
+        }
+        Err(error) => {
+            sink.abandon();
+            Err(error)
+        }
+    }
+}
+
+/// Returns false for directories that are not installed versions at all.
+fn export_version(sink: &mut archive::Sink, id: &str, version: &str) -> Result<bool> {
+    let root = super::validate_version_root(id, version)?;
+    let receipt_path = root.join(RECEIPT_NAME);
+    if !receipt_path.exists() {
+        return Ok(false);
+    }
+    let receipt = ComponentReceipt::read(&receipt_path)?;
+    let pinned = update_catalog::pinned_component(id, version)
+        .ok_or_else(|| anyhow!("the signed catalog does not pin this version"))?;
+    if receipt.id != id || receipt.version != version || !matches_pinned(&receipt, &pinned) {
+        bail!("receipt differs from what the signed catalog pins");
+    }
+    // Held so the version cannot be removed halfway through the copy.
+    let _lease = super::acquire(id)?;
+    for file in &receipt.files {
+        let path = receipt::resolve_owned_path(&root, &file.path)?;
+        if !receipt::file_matches(&path, file)? {
+            bail!("{} was modified after install", file.path.display());
+        }
+    }
+    let prefix = format!("{COMPONENTS_DIR}/{id}/{version}");
+    for file in &receipt.files {
+        let path = receipt::resolve_owned_path(&root, &file.path)?;
+        sink.add_file(
+            &format!("{prefix}/{}", archive::entry_name(&file.path)?),
+            &path,
+        )?;
+    }
+    sink.add_file(&format!("{prefix}/{RECEIPT_NAME}"), &receipt_path)?;
+    Ok(true)
+}
+
+fn scratch_root(label: &str) -> Result<PathBuf> {
+    let app_data = crate::paths::app_local_data_dir();
+    let parent = app_data.join("component-staging");
+    staging::ensure_directory_tree(&app_data, &parent)?;
+    let root = parent.join(format!(
+        "sideload-{label}-{}-{}",
+        std::process::id(),
+        IMPORT_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    ));
+    std::fs::create_dir(&root)?;
+    Ok(root)
+}
+
+fn read_bounded(path: &Path, maximum: u64) -> Result<Vec<u8>> {
+    let metadata = std::fs::symlink_metadata(path)?;
+    if !metadata.is_file() || receipt::is_reparse_point(&metadata) || metadata.len() > maximum {
+        bail!("{} is not a bounded regular file", path.display());
+    }
+    Ok(std::fs::read(path)?)
+}
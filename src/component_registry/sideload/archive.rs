@@ -0,0 +1,147 @@
+//! Zip and directory forms of a component bundle.
+
+use std::fs::File;
+use std::io::Write as _;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Result, anyhow, bail};
+
+use super::super::receipt;
+use super::super::staging;
+
+const MAX_ARCHIVE_ENTRIES: usize = 64 * 1024;
+
+/// Unpacks a bundle archive into `root`. Entries are only placed here, never
+/// trusted: the import verifies every file against the signed catalog next.
+pub(super) fn extract(archive_path: &Path, root: &Path) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(File::open(archive_path)?)
+        .map_err(|error| anyhow!("component bundle is not a valid zip: {error}"))?;
+    if archive.len() > MAX_ARCHIVE_ENTRIES {
+        bail!("component bundle has too many entries");
+    }
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        if entry.is_dir() {
+            continue;
+        }
+        if entry
+            .unix_mode()
+            .is_some_and(|mode| mode & 0o170000 != 0 && mode & 0o170000 != 0o100000)
+        {
+            bail!("component bundle contains a link");
+        }
+        let relative = entry
+            .enclosed_name()
+            .ok_or_else(|| anyhow!("component bundle contains an unsafe path"))?;
+        let target = staging::prepare_target(root, &relative)?;
+        let mut output = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&target)
+            .map_err(|error| anyhow!("component bundle entry is duplicated: {error}"))?;
+        std::io::copy(&mut entry, &mut output)?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+/// Where an export is written: a new directory, or a zip published only once
+/// it is complete.
+pub(super) enum Sink {
+    Directory(PathBuf),
+    Zip {
+        writer: Box<zip::ZipWriter<File>>,
+        partial: PathBuf,
+        destination: PathBuf,
+    },
+}
+
+impl Sink {
+    pub(super) fn create(destination: &Path) -> Result<Self> {
+        let is_zip = destination
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("zip"));
+        if !is_zip {
+            std::fs::create_dir_all(destination)?;
+            return Ok(Self::Directory(destination.to_path_buf()));
+        }
+        let partial = destination.with_extension("zip.partial");
+        Ok(Self::Zip {
+            writer: Box::new(zip::ZipWriter::new(File::create(&partial)?)),
+            partial,
+            destination: destination.to_path_buf(),
+        })
+    }
+
+    pub(super) fn add_bytes(&mut self, name: &str, bytes: &[u8]) -> Result<()> {
+        match self {
+            Self::Directory(root) => {
+                std::fs::write(staging::prepare_target(root, Path::new(name))?, bytes)?;
+            }
+            Self::Zip { writer, .. } => {
+                writer.start_file(name, options())?;
+                writer.write_all(bytes)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn add_file(&mut self, name: &str, source: &Path) -> Result<()> {
+        match self {
+            Self::Directory(root) => {
+                std::fs::copy(source, staging::prepare_target(root, Path::new(name))?)?;
+            }
+            Self::Zip { writer, .. } => {
+                writer.start_file(name, options())?;
+                std::io::copy(&mut File::open(source)?, writer.as_mut())?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn finish(self) -> Result<()> {
+        if let Self::Zip {
+            writer,
+            partial,
+            destination,
+        } = self
+        {
+            writer.finish()?.sync_all()?;
+            std::fs::rename(&partial, &destination)?;
+        }
+        Ok(())
+    }
+
+    /// A half-written directory is left for inspection; a partial zip is not.
+    pub(super) fn abandon(self) {
+        if let Self::Zip {
+            writer, partial, ..
+        } = self
+        {
+            drop(writer);
+            let _ = std::fs::remove_file(partial);
+        }
+    }
+}
+
+fn options() -> zip::write::SimpleFileOptions {
+    // Installed payloads are mostly compressed binaries already.
+    zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Stored)
+        .large_file(true)
+}
+
+/// A receipt path as a zip entry name, which always uses forward slashes.
+pub(super) fn entry_name(path: &Path) -> Result<String> {
+    receipt::validate_relative_path(path)?;
+    let parts = path
+        .components()
+        .map(|component| match component {
+            Component::Normal(part) => part
+                .to_str()
+                .ok_or_else(|| anyhow!("component file path is not UTF-8")),
+            _ => Err(anyhow!("component file path is unsafe")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(parts.join("/"))
+}
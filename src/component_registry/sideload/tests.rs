@@ -0,0 +1,131 @@
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest as _, Sha256};
+
+use super::super::receipt::{ComponentReceipt, OwnedComponentFile};
+use super::super::update_catalog::PinnedComponent;
+use super::{archive, copy_verified, matches_pinned};
+
+fn scratch(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!(
+        "sgt-sideload-test-{name}-{}-{}",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+fn owned(path: &str, bytes: &[u8]) -> OwnedComponentFile {
+    OwnedComponentFile {
+        path: path.into(),
+        size_bytes: bytes.len() as u64,
+        sha256: format!("{:x}", Sha256::digest(bytes)),
+    }
+}
+
+fn receipt(files: Vec<OwnedComponentFile>, dependencies: &[&str]) -> ComponentReceipt {
+    ComponentReceipt {
+        schema_version: 1,
+        id: "fixture-runtime".into(),
+        version: "1.0.0".into(),
+        architecture: "x64".into(),
+        dependencies: dependencies.iter().map(|value| value.to_string()).collect(),
+        files,
+    }
+}
+
+#[test]
+fn a_receipt_must_equal_what_the_catalog_pins() {
+    let worker = owned("bin/worker.exe", b"worker");
+    let license = owned("licenses/LICENSE.txt", b"license");
+    let pinned = PinnedComponent {
+        dependencies: vec!["vc-runtime".into()],
+        files: vec![worker.clone(), license.clone()],
+    };
+    let mut shouted = license.clone();
+    shouted.sha256 = shouted.sha256.to_ascii_uppercase();
+
+    assert!(matches_pinned(
+        &receipt(vec![shouted, worker.clone()], &["vc-runtime"]),
+        &pinned
+    ));
+    assert!(!matches_pinned(
+        &receipt(vec![worker.clone()], &["vc-runtime"]),
+        &pinned
+    ));
+    assert!(!matches_pinned(
+        &receipt(vec![worker.clone(), license.clone()], &[]),
+        &pinned
+    ));
+    let mut swapped = license;
+    swapped.sha256 = worker.sha256.clone();
+    assert!(!matches_pinned(
+        &receipt(vec![worker, swapped], &["vc-runtime"]),
+        &pinned
+    ));
+}
+
+#[test]
+fn only_files_with_the_pinned_size_and_digest_are_copied() {
+    let root = scratch("copy");
+    let source = root.join("source.bin");
+    std::fs::write(&source, b"payload").unwrap();
+
+    copy_verified(
+        &source,
+        &root.join("good.bin"),
+        &owned("good.bin", b"payload"),
+    )
+    .unwrap();
+    assert_eq!(std::fs::read(root.join("good.bin")).unwrap(), b"payload");
+
+    let tampered = root.join("tampered.bin");
+    let error = copy_verified(&source, &tampered, &owned("tampered.bin", b"payloaD")).unwrap_err();
+    assert!(error.to_string().contains("SHA-256"), "{error:#}");
+    assert!(!tampered.exists(), "rejected bytes are not left behind");
+    assert!(
+        copy_verified(
+            &source,
+            &root.join("short.bin"),
+            &owned("short.bin", b"pay")
+        )
+        .is_err()
+    );
+    std::fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn a_zipped_bundle_unpacks_to_the_directory_layout() {
+    let root = scratch("zip");
+    let source = root.join("worker.exe");
+    std::fs::write(&source, b"worker").unwrap();
+    let bundle = root.join("bundle.zip");
+    let mut sink = archive::Sink::create(&bundle).unwrap();
+    let name =
+        archive::entry_name(Path::new("bin").join("x64").join("worker.exe").as_path()).unwrap();
+    assert_eq!(name, "bin/x64/worker.exe");
+    sink.add_file(&format!("components/fixture/1.0.0/{name}"), &source)
+        .unwrap();
+    sink.add_bytes("sgt-component-catalog-v1.json", b"{}")
+        .unwrap();
+    sink.finish().unwrap();
+    assert!(!root.join("bundle.zip.partial").exists());
+
+    let unpacked = root.join("unpacked");
+    std::fs::create_dir(&unpacked).unwrap();
+    archive::extract(&bundle, &unpacked).unwrap();
+    assert_eq!(
+        std::fs::read(unpacked.join("components/fixture/1.0.0/bin/x64/worker.exe")).unwrap(),
+        b"worker"
+    );
+    assert_eq!(
+        std::fs::read(unpacked.join("sgt-component-catalog-v1.json")).unwrap(),
+        b"{}"
+    );
+    assert!(archive::entry_name(Path::new("../escape")).is_err());
+    std::fs::remove_dir_all(root).unwrap();
+}
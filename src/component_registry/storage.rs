@@ -0,0 +1,361 @@
+//! Disk use of installed components and the optional quota that evicts the
+//! least recently used ones.
+//!
+//! Eviction goes through the ordinary removal path, one idle component at a
+//! time: leased components are skipped rather than queued for removal, and
+//! components other installs still depend on stay until their dependents go.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+use anyhow::{Result, bail};
+
+use super::RemovalOutcome;
+use super::catalog::validate_identifier;
+use super::receipt::is_reparse_point;
+
+const BYTES_PER_GB: u64 = 1024 * 1024 * 1024;
+const MAX_COMPONENTS: usize = 256;
+const MAX_DIRECTORY_DEPTH: usize = 32;
+const MAX_ENTRIES_PER_COMPONENT: usize = 200_000;
+/// Components used this recently are never evicted, so a fresh install is
+/// not removed before its first lease.
+const RECENT_USE_GRACE_SECS: i64 = 10 * 60;
+
+static EVICTING: AtomicBool = AtomicBool::new(false);
+static LAST_EVICTION: LazyLock<Mutex<Option<EvictionReport>>> = LazyLock::new(|| Mutex::new(None));
+
+#[derive(Clone, Debug)]
+pub(crate) struct InstalledComponent {
+    pub(crate) id: String,
+    pub(crate) display_name: String,
+    pub(crate) bytes: u64,
+    /// Unix seconds of the last lease or install, falling back to the
+    /// directory's modification time for components installed before usage
+    /// was recorded.
+    pub(crate) last_used: Option<i64>,
+    pub(crate) in_use: bool,
+    pub(crate) removable: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct StorageReport {
+    /// Largest first.
+    pub(crate) components: Vec<InstalledComponent>,
+    /// Bytes per catalog capability, largest first. A component that serves
+    /// several features counts toward each of them.
+    pub(crate) features: Vec<(String, u64)>,
+    pub(crate) total_bytes: u64,
+}
+
+#[derive(Clone, Debug, Default)]
+pub(crate) struct EvictionReport {
+    pub(crate) quota_bytes: u64,
+    pub(crate) bytes_before: u64,
+    pub(crate) bytes_after: u64,
+    /// Removed components and the bytes each one freed, in eviction order.
+    pub(crate) evicted: Vec<(String, u64)>,
+    /// Components whose removal left files behind or failed.
+    pub(crate) kept: Vec<String>,
+}
+
+pub(crate) fn report() -> Result<StorageReport> {
+    let root = super::components_root();
+    let Ok(metadata) = std::fs::symlink_metadata(&root) else {
+        return Ok(StorageReport::default());
+    };
+    if !metadata.is_dir() || is_reparse_point(&metadata) {
+        bail!("component registry root is not a regular directory");
+    }
+    let last_used = super::usage::last_used().unwrap_or_else(|error| {
+        crate::log_info!("[Components] component usage is unreadable: {error:#}");
+        BTreeMap::new()
+    });
+    let catalog = super::embedded_catalog();
+    let mut report = StorageReport::default();
+    let mut features = BTreeMap::<String, u64>::new();
+    for entry in std::fs::read_dir(&root)?.take(MAX_COMPONENTS) {
+        let path = entry?.path();
+        let metadata = std::fs::symlink_metadata(&path)?;
+        let Some(id) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !metadata.is_dir() || is_reparse_point(&metadata) || validate_identifier(id).is_err() {
+            continue;
+        }
+        let bytes = directory_size(&path, 0, &mut 0)?;
+        let definition = catalog
+            .components
+            .iter()
+            .find(|component| component.id == id);
+        for capability in definition
+            .iter()
+            .flat_map(|component| &component.capabilities)
+        {
+            *features.entry(capability.clone()).or_default() += bytes;
+        }
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|age| age.as_secs() as i64);
+        report.total_bytes += bytes;
+        report.components.push(InstalledComponent {
+            id: id.to_string(),
+            display_name: definition.map_or_else(
+                || id.to_string(),
+                |component| component.display_name.clone(),
+            ),
+            bytes,
+            last_used: last_used.get(id).copied().or(modified),
+            in_use: super::lease::in_use(id),
+            removable: catalog.is_removable(id),
+        });
+    }
+    report
+        .components
+        .sort_by_key(|component| std::cmp::Reverse(component.bytes));
+    report.features = features.into_iter().collect();
+    report
+        .features
+        .sort_by_key(|(_, bytes)| std::cmp::Reverse(*bytes));
+    Ok(report)
+}
+
+/// Removes idle components, least recently used first, until the registry
+/// fits in `quota_bytes` or nothing else can go.
+pub(crate) fn enforce_quota(quota_bytes: u64) -> Result<EvictionReport> {
+    let before = report()?;
+    let mut result = EvictionReport {
+        quota_bytes,
+        bytes_before: before.total_bytes,
+        bytes_after: before.total_bytes,
+        ..EvictionReport::default()
+    };
+    let now = chrono::Utc::now().timestamp();
+    let mut remaining = eviction_candidates(&before.components, now);
+    while result.bytes_after > quota_bytes && !remaining.is_empty() {
+        let mut blocked = Vec::new();
+        let mut progress = false;
+        for component in remaining {
+            if result.bytes_after <= quota_bytes {
+                break;
+            }
+            let outcome = match remove_if_idle(&component.id) {
+                Ok(Some(outcome)) => outcome,
+                // Leased since the report was taken.
+                Ok(None) => continue,
+                Err(error) => {
+                    crate::log_info!("[Components] could not evict {}: {error:#}", component.id);
+                    result.kept.push(component.id.clone());
+                    continue;
+                }
+            };
+            let left = directory_size(&super::components_root().join(&component.id), 0, &mut 0)
+                .unwrap_or(component.bytes);
+            let freed = component.bytes.saturating_sub(left);
+            result.bytes_after = result.bytes_after.saturating_sub(freed);
+            match outcome {
+                RemovalOutcome::Removed | RemovalOutcome::Missing => {
+                    result.evicted.push((component.id.clone(), freed));
+                    progress = true;
+                }
+                RemovalOutcome::RequiredBy(_) => blocked.push(component),
+                RemovalOutcome::PreservedModified(_) | RemovalOutcome::Pending => {
+                    result.kept.push(component.id.clone());
+                    progress |= freed > 0;
+                }
+            }
+        }
+        if !progress {
+            break;
+        }
+        // Dependencies may be free to go once their dependents were evicted.
+        remaining = blocked;
+    }
+    Ok(result)
+}
+
+/// Enforces the configured quota off the calling thread. Does nothing when no
+/// quota is set or an eviction is already running.
+pub(crate) fn enforce_quota_in_background() {
+    if EVICTING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    std::thread::spawn(|| {
+        let quota = crate::APP
+            .lock()
+            .map(|app| app.config.component_disk_quota_gb)
+            .unwrap_or_default();
+        if quota > 0 {
+            run_eviction(u64::from(quota) * BYTES_PER_GB);
+        }
+        EVICTING.store(false, Ordering::Release);
+    });
+}
+
+/// Starts an eviction against `quota_gb` now; false when one is running.
+pub(crate) fn start_eviction(quota_gb: u32) -> bool {
+    if quota_gb == 0 || EVICTING.swap(true, Ordering::AcqRel) {
+        return false;
+    }
+    std::thread::spawn(move || {
+        run_eviction(u64::from(quota_gb) * BYTES_PER_GB);
+        EVICTING.store(false, Ordering::Release);
+    });
+    true
+}
+
+pub(crate) fn eviction_running() -> bool {
+    EVICTING.load(Ordering::Acquire)
+}
+
+/// The outcome of the most recent eviction in this process.
+pub(crate) fn last_eviction() -> Option<EvictionReport> {
+    LAST_EVICTION
+        .lock()
+        .unwrap_or_else(|value| value.into_inner())
+        .clone()
+}
+
+fn run_eviction(quota_bytes: u64) {
+    match enforce_quota(quota_bytes) {
+        Ok(report) => {
+            if !report.evicted.is_empty() || report.bytes_after > quota_bytes {
+                crate::log_info!(
+                    "[Components] disk quota: evicted {:?}, {} of {} bytes used",
+                    report.evicted,
+                    report.bytes_after,
+                    quota_bytes
+                );
+            }
+            *LAST_EVICTION
+                .lock()
+                .unwrap_or_else(|value| value.into_inner()) = Some(report);
+        }
+        Err(error) => crate::log_info!("[Components] disk quota enforcement failed: {error:#}"),
+    }
+}
+
+/// Removable, idle components that were not used recently, least recently
+/// used first and larger first among equals.
+fn eviction_candidates(components: &[InstalledComponent], now: i64) -> Vec<&InstalledComponent> {
+    let mut candidates = components
+        .iter()
+        .filter(|component| component.removable && !component.in_use)
+        .filter(|component| {
+            component
+                .last_used
+                .is_none_or(|used| now.saturating_sub(used) >= RECENT_USE_GRACE_SECS)
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|component| {
+        (
+            component.last_used.unwrap_or(i64::MIN),
+            std::cmp::Reverse(component.bytes),
+        )
+    });
+    candidates
+}
+
+/// Removes `id` through the registry's removal path unless something holds
+/// it. `None` means it was busy and nothing changed.
+fn remove_if_idle(id: &str) -> Result<Option<RemovalOutcome>> {
+    let _mutation = super::acquire_mutation_guard()?;
+    validate_identifier(id)?;
+    if !super::embedded_catalog().is_removable(id) {
+        bail!("component is not removable by this host");
+    }
+    if !super::lease::reserve_idle_removal(id) {
+        return Ok(None);
+    }
+    if let Err(error) = super::pending::record(id) {
+        super::lease::finish_removal(id, true);
+        return Err(error);
+    }
+    super::removal::run_reserved_removal(id).map(Some)
+}
+
+fn directory_size(directory: &Path, depth: usize, visited: &mut usize) -> Result<u64> {
+    if depth > MAX_DIRECTORY_DEPTH {
+        bail!("component directory tree is too deep");
+    }
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+        Err(error) => return Err(error.into()),
+    };
+    let mut bytes = 0;
+    for entry in entries {
+        *visited += 1;
+        if *visited > MAX_ENTRIES_PER_COMPONENT {
+            bail!("component directory tree contains too many entries");
+        }
+        let path = entry?.path();
+        let metadata = std::fs::symlink_metadata(&path)?;
+        if is_reparse_point(&metadata) {
+            continue;
+        }
+        if metadata.is_dir() {
+            bytes += directory_size(&path, depth + 1, visited)?;
+        } else if metadata.is_file() {
+            bytes += metadata.len();
+        }
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(id: &str, bytes: u64, last_used: Option<i64>) -> InstalledComponent {
+        InstalledComponent {
+            id: id.to_string(),
+            display_name: id.to_string(),
+            bytes,
+            last_used,
+            in_use: false,
+            removable: true,
+        }
+    }
+
+    #[test]
+    fn eviction_takes_the_least_recently_used_idle_components_first() {
+        let now = 1_000_000;
+        let mut leased = installed("leased", 50, Some(10));
+        leased.in_use = true;
+        let mut pinned = installed("pinned", 50, Some(10));
+        pinned.removable = false;
+        let components = [
+            installed("recent", 90, Some(now - 60)),
+            installed("older", 10, Some(now - 86_400)),
+            leased,
+            installed("oldest-small", 5, Some(100)),
+            installed("oldest-large", 70, Some(100)),
+            pinned,
+        ];
+        let order = eviction_candidates(&components, now)
+            .into_iter()
+            .map(|component| component.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(order, ["oldest-large", "oldest-small", "older"]);
+    }
+
+    #[test]
+    fn directory_size_counts_nested_files() {
+        let root = std::env::temp_dir().join(format!(
+            "screen-goated-toolbox-storage-size-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("1.0.0/bin")).unwrap();
+        std::fs::write(root.join("1.0.0/receipt.json"), [0_u8; 7]).unwrap();
+        std::fs::write(root.join("1.0.0/bin/tool.exe"), [0_u8; 13]).unwrap();
+        assert_eq!(directory_size(&root, 0, &mut 0).unwrap(), 20);
+        assert_eq!(directory_size(&root.join("missing"), 0, &mut 0).unwrap(), 0);
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
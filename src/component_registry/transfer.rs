@@ -0,0 +1,325 @@
+//! Resumable, mirror-aware downloads of pinned component payloads.
+//!
+//! Every payload is pinned by size and SHA-256, so any source that serves those
+//! bytes is as good as the pinned URL. A download tries the configured internal
+//! mirrors in order before the pinned URL, resumes with HTTP Range requests,
+//! and keeps its partial file across cancellation, failures and restarts. The
+//! journal beside a partial file records one digest per completed chunk; resumed
+//! bytes are re-checked against it before they are trusted, and the finished
+//! file is still verified against the pinned digest before it is handed over.
+
+mod partial;
+#[cfg(test)]
+mod tests;
+
+use std::io::Read as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::{Result, anyhow, bail};
+
+use partial::Partial;
+
+/// Consecutive failed attempts, per source, before a download gives up. Any
+/// attempt that stores new bytes starts the count again.
+const ATTEMPTS_PER_SOURCE: u32 = 3;
+const POLICY: Policy = Policy {
+    chunk_bytes: 8 * 1024 * 1024,
+    first_backoff: Duration::from_secs(1),
+    max_backoff: Duration::from_secs(30),
+};
+
+/// One pinned payload. `label` names it in errors, e.g. "model".
+pub(crate) struct Download<'a> {
+    pub(crate) label: &'a str,
+    pub(crate) url: &'a str,
+    pub(crate) size_bytes: u64,
+    pub(crate) sha256: &'a str,
+}
+
+#[derive(Clone, Copy)]
+struct Policy {
+    /// Bytes per journaled digest; at most this much is re-downloaded after
+    /// an interruption.
+    chunk_bytes: u64,
+    first_backoff: Duration,
+    max_backoff: Duration,
+}
+
+/// Downloads `download` to `target`, which must not exist yet. On error or
+/// cancellation the verified prefix is kept for the next attempt.
+pub(crate) fn fetch(
+    download: &Download<'_>,
+    target: &Path,
+    cancelled: &AtomicBool,
+    on_progress: impl Fn(u64),
+) -> Result<()> {
+    let mirrors = crate::APP
+        .lock()
+        .map(|app| app.config.component_mirrors.clone())
+        .unwrap_or_default();
+    let sources = sources(download.url, &mirrors)?;
+    fetch_from(
+        download,
+        &sources,
+        true,
+        POLICY,
+        target,
+        cancelled,
+        &on_progress,
+    )
+}
+
+/// The ordered places to fetch `url` from: each usable mirror prefix as
+/// `{prefix}/{host}{path}`, then the pinned URL itself.
+pub(crate) fn sources(url: &str, mirrors: &[String]) -> Result<Vec<String>> {
+    let origin =
+        url::Url::parse(url).map_err(|error| anyhow!("component URL is invalid: {error}"))?;
+    let host = origin
+        .host_str()
+        .ok_or_else(|| anyhow!("component URL has no host"))?;
+    let mut resource = format!("{host}{}", origin.path());
+    if let Some(query) = origin.query() {
+        resource.push('?');
+        resource.push_str(query);
+    }
+    let mut sources = Vec::new();
+    for mirror in mirrors.iter().map(|mirror| mirror.trim()) {
+        if mirror.is_empty() {
+            continue;
+        }
+        if let Err(error) = validate_mirror(mirror) {
+            crate::log_info!("[Components] ignoring download mirror {mirror}: {error}");
+            continue;
+        }
+        let source = format!("{}/{resource}", mirror.trim_end_matches('/'));
+        if !sources.contains(&source) {
+            sources.push(source);
+        }
+    }
+    if !sources.iter().any(|source| source == url) {
+        sources.push(url.to_string());
+    }
+    Ok(sources)
+}
+
+fn validate_mirror(prefix: &str) -> Result<()> {
+    let parsed = url::Url::parse(prefix).map_err(|error| anyhow!("{error}"))?;
+    if parsed.scheme() != "https" || parsed.host_str().is_none() {
+        bail!("mirror prefixes must be https:// URLs");
+    }
+    if parsed.query().is_some() || parsed.fragment().is_some() {
+        bail!("mirror prefixes cannot carry a query or fragment");
+    }
+    Ok(())
+}
+
+enum Failure {
+    /// This source failed; another attempt may succeed.
+    Retry(anyhow::Error),
+    /// Local failure or cancellation; retrying cannot help.
+    Fatal(anyhow::Error),
+}
+
+fn fetch_from(
+    download: &Download<'_>,
+    sources: &[String],
+    https_only: bool,
+    policy: Policy,
+    target: &Path,
+    cancelled: &AtomicBool,
+    on_progress: &dyn Fn(u64),
+) -> Result<()> {
+    if sources.is_empty() {
+        bail!("{} download has no source", download.label);
+    }
+    if std::fs::symlink_metadata(target).is_ok() {
+        bail!("{} download target already exists", download.label);
+    }
+    let directory = resume_directory(target)?;
+    partial::prune_stale(&directory);
+    let mut partial = Partial::open(&directory, download, policy.chunk_bytes)?;
+    on_progress(partial.len());
+    let give_up_after = ATTEMPTS_PER_SOURCE.saturating_mul(sources.len() as u32);
+    let mut failures = 0_u32;
+    let mut source = 0_usize;
+    while partial.len() < download.size_bytes {
+        if cancelled.load(Ordering::Relaxed) {
+            bail!("{} download cancelled", download.label);
+        }
+        let before = partial.len();
+        let error = match attempt(
+            &sources[source],
+            https_only,
+            &mut partial,
+            download,
+            cancelled,
+            on_progress,
+        ) {
+            Ok(()) => continue,
+            Err(Failure::Fatal(error)) => return Err(error),
+            Err(Failure::Retry(error)) => error,
+        };
+        if partial.len() > before {
+            // A source that was delivering is worth another try.
+            failures = 0;
+        } else {
+            failures += 1;
+            source = (source + 1) % sources.len();
+        }
+        crate::log_info!(
+            "[Components] {} download interrupted at {}/{} bytes: {error:#}",
+            download.label,
+            partial.len(),
+            download.size_bytes
+        );
+        if failures >= give_up_after {
+            return Err(error.context(format!("{} download failed", download.label)));
+        }
+        wait(policy.backoff(failures), cancelled, download.label)?;
+    }
+    partial.finish(target, download)
+}
+
+impl Policy {
+    fn backoff(self, failures: u32) -> Duration {
+        self.first_backoff
+            .saturating_mul(1 << failures.min(16))
+            .min(self.max_backoff)
+    }
+}
+
+fn wait(duration: Duration, cancelled: &AtomicBool, label: &str) -> Result<()> {
+    let deadline = std::time::Instant::now() + duration;
+    while std::time::Instant::now() < deadline {
+        if cancelled.load(Ordering::Relaxed) {
+            bail!("{label} download cancelled");
+        }
+        let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+        std::thread::sleep(remaining.min(Duration::from_millis(100)));
+    }
+    Ok(())
+}
+
+fn attempt(
+    url: &str,
+    https_only: bool,
+    partial: &mut Partial,
+    download: &Download<'_>,
+    cancelled: &AtomicBool,
+    on_progress: &dyn Fn(u64),
+) -> std::result::Result<(), Failure> {
+    let offset = partial.len();
+    let mut request = crate::api::client::UREQ_DOWNLOAD_AGENT
+        .get(url)
+        .config()
+        .https_only(https_only)
+        .build()
+        .header("User-Agent", "ScreenGoatedToolbox")
+        // Byte offsets must refer to the pinned file, not a compressed body.
+        .header("Accept-Encoding", "identity");
+    if offset > 0 {
+        request = request.header("Range", format!("bytes={offset}-"));
+    }
+    let response = match request.call() {
+        Ok(response) => response,
+        Err(ureq::Error::StatusCode(416)) if offset > 0 => {
+            partial.restart().map_err(Failure::Fatal)?;
+            on_progress(0);
+            return Err(Failure::Retry(anyhow!("source rejected the resume offset")));
+        }
+        Err(error) => return Err(Failure::Retry(anyhow!("{error}"))),
+    };
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    match response.status().as_u16() {
+        206 => {
+            let range = header("content-range").and_then(|value| content_range(&value));
+            if range != Some((offset, download.size_bytes - 1, Some(download.size_bytes)))
+                && range != Some((offset, download.size_bytes - 1, None))
+            {
+                return Err(Failure::Retry(anyhow!(
+                    "source answered with an unexpected range"
+                )));
+            }
+        }
+        200 => {
+            if offset > 0 {
+                partial.restart().map_err(Failure::Fatal)?;
+                on_progress(0);
+            }
+            let length = header("content-length").and_then(|value| value.parse::<u64>().ok());
+            if length.is_some_and(|length| length != download.size_bytes) {
+                return Err(Failure::Retry(anyhow!(
+                    "source size does not match this build"
+                )));
+            }
+        }
+        status => return Err(Failure::Retry(anyhow!("unexpected HTTP status {status}"))),
+    }
+    let mut reader = response.into_body().into_reader();
+    let mut buffer = vec![0_u8; 256 * 1024];
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(Failure::Fatal(anyhow!(
+                "{} download cancelled",
+                download.label
+            )));
+        }
+        let read = reader
+            .read(&mut buffer)
+            .map_err(|error| Failure::Retry(error.into()))?;
+        if read == 0 {
+            break;
+        }
+        if partial.len() + read as u64 > download.size_bytes {
+            return Err(Failure::Retry(anyhow!(
+                "source sent more than the pinned size"
+            )));
+        }
+        partial.append(&buffer[..read]).map_err(Failure::Fatal)?;
+        on_progress(partial.len());
+    }
+    if partial.len() < download.size_bytes {
+        return Err(Failure::Retry(anyhow!(
+            "source closed the connection early"
+        )));
+    }
+    Ok(())
+}
+
+/// Parses `bytes <first>-<last>/<total or *>`.
+fn content_range(value: &str) -> Option<(u64, u64, Option<u64>)> {
+    let (range, total) = value.trim().strip_prefix("bytes ")?.split_once('/')?;
+    let (first, last) = range.split_once('-')?;
+    let total = match total {
+        "*" => None,
+        total => Some(total.parse().ok()?),
+    };
+    Some((first.parse().ok()?, last.parse().ok()?, total))
+}
+
+/// Partial files live beside the scratch target so publishing them is a
+/// same-volume rename.
+fn resume_directory(target: &Path) -> Result<PathBuf> {
+    let parent = target
+        .parent()
+        .ok_or_else(|| anyhow!("component download target has no parent"))?;
+    let directory = parent.join("resume");
+    match std::fs::create_dir(&directory) {
+        Ok(()) => {}
+        Err(error) if error.kind() == std::io::ErrorKind::AlreadyExists => {}
+        Err(error) => return Err(error.into()),
+    }
+    let metadata = std::fs::symlink_metadata(&directory)?;
+    if !metadata.is_dir() || super::receipt::is_reparse_point(&metadata) {
+        bail!("component resume directory is not a regular directory");
+    }
+    Ok(directory)
+}
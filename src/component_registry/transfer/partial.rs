@@ -0,0 +1,241 @@
+//! The partial file of an interrupted download and its chunk journal.
+
+use std::fs::File;
+use std::io::{Read as _, Seek as _, SeekFrom, Write as _};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+
+use super::Download;
+
+const JOURNAL_SCHEMA_VERSION: u32 = 1;
+/// Partial files nobody resumed for this long are deleted.
+const STALE_AFTER: Duration = Duration::from_secs(14 * 24 * 60 * 60);
+const MAX_RESUME_ENTRIES: usize = 256;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug)]
+#[serde(rename_all = "camelCase")]
+struct Journal {
+    schema_version: u32,
+    sha256: String,
+    size_bytes: u64,
+    chunk_bytes: u64,
+    /// SHA-256 of each completed chunk, in file order.
+    chunks: Vec<String>,
+}
+
+pub(super) struct Partial {
+    file: File,
+    path: PathBuf,
+    journal_path: PathBuf,
+    journal: Journal,
+    /// Hash of every byte in the file, for the final pinned-digest check.
+    whole: Sha256,
+    /// Hash of the bytes after the last completed chunk.
+    chunk: Sha256,
+    len: u64,
+}
+
+impl Partial {
+    /// Opens the partial file for `download`, keeping only the prefix whose
+    /// chunks still match the journal.
+    pub(super) fn open(
+        directory: &Path,
+        download: &Download<'_>,
+        chunk_bytes: u64,
+    ) -> Result<Self> {
+        let sha256 = download.sha256.to_ascii_lowercase();
+        if sha256.len() != 64 || !sha256.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            bail!("{} download has an invalid pinned SHA-256", download.label);
+        }
+        let path = directory.join(format!("{sha256}.partial"));
+        let journal_path = directory.join(format!("{sha256}.partial.json"));
+        for existing in [&path, &journal_path] {
+            if let Ok(metadata) = std::fs::symlink_metadata(existing)
+                && (!metadata.is_file() || super::super::receipt::is_reparse_point(&metadata))
+            {
+                bail!("{} partial download is not a regular file", download.label);
+            }
+        }
+        let mut options = std::fs::OpenOptions::new();
+        options.read(true).write(true).create(true).truncate(false);
+        #[cfg(windows)]
+        {
+            use std::os::windows::fs::OpenOptionsExt as _;
+            options.share_mode(0);
+        }
+        let file = options.open(&path)?;
+        let fresh = Journal {
+            schema_version: JOURNAL_SCHEMA_VERSION,
+            sha256,
+            size_bytes: download.size_bytes,
+            chunk_bytes,
+            chunks: Vec::new(),
+        };
+        let journal = std::fs::read(&journal_path)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Journal>(&bytes).ok())
+            .filter(|journal| {
+                journal.schema_version == fresh.schema_version
+                    && journal.sha256 == fresh.sha256
+                    && journal.size_bytes == fresh.size_bytes
+                    && journal.chunk_bytes == fresh.chunk_bytes
+            })
+            .unwrap_or(fresh);
+        let mut partial = Self {
+            file,
+            path,
+            journal_path,
+            journal,
+            whole: Sha256::new(),
+            chunk: Sha256::new(),
+            len: 0,
+        };
+        partial.verify_prefix()?;
+        Ok(partial)
+    }
+
+    pub(super) fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Re-hashes the journaled chunks and drops everything from the first one
+    /// that no longer matches, along with any unjournaled tail.
+    fn verify_prefix(&mut self) -> Result<()> {
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut buffer = vec![0_u8; 256 * 1024];
+        let mut verified = 0;
+        'chunks: for expected in &self.journal.chunks {
+            let chunk_len = self.chunk_len(self.len);
+            let mut chunk = Sha256::new();
+            let mut whole = self.whole.clone();
+            let mut read = 0_u64;
+            while read < chunk_len {
+                let wanted = (chunk_len - read).min(buffer.len() as u64) as usize;
+                let count = self.file.read(&mut buffer[..wanted])?;
+                if count == 0 {
+                    break 'chunks;
+                }
+                chunk.update(&buffer[..count]);
+                whole.update(&buffer[..count]);
+                read += count as u64;
+            }
+            if format!("{:x}", chunk.finalize()) != *expected {
+                break;
+            }
+            self.whole = whole;
+            self.len += chunk_len;
+            verified += 1;
+        }
+        self.journal.chunks.truncate(verified);
+        self.file.set_len(self.len)?;
+        self.file.seek(SeekFrom::Start(self.len))?;
+        self.save_journal()
+    }
+
+    fn chunk_len(&self, start: u64) -> u64 {
+        self.journal
+            .chunk_bytes
+            .min(self.journal.size_bytes.saturating_sub(start))
+    }
+
+    pub(super) fn append(&mut self, mut bytes: &[u8]) -> Result<()> {
+        while !bytes.is_empty() {
+            let chunk_start = self.journal.chunks.len() as u64 * self.journal.chunk_bytes;
+            let chunk_end = chunk_start + self.chunk_len(chunk_start);
+            let take = ((chunk_end - self.len) as usize).min(bytes.len());
+            if take == 0 {
+                bail!("partial download exceeds its pinned size");
+            }
+            self.file.write_all(&bytes[..take])?;
+            self.whole.update(&bytes[..take]);
+            self.chunk.update(&bytes[..take]);
+            self.len += take as u64;
+            bytes = &bytes[take..];
+            if self.len == chunk_end {
+                // Bytes must be durable before the journal vouches for them.
+                self.file.sync_data()?;
+                let digest = std::mem::take(&mut self.chunk).finalize();
+                self.journal.chunks.push(format!("{digest:x}"));
+                self.save_journal()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Discards everything, for sources that cannot continue from the offset.
+    pub(super) fn restart(&mut self) -> Result<()> {
+        self.file.set_len(0)?;
+        self.file.seek(SeekFrom::Start(0))?;
+        self.whole = Sha256::new();
+        self.chunk = Sha256::new();
+        self.len = 0;
+        self.journal.chunks.clear();
+        self.save_journal()
+    }
+
+    /// Moves the complete file to `target` once it matches the pinned digest;
+    /// a mismatch discards it so the next attempt starts clean.
+    pub(super) fn finish(self, target: &Path, download: &Download<'_>) -> Result<()> {
+        let Self {
+            file,
+            path,
+            journal_path,
+            journal,
+            whole,
+            len,
+            ..
+        } = self;
+        let matches =
+            len == download.size_bytes && format!("{:x}", whole.finalize()) == journal.sha256;
+        if !matches {
+            drop(file);
+            let _ = std::fs::remove_file(&path);
+            let _ = std::fs::remove_file(&journal_path);
+            bail!(
+                "{} download does not match its pinned SHA-256",
+                download.label
+            );
+        }
+        file.sync_all()?;
+        drop(file);
+        std::fs::rename(&path, target)?;
+        let _ = std::fs::remove_file(&journal_path);
+        Ok(())
+    }
+
+    fn save_journal(&self) -> Result<()> {
+        crate::atomic_json::write_json_atomic(&self.journal_path, &self.journal)?;
+        Ok(())
+    }
+}
+
+/// Deletes partial downloads, and their journals, untouched for a long time.
+/// Files held open by another download cannot be removed and are skipped.
+pub(super) fn prune_stale(directory: &Path) {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+    let now = SystemTime::now();
+    for entry in entries.take(MAX_RESUME_ENTRIES).flatten() {
+        let path = entry.path();
+        let is_partial = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(".partial") || name.ends_with(".partial.json"));
+        let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+            continue;
+        };
+        let stale = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > STALE_AFTER);
+        if is_partial && stale && metadata.is_file() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+use std::io::{BufRead as _, BufReader, Write as _};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+
+use sha2::{Digest as _, Sha256};
+
+use super::partial::Partial;
+use super::{Download, Policy, content_range, fetch_from, sources};
+
+const TEST_POLICY: Policy = Policy {
+    chunk_bytes: 8,
+    first_backoff: Duration::from_millis(1),
+    max_backoff: Duration::from_millis(4),
+};
+
+fn scratch(name: &str) -> PathBuf {
+    let root =
+        std::env::temp_dir().join(format!("sgt-transfer-test-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+fn fixture<'a>(url: &'a str, bytes: &[u8], sha256: &'a str) -> Download<'a> {
+    Download {
+        label: "fixture",
+        url,
+        size_bytes: bytes.len() as u64,
+        sha256,
+    }
+}
+
+fn digest(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[test]
+fn mirrors_are_tried_in_order_before_the_pinned_url() {
+    let url = "https://github.com/owner/repo/releases/download/v1/model.zip?raw=1";
+    let mirrors = [
+        " https://mirror.corp.example/sgt/ ".to_string(),
+        String::new(),
+        "http://plain.example/sgt".to_string(),
+        "https://mirror.corp.example/sgt".to_string(),
+        "https://backup.example".to_string(),
+    ];
+    assert_eq!(
+        sources(url, &mirrors).unwrap(),
+        [
+            "https://mirror.corp.example/sgt/github.com/owner/repo/releases/download/v1/model.zip?raw=1",
+            "https://backup.example/github.com/owner/repo/releases/download/v1/model.zip?raw=1",
+            url,
+        ]
+    );
+    assert_eq!(sources(url, &[]).unwrap(), [url]);
+    assert!(super::validate_mirror("https://mirror.example/?token=1").is_err());
+}
+
+#[test]
+fn content_ranges_are_parsed_strictly() {
+    assert_eq!(content_range("bytes 10-29/30"), Some((10, 29, Some(30))));
+    assert_eq!(content_range("bytes 10-29/*"), Some((10, 29, None)));
+    assert_eq!(content_range("items 10-29/30"), None);
+    assert_eq!(content_range("bytes 10-/30"), None);
+}
+
+#[test]
+fn a_reopened_partial_keeps_only_chunks_that_still_match() {
+    let root = scratch("partial");
+    let payload = b"0123456789abcdefghij";
+    let pinned = digest(payload);
+    let download = fixture("https://example.test/a", payload, &pinned);
+
+    let mut partial = Partial::open(&root, &download, 8).unwrap();
+    partial.append(&payload[..19]).unwrap();
+    drop(partial);
+    let partial = Partial::open(&root, &download, 8).unwrap();
+    assert_eq!(partial.len(), 16, "the unjournaled tail is dropped");
+    drop(partial);
+
+    let path = root.join(format!("{pinned}.partial"));
+    let mut bytes = std::fs::read(&path).unwrap();
+    bytes[9] = b'X';
+    std::fs::write(&path, bytes).unwrap();
+    let mut partial = Partial::open(&root, &download, 8).unwrap();
+    assert_eq!(partial.len(), 8, "a corrupted chunk is downloaded again");
+
+    partial.append(&payload[8..]).unwrap();
+    let target = root.join("payload.bin");
+    partial.finish(&target, &download).unwrap();
+    assert_eq!(std::fs::read(&target).unwrap(), payload);
+    assert!(!path.exists());
+    assert!(!root.join(format!("{pinned}.partial.json")).exists());
+
+    let expected = digest(b"something else");
+    let wrong = fixture("https://example.test/b", b"something else", &expected);
+    let mut partial = Partial::open(&root, &wrong, 8).unwrap();
+    partial.append(b"something elsf").unwrap();
+    let error = partial.finish(&root.join("wrong.bin"), &wrong).unwrap_err();
+    assert!(error.to_string().contains("SHA-256"), "{error:#}");
+    assert!(!root.join(format!("{expected}.partial")).exists());
+    let _ = std::fs::remove_dir_all(root);
+}
+
+/// Serves one scripted response per connection and records each request's
+/// path and Range header.
+fn serve(responses: Vec<Vec<u8>>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        let mut seen = Vec::new();
+        for response in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                let lower = line.to_ascii_lowercase();
+                if request.is_empty() {
+                    request = line.split_whitespace().nth(1).unwrap().to_string();
+                } else if let Some(range) = lower.strip_prefix("range:") {
+                    request.push_str(&format!(" {}", range.trim()));
+                }
+                line.clear();
+            }
+            seen.push(request);
+            stream.write_all(&response).unwrap();
+        }
+        seen
+    });
+    (base, server)
+}
+
+#[test]
+fn an_interrupted_download_moves_past_a_broken_mirror_and_resumes() {
+    let root = scratch("resume");
+    let payload = b"abcdefghijklmnopqrstuvwxyz0123";
+    let mut first = b"HTTP/1.1 200 OK\r\nContent-Length: 30\r\nConnection: close\r\n\r\n".to_vec();
+    first.extend_from_slice(&payload[..10]);
+    let mut rest = b"HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 10-29/30\r\n\
+Content-Length: 20\r\nConnection: close\r\n\r\n"
+        .to_vec();
+    rest.extend_from_slice(&payload[10..]);
+    let missing = b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    let (base, server) = serve(vec![missing.to_vec(), first, rest]);
+
+    let origin = format!("{base}/origin/payload.zip");
+    let pinned = digest(payload);
+    let download = fixture(&origin, payload, &pinned);
+    let sources = [format!("{base}/mirror/payload.zip"), origin.clone()];
+    let target = root.join("payload.download");
+    let progress = std::sync::Mutex::new(Vec::new());
+    fetch_from(
+        &download,
+        &sources,
+        false,
+        TEST_POLICY,
+        &target,
+        &AtomicBool::new(false),
+        &|done| progress.lock().unwrap().push(done),
+    )
+    .unwrap();
+
+    assert_eq!(std::fs::read(&target).unwrap(), payload);
+    assert_eq!(
+        server.join().unwrap(),
+        [
+            "/mirror/payload.zip",
+            "/origin/payload.zip",
+            "/origin/payload.zip bytes=10-"
+        ]
+    );
+    assert_eq!(progress.lock().unwrap().last(), Some(&30));
+    assert!(
+        !root
+            .join("resume")
+            .join(format!("{pinned}.partial"))
+            .exists()
+    );
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[test]
+fn a_cancelled_download_keeps_its_verified_prefix() {
+    let root = scratch("cancel");
+    let payload = b"abcdefghijklmnopqrstuvwxyz0123";
+    let mut response =
+        b"HTTP/1.1 200 OK\r\nContent-Length: 30\r\nConnection: close\r\n\r\n".to_vec();
+    response.extend_from_slice(&payload[..10]);
+    let (base, server) = serve(vec![response]);
+    let origin = format!("{base}/origin/payload.zip");
+    let pinned = digest(payload);
+    let download = fixture(&origin, payload, &pinned);
+    let cancelled = AtomicBool::new(false);
+    let target = root.join("payload.download");
+    let error = fetch_from(
+        &download,
+        std::slice::from_ref(&origin),
+        false,
+        TEST_POLICY,
+        &target,
+        &cancelled,
+        &|done| cancelled.store(done >= 10, std::sync::atomic::Ordering::Relaxed),
+    )
+    .unwrap_err();
+    server.join().unwrap();
+    assert!(error.to_string().contains("cancelled"), "{error:#}");
+    assert!(!target.exists());
+    let partial = Partial::open(&root.join("resume"), &download, 8).unwrap();
+    assert_eq!(partial.len(), 8);
+    drop(partial);
+    let _ = std::fs::remove_dir_all(root);
+}
@@ -229,6 +229,131 @@ fn embedded_catalog_sequence() -> u64 {
     *SEQUENCE
 }
 
+/// A component version exactly as the signed catalog pins it.
+#[derive(Debug)]
+pub(crate) struct PinnedComponent {
+    pub(crate) dependencies: Vec<String>,
+    pub(crate) files: Vec<super::OwnedComponentFile>,
+}
+
+/// Finds `id` at `version` among the active catalog's deliveries. Contracts
+/// lay their deliveries out differently, but every installable component is
+/// an object with an `id` and a `files` list of `path`/`sizeBytes`/`sha256`,
+/// carrying its `version` itself or inheriting it from an enclosing object.
+pub(crate) fn pinned_component(id: &str, version: &str) -> Option<PinnedComponent> {
+    if cfg!(sgt_staging_delivery) {
+        return None;
+    }
+    let catalog = ACTIVE.read().ok().and_then(|catalog| catalog.clone())?;
+    pinned_from(&catalog, id, version, embedded_catalog_sequence())
+}
+
+fn pinned_from(
+    catalog: &UpdateCatalog,
+    id: &str,
+    version: &str,
+    minimum_sequence: u64,
+) -> Option<PinnedComponent> {
+    if catalog.sequence < minimum_sequence {
+        return None;
+    }
+    catalog
+        .contracts
+        .iter()
+        .filter(|contract| matches!(contract.platform.as_str(), PLATFORM | "multi"))
+        .find_map(|contract| find_pinned(&contract.delivery, id, version, None))
+}
+
+fn find_pinned(
+    value: &Value,
+    id: &str,
+    version: &str,
+    inherited_version: Option<&str>,
+) -> Option<PinnedComponent> {
+    match value {
+        Value::Object(object) => {
+            let own_version = object
+                .get("version")
+                .and_then(Value::as_str)
+                .or(inherited_version);
+            if object.get("id").and_then(Value::as_str) == Some(id)
+                && own_version == Some(version)
+                && let Some(files) = object.get("files").and_then(Value::as_array)
+            {
+                return pinned_entry(object, files);
+            }
+            object
+                .values()
+                .find_map(|child| find_pinned(child, id, version, own_version))
+        }
+        Value::Array(items) => items
+            .iter()
+            .find_map(|item| find_pinned(item, id, version, inherited_version)),
+        _ => None,
+    }
+}
+
+fn pinned_entry(
+    object: &serde_json::Map<String, Value>,
+    files: &[Value],
+) -> Option<PinnedComponent> {
+    let files = files
+        .iter()
+        .map(|file| {
+            Some(super::OwnedComponentFile {
+                path: file.get("path")?.as_str()?.into(),
+                size_bytes: file.get("sizeBytes")?.as_u64()?,
+                sha256: file.get("sha256")?.as_str()?.to_ascii_lowercase(),
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    let dependencies = match object.get("dependencies") {
+        None => Vec::new(),
+        Some(dependencies) => dependencies
+            .as_array()?
+            .iter()
+            .map(|dependency| dependency.as_str().map(str::to_string))
+            .collect::<Option<Vec<_>>>()?,
+    };
+    Some(PinnedComponent {
+        dependencies,
+        files,
+    })
+}
+
+/// Adopts the highest cached catalog without touching the network, for
+/// command-line paths that run before the background refresh.
+pub(crate) fn load_cached() -> Result<()> {
+    if let Some(catalog) = cache::load_highest()? {
+        activate(catalog);
+    }
+    Ok(())
+}
+
+/// Verifies and adopts a signed catalog carried in by hand, exactly as one
+/// fetched from the release would be. Returns its sequence.
+pub(crate) fn import_signed(name: &str, catalog_bytes: &[u8], signature: &[u8]) -> Result<u64> {
+    if cfg!(sgt_staging_delivery) {
+        bail!("production component updates are disabled for staging debug builds");
+    }
+    let catalog = parse_verified(catalog_bytes, signature)?;
+    cache::store(name, catalog_bytes, signature)?;
+    let sequence = catalog.sequence;
+    activate(catalog);
+    Ok(sequence)
+}
+
+/// The highest verified catalog as cached, for carrying to another machine.
+pub(crate) struct SignedCatalogFiles {
+    pub(crate) name: String,
+    pub(crate) catalog: Vec<u8>,
+    pub(crate) signature: Vec<u8>,
+}
+
+pub(crate) fn signed_catalog_files() -> Result<Option<SignedCatalogFiles>> {
+    cache::load_highest_files()
+}
+
 pub(crate) fn policy(id: &str) -> Option<(String, u64, String)> {
     if cfg!(sgt_staging_delivery) {
         return None;
@@ -4,12 +4,31 @@ use std::sync::{LazyLock, Mutex};
 
 use anyhow::{Context, Result, bail};
 
-use super::{UpdateCatalog, parse_verified};
+use super::{SignedCatalogFiles, UpdateCatalog, parse_verified};
 
 const MAX_CACHED_CATALOGS: usize = 64;
 static STORE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
 
 pub(super) fn load_highest() -> Result<Option<UpdateCatalog>> {
+    Ok(highest()?.map(|highest| highest.parsed))
+}
+
+pub(super) fn load_highest_files() -> Result<Option<SignedCatalogFiles>> {
+    Ok(highest()?.map(|highest| SignedCatalogFiles {
+        name: highest.name,
+        catalog: highest.catalog,
+        signature: highest.signature,
+    }))
+}
+
+struct Highest {
+    name: String,
+    catalog: Vec<u8>,
+    signature: Vec<u8>,
+    parsed: UpdateCatalog,
+}
+
+fn highest() -> Result<Option<Highest>> {
     let root = root();
     let Ok(metadata) = std::fs::symlink_metadata(&root) else {
         return Ok(None);
@@ -23,14 +42,14 @@ pub(super) fn load_highest() -> Result<Option<UpdateCatalog>> {
             continue;
         };
         if name.starts_with("sgt-component-catalog-v") && name.ends_with(".json") {
-            candidates.push(entry.path());
+            candidates.push((name.to_string(), entry.path()));
         }
     }
     if candidates.len() > MAX_CACHED_CATALOGS {
         bail!("component catalog cache contains too many entries");
     }
-    let mut best = None;
-    for catalog_path in candidates {
+    let mut best: Option<Highest> = None;
+    for (name, catalog_path) in candidates {
         let signature_path = catalog_path.with_extension("sig");
         let Ok(catalog) = read_regular(&catalog_path, 2 * 1024 * 1024) else {
             continue;
@@ -43,9 +62,14 @@ pub(super) fn load_highest() -> Result<Option<UpdateCatalog>> {
         };
         if best
             .as_ref()
-            .is_none_or(|current: &UpdateCatalog| parsed.sequence > current.sequence)
+            .is_none_or(|current| parsed.sequence > current.parsed.sequence)
         {
-            best = Some(parsed);
+            best = Some(Highest {
+                name,
+                catalog,
+                signature,
+                parsed,
+            });
         }
     }
     Ok(best)
@@ -74,6 +74,52 @@ fn older_catalog_cannot_downgrade_the_embedded_contract() {
     assert!(super::contract_from(&catalog, "fixture-v1", baseline).is_some());
 }
 
+#[test]
+fn pinned_components_are_found_whatever_the_contract_layout() {
+    let baseline = super::embedded_catalog_sequence();
+    let file = serde_json::json!({"path": "bin/x64/worker.exe", "sizeBytes": 4, "sha256": "AB".repeat(32)});
+    let catalog = super::UpdateCatalog {
+        schema_version: 1,
+        sequence: baseline,
+        channel: "stable".into(),
+        min_host_version: env!("CARGO_PKG_VERSION").into(),
+        max_host_version_exclusive: "999.0.0".into(),
+        contracts: vec![
+            super::CatalogContract {
+                name: "fixture-runtime-v1".into(),
+                platform: "windows-x64".into(),
+                delivery: serde_json::json!({
+                    "version": "2.0.0",
+                    "windows": {"components": [{
+                        "id": "fixture-runtime",
+                        "dependencies": ["fixture-vc"],
+                        "files": [file.clone()]
+                    }]}
+                }),
+            },
+            super::CatalogContract {
+                name: "fixture-android-v1".into(),
+                platform: "android-arm64".into(),
+                delivery: serde_json::json!({"component": {
+                    "id": "fixture-worker", "version": "1.0.0", "files": [file.clone()]
+                }}),
+            },
+        ],
+        policies: Vec::new(),
+    };
+
+    let pinned = super::pinned_from(&catalog, "fixture-runtime", "2.0.0", baseline).unwrap();
+    assert_eq!(pinned.dependencies, ["fixture-vc"]);
+    assert_eq!(pinned.files[0].size_bytes, 4);
+    assert_eq!(pinned.files[0].sha256, "ab".repeat(32));
+    assert!(super::pinned_from(&catalog, "fixture-runtime", "1.0.0", baseline).is_none());
+    assert!(
+        super::pinned_from(&catalog, "fixture-worker", "1.0.0", baseline).is_none(),
+        "another platform's delivery is never pinned here"
+    );
+    assert!(super::pinned_from(&catalog, "fixture-runtime", "2.0.0", baseline + 1).is_none());
+}
+
 fn decode(value: &str) -> Vec<u8> {
     value
         .as_bytes()
@@ -0,0 +1,162 @@
+//! Last-use times of installed components, for least-recently-used eviction.
+//!
+//! Leases and installs touch a component; the store is written at most once
+//! per `TOUCH_INTERVAL` per component so hot paths stay off the disk. Other
+//! processes may write the same file, so every write merges with what is
+//! already there and keeps the newer time.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Read as _;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::catalog::validate_identifier;
+
+const SCHEMA_VERSION: u32 = 1;
+const MAX_ENTRIES: usize = 256;
+const MAX_FILE_BYTES: u64 = 64 * 1024;
+const TOUCH_INTERVAL: Duration = Duration::from_secs(60);
+static STORE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+static LAST_WRITTEN: LazyLock<Mutex<HashMap<String, Instant>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct ComponentUsage {
+    schema_version: u32,
+    /// Unix seconds of the last use, by component id.
+    last_used: BTreeMap<String, i64>,
+}
+
+/// Records that `id` is in use now. Failures are logged, never returned, so
+/// bookkeeping cannot block a component from running.
+pub(super) fn touch(id: &str) {
+    {
+        let mut written = LAST_WRITTEN
+            .lock()
+            .unwrap_or_else(|value| value.into_inner());
+        if written
+            .get(id)
+            .is_some_and(|at| at.elapsed() < TOUCH_INTERVAL)
+        {
+            return;
+        }
+        written.insert(id.to_string(), Instant::now());
+    }
+    if let Err(error) = record(id, chrono::Utc::now().timestamp()) {
+        crate::log_info!("[Components] could not record use of {id}: {error:#}");
+    }
+}
+
+fn record(id: &str, at: i64) -> Result<()> {
+    validate_identifier(id)?;
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|value| value.into_inner());
+    let mut state = read_unlocked()?;
+    let entry = state.last_used.entry(id.to_string()).or_default();
+    *entry = (*entry).max(at);
+    // The oldest entries belong to components removed by another process.
+    while state.last_used.len() > MAX_ENTRIES {
+        let Some(oldest) = state
+            .last_used
+            .iter()
+            .min_by_key(|(_, used)| **used)
+            .map(|(id, _)| id.clone())
+        else {
+            break;
+        };
+        state.last_used.remove(&oldest);
+    }
+    write_unlocked(&state)
+}
+
+/// Forgets a removed component so a later reinstall starts fresh.
+pub(super) fn forget(id: &str) -> Result<()> {
+    validate_identifier(id)?;
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|value| value.into_inner());
+    let mut state = read_unlocked()?;
+    if state.last_used.remove(id).is_some() {
+        write_unlocked(&state)?;
+    }
+    LAST_WRITTEN
+        .lock()
+        .unwrap_or_else(|value| value.into_inner())
+        .remove(id);
+    Ok(())
+}
+
+#[cfg(not(feature = "recorder-worker"))]
+pub(super) fn last_used() -> Result<BTreeMap<String, i64>> {
+    let _guard = STORE_LOCK.lock().unwrap_or_else(|value| value.into_inner());
+    Ok(read_unlocked()?.last_used)
+}
+
+fn read_unlocked() -> Result<ComponentUsage> {
+    let path = path();
+    let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+        return Ok(ComponentUsage {
+            schema_version: SCHEMA_VERSION,
+            last_used: BTreeMap::new(),
+        });
+    };
+    if !metadata.is_file()
+        || super::receipt::is_reparse_point(&metadata)
+        || metadata.len() > MAX_FILE_BYTES
+    {
+        bail!("component usage state is unsafe");
+    }
+    let mut body = String::new();
+    std::fs::File::open(path)?
+        .take(MAX_FILE_BYTES + 1)
+        .read_to_string(&mut body)?;
+    let state: ComponentUsage = serde_json::from_str(&body)?;
+    validate(&state)?;
+    Ok(state)
+}
+
+fn write_unlocked(state: &ComponentUsage) -> Result<()> {
+    validate(state)?;
+    let path = path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::atomic_json::write_json_atomic(&path, state)?;
+    Ok(())
+}
+
+fn validate(state: &ComponentUsage) -> Result<()> {
+    if state.schema_version != SCHEMA_VERSION || state.last_used.len() > MAX_ENTRIES {
+        bail!("component usage state is invalid");
+    }
+    for id in state.last_used.keys() {
+        validate_identifier(id)?;
+    }
+    Ok(())
+}
+
+fn path() -> std::path::PathBuf {
+    #[cfg(test)]
+    return std::env::temp_dir().join(format!(
+        "screen-goated-toolbox-component-usage-{}.json",
+        std::process::id()
+    ));
+    #[cfg(not(test))]
+    crate::paths::app_runtime_local_data_dir().join("component-usage.json")
+}
+
+#[cfg(all(test, not(feature = "recorder-worker")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_use_keeps_the_newest_time_until_forgotten() {
+        let id = "test-usage-newest";
+        record(id, 200).unwrap();
+        record(id, 100).unwrap();
+        assert_eq!(last_used().unwrap().get(id), Some(&200));
+        forget(id).unwrap();
+        assert_eq!(last_used().unwrap().get(id), None);
+    }
+}
@@ -1,5 +1,5 @@
 use std::collections::HashSet;
-use std::io::{Read as _, Write as _};
+use std::io::Write as _;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{LazyLock, Mutex};
@@ -120,47 +120,17 @@ fn download_archive(
     target: &Path,
     on_progress: impl Fn(u64, u64),
 ) -> Result<()> {
-    let response = crate::api::client::UREQ_DOWNLOAD_AGENT
-        .get(delivery.download_url)
-        .header("User-Agent", "ScreenGoatedToolbox")
-        .call()
-        .map_err(|error| anyhow!("VC runtime download failed: {error}"))?;
-    if response
-        .headers()
-        .get("content-length")
-        .and_then(|value| value.to_str().ok())
-        .and_then(|value| value.parse::<u64>().ok())
-        .is_some_and(|size| size != delivery.size_bytes)
-    {
-        bail!("VC runtime download size does not match this build");
-    }
-    let mut reader = response.into_body().into_reader();
-    let mut output = std::fs::OpenOptions::new()
-        .write(true)
-        .create_new(true)
-        .open(target)?;
-    let mut buffer = [0_u8; 128 * 1024];
-    let mut downloaded = 0_u64;
-    loop {
-        let read = reader.read(&mut buffer)?;
-        if read == 0 {
-            break;
-        }
-        downloaded = downloaded
-            .checked_add(read as u64)
-            .ok_or_else(|| anyhow!("VC runtime download is too large"))?;
-        if downloaded > delivery.size_bytes {
-            bail!("VC runtime download is larger than this build allows");
-        }
-        output.write_all(&buffer[..read])?;
-        on_progress(downloaded, delivery.size_bytes);
-    }
-    if downloaded != delivery.size_bytes {
-        bail!("VC runtime download is incomplete");
-    }
-    output.flush()?;
-    output.sync_all()?;
-    Ok(())
+    let download = super::super::transfer::Download {
+        label: "VC runtime",
+        url: delivery.download_url,
+        size_bytes: delivery.size_bytes,
+        sha256: delivery.sha256,
+    };
+    // VC runtime installs have no cancel control of their own.
+    let cancelled = std::sync::atomic::AtomicBool::new(false);
+    super::super::transfer::fetch(&download, target, &cancelled, |downloaded| {
+        on_progress(downloaded, delivery.size_bytes)
+    })
 }
 
 fn validate_archive(path: &Path, delivery: &VcRuntimeDelivery) -> Result<()> {
@@ -9,12 +9,14 @@ use serde::{Deserialize, Serialize};
 
 use crate::config::preset::{Preset, get_default_presets};
 use crate::config::types::{
-    AdaptiveModelPriority, CustomModelDefinition, DEFAULT_HISTORY_LIMIT, DEFAULT_PROJECTS_LIMIT,
-    EdgeTtsSettings, Hotkey, KokoroSettings, MagpieSettings, ModelPriorityChains,
-    PendingPresetModelUpdate, PresetProfile, RestoreDefaultsSelection, ScreenTranslateSettings,
+    AdaptiveModelPriority, ApiKeyRotation, CustomModelDefinition, DEFAULT_HISTORY_LIMIT,
+    DEFAULT_PROJECTS_LIMIT, EdgeTtsSettings, Hotkey, KokoroSettings, MagpieSettings,
+    ModelPriorityChains, PendingPresetModelUpdate, PooledApiKey, PresetProfile,
+    ResponseCacheSettings, RestoreDefaultsSelection, ScreenTranslateSettings,
     StepAudioReferenceVoice, StepAudioSettings, SupertonicSettings, ThemeMode,
     TranslationGummySettings, TtsLanguageCondition, TtsMethod, TtsPlaygroundSettings,
-    VieneuSettings, VoxtralSettings, default_tts_language_conditions, get_system_ui_language,
+    UpdatePreferences, VieneuSettings, VoxtralSettings, default_tts_language_conditions,
+    get_system_ui_language,
 };
 
 // ============================================================================
@@ -93,18 +95,6 @@ fn default_step_audio_settings() -> StepAudioSettings {
     StepAudioSettings::default()
 }
 
-fn default_step_audio_reference_voices() -> Vec<StepAudioReferenceVoice> {
-    Vec::new()
-}
-
-fn default_magpie_settings() -> MagpieSettings {
-    MagpieSettings::default()
-}
-
-fn default_kokoro_settings() -> KokoroSettings {
-    KokoroSettings::default()
-}
-
 fn default_supertonic_settings() -> SupertonicSettings {
     SupertonicSettings::default()
 }
@@ -113,10 +103,6 @@ fn default_vieneu_settings() -> VieneuSettings {
     VieneuSettings::default()
 }
 
-fn default_voxtral_settings() -> VoxtralSettings {
-    VoxtralSettings::default()
-}
-
 fn default_tts_playground_settings() -> TtsPlaygroundSettings {
     TtsPlaygroundSettings::default()
 }
@@ -182,6 +168,12 @@ pub struct Config {
     #[serde(default)]
     pub nvidia_api_key: String,
 
+    /// Additional keys per provider, used in turn with the primary key.
+    #[serde(default)]
+    pub api_key_pool: Vec<PooledApiKey>,
+    #[serde(default)]
+    pub api_key_rotation: ApiKeyRotation,
+
     // -------------------------------------------------------------------------
     // Presets
     // -------------------------------------------------------------------------
@@ -300,6 +292,9 @@ pub struct Config {
     pub model_priority_chains: ModelPriorityChains,
     #[serde(default)]
     pub adaptive_model_priority: AdaptiveModelPriority,
+    /// Opt-in reuse of completed block results for repeated inputs.
+    #[serde(default)]
+    pub response_cache: ResponseCacheSettings,
 
     // -------------------------------------------------------------------------
     // Ollama Configuration
@@ -395,15 +390,15 @@ pub struct Config {
 
     /// Shared Step Audio reference voices for global TTS, narration, and the
     /// TTS Playground.
-    #[serde(default = "default_step_audio_reference_voices")]
+    #[serde(default)]
     pub step_audio_reference_voices: Vec<StepAudioReferenceVoice>,
 
     /// NVIDIA Magpie-Multilingual 357M settings (managed local sidecar).
-    #[serde(default = "default_magpie_settings")]
+    #[serde(default)]
     pub magpie_settings: MagpieSettings,
 
     /// Kokoro 82M v1.0 settings (sherpa-onnx local TTS).
-    #[serde(default = "default_kokoro_settings")]
+    #[serde(default)]
     pub kokoro_settings: KokoroSettings,
 
     /// Supertonic 3 settings (sherpa-onnx local TTS).
@@ -415,7 +410,7 @@ pub struct Config {
     pub vieneu_settings: VieneuSettings,
 
     /// Legacy TTS settings retained for config migration.
-    #[serde(default = "default_voxtral_settings")]
+    #[serde(default)]
     pub voxtral_settings: VoxtralSettings,
 
     /// Independent sandbox profile for the TTS Playground mini app.
@@ -448,6 +443,18 @@ pub struct Config {
     #[serde(default)]
     pub clear_webview_on_startup: bool,
 
+    /// Mirror URL prefixes tried in order before each pinned component URL.
+    #[serde(default)]
+    pub component_mirrors: Vec<String>,
+
+    /// Disk quota for downloaded components in GB; 0 disables eviction.
+    #[serde(default)]
+    pub component_disk_quota_gb: u32,
+
+    /// Update channel and the release the user chose to defer.
+    #[serde(default)]
+    pub updates: UpdatePreferences,
+
     /// Global hotkeys for screen recording start/stop
     #[serde(default = "default_screen_record_hotkeys")]
     pub screen_record_hotkeys: Vec<Hotkey>,
@@ -492,6 +499,8 @@ impl Default for Config {
             gemini_api_key: String::new(),
             openrouter_api_key: String::new(),
             nvidia_api_key: String::new(),
+            api_key_pool: Vec::new(),
+            api_key_rotation: ApiKeyRotation::default(),
 
             // Presets - use the centralized ordered list
             presets: get_default_presets(),
@@ -527,6 +536,7 @@ impl Default for Config {
             use_ollama: crate::model_config::DEFAULT_USE_OLLAMA,
             model_priority_chains: ModelPriorityChains::default(),
             adaptive_model_priority: AdaptiveModelPriority::default(),
+            response_cache: ResponseCacheSettings::default(),
 
             // Ollama
             ollama_base_url: "http://localhost:11434".to_string(),
@@ -571,6 +581,9 @@ impl Default for Config {
 
             // Maintenance
             clear_webview_on_startup: false,
+            component_mirrors: Vec::new(),
+            component_disk_quota_gb: 0,
+            updates: UpdatePreferences::default(),
 
             // Screen Record
             screen_record_hotkeys: default_screen_record_hotkeys(),
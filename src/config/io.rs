@@ -56,6 +56,8 @@ pub fn load_config() -> Config {
         }
     };
 
+    // Plaintext keys from before the secrets store get sealed by the resave.
+    let holds_plaintext_keys = crate::config::secrets::reveal(&mut config);
     let should_persist_retirement = config
         .presets
         .iter()
@@ -69,9 +71,10 @@ pub fn load_config() -> Config {
 
     // Apply migrations and merge new defaults
     migrate_config(&mut config);
-    if should_persist_retirement {
+    if should_persist_retirement || holds_plaintext_keys {
         save_config(&config);
     }
+    crate::api::key_pool::configure(&config);
 
     config
 }
@@ -372,11 +375,20 @@ fn normalize_translation_gummy_settings(config: &mut Config) {
 // ============================================================================
 
 /// Save config to disk atomically (temp + rename), so an interrupted write
-/// never truncates the single file that holds every preset, profile and API key.
+/// never truncates the single file that holds every preset and profile. API
+/// keys are written to the secrets store; the file keeps only their handles.
 pub fn save_config(config: &Config) {
     let path = get_config_path();
     let mut config_to_save = config.clone();
     config_to_save.sync_active_profile_from_presets();
+    crate::api::key_pool::configure(&config_to_save);
+    let config_to_save = match crate::config::secrets::seal(&config_to_save) {
+        Ok(sealed) => sealed,
+        Err(e) => {
+            crate::log_info!("[config] not saving config, secrets could not be sealed: {e}");
+            return;
+        }
+    };
     if let Err(e) = crate::atomic_json::write_json_atomic(&path, &config_to_save) {
         crate::log_info!("[config] failed to save config: {e}");
     }
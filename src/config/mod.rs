@@ -7,6 +7,7 @@
 //! - `preset`: Preset and ProcessingBlock with builder patterns
 //! - `types`: Core types (enums, TTS settings, hotkeys)
 //! - `io`: Load/save operations
+//! - `secrets`: API keys encrypted at rest, referenced from the config by handle
 //!
 //! ## Usage
 //! ```rust
@@ -32,6 +33,7 @@
 mod config;
 mod io;
 pub mod preset;
+pub mod secrets;
 pub mod tts_catalog;
 mod tts_catalog_gemini;
 pub mod types;
@@ -73,6 +75,10 @@ pub use types::ModelPriorityChains;
 #[cfg(not(feature = "recorder-worker"))]
 pub use types::RestoreDefaultsSelection;
 
+// Updater channel and deferral
+#[cfg(not(feature = "recorder-worker"))]
+pub use types::{UpdateChannel, UpdatePreferences};
+
 // TTS types
 #[cfg(feature = "recorder-worker")]
 pub use types::StepAudioVoiceConfig;
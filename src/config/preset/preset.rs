@@ -9,7 +9,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::block::ProcessingBlock;
-use crate::config::types::Hotkey;
+use crate::config::types::{DEFAULT_QUALITY_FLOOR, Hotkey, RoutingObjective};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct WindowGeometry {
@@ -100,6 +100,17 @@ pub struct Preset {
     #[serde(default)]
     pub continuous_input: bool,
 
+    // -------------------------------------------------------------------------
+    // Model Routing
+    // -------------------------------------------------------------------------
+    /// How the retry chain orders fallback models for this preset
+    #[serde(default)]
+    pub routing_objective: RoutingObjective,
+
+    /// Lowest intelligence tier the `Cheapest` objective prefers for its price
+    #[serde(default = "default_routing_quality_floor")]
+    pub routing_quality_floor: u8,
+
     // -------------------------------------------------------------------------
     // Hotkeys
     // -------------------------------------------------------------------------
@@ -163,6 +174,10 @@ fn default_true() -> bool {
     true
 }
 
+fn default_routing_quality_floor() -> u8 {
+    DEFAULT_QUALITY_FLOOR
+}
+
 // ============================================================================
 // PRESET DEFAULT IMPL
 // ============================================================================
@@ -186,6 +201,8 @@ impl Default for Preset {
             hide_recording_ui: false,
             auto_stop_recording: false,
             continuous_input: false,
+            routing_objective: RoutingObjective::default(),
+            routing_quality_floor: DEFAULT_QUALITY_FLOOR,
             hotkeys: vec![],
             is_upcoming: false,
             is_master: false,
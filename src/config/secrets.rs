@@ -0,0 +1,495 @@
+//! Provider API keys, encrypted at rest.
+//!
+//! On disk `config_v3.json` never holds a key: each secret field carries a
+//! handle such as `secret:groq`, and the sealed value lives in
+//! `secrets_v1.json` beside it. In memory the `Config` keeps plaintext, so
+//! nothing outside `load_config`/`save_config` needs to know about handles.
+//!
+//! Two backends seal values. The OS keystore (Windows DPAPI) needs nothing
+//! from the user. The passphrase backend derives a key with PBKDF2, seals with
+//! ChaCha20-Poly1305 and starts locked; until it is unlocked its keys load as
+//! empty and saving keeps the sealed values it cannot read. Unlocking a store
+//! derived with fewer iterations than this release uses reseals it. A config
+//! that still holds plaintext keys is migrated on the first load.
+
+mod cipher;
+mod dpapi;
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::Config;
+
+const FILE_NAME: &str = "secrets_v1.json";
+const SUPPORTED_VERSION: u32 = 1;
+const HANDLE_PREFIX: &str = "secret:";
+const POOL_PREFIX: &str = "pool/";
+/// Read once when the store loads, so unattended runs can unlock the store.
+const PASSPHRASE_ENV: &str = "SGT_SECRETS_PASSPHRASE";
+const PASSPHRASE_ITERATIONS: u32 = 600_000;
+/// Sealed under the store key so a wrong passphrase is caught at unlock time.
+const CHECK_PLAINTEXT: &[u8] = b"screen-goated-toolbox secrets";
+const LOCKED: &str = "the secrets store is locked";
+
+/// Where sealed API keys get their key material.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretBackend {
+    /// Windows DPAPI, tied to the signed-in account.
+    #[default]
+    OsKeystore,
+    /// A passphrase the user types once per run.
+    Passphrase,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct PassphraseParams {
+    /// [`CHECK_PLAINTEXT`] sealed under the store key. Its header carries the
+    /// salt and iteration count that derive that key.
+    check: String,
+}
+
+impl PassphraseParams {
+    fn new(passphrase: &str, iterations: u32) -> Result<(Self, cipher::Key), String> {
+        if passphrase.is_empty() {
+            return Err("the passphrase is empty".into());
+        }
+        let key = cipher::Key::derive(
+            passphrase.as_bytes(),
+            cipher::KdfParams::generate(iterations)?,
+        );
+        let params = Self {
+            check: BASE64.encode(cipher::seal(&key, CHECK_PLAINTEXT)?),
+        };
+        Ok((params, key))
+    }
+
+    fn derive(&self, passphrase: &str) -> Result<cipher::Key, String> {
+        let check = BASE64
+            .decode(&self.check)
+            .map_err(|error| format!("the stored check value is damaged: {error}"))?;
+        let key = cipher::Key::derive(passphrase.as_bytes(), cipher::KdfParams::of(&check)?);
+        match cipher::open(&key, &check) {
+            Ok(plaintext) if plaintext == CHECK_PLAINTEXT => Ok(key),
+            _ => Err("wrong passphrase".into()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+struct StoreFile {
+    version: u32,
+    #[serde(default)]
+    backend: SecretBackend,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    passphrase: Option<PassphraseParams>,
+    /// Secret name → base64 of the backend's sealed bytes.
+    #[serde(default)]
+    entries: BTreeMap<String, String>,
+}
+
+impl Default for StoreFile {
+    fn default() -> Self {
+        Self {
+            version: SUPPORTED_VERSION,
+            backend: SecretBackend::default(),
+            passphrase: None,
+            entries: BTreeMap::new(),
+        }
+    }
+}
+
+struct Store {
+    file: StoreFile,
+    /// The derived passphrase key once unlocked.
+    key: Option<cipher::Key>,
+    /// Plaintext last sealed or opened per entry, so an unchanged key is not
+    /// resealed (and the store file not rewritten) on every config save.
+    revealed: HashMap<String, String>,
+    /// `None` keeps the store in memory only.
+    path: Option<PathBuf>,
+    iterations: u32,
+}
+
+impl Store {
+    fn in_memory(file: StoreFile) -> Self {
+        Self {
+            file,
+            key: None,
+            revealed: HashMap::new(),
+            path: None,
+            iterations: PASSPHRASE_ITERATIONS,
+        }
+    }
+
+    fn load(path: PathBuf) -> Self {
+        let file = match std::fs::read(&path) {
+            Ok(bytes) => match serde_json::from_slice::<StoreFile>(&bytes) {
+                Ok(file) if file.version == SUPPORTED_VERSION => file,
+                Ok(file) => {
+                    crate::log_info!("[secrets] unsupported store version {}", file.version);
+                    set_aside(&path);
+                    StoreFile::default()
+                }
+                Err(error) => {
+                    crate::log_info!("[secrets] unreadable store: {error}");
+                    set_aside(&path);
+                    StoreFile::default()
+                }
+            },
+            Err(_) => StoreFile::default(),
+        };
+        let mut store = Self {
+            path: Some(path),
+            ..Self::in_memory(file)
+        };
+        if store.is_locked()
+            && let Ok(passphrase) = std::env::var(PASSPHRASE_ENV)
+            && let Err(error) = store.unlock(&passphrase)
+        {
+            crate::log_info!("[secrets] {PASSPHRASE_ENV} did not unlock the store: {error}");
+        }
+        store
+    }
+
+    fn is_locked(&self) -> bool {
+        self.file.backend == SecretBackend::Passphrase && self.key.is_none()
+    }
+
+    fn unlock(&mut self, passphrase: &str) -> Result<(), String> {
+        let params = self
+            .file
+            .passphrase
+            .as_ref()
+            .ok_or("no passphrase has been set")?;
+        let key = params.derive(passphrase)?;
+        let outdated = key.params().iterations < self.iterations;
+        self.key = Some(key);
+        if outdated && let Err(error) = self.switch(SecretBackend::Passphrase, Some(passphrase)) {
+            crate::log_info!("[secrets] could not strengthen the store key: {error}");
+        }
+        Ok(())
+    }
+
+    fn seal_value(&self, plaintext: &str) -> Result<String, String> {
+        let sealed = match self.file.backend {
+            SecretBackend::OsKeystore => dpapi::protect(plaintext.as_bytes())?,
+            SecretBackend::Passphrase => {
+                cipher::seal(self.key.as_ref().ok_or(LOCKED)?, plaintext.as_bytes())?
+            }
+        };
+        Ok(BASE64.encode(sealed))
+    }
+
+    fn open_value(&self, name: &str) -> Result<String, String> {
+        let sealed = self
+            .file
+            .entries
+            .get(name)
+            .ok_or_else(|| format!("no stored secret named '{name}'"))?;
+        let sealed = BASE64
+            .decode(sealed)
+            .map_err(|error| format!("stored secret '{name}' is damaged: {error}"))?;
+        let plaintext = match self.file.backend {
+            SecretBackend::OsKeystore => dpapi::unprotect(&sealed)?,
+            SecretBackend::Passphrase => cipher::open(self.key.as_ref().ok_or(LOCKED)?, &sealed)?,
+        };
+        String::from_utf8(plaintext).map_err(|_| format!("stored secret '{name}' is not text"))
+    }
+
+    /// Replaces every handle in `config` with its plaintext. Returns true when
+    /// some field still held a plaintext key and the config needs resaving.
+    fn reveal(&mut self, config: &mut Config) -> bool {
+        assign_pool_ids(config);
+        let mut found_plaintext = false;
+        let locked = self.is_locked();
+        for (_, value) in secret_fields(config) {
+            let Some(name) = value.strip_prefix(HANDLE_PREFIX).map(str::to_string) else {
+                found_plaintext |= !value.trim().is_empty();
+                continue;
+            };
+            *value = match self.open_value(&name) {
+                Ok(plaintext) => {
+                    self.revealed.insert(name, plaintext.clone());
+                    plaintext
+                }
+                Err(error) => {
+                    if !locked {
+                        crate::log_info!("[secrets] could not open '{name}': {error}");
+                    }
+                    String::new()
+                }
+            };
+        }
+        found_plaintext
+    }
+
+    /// Fills empty secret fields from the store, for a config that was loaded
+    /// while the store was still locked.
+    fn fill_empty(&mut self, config: &mut Config) {
+        for (name, value) in secret_fields(config) {
+            if value.is_empty()
+                && let Ok(plaintext) = self.open_value(&name)
+            {
+                self.revealed.insert(name, plaintext.clone());
+                *value = plaintext;
+            }
+        }
+    }
+
+    /// The copy of `config` that goes to disk, with every key replaced by its
+    /// handle. The store is written first, so a saved config never points at
+    /// an entry that does not exist yet.
+    fn seal(&mut self, config: &Config) -> Result<Config, String> {
+        let mut sealed = config.clone();
+        let locked = self.is_locked();
+        let mut entries = BTreeMap::new();
+        let mut revealed = HashMap::new();
+        for (name, value) in secret_fields(&mut sealed) {
+            let existing = self.file.entries.get(&name);
+            let unchanged = self.revealed.get(&name) == Some(&*value);
+            let entry = match existing {
+                // A locked store loaded this key as empty; keep what it holds.
+                Some(existing) if value.is_empty() && locked => existing.clone(),
+                _ if value.is_empty() => continue,
+                Some(existing) if unchanged => existing.clone(),
+                Some(existing) if locked => {
+                    crate::log_info!("[secrets] {LOCKED}; keeping the saved '{name}' key");
+                    existing.clone()
+                }
+                None if locked => {
+                    crate::log_info!("[secrets] {LOCKED}; not saving the new '{name}' key");
+                    value.clear();
+                    continue;
+                }
+                _ => self.seal_value(value)?,
+            };
+            if !locked || unchanged {
+                revealed.insert(name.clone(), std::mem::take(value));
+            }
+            *value = format!("{HANDLE_PREFIX}{name}");
+            entries.insert(name, entry);
+        }
+        if !locked {
+            self.revealed = revealed;
+        }
+        if entries != self.file.entries {
+            let previous = std::mem::replace(&mut self.file.entries, entries);
+            if let Err(error) = self.save() {
+                self.file.entries = previous;
+                return Err(error);
+            }
+        }
+        Ok(sealed)
+    }
+
+    /// Reseals every entry under `backend`. Needs every entry readable, so a
+    /// locked store must be unlocked first.
+    fn switch(&mut self, backend: SecretBackend, passphrase: Option<&str>) -> Result<(), String> {
+        if self.is_locked() {
+            return Err(LOCKED.into());
+        }
+        let plaintexts = self
+            .file
+            .entries
+            .keys()
+            .map(|name| Ok((name.clone(), self.open_value(name)?)))
+            .collect::<Result<Vec<_>, String>>()?;
+        let (params, key) = match backend {
+            SecretBackend::OsKeystore => (None, None),
+            SecretBackend::Passphrase => {
+                let (params, key) =
+                    PassphraseParams::new(passphrase.unwrap_or_default(), self.iterations)?;
+                (Some(params), Some(key))
+            }
+        };
+        let previous = (self.file.clone(), self.key.clone());
+        self.file.backend = backend;
+        self.file.passphrase = params;
+        self.key = key;
+        let resealed = plaintexts
+            .iter()
+            .map(|(name, plaintext)| Ok((name.clone(), self.seal_value(plaintext)?)))
+            .collect::<Result<BTreeMap<_, _>, String>>()
+            .and_then(|entries| {
+                self.file.entries = entries;
+                self.save()
+            });
+        if resealed.is_err() {
+            (self.file, self.key) = previous;
+        }
+        resealed
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+        }
+        crate::atomic_json::write_json_atomic(path, &self.file)
+            .map_err(|error| format!("could not write {}: {error}", path.display()))
+    }
+}
+
+/// Keeps an unreadable store for recovery instead of overwriting it.
+fn set_aside(path: &std::path::Path) {
+    let ts = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".corrupt-{ts}"));
+    if let Err(error) = std::fs::rename(path, &backup) {
+        crate::log_info!("[secrets] could not set the unreadable store aside: {error}");
+    }
+}
+
+/// Every field of `config` that holds a secret, by its store name.
+fn secret_fields(config: &mut Config) -> Vec<(String, &mut String)> {
+    let mut fields = vec![
+        ("groq".to_string(), &mut config.api_key),
+        ("gemini".to_string(), &mut config.gemini_api_key),
+        ("openrouter".to_string(), &mut config.openrouter_api_key),
+        ("nvidia".to_string(), &mut config.nvidia_api_key),
+    ];
+    fields.extend(
+        config
+            .api_key_pool
+            .iter_mut()
+            .enumerate()
+            .map(|(index, entry)| {
+                // Only a row that has not been through `assign_pool_ids` lacks an id.
+                let id = if entry.id.is_empty() {
+                    index.to_string()
+                } else {
+                    entry.id.clone()
+                };
+                (format!("{POOL_PREFIX}{id}"), &mut entry.key)
+            }),
+    );
+    fields
+}
+
+/// Gives every pool row an id. A row saved before ids existed keeps the name
+/// its handle already points at, so its sealed key still opens.
+fn assign_pool_ids(config: &mut Config) {
+    for entry in config
+        .api_key_pool
+        .iter_mut()
+        .filter(|entry| entry.id.is_empty())
+    {
+        entry.id = entry
+            .key
+            .strip_prefix(HANDLE_PREFIX)
+            .and_then(|name| name.strip_prefix(POOL_PREFIX))
+            .map(str::to_string)
+            .unwrap_or_else(crate::config::types::generate_pool_key_id);
+    }
+}
+
+static STORE: LazyLock<Mutex<Store>> = LazyLock::new(|| {
+    Mutex::new(if cfg!(test) {
+        Store::in_memory(StoreFile::default())
+    } else {
+        Store::load(crate::paths::app_config_dir().join(FILE_NAME))
+    })
+});
+
+fn store() -> MutexGuard<'static, Store> {
+    STORE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+pub(super) fn reveal(config: &mut Config) -> bool {
+    store().reveal(config)
+}
+
+pub(super) fn seal(config: &Config) -> Result<Config, String> {
+    store().seal(config)
+}
+
+pub fn backend() -> SecretBackend {
+    store().file.backend
+}
+
+/// True while the passphrase backend is waiting for its passphrase.
+pub fn is_locked() -> bool {
+    store().is_locked()
+}
+
+/// Unlocks the passphrase backend, fills the keys `config` loaded without and
+/// rebuilds the key pools, which were built from the empty keys.
+pub fn unlock(passphrase: &str, config: &mut Config) -> Result<(), String> {
+    {
+        let mut store = store();
+        store.unlock(passphrase)?;
+        store.fill_empty(config);
+    }
+    crate::api::key_pool::configure(config);
+    Ok(())
+}
+
+/// Moves every key to the passphrase backend under a new passphrase.
+pub fn use_passphrase(passphrase: &str) -> Result<(), String> {
+    store().switch(SecretBackend::Passphrase, Some(passphrase))
+}
+
+pub fn use_os_keystore() -> Result<(), String> {
+    store().switch(SecretBackend::OsKeystore, None)
+}
+
+/// Keys shorter than this are blanked only where they make up a whole string,
+/// so a stray short value cannot shred unrelated text.
+const MIN_EMBEDDED_KEY_LEN: usize = 8;
+
+/// `export` as JSON with every key `config` holds cut out of it. Anything that
+/// leaves the machine (a project archive, a timeline, a diagnostic or eval
+/// report) is built from this copy; a `Config` comes out with its keys blank.
+pub fn without_secrets<T: Serialize>(export: &T, config: &Config) -> Result<Value, String> {
+    let mut value = serde_json::to_value(export)
+        .map_err(|error| format!("could not prepare the export: {error}"))?;
+    scrub(&mut value, &secret_values(config));
+    Ok(value)
+}
+
+/// [`without_secrets`] for text that is written out as is.
+pub fn text_without_secrets(text: &str, config: &Config) -> String {
+    let mut value = Value::String(text.to_string());
+    scrub(&mut value, &secret_values(config));
+    value.as_str().unwrap_or_default().to_string()
+}
+
+fn secret_values(config: &Config) -> Vec<String> {
+    let mut config = config.clone();
+    secret_fields(&mut config)
+        .into_iter()
+        .map(|(_, value)| std::mem::take(value))
+        .filter(|key| !key.trim().is_empty())
+        .collect()
+}
+
+fn scrub(value: &mut Value, keys: &[String]) {
+    match value {
+        Value::String(text) => {
+            for key in keys {
+                if text == key {
+                    text.clear();
+                } else if key.len() >= MIN_EMBEDDED_KEY_LEN && text.contains(key.as_str()) {
+                    *text = text.replace(key.as_str(), "");
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| scrub(item, keys)),
+        Value::Object(fields) => fields.values_mut().for_each(|field| scrub(field, keys)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,176 @@
+//! Passphrase sealing with audited primitives.
+//!
+//! PBKDF2-HMAC-SHA256 (the `pbkdf2` crate) stretches the passphrase into a
+//! 256-bit key and ChaCha20-Poly1305 seals each value. Every sealed blob opens
+//! with a header naming its format version and the parameters that derived
+//! its key; the header is authenticated as associated data, so a release can
+//! raise the iteration count and still recognise what older blobs need.
+//! Layout: `version (1) || kdf (1) || iterations (4, BE) || salt (16) ||
+//! nonce (12) || ciphertext || tag (16)`.
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use sha2::Sha256;
+
+const FORMAT_VERSION: u8 = 1;
+const KDF_PBKDF2_SHA256: u8 = 1;
+const SALT_LEN: usize = 16;
+const HEADER_LEN: usize = 2 + 4 + SALT_LEN;
+const NONCE_LEN: usize = 12;
+const TAG_LEN: usize = 16;
+
+/// How a key was derived from the passphrase.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct KdfParams {
+    pub(super) iterations: u32,
+    salt: [u8; SALT_LEN],
+}
+
+impl KdfParams {
+    /// Parameters with a fresh random salt.
+    pub(super) fn generate(iterations: u32) -> Result<Self, String> {
+        let mut salt = [0u8; SALT_LEN];
+        getrandom::fill(&mut salt).map_err(|error| format!("no randomness for a salt: {error}"))?;
+        Ok(Self { iterations, salt })
+    }
+
+    /// The parameters named in the header of `sealed`.
+    pub(super) fn of(sealed: &[u8]) -> Result<Self, String> {
+        let Some(&[version, kdf, i0, i1, i2, i3, ref salt @ ..]) = sealed.get(..HEADER_LEN) else {
+            return Err("sealed secret is truncated".into());
+        };
+        if version != FORMAT_VERSION {
+            return Err(format!("unsupported sealed secret version {version}"));
+        }
+        if kdf != KDF_PBKDF2_SHA256 {
+            return Err(format!("unsupported key derivation {kdf}"));
+        }
+        let mut params = Self {
+            iterations: u32::from_be_bytes([i0, i1, i2, i3]),
+            salt: [0; SALT_LEN],
+        };
+        params.salt.copy_from_slice(salt);
+        Ok(params)
+    }
+
+    fn header(&self) -> Vec<u8> {
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        header.extend_from_slice(&[FORMAT_VERSION, KDF_PBKDF2_SHA256]);
+        header.extend_from_slice(&self.iterations.to_be_bytes());
+        header.extend_from_slice(&self.salt);
+        header
+    }
+}
+
+/// A derived key together with the parameters that made it.
+#[derive(Clone)]
+pub(super) struct Key {
+    params: KdfParams,
+    bytes: chacha20poly1305::Key,
+}
+
+impl Key {
+    pub(super) fn derive(passphrase: &[u8], params: KdfParams) -> Self {
+        let mut bytes = chacha20poly1305::Key::default();
+        pbkdf2::pbkdf2_hmac::<Sha256>(passphrase, &params.salt, params.iterations, &mut bytes);
+        Self { params, bytes }
+    }
+
+    pub(super) fn params(&self) -> KdfParams {
+        self.params
+    }
+}
+
+pub(super) fn seal(key: &Key, plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    let mut nonce = [0u8; NONCE_LEN];
+    getrandom::fill(&mut nonce).map_err(|error| format!("no randomness for a nonce: {error}"))?;
+    seal_with_nonce(key, &nonce, plaintext)
+}
+
+fn seal_with_nonce(
+    key: &Key,
+    nonce: &[u8; NONCE_LEN],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, String> {
+    let mut sealed = key.params.header();
+    let ciphertext = ChaCha20Poly1305::new(&key.bytes)
+        .encrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: plaintext,
+                aad: &sealed,
+            },
+        )
+        .map_err(|_| "could not seal the secret".to_string())?;
+    sealed.extend_from_slice(nonce);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Fails without revealing anything when the key is wrong or a byte changed.
+pub(super) fn open(key: &Key, sealed: &[u8]) -> Result<Vec<u8>, String> {
+    if sealed.len() < HEADER_LEN + NONCE_LEN + TAG_LEN {
+        return Err("sealed secret is truncated".into());
+    }
+    if KdfParams::of(sealed)? != key.params {
+        return Err("sealed secret was made with a different key".into());
+    }
+    let (header, body) = sealed.split_at(HEADER_LEN);
+    let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+    ChaCha20Poly1305::new(&key.bytes)
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: header,
+            },
+        )
+        .map_err(|_| "sealed secret failed authentication".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(passphrase: &str) -> Key {
+        let params = KdfParams {
+            iterations: 2,
+            salt: [3; SALT_LEN],
+        };
+        Key::derive(passphrase.as_bytes(), params)
+    }
+
+    #[test]
+    fn sealing_round_trips_and_names_its_parameters() {
+        let key = key("correct horse");
+        let plaintext = b"gsk_live_key_that_spans_more_than_one_block_of_keystream";
+        let sealed = seal(&key, plaintext).unwrap();
+        assert!(!sealed.windows(8).any(|window| window == &plaintext[..8]));
+        assert_eq!(open(&key, &sealed).unwrap(), plaintext);
+        assert_ne!(seal(&key, plaintext).unwrap(), sealed, "nonces must differ");
+        assert_eq!(KdfParams::of(&sealed).unwrap(), key.params());
+
+        let mut future = sealed.clone();
+        future[0] = FORMAT_VERSION + 1;
+        assert_eq!(
+            open(&key, &future).unwrap_err(),
+            format!("unsupported sealed secret version {}", FORMAT_VERSION + 1)
+        );
+    }
+
+    #[test]
+    fn a_wrong_key_or_any_flipped_byte_is_rejected() {
+        let key = key("correct horse");
+        let sealed = seal_with_nonce(&key, &[7; NONCE_LEN], b"secret").unwrap();
+        assert!(open(&self::key("battery staple"), &sealed).is_err());
+        for index in 0..sealed.len() {
+            let mut tampered = sealed.clone();
+            tampered[index] ^= 1;
+            assert!(
+                open(&key, &tampered).is_err(),
+                "byte {index} was not covered"
+            );
+        }
+        assert!(open(&key, &sealed[..20]).is_err());
+    }
+}
@@ -0,0 +1,63 @@
+//! The OS keystore backend: Windows DPAPI, bound to the signed-in user.
+//!
+//! DPAPI keeps the key material itself; a blob protected here opens only for
+//! the same Windows account, so copying the config folder to another machine
+//! or account carries no usable keys.
+
+use windows::Win32::Foundation::{HLOCAL, LocalFree};
+use windows::Win32::Security::Cryptography::{
+    CRYPT_INTEGER_BLOB, CRYPTPROTECT_UI_FORBIDDEN, CryptProtectData, CryptUnprotectData,
+};
+use windows::core::w;
+
+pub(super) fn protect(plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    transform(plaintext, |input, output| unsafe {
+        CryptProtectData(
+            input,
+            w!("screen-goated-toolbox API key"),
+            None,
+            None,
+            None,
+            CRYPTPROTECT_UI_FORBIDDEN,
+            output,
+        )
+    })
+    .map_err(|error| format!("Windows could not protect the secret: {error}"))
+}
+
+pub(super) fn unprotect(sealed: &[u8]) -> Result<Vec<u8>, String> {
+    transform(sealed, |input, output| unsafe {
+        CryptUnprotectData(
+            input,
+            None,
+            None,
+            None,
+            None,
+            CRYPTPROTECT_UI_FORBIDDEN,
+            output,
+        )
+    })
+    .map_err(|error| format!("Windows could not unprotect the secret: {error}"))
+}
+
+/// Runs one DPAPI call and copies its `LocalAlloc`ed output before freeing it.
+fn transform(
+    data: &[u8],
+    call: impl FnOnce(*const CRYPT_INTEGER_BLOB, *mut CRYPT_INTEGER_BLOB) -> windows::core::Result<()>,
+) -> windows::core::Result<Vec<u8>> {
+    let input = CRYPT_INTEGER_BLOB {
+        cbData: data.len() as u32,
+        pbData: data.as_ptr() as *mut u8,
+    };
+    let mut output = CRYPT_INTEGER_BLOB::default();
+    call(&input, &mut output)?;
+    if output.pbData.is_null() {
+        return Ok(Vec::new());
+    }
+    let bytes =
+        unsafe { std::slice::from_raw_parts(output.pbData, output.cbData as usize) }.to_vec();
+    unsafe {
+        LocalFree(Some(HLOCAL(output.pbData.cast())));
+    }
+    Ok(bytes)
+}
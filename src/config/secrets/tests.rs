@@ -0,0 +1,228 @@
+use super::*;
+use crate::config::types::PooledApiKey;
+
+const GROQ_KEY: &str = "gsk_plaintext_groq_key";
+const POOL_KEY: &str = "gsk_plaintext_pool_key";
+
+/// A passphrase store with a cheap key derivation, so tests stay fast.
+fn passphrase_store(passphrase: &str) -> Store {
+    let mut store = Store::in_memory(StoreFile::default());
+    store.iterations = 2;
+    store
+        .switch(SecretBackend::Passphrase, Some(passphrase))
+        .unwrap();
+    store
+}
+
+/// The same store as a fresh process would load it.
+fn reloaded(store: &Store) -> Store {
+    let mut reloaded = Store::in_memory(store.file.clone());
+    reloaded.iterations = store.iterations;
+    reloaded
+}
+
+fn pooled(id: &str, label: &str, key: &str) -> PooledApiKey {
+    PooledApiKey {
+        id: id.to_string(),
+        provider: "groq".to_string(),
+        label: label.to_string(),
+        key: key.to_string(),
+    }
+}
+
+fn config_with_keys() -> Config {
+    Config {
+        api_key: GROQ_KEY.to_string(),
+        gemini_api_key: "gemini-key".to_string(),
+        api_key_pool: vec![pooled("spare-id", "spare", POOL_KEY)],
+        ..Config::default()
+    }
+}
+
+#[test]
+fn a_sealed_config_holds_only_handles_and_reveals_back() {
+    let mut store = passphrase_store("hunter2");
+    let config = config_with_keys();
+    let sealed = store.seal(&config).unwrap();
+
+    assert_eq!(sealed.api_key, "secret:groq");
+    assert_eq!(sealed.gemini_api_key, "secret:gemini");
+    assert_eq!(sealed.openrouter_api_key, "");
+    assert_eq!(sealed.api_key_pool[0].key, "secret:pool/spare-id");
+    let on_disk =
+        serde_json::to_string(&sealed).unwrap() + &serde_json::to_string(&store.file).unwrap();
+    assert!(!on_disk.contains(GROQ_KEY) && !on_disk.contains(POOL_KEY));
+
+    let mut store = reloaded(&store);
+    store.unlock("hunter2").unwrap();
+    let mut loaded = sealed;
+    assert!(!store.reveal(&mut loaded));
+    assert_eq!(loaded.api_key, GROQ_KEY);
+    assert_eq!(loaded.api_key_pool[0].key, POOL_KEY);
+}
+
+#[test]
+fn a_config_with_plaintext_keys_asks_to_be_migrated() {
+    let mut store = passphrase_store("hunter2");
+    let mut plaintext = config_with_keys();
+    assert!(store.reveal(&mut plaintext));
+    assert_eq!(
+        plaintext.api_key, GROQ_KEY,
+        "plaintext is kept for the resave"
+    );
+
+    let mut sealed = store.seal(&plaintext).unwrap();
+    assert!(!store.reveal(&mut sealed));
+    assert!(!store.reveal(&mut Config::default()));
+}
+
+#[test]
+fn unchanged_keys_are_not_resealed_and_removed_keys_are_dropped() {
+    let mut store = passphrase_store("hunter2");
+    let mut config = config_with_keys();
+    store.seal(&config).unwrap();
+    let first = store.file.entries.clone();
+
+    store.seal(&config).unwrap();
+    assert_eq!(store.file.entries, first, "same bytes, so no rewrite");
+
+    config.api_key_pool.clear();
+    config.gemini_api_key = "rotated".to_string();
+    store.seal(&config).unwrap();
+    assert!(!store.file.entries.contains_key("pool/spare-id"));
+    assert_eq!(store.file.entries["groq"], first["groq"]);
+    assert_ne!(store.file.entries["gemini"], first["gemini"]);
+}
+
+#[test]
+fn a_locked_store_loads_empty_keys_and_keeps_its_entries() {
+    let mut unlocked = passphrase_store("hunter2");
+    let sealed = unlocked.seal(&config_with_keys()).unwrap();
+    let mut store = reloaded(&unlocked);
+    assert!(store.is_locked());
+
+    let mut loaded = sealed.clone();
+    assert!(!store.reveal(&mut loaded));
+    assert_eq!(loaded.api_key, "");
+    assert_eq!(loaded.api_key_pool[0].key, "");
+
+    loaded.nvidia_api_key = "typed-while-locked".to_string();
+    let resaved = store.seal(&loaded).unwrap();
+    assert_eq!(resaved.api_key, "secret:groq");
+    assert_eq!(resaved.nvidia_api_key, "", "never written as plaintext");
+    assert_eq!(store.file.entries, unlocked.file.entries);
+
+    assert_eq!(store.unlock("wrong").unwrap_err(), "wrong passphrase");
+    store.unlock("hunter2").unwrap();
+    store.fill_empty(&mut loaded);
+    assert_eq!(loaded.api_key, GROQ_KEY);
+    assert_eq!(loaded.api_key_pool[0].key, POOL_KEY);
+}
+
+#[test]
+fn removing_a_pool_row_while_locked_keeps_every_other_key_on_its_row() {
+    let mut unlocked = passphrase_store("hunter2");
+    let mut config = config_with_keys();
+    config
+        .api_key_pool
+        .push(pooled("team-id", "team", "gsk_plaintext_team_key"));
+    let sealed = unlocked.seal(&config).unwrap();
+
+    let mut store = reloaded(&unlocked);
+    let mut loaded = sealed;
+    store.reveal(&mut loaded);
+    loaded.api_key_pool.remove(0);
+    let resaved = store.seal(&loaded).unwrap();
+    assert!(!store.file.entries.contains_key("pool/spare-id"));
+
+    store.unlock("hunter2").unwrap();
+    let mut reopened = resaved;
+    store.reveal(&mut reopened);
+    assert_eq!(reopened.api_key_pool[0].label, "team");
+    assert_eq!(reopened.api_key_pool[0].key, "gsk_plaintext_team_key");
+}
+
+#[test]
+fn pool_rows_saved_before_ids_keep_the_name_their_handle_points_at() {
+    let mut store = passphrase_store("hunter2");
+    let mut legacy = config_with_keys();
+    legacy.api_key_pool[0].id.clear();
+    let mut sealed = store.seal(&legacy).unwrap();
+    assert_eq!(sealed.api_key_pool[0].key, "secret:pool/0");
+    sealed.api_key_pool[0].id.clear();
+
+    assert!(!store.reveal(&mut sealed));
+    assert_eq!(sealed.api_key_pool[0].id, "0");
+    assert_eq!(sealed.api_key_pool[0].key, POOL_KEY);
+
+    let mut plaintext = legacy;
+    assert!(store.reveal(&mut plaintext));
+    assert_eq!(plaintext.api_key_pool[0].id.len(), 16);
+}
+
+#[test]
+fn changing_the_passphrase_reseals_every_entry() {
+    let mut store = passphrase_store("hunter2");
+    let sealed = store.seal(&config_with_keys()).unwrap();
+    store
+        .switch(SecretBackend::Passphrase, Some("correct horse"))
+        .unwrap();
+
+    let mut old = reloaded(&store);
+    assert!(old.unlock("hunter2").is_err());
+    let mut new = reloaded(&store);
+    new.unlock("correct horse").unwrap();
+    let mut loaded = sealed;
+    new.reveal(&mut loaded);
+    assert_eq!(loaded.api_key, GROQ_KEY);
+
+    assert_eq!(
+        reloaded(&store).switch(SecretBackend::OsKeystore, None),
+        Err(LOCKED.to_string())
+    );
+    assert!(Store::in_memory(StoreFile::default()).unlock("x").is_err());
+}
+
+#[test]
+fn unlocking_a_weaker_store_reseals_it_with_the_current_iterations() {
+    let mut weak = passphrase_store("hunter2");
+    let sealed = weak.seal(&config_with_keys()).unwrap();
+    let mut store = reloaded(&weak);
+    store.iterations = 3;
+
+    store.unlock("hunter2").unwrap();
+    let check = BASE64
+        .decode(&store.file.passphrase.as_ref().unwrap().check)
+        .unwrap();
+    assert_eq!(cipher::KdfParams::of(&check).unwrap().iterations, 3);
+    assert_ne!(store.file.entries, weak.file.entries);
+
+    let mut fresh = reloaded(&store);
+    fresh.unlock("hunter2").unwrap();
+    let mut loaded = sealed;
+    fresh.reveal(&mut loaded);
+    assert_eq!(loaded.api_key, GROQ_KEY);
+}
+
+#[test]
+fn exports_never_carry_a_secret() {
+    let config = config_with_keys();
+    let scrubbed = without_secrets(&config, &config).unwrap();
+    let exported = scrubbed.to_string();
+    assert!(!exported.contains(GROQ_KEY) && !exported.contains(POOL_KEY));
+    assert!(!exported.contains("gemini-key"));
+    assert_eq!(scrubbed["api_key_pool"][0]["label"], "spare");
+
+    let report = serde_json::json!({
+        "error": format!("401 from https://api.example/v1?key={GROQ_KEY}"),
+        "runs": [{ "output": "gemini-key", "note": "a gemini-key-free note" }],
+    });
+    let scrubbed = without_secrets(&report, &config).unwrap();
+    assert_eq!(scrubbed["error"], "401 from https://api.example/v1?key=");
+    assert_eq!(scrubbed["runs"][0]["output"], "");
+    assert_eq!(scrubbed["runs"][0]["note"], "a -free note");
+    assert_eq!(
+        text_without_secrets(&format!("<pre>{POOL_KEY}</pre>"), &config),
+        "<pre></pre>"
+    );
+}
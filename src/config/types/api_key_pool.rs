@@ -0,0 +1,51 @@
+//! Extra API keys per provider, rotated alongside the primary key.
+
+use serde::{Deserialize, Serialize};
+
+/// One additional key for a provider. `provider` uses the retry-chain names:
+/// `groq`, `google`, `openrouter`, or `nvidia`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PooledApiKey {
+    /// Names the entry's sealed key, which must not follow the row's position:
+    /// removing a row would shift every later key onto its neighbour.
+    #[serde(default)]
+    pub id: String,
+    pub provider: String,
+    pub label: String,
+    pub key: String,
+}
+
+impl PooledApiKey {
+    /// An empty row for `provider` with a fresh id.
+    pub fn new(provider: &str) -> Self {
+        Self {
+            id: generate_pool_key_id(),
+            provider: provider.to_string(),
+            label: String::new(),
+            key: String::new(),
+        }
+    }
+}
+
+/// Random, so rows added in the same instant still get distinct ids.
+pub fn generate_pool_key_id() -> String {
+    let mut bytes = [0u8; 8];
+    if getrandom::fill(&mut bytes).is_err() {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        bytes = (nanos as u64).to_le_bytes();
+    }
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// How the next key is picked when a provider has more than one.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ApiKeyRotation {
+    /// Take keys in turn so the free-tier quotas drain evenly.
+    #[default]
+    RoundRobin,
+    /// Prefer the key whose last rate limit is the oldest, or that never hit one.
+    LeastRecentlyLimited,
+}
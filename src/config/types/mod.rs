@@ -6,17 +6,25 @@
 //! - `hotkey`: Hotkey binding type
 //! - `model_priority`: Smart retry priority chains
 //! - `tts`: TTS-related types (TtsMethod, EdgeTtsSettings, etc.)
+//! - `api_key_pool`: Extra provider API keys and their rotation
+//! - `updates`: Update channel and deferred release
+//! - `routing`: Per-preset routing objective for the retry chain
+//! - `response_cache`: Opt-in cache of completed block results
 
+mod api_key_pool;
 mod custom_models;
 mod enums;
 mod hotkey;
 mod model_priority;
 mod preset_model_update;
 mod profile;
+mod response_cache;
 mod restore_defaults;
+mod routing;
 mod screen_translate;
 mod translation_gummy;
 mod tts;
+mod updates;
 
 // Re-export all types for easy access
 pub use screen_translate::ScreenTranslateSettings;
@@ -24,6 +32,8 @@ pub use translation_gummy::TranslationGummySettings;
 #[cfg(not(feature = "recorder-worker"))]
 pub use translation_gummy::{MAX_TRANSCRIPT_ITEMS, MIN_TRANSCRIPT_ITEMS};
 
+pub use api_key_pool::{ApiKeyRotation, PooledApiKey, generate_pool_key_id};
+
 pub use custom_models::{CustomModelDefinition, CustomModelType};
 
 pub use enums::{DEFAULT_HISTORY_LIMIT, DEFAULT_PROJECTS_LIMIT, ThemeMode, get_system_ui_language};
@@ -37,8 +47,14 @@ pub use preset_model_update::{PendingPresetModelUpdate, RecommendedProviderDefau
 
 pub use profile::PresetProfile;
 
+pub use response_cache::ResponseCacheSettings;
+
 pub use restore_defaults::RestoreDefaultsSelection;
 
+pub use routing::{DEFAULT_QUALITY_FLOOR, MAX_QUALITY_TIER, RoutingObjective, RoutingWeights};
+
+pub use updates::{UpdateChannel, UpdatePreferences};
+
 #[cfg(feature = "recorder-worker")]
 pub use tts::StepAudioVoiceConfig;
 #[cfg(not(feature = "recorder-worker"))]
@@ -0,0 +1,30 @@
+//! Opt-in cache of completed preset block results.
+
+use serde::{Deserialize, Serialize};
+
+/// Reuses a block's earlier answer when the same prompt, model and input run
+/// again. Off by default: a cached answer never reflects a provider change.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct ResponseCacheSettings {
+    pub enabled: bool,
+    /// Entries older than this are treated as missing.
+    pub ttl_hours: u32,
+    /// Oldest entries are evicted once the cache grows past this size.
+    pub max_size_mb: u32,
+}
+
+impl ResponseCacheSettings {
+    pub const MAX_TTL_HOURS: u32 = 720;
+    pub const MAX_SIZE_MB: u32 = 1024;
+}
+
+impl Default for ResponseCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ttl_hours: 24,
+            max_size_mb: 64,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Per-preset routing objective for the retry chain.
+
+use serde::{Deserialize, Serialize};
+
+/// How the retry chain picks the next model after one fails.
+///
+/// `Priority` walks the effective chain as configured. The other objectives
+/// reorder that same chain, so cost or speed can never route a request to a
+/// model the user did not accept for it.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RoutingObjective {
+    #[default]
+    Priority,
+    Cheapest,
+    Fastest,
+    BestQuality,
+    Weighted(RoutingWeights),
+}
+
+/// Catalog intelligence tiers run from 1 to this.
+pub const MAX_QUALITY_TIER: u8 = 6;
+
+/// Lowest tier [`RoutingObjective::Cheapest`] may prefer for its price; models
+/// below it are only tried after every model that meets it.
+pub const DEFAULT_QUALITY_FLOOR: u8 = 3;
+
+/// Relative weights of a weighted mix, each from 0 to [`Self::MAX`].
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct RoutingWeights {
+    pub cost: u8,
+    pub speed: u8,
+    pub quality: u8,
+}
+
+impl RoutingWeights {
+    pub const MAX: u8 = 10;
+}
+
+impl Default for RoutingWeights {
+    fn default() -> Self {
+        Self {
+            cost: 5,
+            speed: 5,
+            quality: 5,
+        }
+    }
+}
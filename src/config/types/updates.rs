@@ -0,0 +1,30 @@
+//! Updater preferences: release channel and deferred release.
+
+use serde::{Deserialize, Serialize};
+
+/// Release feed the updater follows. Beta also sees stable releases, so a
+/// pilot install never falls behind the stable channel.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct UpdatePreferences {
+    pub channel: UpdateChannel,
+    /// Release the user chose to skip; newer releases are still offered.
+    pub deferred_version: Option<String>,
+}
@@ -0,0 +1,231 @@
+//! Golden-set evaluation of presets and models.
+//!
+//! Runs each chosen preset headless over a folder of inputs with known-good
+//! outputs, then again with each chosen model swapped into the blocks of its
+//! kind, and writes `report.json` and `report.html` comparing them. A model
+//! that does noticeably worse than the preset as saved on any case is flagged,
+//! so switching models cannot quietly degrade a translation or OCR setup.
+
+mod golden_set;
+mod judge;
+mod report;
+#[cfg(test)]
+mod tests;
+
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::config::{Config, Preset};
+use crate::model_config::ModelType;
+use crate::overlay::process::chain::{HeadlessRun, run_preset_headless};
+
+use golden_set::GoldenCase;
+use judge::Judge;
+use report::{CaseResult, TargetInfo};
+
+pub struct EvalOptions {
+    pub folder: PathBuf,
+    pub preset_ids: Vec<String>,
+    pub model_ids: Vec<String>,
+    pub judge_model: Option<String>,
+    /// Defaults to `<folder>/eval-reports/<timestamp>`.
+    pub output: Option<PathBuf>,
+}
+
+struct Target {
+    info: TargetInfo,
+    preset: Preset,
+}
+
+/// Fails when any model override regresses, so scripts can gate on it.
+pub fn run_cli(options: EvalOptions) -> Result<(), String> {
+    let config = crate::APP
+        .lock()
+        .map_err(|_| "app state is unavailable".to_string())?
+        .config
+        .clone();
+    let set = golden_set::load(&options.folder)?;
+    let targets = build_targets(&config, &options.preset_ids, &options.model_ids)?;
+    let judge = options
+        .judge_model
+        .as_deref()
+        .map(|model_id| Judge::new(model_id, &config))
+        .transpose()?;
+
+    let mut results = Vec::new();
+    let mut skipped_runs = Vec::new();
+    for target in &targets {
+        for case in &set.cases {
+            if let Some(reason) = mismatch(&target.preset, case) {
+                println!(
+                    "[golden-eval] {} · {} skipped: {reason}",
+                    target.info.label, case.id
+                );
+                skipped_runs.push(format!("{} · {}: {reason}", target.info.label, case.id));
+                continue;
+            }
+            println!("[golden-eval] {} · {}", target.info.label, case.id);
+            results.push(run_case(target, case, &config, judge.as_ref()));
+        }
+    }
+
+    let infos: Vec<TargetInfo> = targets.into_iter().map(|target| target.info).collect();
+    let judge_model = judge.as_ref().map(|judge| judge.model_id().to_string());
+    let report = report::build(&set, judge_model, &infos, results, skipped_runs);
+    let directory = options.output.unwrap_or_else(|| {
+        set.root
+            .join("eval-reports")
+            .join(chrono::Local::now().format("%Y%m%d-%H%M%S").to_string())
+    });
+    report::write(&report, &config, &directory)?;
+
+    for summary in &report.targets {
+        println!(
+            "[golden-eval] {}: {} runs, {} failed, similarity {}",
+            summary.target.label,
+            summary.runs,
+            summary.failures,
+            summary
+                .mean_similarity
+                .map(|value| format!("{:.1}%", value * 100.0))
+                .unwrap_or_else(|| "–".to_string()),
+        );
+    }
+    println!("[golden-eval] report: {}", directory.display());
+    match report.regressions.len() {
+        0 => Ok(()),
+        count => Err(format!("{count} case(s) regressed against their baseline")),
+    }
+}
+
+fn build_targets(
+    config: &Config,
+    preset_ids: &[String],
+    model_ids: &[String],
+) -> Result<Vec<Target>, String> {
+    if preset_ids.is_empty() {
+        return Err("pass at least one preset id with --golden-eval-preset".to_string());
+    }
+    let mut targets = Vec::new();
+    for preset_id in preset_ids {
+        let preset = config
+            .presets
+            .iter()
+            .find(|preset| preset.id == *preset_id)
+            .ok_or_else(|| format!("unknown preset '{preset_id}'"))?;
+        targets.push(Target {
+            info: TargetInfo {
+                label: preset_id.clone(),
+                preset_id: preset_id.clone(),
+                model_override: None,
+            },
+            preset: preset.clone(),
+        });
+        for model_id in model_ids {
+            let model =
+                crate::model_config::get_model_by_id_with_custom(model_id, &config.custom_models)
+                    .ok_or_else(|| format!("unknown model '{model_id}'"))?;
+            let Some(preset) = with_model(preset, model_id, block_type(model.model_type)) else {
+                println!("[golden-eval] {preset_id} has no block {model_id} can run; skipped");
+                continue;
+            };
+            targets.push(Target {
+                info: TargetInfo {
+                    label: format!("{preset_id} @ {model_id}"),
+                    preset_id: preset_id.clone(),
+                    model_override: Some(model_id.clone()),
+                },
+                preset,
+            });
+        }
+    }
+    Ok(targets)
+}
+
+fn block_type(model_type: ModelType) -> &'static str {
+    match model_type {
+        ModelType::Vision => "image",
+        ModelType::Text => "text",
+        ModelType::Audio => "audio",
+    }
+}
+
+/// The preset with every block of `block_type` moved to `model_id`, or `None`
+/// when it has no such block.
+fn with_model(preset: &Preset, model_id: &str, block_type: &str) -> Option<Preset> {
+    let mut preset = preset.clone();
+    let mut matched = false;
+    for block in preset
+        .blocks
+        .iter_mut()
+        .filter(|block| block.block_type == block_type)
+    {
+        block.model = model_id.to_string();
+        matched = true;
+    }
+    matched.then_some(preset)
+}
+
+/// Why `preset` cannot run `case`, when its input is of another kind: an
+/// image fed to a text preset would only measure the failure.
+fn mismatch(preset: &Preset, case: &GoldenCase) -> Option<String> {
+    let wanted = case.kind.preset_type();
+    (preset.preset_type != wanted).then(|| {
+        format!(
+            "the preset takes {} input, not {wanted}",
+            preset.preset_type
+        )
+    })
+}
+
+fn run_case(
+    target: &Target,
+    case: &GoldenCase,
+    config: &Config,
+    judge: Option<&Judge>,
+) -> CaseResult {
+    let started = Instant::now();
+    let run = case
+        .load_input()
+        .and_then(|input| run_preset_headless(&target.preset, config, input));
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+    case_result(&target.info, case, run, latency_ms, judge)
+}
+
+/// A run a fallback model answered says nothing about the model under test,
+/// so it counts as a failure whatever its output.
+fn case_result(
+    target: &TargetInfo,
+    case: &GoldenCase,
+    run: Result<HeadlessRun, String>,
+    latency_ms: u64,
+    judge: Option<&Judge>,
+) -> CaseResult {
+    let (output, error, scores, answered_by) = match run {
+        Ok(run) if !run.fallbacks.is_empty() => (
+            Some(run.output),
+            Some(format!(
+                "answered by a fallback model: {}",
+                run.fallbacks.join("; ")
+            )),
+            None,
+            run.answered_by,
+        ),
+        Ok(run) => {
+            let graded = judge.map(|judge| judge.grade(&case.expected, &run.output));
+            let scores = report::score(case, &run.output, graded);
+            (Some(run.output), None, Some(scores), run.answered_by)
+        }
+        Err(error) => (None, Some(error), None, Vec::new()),
+    };
+    CaseResult {
+        target: target.label.clone(),
+        case_id: case.id.clone(),
+        kind: case.kind,
+        latency_ms,
+        answered_by,
+        output,
+        error,
+        scores,
+    }
+}
@@ -0,0 +1,149 @@
+//! Reading a golden-set folder.
+//!
+//! Every input file sits beside `<stem>.expected.txt`, the output it should
+//! produce; an optional `<stem>.terms.txt` lists, one per line, terms the
+//! output must contain. Inputs are recognised by extension. Anything else in
+//! the folder (including the reports written into it) is ignored, and an input
+//! without an expected output is listed as skipped rather than failing the run.
+
+use std::path::{Path, PathBuf};
+
+use crate::overlay::process::chain::HeadlessInput;
+
+const EXPECTED_SUFFIX: &str = ".expected.txt";
+const TERMS_SUFFIX: &str = ".terms.txt";
+const TEXT_EXTENSIONS: &[&str] = &["txt", "md"];
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "bmp", "webp", "gif", "tif", "tiff"];
+const AUDIO_EXTENSIONS: &[&str] = &["wav"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum InputKind {
+    Text,
+    Image,
+    Audio,
+}
+
+#[derive(Debug)]
+pub(crate) struct GoldenCase {
+    /// The input's file name, unique within the folder.
+    pub(crate) id: String,
+    pub(crate) kind: InputKind,
+    pub(crate) input: PathBuf,
+    pub(crate) expected: String,
+    pub(crate) terms: Vec<String>,
+}
+
+impl InputKind {
+    /// The `preset_type` of the presets that take this kind of input.
+    pub(crate) fn preset_type(self) -> &'static str {
+        match self {
+            InputKind::Text => "text",
+            InputKind::Image => "image",
+            InputKind::Audio => "audio",
+        }
+    }
+}
+
+impl GoldenCase {
+    /// The input as a preset would receive it; images are re-encoded as PNG.
+    pub(crate) fn load_input(&self) -> Result<HeadlessInput, String> {
+        let read = |path: &Path| {
+            std::fs::read(path).map_err(|error| format!("read {}: {error}", path.display()))
+        };
+        Ok(match self.kind {
+            InputKind::Text => HeadlessInput::Text(
+                String::from_utf8(read(&self.input)?)
+                    .map_err(|_| format!("{} is not UTF-8 text", self.input.display()))?,
+            ),
+            InputKind::Image => {
+                let image = image::open(&self.input)
+                    .map_err(|error| format!("decode {}: {error}", self.input.display()))?;
+                let mut png = std::io::Cursor::new(Vec::new());
+                image
+                    .write_to(&mut png, image::ImageFormat::Png)
+                    .map_err(|error| format!("encode {}: {error}", self.input.display()))?;
+                HeadlessInput::Image(png.into_inner())
+            }
+            InputKind::Audio => HeadlessInput::Audio(read(&self.input)?),
+        })
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct GoldenSet {
+    pub(crate) root: PathBuf,
+    pub(crate) cases: Vec<GoldenCase>,
+    /// Inputs left out because nothing says what they should produce.
+    pub(crate) skipped: Vec<String>,
+}
+
+pub(crate) fn load(root: &Path) -> Result<GoldenSet, String> {
+    let entries = std::fs::read_dir(root)
+        .map_err(|error| format!("read golden set {}: {error}", root.display()))?;
+    let mut names: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_file()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+
+    let mut cases = Vec::new();
+    let mut skipped = Vec::new();
+    for name in &names {
+        if name.ends_with(EXPECTED_SUFFIX) || name.ends_with(TERMS_SUFFIX) {
+            continue;
+        }
+        let Some((stem, extension)) = name.rsplit_once('.') else {
+            continue;
+        };
+        let Some(kind) = input_kind(&extension.to_ascii_lowercase()) else {
+            continue;
+        };
+        let expected_path = root.join(format!("{stem}{EXPECTED_SUFFIX}"));
+        let Ok(expected) = std::fs::read_to_string(&expected_path) else {
+            skipped.push(name.clone());
+            continue;
+        };
+        let terms = std::fs::read_to_string(root.join(format!("{stem}{TERMS_SUFFIX}")))
+            .map(|terms| {
+                terms
+                    .lines()
+                    .map(str::trim)
+                    .filter(|term| !term.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        cases.push(GoldenCase {
+            id: name.clone(),
+            kind,
+            input: root.join(name),
+            expected: expected.trim().to_string(),
+            terms,
+        });
+    }
+    if cases.is_empty() {
+        return Err(format!(
+            "{} has no inputs with a matching <name>{EXPECTED_SUFFIX}",
+            root.display()
+        ));
+    }
+    Ok(GoldenSet {
+        root: root.to_path_buf(),
+        cases,
+        skipped,
+    })
+}
+
+fn input_kind(extension: &str) -> Option<InputKind> {
+    if TEXT_EXTENSIONS.contains(&extension) {
+        Some(InputKind::Text)
+    } else if IMAGE_EXTENSIONS.contains(&extension) {
+        Some(InputKind::Image)
+    } else if AUDIO_EXTENSIONS.contains(&extension) {
+        Some(InputKind::Audio)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,83 @@
+//! Optional LLM grading of an output against its expected text, for cases
+//! where wording may legitimately differ from the golden translation.
+
+use crate::api::{TranslateTextRequest, translate_text_streaming};
+use crate::config::Config;
+
+const JUDGE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(90);
+const JUDGE_INSTRUCTION: &str = "You grade the output of a translation/OCR tool against the expected output. \
+Judge meaning, completeness and terminology; ignore harmless differences in wording or formatting. \
+Reply with only a JSON object {\"score\": <integer 0-10>} where 10 means equivalent and 0 means unrelated or wrong.";
+
+pub(super) struct Judge {
+    model_id: String,
+    provider: String,
+    full_name: String,
+    groq_api_key: String,
+    gemini_api_key: String,
+    ui_language: String,
+}
+
+impl Judge {
+    pub(super) fn new(model_id: &str, config: &Config) -> Result<Self, String> {
+        let model =
+            crate::model_config::get_model_by_id_with_custom(model_id, &config.custom_models)
+                .ok_or_else(|| format!("unknown judge model '{model_id}'"))?;
+        Ok(Self {
+            model_id: model.id,
+            provider: model.provider,
+            full_name: model.full_name,
+            groq_api_key: config.api_key.clone(),
+            gemini_api_key: config.gemini_api_key.clone(),
+            ui_language: config.ui_language.clone(),
+        })
+    }
+
+    pub(super) fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    /// A score in `0..=1`.
+    pub(super) fn grade(&self, expected: &str, actual: &str) -> Result<f64, String> {
+        let reply = translate_text_streaming(
+            TranslateTextRequest {
+                groq_api_key: &self.groq_api_key,
+                gemini_api_key: &self.gemini_api_key,
+                text: format!("Expected output:\n{expected}\n\nActual output:\n{actual}"),
+                instruction: JUDGE_INSTRUCTION.to_string(),
+                model: self.full_name.clone(),
+                provider: self.provider.clone(),
+                streaming_enabled: false,
+                use_json_format: true,
+                response_schema: None,
+                search_label: None,
+                ui_language: &self.ui_language,
+                cancel_token: None,
+                request_timeout: Some(JUDGE_TIMEOUT),
+                target_language: None,
+            },
+            |_| {},
+        )
+        .map_err(|error| format!("judge: {error}"))?;
+        parse_score(&reply)
+    }
+}
+
+/// Reads `{"score": n}` with `n` in `0..=10`, tolerating a fenced or chatty
+/// reply around the object.
+pub(super) fn parse_score(reply: &str) -> Result<f64, String> {
+    let start = reply.find('{');
+    let end = reply.rfind('}');
+    let object = match (start, end) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => return Err(format!("judge reply has no JSON object: {reply}")),
+    };
+    let value: serde_json::Value = serde_json::from_str(object)
+        .map_err(|error| format!("judge reply is not JSON ({error}): {reply}"))?;
+    let score = value
+        .get("score")
+        .and_then(serde_json::Value::as_f64)
+        .filter(|score| (0.0..=10.0).contains(score))
+        .ok_or_else(|| format!("judge reply has no score between 0 and 10: {reply}"))?;
+    Ok(score / 10.0)
+}
@@ -0,0 +1,345 @@
+//! Scoring, per-target summaries, regression flags and the JSON/HTML report.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use serde::Serialize;
+
+use super::golden_set::{GoldenCase, GoldenSet, InputKind};
+use crate::config::{Config, secrets};
+use crate::overlay::utils::escape_html;
+use crate::text_metrics::{
+    character_error_rate, ocr_similarity, term_coverage, text_similarity, word_error_rate,
+};
+
+/// How far a case's similarity may fall below the preset's own baseline before
+/// the swapped-in model is flagged for it.
+pub(super) const REGRESSION_MARGIN: f64 = 0.05;
+
+#[derive(Clone, Debug, Serialize)]
+pub(super) struct TargetInfo {
+    pub(super) label: String,
+    pub(super) preset_id: String,
+    /// `None` for the preset as saved, which is the baseline for its overrides.
+    pub(super) model_override: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub(super) struct Scores {
+    /// Insensitive to case, punctuation and Unicode form.
+    pub(super) similarity: f64,
+    /// Keeps case, punctuation and diacritics.
+    pub(super) strict_similarity: f64,
+    pub(super) cer: f64,
+    pub(super) wer: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) term_coverage: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) judge: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) judge_error: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub(super) struct CaseResult {
+    pub(super) target: String,
+    pub(super) case_id: String,
+    pub(super) kind: InputKind,
+    pub(super) latency_ms: u64,
+    /// The model that answered each block, which a fallback may have replaced.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(super) answered_by: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) scores: Option<Scores>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct TargetSummary {
+    pub(super) target: TargetInfo,
+    pub(super) runs: usize,
+    pub(super) failures: usize,
+    pub(super) mean_similarity: Option<f64>,
+    pub(super) mean_strict_similarity: Option<f64>,
+    pub(super) mean_cer: Option<f64>,
+    pub(super) mean_wer: Option<f64>,
+    pub(super) mean_term_coverage: Option<f64>,
+    pub(super) mean_judge: Option<f64>,
+    pub(super) median_latency_ms: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct Regression {
+    pub(super) case_id: String,
+    pub(super) baseline: String,
+    pub(super) target: String,
+    pub(super) baseline_similarity: f64,
+    /// `None` when the target failed outright.
+    pub(super) similarity: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct Report {
+    pub(super) generated_at: String,
+    pub(super) golden_set: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) judge_model: Option<String>,
+    pub(super) cases: usize,
+    pub(super) skipped_inputs: Vec<String>,
+    /// Target and case pairs left out because the preset takes other input.
+    pub(super) skipped_runs: Vec<String>,
+    pub(super) targets: Vec<TargetSummary>,
+    pub(super) regressions: Vec<Regression>,
+    pub(super) results: Vec<CaseResult>,
+}
+
+/// Judge scores, when asked for, come in already graded so this stays pure.
+pub(super) fn score(case: &GoldenCase, output: &str, judge: Option<Result<f64, String>>) -> Scores {
+    let (judge, judge_error) = match judge {
+        Some(Ok(score)) => (Some(score), None),
+        Some(Err(error)) => (None, Some(error)),
+        None => (None, None),
+    };
+    Scores {
+        similarity: text_similarity(output, &case.expected),
+        strict_similarity: ocr_similarity(output, &case.expected),
+        cer: character_error_rate(output, &case.expected),
+        wer: word_error_rate(output, &case.expected),
+        term_coverage: (!case.terms.is_empty()).then(|| term_coverage(output, &case.terms)),
+        judge,
+        judge_error,
+    }
+}
+
+pub(super) fn build(
+    set: &GoldenSet,
+    judge_model: Option<String>,
+    targets: &[TargetInfo],
+    results: Vec<CaseResult>,
+    skipped_runs: Vec<String>,
+) -> Report {
+    let summaries = targets
+        .iter()
+        .map(|target| summarize(target, &results))
+        .collect();
+    Report {
+        generated_at: chrono::Local::now().to_rfc3339(),
+        golden_set: set.root.display().to_string(),
+        judge_model,
+        cases: set.cases.len(),
+        skipped_inputs: set.skipped.clone(),
+        skipped_runs,
+        targets: summaries,
+        regressions: regressions(targets, &results),
+        results,
+    }
+}
+
+fn summarize(target: &TargetInfo, results: &[CaseResult]) -> TargetSummary {
+    let runs: Vec<&CaseResult> = results
+        .iter()
+        .filter(|result| result.target == target.label)
+        .collect();
+    let scores: Vec<&Scores> = runs.iter().filter_map(|run| run.scores.as_ref()).collect();
+    let mean_of =
+        |metric: fn(&Scores) -> Option<f64>| mean(scores.iter().filter_map(|s| metric(s)));
+    let mut latencies: Vec<u64> = runs.iter().map(|run| run.latency_ms).collect();
+    latencies.sort_unstable();
+    TargetSummary {
+        target: target.clone(),
+        runs: runs.len(),
+        failures: runs.iter().filter(|run| run.error.is_some()).count(),
+        mean_similarity: mean_of(|s| Some(s.similarity)),
+        mean_strict_similarity: mean_of(|s| Some(s.strict_similarity)),
+        mean_cer: mean_of(|s| Some(s.cer)),
+        mean_wer: mean_of(|s| Some(s.wer)),
+        mean_term_coverage: mean_of(|s| s.term_coverage),
+        mean_judge: mean_of(|s| s.judge),
+        median_latency_ms: latencies.get(latencies.len() / 2).copied(),
+    }
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), value| {
+        (sum + value, count + 1)
+    });
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Cases a model override does worse on than its preset as saved. Cases the
+/// baseline itself failed say nothing about the override and are left out.
+fn regressions(targets: &[TargetInfo], results: &[CaseResult]) -> Vec<Regression> {
+    let similarity = |target: &str, case_id: &str| {
+        results
+            .iter()
+            .find(|result| result.target == target && result.case_id == case_id)
+            .map(|result| result.scores.as_ref().map(|scores| scores.similarity))
+    };
+    let mut regressions = Vec::new();
+    for target in targets
+        .iter()
+        .filter(|target| target.model_override.is_some())
+    {
+        let Some(baseline) = targets.iter().find(|baseline| {
+            baseline.model_override.is_none() && baseline.preset_id == target.preset_id
+        }) else {
+            continue;
+        };
+        for result in results
+            .iter()
+            .filter(|result| result.target == target.label)
+        {
+            let Some(Some(baseline_similarity)) = similarity(&baseline.label, &result.case_id)
+            else {
+                continue;
+            };
+            let similarity = result.scores.as_ref().map(|scores| scores.similarity);
+            if similarity.is_none_or(|value| baseline_similarity - value > REGRESSION_MARGIN) {
+                regressions.push(Regression {
+                    case_id: result.case_id.clone(),
+                    baseline: baseline.label.clone(),
+                    target: target.label.clone(),
+                    baseline_similarity,
+                    similarity,
+                });
+            }
+        }
+    }
+    regressions
+}
+
+/// Model output and provider errors can echo a key back, so both files are
+/// scrubbed of every key `config` holds.
+pub(super) fn write(report: &Report, config: &Config, directory: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(directory)
+        .map_err(|error| format!("create {}: {error}", directory.display()))?;
+    let json = serde_json::to_string_pretty(&secrets::without_secrets(report, config)?)
+        .map_err(|error| error.to_string())?;
+    let html = secrets::text_without_secrets(&render_html(report), config);
+    for (name, contents) in [("report.json", json), ("report.html", html)] {
+        let path = directory.join(name);
+        std::fs::write(&path, contents)
+            .map_err(|error| format!("write {}: {error}", path.display()))?;
+    }
+    Ok(())
+}
+
+pub(super) fn render_html(report: &Report) -> String {
+    let mut html = String::from(
+        "<!doctype html><html lang=\"en\"><head><meta charset=\"utf-8\"><meta name=\"viewport\" content=\"width=device-width\"><title>Golden-set evaluation</title><style>body{font:14px system-ui,sans-serif;background:#15171b;color:#edf1f5;margin:24px}h1{margin:0 0 8px}.note{color:#b7c0ca}table{border-collapse:collapse;margin:12px 0 24px}th,td{border:1px solid #3a414c;padding:5px 9px;text-align:left;vertical-align:top}th{background:#22262d}.bad{color:#ff6b81}.good{color:#48e692}pre{white-space:pre-wrap;margin:4px 0;max-width:70ch}</style></head><body><h1>Golden-set evaluation</h1>",
+    );
+    let _ = write!(
+        html,
+        "<p class=\"note\">{} · {} cases · generated {}{}</p>",
+        escape_html(&report.golden_set),
+        report.cases,
+        escape_html(&report.generated_at),
+        report
+            .judge_model
+            .as_deref()
+            .map(|model| format!(" · judge {}", escape_html(model)))
+            .unwrap_or_default(),
+    );
+    if !report.skipped_inputs.is_empty() {
+        let _ = write!(
+            html,
+            "<p class=\"note\">Skipped without an expected output: {}</p>",
+            escape_html(&report.skipped_inputs.join(", "))
+        );
+    }
+    if !report.skipped_runs.is_empty() {
+        let _ = write!(
+            html,
+            "<p class=\"note\">Skipped for their input kind: {}</p>",
+            escape_html(&report.skipped_runs.join("; "))
+        );
+    }
+
+    html.push_str("<h2>Targets</h2><table><tr><th>Target</th><th>Runs</th><th>Failures</th><th>Similarity</th><th>Strict</th><th>CER</th><th>WER</th><th>Terms</th><th>Judge</th><th>Median latency</th></tr>");
+    for summary in &report.targets {
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            escape_html(&summary.target.label),
+            summary.runs,
+            summary.failures,
+            percent(summary.mean_similarity),
+            percent(summary.mean_strict_similarity),
+            percent(summary.mean_cer),
+            percent(summary.mean_wer),
+            percent(summary.mean_term_coverage),
+            percent(summary.mean_judge),
+            summary
+                .median_latency_ms
+                .map(|ms| format!("{ms} ms"))
+                .unwrap_or_else(|| "–".to_string()),
+        );
+    }
+    html.push_str("</table><h2>Regressions</h2>");
+    if report.regressions.is_empty() {
+        html.push_str("<p class=\"good\">None against the presets as saved.</p>");
+    } else {
+        html.push_str(
+            "<table><tr><th>Case</th><th>Baseline</th><th>Target</th><th>Similarity</th></tr>",
+        );
+        for regression in &report.regressions {
+            let _ = write!(
+                html,
+                "<tr class=\"bad\"><td>{}</td><td>{}</td><td>{}</td><td>{} → {}</td></tr>",
+                escape_html(&regression.case_id),
+                escape_html(&regression.baseline),
+                escape_html(&regression.target),
+                percent(Some(regression.baseline_similarity)),
+                regression
+                    .similarity
+                    .map(|value| percent(Some(value)))
+                    .unwrap_or_else(|| "failed".to_string()),
+            );
+        }
+        html.push_str("</table>");
+    }
+
+    html.push_str("<h2>Cases</h2><table><tr><th>Target</th><th>Case</th><th>Similarity</th><th>CER</th><th>WER</th><th>Terms</th><th>Judge</th><th>Latency</th><th>Output</th></tr>");
+    for result in &report.results {
+        let scores = result.scores.as_ref();
+        let body = match (&result.output, &result.error) {
+            (_, Some(error)) => format!("<pre class=\"bad\">{}</pre>", escape_html(error)),
+            (Some(output), None) => format!("<pre>{}</pre>", escape_html(output)),
+            (None, None) => String::new(),
+        };
+        let judge = match scores {
+            Some(Scores {
+                judge_error: Some(error),
+                ..
+            }) => format!(
+                "<span class=\"bad\" title=\"{}\">error</span>",
+                escape_html(error)
+            ),
+            _ => percent(scores.and_then(|s| s.judge)),
+        };
+        let _ = write!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{} ms</td><td>{}</td></tr>",
+            escape_html(&result.target),
+            escape_html(&result.case_id),
+            percent(scores.map(|s| s.similarity)),
+            percent(scores.map(|s| s.cer)),
+            percent(scores.map(|s| s.wer)),
+            percent(scores.and_then(|s| s.term_coverage)),
+            judge,
+            result.latency_ms,
+            body,
+        );
+    }
+    html.push_str("</table></body></html>");
+    html
+}
+
+fn percent(value: Option<f64>) -> String {
+    value
+        .map(|value| format!("{:.1}%", value * 100.0))
+        .unwrap_or_else(|| "–".to_string())
+}
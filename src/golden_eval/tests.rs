@@ -0,0 +1,240 @@
+use super::golden_set::{self, GoldenCase, GoldenSet, InputKind};
+use super::report::{self, CaseResult, REGRESSION_MARGIN, TargetInfo};
+use super::{case_result, mismatch, with_model};
+use crate::config::{Config, Preset, ProcessingBlock};
+use crate::overlay::process::chain::HeadlessRun;
+
+fn temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("sgt-golden-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn case(id: &str, expected: &str, terms: &[&str]) -> GoldenCase {
+    GoldenCase {
+        id: id.to_string(),
+        kind: InputKind::Text,
+        input: id.into(),
+        expected: expected.to_string(),
+        terms: terms.iter().map(|term| term.to_string()).collect(),
+    }
+}
+
+fn target(label: &str, model: Option<&str>) -> TargetInfo {
+    TargetInfo {
+        label: label.to_string(),
+        preset_id: "preset_translate".to_string(),
+        model_override: model.map(str::to_string),
+    }
+}
+
+fn result(target: &str, case: &GoldenCase, output: Result<&str, &str>) -> CaseResult {
+    CaseResult {
+        target: target.to_string(),
+        case_id: case.id.clone(),
+        kind: case.kind,
+        latency_ms: 100,
+        answered_by: Vec::new(),
+        output: output.ok().map(str::to_string),
+        error: output.err().map(str::to_string),
+        scores: output.ok().map(|output| report::score(case, output, None)),
+    }
+}
+
+#[test]
+fn the_folder_pairs_inputs_with_expected_outputs_and_terms() {
+    let dir = temp_dir("load");
+    std::fs::write(dir.join("menu.png"), b"png").unwrap();
+    std::fs::write(dir.join("menu.expected.txt"), "Beef noodle soup\n").unwrap();
+    std::fs::write(dir.join("menu.terms.txt"), "Beef\n\n noodle \n").unwrap();
+    std::fs::write(dir.join("note.TXT"), "xin chào").unwrap();
+    std::fs::write(dir.join("note.expected.txt"), "hello").unwrap();
+    std::fs::write(dir.join("orphan.wav"), b"wav").unwrap();
+    std::fs::write(dir.join("readme.pdf"), b"pdf").unwrap();
+    std::fs::create_dir_all(dir.join("eval-reports")).unwrap();
+
+    let set = golden_set::load(&dir).unwrap();
+    let ids: Vec<_> = set.cases.iter().map(|case| case.id.as_str()).collect();
+    assert_eq!(ids, ["menu.png", "note.TXT"]);
+    assert_eq!(set.cases[0].kind, InputKind::Image);
+    assert_eq!(set.cases[0].expected, "Beef noodle soup");
+    assert_eq!(set.cases[0].terms, ["Beef", "noodle"]);
+    assert_eq!(set.cases[1].kind, InputKind::Text);
+    assert!(set.cases[1].terms.is_empty());
+    assert_eq!(set.skipped, ["orphan.wav"]);
+
+    let empty = temp_dir("empty");
+    assert!(golden_set::load(&empty).is_err());
+    let _ = std::fs::remove_dir_all(dir);
+    let _ = std::fs::remove_dir_all(empty);
+}
+
+#[test]
+fn scores_cover_similarity_error_rates_terms_and_the_judge() {
+    let case = case("a.txt", "The red Apple.", &["apple", "pear"]);
+    let scores = report::score(&case, "the red apple", Some(Ok(0.8)));
+    assert_eq!(scores.similarity, 1.0);
+    assert!(scores.strict_similarity < 1.0);
+    assert!(scores.cer > 0.0);
+    assert_eq!(scores.wer, 2.0 / 3.0);
+    assert_eq!(scores.term_coverage, Some(0.5));
+    assert_eq!(scores.judge, Some(0.8));
+
+    let unjudged = report::score(&case, "x", Some(Err("timeout".to_string())));
+    assert_eq!(unjudged.judge, None);
+    assert_eq!(unjudged.judge_error.as_deref(), Some("timeout"));
+}
+
+#[test]
+fn a_model_override_is_flagged_where_it_falls_behind_its_baseline() {
+    let good = case("good.txt", "hello world", &[]);
+    let close = case("close.txt", "hello world", &[]);
+    let broken = case("broken.txt", "hello world", &[]);
+    let baseline_failed = case("hard.txt", "hello world", &[]);
+    let targets = [target("base", None), target("base @ other", Some("other"))];
+    let results = vec![
+        result("base", &good, Ok("hello world")),
+        result("base", &close, Ok("hello world")),
+        result("base", &broken, Ok("hello world")),
+        result("base", &baseline_failed, Err("timeout")),
+        result("base @ other", &good, Ok("goodbye")),
+        result("base @ other", &close, Ok("hello world!")),
+        result("base @ other", &broken, Err("400")),
+        result("base @ other", &baseline_failed, Err("timeout")),
+    ];
+    let set = GoldenSet {
+        root: "golden".into(),
+        cases: vec![good, close, broken, baseline_failed],
+        skipped: Vec::new(),
+    };
+    let report = report::build(&set, None, &targets, results, Vec::new());
+
+    let flagged: Vec<_> = report
+        .regressions
+        .iter()
+        .map(|regression| (regression.case_id.as_str(), regression.similarity))
+        .collect();
+    assert_eq!(flagged.len(), 2);
+    assert_eq!(flagged[0].0, "good.txt");
+    assert!(flagged[0].1.unwrap() < 1.0 - REGRESSION_MARGIN);
+    assert_eq!(flagged[1], ("broken.txt", None));
+
+    let baseline = &report.targets[0];
+    assert_eq!((baseline.runs, baseline.failures), (4, 1));
+    assert_eq!(baseline.mean_similarity, Some(1.0));
+    assert_eq!(baseline.mean_term_coverage, None);
+    assert_eq!(report.targets[1].failures, 2);
+}
+
+#[test]
+fn a_run_answered_by_a_fallback_model_is_a_failure() {
+    let case = case("a.txt", "hello", &[]);
+    let base = target("base", Some("text-b"));
+    let run = |fallbacks: Vec<String>| HeadlessRun {
+        output: "hello".to_string(),
+        answered_by: vec!["Text B".to_string()],
+        fallbacks,
+    };
+
+    let clean = case_result(&base, &case, Ok(run(Vec::new())), 100, None);
+    assert!(clean.error.is_none() && clean.scores.is_some());
+    assert_eq!(clean.answered_by, ["Text B"]);
+
+    let fell_back = vec!["block 0: Text C answered for Text B".to_string()];
+    let failed = case_result(&base, &case, Ok(run(fell_back)), 100, None);
+    assert!(failed.error.unwrap().contains("Text C answered for Text B"));
+    assert!(failed.scores.is_none());
+    assert_eq!(failed.output.as_deref(), Some("hello"));
+}
+
+#[test]
+fn the_html_report_escapes_model_output() {
+    let case = case("a.txt", "x", &[]);
+    let set = GoldenSet {
+        root: "golden".into(),
+        cases: Vec::new(),
+        skipped: vec!["<b>.wav".to_string()],
+    };
+    let results = vec![result("base", &case, Ok("<script>alert(1)</script>"))];
+    let html = report::render_html(&report::build(
+        &set,
+        None,
+        &[target("base", None)],
+        results,
+        Vec::new(),
+    ));
+    assert!(!html.contains("<script>") && !html.contains("<b>.wav"));
+    assert!(html.contains("&lt;script&gt;"));
+}
+
+#[test]
+fn written_reports_never_carry_an_api_key() {
+    let key = "gsk_golden_eval_report_key";
+    let config = Config {
+        api_key: key.to_string(),
+        ..Config::default()
+    };
+    let case = case("a.txt", "x", &[]);
+    let set = GoldenSet {
+        root: "golden".into(),
+        cases: Vec::new(),
+        skipped: Vec::new(),
+    };
+    let error = format!("401: invalid key {key}");
+    let results = vec![
+        result("base", &case, Ok(key)),
+        result("base", &case, Err(&error)),
+    ];
+    let report = report::build(&set, None, &[target("base", None)], results, Vec::new());
+    let dir = temp_dir("secrets");
+
+    report::write(&report, &config, &dir).unwrap();
+    for name in ["report.json", "report.html"] {
+        let contents = std::fs::read_to_string(dir.join(name)).unwrap();
+        assert!(
+            contents.contains("invalid key") && !contents.contains(key),
+            "{name}"
+        );
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn cases_run_only_against_presets_that_take_their_input() {
+    let text_preset = Preset {
+        preset_type: "text".to_string(),
+        ..Preset::default()
+    };
+    let mut image = case("scan.png", "x", &[]);
+    image.kind = InputKind::Image;
+
+    assert_eq!(mismatch(&text_preset, &case("a.txt", "x", &[])), None);
+    assert_eq!(
+        mismatch(&text_preset, &image).as_deref(),
+        Some("the preset takes text input, not image")
+    );
+}
+
+#[test]
+fn a_model_replaces_only_blocks_of_its_kind() {
+    let preset = Preset {
+        blocks: vec![
+            ProcessingBlock {
+                block_type: "image".to_string(),
+                model: "vision-a".to_string(),
+                ..ProcessingBlock::default()
+            },
+            ProcessingBlock {
+                block_type: "text".to_string(),
+                model: "text-a".to_string(),
+                ..ProcessingBlock::default()
+            },
+        ],
+        ..Preset::default()
+    };
+    let swapped = with_model(&preset, "text-b", "text").unwrap();
+    assert_eq!(swapped.blocks[0].model, "vision-a");
+    assert_eq!(swapped.blocks[1].model, "text-b");
+    assert!(with_model(&preset, "whisper", "audio").is_none());
+}
@@ -136,6 +136,8 @@ impl eframe::App for SettingsApp {
         let _ = crate::overlay::image_creator::shutdown();
         crate::overlay::creation_runtime::shutdown();
         crate::overlay::screen_record::cleanup_on_app_exit();
+        crate::usage_stats::spend::flush();
+        crate::retry_model_chain::flush_state();
         self.tray_icon = None;
     }
 }
@@ -196,7 +196,7 @@ impl SettingsApp {
 
             // 2. Trigger auto-update check (Network/Disk IO)
             if let Some(updater) = &self.updater {
-                updater.check_for_updates();
+                updater.check_for_updates(&self.config.updates);
             }
 
             self.startup_stage = 2;
@@ -46,4 +46,6 @@ pub struct BadgeLocaleText {
     pub feature_screen_record: &'static str,
     pub feature_markdown_view: &'static str,
     pub install_webview2_hint: &'static str,
+    pub cc_trigger_finished_fmt: &'static str,
+    pub cc_trigger_failed_fmt: &'static str,
 }
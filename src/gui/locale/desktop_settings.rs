@@ -17,6 +17,14 @@ pub struct DesktopSettingsLocaleText {
     pub usage_updated_now: &'static str,
     pub usage_minutes_ago: &'static str,
     pub usage_stale: &'static str,
+    pub usage_key_requests: &'static str,
+    pub usage_key_rate_limited: &'static str,
+    pub usage_key_cooling: &'static str,
+    pub usage_cooldown_rate_limit: &'static str,
+    pub usage_cooldown_timeout: &'static str,
+    pub usage_cooldown_unavailable: &'static str,
+    pub usage_cooldown_billing: &'static str,
+    pub usage_cooldown_clear: &'static str,
     pub footer_admin_text: &'static str,
     pub check_for_updates_btn: &'static str,
     pub current_version_label: &'static str,
@@ -33,6 +41,12 @@ pub struct DesktopSettingsLocaleText {
     pub update_success: &'static str,
     pub restart_to_use_new_version: &'static str,
     pub restart_app_btn: &'static str,
+    pub update_channel_label: &'static str,
+    pub update_channel_stable: &'static str,
+    pub update_channel_beta: &'static str,
+    pub defer_update_btn: &'static str,
+    pub update_deferred_fmt: &'static str,
+    pub show_deferred_update_btn: &'static str,
     pub preset_model_update_title: &'static str,
     pub preset_model_update_body: &'static str,
     pub preset_model_update_skip: &'static str,
@@ -49,5 +49,7 @@ pub(super) fn get() -> BadgeLocaleText {
         feature_screen_record: "Screen recorder",
         feature_markdown_view: "Markdown view",
         install_webview2_hint: "Open Downloaded Tools, install Microsoft Edge WebView2 Runtime, then try again.",
+        cc_trigger_finished_fmt: "Task {name} finished",
+        cc_trigger_failed_fmt: "Task {name} failed",
     }
 }
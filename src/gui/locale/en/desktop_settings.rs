@@ -20,6 +20,14 @@ pub(super) fn get() -> DesktopSettingsLocaleText {
         usage_updated_now: "Updated now",
         usage_minutes_ago: "min ago",
         usage_stale: "Stale",
+        usage_key_requests: "req",
+        usage_key_rate_limited: "limited",
+        usage_key_cooling: "cooling",
+        usage_cooldown_rate_limit: "Rate limited",
+        usage_cooldown_timeout: "Timing out",
+        usage_cooldown_unavailable: "Unavailable",
+        usage_cooldown_billing: "Out of credit",
+        usage_cooldown_clear: "Clear",
         footer_admin_text: "Run with admin to translate games",
         check_for_updates_btn: "Check for Updates",
         current_version_label: "Current Version:",
@@ -36,6 +44,12 @@ pub(super) fn get() -> DesktopSettingsLocaleText {
         update_success: "Update Success!",
         restart_to_use_new_version: "Restart to use the new version.",
         restart_app_btn: "Restart App",
+        update_channel_label: "Update channel:",
+        update_channel_stable: "Stable",
+        update_channel_beta: "Beta",
+        defer_update_btn: "Skip this version",
+        update_deferred_fmt: "Version {version} is skipped.",
+        show_deferred_update_btn: "Show update",
         preset_model_update_title: "Apply new recommended settings?",
         preset_model_update_body: "This update has new recommendations for preset models, model priorities, and active providers. Apply to update affected built-in model fields, restore both priority lists, and enable recommended providers. No provider is disabled; prompts, graphs, modes, favorites, hotkeys, API keys, and other settings stay unchanged. Skip to keep your current choices. You can apply preset defaults later by restoring presets.",
         preset_model_update_skip: "Don't apply",
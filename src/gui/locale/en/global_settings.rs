@@ -15,6 +15,19 @@ pub(super) fn get() -> GlobalSettingsLocaleText {
         favorite_overlay_opacity_label: "Favorite overlay opacity",
         model_thinking: "Thinking...",
         ollama_url_guide: "View guide at ollama.com",
+        api_key_pool_add: "+ Add another key",
+        api_key_pool_label_hint: "Label",
+        api_key_pool_remove: "Remove this key",
+        api_key_rotation_label: "Key rotation:",
+        api_key_rotation_round_robin: "Round robin",
+        api_key_rotation_least_limited: "Least recently limited",
+        secret_storage_label: "Key storage:",
+        secret_storage_os: "Windows account",
+        secret_storage_passphrase: "Passphrase",
+        secret_storage_passphrase_hint: "Passphrase",
+        secret_storage_unlock: "Unlock",
+        secret_storage_apply: "Use this passphrase",
+        secret_storage_locked: "Keys are locked. Enter the passphrase to use them.",
         restore_defaults_title: "Restore defaults",
         restore_defaults_description: "Choose what to restore. SGT remembers this checklist and restarts after restoring.",
         restore_defaults_select_all: "Select all",
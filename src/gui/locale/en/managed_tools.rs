@@ -21,6 +21,24 @@ pub(super) fn get() -> ManagedToolsLocaleText {
         downloaded_tools_clean_step_runtimes: "Native runtimes",
         downloaded_tools_clean_step_interfaces: "Mini-app interfaces",
         downloaded_tools_clean_step_media: "Recorder assets and pointer packs",
+        download_mirrors_title: "Download mirrors",
+        download_mirrors_description: "Internal HTTPS mirrors to try, in order, before the official download servers. Each file is still checked against its published fingerprint.",
+        download_mirrors_hint: "https://mirror.example.com/sgt (one per line)",
+        storage_title: "Storage",
+        storage_description: "Disk space used by downloaded components. With a quota set, the least recently used components are removed after installs and at startup until everything fits; components in use are never removed.",
+        storage_quota_label: "Quota:",
+        storage_quota_hint: "0 = no limit",
+        storage_free_space: "Free space now",
+        storage_evicting: "Freeing space...",
+        storage_loading: "Measuring downloaded components...",
+        storage_total_fmt: "Downloaded components use {size}",
+        storage_by_feature: "By feature",
+        storage_by_component: "By component",
+        storage_in_use: "In use",
+        storage_last_used_fmt: "Last used {date}",
+        storage_evicted_fmt: "Removed {count} component(s), freeing {size}: {names}.",
+        storage_nothing_evicted: "Nothing needed to be removed.",
+        storage_still_over_fmt: "Still {size} over the quota; the remaining components are in use, recently used or required by others.",
         tool_ai_runtime: "DirectML + ONNX Runtime",
         tool_parakeet: "Parakeet Realtime Model",
         tool_parakeet_tdt: "Parakeet TDT 0.6B v3",
@@ -16,6 +16,13 @@ pub(super) fn get() -> ModelCatalogLocaleText {
         model_priority_auto: "Auto",
         model_priority_auto_hint: "continue with smart fallback order",
         model_priority_skip_hint: "Unavailable providers, missing keys, invalid keys, and unsupported models are skipped immediately during retry.",
+        model_priority_monthly_spend_fmt: "This month: about ${total} across {requests} requests ({unpriced} without catalog pricing)",
+        response_cache_toggle: "Reuse answers for repeated inputs",
+        response_cache_hint: "Re-running a preset on the same input, prompt and model shows the saved answer instead of calling the provider. Search-grounded models are never cached.",
+        response_cache_ttl_label: "Keep for",
+        response_cache_ttl_suffix: " h",
+        response_cache_size_label: "Max size",
+        response_cache_clear_btn: "Clear cache",
         custom_models_desc: "Manage user-added models. Built-in models are visible but locked.",
         custom_models_builtin_locked: "Built-in - locked",
         custom_models_discovered_models: "Discovered",
@@ -13,6 +13,7 @@ pub(super) fn get() -> OverlayLocaleText {
         overlay_back_tooltip: "Back",
         overlay_forward_tooltip: "Forward",
         overlay_opacity_tooltip: "Opacity",
+        overlay_cached_badge: "cached",
         overlay_cancel_tooltip: "Cancel",
         text_input_close_tooltip: "Close",
         text_input_speech_to_text_tooltip: "Speech to text",
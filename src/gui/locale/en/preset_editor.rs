@@ -42,5 +42,17 @@ pub(super) fn get() -> PresetEditorLocaleText {
         node_menu_add_special_audio: "Add Audio → Text Node",
         input_auto_copy_tooltip: "Auto-copy (Source)",
         input_auto_speak_tooltip: "Speak Source",
+        routing_objective_label: "Model routing",
+        routing_objective_hint: "Orders the retry chain when a model fails. Only models already in the chain are used.",
+        routing_priority: "Priority",
+        routing_cheapest: "Cheapest",
+        routing_fastest: "Fastest",
+        routing_best_quality: "Best quality",
+        routing_weighted: "Weighted",
+        routing_weight_cost: "Cost",
+        routing_weight_speed: "Speed",
+        routing_weight_quality: "Quality",
+        routing_quality_floor: "Minimum quality",
+        routing_quality_floor_hint: "Models below this intelligence tier (1–6) are only tried after every model that meets it.",
     }
 }
@@ -24,6 +24,7 @@ pub(super) fn get() -> TtsPlaygroundLocaleText {
         tts_playground_ffmpeg_downloading: "Downloading FFmpeg for MP3 export",
         screen_record_gif_ffmpeg_downloading: "Downloading FFmpeg for GIF export",
         screen_record_audio_ffmpeg_downloading: "Downloading FFmpeg for pitch-preserving audio export",
+        screen_record_archive_ffmpeg_downloading: "Downloading FFmpeg for project archive proxies",
         tts_playground_ffmpeg_extracting: "Extracting FFmpeg",
         tts_playground_ffmpeg_installed: "FFmpeg installed",
         tts_playground_ffmpeg_failed: "FFmpeg install failed",
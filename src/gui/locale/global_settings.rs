@@ -12,6 +12,19 @@ pub struct GlobalSettingsLocaleText {
     pub favorite_overlay_opacity_label: &'static str,
     pub model_thinking: &'static str,
     pub ollama_url_guide: &'static str,
+    pub api_key_pool_add: &'static str,
+    pub api_key_pool_label_hint: &'static str,
+    pub api_key_pool_remove: &'static str,
+    pub api_key_rotation_label: &'static str,
+    pub api_key_rotation_round_robin: &'static str,
+    pub api_key_rotation_least_limited: &'static str,
+    pub secret_storage_label: &'static str,
+    pub secret_storage_os: &'static str,
+    pub secret_storage_passphrase: &'static str,
+    pub secret_storage_passphrase_hint: &'static str,
+    pub secret_storage_unlock: &'static str,
+    pub secret_storage_apply: &'static str,
+    pub secret_storage_locked: &'static str,
     pub restore_defaults_title: &'static str,
     pub restore_defaults_description: &'static str,
     pub restore_defaults_select_all: &'static str,
@@ -49,5 +49,7 @@ pub(super) fn get() -> BadgeLocaleText {
         feature_screen_record: "화면 녹화",
         feature_markdown_view: "Markdown 보기",
         install_webview2_hint: "다운로드한 도구를 열어 Microsoft Edge WebView2 Runtime을 설치한 후 다시 시도하세요.",
+        cc_trigger_finished_fmt: "작업 {name} 완료",
+        cc_trigger_failed_fmt: "작업 {name} 실패",
     }
 }
@@ -20,6 +20,14 @@ pub(super) fn get() -> DesktopSettingsLocaleText {
         usage_updated_now: "방금 업데이트",
         usage_minutes_ago: "분 전",
         usage_stale: "오래됨",
+        usage_key_requests: "요청",
+        usage_key_rate_limited: "제한",
+        usage_key_cooling: "대기",
+        usage_cooldown_rate_limit: "요청 제한",
+        usage_cooldown_timeout: "응답 시간 초과",
+        usage_cooldown_unavailable: "사용 불가",
+        usage_cooldown_billing: "크레딧 소진",
+        usage_cooldown_clear: "해제",
         footer_admin_text: "게임을 번역하려면 관리자로 실행하세요",
         check_for_updates_btn: "업데이트 확인",
         current_version_label: "현재 버전:",
@@ -36,6 +44,12 @@ pub(super) fn get() -> DesktopSettingsLocaleText {
         update_success: "업데이트 성공!",
         restart_to_use_new_version: "새 버전을 사용하려면 다시 시작하세요.",
         restart_app_btn: "앱 다시 시작",
+        update_channel_label: "업데이트 채널:",
+        update_channel_stable: "안정",
+        update_channel_beta: "베타",
+        defer_update_btn: "이 버전 건너뛰기",
+        update_deferred_fmt: "{version} 버전을 건너뛰었습니다.",
+        show_deferred_update_btn: "업데이트 보기",
         preset_model_update_title: "새 권장 설정을 적용할까요?",
         preset_model_update_body: "이번 업데이트에는 프리셋 모델, 모델 우선순위, 활성 공급자에 대한 새 권장 사항이 포함되어 있습니다. 적용하면 영향을 받는 기본 프리셋의 모델 필드를 업데이트하고 두 우선순위 목록을 복원하며 권장 공급자를 활성화합니다. 다른 공급자는 비활성화하지 않으며, 프롬프트, 그래프, 모드, 즐겨찾기, 단축키, API 키와 기타 설정은 그대로 유지됩니다. 적용하지 않으면 현재 선택이 유지됩니다. 나중에 프리셋을 복원해 새 프리셋 기본값을 적용할 수 있습니다.",
         preset_model_update_skip: "적용 안 함",
@@ -15,6 +15,19 @@ pub(super) fn get() -> GlobalSettingsLocaleText {
         favorite_overlay_opacity_label: "결과 오버레이 기본 불투명도",
         model_thinking: "생각 중...",
         ollama_url_guide: "올라마 설명서 보기",
+        api_key_pool_add: "+ 키 추가",
+        api_key_pool_label_hint: "이름",
+        api_key_pool_remove: "이 키 삭제",
+        api_key_rotation_label: "키 순환:",
+        api_key_rotation_round_robin: "순서대로",
+        api_key_rotation_least_limited: "최근 제한이 가장 오래된 키",
+        secret_storage_label: "키 저장 방식:",
+        secret_storage_os: "Windows 계정",
+        secret_storage_passphrase: "암호 문구",
+        secret_storage_passphrase_hint: "암호 문구",
+        secret_storage_unlock: "잠금 해제",
+        secret_storage_apply: "이 암호 문구 사용",
+        secret_storage_locked: "키가 잠겨 있습니다. 사용하려면 암호 문구를 입력하세요.",
         restore_defaults_title: "기본값 복원",
         restore_defaults_description: "복원할 항목을 선택하세요. SGT는 이 목록을 기억하며 복원이 끝나면 다시 시작합니다.",
         restore_defaults_select_all: "모두 선택",
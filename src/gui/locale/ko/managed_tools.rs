@@ -21,6 +21,24 @@ pub(super) fn get() -> ManagedToolsLocaleText {
         downloaded_tools_clean_step_runtimes: "네이티브 런타임",
         downloaded_tools_clean_step_interfaces: "미니 앱 인터페이스",
         downloaded_tools_clean_step_media: "화면 녹화 자산 및 포인터 팩",
+        download_mirrors_title: "다운로드 미러",
+        download_mirrors_description: "공식 다운로드 서버보다 먼저 순서대로 시도할 내부 HTTPS 미러입니다. 모든 파일은 게시된 지문으로 계속 검증됩니다.",
+        download_mirrors_hint: "https://mirror.example.com/sgt (한 줄에 하나씩)",
+        storage_title: "저장 공간",
+        storage_description: "다운로드한 구성 요소가 사용하는 디스크 공간입니다. 할당량을 설정하면 설치 후와 시작 시 가장 오래 사용하지 않은 구성 요소부터 할당량에 맞을 때까지 제거하며, 사용 중인 구성 요소는 제거하지 않습니다.",
+        storage_quota_label: "할당량:",
+        storage_quota_hint: "0 = 제한 없음",
+        storage_free_space: "지금 공간 확보",
+        storage_evicting: "공간 확보 중...",
+        storage_loading: "다운로드한 구성 요소 크기 계산 중...",
+        storage_total_fmt: "다운로드한 구성 요소 사용량: {size}",
+        storage_by_feature: "기능별",
+        storage_by_component: "구성 요소별",
+        storage_in_use: "사용 중",
+        storage_last_used_fmt: "마지막 사용 {date}",
+        storage_evicted_fmt: "구성 요소 {count}개를 제거하여 {size}를 확보했습니다: {names}.",
+        storage_nothing_evicted: "제거할 항목이 없습니다.",
+        storage_still_over_fmt: "아직 할당량을 {size} 초과합니다. 남은 구성 요소는 사용 중이거나 최근에 사용했거나 다른 구성 요소에 필요합니다.",
         tool_ai_runtime: "DirectML + ONNX Runtime",
         tool_parakeet: "Parakeet 실시간 모델",
         tool_parakeet_tdt: "Parakeet TDT 0.6B v3",
@@ -16,6 +16,13 @@ pub(super) fn get() -> ModelCatalogLocaleText {
         model_priority_auto: "자동",
         model_priority_auto_hint: "스마트 폴백 순서로 계속",
         model_priority_skip_hint: "비활성 공급자, 누락된 키, 잘못된 키, 지원되지 않는 모델은 재시도 시 즉시 건너뜁니다.",
+        model_priority_monthly_spend_fmt: "이번 달: 요청 {requests}건에 약 ${total} (카탈로그 가격이 없는 요청 {unpriced}건)",
+        response_cache_toggle: "반복 입력에 답변 재사용",
+        response_cache_hint: "같은 입력, 프롬프트, 모델로 프리셋을 다시 실행하면 제공자를 호출하지 않고 저장된 답변을 표시합니다. 웹 검색 모델은 캐시하지 않습니다.",
+        response_cache_ttl_label: "보관 기간",
+        response_cache_ttl_suffix: "시간",
+        response_cache_size_label: "최대 크기",
+        response_cache_clear_btn: "캐시 지우기",
         custom_models_desc: "직접 추가한 모델을 관리합니다. 기본 모델은 보기 전용입니다.",
         custom_models_builtin_locked: "기본 - 잠김",
         custom_models_discovered_models: "검색됨",
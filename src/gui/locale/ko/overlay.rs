@@ -13,6 +13,7 @@ pub(super) fn get() -> OverlayLocaleText {
         overlay_back_tooltip: "뒤로",
         overlay_forward_tooltip: "앞으로",
         overlay_opacity_tooltip: "불투명도",
+        overlay_cached_badge: "캐시됨",
         overlay_cancel_tooltip: "취소",
         text_input_close_tooltip: "닫기",
         text_input_speech_to_text_tooltip: "음성을 텍스트로",
@@ -42,5 +42,17 @@ pub(super) fn get() -> PresetEditorLocaleText {
         node_menu_add_special_audio: "오디오 → 텍스트 노드 추가",
         input_auto_copy_tooltip: "자동 복사 (소스)",
         input_auto_speak_tooltip: "소스 읽기",
+        routing_objective_label: "모델 라우팅",
+        routing_objective_hint: "모델이 실패하면 재시도 체인의 순서를 정합니다. 체인에 이미 있는 모델만 사용합니다.",
+        routing_priority: "우선순위",
+        routing_cheapest: "최저 비용",
+        routing_fastest: "최고 속도",
+        routing_best_quality: "최고 품질",
+        routing_weighted: "가중치",
+        routing_weight_cost: "비용",
+        routing_weight_speed: "속도",
+        routing_weight_quality: "품질",
+        routing_quality_floor: "최소 품질",
+        routing_quality_floor_hint: "이 지능 등급(1–6)보다 낮은 모델은 등급을 충족하는 모든 모델 다음에만 시도합니다.",
     }
 }
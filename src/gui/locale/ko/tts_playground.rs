@@ -24,6 +24,7 @@ pub(super) fn get() -> TtsPlaygroundLocaleText {
         tts_playground_ffmpeg_downloading: "MP3 내보내기를 위해 FFmpeg 다운로드 중",
         screen_record_gif_ffmpeg_downloading: "GIF 내보내기를 위해 FFmpeg 다운로드 중",
         screen_record_audio_ffmpeg_downloading: "피치 보존 오디오 내보내기를 위해 FFmpeg 다운로드 중",
+        screen_record_archive_ffmpeg_downloading: "프로젝트 아카이브 프록시를 위해 FFmpeg 다운로드 중",
         tts_playground_ffmpeg_extracting: "FFmpeg 압축 해제 중",
         tts_playground_ffmpeg_installed: "FFmpeg 설치 완료",
         tts_playground_ffmpeg_failed: "FFmpeg 설치 실패",
@@ -18,6 +18,24 @@ pub struct ManagedToolsLocaleText {
     pub downloaded_tools_clean_step_runtimes: &'static str,
     pub downloaded_tools_clean_step_interfaces: &'static str,
     pub downloaded_tools_clean_step_media: &'static str,
+    pub download_mirrors_title: &'static str,
+    pub download_mirrors_description: &'static str,
+    pub download_mirrors_hint: &'static str,
+    pub storage_title: &'static str,
+    pub storage_description: &'static str,
+    pub storage_quota_label: &'static str,
+    pub storage_quota_hint: &'static str,
+    pub storage_free_space: &'static str,
+    pub storage_evicting: &'static str,
+    pub storage_loading: &'static str,
+    pub storage_total_fmt: &'static str,
+    pub storage_by_feature: &'static str,
+    pub storage_by_component: &'static str,
+    pub storage_in_use: &'static str,
+    pub storage_last_used_fmt: &'static str,
+    pub storage_evicted_fmt: &'static str,
+    pub storage_nothing_evicted: &'static str,
+    pub storage_still_over_fmt: &'static str,
     pub tool_ai_runtime: &'static str,
     pub tool_parakeet: &'static str,
     pub tool_parakeet_tdt: &'static str,
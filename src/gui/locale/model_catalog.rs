@@ -13,6 +13,13 @@ pub struct ModelCatalogLocaleText {
     pub model_priority_auto: &'static str,
     pub model_priority_auto_hint: &'static str,
     pub model_priority_skip_hint: &'static str,
+    pub model_priority_monthly_spend_fmt: &'static str,
+    pub response_cache_toggle: &'static str,
+    pub response_cache_hint: &'static str,
+    pub response_cache_ttl_label: &'static str,
+    pub response_cache_ttl_suffix: &'static str,
+    pub response_cache_size_label: &'static str,
+    pub response_cache_clear_btn: &'static str,
     pub custom_models_desc: &'static str,
     pub custom_models_builtin_locked: &'static str,
     pub custom_models_discovered_models: &'static str,
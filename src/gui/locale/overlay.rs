@@ -10,6 +10,7 @@ pub struct OverlayLocaleText {
     pub overlay_back_tooltip: &'static str,
     pub overlay_forward_tooltip: &'static str,
     pub overlay_opacity_tooltip: &'static str,
+    pub overlay_cached_badge: &'static str,
     pub overlay_cancel_tooltip: &'static str,
     pub text_input_close_tooltip: &'static str,
     pub text_input_speech_to_text_tooltip: &'static str,
@@ -39,4 +39,16 @@ pub struct PresetEditorLocaleText {
     pub node_menu_add_special_audio: &'static str,
     pub input_auto_copy_tooltip: &'static str,
     pub input_auto_speak_tooltip: &'static str,
+    pub routing_objective_label: &'static str,
+    pub routing_objective_hint: &'static str,
+    pub routing_priority: &'static str,
+    pub routing_cheapest: &'static str,
+    pub routing_fastest: &'static str,
+    pub routing_best_quality: &'static str,
+    pub routing_weighted: &'static str,
+    pub routing_weight_cost: &'static str,
+    pub routing_weight_speed: &'static str,
+    pub routing_weight_quality: &'static str,
+    pub routing_quality_floor: &'static str,
+    pub routing_quality_floor_hint: &'static str,
 }
@@ -123,14 +123,14 @@ fn locale_root_contains_only_the_seventeen_typed_sections() {
 #[test]
 fn locale_leaf_fields_have_one_section_owner() {
     let sections = [
-        ("badge", include_str!("badge.rs"), 47),
+        ("badge", include_str!("badge.rs"), 49),
         ("workspace", include_str!("workspace.rs"), 25),
         ("preset_basics", include_str!("preset_basics.rs"), 36),
-        ("desktop_settings", include_str!("desktop_settings.rs"), 38),
-        ("preset_editor", include_str!("preset_editor.rs"), 40),
-        ("global_settings", include_str!("global_settings.rs"), 34),
-        ("tts_playground", include_str!("tts_playground.rs"), 29),
-        ("model_catalog", include_str!("model_catalog.rs"), 28),
+        ("desktop_settings", include_str!("desktop_settings.rs"), 52),
+        ("preset_editor", include_str!("preset_editor.rs"), 52),
+        ("global_settings", include_str!("global_settings.rs"), 47),
+        ("tts_playground", include_str!("tts_playground.rs"), 30),
+        ("model_catalog", include_str!("model_catalog.rs"), 35),
         ("tts_settings", include_str!("tts_settings.rs"), 29),
         ("tts_advanced", include_str!("tts_advanced.rs"), 35),
         ("realtime", include_str!("realtime.rs"), 32),
@@ -142,7 +142,7 @@ fn locale_leaf_fields_have_one_section_owner() {
             30,
         ),
         ("tool_runtime", include_str!("tool_runtime.rs"), 44),
-        ("overlay", include_str!("overlay.rs"), 22),
+        ("overlay", include_str!("overlay.rs"), 23),
         ("auxiliary", include_str!("auxiliary.rs"), 10),
     ];
     let mut owners = BTreeMap::new();
@@ -158,7 +158,7 @@ fn locale_leaf_fields_have_one_section_owner() {
         }
     }
 
-    assert_eq!(owners.len(), 545);
+    assert_eq!(owners.len(), 595);
     assert_eq!(owners["cancel_label"], "preset_basics");
     assert_eq!(owners["favorites_keep_open"], "shell");
     assert_eq!(owners["image_creator_btn"], "shell");
@@ -171,7 +171,7 @@ fn locale_leaf_fields_have_one_section_owner() {
 fn downloaded_tools_progress_and_cards_are_localized() {
     assert_eq!(
         public_field_names(include_str!("managed_tools.rs")).len(),
-        149
+        167
     );
     let english = LocaleText::get("en");
     for code in ["en", "ko", "vi"] {
@@ -187,6 +187,8 @@ fn downloaded_tools_progress_and_cards_are_localized() {
             badge.removing_component_fmt,
             badge.component_removed_fmt,
             badge.component_remove_failed_fmt,
+            badge.cc_trigger_finished_fmt,
+            badge.cc_trigger_failed_fmt,
         ] {
             assert!(template.contains("{name}"), "{code}: {template}");
         }
@@ -21,6 +21,7 @@ pub struct TtsPlaygroundLocaleText {
     pub tts_playground_ffmpeg_downloading: &'static str,
     pub screen_record_gif_ffmpeg_downloading: &'static str,
     pub screen_record_audio_ffmpeg_downloading: &'static str,
+    pub screen_record_archive_ffmpeg_downloading: &'static str,
     pub tts_playground_ffmpeg_extracting: &'static str,
     pub tts_playground_ffmpeg_installed: &'static str,
     pub tts_playground_ffmpeg_failed: &'static str,
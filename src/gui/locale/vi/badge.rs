@@ -49,5 +49,7 @@ pub(super) fn get() -> BadgeLocaleText {
         feature_screen_record: "Trình ghi màn hình",
         feature_markdown_view: "Chế độ xem Markdown",
         install_webview2_hint: "Mở Công cụ, cài Microsoft Edge WebView2 Runtime rồi thử lại.",
+        cc_trigger_finished_fmt: "Đã xong tác vụ {name}",
+        cc_trigger_failed_fmt: "Tác vụ {name} thất bại",
     }
 }
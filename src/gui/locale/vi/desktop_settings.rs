@@ -20,6 +20,14 @@ pub(super) fn get() -> DesktopSettingsLocaleText {
         usage_updated_now: "Vừa cập nhật",
         usage_minutes_ago: "phút trước",
         usage_stale: "Đã cũ",
+        usage_key_requests: "lượt",
+        usage_key_rate_limited: "bị giới hạn",
+        usage_key_cooling: "đang chờ",
+        usage_cooldown_rate_limit: "Bị giới hạn",
+        usage_cooldown_timeout: "Quá thời gian",
+        usage_cooldown_unavailable: "Không khả dụng",
+        usage_cooldown_billing: "Hết tín dụng",
+        usage_cooldown_clear: "Bỏ chờ",
         footer_admin_text: "chạy bằng admin để dịch game",
         check_for_updates_btn: "Kiểm Tra Cập Nhật",
         current_version_label: "Phiên Bản Hiện Tại:",
@@ -36,6 +44,12 @@ pub(super) fn get() -> DesktopSettingsLocaleText {
         update_success: "Cập Nhật Thành Công!",
         restart_to_use_new_version: "Khởi động lại để sử dụng phiên bản mới.",
         restart_app_btn: "Khởi Động Lại Ứng Dụng",
+        update_channel_label: "Kênh cập nhật:",
+        update_channel_stable: "Ổn định",
+        update_channel_beta: "Beta",
+        defer_update_btn: "Bỏ qua phiên bản này",
+        update_deferred_fmt: "Đã bỏ qua phiên bản {version}.",
+        show_deferred_update_btn: "Hiện bản cập nhật",
         preset_model_update_title: "Áp dụng các cài đặt đề xuất mới?",
         preset_model_update_body: "Bản cập nhật này có đề xuất mới cho mô hình preset, chuỗi ưu tiên và nhà cung cấp nên bật. Áp dụng để cập nhật trường mô hình của các preset dựng sẵn bị ảnh hưởng, khôi phục hai chuỗi ưu tiên và bật các nhà cung cấp được đề xuất. Không nhà cung cấp nào bị tắt; prompt, sơ đồ, chế độ, mục yêu thích, phím tắt, khóa API và các cài đặt khác vẫn giữ nguyên. Không áp dụng để giữ các lựa chọn hiện tại. Bạn có thể khôi phục preset sau để nhận mặc định preset mới.",
         preset_model_update_skip: "Không áp dụng",
@@ -15,6 +15,19 @@ pub(super) fn get() -> GlobalSettingsLocaleText {
         favorite_overlay_opacity_label: "Độ mờ mặc định cửa sổ kết quả",
         model_thinking: "Đang suy nghĩ...",
         ollama_url_guide: "Xem hướng dẫn tại ollama.com",
+        api_key_pool_add: "+ Thêm mã khác",
+        api_key_pool_label_hint: "Nhãn",
+        api_key_pool_remove: "Xóa mã này",
+        api_key_rotation_label: "Xoay vòng mã:",
+        api_key_rotation_round_robin: "Lần lượt",
+        api_key_rotation_least_limited: "Mã ít bị giới hạn gần đây nhất",
+        secret_storage_label: "Lưu trữ mã:",
+        secret_storage_os: "Tài khoản Windows",
+        secret_storage_passphrase: "Cụm mật khẩu",
+        secret_storage_passphrase_hint: "Cụm mật khẩu",
+        secret_storage_unlock: "Mở khóa",
+        secret_storage_apply: "Dùng cụm mật khẩu này",
+        secret_storage_locked: "Các mã đang bị khóa. Nhập cụm mật khẩu để sử dụng.",
         restore_defaults_title: "Khôi phục mặc định",
         restore_defaults_description: "Chọn những phần cần khôi phục. SGT sẽ ghi nhớ danh sách này và khởi động lại sau khi hoàn tất.",
         restore_defaults_select_all: "Chọn tất cả",
@@ -21,6 +21,24 @@ pub(super) fn get() -> ManagedToolsLocaleText {
         downloaded_tools_clean_step_runtimes: "Runtime native",
         downloaded_tools_clean_step_interfaces: "Giao diện ứng dụng mini",
         downloaded_tools_clean_step_media: "Tài nguyên Quay màn hình và bộ con trỏ",
+        download_mirrors_title: "Máy chủ tải thay thế",
+        download_mirrors_description: "Các máy chủ HTTPS nội bộ được thử lần lượt trước máy chủ tải chính thức. Mỗi tệp vẫn được kiểm tra theo dấu vân tay đã công bố.",
+        download_mirrors_hint: "https://mirror.example.com/sgt (mỗi dòng một địa chỉ)",
+        storage_title: "Dung lượng",
+        storage_description: "Dung lượng ổ đĩa mà các thành phần đã tải đang dùng. Khi đặt hạn mức, các thành phần lâu không dùng nhất sẽ bị gỡ sau mỗi lần cài đặt và khi khởi động cho đến khi vừa hạn mức; thành phần đang dùng sẽ không bao giờ bị gỡ.",
+        storage_quota_label: "Hạn mức:",
+        storage_quota_hint: "0 = không giới hạn",
+        storage_free_space: "Giải phóng ngay",
+        storage_evicting: "Đang giải phóng dung lượng...",
+        storage_loading: "Đang đo các thành phần đã tải...",
+        storage_total_fmt: "Các thành phần đã tải dùng {size}",
+        storage_by_feature: "Theo tính năng",
+        storage_by_component: "Theo thành phần",
+        storage_in_use: "Đang dùng",
+        storage_last_used_fmt: "Dùng lần cuối {date}",
+        storage_evicted_fmt: "Đã gỡ {count} thành phần, giải phóng {size}: {names}.",
+        storage_nothing_evicted: "Không cần gỡ thành phần nào.",
+        storage_still_over_fmt: "Vẫn vượt hạn mức {size}; các thành phần còn lại đang được dùng, vừa được dùng hoặc là phụ thuộc của thành phần khác.",
         tool_ai_runtime: "DirectML + ONNX Runtime",
         tool_parakeet: "Mô hình Parakeet Realtime",
         tool_parakeet_tdt: "Parakeet TDT 0.6B v3",
@@ -16,6 +16,13 @@ pub(super) fn get() -> ModelCatalogLocaleText {
         model_priority_auto: "Tự động",
         model_priority_auto_hint: "tiếp tục theo thứ tự fallback thông minh",
         model_priority_skip_hint: "Nhà cung cấp tắt, thiếu khóa, khóa sai, hoặc mô hình không hỗ trợ sẽ bị bỏ qua ngay khi thử lại.",
+        model_priority_monthly_spend_fmt: "Tháng này: khoảng ${total} cho {requests} yêu cầu ({unpriced} không có giá trong danh mục)",
+        response_cache_toggle: "Dùng lại câu trả lời cho đầu vào lặp lại",
+        response_cache_hint: "Chạy lại preset với cùng đầu vào, lời nhắc và mô hình sẽ hiện câu trả lời đã lưu thay vì gọi nhà cung cấp. Mô hình có tìm kiếm web không bao giờ được lưu đệm.",
+        response_cache_ttl_label: "Giữ trong",
+        response_cache_ttl_suffix: " giờ",
+        response_cache_size_label: "Dung lượng tối đa",
+        response_cache_clear_btn: "Xóa bộ nhớ đệm",
         custom_models_desc: "Quản lý mô hình tự thêm. Mô hình mặc định chỉ xem, không chỉnh sửa.",
         custom_models_builtin_locked: "Mặc định - đã khóa",
         custom_models_discovered_models: "Tự quét",
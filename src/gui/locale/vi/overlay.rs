@@ -13,6 +13,7 @@ pub(super) fn get() -> OverlayLocaleText {
         overlay_back_tooltip: "Quay lại",
         overlay_forward_tooltip: "Tiếp theo",
         overlay_opacity_tooltip: "Độ mờ",
+        overlay_cached_badge: "bộ nhớ đệm",
         overlay_cancel_tooltip: "Hủy",
         text_input_close_tooltip: "Đóng",
         text_input_speech_to_text_tooltip: "Giọng nói sang văn bản",
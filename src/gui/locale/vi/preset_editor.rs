@@ -42,5 +42,17 @@ pub(super) fn get() -> PresetEditorLocaleText {
         node_menu_add_special_audio: "Thêm node Audio → Text",
         input_auto_copy_tooltip: "Tự động copy (Nguồn)",
         input_auto_speak_tooltip: "Đọc to nguồn",
+        routing_objective_label: "Định tuyến mô hình",
+        routing_objective_hint: "Sắp xếp chuỗi thử lại khi một mô hình gặp lỗi. Chỉ dùng các mô hình đã có trong chuỗi.",
+        routing_priority: "Ưu tiên",
+        routing_cheapest: "Rẻ nhất",
+        routing_fastest: "Nhanh nhất",
+        routing_best_quality: "Chất lượng nhất",
+        routing_weighted: "Tùy trọng số",
+        routing_weight_cost: "Chi phí",
+        routing_weight_speed: "Tốc độ",
+        routing_weight_quality: "Chất lượng",
+        routing_quality_floor: "Chất lượng tối thiểu",
+        routing_quality_floor_hint: "Các mô hình dưới cấp độ thông minh này (1–6) chỉ được thử sau mọi mô hình đạt cấp độ đó.",
     }
 }
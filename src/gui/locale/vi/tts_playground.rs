@@ -24,6 +24,7 @@ pub(super) fn get() -> TtsPlaygroundLocaleText {
         tts_playground_ffmpeg_downloading: "Đang tải FFmpeg để xuất MP3",
         screen_record_gif_ffmpeg_downloading: "Đang tải FFmpeg để xuất GIF",
         screen_record_audio_ffmpeg_downloading: "Đang tải FFmpeg để xuất âm thanh giữ nguyên cao độ",
+        screen_record_archive_ffmpeg_downloading: "Đang tải FFmpeg để tạo bản proxy cho tệp dự án",
         tts_playground_ffmpeg_extracting: "Đang giải nén FFmpeg",
         tts_playground_ffmpeg_installed: "Đã cài FFmpeg",
         tts_playground_ffmpeg_failed: "Cài FFmpeg thất bại",
@@ -0,0 +1,102 @@
+//! Extra keys under each provider's primary key, plus the rotation picker.
+//!
+//! Rows are edited in place in `Config::api_key_pool`; a key left blank is
+//! kept as a row but never joins the pool.
+
+use super::secret_row;
+use crate::config::Config;
+use crate::config::types::{ApiKeyRotation, PooledApiKey};
+use crate::gui::icons::{ICON_SM, Icon, icon_button_sized};
+use crate::gui::locale::LocaleText;
+use eframe::egui;
+
+const LABEL_FIELD_WIDTH: f32 = 96.0;
+
+/// The extra key rows for `provider` and the link that adds one.
+pub(super) fn render_pool_rows(
+    ui: &mut egui::Ui,
+    config: &mut Config,
+    provider: &str,
+    text: &LocaleText,
+) -> bool {
+    let mut changed = false;
+    let mut remove = None;
+    for (index, entry) in config.api_key_pool.iter_mut().enumerate() {
+        if entry.provider != provider {
+            continue;
+        }
+        ui.horizontal(|ui| {
+            if icon_button_sized(ui, Icon::Close, ICON_SM)
+                .on_hover_text(text.global_settings.api_key_pool_remove)
+                .clicked()
+            {
+                remove = Some(index);
+            }
+            changed |= ui
+                .add(
+                    egui::TextEdit::singleline(&mut entry.label)
+                        .id(egui::Id::new(("settings_api_key_pool_label", index)))
+                        .hint_text(text.global_settings.api_key_pool_label_hint)
+                        .desired_width(LABEL_FIELD_WIDTH),
+                )
+                .changed();
+            let visible_id = egui::Id::new(("settings_api_key_pool_visible", index));
+            let mut visible = ui
+                .ctx()
+                .data(|data| data.get_temp(visible_id).unwrap_or(false));
+            changed |= secret_row(
+                ui,
+                &format!("settings_api_key_pool_{index}"),
+                &mut entry.key,
+                &mut visible,
+            );
+            ui.ctx()
+                .data_mut(|data| data.insert_temp(visible_id, visible));
+        });
+    }
+    if let Some(index) = remove {
+        config.api_key_pool.remove(index);
+        changed = true;
+    }
+    if ui.link(text.global_settings.api_key_pool_add).clicked() {
+        config.api_key_pool.push(PooledApiKey::new(provider));
+        changed = true;
+    }
+    changed
+}
+
+/// The rotation picker, shown once any provider has an extra key.
+pub(super) fn render_rotation(ui: &mut egui::Ui, config: &mut Config, text: &LocaleText) -> bool {
+    if config.api_key_pool.is_empty() {
+        return false;
+    }
+    let mut changed = false;
+    ui.add_space(4.0);
+    ui.horizontal(|ui| {
+        ui.label(text.global_settings.api_key_rotation_label);
+        crate::gui::widgets::combo("settings_api_key_rotation")
+            .selected_text(rotation_label(config.api_key_rotation, text))
+            .show_ui(ui, |ui| {
+                for rotation in [
+                    ApiKeyRotation::RoundRobin,
+                    ApiKeyRotation::LeastRecentlyLimited,
+                ] {
+                    changed |= ui
+                        .selectable_value(
+                            &mut config.api_key_rotation,
+                            rotation,
+                            rotation_label(rotation, text),
+                        )
+                        .changed();
+                }
+            });
+    });
+    changed
+}
+
+fn rotation_label(rotation: ApiKeyRotation, text: &LocaleText) -> &'static str {
+    match rotation {
+        ApiKeyRotation::RoundRobin => text.global_settings.api_key_rotation_round_robin,
+        ApiKeyRotation::LeastRecentlyLimited => text.global_settings.api_key_rotation_least_limited,
+    }
+}
@@ -3,6 +3,11 @@ use crate::gui::icons::{Icon, draw_icon_static, icon_button};
 use crate::gui::locale::LocaleText;
 use eframe::egui;
 
+#[path = "api_key_pool.rs"]
+mod api_key_pool;
+#[path = "secret_storage.rs"]
+mod secret_storage;
+
 /// A key row: the field claims every point left after the row's trailing
 /// control, so the eye stays pinned to the right edge at any card width.
 ///
@@ -154,6 +159,7 @@ pub(super) fn render_api_keys_card(
                 if secret_row(ui, "settings_api_key_groq", &mut config.api_key, groq) {
                     changed = true;
                 }
+                changed |= api_key_pool::render_pool_rows(ui, config, "groq", text);
             }
 
             if config.use_gemini {
@@ -171,6 +177,7 @@ pub(super) fn render_api_keys_card(
                 ) {
                     changed = true;
                 }
+                changed |= api_key_pool::render_pool_rows(ui, config, "google", text);
             }
 
             if config.use_openrouter {
@@ -191,6 +198,7 @@ pub(super) fn render_api_keys_card(
                 ) {
                     changed = true;
                 }
+                changed |= api_key_pool::render_pool_rows(ui, config, "openrouter", text);
             }
 
             if config.use_nvidia {
@@ -208,8 +216,12 @@ pub(super) fn render_api_keys_card(
                 ) {
                     changed = true;
                 }
+                changed |= api_key_pool::render_pool_rows(ui, config, "nvidia", text);
             }
 
+            changed |= api_key_pool::render_rotation(ui, config, text);
+            changed |= secret_storage::render_secret_storage(ui, config, text);
+
             if config.use_ollama {
                 ui.horizontal(|ui| {
                     ui.label("Ollama URL:");
@@ -1,3 +1,4 @@
+use crate::config::Config;
 use crate::gui::locale::LocaleText;
 use crate::gui::settings_ui::download_manager::DownloadManager;
 use crate::gui::theme::AppTheme;
@@ -9,11 +10,13 @@ mod backgrounds;
 mod cleanup;
 mod computer_control;
 mod mcp;
+mod mirrors;
 mod model_card;
 mod model_sections;
 mod pointer_packs;
 mod recorder;
 mod screen_text_detector;
+mod storage;
 mod three_d_generator;
 mod tts_models;
 mod utils;
@@ -25,12 +28,14 @@ use self::{
     backgrounds::render_background_downloads_section,
     computer_control::render_computer_control_card,
     mcp::render_mcp_card,
+    mirrors::render_mirrors_card,
     model_sections::{
         render_kokoro_card, render_parakeet_card, render_qwen3_card, render_supertonic_card,
     },
     pointer_packs::render_pointer_pack_downloads_section,
     recorder::render_recorder_card,
     screen_text_detector::render as render_screen_text_detector_card,
+    storage::render_storage_card,
     three_d_generator::render_three_d_generator_card,
     tts_models::{render_magpie_card, render_step_audio_card, render_vieneu_card},
     video_downloader::render_video_downloader_card,
@@ -79,9 +84,11 @@ pub fn render_downloaded_tools_modal(
     ctx: &egui::Context,
     _ui: &mut egui::Ui,
     show_modal: &mut bool,
+    config: &mut Config,
     download_manager: &mut DownloadManager,
     text: &LocaleText,
-) {
+) -> bool {
+    let mut changed = false;
     if *show_modal {
         let mut open = true;
         let theme = AppTheme::from_dark(ctx.global_style().visuals.dark_mode);
@@ -210,10 +217,15 @@ pub fn render_downloaded_tools_modal(
                                 });
                             });
                         });
+                        ui.add_space(8.0);
+                        changed |= render_storage_card(ui, config, text);
+                        ui.add_space(8.0);
+                        changed |= render_mirrors_card(ui, config, text);
                     });
             });
 
         *show_modal = open;
     }
     cleanup::render(ctx, text, download_manager);
+    changed
 }
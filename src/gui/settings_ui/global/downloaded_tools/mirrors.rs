@@ -0,0 +1,52 @@
+//! Downloaded Tools card for internal download mirrors.
+//!
+//! One URL prefix per line, stored in `Config::component_mirrors`. Downloads
+//! try each prefix in order before the pinned URL and verify every file
+//! against its pinned digest, so a mirror only needs to copy the files.
+
+use crate::config::Config;
+use crate::gui::locale::LocaleText;
+use eframe::egui;
+
+use super::utils::tool_card;
+
+pub(super) fn render_mirrors_card(
+    ui: &mut egui::Ui,
+    config: &mut Config,
+    text: &LocaleText,
+) -> bool {
+    let managed = &text.auxiliary.managed_tools;
+    // Keep the typed text, blank lines included, while the list is edited.
+    let buffer_id = egui::Id::new("downloaded_tools_mirrors_text");
+    let mut buffer = ui
+        .ctx()
+        .data(|data| data.get_temp::<String>(buffer_id))
+        .unwrap_or_else(|| config.component_mirrors.join("\n"));
+    let mut changed = false;
+    tool_card(ui, |ui| {
+        ui.heading(managed.download_mirrors_title);
+        ui.add_space(4.0);
+        ui.label(managed.download_mirrors_description);
+        ui.add_space(4.0);
+        if ui
+            .add(
+                egui::TextEdit::multiline(&mut buffer)
+                    .hint_text(managed.download_mirrors_hint)
+                    .desired_rows(2)
+                    .desired_width(f32::INFINITY),
+            )
+            .changed()
+        {
+            config.component_mirrors = buffer
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect();
+            changed = true;
+        }
+    });
+    ui.ctx()
+        .data_mut(|data| data.insert_temp(buffer_id, buffer));
+    changed
+}
@@ -0,0 +1,217 @@
+//! Downloaded Tools card for component disk use and the eviction quota.
+//!
+//! The size report walks every component directory, so it is computed off the
+//! UI thread and cached briefly; an eviction refreshes it when it finishes.
+
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::component_registry::storage::{self, EvictionReport, StorageReport};
+use crate::config::Config;
+use crate::gui::locale::{LocaleText, ManagedToolsLocaleText};
+use crate::gui::theme::AppTheme;
+use eframe::egui;
+
+use super::utils::{clear_downloaded_tools_caches, format_size, tool_card};
+
+const REPORT_TTL: Duration = Duration::from_secs(30);
+const MAX_QUOTA_GB: u32 = 4096;
+
+#[derive(Default)]
+struct ReportCache {
+    report: Option<Result<StorageReport, String>>,
+    updated_at: Option<Instant>,
+    loading: bool,
+    /// Whether an eviction was running when the report was last requested.
+    evicting: bool,
+}
+
+static REPORT: LazyLock<Mutex<ReportCache>> = LazyLock::new(Mutex::default);
+
+pub(super) fn render_storage_card(
+    ui: &mut egui::Ui,
+    config: &mut Config,
+    text: &LocaleText,
+) -> bool {
+    let managed = &text.auxiliary.managed_tools;
+    let evicting = storage::eviction_running();
+    let report = cached_report(ui.ctx(), evicting);
+    let mut changed = false;
+    tool_card(ui, |ui| {
+        let theme = AppTheme::from_ui(ui);
+        ui.heading(managed.storage_title);
+        ui.add_space(4.0);
+        ui.label(managed.storage_description);
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label(managed.storage_quota_label);
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut config.component_disk_quota_gb)
+                        .range(0..=MAX_QUOTA_GB)
+                        .suffix(" GB"),
+                )
+                .changed();
+            ui.label(egui::RichText::new(managed.storage_quota_hint).color(egui::Color32::GRAY));
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if evicting {
+                    ui.label(managed.storage_evicting);
+                    ui.spinner();
+                } else if ui
+                    .add_enabled(
+                        config.component_disk_quota_gb > 0,
+                        egui::Button::new(managed.storage_free_space),
+                    )
+                    .clicked()
+                {
+                    storage::start_eviction(config.component_disk_quota_gb);
+                }
+            });
+        });
+        if let Some(eviction) = storage::last_eviction() {
+            ui.label(eviction_summary(&eviction, managed));
+        }
+        ui.add_space(4.0);
+        match &report {
+            None => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(managed.storage_loading);
+                });
+            }
+            Some(Err(error)) => {
+                ui.label(egui::RichText::new(error).color(theme.danger_text()));
+            }
+            Some(Ok(report)) => render_report(ui, report, managed, &theme),
+        }
+    });
+    changed
+}
+
+fn render_report(
+    ui: &mut egui::Ui,
+    report: &StorageReport,
+    managed: &ManagedToolsLocaleText,
+    theme: &AppTheme,
+) {
+    ui.label(
+        egui::RichText::new(
+            managed
+                .storage_total_fmt
+                .replace("{size}", &format_size(report.total_bytes)),
+        )
+        .strong(),
+    );
+    egui::CollapsingHeader::new(managed.storage_by_feature)
+        .id_salt("downloaded_tools_storage_features")
+        .show(ui, |ui| {
+            for (feature, bytes) in &report.features {
+                size_row(ui, &feature.replace('_', " "), *bytes, None);
+            }
+        });
+    egui::CollapsingHeader::new(managed.storage_by_component)
+        .id_salt("downloaded_tools_storage_components")
+        .show(ui, |ui| {
+            for component in &report.components {
+                let detail = if component.in_use {
+                    Some(egui::RichText::new(managed.storage_in_use).color(theme.success()))
+                } else {
+                    component.last_used.and_then(last_used_date).map(|date| {
+                        egui::RichText::new(managed.storage_last_used_fmt.replace("{date}", &date))
+                            .color(egui::Color32::GRAY)
+                    })
+                };
+                size_row(ui, &component.display_name, component.bytes, detail);
+            }
+        });
+}
+
+fn size_row(ui: &mut egui::Ui, name: &str, bytes: u64, detail: Option<egui::RichText>) {
+    ui.horizontal(|ui| {
+        ui.label(name);
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.label(format_size(bytes));
+            if let Some(detail) = detail {
+                ui.label(detail);
+            }
+        });
+    });
+}
+
+fn eviction_summary(report: &EvictionReport, managed: &ManagedToolsLocaleText) -> String {
+    let mut summary = if report.evicted.is_empty() {
+        managed.storage_nothing_evicted.to_string()
+    } else {
+        let freed = report.evicted.iter().map(|(_, bytes)| bytes).sum::<u64>();
+        let names = report
+            .evicted
+            .iter()
+            .map(|(id, _)| display_name(id))
+            .collect::<Vec<_>>()
+            .join(", ");
+        managed
+            .storage_evicted_fmt
+            .replace("{count}", &report.evicted.len().to_string())
+            .replace("{size}", &format_size(freed))
+            .replace("{names}", &names)
+    };
+    if report.bytes_after > report.quota_bytes {
+        summary.push(' ');
+        summary.push_str(&managed.storage_still_over_fmt.replace(
+            "{size}",
+            &format_size(report.bytes_after - report.quota_bytes),
+        ));
+    }
+    summary
+}
+
+fn display_name(id: &str) -> String {
+    crate::component_registry::embedded_catalog()
+        .components
+        .iter()
+        .find(|component| component.id == id)
+        .map_or_else(
+            || id.to_string(),
+            |component| component.display_name.clone(),
+        )
+}
+
+fn last_used_date(seconds: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(seconds, 0).map(|time| {
+        time.with_timezone(&chrono::Local)
+            .format("%Y-%m-%d")
+            .to_string()
+    })
+}
+
+/// Returns the cached report, refreshing it in the background when it is
+/// stale or an eviction has just finished.
+fn cached_report(ctx: &egui::Context, evicting: bool) -> Option<Result<StorageReport, String>> {
+    let mut cache = REPORT.lock().unwrap_or_else(|value| value.into_inner());
+    let eviction_finished = cache.evicting && !evicting;
+    cache.evicting = evicting;
+    let stale = cache
+        .updated_at
+        .is_none_or(|updated_at| updated_at.elapsed() >= REPORT_TTL);
+    if (stale || eviction_finished) && !cache.loading {
+        cache.loading = true;
+        if eviction_finished {
+            clear_downloaded_tools_caches();
+        }
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let report = storage::report().map_err(|error| format!("{error:#}"));
+            let mut cache = REPORT.lock().unwrap_or_else(|value| value.into_inner());
+            cache.report = Some(report);
+            cache.updated_at = Some(Instant::now());
+            cache.loading = false;
+            drop(cache);
+            ctx.request_repaint();
+        });
+    }
+    if evicting {
+        // Poll until the eviction thread finishes.
+        ctx.request_repaint_after(Duration::from_millis(500));
+    }
+    cache.report.clone()
+}
@@ -175,7 +175,9 @@ pub fn render_global_settings(
 
     // === TOOLS MODAL ===
     let ctx = ui.ctx().clone();
-    render_downloaded_tools_modal(&ctx, ui, show_tools_modal, download_manager, text);
+    if render_downloaded_tools_modal(&ctx, ui, show_tools_modal, config, download_manager, text) {
+        changed = true;
+    }
 
     // === TTS SETTINGS MODAL ===
     if render_tts_settings_modal(ui, config, text, show_tts_modal) {
@@ -212,7 +214,7 @@ pub fn render_global_settings(
                 );
             });
             ui.add_space(6.0);
-            render_update_section_content(ui, updater, update_status, text);
+            changed |= render_update_section_content(ui, config, updater, update_status, text);
         });
 
     ui.add_space(10.0);
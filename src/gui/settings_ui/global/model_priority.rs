@@ -10,6 +10,8 @@ use eframe::egui::{self, Color32, CornerRadius, Margin};
 
 #[path = "model_priority/feed.rs"]
 mod feed;
+#[path = "model_priority/spend.rs"]
+mod spend;
 #[path = "model_priority/step_keys.rs"]
 mod step_keys;
 
@@ -33,6 +35,7 @@ pub fn render_model_priority_body(
     text: &LocaleText,
 ) -> bool {
     let mut changed = false;
+    spend::render_monthly_spend(ui, text);
     let ui_language = config.ui_language.clone();
     let mut image_adaptive = config.adaptive_model_priority.image_to_text;
     let mut text_adaptive = config.adaptive_model_priority.text_to_text;
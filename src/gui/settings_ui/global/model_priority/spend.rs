@@ -0,0 +1,18 @@
+use crate::gui::locale::LocaleText;
+use eframe::egui;
+
+/// One line with this month's estimated spend, above the chains it comes from.
+pub(super) fn render_monthly_spend(ui: &mut egui::Ui, text: &LocaleText) {
+    let spend = crate::usage_stats::spend::current();
+    let line = text
+        .model_catalog
+        .model_priority_monthly_spend_fmt
+        .replace("{total}", &format!("{:.2}", spend.total_usd))
+        .replace("{requests}", &spend.requests.to_string())
+        .replace(
+            "{unpriced}",
+            &(spend.requests - spend.priced_requests).to_string(),
+        );
+    ui.label(egui::RichText::new(line).color(ui.visuals().weak_text_color()));
+    ui.add_space(6.0);
+}
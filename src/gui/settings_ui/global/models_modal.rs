@@ -15,6 +15,10 @@ use super::custom_models::render_custom_models_body;
 use super::model_priority::render_model_priority_body;
 use super::usage_stats::{render_usage_body, usage_descriptions, usage_dialog_layout};
 
+mod response_cache;
+
+use response_cache::render_response_cache;
+
 /// Header title size; the tab strip and description are baseline-matched to it.
 const TITLE_FONT_SIZE: f32 = crate::gui::widgets::DIALOG_TITLE_SIZE;
 
@@ -130,6 +134,9 @@ pub fn render_models_modal(
                                 if render_model_priority_body(ui, config, text) {
                                     changed = true;
                                 }
+                                if render_response_cache(ui, config, text) {
+                                    changed = true;
+                                }
                             });
                     }
                     ModelsTab::Usage => render_usage_body(
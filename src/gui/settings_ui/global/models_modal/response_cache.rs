@@ -0,0 +1,47 @@
+use crate::config::Config;
+use crate::config::types::ResponseCacheSettings;
+use crate::gui::locale::LocaleText;
+use eframe::egui;
+
+/// Opt-in reuse of earlier block results, below the chains it bypasses.
+pub(super) fn render_response_cache(
+    ui: &mut egui::Ui,
+    config: &mut Config,
+    text: &LocaleText,
+) -> bool {
+    let catalog = &text.model_catalog;
+    let settings = &mut config.response_cache;
+    let mut changed = false;
+    ui.add_space(8.0);
+    ui.separator();
+    ui.horizontal(|ui| {
+        changed |= ui
+            .checkbox(&mut settings.enabled, catalog.response_cache_toggle)
+            .on_hover_text(catalog.response_cache_hint)
+            .changed();
+        ui.add_enabled_ui(settings.enabled, |ui| {
+            ui.label(catalog.response_cache_ttl_label);
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut settings.ttl_hours)
+                        .range(1..=ResponseCacheSettings::MAX_TTL_HOURS)
+                        .suffix(catalog.response_cache_ttl_suffix),
+                )
+                .changed();
+            ui.label(catalog.response_cache_size_label);
+            changed |= ui
+                .add(
+                    egui::DragValue::new(&mut settings.max_size_mb)
+                        .range(1..=ResponseCacheSettings::MAX_SIZE_MB)
+                        .suffix(" MB"),
+                )
+                .changed();
+        });
+        if ui.button(catalog.response_cache_clear_btn).clicked()
+            && let Err(error) = crate::response_cache::clear()
+        {
+            crate::log_info!("[response-cache] could not clear: {error}");
+        }
+    });
+    changed
+}
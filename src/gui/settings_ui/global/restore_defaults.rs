@@ -367,11 +367,13 @@ fn apply_selected_config_defaults_from(
         config.favorite_bubble_position = defaults.favorite_bubble_position;
         config.favorites_keep_open = defaults.favorites_keep_open;
         config.favorite_bubble_size = defaults.favorite_bubble_size;
+        config.updates = defaults.updates.clone();
     }
 
     if selection.model_settings {
         config.model_priority_chains = defaults.model_priority_chains.clone();
         config.adaptive_model_priority = defaults.adaptive_model_priority.clone();
+        config.response_cache = defaults.response_cache.clone();
         config.ollama_vision_model = defaults.ollama_vision_model.clone();
         config.ollama_text_model = defaults.ollama_text_model.clone();
     }
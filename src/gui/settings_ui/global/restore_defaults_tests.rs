@@ -1,6 +1,8 @@
 use super::{apply_selected_config_defaults_from, render_restore_defaults_modal};
-use crate::config::types::{PendingPresetModelUpdate, PresetProfile};
-use crate::config::{Config, Hotkey, Preset, RestoreDefaultsSelection, ThemeMode};
+use crate::config::types::{ApiKeyRotation, PendingPresetModelUpdate, PooledApiKey, PresetProfile};
+use crate::config::{
+    Config, Hotkey, Preset, RestoreDefaultsSelection, ThemeMode, UpdateChannel, UpdatePreferences,
+};
 use crate::gui::locale::LocaleText;
 use auto_launch::AutoLaunch;
 use eframe::egui;
@@ -11,6 +13,8 @@ const ALWAYS_KEPT: &[&str] = &[
     "gemini_api_key",
     "openrouter_api_key",
     "nvidia_api_key",
+    "api_key_pool",
+    "api_key_rotation",
     "ui_language",
     "use_groq",
     "use_gemini",
@@ -19,6 +23,8 @@ const ALWAYS_KEPT: &[&str] = &[
     "use_ollama",
     "ollama_base_url",
     "custom_models",
+    "component_mirrors",
+    "component_disk_quota_gb",
     "restore_defaults_selection",
     "pending_preset_model_update",
     "screen_record_hotkeys",
@@ -48,10 +54,12 @@ const APP: &[&str] = &[
     "favorite_bubble_position",
     "favorites_keep_open",
     "favorite_bubble_size",
+    "updates",
 ];
 const MODELS: &[&str] = &[
     "model_priority_chains",
     "adaptive_model_priority",
+    "response_cache",
     "ollama_vision_model",
     "ollama_text_model",
 ];
@@ -453,6 +461,13 @@ fn non_default_config() -> Config {
         api_key: "groq-secret".to_string(),
         gemini_api_key: "gemini-secret".to_string(),
         openrouter_api_key: "openrouter-secret".to_string(),
+        api_key_pool: vec![PooledApiKey {
+            id: "team-id".to_string(),
+            provider: "groq".to_string(),
+            label: "team".to_string(),
+            key: "groq-team-secret".to_string(),
+        }],
+        api_key_rotation: ApiKeyRotation::LeastRecentlyLimited,
         presets: active_profile.presets.clone(),
         active_preset_idx: active_profile.active_preset_idx,
         preset_profiles: vec![first_profile, active_profile],
@@ -484,6 +499,12 @@ fn non_default_config() -> Config {
         ollama_vision_model: "vision-local".to_string(),
         ollama_text_model: "text-local".to_string(),
         custom_models: vec![Default::default()],
+        component_mirrors: vec!["https://mirror.example/sgt".to_string()],
+        component_disk_quota_gb: 40,
+        updates: UpdatePreferences {
+            channel: UpdateChannel::Beta,
+            deferred_version: Some("9.9.9".to_string()),
+        },
         realtime_translation_model: "translation-model".to_string(),
         realtime_transcription_model: "transcription-model".to_string(),
         realtime_transcription_language: "fr".to_string(),
@@ -512,6 +533,7 @@ fn non_default_config() -> Config {
     config.model_priority_chains.text_to_text.clear();
     config.adaptive_model_priority.image_to_text = false;
     config.adaptive_model_priority.text_to_text = false;
+    config.response_cache.enabled = true;
     config
         .adaptive_model_priority
         .image_to_text_overrides
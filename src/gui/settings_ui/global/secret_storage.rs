@@ -0,0 +1,118 @@
+//! Where API keys are sealed, and the passphrase prompt for a locked store.
+//!
+//! Switching backends reseals the stored keys at once; the config itself
+//! only ever holds handles, so it needs no resave for that.
+
+use crate::config::Config;
+use crate::config::secrets::{self, SecretBackend};
+use crate::gui::locale::LocaleText;
+use eframe::egui;
+
+const PASSPHRASE_FIELD_WIDTH: f32 = 180.0;
+
+/// Returns true when unlocking filled keys into `config`.
+pub(super) fn render_secret_storage(
+    ui: &mut egui::Ui,
+    config: &mut Config,
+    text: &LocaleText,
+) -> bool {
+    let labels = &text.global_settings;
+    let passphrase_id = egui::Id::new("settings_secret_storage_passphrase");
+    let error_id = egui::Id::new("settings_secret_storage_error");
+    let mut passphrase: String = ui
+        .ctx()
+        .data(|data| data.get_temp(passphrase_id).unwrap_or_default());
+    let mut error: Option<String> = ui.ctx().data(|data| data.get_temp(error_id));
+    let current = secrets::backend();
+    let locked = secrets::is_locked();
+    let mut wanted = ui
+        .ctx()
+        .data(|data| data.get_temp(passphrase_id.with("wanted")))
+        .unwrap_or(current);
+    let mut changed = false;
+
+    ui.add_space(4.0);
+    // A locked store cannot be resealed, so the choice waits for the unlock.
+    if locked {
+        wanted = current;
+    }
+    ui.add_enabled_ui(!locked, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(labels.secret_storage_label);
+            ui.selectable_value(
+                &mut wanted,
+                SecretBackend::OsKeystore,
+                labels.secret_storage_os,
+            );
+            ui.selectable_value(
+                &mut wanted,
+                SecretBackend::Passphrase,
+                labels.secret_storage_passphrase,
+            );
+        });
+    });
+    if wanted == SecretBackend::OsKeystore && current == SecretBackend::Passphrase {
+        error = secrets::use_os_keystore().err();
+    }
+
+    let action = if locked {
+        ui.label(
+            egui::RichText::new(labels.secret_storage_locked)
+                .size(11.0)
+                .color(ui.visuals().warn_fg_color),
+        );
+        Some(labels.secret_storage_unlock)
+    } else if wanted == SecretBackend::Passphrase && current == SecretBackend::OsKeystore {
+        Some(labels.secret_storage_apply)
+    } else {
+        None
+    };
+    if let Some(action) = action {
+        ui.horizontal(|ui| {
+            let field = ui.add(
+                egui::TextEdit::singleline(&mut passphrase)
+                    .id(passphrase_id.with("field"))
+                    .password(true)
+                    .hint_text(labels.secret_storage_passphrase_hint)
+                    .desired_width(PASSPHRASE_FIELD_WIDTH),
+            );
+            let submitted =
+                field.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter));
+            if (ui.button(action).clicked() || submitted) && !passphrase.is_empty() {
+                let result = if locked {
+                    secrets::unlock(&passphrase, config)
+                } else {
+                    secrets::use_passphrase(&passphrase)
+                };
+                error = result.err();
+                if error.is_none() {
+                    changed = locked;
+                    passphrase.clear();
+                }
+            }
+        });
+    }
+    if let Some(message) = &error {
+        ui.label(
+            egui::RichText::new(message)
+                .size(11.0)
+                .color(ui.visuals().error_fg_color),
+        );
+    }
+
+    // A failed switch falls back to showing the backend actually in use.
+    let wanted = if error.is_some() && !locked {
+        secrets::backend()
+    } else {
+        wanted
+    };
+    ui.ctx().data_mut(|data| {
+        data.insert_temp(passphrase_id, passphrase);
+        data.insert_temp(passphrase_id.with("wanted"), wanted);
+        match error {
+            Some(message) => data.insert_temp(error_id, message),
+            None => data.remove::<String>(error_id),
+        }
+    });
+    changed
+}
@@ -1,3 +1,4 @@
+use crate::config::{Config, UpdateChannel};
 use crate::gui::locale::LocaleText;
 use crate::updater::{UpdateStatus, Updater};
 use eframe::egui;
@@ -6,11 +7,40 @@ use std::os::windows::process::CommandExt;
 
 pub fn render_update_section_content(
     ui: &mut egui::Ui,
+    config: &mut Config,
     updater: &Option<Updater>,
     status: &UpdateStatus,
     text: &LocaleText,
-) {
+) -> bool {
     let theme = crate::gui::theme::AppTheme::from_ui(ui);
+    let mut changed = false;
+    let installing = matches!(
+        status,
+        UpdateStatus::Downloading | UpdateStatus::UpdatedAndRestartRequired(_)
+    );
+    ui.add_enabled_ui(!installing, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(text.desktop_settings.update_channel_label);
+            let previous = config.updates.channel;
+            ui.selectable_value(
+                &mut config.updates.channel,
+                UpdateChannel::Stable,
+                text.desktop_settings.update_channel_stable,
+            );
+            ui.selectable_value(
+                &mut config.updates.channel,
+                UpdateChannel::Beta,
+                text.desktop_settings.update_channel_beta,
+            );
+            if config.updates.channel != previous {
+                changed = true;
+                if let Some(u) = updater {
+                    u.check_for_updates(&config.updates);
+                }
+            }
+        });
+    });
+    ui.add_space(4.0);
     match status {
         UpdateStatus::Idle => {
             ui.horizontal(|ui| {
@@ -25,7 +55,7 @@ pub fn render_update_section_content(
                     .clicked()
                     && let Some(u) = updater
                 {
-                    u.check_for_updates();
+                    u.check_for_updates(&config.updates);
                 }
             });
         }
@@ -44,7 +74,7 @@ pub fn render_update_section_content(
                 if ui.button(text.desktop_settings.check_again_btn).clicked()
                     && let Some(u) = updater
                 {
-                    u.check_for_updates();
+                    u.check_for_updates(&config.updates);
                 }
             });
         }
@@ -62,13 +92,40 @@ pub fn render_update_section_content(
                     ui.label(body);
                 });
             ui.add_space(5.0);
-            if ui
-                .button(egui::RichText::new(text.desktop_settings.download_update_btn).strong())
-                .clicked()
-                && let Some(u) = updater
-            {
-                u.perform_update();
-            }
+            ui.horizontal(|ui| {
+                if ui
+                    .button(egui::RichText::new(text.desktop_settings.download_update_btn).strong())
+                    .clicked()
+                    && let Some(u) = updater
+                {
+                    u.perform_update();
+                }
+                if ui.button(text.desktop_settings.defer_update_btn).clicked()
+                    && let Some(u) = updater
+                {
+                    config.updates.deferred_version = Some(version.clone());
+                    changed = true;
+                    u.defer(version);
+                }
+            });
+        }
+        UpdateStatus::Deferred(version) => {
+            ui.horizontal(|ui| {
+                ui.label(
+                    text.desktop_settings
+                        .update_deferred_fmt
+                        .replace("{version}", version),
+                );
+                if ui
+                    .button(text.desktop_settings.show_deferred_update_btn)
+                    .clicked()
+                    && let Some(u) = updater
+                {
+                    config.updates.deferred_version = None;
+                    changed = true;
+                    u.check_for_updates(&config.updates);
+                }
+            });
         }
         UpdateStatus::Downloading => {
             ui.horizontal(|ui| {
@@ -87,7 +144,7 @@ pub fn render_update_section_content(
             if ui.button(text.desktop_settings.retry_btn).clicked()
                 && let Some(u) = updater
             {
-                u.check_for_updates();
+                u.check_for_updates(&config.updates);
             }
         }
         UpdateStatus::UpdatedAndRestartRequired(staged) => {
@@ -103,7 +160,7 @@ pub fn render_update_section_content(
                     .and_then(|exe_path| staged.verified_path_beside(&exe_path));
                 let Ok(path) = path else {
                     eprintln!("Refused to launch an unverified staged update");
-                    return;
+                    return changed;
                 };
                 println!("Attempting to spawn with delay: {:?}", path);
 
@@ -143,4 +200,5 @@ pub fn render_update_section_content(
             }
         }
     }
+    changed
 }
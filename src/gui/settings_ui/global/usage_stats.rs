@@ -9,6 +9,8 @@ use crate::usage_stats::{
 use eframe::egui;
 use std::collections::BTreeMap;
 
+#[path = "usage_stats_health.rs"]
+mod usage_stats_health;
 #[path = "usage_stats_table.rs"]
 mod usage_stats_table;
 
@@ -253,6 +255,8 @@ fn render_provider_section(
                 );
             }
 
+            usage_stats_health::render_provider_health(ui, theme, text, section_key, rows, accent);
+
             for model in rows {
                 render_endpoint_row(
                     ui,
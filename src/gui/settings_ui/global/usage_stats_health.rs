@@ -0,0 +1,136 @@
+//! Health strips under a provider's section: one per pooled API key, then one
+//! per benched model with a button that lifts its cooldown.
+
+use super::usage_stats_table::render_status_strip;
+use super::{ENDPOINT_ROW_HEIGHT, ENDPOINT_STATUS_FONT_SIZE};
+use crate::api::key_pool::{self, KeyUsage};
+use crate::gui::locale::LocaleText;
+use crate::gui::theme::{AppTheme, blend};
+use crate::model_config::ModelConfig;
+use crate::retry_model_chain::{
+    ModelCooldown, ModelCooldownKind, active_model_cooldowns, clear_model_cooldown,
+};
+use eframe::egui;
+use std::time::Duration;
+
+/// Nothing is drawn for a provider with only its primary key and no model in
+/// cooldown.
+pub(super) fn render_provider_health(
+    ui: &mut egui::Ui,
+    theme: &AppTheme,
+    text: &LocaleText,
+    section_key: &str,
+    rows: &[&ModelConfig],
+    accent: egui::Color32,
+) {
+    for key in key_pool::usage(section_key) {
+        let color = if key.cooling.is_some() {
+            theme.warning()
+        } else {
+            accent
+        };
+        render_status_strip(
+            ui,
+            &key.label,
+            key_status(&key, text),
+            color,
+            theme,
+            ENDPOINT_ROW_HEIGHT,
+            ENDPOINT_STATUS_FONT_SIZE,
+        );
+    }
+    let cooldowns = active_model_cooldowns();
+    for model in rows {
+        if let Some(cooldown) = cooldowns.iter().find(|entry| entry.model_id == model.id) {
+            render_cooldown_row(ui, theme, text, model, cooldown);
+        }
+    }
+}
+
+fn key_status(key: &KeyUsage, text: &LocaleText) -> String {
+    let labels = &text.desktop_settings;
+    let mut status = format!("{} {}", key.requests, labels.usage_key_requests);
+    if key.rate_limits > 0 {
+        status.push_str(&format!(
+            " · {} {}",
+            key.rate_limits, labels.usage_key_rate_limited
+        ));
+    }
+    if let Some(cooling) = key.cooling {
+        status.push_str(&format!(
+            " · {} {}",
+            labels.usage_key_cooling,
+            remaining_text(cooling)
+        ));
+    }
+    status
+}
+
+fn render_cooldown_row(
+    ui: &mut egui::Ui,
+    theme: &AppTheme,
+    text: &LocaleText,
+    model: &ModelConfig,
+    cooldown: &ModelCooldown,
+) {
+    let color = theme.warning();
+    egui::Frame::new()
+        .fill(blend(theme.dialog_surface(), color, 0.08))
+        .corner_radius(egui::CornerRadius::same(4))
+        .inner_margin(egui::Margin::symmetric(
+            crate::gui::theme::space::TIGHT,
+            crate::gui::theme::space::HAIR,
+        ))
+        .show(ui, |ui| {
+            ui.set_width(ui.available_width());
+            ui.horizontal(|ui| {
+                ui.set_min_height(ENDPOINT_ROW_HEIGHT);
+                ui.add(
+                    egui::Label::new(
+                        egui::RichText::new(&model.full_name)
+                            .size(9.5)
+                            .strong()
+                            .color(color),
+                    )
+                    .truncate(),
+                );
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui
+                        .small_button(text.desktop_settings.usage_cooldown_clear)
+                        .clicked()
+                    {
+                        clear_model_cooldown(&model.id);
+                    }
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} · {}",
+                            kind_label(cooldown.kind, text),
+                            remaining_text(cooldown.remaining)
+                        ))
+                        .size(ENDPOINT_STATUS_FONT_SIZE)
+                        .color(color),
+                    );
+                });
+            });
+        });
+}
+
+fn kind_label(kind: ModelCooldownKind, text: &LocaleText) -> &'static str {
+    let labels = &text.desktop_settings;
+    match kind {
+        ModelCooldownKind::RateLimit => labels.usage_cooldown_rate_limit,
+        ModelCooldownKind::Timeout => labels.usage_cooldown_timeout,
+        ModelCooldownKind::Unavailable => labels.usage_cooldown_unavailable,
+        ModelCooldownKind::Billing => labels.usage_cooldown_billing,
+    }
+}
+
+/// `42s`, `4m 12s`, or `5h 3m`.
+fn remaining_text(remaining: Duration) -> String {
+    let seconds = remaining.as_secs().max(1);
+    match (seconds / 3600, seconds % 3600 / 60, seconds % 60) {
+        (0, 0, seconds) => format!("{seconds}s"),
+        (0, minutes, seconds) => format!("{minutes}m {seconds}s"),
+        (hours, minutes, _) => format!("{hours}h {minutes}m"),
+    }
+}
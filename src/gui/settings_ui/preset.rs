@@ -10,10 +10,12 @@ use egui_snarl::Snarl;
 mod controller_description;
 mod graph_helpers;
 mod hotkeys;
+mod routing;
 
 use controller_description::render_controller_mode_description;
 use graph_helpers::{create_default_block_for_type, sync_graph_type};
 use hotkeys::render_hotkeys;
+use routing::render_routing_objective;
 
 #[expect(
     clippy::too_many_arguments,
@@ -495,6 +497,18 @@ pub fn render_preset_editor(
         }
     }
 
+    // Routing belongs with the processing chain, which the controller UI hides.
+    if !preset.show_controller_ui
+        && render_routing_objective(
+            ui,
+            &mut preset.routing_objective,
+            &mut preset.routing_quality_floor,
+            text,
+        )
+    {
+        changed = true;
+    }
+
     ui.add_space(10.0);
 
     // Hotkeys - always visible, even when controller UI is enabled
@@ -0,0 +1,58 @@
+use crate::config::types::{MAX_QUALITY_TIER, RoutingObjective, RoutingWeights};
+use crate::gui::locale::LocaleText;
+use eframe::egui;
+
+pub(super) fn render_routing_objective(
+    ui: &mut egui::Ui,
+    objective: &mut RoutingObjective,
+    quality_floor: &mut u8,
+    text: &LocaleText,
+) -> bool {
+    let editor = &text.preset_editor;
+    let previous = (*objective, *quality_floor);
+
+    ui.horizontal(|ui| {
+        ui.label(editor.routing_objective_label)
+            .on_hover_text(editor.routing_objective_hint);
+        for (choice, label) in [
+            (RoutingObjective::Priority, editor.routing_priority),
+            (RoutingObjective::Cheapest, editor.routing_cheapest),
+            (RoutingObjective::Fastest, editor.routing_fastest),
+            (RoutingObjective::BestQuality, editor.routing_best_quality),
+        ] {
+            ui.selectable_value(objective, choice, label);
+        }
+        // Re-selecting the mix keeps the weights already chosen.
+        let weighted = matches!(objective, RoutingObjective::Weighted(_));
+        if ui
+            .selectable_label(weighted, editor.routing_weighted)
+            .clicked()
+            && !weighted
+        {
+            *objective = RoutingObjective::Weighted(RoutingWeights::default());
+        }
+    });
+
+    if let RoutingObjective::Weighted(weights) = objective {
+        ui.horizontal(|ui| {
+            for (weight, label) in [
+                (&mut weights.cost, editor.routing_weight_cost),
+                (&mut weights.speed, editor.routing_weight_speed),
+                (&mut weights.quality, editor.routing_weight_quality),
+            ] {
+                ui.label(label);
+                ui.add(egui::DragValue::new(weight).range(0..=RoutingWeights::MAX));
+            }
+        });
+    }
+
+    if *objective == RoutingObjective::Cheapest {
+        ui.horizontal(|ui| {
+            ui.label(editor.routing_quality_floor)
+                .on_hover_text(editor.routing_quality_floor_hint);
+            ui.add(egui::DragValue::new(quality_floor).range(1..=MAX_QUALITY_TIER));
+        });
+    }
+
+    (*objective, *quality_floor) != previous
+}
@@ -30,6 +30,7 @@ pub const WM_RELOAD_HOTKEYS: u32 = WM_USER + 101;
 pub const WM_UNREGISTER_HOTKEYS: u32 = WM_USER + 103;
 pub const WM_REGISTER_HOTKEYS: u32 = WM_USER + 104;
 pub const COMPUTER_CONTROL_HOTKEY_ID: i32 = 9700;
+pub const ROUTINE_HOTKEY_ID: i32 = 9500;
 pub const SCREEN_TRANSLATE_HOTKEY_ID: i32 = 9600;
 pub const TRANSLATION_GUMMY_HOTKEY_ID: i32 = 9800;
 
@@ -38,6 +39,7 @@ enum HotkeyRegistrationGroup {
     Preset(usize),
     ScreenTranslate,
     ComputerControl,
+    Routine,
     ScreenRecord,
     TranslationGummy,
 }
@@ -48,6 +50,7 @@ impl HotkeyRegistrationGroup {
             Self::Preset(index) => format!("preset[{index}]"),
             Self::ScreenTranslate => "screen_translate".to_string(),
             Self::ComputerControl => "computer_control".to_string(),
+            Self::Routine => "routine".to_string(),
             Self::ScreenRecord => "screen_record".to_string(),
             Self::TranslationGummy => "translation_gummy".to_string(),
         }
@@ -158,6 +161,24 @@ pub fn register_all_hotkeys(hwnd: HWND) {
         );
     }
 
+    // Saved Computer Control routines declare their own hotkeys (IDs: 9500-9599).
+    // Mouse buttons are already left out, so each index is a bound routine.
+    for (idx, hotkey) in crate::overlay::computer_control::routine_hotkeys()
+        .iter()
+        .enumerate()
+    {
+        if idx >= 100 {
+            break;
+        }
+        register_and_track(
+            hwnd,
+            HotkeyRegistrationGroup::Routine,
+            ROUTINE_HOTKEY_ID + idx as i32,
+            hotkey,
+            &mut registered_ids,
+        );
+    }
+
     for (idx, hotkey) in app.config.screen_translate.hotkeys.iter().enumerate() {
         if idx >= 100 || [0x04, 0x05, 0x06].contains(&hotkey.code) {
             continue;
@@ -488,6 +509,7 @@ mod tests {
             (HotkeyRegistrationGroup::Preset(3), "preset[3]"),
             (HotkeyRegistrationGroup::ScreenTranslate, "screen_translate"),
             (HotkeyRegistrationGroup::ComputerControl, "computer_control"),
+            (HotkeyRegistrationGroup::Routine, "routine"),
             (HotkeyRegistrationGroup::ScreenRecord, "screen_record"),
             (
                 HotkeyRegistrationGroup::TranslationGummy,
@@ -45,6 +45,13 @@ pub unsafe extern "system" fn hotkey_proc(
 
 /// Handle a hotkey message.
 fn handle_hotkey(id: i32) {
+    if (crate::hotkey::ROUTINE_HOTKEY_ID..crate::hotkey::SCREEN_TRANSLATE_HOTKEY_ID).contains(&id) {
+        overlay::computer_control::run_routine_hotkey(
+            (id - crate::hotkey::ROUTINE_HOTKEY_ID) as usize,
+        );
+        return;
+    }
+
     if (crate::hotkey::SCREEN_TRANSLATE_HOTKEY_ID..crate::hotkey::COMPUTER_CONTROL_HOTKEY_ID)
         .contains(&id)
     {
@@ -57,7 +64,7 @@ fn handle_hotkey(id: i32) {
     {
         if overlay::computer_control::is_active() {
             overlay::computer_control::stop_overlay();
-        } else {
+        } else if !overlay::computer_control::cancel_trigger_run() {
             overlay::computer_control::show_overlay();
         }
         return;
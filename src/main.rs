@@ -16,6 +16,7 @@ mod config;
 mod creation_feature_availability;
 mod crypto;
 mod debug_log;
+mod golden_eval;
 pub mod gui;
 mod history;
 mod hotkey;
@@ -27,12 +28,14 @@ mod model_feed;
 mod overlay;
 mod paths;
 mod registry_integration;
+mod response_cache;
 mod retry_model_chain;
 mod runtime_support;
 mod screen_capture;
 #[cfg(test)]
 mod source_contract_tests;
 mod startup_launch;
+mod text_metrics;
 mod unpack_dlls;
 mod updater;
 mod usage_stats;
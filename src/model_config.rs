@@ -48,6 +48,26 @@ pub struct LiveEndpointProfile {
     pub protocol: Option<&'static str>,
 }
 
+/// Published list price of an endpoint in USD per million tokens, by modality.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TokenPricing {
+    pub input_text: f64,
+    pub input_audio: f64,
+    pub input_image: f64,
+    pub output_text: f64,
+    pub output_audio: f64,
+}
+
+impl TokenPricing {
+    /// List price in USD of one text request with `input_image` image tokens.
+    pub fn text_request_usd(&self, input_text: u64, input_image: u64, output_text: u64) -> f64 {
+        (input_text as f64 * self.input_text
+            + input_image as f64 * self.input_image
+            + output_text as f64 * self.output_text)
+            / 1_000_000.0
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ModelConfig {
     pub id: String,
@@ -289,6 +309,17 @@ pub fn live_endpoint_profile(api_model: &str) -> Option<LiveEndpointProfile> {
     generated_live_endpoint_profile(api_model)
 }
 
+/// Catalog pricing for an endpoint; `None` when the catalog lists no price.
+pub fn endpoint_pricing(api_model: &str) -> Option<TokenPricing> {
+    generated_endpoint_pricing(api_model)
+}
+
+/// Catalog price of a routable model: its profile price, else the price of the
+/// Live endpoint it names; `None` when the catalog lists neither.
+pub fn model_pricing(provider: &str, api_model: &str) -> Option<TokenPricing> {
+    generated_model_pricing(provider, api_model).or_else(|| generated_endpoint_pricing(api_model))
+}
+
 /// Whether an endpoint has been withdrawn from the product.
 ///
 /// Withdrawal is a curated judgement and outranks every live signal. The
@@ -152,6 +152,30 @@ fn live_thinking_schema_follows_exact_endpoint() {
     );
 }
 
+#[test]
+fn live_endpoints_carry_catalog_pricing() {
+    for model in [GEMINI_LIVE_API_MODEL_2_5, GEMINI_LIVE_API_MODEL_3_1] {
+        let pricing = endpoint_pricing(model).expect("live endpoint pricing");
+        assert!(pricing.input_text > 0.0 && pricing.output_audio > pricing.output_text);
+    }
+    assert_eq!(endpoint_pricing("not-a-catalog-model"), None);
+}
+
+#[test]
+fn free_profiles_are_priced_at_zero_and_live_endpoints_fall_back() {
+    let free = get_all_models()
+        .iter()
+        .find(|model| model.provider == "openrouter" && model.full_name.ends_with(":free"))
+        .expect("a free OpenRouter model");
+    let pricing = model_pricing(&free.provider, &free.full_name).expect("free profile pricing");
+    assert_eq!(pricing.text_request_usd(1_000, 770, 500), 0.0);
+    assert_eq!(
+        model_pricing("gemini-live", GEMINI_LIVE_API_MODEL_3_1),
+        endpoint_pricing(GEMINI_LIVE_API_MODEL_3_1)
+    );
+    assert_eq!(model_pricing("groq", "not-a-catalog-model"), None);
+}
+
 #[test]
 fn live_output_limits_match_shared_parity_fixture() {
     let fixture: serde_json::Value = serde_json::from_str(include_str!(concat!(
@@ -182,8 +182,37 @@ pub(super) fn save_tool(id: &str, path: Option<&str>, overwrite: bool) -> Value
             "effect_may_have_occurred": true,
         });
     }
-    match std::fs::write(&target, text) {
-        Ok(()) => verified_save_result(&target, &artifact),
+    // An overwritten target is journaled so the edit can be previewed and undone.
+    let replaced = match target.is_file().then(|| snapshot_target(&target)) {
+        None => None,
+        Some(Ok(replaced)) => Some(replaced),
+        Some(Err(e)) => {
+            return json!({
+                "ok": false,
+                "code": "ERR_ARTIFACT_SNAPSHOT",
+                "error": format!("could not snapshot the existing target for undo: {e}"),
+                "path": path,
+                "effect_may_have_occurred": false,
+                "executed": false,
+            });
+        }
+    };
+    match std::fs::write(&target, &text) {
+        Ok(()) => {
+            let mut result = verified_save_result(&target, &artifact);
+            if let (Some((pre_image, old)), Some(true)) =
+                (replaced, result.get("ok").and_then(Value::as_bool))
+            {
+                result["edit"] = super::edit_journal::record(
+                    pre_image,
+                    "save_artifact",
+                    &target,
+                    &old,
+                    text.as_bytes(),
+                );
+            }
+            result
+        }
         Err(e) => json!({
             "ok": false,
             "error": format!("write failed: {e}"),
@@ -193,6 +222,11 @@ pub(super) fn save_tool(id: &str, path: Option<&str>, overwrite: bool) -> Value
     }
 }
 
+fn snapshot_target(target: &Path) -> std::io::Result<(super::edit_journal::PreImage, Vec<u8>)> {
+    let old = std::fs::read(target)?;
+    Ok((super::edit_journal::PreImage::capture(&old)?, old))
+}
+
 fn verified_save_result(target: &Path, artifact: &TextArtifact) -> Value {
     let bytes = match std::fs::read(target) {
         Ok(bytes) => bytes,
@@ -0,0 +1,264 @@
+//! Session journal of agent file edits, for preview and undo.
+//!
+//! Every mutating file tool snapshots the bytes it is about to replace into
+//! the session artifacts store before it commits. A verified commit journals
+//! the snapshot with the before/after hashes and a unified diff; the orb shows
+//! the newest entry with an Undo button, and `undo_file_edit` restores entries
+//! newest first. The journal starts empty with every `Brain`, and its
+//! snapshots live in a directory of their own per session, so starting a
+//! session never touches another process's snapshots.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+mod diff;
+
+/// Oldest entries beyond this lose their snapshot and can no longer be undone;
+/// the journal counts them so a session undo can say it was incomplete.
+const MAX_ENTRIES: usize = 64;
+/// Diff lines the orb previews; the tool result carries the bounded full diff.
+const ORB_DIFF_LINES: usize = 12;
+
+#[derive(Clone, Debug)]
+pub(super) struct Entry {
+    pub(super) id: String,
+    pub(super) tool: String,
+    pub(super) path: PathBuf,
+    snapshot: PathBuf,
+    pub(super) before_sha256: String,
+    pub(super) after_sha256: String,
+    diff: Option<diff::UnifiedDiff>,
+}
+
+struct Journal {
+    entries: Vec<Entry>,
+    /// Entries of this session dropped past [`MAX_ENTRIES`].
+    evicted: usize,
+    /// This session's snapshot directory, named on first use.
+    dir: Option<PathBuf>,
+}
+
+static JOURNAL: Mutex<Journal> = Mutex::new(Journal {
+    entries: Vec::new(),
+    evicted: 0,
+    dir: None,
+});
+
+fn journal() -> std::sync::MutexGuard<'static, Journal> {
+    JOURNAL
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn snapshot_dir() -> PathBuf {
+    static NEXT_SESSION: AtomicU64 = AtomicU64::new(1);
+    journal()
+        .dir
+        .get_or_insert_with(|| {
+            crate::paths::app_temp_dir()
+                .join("cc-artifacts")
+                .join("edits")
+                .join(format!(
+                    "session_{}_{}",
+                    std::process::id(),
+                    NEXT_SESSION.fetch_add(1, Ordering::SeqCst)
+                ))
+        })
+        .clone()
+}
+
+/// The bytes an edit is about to replace, durably written before the commit.
+/// Dropped without [`record`], the snapshot file is removed.
+pub(super) struct PreImage {
+    path: Option<PathBuf>,
+    sha256: String,
+}
+
+impl PreImage {
+    pub(super) fn capture(original: &[u8]) -> io::Result<Self> {
+        static NEXT: AtomicU64 = AtomicU64::new(1);
+        let dir = snapshot_dir();
+        std::fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("pre_{}.bin", NEXT.fetch_add(1, Ordering::SeqCst)));
+        write_synced(&path, original)?;
+        Ok(Self {
+            path: Some(path),
+            sha256: sha256_hex(original),
+        })
+    }
+}
+
+impl Drop for PreImage {
+    fn drop(&mut self) {
+        if let Some(path) = self.path.take() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn write_synced(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+    let mut file = std::fs::File::create(path)?;
+    file.write_all(bytes)?;
+    file.sync_all()
+}
+
+/// Journals a verified edit and returns its `edit` result object. The diff is
+/// present when both versions are UTF-8 text.
+pub(super) fn record(
+    mut pre_image: PreImage,
+    tool: &str,
+    path: &Path,
+    original: &[u8],
+    edited: &[u8],
+) -> Value {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    let Some(snapshot) = pre_image.path.take() else {
+        return json!({ "undo_available": false });
+    };
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let diff = match (text(original), text(edited)) {
+        (Some(before), Some(after)) => Some(diff::unified(&name, before, after)),
+        _ => None,
+    };
+    let entry = Entry {
+        id: format!("edit_{}", NEXT.fetch_add(1, Ordering::SeqCst)),
+        tool: tool.to_string(),
+        path: path.to_path_buf(),
+        snapshot,
+        before_sha256: pre_image.sha256.clone(),
+        after_sha256: sha256_hex(edited),
+        diff,
+    };
+    let result = entry.result();
+    {
+        let mut journal = journal();
+        journal.entries.push(entry);
+        while journal.entries.len() > MAX_ENTRIES {
+            let evicted = journal.entries.remove(0);
+            journal.evicted += 1;
+            let _ = std::fs::remove_file(&evicted.snapshot);
+        }
+    }
+    publish();
+    result
+}
+
+fn text(bytes: &[u8]) -> Option<&str> {
+    std::str::from_utf8(bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes)).ok()
+}
+
+impl Entry {
+    fn result(&self) -> Value {
+        let mut result = json!({
+            "edit_id": self.id,
+            "undo_available": true,
+        });
+        match &self.diff {
+            Some(diff) => {
+                result["diff"] = json!(diff.text);
+                result["diff_truncated"] = json!(diff.truncated);
+                result["lines_added"] = json!(diff.added);
+                result["lines_removed"] = json!(diff.removed);
+            }
+            None => result["diff_unavailable"] = json!("binary_package"),
+        }
+        result
+    }
+
+    /// The journaled pre-image, verified against the hash taken at capture.
+    pub(super) fn pre_image(&self) -> io::Result<Vec<u8>> {
+        let bytes = std::fs::read(&self.snapshot)?;
+        if sha256_hex(&bytes) != self.before_sha256 {
+            return Err(io::Error::other(
+                "the edit snapshot no longer matches its hash",
+            ));
+        }
+        Ok(bytes)
+    }
+}
+
+/// The newest edit that can still be undone.
+pub(super) fn latest() -> Option<Entry> {
+    journal().entries.last().cloned()
+}
+
+pub(super) fn remaining() -> usize {
+    journal().entries.len()
+}
+
+/// Edits of this session that can no longer be undone because the journal
+/// dropped them.
+pub(super) fn evicted() -> usize {
+    journal().evicted
+}
+
+/// Drops a restored entry and its snapshot, then shows the next one.
+pub(super) fn retire(id: &str) {
+    let retired = {
+        let mut journal = journal();
+        journal
+            .entries
+            .iter()
+            .position(|entry| entry.id == id)
+            .map(|index| journal.entries.remove(index))
+    };
+    if let Some(entry) = retired {
+        let _ = std::fs::remove_file(entry.snapshot);
+    }
+    publish();
+}
+
+/// Starts an empty journal and removes the previous session's snapshots.
+pub(super) fn begin_session() {
+    let previous = {
+        let mut journal = journal();
+        journal.entries.clear();
+        journal.evicted = 0;
+        journal.dir.take()
+    };
+    if let Some(dir) = previous {
+        let _ = std::fs::remove_dir_all(dir);
+    }
+    publish();
+}
+
+/// Shows the newest undoable edit on the orb, or hides the edit chip.
+fn publish() {
+    let chip = latest().map(|entry| {
+        let preview = entry.diff.as_ref().map(|diff| {
+            diff.text
+                .lines()
+                .skip(2)
+                .take(ORB_DIFF_LINES)
+                .collect::<Vec<_>>()
+                .join("\n")
+        });
+        json!({
+            "file": entry.path.file_name().map(|name| name.to_string_lossy().into_owned()),
+            "added": entry.diff.as_ref().map(|diff| diff.added),
+            "removed": entry.diff.as_ref().map(|diff| diff.removed),
+            "diff": preview,
+        })
+    });
+    super::orb::post_orb_script(format!(
+        "window.cc&&window.cc.setEdit({});",
+        chip.unwrap_or(Value::Null)
+    ));
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    let digest = Sha256::digest(bytes);
+    digest.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests;
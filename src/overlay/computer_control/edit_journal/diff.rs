@@ -0,0 +1,193 @@
+//! Line-based unified diff with three lines of context.
+//!
+//! Common leading and trailing lines are trimmed first, so a small edit to a
+//! large file costs only its changed region. A changed region too large for
+//! the quadratic LCS table is shown as one delete-then-insert block.
+
+const CONTEXT: usize = 3;
+const MAX_LCS_CELLS: usize = 1_000_000;
+const MAX_DIFF_CHARS: usize = 8_000;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct UnifiedDiff {
+    pub(super) text: String,
+    /// The text stopped at `MAX_DIFF_CHARS`; the line counts are still complete.
+    pub(super) truncated: bool,
+    pub(super) added: usize,
+    pub(super) removed: usize,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    /// Old line index; the new line is identical.
+    Equal(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+pub(super) fn unified(name: &str, before: &str, after: &str) -> UnifiedDiff {
+    let old: Vec<&str> = before.split_inclusive('\n').collect();
+    let new: Vec<&str> = after.split_inclusive('\n').collect();
+    let ops = line_ops(&old, &new);
+    let added = ops.iter().filter(|op| matches!(op, Op::Insert(_))).count();
+    let removed = ops.iter().filter(|op| matches!(op, Op::Delete(_))).count();
+    let mut out = Output::default();
+    out.push(&format!("--- a/{name}\n+++ b/{name}\n"));
+    for (start, end) in hunks(&ops) {
+        let hunk = &ops[start..end];
+        let (old_start, new_start) = first_lines(&ops, start);
+        let old_len = hunk
+            .iter()
+            .filter(|op| !matches!(op, Op::Insert(_)))
+            .count();
+        let new_len = hunk
+            .iter()
+            .filter(|op| !matches!(op, Op::Delete(_)))
+            .count();
+        out.push(&format!(
+            "@@ -{} +{} @@\n",
+            range(old_start, old_len),
+            range(new_start, new_len)
+        ));
+        for op in hunk {
+            let (marker, line) = match *op {
+                Op::Equal(index) => (' ', old[index]),
+                Op::Delete(index) => ('-', old[index]),
+                Op::Insert(index) => ('+', new[index]),
+            };
+            let ending = if line.ends_with('\n') {
+                ""
+            } else {
+                "\n\\ No newline at end of file"
+            };
+            out.push(&format!(
+                "{marker}{}{ending}\n",
+                line.strip_suffix('\n').unwrap_or(line)
+            ));
+        }
+    }
+    UnifiedDiff {
+        text: out.text,
+        truncated: out.truncated,
+        added,
+        removed,
+    }
+}
+
+#[derive(Default)]
+struct Output {
+    text: String,
+    truncated: bool,
+}
+
+impl Output {
+    fn push(&mut self, piece: &str) {
+        if self.truncated {
+            return;
+        }
+        if self.text.len() + piece.len() > MAX_DIFF_CHARS {
+            self.truncated = true;
+            return;
+        }
+        self.text.push_str(piece);
+    }
+}
+
+fn line_ops(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let prefix = old
+        .iter()
+        .zip(new)
+        .take_while(|(left, right)| left == right)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let (old_end, new_end) = (old.len() - suffix, new.len() - suffix);
+    let mut ops: Vec<Op> = (0..prefix).map(Op::Equal).collect();
+    let (rows, columns) = (old_end - prefix, new_end - prefix);
+    if (rows + 1).saturating_mul(columns + 1) <= MAX_LCS_CELLS {
+        ops.extend(lcs_ops(
+            &old[prefix..old_end],
+            &new[prefix..new_end],
+            prefix,
+        ));
+    } else {
+        ops.extend((prefix..old_end).map(Op::Delete));
+        ops.extend((prefix..new_end).map(Op::Insert));
+    }
+    ops.extend((old_end..old.len()).map(Op::Equal));
+    ops
+}
+
+fn lcs_ops(old: &[&str], new: &[&str], offset: usize) -> Vec<Op> {
+    let columns = new.len() + 1;
+    // lengths[i * columns + j] = LCS length of old[i..] and new[j..].
+    let mut lengths = vec![0u32; (old.len() + 1) * columns];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * columns + j] = if old[i] == new[j] {
+                lengths[(i + 1) * columns + j + 1] + 1
+            } else {
+                lengths[(i + 1) * columns + j].max(lengths[i * columns + j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::with_capacity(old.len() + new.len());
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push(Op::Equal(offset + i));
+            i += 1;
+            j += 1;
+        } else if i < old.len()
+            && (j == new.len() || lengths[(i + 1) * columns + j] >= lengths[i * columns + j + 1])
+        {
+            ops.push(Op::Delete(offset + i));
+            i += 1;
+        } else {
+            ops.push(Op::Insert(offset + j));
+            j += 1;
+        }
+    }
+    ops
+}
+
+/// `[start, end)` op ranges of each hunk: changes plus up to `CONTEXT`
+/// equal lines on each side, merged when their context would overlap.
+fn hunks(ops: &[Op]) -> Vec<(usize, usize)> {
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (index, op) in ops.iter().enumerate() {
+        if matches!(op, Op::Equal(_)) {
+            continue;
+        }
+        let start = index.saturating_sub(CONTEXT);
+        let end = (index + 1 + CONTEXT).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+    hunks
+}
+
+/// Zero-based old and new line indices where the op at `start` sits.
+fn first_lines(ops: &[Op], start: usize) -> (usize, usize) {
+    ops[..start].iter().fold((0, 0), |(old, new), op| match op {
+        Op::Equal(_) => (old + 1, new + 1),
+        Op::Delete(_) => (old + 1, new),
+        Op::Insert(_) => (old, new + 1),
+    })
+}
+
+/// A hunk range in unified-diff form: one-based start, length omitted when 1,
+/// and the line before the hunk when it is empty.
+fn range(start: usize, len: usize) -> String {
+    match len {
+        0 => format!("{start},0"),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{len}", start + 1),
+    }
+}
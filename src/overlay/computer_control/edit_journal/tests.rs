@@ -0,0 +1,119 @@
+use super::*;
+
+#[test]
+fn replacement_diff_has_context_and_counts() {
+    let before = "a\nb\nc\nd\ne\nf\ng\nh\n";
+    let after = "a\nb\nc\nd\nE\nf\ng\nh\n";
+    let diff = diff::unified("notes.txt", before, after);
+    assert_eq!(
+        diff.text,
+        "--- a/notes.txt\n+++ b/notes.txt\n@@ -2,7 +2,7 @@\n b\n c\n d\n-e\n+E\n f\n g\n h\n"
+    );
+    assert_eq!((diff.added, diff.removed, diff.truncated), (1, 1, false));
+}
+
+#[test]
+fn distant_changes_get_separate_hunks() {
+    let before: String = (1..=20).map(|line| format!("{line}\n")).collect();
+    let after = before
+        .replacen("2\n", "two\n", 1)
+        .replace("19\n", "nineteen\n");
+    let diff = diff::unified("n", &before, &after);
+    let headers: Vec<&str> = diff
+        .text
+        .lines()
+        .filter(|line| line.starts_with("@@"))
+        .collect();
+    assert_eq!(headers, ["@@ -1,5 +1,5 @@", "@@ -16,5 +16,5 @@"]);
+}
+
+#[test]
+fn insertions_deletions_and_missing_final_newline() {
+    let diff = diff::unified("f", "", "new\n");
+    assert!(
+        diff.text.ends_with("@@ -0,0 +1 @@\n+new\n"),
+        "{}",
+        diff.text
+    );
+    let diff = diff::unified("f", "keep\nend", "keep\n");
+    assert!(
+        diff.text
+            .ends_with("@@ -1,2 +1 @@\n keep\n-end\n\\ No newline at end of file\n"),
+        "{}",
+        diff.text
+    );
+    assert_eq!((diff.added, diff.removed), (0, 1));
+}
+
+#[test]
+fn long_diffs_are_truncated_but_counted() {
+    let before: String = (0..5000).map(|line| format!("line {line}\n")).collect();
+    let diff = diff::unified("big", &before, "");
+    assert!(diff.truncated);
+    assert!(diff.text.len() <= 8_000);
+    assert_eq!((diff.added, diff.removed), (0, 5000));
+}
+
+#[test]
+fn journal_records_newest_first_and_retires_snapshots() {
+    begin_session();
+    let path = Path::new("/docs/report.csv");
+    let first = PreImage::capture(b"a,b\n1,2\n").unwrap();
+    let result = record(first, "edit_text_file", path, b"a,b\n1,2\n", b"a,b\n1,3\n");
+    assert_eq!(result["undo_available"], true);
+    assert_eq!(
+        (
+            result["lines_added"].as_u64(),
+            result["lines_removed"].as_u64()
+        ),
+        (Some(1), Some(1))
+    );
+    let second = PreImage::capture(b"PK\x03\x04\xff").unwrap();
+    let result = record(
+        second,
+        "edit_office_file",
+        path,
+        b"PK\x03\x04\xff",
+        b"PK\x03\x04\xfe",
+    );
+    assert_eq!(result["diff_unavailable"], "binary_package");
+    assert_eq!(remaining(), 2);
+
+    let newest = latest().unwrap();
+    assert_eq!(newest.tool, "edit_office_file");
+    assert_eq!(newest.pre_image().unwrap(), b"PK\x03\x04\xff");
+    retire(&newest.id);
+    assert!(!newest.snapshot.exists());
+    assert_eq!(latest().unwrap().pre_image().unwrap(), b"a,b\n1,2\n");
+
+    let abandoned = PreImage::capture(b"unused").unwrap();
+    let abandoned_path = abandoned.path.clone().unwrap();
+    drop(abandoned);
+    assert!(!abandoned_path.exists());
+    let session_dir = abandoned_path.parent().unwrap().to_path_buf();
+    let neighbour = session_dir.with_file_name("session_other");
+    std::fs::create_dir_all(&neighbour).unwrap();
+    std::fs::write(neighbour.join("pre_1.bin"), b"theirs").unwrap();
+    begin_session();
+    assert_eq!(remaining(), 0);
+    assert!(!session_dir.exists());
+    assert!(
+        neighbour.join("pre_1.bin").exists(),
+        "other sessions keep theirs"
+    );
+    std::fs::remove_dir_all(neighbour).unwrap();
+
+    for index in 0..=MAX_ENTRIES {
+        let pre_image = PreImage::capture(b"x").unwrap();
+        record(
+            pre_image,
+            "edit_text_file",
+            path,
+            b"x",
+            index.to_string().as_bytes(),
+        );
+    }
+    assert_eq!((remaining(), evicted()), (MAX_ENTRIES, 1));
+    begin_session();
+    assert_eq!(evicted(), 0);
+}
@@ -115,6 +115,9 @@ pub fn execute_ex(name: &str, args: &Value, profile: &HumanProfile, cancel: &Ato
         "read_text_file" => Ok(text_file::read_text_file(args)),
         "edit_text_file" => Ok(text_file::edit_text_file(args)),
         "edit_text_file_structure" => Ok(text_file::edit_text_file_structure(args)),
+        "read_office_file" => Ok(text_file::read_office_file(args)),
+        "edit_office_file" => Ok(text_file::edit_office_file(args)),
+        "undo_file_edit" => Ok(text_file::undo_file_edit(args)),
         "click_here" => mouse::click_here(args, cancel),
         "point" => mouse::point(args, profile, cancel),
         other => Err(anyhow!("unknown action: {other}")),
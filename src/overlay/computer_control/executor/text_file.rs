@@ -14,16 +14,21 @@ mod failures;
 mod formula_preservation;
 #[path = "text_file_match_diagnostics.rs"]
 mod match_diagnostics;
+#[path = "text_file_office.rs"]
+mod office;
 #[path = "text_file_read.rs"]
 mod stable_read;
 #[path = "text_file_structure.rs"]
 mod structure;
 #[path = "text_file_transaction.rs"]
 mod transaction;
+#[path = "text_file_undo.rs"]
+mod undo;
 
+use super::super::edit_journal::{self, PreImage};
 use failures::{
-    ambiguous_commit, concurrent_change, failure, oversized_edit, replacement_failure, stale,
-    transaction_failure,
+    ambiguous_commit, concurrent_change, failure, oversized_edit, replacement_failure,
+    snapshot_failure, stale, transaction_failure,
 };
 use stable_read::ReadFailure;
 #[cfg(test)]
@@ -105,6 +110,18 @@ pub(super) fn read_text_file(args: &Value) -> Value {
     })
 }
 
+pub(super) fn read_office_file(args: &Value) -> Value {
+    office::read(args)
+}
+
+pub(super) fn edit_office_file(args: &Value) -> Value {
+    office::edit(args)
+}
+
+pub(super) fn undo_file_edit(args: &Value) -> Value {
+    undo::undo_file_edit(args)
+}
+
 pub(super) fn edit_text_file(args: &Value) -> Value {
     edit_text_file_with_scope(args, EditScope::Content)
 }
@@ -273,6 +290,10 @@ fn edit_text_file_with_scope(args: &Value, scope: EditScope) -> Value {
         partial_proof.push(format!("/replacement_evidence/{index}/result_prefix"));
         partial_proof.push(format!("/replacement_evidence/{index}/result_suffix"));
     }
+    let pre_image = match PreImage::capture(&original) {
+        Ok(pre_image) => pre_image,
+        Err(error) => return snapshot_failure(&path, &error),
+    };
     let temporary = match write_synced_sibling(&path, &edited) {
         Ok(path) => path,
         Err(error) => {
@@ -322,10 +343,15 @@ fn edit_text_file_with_scope(args: &Value, scope: EditScope) -> Value {
             );
         }
     };
+    let tool = match scope {
+        EditScope::Content => "edit_text_file",
+        EditScope::StructurePreflight | EditScope::StructureCommit => "edit_text_file_structure",
+    };
 
     json!({
         "ok": true,
         "path": path.to_string_lossy(),
+        "edit": edit_journal::record(pre_image, tool, &path, &original, &edited),
         "before_sha256": original_hash,
         "sha256": edited_hash,
         "before_byte_count": original.len(),
@@ -87,6 +87,15 @@ pub(super) fn transaction_failure(path: &Path, error: TransactionFailure) -> Val
     failure(code, Some(path), &message, true)
 }
 
+pub(super) fn snapshot_failure(path: &Path, error: &std::io::Error) -> Value {
+    failure(
+        "ERR_TEXT_FILE_SNAPSHOT",
+        Some(path),
+        &format!("could not snapshot the original for undo: {error}"),
+        true,
+    )
+}
+
 pub(super) fn oversized_edit(path: &Path, proposed_size: u64) -> Value {
     let mut value = failure(
         "ERR_TEXT_FILE_TOO_LARGE",
@@ -0,0 +1,246 @@
+//! Bounded reads and transactional edits of `.xlsx`, `.docx` and `.pptx`
+//! files. The package work happens in memory; the file itself goes through
+//! the same stale-read check, synced sibling, revalidation, and audited
+//! atomic replace as a text edit.
+
+use serde_json::{Value, json};
+use std::path::Path;
+
+#[path = "text_file_office_package.rs"]
+mod package;
+
+use super::stable_read::{self, ReadFailure};
+use super::transaction::{CommitOutcome, EditGuard, TransactionFailure, write_synced_sibling};
+use super::{
+    PreImage, ambiguous_commit, concurrent_change, edit_journal, failure, hash_matches,
+    required_string, sha256_hex, snapshot_failure, stale, transaction_failure, valid_hash,
+};
+use package::{EditRequest, OfficeError, ReadRequest};
+
+const MAX_OFFICE_FILE_BYTES: u64 = 32 * 1024 * 1024;
+const EDIT_HASH_ERROR: &str = "expected_sha256 must be a 64-character hexadecimal SHA-256; call read_office_file on this path and use its returned sha256—never guess or use a placeholder";
+
+pub(super) fn read(args: &Value) -> Value {
+    let path = match absolute_path(args, "no file was read") {
+        Ok(path) => path,
+        Err(error) => return error,
+    };
+    let request = match ReadRequest::from_args(args) {
+        Ok(request) => request,
+        Err(error) => return office_failure(path, error),
+    };
+    let bytes = match stable_read::read_stable_bounded(path, MAX_OFFICE_FILE_BYTES) {
+        Ok(bytes) => bytes,
+        Err(ReadFailure::Oversize(size)) => return oversized(path, size),
+        Err(error) => return error.into_value(path),
+    };
+    let sha256 = sha256_hex(&bytes);
+    if let Some(expected) = args.get("expected_sha256").and_then(Value::as_str) {
+        if !valid_hash(expected) {
+            return failure(
+                "ERR_OFFICE_FILE_BAD_ARGUMENT",
+                Some(path),
+                "expected_sha256 must be a 64-character hexadecimal SHA-256",
+                true,
+            );
+        }
+        if !hash_matches(expected, &sha256) {
+            return stale(path, expected, &sha256);
+        }
+    }
+    let mut view = match package::read(&bytes, &request) {
+        Ok(view) => view,
+        Err(error) => return office_failure(path, error),
+    };
+    let truncated = view["truncated"].as_bool() == Some(true);
+    let content_pointers: Vec<&str> = ["/cells", "/paragraphs", "/tables", "/slides"]
+        .into_iter()
+        .filter(|pointer| truncated && view.pointer(pointer).is_some())
+        .collect();
+    if let Some(fields) = view.as_object_mut() {
+        fields.insert("ok".to_string(), Value::Bool(true));
+        fields.insert("path".to_string(), json!(path.to_string_lossy()));
+        fields.insert("sha256".to_string(), json!(sha256));
+        fields.insert("byte_count".to_string(), json!(bytes.len()));
+        fields.insert("read_limit_bytes".to_string(), json!(MAX_OFFICE_FILE_BYTES));
+        fields.insert("original_unchanged".to_string(), Value::Bool(true));
+        fields.insert(
+            "completion_proof".to_string(),
+            json!({
+                "partial": content_pointers,
+                "exact": ["/path", "/sha256"],
+            }),
+        );
+    }
+    view
+}
+
+pub(super) fn edit(args: &Value) -> Value {
+    let requested_path = match absolute_path(args, "no file was changed") {
+        Ok(path) => path,
+        Err(error) => return error,
+    };
+    let Some(expected_hash) =
+        required_string(args, "expected_sha256").filter(|hash| valid_hash(hash))
+    else {
+        return failure(
+            "ERR_OFFICE_FILE_BAD_ARGUMENT",
+            Some(requested_path),
+            EDIT_HASH_ERROR,
+            true,
+        );
+    };
+    let request = match EditRequest::from_args(args) {
+        Ok(request) => request,
+        Err(error) => return office_failure(requested_path, error),
+    };
+    let path = match std::fs::canonicalize(requested_path) {
+        Ok(path) => path,
+        Err(error) => return ReadFailure::from_io(error).into_value(requested_path),
+    };
+    let mut guard = match EditGuard::acquire(&path) {
+        Ok(guard) => guard,
+        Err(error) => return guarded_failure(&path, error),
+    };
+    let original = match guard.read_bounded(MAX_OFFICE_FILE_BYTES) {
+        Ok(bytes) => bytes,
+        Err(error) => return guarded_failure(&path, error),
+    };
+    let original_hash = sha256_hex(&original);
+    if !hash_matches(expected_hash, &original_hash) {
+        return stale(&path, expected_hash, &original_hash);
+    }
+    let outcome = match package::edit(&original, &request) {
+        Ok(outcome) => outcome,
+        Err(error) => return office_failure(&path, error),
+    };
+    let edited = outcome.bytes;
+    if edited.len() as u64 > MAX_OFFICE_FILE_BYTES {
+        return oversized(&path, edited.len() as u64);
+    }
+    let edited_hash = sha256_hex(&edited);
+    let pre_image = match PreImage::capture(&original) {
+        Ok(pre_image) => pre_image,
+        Err(error) => return snapshot_failure(&path, &error),
+    };
+    let temporary = match write_synced_sibling(&path, &edited) {
+        Ok(path) => path,
+        Err(error) => {
+            return failure(
+                "ERR_OFFICE_FILE_TEMP_WRITE",
+                Some(&path),
+                &format!("could not stage the replacement: {error}"),
+                true,
+            );
+        }
+    };
+    let current = match guard.validate_current(&path, &original, MAX_OFFICE_FILE_BYTES) {
+        Ok(current) => current,
+        Err(change) => {
+            return concurrent_change(&path, expected_hash, change.actual_hash.as_deref());
+        }
+    };
+    let retained_backup = match guard.commit_audited(
+        &path,
+        current,
+        temporary,
+        &original,
+        &edited,
+        MAX_OFFICE_FILE_BYTES,
+    ) {
+        CommitOutcome::Verified { retained_backup } => retained_backup,
+        CommitOutcome::NoEffect { error } => {
+            return failure("ERR_OFFICE_FILE_ATOMIC_REPLACE", Some(&path), &error, true);
+        }
+        CommitOutcome::Ambiguous {
+            error,
+            tool_mutated_file,
+            external_change_detected,
+            recovery_backup,
+            recovery_sha256,
+        } => {
+            return ambiguous_commit(
+                &path,
+                &error,
+                tool_mutated_file,
+                external_change_detected,
+                recovery_backup.as_deref(),
+                recovery_sha256.as_deref(),
+            );
+        }
+    };
+
+    json!({
+        "ok": true,
+        "path": path.to_string_lossy(),
+        "kind": outcome.kind.as_str(),
+        "edit": edit_journal::record(pre_image, "edit_office_file", &path, &original, &edited),
+        "before_sha256": original_hash,
+        "sha256": edited_hash,
+        "before_byte_count": original.len(),
+        "byte_count": edited.len(),
+        "replacements_applied": outcome.replacements_applied,
+        "cells": outcome.cells,
+        "parts_changed": outcome.parts_changed,
+        "recalculation_requested": outcome.recalculation_requested,
+        "atomic": true,
+        "durable_stage": true,
+        "effect_verified": true,
+        "effect_may_have_occurred": true,
+        "executed": true,
+        "original_unchanged": false,
+        "tool_mutated_file": true,
+        "external_change_detected": false,
+        "retained_backup": retained_backup.map(|path| path.to_string_lossy().to_string()),
+        "completion_proof": {
+            "postcondition_only": ["/sha256", "/byte_count", "/cells", "/parts_changed"],
+            "partial": [],
+            "exact": ["/path", "/before_sha256", "/sha256"],
+        },
+    })
+}
+
+fn absolute_path<'a>(args: &'a Value, effect: &str) -> Result<&'a Path, Value> {
+    let Some(path) = required_string(args, "path") else {
+        return Err(failure(
+            "ERR_OFFICE_FILE_BAD_ARGUMENT",
+            None,
+            "path must be a non-empty string",
+            true,
+        ));
+    };
+    let path = Path::new(path);
+    if !path.is_absolute() {
+        return Err(failure(
+            "ERR_OFFICE_FILE_PATH_NOT_ABSOLUTE",
+            Some(path),
+            &format!("path must be absolute; {effect}"),
+            true,
+        ));
+    }
+    Ok(path)
+}
+
+fn office_failure(path: &Path, error: OfficeError) -> Value {
+    let mut value = failure(error.code, Some(path), &error.message, true);
+    if let (Some(fields), Value::Object(details)) = (value.as_object_mut(), error.details) {
+        fields.extend(details);
+    }
+    value
+}
+
+fn guarded_failure(path: &Path, error: TransactionFailure) -> Value {
+    match error {
+        TransactionFailure::Oversize(size) => oversized(path, size),
+        error => transaction_failure(path, error),
+    }
+}
+
+fn oversized(path: &Path, size: u64) -> Value {
+    failure(
+        "ERR_OFFICE_FILE_TOO_LARGE",
+        Some(path),
+        &format!("file is {size} bytes; limit is {MAX_OFFICE_FILE_BYTES} bytes"),
+        true,
+    )
+}
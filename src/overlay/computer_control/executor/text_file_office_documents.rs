@@ -0,0 +1,194 @@
+//! Word and PowerPoint text extraction: body paragraphs with their style,
+//! top-level tables as rows of cell text, and slide paragraphs.
+
+use std::collections::HashMap;
+
+use quick_xml::events::Event;
+use serde_json::{Value, json};
+
+use super::xml::{self, Events, attribute, local};
+
+/// Shared character budget across everything a read returns.
+pub(super) struct Budget {
+    remaining: usize,
+    pub(super) truncated: bool,
+}
+
+impl Budget {
+    pub(super) fn new(max_chars: usize) -> Self {
+        Self {
+            remaining: max_chars,
+            truncated: false,
+        }
+    }
+
+    /// Takes as much of `text` as still fits; `None` once the budget is spent.
+    pub(super) fn take(&mut self, text: &str) -> Option<String> {
+        if self.remaining == 0 {
+            self.truncated = true;
+            return None;
+        }
+        let count = text.chars().count();
+        if count > self.remaining {
+            self.truncated = true;
+        }
+        let kept: String = text.chars().take(self.remaining).collect();
+        self.remaining -= count.min(self.remaining);
+        Some(kept)
+    }
+}
+
+/// Body paragraphs and tables of `word/document.xml`. Paragraph text is
+/// exactly what a replacement's `old_text` is matched against.
+pub(super) fn docx_outline(document: &Events, budget: &mut Budget) -> (Vec<Value>, Vec<Value>) {
+    let texts: HashMap<usize, String> = xml::paragraphs(document, &[b"p"])
+        .iter()
+        .map(|paragraph| (paragraph.start, paragraph.text()))
+        .collect();
+    let mut paragraphs = Vec::new();
+    let mut tables: Vec<Vec<Vec<String>>> = Vec::new();
+    let mut table_depth = 0usize;
+    let mut paragraph_depth = 0usize;
+    let mut current: Option<(usize, Option<String>)> = None;
+    for (index, event) in document.iter().enumerate() {
+        match event {
+            Event::Start(element) => match local(element.name().as_ref()) {
+                b"tbl" => {
+                    table_depth += 1;
+                    if table_depth == 1 {
+                        tables.push(Vec::new());
+                    }
+                }
+                b"tr" if table_depth == 1 => {
+                    if let Some(table) = tables.last_mut() {
+                        table.push(Vec::new());
+                    }
+                }
+                b"tc" if table_depth == 1 => {
+                    if let Some(row) = tables.last_mut().and_then(|table| table.last_mut()) {
+                        row.push(String::new());
+                    }
+                }
+                b"p" => {
+                    paragraph_depth += 1;
+                    if paragraph_depth == 1 {
+                        current = Some((index, None));
+                    }
+                }
+                b"pStyle" => set_style(&mut current, paragraph_depth, element),
+                _ => {}
+            },
+            Event::Empty(element) => match local(element.name().as_ref()) {
+                b"pStyle" => set_style(&mut current, paragraph_depth, element),
+                b"p" if paragraph_depth == 0 && table_depth == 0 => {
+                    paragraphs.push((None, String::new()));
+                }
+                _ => {}
+            },
+            Event::End(element) => match local(element.name().as_ref()) {
+                b"tbl" => table_depth = table_depth.saturating_sub(1),
+                b"p" => {
+                    if paragraph_depth == 1
+                        && let Some((start, style)) = current.take()
+                    {
+                        let text = texts.get(&start).cloned().unwrap_or_default();
+                        if table_depth == 0 {
+                            paragraphs.push((style, text));
+                        } else if let Some(cell) = tables
+                            .last_mut()
+                            .and_then(|table| table.last_mut())
+                            .and_then(|row| row.last_mut())
+                        {
+                            if !cell.is_empty() {
+                                cell.push('\n');
+                            }
+                            cell.push_str(&text);
+                        }
+                    }
+                    paragraph_depth = paragraph_depth.saturating_sub(1);
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+
+    let mut paragraph_values = Vec::new();
+    for (index, (style, text)) in paragraphs.into_iter().enumerate() {
+        if text.is_empty() {
+            continue;
+        }
+        let Some(text) = budget.take(&text) else {
+            break;
+        };
+        let mut value = json!({ "index": index, "text": text });
+        if let Some(style) = style {
+            value["style"] = json!(style);
+        }
+        paragraph_values.push(value);
+    }
+    let mut table_values = Vec::new();
+    'tables: for (index, table) in tables.into_iter().enumerate() {
+        let mut rows = Vec::new();
+        for row in table {
+            let mut cells = Vec::new();
+            for cell in row {
+                match budget.take(&cell) {
+                    Some(text) => cells.push(text),
+                    None => {
+                        rows.push(cells);
+                        table_values.push(json!({ "index": index, "rows": rows }));
+                        break 'tables;
+                    }
+                }
+            }
+            rows.push(cells);
+        }
+        table_values.push(json!({ "index": index, "rows": rows }));
+    }
+    (paragraph_values, table_values)
+}
+
+fn set_style(
+    current: &mut Option<(usize, Option<String>)>,
+    paragraph_depth: usize,
+    element: &quick_xml::events::BytesStart,
+) {
+    if paragraph_depth == 1
+        && let Some((_, style)) = current
+    {
+        *style = attribute(element, b"val");
+    }
+}
+
+/// Non-empty paragraphs of one slide, in shape order.
+pub(super) fn slide_paragraphs(slide: &Events, budget: &mut Budget) -> Vec<String> {
+    let mut found = Vec::new();
+    for paragraph in xml::paragraphs(slide, &[b"p"]) {
+        let text = paragraph.text();
+        if text.is_empty() {
+            continue;
+        }
+        match budget.take(&text) {
+            Some(text) => found.push(text),
+            None => break,
+        }
+    }
+    found
+}
+
+/// Relationship ids of the slides listed in `ppt/presentation.xml`, in
+/// presentation order.
+pub(super) fn slide_order(presentation: &Events) -> Vec<String> {
+    presentation
+        .iter()
+        .filter_map(|event| match event {
+            Event::Start(element) | Event::Empty(element)
+                if local(element.name().as_ref()) == b"sldId" =>
+            {
+                xml::relationship_id(element)
+            }
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,296 @@
+//! Planning and applying an `edit_office_file` request: exact text
+//! replacements across a package's text parts and worksheet cell writes,
+//! verified by reading the rebuilt package back before it is returned.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Value, json};
+
+use super::xlsx;
+use super::xml::{self, Events, Match};
+use super::{
+    CellEdit, EditOutcome, EditRequest, OfficeError, OfficeKind, Package, TextReplacement,
+    bad_argument,
+};
+
+/// A text-bearing part loaded for replacement.
+struct TextPart {
+    name: String,
+    events: Events,
+    paragraphs: Vec<xml::Paragraph>,
+    texts: Vec<String>,
+}
+
+pub(super) fn apply(
+    package: &mut Package,
+    request: &EditRequest,
+) -> Result<EditOutcome, OfficeError> {
+    if !request.cells.is_empty() && package.kind != OfficeKind::Xlsx {
+        return Err(bad_argument(
+            "cells apply only to .xlsx workbooks; use replacements for documents and presentations"
+                .to_string(),
+        ));
+    }
+    let mut edited: BTreeMap<String, Events> = BTreeMap::new();
+    let mut replacements_applied = 0;
+    if !request.replacements.is_empty() {
+        let parts = text_parts(package)?;
+        replacements_applied = request
+            .replacements
+            .iter()
+            .map(|item| item.expected_count)
+            .sum();
+        for (name, events) in replace_text(parts, &request.replacements)? {
+            edited.insert(name, events);
+        }
+    }
+
+    let mut recalculation_requested = false;
+    let mut targets = Vec::new();
+    let mut sheet_parts: BTreeMap<Option<String>, String> = BTreeMap::new();
+    for cell in &request.cells {
+        let key = cell.sheet.as_deref().map(str::to_lowercase);
+        let part = match sheet_parts.get(&key) {
+            Some(part) => part.clone(),
+            None => {
+                let (_, _, part) = super::sheet_part(package, cell.sheet.as_deref())?;
+                sheet_parts.insert(key, part.clone());
+                part
+            }
+        };
+        let current = match edited.remove(&part) {
+            Some(events) => events,
+            None => package.required_xml(&part)?,
+        };
+        let updated = xlsx::set_cell(&current, cell.column, cell.row, &cell.value)?;
+        edited.insert(part.clone(), updated);
+        targets.push(part);
+    }
+    if !request.cells.is_empty() {
+        let workbook = package.required_xml("xl/workbook.xml")?;
+        if let Some(updated) = xlsx::request_full_calculation(&workbook) {
+            edited.insert("xl/workbook.xml".to_string(), updated);
+            recalculation_requested = true;
+        }
+    }
+
+    let mut changed = BTreeMap::new();
+    for (name, events) in edited {
+        let bytes = xml::serialize(&events);
+        if package.part(&name)?.as_deref() != Some(bytes.as_slice()) {
+            changed.insert(name, bytes);
+        }
+    }
+    if changed.is_empty() {
+        return Err(OfficeError::new(
+            "ERR_OFFICE_FILE_NO_CHANGE",
+            "the requested edits would not change the package".to_string(),
+        ));
+    }
+    let bytes = package.rewrite(&changed)?;
+    let cells = verify(&bytes, package.kind, &changed, &request.cells, &targets)?;
+    Ok(EditOutcome {
+        kind: package.kind,
+        bytes,
+        replacements_applied,
+        parts_changed: changed.into_keys().collect(),
+        cells,
+        recalculation_requested,
+    })
+}
+
+/// Parts whose text replacements may touch, with the element that groups
+/// runs into one matchable string.
+fn text_parts(package: &mut Package) -> Result<Vec<TextPart>, OfficeError> {
+    let mut names: Vec<(String, &[&[u8]])> = package
+        .names()
+        .into_iter()
+        .filter_map(|name| {
+            let containers: &[&[u8]] = match package.kind {
+                OfficeKind::Docx if is_word_text_part(&name) => &[b"p"],
+                OfficeKind::Pptx if is_numbered(&name, "ppt/slides/slide") => &[b"p"],
+                OfficeKind::Xlsx if name == "xl/sharedStrings.xml" => &[b"si"],
+                OfficeKind::Xlsx if is_numbered(&name, "xl/worksheets/sheet") => &[b"is"],
+                _ => return None,
+            };
+            Some((name, containers))
+        })
+        .collect();
+    names.sort();
+    let mut parts = Vec::with_capacity(names.len());
+    for (name, containers) in names {
+        let events = package.required_xml(&name)?;
+        let paragraphs = xml::paragraphs(&events, containers);
+        let texts = paragraphs.iter().map(xml::Paragraph::text).collect();
+        parts.push(TextPart {
+            name,
+            events,
+            paragraphs,
+            texts,
+        });
+    }
+    Ok(parts)
+}
+
+fn is_word_text_part(name: &str) -> bool {
+    matches!(
+        name,
+        "word/document.xml" | "word/footnotes.xml" | "word/endnotes.xml"
+    ) || is_numbered(name, "word/header")
+        || is_numbered(name, "word/footer")
+}
+
+/// `prefix` followed by an optional number and `.xml`, e.g. `word/header2.xml`.
+fn is_numbered(name: &str, prefix: &str) -> bool {
+    name.strip_prefix(prefix)
+        .and_then(|rest| rest.strip_suffix(".xml"))
+        .is_some_and(|number| number.bytes().all(|byte| byte.is_ascii_digit()))
+}
+
+/// Byte range of a match in its paragraph text and the replacement index.
+type PlannedMatch = (usize, usize, usize);
+
+/// Counts every replacement across all parts first, so a count mismatch
+/// anywhere leaves every part untouched, then rewrites the matched runs.
+fn replace_text(
+    parts: Vec<TextPart>,
+    replacements: &[TextReplacement],
+) -> Result<Vec<(String, Events)>, OfficeError> {
+    let mut planned: BTreeMap<(usize, usize), Vec<PlannedMatch>> = BTreeMap::new();
+    for (index, replacement) in replacements.iter().enumerate() {
+        let mut found = 0;
+        for (part_index, part) in parts.iter().enumerate() {
+            for (paragraph_index, text) in part.texts.iter().enumerate() {
+                for (start, _) in text.match_indices(&replacement.old_text) {
+                    found += 1;
+                    planned
+                        .entry((part_index, paragraph_index))
+                        .or_default()
+                        .push((start, start + replacement.old_text.len(), index));
+                }
+            }
+        }
+        if found != replacement.expected_count {
+            let code = if found == 0 {
+                "ERR_OFFICE_FILE_MATCH_MISSING"
+            } else {
+                "ERR_OFFICE_FILE_MATCH_AMBIGUOUS"
+            };
+            return Err(OfficeError::new(
+                code,
+                "an exact replacement count did not match; read the file again and copy old_text from one paragraph or cell".to_string(),
+            )
+            .with_details(json!({
+                "replacement_index": index,
+                "expected_count": replacement.expected_count,
+                "actual_count": found,
+            })));
+        }
+    }
+
+    let mut rewritten: BTreeMap<usize, BTreeMap<usize, String>> = BTreeMap::new();
+    for ((part_index, paragraph_index), mut matches) in planned {
+        matches.sort();
+        if matches.windows(2).any(|pair| pair[1].0 < pair[0].1) {
+            return Err(OfficeError::new(
+                "ERR_OFFICE_FILE_OVERLAPPING_REPLACEMENTS",
+                "replacement match ranges overlap".to_string(),
+            ));
+        }
+        let matches: Vec<Match> = matches
+            .iter()
+            .map(|&(start, end, index)| Match {
+                start,
+                end,
+                new_text: &replacements[index].new_text,
+            })
+            .collect();
+        xml::rewrite_nodes(
+            &parts[part_index].paragraphs[paragraph_index],
+            &matches,
+            rewritten.entry(part_index).or_default(),
+        );
+    }
+    Ok(rewritten
+        .into_iter()
+        .map(|(part_index, nodes)| {
+            let part = &parts[part_index];
+            (
+                part.name.clone(),
+                xml::apply_node_text(&part.events, &nodes),
+            )
+        })
+        .collect())
+}
+
+/// Reopens the rebuilt package, re-parses every changed part, and reads
+/// each edited cell back. Returns the read-back cells.
+fn verify(
+    bytes: &[u8],
+    kind: OfficeKind,
+    changed: &BTreeMap<String, Vec<u8>>,
+    cells: &[CellEdit],
+    targets: &[String],
+) -> Result<Vec<Value>, OfficeError> {
+    let unverified = |message: String| OfficeError::new("ERR_OFFICE_FILE_VERIFY", message);
+    let mut package = Package::open(bytes).map_err(|error| {
+        unverified(format!(
+            "the rebuilt package does not open: {}",
+            error.message
+        ))
+    })?;
+    if package.kind != kind {
+        return Err(unverified("the rebuilt package changed type".to_string()));
+    }
+    for (name, expected) in changed {
+        let actual = package.part(name)?;
+        if actual.as_deref() != Some(expected.as_slice()) {
+            return Err(unverified(format!("{name} did not read back as written")));
+        }
+        xml::parse(expected, name)?;
+    }
+    if cells.is_empty() {
+        return Ok(Vec::new());
+    }
+    let shared = match package.xml("xl/sharedStrings.xml")? {
+        Some(events) => xlsx::shared_strings(&events),
+        None => Vec::new(),
+    };
+    let mut sheets: BTreeMap<&str, Vec<xlsx::Cell>> = BTreeMap::new();
+    let mut read_back = Vec::with_capacity(cells.len());
+    for (edit, part) in cells.iter().zip(targets) {
+        if !sheets.contains_key(part.as_str()) {
+            let events = package.required_xml(part)?;
+            sheets.insert(part, xlsx::cells(&events, &shared));
+        }
+        let cell = sheets[part.as_str()]
+            .iter()
+            .rev()
+            .find(|cell| cell.column == edit.column && cell.row == edit.row);
+        // A later edit of the same cell wins; only the last one must hold.
+        let superseded = cells
+            .iter()
+            .zip(targets)
+            .rev()
+            .find(|(other, other_part)| {
+                other.column == edit.column && other.row == edit.row && *other_part == part
+            })
+            .is_some_and(|(last, _)| !std::ptr::eq(last, edit));
+        match cell {
+            Some(cell) if superseded || edit.value.matches(cell) => {
+                let mut value = cell.to_json();
+                if let Some(sheet) = &edit.sheet {
+                    value["sheet"] = json!(sheet);
+                }
+                read_back.push(value);
+            }
+            _ => {
+                return Err(unverified(format!(
+                    "{} did not read back with the requested value",
+                    xlsx::cell_name(edit.column, edit.row)
+                )));
+            }
+        }
+    }
+    Ok(read_back)
+}
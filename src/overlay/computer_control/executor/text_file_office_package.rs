@@ -0,0 +1,552 @@
+//! OOXML packages (`.xlsx`, `.docx`, `.pptx`) in memory: bounded part reads,
+//! relationship lookup, read views, and edits that rewrite only the parts
+//! they change. Untouched entries are copied raw, so embedded media, macros
+//! and custom XML keep their exact compressed bytes.
+
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read, Write};
+
+use quick_xml::events::Event;
+use serde_json::{Value, json};
+use zip::ZipArchive;
+
+#[path = "text_file_office_documents.rs"]
+mod documents;
+#[path = "text_file_office_edit.rs"]
+mod edit;
+#[path = "text_file_office_xlsx.rs"]
+mod xlsx;
+#[path = "text_file_office_xml.rs"]
+mod xml;
+
+use documents::Budget;
+use xlsx::CellValue;
+use xml::{Events, local};
+
+const MAX_ENTRIES: usize = 4_096;
+const MAX_PART_BYTES: u64 = 32 * 1024 * 1024;
+const MAX_INFLATED_BYTES: u64 = 96 * 1024 * 1024;
+const MAX_READ_CELLS: usize = 2_000;
+const DEFAULT_READ_CHARS: usize = 24_000;
+const MAX_READ_CHARS: usize = 64_000;
+const MAX_REPLACEMENTS: usize = 64;
+const MAX_CELL_EDITS: usize = 256;
+
+/// A typed, non-mutating failure: the caller maps `code` straight into the
+/// tool result.
+#[derive(Debug)]
+pub(super) struct OfficeError {
+    pub(super) code: &'static str,
+    pub(super) message: String,
+    pub(super) details: Value,
+}
+
+impl OfficeError {
+    pub(super) fn new(code: &'static str, message: String) -> Self {
+        Self {
+            code,
+            message,
+            details: Value::Null,
+        }
+    }
+
+    fn with_details(mut self, details: Value) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum OfficeKind {
+    Xlsx,
+    Docx,
+    Pptx,
+}
+
+impl OfficeKind {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            Self::Xlsx => "xlsx",
+            Self::Docx => "docx",
+            Self::Pptx => "pptx",
+        }
+    }
+}
+
+struct Package<'a> {
+    archive: ZipArchive<Cursor<&'a [u8]>>,
+    kind: OfficeKind,
+    inflated: u64,
+}
+
+impl<'a> Package<'a> {
+    fn open(bytes: &'a [u8]) -> Result<Self, OfficeError> {
+        let archive = ZipArchive::new(Cursor::new(bytes)).map_err(|error| {
+            OfficeError::new(
+                "ERR_OFFICE_FILE_NOT_OOXML",
+                format!(
+                    "not an Office Open XML package ({error}); password-protected and legacy .xls/.doc/.ppt files are not supported"
+                ),
+            )
+        })?;
+        if archive.len() > MAX_ENTRIES {
+            return Err(OfficeError::new(
+                "ERR_OFFICE_FILE_TOO_LARGE",
+                format!(
+                    "the package has {} entries; limit is {MAX_ENTRIES}",
+                    archive.len()
+                ),
+            ));
+        }
+        let has = |name: &str| archive.file_names().any(|entry| entry == name);
+        let kind = if has("xl/workbook.xml") {
+            OfficeKind::Xlsx
+        } else if has("word/document.xml") {
+            OfficeKind::Docx
+        } else if has("ppt/presentation.xml") {
+            OfficeKind::Pptx
+        } else {
+            return Err(OfficeError::new(
+                "ERR_OFFICE_FILE_UNSUPPORTED",
+                "the package is not a workbook, Word document, or presentation".to_string(),
+            ));
+        };
+        Ok(Self {
+            archive,
+            kind,
+            inflated: 0,
+        })
+    }
+
+    fn names(&self) -> Vec<String> {
+        self.archive.file_names().map(str::to_string).collect()
+    }
+
+    /// Bytes of one part, or `None` when the package has no such entry.
+    fn part(&mut self, name: &str) -> Result<Option<Vec<u8>>, OfficeError> {
+        let mut file = match self.archive.by_name(name) {
+            Ok(file) => file,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(error) => {
+                return Err(OfficeError::new(
+                    "ERR_OFFICE_FILE_MALFORMED",
+                    format!("cannot open {name}: {error}"),
+                ));
+            }
+        };
+        let too_large = || {
+            OfficeError::new(
+                "ERR_OFFICE_FILE_TOO_LARGE",
+                format!(
+                    "{name} inflates beyond the part limit of {MAX_PART_BYTES} bytes or the package limit of {MAX_INFLATED_BYTES} bytes"
+                ),
+            )
+        };
+        if file.size() > MAX_PART_BYTES || self.inflated + file.size() > MAX_INFLATED_BYTES {
+            return Err(too_large());
+        }
+        let mut bytes = Vec::with_capacity(file.size() as usize);
+        // The declared size is not trusted; the read itself is bounded too.
+        (&mut file)
+            .take(MAX_PART_BYTES + 1)
+            .read_to_end(&mut bytes)
+            .map_err(|error| {
+                OfficeError::new(
+                    "ERR_OFFICE_FILE_MALFORMED",
+                    format!("cannot inflate {name}: {error}"),
+                )
+            })?;
+        self.inflated += bytes.len() as u64;
+        if bytes.len() as u64 > MAX_PART_BYTES || self.inflated > MAX_INFLATED_BYTES {
+            return Err(too_large());
+        }
+        Ok(Some(bytes))
+    }
+
+    fn xml(&mut self, name: &str) -> Result<Option<Events>, OfficeError> {
+        match self.part(name)? {
+            Some(bytes) => xml::parse(&bytes, name).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn required_xml(&mut self, name: &str) -> Result<Events, OfficeError> {
+        self.xml(name)?.ok_or_else(|| {
+            OfficeError::new(
+                "ERR_OFFICE_FILE_MALFORMED",
+                format!("the package has no {name} part"),
+            )
+        })
+    }
+
+    /// Internal relationship targets of `part`, keyed by relationship id and
+    /// resolved to package entry names.
+    fn relationships(&mut self, part: &str) -> Result<BTreeMap<String, String>, OfficeError> {
+        let (folder, file) = part.rsplit_once('/').unwrap_or(("", part));
+        let rels = if folder.is_empty() {
+            format!("_rels/{file}.rels")
+        } else {
+            format!("{folder}/_rels/{file}.rels")
+        };
+        let Some(events) = self.xml(&rels)? else {
+            return Ok(BTreeMap::new());
+        };
+        Ok(events
+            .iter()
+            .filter_map(|event| match event {
+                Event::Start(element) | Event::Empty(element)
+                    if local(element.name().as_ref()) == b"Relationship"
+                        && xml::attribute(element, b"TargetMode").as_deref()
+                            != Some("External") =>
+                {
+                    let id = xml::attribute(element, b"Id")?;
+                    let target = xml::attribute(element, b"Target")?;
+                    Some((id, resolve_target(folder, &target)))
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Rebuilds the package with `changed` parts replaced, keeping entry order.
+    fn rewrite(&mut self, changed: &BTreeMap<String, Vec<u8>>) -> Result<Vec<u8>, OfficeError> {
+        let failed = |error: &dyn std::fmt::Display| {
+            OfficeError::new(
+                "ERR_OFFICE_FILE_REWRITE",
+                format!("could not rebuild the package: {error}"),
+            )
+        };
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for index in 0..self.archive.len() {
+            let entry = self.archive.by_index_raw(index).map_err(|e| failed(&e))?;
+            match changed.get(entry.name()) {
+                Some(bytes) => {
+                    let mut options = zip::write::SimpleFileOptions::default()
+                        .compression_method(zip::CompressionMethod::Deflated);
+                    if let Some(modified) = entry.last_modified() {
+                        options = options.last_modified_time(modified);
+                    }
+                    let name = entry.name().to_string();
+                    drop(entry);
+                    writer.start_file(name, options).map_err(|e| failed(&e))?;
+                    writer.write_all(bytes).map_err(|e| failed(&e))?;
+                }
+                None => writer.raw_copy_file(entry).map_err(|e| failed(&e))?,
+            }
+        }
+        let output = writer.finish().map_err(|e| failed(&e))?;
+        Ok(output.into_inner())
+    }
+}
+
+fn resolve_target(folder: &str, target: &str) -> String {
+    let joined = match target.strip_prefix('/') {
+        Some(absolute) => absolute.to_string(),
+        None if folder.is_empty() => target.to_string(),
+        None => format!("{folder}/{target}"),
+    };
+    let mut parts: Vec<&str> = Vec::new();
+    for segment in joined.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            segment => parts.push(segment),
+        }
+    }
+    parts.join("/")
+}
+
+/// Parsed `read_office_file` options.
+pub(super) struct ReadRequest {
+    pub(super) sheet: Option<String>,
+    pub(super) range: Option<String>,
+    pub(super) max_chars: usize,
+}
+
+impl ReadRequest {
+    pub(super) fn from_args(args: &Value) -> Result<Self, OfficeError> {
+        let text = |field: &str| {
+            args.get(field)
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        let range = text("range");
+        if let Some(range) = &range
+            && xlsx::parse_range(range).is_none()
+        {
+            return Err(OfficeError::new(
+                "ERR_OFFICE_FILE_BAD_ARGUMENT",
+                format!("range {range:?} is not an A1 cell or range such as B2:D40"),
+            ));
+        }
+        Ok(Self {
+            sheet: text("sheet"),
+            range,
+            max_chars: args
+                .get("max_chars")
+                .and_then(Value::as_u64)
+                .map(|value| value.clamp(1, MAX_READ_CHARS as u64) as usize)
+                .unwrap_or(DEFAULT_READ_CHARS),
+        })
+    }
+}
+
+/// Structured contents of a package: sheets and cells, or paragraphs and
+/// tables, or slides.
+pub(super) fn read(bytes: &[u8], request: &ReadRequest) -> Result<Value, OfficeError> {
+    let mut package = Package::open(bytes)?;
+    let mut budget = Budget::new(request.max_chars);
+    let mut view = match package.kind {
+        OfficeKind::Xlsx => read_workbook(&mut package, request, &mut budget)?,
+        OfficeKind::Docx => {
+            if request.sheet.is_some() || request.range.is_some() {
+                return Err(not_a_workbook());
+            }
+            let document = package.required_xml("word/document.xml")?;
+            let (paragraphs, tables) = documents::docx_outline(&document, &mut budget);
+            json!({ "paragraphs": paragraphs, "tables": tables })
+        }
+        OfficeKind::Pptx => {
+            if request.sheet.is_some() || request.range.is_some() {
+                return Err(not_a_workbook());
+            }
+            let mut slides = Vec::new();
+            for (index, part) in slide_parts(&mut package)?.into_iter().enumerate() {
+                let slide = package.required_xml(&part)?;
+                slides.push(json!({
+                    "index": index + 1,
+                    "paragraphs": documents::slide_paragraphs(&slide, &mut budget),
+                }));
+            }
+            json!({ "slides": slides })
+        }
+    };
+    view["kind"] = json!(package.kind.as_str());
+    view["truncated"] = json!(budget.truncated);
+    Ok(view)
+}
+
+fn not_a_workbook() -> OfficeError {
+    OfficeError::new(
+        "ERR_OFFICE_FILE_BAD_ARGUMENT",
+        "sheet and range apply only to .xlsx workbooks".to_string(),
+    )
+}
+
+fn read_workbook(
+    package: &mut Package,
+    request: &ReadRequest,
+    budget: &mut Budget,
+) -> Result<Value, OfficeError> {
+    let (sheets, sheet_index, part) = sheet_part(package, request.sheet.as_deref())?;
+    let shared = match package.xml("xl/sharedStrings.xml")? {
+        Some(events) => xlsx::shared_strings(&events),
+        None => Vec::new(),
+    };
+    let events = package.required_xml(&part)?;
+    let bounds = request.range.as_deref().and_then(xlsx::parse_range);
+    let mut cells = Vec::new();
+    let mut truncated_cells = false;
+    for cell in xlsx::cells(&events, &shared) {
+        if cell.kind == "empty" && cell.formula.is_none() {
+            continue;
+        }
+        if let Some((c1, r1, c2, r2)) = bounds
+            && !((c1..=c2).contains(&cell.column) && (r1..=r2).contains(&cell.row))
+        {
+            continue;
+        }
+        if cells.len() == MAX_READ_CELLS {
+            truncated_cells = true;
+            break;
+        }
+        let mut value = cell.to_json();
+        if let Some(text) = value["value"].as_str().map(str::to_string) {
+            match budget.take(&text) {
+                Some(kept) => value["value"] = json!(kept),
+                None => break,
+            }
+        }
+        cells.push(value);
+    }
+    budget.truncated |= truncated_cells;
+    Ok(json!({
+        "sheets": sheets.iter().map(|sheet| {
+            let mut value = json!({ "name": sheet.name });
+            if let Some(state) = &sheet.state {
+                value["state"] = json!(state);
+            }
+            value
+        }).collect::<Vec<_>>(),
+        "sheet": sheets[sheet_index].name,
+        "range": request.range,
+        "cells": cells,
+    }))
+}
+
+/// Sheets of the workbook, the index of the one `name` selects (the first
+/// when `None`), and that sheet's part name.
+fn sheet_part(
+    package: &mut Package,
+    name: Option<&str>,
+) -> Result<(Vec<xlsx::Sheet>, usize, String), OfficeError> {
+    let workbook = package.required_xml("xl/workbook.xml")?;
+    let sheets = xlsx::sheets(&workbook);
+    let index = match name {
+        Some(name) => sheets
+            .iter()
+            .position(|sheet| sheet.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                OfficeError::new(
+                    "ERR_OFFICE_FILE_SHEET_MISSING",
+                    format!("the workbook has no sheet named {name:?}"),
+                )
+                .with_details(json!({
+                    "sheets": sheets.iter().map(|sheet| &sheet.name).collect::<Vec<_>>(),
+                }))
+            })?,
+        None if sheets.is_empty() => {
+            return Err(OfficeError::new(
+                "ERR_OFFICE_FILE_MALFORMED",
+                "the workbook lists no sheets".to_string(),
+            ));
+        }
+        None => 0,
+    };
+    let relationships = package.relationships("xl/workbook.xml")?;
+    let part = relationships
+        .get(&sheets[index].relationship)
+        .cloned()
+        .ok_or_else(|| {
+            OfficeError::new(
+                "ERR_OFFICE_FILE_MALFORMED",
+                format!(
+                    "sheet {:?} points at a missing relationship",
+                    sheets[index].name
+                ),
+            )
+        })?;
+    Ok((sheets, index, part))
+}
+
+fn slide_parts(package: &mut Package) -> Result<Vec<String>, OfficeError> {
+    let presentation = package.required_xml("ppt/presentation.xml")?;
+    let relationships = package.relationships("ppt/presentation.xml")?;
+    Ok(documents::slide_order(&presentation)
+        .iter()
+        .filter_map(|id| relationships.get(id).cloned())
+        .collect())
+}
+
+struct TextReplacement {
+    old_text: String,
+    new_text: String,
+    expected_count: usize,
+}
+
+struct CellEdit {
+    sheet: Option<String>,
+    column: u32,
+    row: u32,
+    value: CellValue,
+}
+
+/// Parsed `edit_office_file` arguments.
+pub(super) struct EditRequest {
+    replacements: Vec<TextReplacement>,
+    cells: Vec<CellEdit>,
+}
+
+/// The rebuilt package and what changed in it.
+pub(super) struct EditOutcome {
+    pub(super) kind: OfficeKind,
+    pub(super) bytes: Vec<u8>,
+    pub(super) replacements_applied: usize,
+    pub(super) parts_changed: Vec<String>,
+    /// Edited cells as read back from the rebuilt package.
+    pub(super) cells: Vec<Value>,
+    pub(super) recalculation_requested: bool,
+}
+
+fn bad_argument(message: String) -> OfficeError {
+    OfficeError::new("ERR_OFFICE_FILE_BAD_ARGUMENT", message)
+}
+
+impl EditRequest {
+    pub(super) fn from_args(args: &Value) -> Result<Self, OfficeError> {
+        let list = |field: &str, limit: usize| match args.get(field) {
+            None | Some(Value::Null) => Ok(&[][..]),
+            Some(Value::Array(items)) if items.len() <= limit => Ok(items.as_slice()),
+            Some(_) => Err(bad_argument(format!(
+                "{field} must be an array of at most {limit} items"
+            ))),
+        };
+        let replacements = list("replacements", MAX_REPLACEMENTS)?
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let old_text = item.get("old_text").and_then(Value::as_str);
+                let new_text = item.get("new_text").and_then(Value::as_str);
+                let expected_count = item.get("expected_count").and_then(Value::as_u64);
+                match (old_text, new_text, expected_count) {
+                    (Some(old_text), Some(new_text), Some(expected_count))
+                        if !old_text.is_empty() && expected_count > 0 =>
+                    {
+                        Ok(TextReplacement {
+                            old_text: old_text.to_string(),
+                            new_text: new_text.to_string(),
+                            expected_count: expected_count as usize,
+                        })
+                    }
+                    _ => Err(bad_argument(format!(
+                        "replacement {index} needs non-empty old_text, string new_text, and positive integer expected_count"
+                    ))),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let cells = list("cells", MAX_CELL_EDITS)?
+            .iter()
+            .enumerate()
+            .map(|(index, item)| {
+                let position = item
+                    .get("cell")
+                    .and_then(Value::as_str)
+                    .and_then(xlsx::parse_cell);
+                let value = CellValue::from_edit(item);
+                let sheet = item.get("sheet").and_then(Value::as_str);
+                match (position, value) {
+                    (Some((column, row)), Some(value)) => Ok(CellEdit {
+                        sheet: sheet.map(str::to_string),
+                        column,
+                        row,
+                        value,
+                    }),
+                    _ => Err(bad_argument(format!(
+                        "cell edit {index} needs an A1 cell and exactly one of text, number, boolean, or clear: true"
+                    ))),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if replacements.is_empty() && cells.is_empty() {
+            return Err(bad_argument(
+                "supply at least one replacement or cell edit".to_string(),
+            ));
+        }
+        Ok(Self {
+            replacements,
+            cells,
+        })
+    }
+}
+
+pub(super) fn edit(bytes: &[u8], request: &EditRequest) -> Result<EditOutcome, OfficeError> {
+    edit::apply(&mut Package::open(bytes)?, request)
+}
+
+#[cfg(test)]
+#[path = "text_file_office_tests.rs"]
+mod tests;
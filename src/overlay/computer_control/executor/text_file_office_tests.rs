@@ -0,0 +1,331 @@
+use super::*;
+
+const W: &str = r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main""#;
+const RELS: &str = r#"xmlns="http://schemas.openxmlformats.org/package/2006/relationships""#;
+
+fn package(parts: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    for (name, bytes) in parts {
+        let options = if name.ends_with(".png") {
+            zip::write::SimpleFileOptions::default()
+                .compression_method(zip::CompressionMethod::Stored)
+        } else {
+            zip::write::SimpleFileOptions::default()
+        };
+        writer.start_file(*name, options).unwrap();
+        writer.write_all(bytes).unwrap();
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+fn part_text(bytes: &[u8], name: &str) -> String {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).unwrap();
+    let mut text = String::new();
+    archive
+        .by_name(name)
+        .unwrap()
+        .read_to_string(&mut text)
+        .unwrap();
+    text
+}
+
+fn docx() -> Vec<u8> {
+    let document = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document {W}><w:body>
+<w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>Quarterly report</w:t></w:r></w:p>
+<w:p><w:r><w:rPr><w:b/></w:rPr><w:t xml:space="preserve">Revenue was </w:t></w:r><w:r><w:rPr><w:i/></w:rPr><w:t>12 &amp; rising</w:t></w:r><w:r><w:t>.</w:t></w:r></w:p>
+<w:tbl><w:tr><w:tc><w:p><w:r><w:t>Region</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Total</w:t></w:r></w:p></w:tc></w:tr>
+<w:tr><w:tc><w:p><w:r><w:t>North</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>12</w:t></w:r></w:p></w:tc></w:tr></w:tbl>
+<w:p/>
+</w:body></w:document>"#
+    );
+    let footer = format!(r#"<w:ftr {W}><w:p><w:r><w:t>Draft 12</w:t></w:r></w:p></w:ftr>"#);
+    package(&[
+        ("[Content_Types].xml", b"<Types/>"),
+        ("word/document.xml", document.as_bytes()),
+        ("word/footer1.xml", footer.as_bytes()),
+        ("word/media/image1.png", b"\x89PNG not really"),
+    ])
+}
+
+fn xlsx() -> Vec<u8> {
+    let workbook = r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">
+<sheets><sheet name="Summary" sheetId="1" r:id="rId2"/><sheet name="Data" sheetId="2" r:id="rId1" state="hidden"/></sheets>
+<calcPr calcId="191029"/></workbook>"#;
+    let rels = format!(
+        r#"<Relationships {RELS}><Relationship Id="rId1" Type="worksheet" Target="worksheets/sheet1.xml"/><Relationship Id="rId2" Type="worksheet" Target="/xl/worksheets/sheet2.xml"/></Relationships>"#
+    );
+    let shared = r#"<sst xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" count="3" uniqueCount="3"><si><t>Region</t></si><si><r><rPr><b/></rPr><t>No</t></r><r><t>rth</t></r><rPh><t>ノース</t></rPh></si><si/></sst>"#;
+    let summary = r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>
+<row r="1" spans="1:3"><c r="A1" s="4" t="s"><v>0</v></c><c r="C1"><v>12.5</v></c></row>
+<row r="3"><c r="A3" t="s"><v>1</v></c><c r="B3"><f>C1*2</f><v>25</v></c><c r="C3" t="b"><v>1</v></c></row>
+</sheetData></worksheet>"#;
+    let data = r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData/></worksheet>"#;
+    package(&[
+        ("[Content_Types].xml", b"<Types/>"),
+        ("xl/workbook.xml", workbook.as_bytes()),
+        ("xl/_rels/workbook.xml.rels", rels.as_bytes()),
+        ("xl/sharedStrings.xml", shared.as_bytes()),
+        ("xl/worksheets/sheet1.xml", data.as_bytes()),
+        ("xl/worksheets/sheet2.xml", summary.as_bytes()),
+        ("xl/media/image1.png", b"\x89PNG chart"),
+    ])
+}
+
+fn read_args(args: Value) -> ReadRequest {
+    ReadRequest::from_args(&args).unwrap()
+}
+
+fn edit_args(args: Value) -> EditRequest {
+    EditRequest::from_args(&args).unwrap()
+}
+
+#[test]
+fn docx_read_reports_styled_paragraphs_and_tables() {
+    let view = read(&docx(), &read_args(json!({}))).unwrap();
+    assert_eq!(view["kind"], "docx");
+    assert_eq!(
+        view["paragraphs"],
+        json!([
+            { "index": 0, "style": "Heading1", "text": "Quarterly report" },
+            { "index": 1, "text": "Revenue was 12 & rising." },
+        ])
+    );
+    assert_eq!(
+        view["tables"],
+        json!([{ "index": 0, "rows": [["Region", "Total"], ["North", "12"]] }])
+    );
+    assert_eq!(view["truncated"], false);
+
+    let short = read(&docx(), &read_args(json!({ "max_chars": 20 }))).unwrap();
+    assert_eq!(short["truncated"], true);
+    assert_eq!(short["paragraphs"][1]["text"], "Reve");
+}
+
+#[test]
+fn docx_replacement_spanning_runs_keeps_run_formatting() {
+    let original = docx();
+    let outcome = edit(
+        &original,
+        &edit_args(json!({ "replacements": [
+            { "old_text": "was 12 & rising", "new_text": "is 14 <up>", "expected_count": 1 },
+            { "old_text": "Draft", "new_text": " Final", "expected_count": 1 },
+        ]})),
+    )
+    .unwrap();
+    assert_eq!(outcome.replacements_applied, 2);
+    assert_eq!(
+        outcome.parts_changed,
+        ["word/document.xml", "word/footer1.xml"]
+    );
+    let document = part_text(&outcome.bytes, "word/document.xml");
+    assert!(document.contains(
+        r#"<w:r><w:rPr><w:b/></w:rPr><w:t xml:space="preserve">Revenue is 14 &lt;up&gt;</w:t></w:r><w:r><w:rPr><w:i/></w:rPr><w:t></w:t></w:r><w:r><w:t>.</w:t></w:r>"#
+    ));
+    let footer = part_text(&outcome.bytes, "word/footer1.xml");
+    assert!(footer.contains(r#"<w:t xml:space="preserve"> Final 12</w:t>"#));
+
+    let view = read(&outcome.bytes, &read_args(json!({}))).unwrap();
+    assert_eq!(view["paragraphs"][1]["text"], "Revenue is 14 <up>.");
+    // Untouched entries are copied byte for byte.
+    let mut before = ZipArchive::new(Cursor::new(original.as_slice())).unwrap();
+    let mut after = ZipArchive::new(Cursor::new(outcome.bytes.as_slice())).unwrap();
+    assert_eq!(
+        before.by_name("word/media/image1.png").unwrap().crc32(),
+        after.by_name("word/media/image1.png").unwrap().crc32()
+    );
+    assert_eq!(
+        after.file_names().collect::<Vec<_>>(),
+        before.file_names().collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn replacement_counts_span_every_text_part_and_must_match() {
+    let error = edit(
+        &docx(),
+        &edit_args(json!({ "replacements": [
+            { "old_text": "12", "new_text": "13", "expected_count": 1 },
+        ]})),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(error.code, "ERR_OFFICE_FILE_MATCH_AMBIGUOUS");
+    assert_eq!(error.details["actual_count"], 3);
+
+    let error = edit(
+        &docx(),
+        &edit_args(json!({ "replacements": [
+            { "old_text": "Revenue was", "new_text": "x", "expected_count": 1 },
+            { "old_text": "was 12", "new_text": "y", "expected_count": 1 },
+        ]})),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(error.code, "ERR_OFFICE_FILE_OVERLAPPING_REPLACEMENTS");
+
+    let error = edit(
+        &docx(),
+        &edit_args(json!({ "cells": [{ "cell": "A1", "number": 1 }] })),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(error.code, "ERR_OFFICE_FILE_BAD_ARGUMENT");
+}
+
+#[test]
+fn xlsx_read_lists_sheets_and_resolves_cells() {
+    let view = read(&xlsx(), &read_args(json!({}))).unwrap();
+    assert_eq!(
+        view["sheets"],
+        json!([{ "name": "Summary" }, { "name": "Data", "state": "hidden" }])
+    );
+    assert_eq!(view["sheet"], "Summary");
+    assert_eq!(
+        view["cells"],
+        json!([
+            { "cell": "A1", "type": "text", "value": "Region" },
+            { "cell": "C1", "type": "number", "value": "12.5" },
+            { "cell": "A3", "type": "text", "value": "North" },
+            { "cell": "B3", "type": "number", "value": "25", "formula": "C1*2" },
+            { "cell": "C3", "type": "bool", "value": true },
+        ])
+    );
+    let ranged = read(&xlsx(), &read_args(json!({ "range": "B1:C3" }))).unwrap();
+    assert_eq!(ranged["cells"].as_array().unwrap().len(), 3);
+
+    let error = read(&xlsx(), &read_args(json!({ "sheet": "Missing" })))
+        .err()
+        .unwrap();
+    assert_eq!(error.code, "ERR_OFFICE_FILE_SHEET_MISSING");
+    assert_eq!(error.details["sheets"], json!(["Summary", "Data"]));
+    assert!(ReadRequest::from_args(&json!({ "range": "1A" })).is_err());
+}
+
+#[test]
+fn xlsx_cell_edits_keep_styles_insert_in_order_and_refuse_formulas() {
+    let outcome = edit(
+        &xlsx(),
+        &edit_args(json!({ "cells": [
+            { "cell": "A1", "text": " Area" },
+            { "cell": "B1", "number": 7 },
+            { "cell": "B2", "boolean": false },
+            { "cell": "C3", "clear": true },
+            { "sheet": "data", "cell": "$D$4", "text": "x" },
+        ]})),
+    )
+    .unwrap();
+    assert!(outcome.recalculation_requested);
+    assert_eq!(
+        outcome.parts_changed,
+        [
+            "xl/workbook.xml",
+            "xl/worksheets/sheet1.xml",
+            "xl/worksheets/sheet2.xml"
+        ]
+    );
+    assert_eq!(
+        outcome.cells[4],
+        json!({ "cell": "D4", "type": "text", "value": "x", "sheet": "data" })
+    );
+    let summary = part_text(&outcome.bytes, "xl/worksheets/sheet2.xml");
+    assert!(summary.contains(
+        r#"<row r="1"><c r="A1" s="4" t="inlineStr"><is><t xml:space="preserve"> Area</t></is></c><c r="B1"><v>7</v></c><c r="C1"><v>12.5</v></c></row>
+<row r="2"><c r="B2" t="b"><v>0</v></c></row>"#
+    ));
+    assert!(summary.contains(r#"<c r="C3"></c>"#));
+    assert!(
+        part_text(&outcome.bytes, "xl/workbook.xml")
+            .contains(r#"<calcPr calcId="191029" fullCalcOnLoad="1"/>"#)
+    );
+    let data = part_text(&outcome.bytes, "xl/worksheets/sheet1.xml");
+    assert!(data.contains(
+        r#"<sheetData><row r="4"><c r="D4" t="inlineStr"><is><t>x</t></is></c></row></sheetData>"#
+    ));
+
+    let error = edit(
+        &xlsx(),
+        &edit_args(json!({ "cells": [{ "cell": "B3", "number": 1 }] })),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(error.code, "ERR_OFFICE_FILE_FORMULA_CELL");
+}
+
+#[test]
+fn xlsx_replacements_skip_phonetic_runs() {
+    let outcome = edit(
+        &xlsx(),
+        &edit_args(json!({ "replacements": [
+            { "old_text": "North", "new_text": "South", "expected_count": 1 },
+        ]})),
+    )
+    .unwrap();
+    assert_eq!(outcome.parts_changed, ["xl/sharedStrings.xml"]);
+    let shared = part_text(&outcome.bytes, "xl/sharedStrings.xml");
+    assert!(shared.contains(
+        "<si><r><rPr><b/></rPr><t>South</t></r><r><t></t></r><rPh><t>ノース</t></rPh></si>"
+    ));
+}
+
+#[test]
+fn pptx_slides_follow_presentation_order() {
+    let slide = |text: &str| {
+        format!(
+            r#"<p:sld xmlns:p="p" xmlns:a="a"><p:cSld><p:spTree><p:sp><p:txBody><a:p><a:r><a:t>{text}</a:t></a:r></a:p><a:p/></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#
+        )
+    };
+    let presentation = r#"<p:presentation xmlns:p="p" xmlns:r="r"><p:sldIdLst><p:sldId id="256" r:id="rId7"/><p:sldId id="257" r:id="rId3"/></p:sldIdLst></p:presentation>"#;
+    let rels = format!(
+        r#"<Relationships {RELS}><Relationship Id="rId3" Target="slides/slide1.xml"/><Relationship Id="rId7" Target="slides/slide2.xml"/><Relationship Id="rId9" TargetMode="External" Target="https://example.com"/></Relationships>"#
+    );
+    let (first, second) = (slide("Agenda"), slide("Title"));
+    let bytes = package(&[
+        ("ppt/presentation.xml", presentation.as_bytes()),
+        ("ppt/_rels/presentation.xml.rels", rels.as_bytes()),
+        ("ppt/slides/slide1.xml", first.as_bytes()),
+        ("ppt/slides/slide2.xml", second.as_bytes()),
+    ]);
+    let view = read(&bytes, &read_args(json!({}))).unwrap();
+    assert_eq!(view["kind"], "pptx");
+    assert_eq!(
+        view["slides"],
+        json!([
+            { "index": 1, "paragraphs": ["Title"] },
+            { "index": 2, "paragraphs": ["Agenda"] },
+        ])
+    );
+    let outcome = edit(
+        &bytes,
+        &edit_args(json!({ "replacements": [
+            { "old_text": "Agenda", "new_text": "Plan", "expected_count": 1 },
+        ]})),
+    )
+    .unwrap();
+    assert_eq!(outcome.parts_changed, ["ppt/slides/slide1.xml"]);
+}
+
+#[test]
+fn non_packages_and_empty_requests_are_rejected() {
+    let error = read(b"PK not a zip", &read_args(json!({}))).err().unwrap();
+    assert_eq!(error.code, "ERR_OFFICE_FILE_NOT_OOXML");
+    let error = read(&package(&[("readme.txt", b"hi")]), &read_args(json!({})))
+        .err()
+        .unwrap();
+    assert_eq!(error.code, "ERR_OFFICE_FILE_UNSUPPORTED");
+    assert!(EditRequest::from_args(&json!({})).is_err());
+    assert!(
+        EditRequest::from_args(&json!({ "cells": [{ "cell": "A1", "text": "a", "number": 1 }] }))
+            .is_err()
+    );
+    let error = edit(
+        &docx(),
+        &edit_args(json!({ "replacements": [
+            { "old_text": "Total", "new_text": "Total", "expected_count": 1 },
+        ]})),
+    )
+    .err()
+    .unwrap();
+    assert_eq!(error.code, "ERR_OFFICE_FILE_NO_CHANGE");
+}
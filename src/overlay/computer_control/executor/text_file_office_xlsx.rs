@@ -0,0 +1,446 @@
+//! Workbook parts: sheet listing, cell reads over an A1 range, and in-place
+//! cell writes that keep the cell style and refuse to overwrite formulas.
+
+use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+use serde_json::{Value, json};
+
+use super::OfficeError;
+use super::xml::{self, Events, attribute, closing_index, local, with_attribute};
+
+const MAX_COLUMN: u32 = 16_384;
+const MAX_ROW: u32 = 1_048_576;
+
+/// One worksheet listed in `xl/workbook.xml`.
+pub(super) struct Sheet {
+    pub(super) name: String,
+    pub(super) relationship: String,
+    pub(super) state: Option<String>,
+}
+
+pub(super) fn sheets(workbook: &Events) -> Vec<Sheet> {
+    workbook
+        .iter()
+        .filter_map(|event| match event {
+            Event::Start(element) | Event::Empty(element)
+                if local(element.name().as_ref()) == b"sheet" =>
+            {
+                Some(Sheet {
+                    name: attribute(element, b"name")?,
+                    relationship: xml::relationship_id(element)?,
+                    state: attribute(element, b"state"),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Shared string table entries, one per `<si>`, including empty ones.
+pub(super) fn shared_strings(events: &Events) -> Vec<String> {
+    xml::paragraphs(events, &[b"si"])
+        .iter()
+        .map(xml::Paragraph::text)
+        .collect()
+}
+
+/// 1-based column and row of an A1 reference such as `B12` or `$B$12`.
+pub(super) fn parse_cell(reference: &str) -> Option<(u32, u32)> {
+    let reference = reference.trim().replace('$', "");
+    let split = reference.find(|c: char| c.is_ascii_digit())?;
+    let (letters, digits) = reference.split_at(split);
+    if letters.is_empty() || letters.len() > 3 || !letters.chars().all(|c| c.is_ascii_alphabetic())
+    {
+        return None;
+    }
+    let column = letters.bytes().fold(0u32, |acc, byte| {
+        acc * 26 + u32::from(byte.to_ascii_uppercase() - b'A' + 1)
+    });
+    let row: u32 = digits.parse().ok()?;
+    ((1..=MAX_COLUMN).contains(&column) && (1..=MAX_ROW).contains(&row)).then_some((column, row))
+}
+
+pub(super) fn cell_name(column: u32, row: u32) -> String {
+    let mut letters = Vec::new();
+    let mut remaining = column;
+    while remaining > 0 {
+        let digit = (remaining - 1) % 26;
+        letters.push(b'A' + digit as u8);
+        remaining = (remaining - 1) / 26;
+    }
+    letters.reverse();
+    format!("{}{row}", String::from_utf8_lossy(&letters))
+}
+
+/// Inclusive `(first column, first row, last column, last row)` of `A1:C9`
+/// or a single cell.
+pub(super) fn parse_range(range: &str) -> Option<(u32, u32, u32, u32)> {
+    let (first, last) = range.split_once(':').unwrap_or((range, range));
+    let (c1, r1) = parse_cell(first)?;
+    let (c2, r2) = parse_cell(last)?;
+    Some((c1.min(c2), r1.min(r2), c1.max(c2), r1.max(r2)))
+}
+
+/// A cell as read back from a worksheet.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Cell {
+    pub(super) column: u32,
+    pub(super) row: u32,
+    pub(super) kind: &'static str,
+    pub(super) value: Value,
+    pub(super) formula: Option<String>,
+    pub(super) style: Option<String>,
+}
+
+impl Cell {
+    pub(super) fn to_json(&self) -> Value {
+        let mut value = json!({
+            "cell": cell_name(self.column, self.row),
+            "type": self.kind,
+            "value": self.value,
+        });
+        if let Some(formula) = &self.formula {
+            value["formula"] = json!(formula);
+        }
+        value
+    }
+}
+
+/// Every cell of a worksheet in document order. Rows and cells without an
+/// `r` attribute take the position after their predecessor, as Excel does.
+pub(super) fn cells(sheet: &Events, shared: &[String]) -> Vec<Cell> {
+    let mut found = Vec::new();
+    let mut row = 0u32;
+    let mut column = 0u32;
+    let mut index = 0;
+    while index < sheet.len() {
+        match &sheet[index] {
+            Event::Start(element) | Event::Empty(element)
+                if local(element.name().as_ref()) == b"row" =>
+            {
+                row = attribute(element, b"r")
+                    .and_then(|r| r.parse().ok())
+                    .unwrap_or(row + 1);
+                column = 0;
+            }
+            Event::Start(element) if local(element.name().as_ref()) == b"c" => {
+                let end = closing_index(sheet, index);
+                column = cell_column(element).unwrap_or(column + 1);
+                found.push(read_cell(
+                    element,
+                    &sheet[index + 1..end],
+                    column,
+                    row,
+                    shared,
+                ));
+                index = end;
+            }
+            Event::Empty(element) if local(element.name().as_ref()) == b"c" => {
+                column = cell_column(element).unwrap_or(column + 1);
+                found.push(read_cell(element, &[], column, row, shared));
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    found
+}
+
+fn cell_column(element: &BytesStart) -> Option<u32> {
+    attribute(element, b"r")
+        .and_then(|reference| parse_cell(&reference))
+        .map(|(column, _)| column)
+}
+
+fn read_cell(
+    element: &BytesStart,
+    content: &[Event<'static>],
+    column: u32,
+    row: u32,
+    shared: &[String],
+) -> Cell {
+    let cell_type = attribute(element, b"t").unwrap_or_default();
+    let mut raw_value = None;
+    let mut formula = None;
+    let mut index = 0;
+    while index < content.len() {
+        if let Event::Start(child) = &content[index] {
+            let end = closing_index(content, index);
+            let text: String = content[index + 1..end]
+                .iter()
+                .filter_map(xml::text_of)
+                .collect();
+            match local(child.name().as_ref()) {
+                b"v" => raw_value = Some(text),
+                b"f" => formula = Some(text),
+                b"is" => raw_value = Some(text),
+                _ => {}
+            }
+            index = end;
+        } else if let Event::Empty(child) = &content[index]
+            && local(child.name().as_ref()) == b"f"
+        {
+            // A shared-formula follower: the formula lives on another cell.
+            formula = Some(String::new());
+        }
+        index += 1;
+    }
+    let (kind, value) = match (cell_type.as_str(), raw_value) {
+        (_, None) => ("empty", Value::Null),
+        ("s", Some(raw)) => (
+            "text",
+            raw.trim()
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| shared.get(index))
+                .map_or(Value::Null, |text| json!(text)),
+        ),
+        ("inlineStr" | "str", Some(raw)) => ("text", json!(raw)),
+        ("b", Some(raw)) => ("bool", json!(raw.trim() == "1")),
+        ("e", Some(raw)) => ("error", json!(raw)),
+        (_, Some(raw)) => ("number", json!(raw)),
+    };
+    Cell {
+        column,
+        row,
+        kind,
+        value,
+        formula,
+        style: attribute(element, b"s"),
+    }
+}
+
+/// Requested new content of one cell.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum CellValue {
+    Text(String),
+    /// Kept as the caller wrote it so no float formatting is introduced.
+    Number(String),
+    Bool(bool),
+    Clear,
+}
+
+impl CellValue {
+    /// Reads a cell edit that sets exactly one of `text`, `number`,
+    /// `boolean`, or `clear: true`.
+    pub(super) fn from_edit(edit: &Value) -> Option<Self> {
+        let mut values = [
+            edit.get("text")
+                .and_then(Value::as_str)
+                .map(|text| Self::Text(text.to_string())),
+            edit.get("number")
+                .and_then(Value::as_number)
+                .filter(|number| number.as_f64().is_some_and(f64::is_finite))
+                .map(|number| Self::Number(number.to_string())),
+            edit.get("boolean").and_then(Value::as_bool).map(Self::Bool),
+            (edit.get("clear").and_then(Value::as_bool) == Some(true)).then_some(Self::Clear),
+        ]
+        .into_iter()
+        .flatten();
+        let value = values.next()?;
+        values.next().is_none().then_some(value)
+    }
+
+    /// Whether `cell`, as read back, holds this value.
+    pub(super) fn matches(&self, cell: &Cell) -> bool {
+        match self {
+            Self::Text(text) => cell.kind == "text" && cell.value == json!(text),
+            Self::Number(number) => cell.kind == "number" && cell.value == json!(number),
+            Self::Bool(flag) => cell.kind == "bool" && cell.value == json!(flag),
+            Self::Clear => cell.kind == "empty",
+        }
+    }
+}
+
+/// Writes `value` into the cell at `column`/`row`, creating the row and cell
+/// in sorted position when they do not exist yet.
+pub(super) fn set_cell(
+    sheet: &Events,
+    column: u32,
+    row: u32,
+    value: &CellValue,
+) -> Result<Events, OfficeError> {
+    let mut events = sheet.clone();
+    let data = match events
+        .iter()
+        .position(|event| is_element(event, b"sheetData"))
+    {
+        Some(index) => index,
+        None => {
+            return Err(OfficeError::new(
+                "ERR_OFFICE_FILE_MALFORMED",
+                "the worksheet has no sheetData element".to_string(),
+            ));
+        }
+    };
+    let data_end = expand_empty(&mut events, data);
+
+    let mut current_row = 0u32;
+    let mut index = data + 1;
+    let (row_start, row_end) = loop {
+        if index >= data_end {
+            events.insert(index, start_tag("row", &[("r", &row.to_string())]));
+            events.insert(index + 1, end_tag("row"));
+            break (index, index + 1);
+        }
+        match &events[index] {
+            Event::Start(element) | Event::Empty(element)
+                if local(element.name().as_ref()) == b"row" =>
+            {
+                current_row = attribute(element, b"r")
+                    .and_then(|r| r.parse().ok())
+                    .unwrap_or(current_row + 1);
+                if current_row == row {
+                    // Column spans are an optional hint; drop it rather than
+                    // let it contradict a newly inserted cell.
+                    let updated = with_attribute(element, "spans", None);
+                    events[index] = match &events[index] {
+                        Event::Empty(_) => Event::Empty(updated),
+                        _ => Event::Start(updated),
+                    };
+                    let end = expand_empty(&mut events, index);
+                    break (index, end);
+                }
+                if current_row > row {
+                    events.insert(index, start_tag("row", &[("r", &row.to_string())]));
+                    events.insert(index + 1, end_tag("row"));
+                    break (index, index + 1);
+                }
+                index = match &events[index] {
+                    Event::Start(_) => closing_index(&events, index),
+                    _ => index,
+                };
+            }
+            _ => {}
+        }
+        index += 1;
+    };
+
+    let reference = cell_name(column, row);
+    let mut current_column = 0u32;
+    let mut index = row_start + 1;
+    while index < row_end {
+        let (element, end) = match &events[index] {
+            Event::Start(element) if local(element.name().as_ref()) == b"c" => {
+                (element.clone(), closing_index(&events, index))
+            }
+            Event::Empty(element) if local(element.name().as_ref()) == b"c" => {
+                (element.clone(), index)
+            }
+            Event::Start(_) => {
+                index = closing_index(&events, index) + 1;
+                continue;
+            }
+            _ => {
+                index += 1;
+                continue;
+            }
+        };
+        current_column = cell_column(&element).unwrap_or(current_column + 1);
+        if current_column == column {
+            if events[index..=end]
+                .iter()
+                .any(|event| is_element(event, b"f"))
+            {
+                return Err(OfficeError::new(
+                    "ERR_OFFICE_FILE_FORMULA_CELL",
+                    format!("{reference} holds a formula; formula cells are never overwritten"),
+                ));
+            }
+            let style = attribute(&element, b"s");
+            let replacement = cell_events(&reference, style.as_deref(), value);
+            events.splice(index..=end, replacement);
+            return Ok(events);
+        }
+        if current_column > column {
+            break;
+        }
+        index = end + 1;
+    }
+    let insert_at = index.min(row_end);
+    events.splice(insert_at..insert_at, cell_events(&reference, None, value));
+    Ok(events)
+}
+
+/// Asks Excel to recalculate every formula when the workbook next opens, so
+/// cached results of formulas that depend on edited cells are not trusted.
+pub(super) fn request_full_calculation(workbook: &Events) -> Option<Events> {
+    let mut events = workbook.clone();
+    let index = events
+        .iter()
+        .position(|event| is_element(event, b"calcPr"))?;
+    events[index] = match &events[index] {
+        Event::Start(element) => Event::Start(with_attribute(element, "fullCalcOnLoad", Some("1"))),
+        Event::Empty(element) => Event::Empty(with_attribute(element, "fullCalcOnLoad", Some("1"))),
+        _ => return None,
+    };
+    Some(events)
+}
+
+fn is_element(event: &Event, name: &[u8]) -> bool {
+    matches!(event, Event::Start(element) | Event::Empty(element)
+        if local(element.name().as_ref()) == name)
+}
+
+/// Turns an empty element at `index` into a start/end pair and returns the
+/// index of its end tag.
+fn expand_empty(events: &mut Events, index: usize) -> usize {
+    if let Event::Empty(element) = &events[index] {
+        let element = element.clone();
+        let tag = String::from_utf8_lossy(element.name().as_ref()).into_owned();
+        events[index] = Event::Start(element);
+        events.insert(index + 1, Event::End(BytesEnd::new(tag)));
+        return index + 1;
+    }
+    closing_index(events, index)
+}
+
+fn start_tag(name: &str, attributes: &[(&str, &str)]) -> Event<'static> {
+    let mut element = BytesStart::new(name.to_string());
+    for attribute in attributes {
+        element.push_attribute(*attribute);
+    }
+    Event::Start(element.into_owned())
+}
+
+fn end_tag(name: &str) -> Event<'static> {
+    Event::End(BytesEnd::new(name.to_string()))
+}
+
+fn text_element(name: &str, text: &str, preserve: bool) -> Vec<Event<'static>> {
+    let attributes: &[(&str, &str)] = if preserve {
+        &[("xml:space", "preserve")]
+    } else {
+        &[]
+    };
+    vec![
+        start_tag(name, attributes),
+        Event::Text(BytesText::new(text).into_owned()),
+        end_tag(name),
+    ]
+}
+
+fn cell_events(reference: &str, style: Option<&str>, value: &CellValue) -> Vec<Event<'static>> {
+    let mut attributes = vec![("r", reference)];
+    if let Some(style) = style {
+        attributes.push(("s", style));
+    }
+    let mut body = Vec::new();
+    match value {
+        CellValue::Text(text) => {
+            attributes.push(("t", "inlineStr"));
+            body.push(start_tag("is", &[]));
+            body.extend(text_element("t", text, text.trim() != text));
+            body.push(end_tag("is"));
+        }
+        CellValue::Number(number) => body.extend(text_element("v", number, false)),
+        CellValue::Bool(flag) => {
+            attributes.push(("t", "b"));
+            body.extend(text_element("v", if *flag { "1" } else { "0" }, false));
+        }
+        CellValue::Clear => {}
+    }
+    let mut events = vec![start_tag("c", &attributes)];
+    events.extend(body);
+    events.push(end_tag("c"));
+    events
+}
@@ -0,0 +1,327 @@
+//! Event-level access to OOXML text: `<w:t>`, `<a:t>` and spreadsheet `<t>`
+//! nodes grouped by their paragraph, and find-replace that rewrites only the
+//! text of the runs a match covers, so run and paragraph properties survive.
+
+use std::collections::BTreeMap;
+
+use quick_xml::events::{BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+
+use super::OfficeError;
+
+pub(super) type Events = Vec<Event<'static>>;
+
+pub(super) fn parse(bytes: &[u8], part: &str) -> Result<Events, OfficeError> {
+    let mut reader = Reader::from_reader(bytes);
+    let mut events = Vec::new();
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) => return Ok(events),
+            Ok(Event::DocType(_)) => {
+                return Err(OfficeError::new(
+                    "ERR_OFFICE_FILE_MALFORMED",
+                    format!("{part} declares a DOCTYPE, which OOXML never uses"),
+                ));
+            }
+            Ok(event) => events.push(event.into_owned()),
+            Err(error) => {
+                return Err(OfficeError::new(
+                    "ERR_OFFICE_FILE_MALFORMED",
+                    format!(
+                        "{part} is not well-formed XML at byte {}: {error}",
+                        reader.error_position()
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+pub(super) fn serialize(events: &[Event<'static>]) -> Vec<u8> {
+    let mut writer = Writer::new(Vec::new());
+    for event in events {
+        // Writing into a Vec cannot fail.
+        let _ = writer.write_event(event.borrow());
+    }
+    writer.into_inner()
+}
+
+/// Element name without its namespace prefix.
+pub(super) fn local(name: &[u8]) -> &[u8] {
+    name.rsplit(|&byte| byte == b':').next().unwrap_or(name)
+}
+
+/// Unescaped value of the attribute whose local name is `name`.
+pub(super) fn attribute(element: &BytesStart, name: &[u8]) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| local(attribute.key.as_ref()) == name)
+        .and_then(|attribute| attribute.unescape_value().ok())
+        .map(|value| value.into_owned())
+}
+
+/// The `r:id` relationship reference of an element (any prefix), as opposed
+/// to an unprefixed numeric `id`.
+pub(super) fn relationship_id(element: &BytesStart) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| {
+            let key = attribute.key.as_ref();
+            key.contains(&b':') && local(key) == b"id"
+        })
+        .and_then(|attribute| attribute.unescape_value().ok())
+        .map(|value| value.into_owned())
+}
+
+/// Copy of `element` with `name` set to `value` (or removed when `None`),
+/// keeping every other attribute in its original order.
+pub(super) fn with_attribute(
+    element: &BytesStart,
+    name: &str,
+    value: Option<&str>,
+) -> BytesStart<'static> {
+    let tag = String::from_utf8_lossy(element.name().as_ref()).into_owned();
+    let mut updated = BytesStart::new(tag);
+    let mut replaced = false;
+    for attribute in element.attributes().flatten() {
+        if attribute.key.as_ref() == name.as_bytes() {
+            if let Some(value) = value
+                && !replaced
+            {
+                updated.push_attribute((name, value));
+            }
+            replaced = true;
+        } else {
+            updated.push_attribute(attribute);
+        }
+    }
+    if !replaced && let Some(value) = value {
+        updated.push_attribute((name, value));
+    }
+    updated.into_owned()
+}
+
+/// Character data of a text or entity event; `None` for anything else or an
+/// entity that is not one of the five XML predefined ones.
+pub(super) fn text_of(event: &Event) -> Option<String> {
+    match event {
+        Event::Text(text) => text.decode().ok().map(|text| text.into_owned()),
+        Event::CData(data) => data.decode().ok().map(|text| text.into_owned()),
+        Event::GeneralRef(reference) => {
+            if reference.is_char_ref() {
+                return reference
+                    .resolve_char_ref()
+                    .ok()
+                    .flatten()
+                    .map(String::from);
+            }
+            let name = reference.decode().ok()?;
+            let resolved = match name.as_ref() {
+                "amp" => "&",
+                "lt" => "<",
+                "gt" => ">",
+                "quot" => "\"",
+                "apos" => "'",
+                _ => return None,
+            };
+            Some(resolved.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// One `<t>` element: the index of its start tag and its decoded text.
+pub(super) struct TextNode {
+    pub(super) start: usize,
+    pub(super) text: String,
+}
+
+/// A paragraph-like container and the text nodes that belong directly to it.
+pub(super) struct Paragraph {
+    pub(super) start: usize,
+    pub(super) nodes: Vec<TextNode>,
+}
+
+impl Paragraph {
+    pub(super) fn text(&self) -> String {
+        self.nodes.iter().map(|node| node.text.as_str()).collect()
+    }
+}
+
+/// Every container named in `containers` (local names), in document order,
+/// with the `<t>` nodes that belong to it. A node belongs to its innermost
+/// open container, so a text box paragraph inside a paragraph stays separate.
+/// Phonetic runs (`rPh`) and nodes with markup or unknown entities inside are
+/// skipped: they are never matched and never rewritten.
+pub(super) fn paragraphs(events: &[Event<'static>], containers: &[&[u8]]) -> Vec<Paragraph> {
+    let mut found: Vec<Paragraph> = Vec::new();
+    let mut open: Vec<usize> = Vec::new();
+    let mut phonetic_depth = 0usize;
+    let mut index = 0;
+    while index < events.len() {
+        match &events[index] {
+            Event::Start(element) => {
+                let qname = element.name();
+                let name = local(qname.as_ref());
+                if containers.contains(&name) {
+                    open.push(found.len());
+                    found.push(Paragraph {
+                        start: index,
+                        nodes: Vec::new(),
+                    });
+                } else if name == b"rPh" {
+                    phonetic_depth += 1;
+                } else if name == b"t"
+                    && phonetic_depth == 0
+                    && let Some(&paragraph) = open.last()
+                {
+                    let end = closing_index(events, index);
+                    if let Some(text) = node_text(&events[index + 1..end]) {
+                        found[paragraph].nodes.push(TextNode { start: index, text });
+                    }
+                    index = end;
+                }
+            }
+            Event::Empty(element) => {
+                let qname = element.name();
+                let name = local(qname.as_ref());
+                if containers.contains(&name) {
+                    found.push(Paragraph {
+                        start: index,
+                        nodes: Vec::new(),
+                    });
+                } else if name == b"t"
+                    && phonetic_depth == 0
+                    && let Some(&paragraph) = open.last()
+                {
+                    found[paragraph].nodes.push(TextNode {
+                        start: index,
+                        text: String::new(),
+                    });
+                }
+            }
+            Event::End(element) => {
+                let qname = element.name();
+                let name = local(qname.as_ref());
+                if containers.contains(&name) {
+                    open.pop();
+                } else if name == b"rPh" {
+                    phonetic_depth = phonetic_depth.saturating_sub(1);
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+    found
+}
+
+/// Index of the end tag matching the start tag at `start`.
+pub(super) fn closing_index(events: &[Event<'static>], start: usize) -> usize {
+    let mut depth = 0usize;
+    for (index, event) in events.iter().enumerate().skip(start) {
+        match event {
+            Event::Start(_) => depth += 1,
+            Event::End(_) => {
+                depth -= 1;
+                if depth == 0 {
+                    return index;
+                }
+            }
+            _ => {}
+        }
+    }
+    events.len().saturating_sub(1)
+}
+
+fn node_text(content: &[Event<'static>]) -> Option<String> {
+    content.iter().map(text_of).collect()
+}
+
+/// A text match inside one paragraph, in bytes of [`Paragraph::text`].
+pub(super) struct Match<'a> {
+    pub(super) start: usize,
+    pub(super) end: usize,
+    pub(super) new_text: &'a str,
+}
+
+/// New text for every node a set of non-overlapping, sorted matches touches.
+/// The replacement goes into the run where a match starts; the rest of the
+/// matched text is removed from the following runs, which keep their
+/// formatting for whatever text of theirs is left.
+pub(super) fn rewrite_nodes(
+    paragraph: &Paragraph,
+    matches: &[Match],
+    rewritten: &mut BTreeMap<usize, String>,
+) {
+    let mut offset = 0;
+    for node in &paragraph.nodes {
+        let node_start = offset;
+        let node_end = offset + node.text.len();
+        offset = node_end;
+        let mut text = String::new();
+        let mut cursor = node_start;
+        let mut touched = false;
+        for item in matches {
+            if item.end <= node_start || item.start >= node_end {
+                // A match that starts exactly at the end of a non-final node
+                // is written into the node where its text begins.
+                continue;
+            }
+            touched = true;
+            let covered_start = item.start.max(node_start);
+            text.push_str(&node.text[cursor - node_start..covered_start - node_start]);
+            if item.start >= node_start {
+                text.push_str(item.new_text);
+            }
+            cursor = item.end.min(node_end);
+        }
+        if touched {
+            text.push_str(&node.text[cursor - node_start..]);
+            rewritten.insert(node.start, text);
+        }
+    }
+}
+
+/// Applies node rewrites produced by [`rewrite_nodes`]. Text that gains
+/// leading or trailing whitespace gets `xml:space="preserve"` so Office does
+/// not trim it.
+pub(super) fn apply_node_text(
+    events: &[Event<'static>],
+    rewritten: &BTreeMap<usize, String>,
+) -> Events {
+    let mut output = Vec::with_capacity(events.len());
+    let mut index = 0;
+    while index < events.len() {
+        let Some(text) = rewritten.get(&index) else {
+            output.push(events[index].clone());
+            index += 1;
+            continue;
+        };
+        let (element, end) = match &events[index] {
+            Event::Start(element) => (element, closing_index(events, index)),
+            Event::Empty(element) => (element, index),
+            _ => {
+                output.push(events[index].clone());
+                index += 1;
+                continue;
+            }
+        };
+        let needs_preserve = text.trim() != text;
+        let element = if needs_preserve {
+            with_attribute(element, "xml:space", Some("preserve"))
+        } else {
+            element.clone().into_owned()
+        };
+        let tag = String::from_utf8_lossy(element.name().as_ref()).into_owned();
+        output.push(Event::Start(element));
+        if !text.is_empty() {
+            output.push(Event::Text(BytesText::new(text).into_owned()));
+        }
+        output.push(Event::End(quick_xml::events::BytesEnd::new(tag)));
+        index = end + 1;
+    }
+    output
+}
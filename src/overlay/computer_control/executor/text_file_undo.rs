@@ -0,0 +1,182 @@
+//! Restores journaled pre-images of this session's file edits, newest first.
+//!
+//! Each restore goes through the same guarded transaction as the edit it
+//! reverses, and only when the file still holds exactly the bytes that edit
+//! wrote; a file changed since is refused and stops the undo.
+
+use serde_json::{Value, json};
+use std::path::Path;
+
+use super::super::super::edit_journal::{self, Entry};
+use super::super::super::telemetry::{self, Privacy};
+use super::transaction::{CommitOutcome, EditGuard, write_synced_sibling};
+use super::{ambiguous_commit, failure, sha256_hex, transaction_failure};
+
+/// Covers both text and Office edits.
+const MAX_UNDO_BYTES: u64 = 32 * 1024 * 1024;
+
+pub(super) fn undo_file_edit(args: &Value) -> Value {
+    let all = match args.get("scope").and_then(Value::as_str).unwrap_or("last") {
+        "last" => false,
+        "session" => true,
+        _ => {
+            return failure(
+                "ERR_FILE_EDIT_UNDO_BAD_ARGUMENT",
+                None,
+                "scope must be \"last\" or \"session\"",
+                true,
+            );
+        }
+    };
+    let mut restored = Vec::new();
+    while let Some(entry) = edit_journal::latest() {
+        if let Err(error) = restore(&entry) {
+            if restored.is_empty() {
+                return error;
+            }
+            return partial(error, restored);
+        }
+        edit_journal::retire(&entry.id);
+        restored.push(json!({
+            "edit_id": entry.id,
+            "tool": entry.tool,
+            "path": entry.path.to_string_lossy(),
+            "sha256": entry.before_sha256,
+        }));
+        if !all {
+            break;
+        }
+    }
+    if restored.is_empty() {
+        return failure(
+            "ERR_FILE_EDIT_UNDO_EMPTY",
+            None,
+            "this session has no file edit left to undo",
+            true,
+        );
+    }
+    let evicted = edit_journal::evicted();
+    telemetry::event(
+        "file_edit_undo",
+        "executor",
+        Privacy::Safe,
+        json!({
+            "restored": restored.len(),
+            "scope": if all { "session" } else { "last" },
+            "evicted": evicted,
+        }),
+    );
+    let mut result = json!({
+        "ok": true,
+        "restored": restored,
+        "remaining": edit_journal::remaining(),
+        "atomic": true,
+        "effect_verified": true,
+        "effect_may_have_occurred": true,
+        "executed": true,
+    });
+    if all && evicted > 0 {
+        // The oldest edits fell out of the journal; the files are not back
+        // to their state at session start.
+        result["session_restored"] = json!(false);
+        result["not_undoable"] = json!(evicted);
+        result["warning"] = json!(format!(
+            "{evicted} older edits of this session were dropped from the journal and were not undone"
+        ));
+    }
+    result
+}
+
+/// Puts one entry's pre-image back, verifying the file is unchanged since the
+/// edit both before staging and at the atomic replace.
+fn restore(entry: &Entry) -> Result<(), Value> {
+    let path = entry.path.as_path();
+    let mut guard = EditGuard::acquire(path).map_err(|error| transaction_failure(path, error))?;
+    let current = guard
+        .read_bounded(MAX_UNDO_BYTES)
+        .map_err(|error| transaction_failure(path, error))?;
+    let current_hash = sha256_hex(&current);
+    if current_hash != entry.after_sha256 {
+        return Err(changed_since(path, &entry.after_sha256, &current_hash));
+    }
+    let original = entry.pre_image().map_err(|error| {
+        failure(
+            "ERR_FILE_EDIT_UNDO_SNAPSHOT",
+            Some(path),
+            &format!("the edit snapshot is unusable: {error}"),
+            true,
+        )
+    })?;
+    let temporary = write_synced_sibling(path, &original).map_err(|error| {
+        failure(
+            "ERR_FILE_EDIT_UNDO_TEMP_WRITE",
+            Some(path),
+            &format!("could not stage the restore: {error}"),
+            true,
+        )
+    })?;
+    let validated = guard
+        .validate_current(path, &current, MAX_UNDO_BYTES)
+        .map_err(|change| {
+            changed_since(
+                path,
+                &entry.after_sha256,
+                change.actual_hash.as_deref().unwrap_or("unavailable"),
+            )
+        })?;
+    match guard.commit_audited(
+        path,
+        validated,
+        temporary,
+        &current,
+        &original,
+        MAX_UNDO_BYTES,
+    ) {
+        CommitOutcome::Verified { .. } => Ok(()),
+        CommitOutcome::NoEffect { error } => Err(failure(
+            "ERR_FILE_EDIT_UNDO_ATOMIC_REPLACE",
+            Some(path),
+            &error,
+            true,
+        )),
+        CommitOutcome::Ambiguous {
+            error,
+            tool_mutated_file,
+            external_change_detected,
+            recovery_backup,
+            recovery_sha256,
+        } => Err(ambiguous_commit(
+            path,
+            &error,
+            tool_mutated_file,
+            external_change_detected,
+            recovery_backup.as_deref(),
+            recovery_sha256.as_deref(),
+        )),
+    }
+}
+
+fn changed_since(path: &Path, expected: &str, actual: &str) -> Value {
+    let mut value = failure(
+        "ERR_FILE_EDIT_UNDO_CHANGED_SINCE",
+        Some(path),
+        "the file changed after the edit; it was left as is and the undo stopped",
+        true,
+    );
+    if let Some(fields) = value.as_object_mut() {
+        fields.insert("expected_sha256".to_string(), json!(expected));
+        fields.insert("actual_sha256".to_string(), json!(actual));
+        fields.insert("external_change_detected".to_string(), Value::Bool(true));
+    }
+    value
+}
+
+/// A session undo that restored some files before one failed.
+fn partial(mut error: Value, restored: Vec<Value>) -> Value {
+    if let Some(fields) = error.as_object_mut() {
+        fields.insert("effect_may_have_occurred".to_string(), Value::Bool(true));
+        fields.insert("restored".to_string(), Value::Array(restored));
+        fields.insert("remaining".to_string(), json!(edit_journal::remaining()));
+    }
+    error
+}
@@ -1,11 +1,16 @@
 //! Computer Control conversation memory: each voice session is auto-saved and
 //! auto-embedded (Gemini Embedding 2, multimodal) so the agent can SEARCH past
-//! conversations semantically and open the relevant one — no preloading. Mirrors
+//! conversations semantically and open the relevant one — no preloading. A local
+//! BM25/trigram index (`memory/lexical.rs`) is always kept alongside and fused
+//! with the embedding ranking, so search also works offline or without a key;
+//! conversations saved without an embedding are re-embedded once a key works. Mirrors
 //! the `history.rs` store (file-backed, atomic, auto-pruned) but with its OWN
 //! directory and max so the busy media history can't evict it.
 
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use base64::{Engine as _, engine::general_purpose};
 use chrono::Local;
@@ -13,6 +18,10 @@ use serde::{Deserialize, Serialize};
 
 use crate::api::gemini_embed::{self, EmbedPart};
 
+mod lexical;
+
+use lexical::LexicalDoc;
+
 /// Index entry per saved conversation. The full transcript lives in a sidecar
 /// file (`conv_<id>.json`); the index keeps only what search needs in memory.
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
@@ -35,6 +44,14 @@ struct Transcript {
 const EMBED_TEXT_BUDGET: usize = 12_000;
 /// Embedding 2 accepts up to 6 images per call.
 const MAX_EMBED_IMAGES: usize = 6;
+/// RRF weight of the recency ranking: enough to order near-ties newest first
+/// ("the last conversation about X") without outranking a clearer match.
+const RECENCY_WEIGHT: f32 = 0.25;
+
+/// Serialises read-modify-write of the index files between the detached save
+/// and backfill threads.
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+static BACKFILL_RUNNING: AtomicBool = AtomicBool::new(false);
 
 fn mem_dir() -> PathBuf {
     crate::paths::app_config_dir().join("cc_memory")
@@ -55,6 +72,34 @@ fn save_index(items: &[ConvMeta]) {
     let _ = crate::atomic_json::write_json_atomic(&index_path(), &items);
 }
 
+fn lexical_path() -> PathBuf {
+    mem_dir().join("lexical.json")
+}
+
+/// The lexical index matching `index`: entries for pruned conversations are
+/// dropped and conversations saved before the index existed are indexed from
+/// their transcript sidecars. Persists only when something changed.
+fn synced_lexical(index: &[ConvMeta]) -> Vec<LexicalDoc> {
+    let mut docs: Vec<LexicalDoc> = fs::File::open(lexical_path())
+        .ok()
+        .and_then(|f| serde_json::from_reader(f).ok())
+        .unwrap_or_default();
+    let before = docs.len();
+    docs.retain(|doc| index.iter().any(|m| m.id == doc.id));
+    let mut changed = docs.len() != before;
+    for meta in index {
+        if !docs.iter().any(|doc| doc.id == meta.id) {
+            let text = open(meta.id).unwrap_or_else(|| format!("{}\n{}", meta.title, meta.snippet));
+            docs.push(LexicalDoc::from_text(meta.id, &text));
+            changed = true;
+        }
+    }
+    if changed {
+        let _ = crate::atomic_json::write_json_atomic(&lexical_path(), &docs);
+    }
+    docs
+}
+
 fn max_items() -> usize {
     crate::load_config().cc_max_memory_items.max(1)
 }
@@ -103,7 +148,7 @@ fn save_inner(transcript: Vec<String>, frames: Vec<Vec<u8>>) -> anyhow::Result<(
     )?;
 
     // Embed the transcript text + representative frames, interleaved, into one
-    // cross-modal vector. Empty on failure (re-embedded on a later launch).
+    // cross-modal vector. Empty on failure (re-embedded by the backfill once a key works).
     let embedding = embed_conversation(&transcript, &frames).unwrap_or_default();
     if embedding.is_empty() {
         eprintln!(
@@ -111,6 +156,7 @@ fn save_inner(transcript: Vec<String>, frames: Vec<Vec<u8>>) -> anyhow::Result<(
         );
     }
 
+    let guard = INDEX_LOCK.lock().unwrap_or_else(|p| p.into_inner());
     let mut index = load_index();
     index.insert(
         0,
@@ -125,6 +171,13 @@ fn save_inner(transcript: Vec<String>, frames: Vec<Vec<u8>>) -> anyhow::Result<(
     );
     prune(&mut index, &dir);
     save_index(&index);
+    synced_lexical(&index);
+    drop(guard);
+    // A working embedding means a key is available now: catch up on older
+    // conversations saved offline.
+    if !index[0].embedding.is_empty() {
+        backfill_embeddings();
+    }
     Ok(())
 }
 
@@ -144,6 +197,52 @@ fn embed_conversation(transcript: &[String], frames: &[Vec<u8>]) -> anyhow::Resu
     gemini_embed::embed(&parts, &key)
 }
 
+/// Re-embeds conversations saved without an embedding, on a detached thread.
+/// At most one backfill runs; it stops at the first failure (offline, quota)
+/// and the next save or search retries.
+fn backfill_embeddings() {
+    if BACKFILL_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    std::thread::spawn(|| {
+        let dir = mem_dir();
+        let missing: Vec<ConvMeta> = load_index()
+            .into_iter()
+            .filter(|m| m.embedding.is_empty())
+            .collect();
+        for meta in missing {
+            let Some(transcript) = open(meta.id) else {
+                continue;
+            };
+            let lines: Vec<String> = transcript.lines().map(str::to_string).collect();
+            let frames: Vec<Vec<u8>> = meta
+                .images
+                .iter()
+                .filter_map(|img| fs::read(dir.join(img)).ok())
+                .collect();
+            let embedding = match embed_conversation(&lines, &frames) {
+                Ok(embedding) if !embedding.is_empty() => embedding,
+                Ok(_) => break,
+                Err(e) => {
+                    eprintln!("[cc-mem] re-embedding paused: {e}");
+                    break;
+                }
+            };
+            let _guard = INDEX_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+            let mut index = load_index();
+            if let Some(entry) = index
+                .iter_mut()
+                .find(|m| m.id == meta.id && m.embedding.is_empty())
+            {
+                entry.embedding = embedding;
+                save_index(&index);
+                eprintln!("[cc-mem] re-embedded conversation {}", meta.id);
+            }
+        }
+        BACKFILL_RUNNING.store(false, Ordering::SeqCst);
+    });
+}
+
 fn derive_title(transcript: &[String]) -> String {
     let first_user = transcript
         .iter()
@@ -184,56 +283,67 @@ pub(super) struct Hit {
     pub score: f32,
 }
 
-/// Semantic search over saved conversations. Embeds the query and cosine-ranks;
-/// falls back to keyword matching when the query can't be embedded (offline /
-/// quota) or for a conversation whose own embedding failed.
+/// Hybrid search over saved conversations: word BM25, trigram BM25 and (when
+/// the query can be embedded) cosine similarity, merged by reciprocal rank
+/// fusion with a light recency ranking. Works lexically when offline or
+/// without a key. An empty query lists the most recent conversations.
 pub(super) fn search(query: &str, top_k: usize) -> Vec<Hit> {
-    let index = load_index();
+    let (index, docs) = {
+        let _guard = INDEX_LOCK.lock().unwrap_or_else(|p| p.into_inner());
+        let index = load_index();
+        let docs = synced_lexical(&index);
+        (index, docs)
+    };
     if index.is_empty() {
         return Vec::new();
     }
-    let q_vec = super::session::load_key()
-        .ok()
-        .and_then(|k| gemini_embed::embed(&[EmbedPart::Text(query.to_string())], &k).ok());
-    let ql = query.to_lowercase();
-    // The index is newest-first, so `rank` 0 is the most recent conversation.
-    // Add a small recency boost so "the last / most recent conversation" surfaces
-    // for a vague query, without overriding a clearly stronger semantic match.
-    let n = index.len().max(1) as f32;
-
-    let mut scored: Vec<Hit> = index
-        .iter()
-        .enumerate()
-        .map(|(rank, m)| {
-            let semantic = q_vec
-                .as_ref()
-                .map(|qv| gemini_embed::cosine(qv, &m.embedding))
-                .unwrap_or(0.0);
-            // Keyword fallback/boost on title+snippet.
-            let hay = format!("{} {}", m.title, m.snippet).to_lowercase();
-            let keyword = if !ql.is_empty() && hay.contains(&ql) {
-                0.5
-            } else {
-                0.0
-            };
-            let recency = 1.0 - (rank as f32 / n); // 1.0 newest .. ~0 oldest
-            Hit {
+    // The index is newest-first, so its order is the recency ranking.
+    let recency: Vec<i64> = index.iter().map(|m| m.id).collect();
+    let fused = if query.trim().is_empty() {
+        lexical::fuse(&[(1.0, recency)])
+    } else {
+        let q_vec = super::session::load_key()
+            .ok()
+            .and_then(|k| gemini_embed::embed(&[EmbedPart::Text(query.to_string())], &k).ok());
+        let (words, trigrams) = lexical::rank(&docs, query);
+        let mut rankings = vec![(1.0, words), (1.0, trigrams)];
+        if let Some(qv) = &q_vec {
+            let mut semantic: Vec<(i64, f32)> = index
+                .iter()
+                .map(|m| (m.id, gemini_embed::cosine(qv, &m.embedding)))
+                .filter(|(_, score)| *score > 0.0)
+                .collect();
+            semantic.sort_by(|a, b| b.1.total_cmp(&a.1));
+            rankings.push((1.0, semantic.into_iter().map(|(id, _)| id).collect()));
+            if index.iter().any(|m| m.embedding.is_empty()) {
+                backfill_embeddings();
+            }
+        }
+        let matched: Vec<i64> = rankings
+            .iter()
+            .flat_map(|(_, r)| r.iter().copied())
+            .collect();
+        let recency = recency
+            .into_iter()
+            .filter(|id| matched.contains(id))
+            .collect();
+        rankings.push((RECENCY_WEIGHT, recency));
+        lexical::fuse(&rankings)
+    };
+    fused
+        .into_iter()
+        .take(top_k.max(1))
+        .filter_map(|(id, score)| {
+            let m = index.iter().find(|m| m.id == id)?;
+            Some(Hit {
                 id: m.id,
                 timestamp: m.timestamp.clone(),
                 title: m.title.clone(),
                 snippet: m.snippet.clone(),
-                score: semantic.max(keyword) + 0.08 * recency,
-            }
+                score,
+            })
         })
-        .collect();
-    scored.sort_by(|a, b| {
-        b.score
-            .partial_cmp(&a.score)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
-    scored.retain(|h| h.score > 0.0);
-    scored.truncate(top_k.max(1));
-    scored
+        .collect()
 }
 
 /// The full transcript of one saved conversation, formatted for the agent.
@@ -0,0 +1,139 @@
+//! Local lexical index over saved transcripts: BM25 on words plus BM25 on
+//! character trigrams (so a misspelled or partial word still lands), and
+//! reciprocal rank fusion to merge those rankings with the embedding one.
+//! Needs no key and no network, so memory search keeps working offline.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+/// BM25 term-saturation and length-normalisation constants (the usual values).
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+/// Reciprocal rank fusion damping; 60 is the value from the original paper.
+const RRF_K: f32 = 60.0;
+/// Transcript characters indexed per conversation.
+const INDEX_TEXT_BUDGET: usize = 64_000;
+
+/// Term frequencies of one saved conversation.
+#[derive(Clone, Debug, Serialize, Deserialize, Default, PartialEq)]
+pub(super) struct LexicalDoc {
+    pub(super) id: i64,
+    words: BTreeMap<String, u32>,
+    trigrams: BTreeMap<String, u32>,
+}
+
+impl LexicalDoc {
+    pub(super) fn from_text(id: i64, text: &str) -> Self {
+        let text: String = text.chars().take(INDEX_TEXT_BUDGET).collect();
+        let mut words = BTreeMap::new();
+        let mut trigrams = BTreeMap::new();
+        for word in tokenize(&text) {
+            for gram in word_trigrams(&word) {
+                *trigrams.entry(gram).or_insert(0) += 1;
+            }
+            *words.entry(word).or_insert(0) += 1;
+        }
+        Self {
+            id,
+            words,
+            trigrams,
+        }
+    }
+}
+
+/// Lower-cased alphanumeric runs. Role prefixes (`User:`) survive as ordinary
+/// words; BM25's IDF makes a word present in every transcript nearly free.
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Trigrams of ` word ` so short words and word boundaries still count.
+fn word_trigrams(word: &str) -> Vec<String> {
+    let padded: Vec<char> = format!(" {word} ").chars().collect();
+    padded
+        .windows(3)
+        .map(|window| window.iter().collect())
+        .collect()
+}
+
+/// Ids ranked by word BM25 and by trigram BM25, best first. Documents with
+/// no matching term are left out of a ranking.
+pub(super) fn rank(docs: &[LexicalDoc], query: &str) -> (Vec<i64>, Vec<i64>) {
+    let words = tokenize(query);
+    let trigrams: Vec<String> = words.iter().flat_map(|word| word_trigrams(word)).collect();
+    (
+        bm25(docs, &words, |doc| &doc.words),
+        bm25(docs, &trigrams, |doc| &doc.trigrams),
+    )
+}
+
+fn bm25(
+    docs: &[LexicalDoc],
+    query: &[String],
+    field: impl Fn(&LexicalDoc) -> &BTreeMap<String, u32>,
+) -> Vec<i64> {
+    if docs.is_empty() || query.is_empty() {
+        return Vec::new();
+    }
+    let lengths: Vec<f32> = docs
+        .iter()
+        .map(|doc| field(doc).values().sum::<u32>() as f32)
+        .collect();
+    let average = (lengths.iter().sum::<f32>() / docs.len() as f32).max(1.0);
+    let n = docs.len() as f32;
+    let mut query_counts: HashMap<&str, f32> = HashMap::new();
+    for term in query {
+        *query_counts.entry(term.as_str()).or_insert(0.0) += 1.0;
+    }
+    let mut scored: Vec<(i64, f32)> = docs
+        .iter()
+        .zip(&lengths)
+        .map(|(doc, &length)| {
+            let terms = field(doc);
+            let score = query_counts
+                .iter()
+                .filter_map(|(term, &weight)| {
+                    let tf = *terms.get(*term)? as f32;
+                    let df = docs
+                        .iter()
+                        .filter(|other| field(other).contains_key(*term))
+                        .count() as f32;
+                    let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+                    let norm = tf * (BM25_K1 + 1.0)
+                        / (tf + BM25_K1 * (1.0 - BM25_B + BM25_B * length / average));
+                    Some(weight * idf * norm)
+                })
+                .sum::<f32>();
+            (doc.id, score)
+        })
+        .filter(|(_, score)| *score > 0.0)
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scored.into_iter().map(|(id, _)| id).collect()
+}
+
+/// Reciprocal rank fusion of weighted rankings: each id scores
+/// `weight / (RRF_K + rank)` per ranking it appears in. Highest first; ties
+/// keep the order of first appearance.
+pub(super) fn fuse(rankings: &[(f32, Vec<i64>)]) -> Vec<(i64, f32)> {
+    let mut fused: Vec<(i64, f32)> = Vec::new();
+    for (weight, ranking) in rankings {
+        for (rank, id) in ranking.iter().enumerate() {
+            let contribution = weight / (RRF_K + rank as f32 + 1.0);
+            match fused.iter_mut().find(|(seen, _)| seen == id) {
+                Some((_, score)) => *score += contribution,
+                None => fused.push((*id, contribution)),
+            }
+        }
+    }
+    fused.sort_by(|a, b| b.1.total_cmp(&a.1));
+    fused
+}
+
+#[cfg(test)]
+#[path = "lexical_tests.rs"]
+mod tests;
@@ -0,0 +1,104 @@
+use super::*;
+
+/// Saved-conversation fixtures, newest first like the memory index.
+const TRANSCRIPTS: &[(i64, &str)] = &[
+    (
+        8,
+        "User: rename the vacation photos by date\nAssistant: Renamed 42 files in Pictures/Vacation.",
+    ),
+    (
+        7,
+        "User: open the quarterly budget spreadsheet and sum column D\nObserved: Excel - budget_q3.xlsx\nAssistant: Column D totals 18,240.",
+    ),
+    (
+        6,
+        "User: mute the browser tab playing music\nAssistant: Muted the YouTube tab in Firefox.",
+    ),
+    (
+        5,
+        "User: write an email to Priya about the invoice\nAssistant: Drafted the invoice reminder in Outlook.",
+    ),
+    (
+        4,
+        "User: find the wifi password for the office network\nAssistant: The saved network OfficeNet uses WPA2.",
+    ),
+    (
+        3,
+        "User: convert the meeting notes to PDF\nObserved: Word - meeting_notes.docx\nAssistant: Exported meeting_notes.pdf to Documents.",
+    ),
+    (
+        2,
+        "User: schedule a dentist appointment reminder for Friday\nAssistant: Added a calendar reminder on Friday at 9:00.",
+    ),
+    (
+        1,
+        "User: clean up the downloads folder\nAssistant: Moved 12 installers from Downloads to the recycle bin.",
+    ),
+];
+
+fn docs() -> Vec<LexicalDoc> {
+    TRANSCRIPTS
+        .iter()
+        .map(|(id, text)| LexicalDoc::from_text(*id, text))
+        .collect()
+}
+
+fn top_hit(query: &str) -> Option<i64> {
+    let (words, trigrams) = rank(&docs(), query);
+    fuse(&[(1.0, words), (1.0, trigrams)])
+        .first()
+        .map(|(id, _)| *id)
+}
+
+#[test]
+fn fixture_queries_retrieve_the_expected_conversation() {
+    let cases = [
+        ("budget spreadsheet", 7),
+        ("the email I wrote about an invoice", 5),
+        ("wifi password", 4),
+        ("PDF export of meeting notes", 3),
+        ("dentist", 2),
+        ("which tab did you mute", 6),
+        ("vacation photos", 8),
+        ("downloads cleanup", 1),
+    ];
+    for (query, expected) in cases {
+        assert_eq!(top_hit(query), Some(expected), "query {query:?}");
+    }
+}
+
+#[test]
+fn trigrams_recover_misspelled_and_partial_words() {
+    assert_eq!(top_hit("spreadshet"), Some(7));
+    assert_eq!(top_hit("dentst apointment"), Some(2));
+    assert_eq!(top_hit("invoic"), Some(5));
+    let (words, _) = rank(&docs(), "spreadshet");
+    assert!(words.is_empty(), "exact-word BM25 has no match for a typo");
+}
+
+#[test]
+fn unrelated_queries_match_nothing() {
+    let (words, trigrams) = rank(&docs(), "zzqx");
+    assert!(words.is_empty());
+    assert!(trigrams.is_empty());
+    assert!(rank(&docs(), "").0.is_empty());
+}
+
+#[test]
+fn fusion_rewards_agreement_between_lexical_and_embedding_rankings() {
+    // Lexically "notes" favours 3, the embedding ranking favours 5; a
+    // conversation both rank highly wins over either list's single top hit.
+    let fused = fuse(&[(1.0, vec![3, 7, 5]), (1.0, vec![5, 7, 3]), (1.0, vec![7])]);
+    assert_eq!(fused[0].0, 7);
+    assert_eq!(fused.len(), 3);
+    let lower_weight = fuse(&[(1.0, vec![3]), (0.25, vec![5])]);
+    assert_eq!(lower_weight[0].0, 3);
+}
+
+#[test]
+fn index_round_trips_through_json() {
+    let doc = LexicalDoc::from_text(9, "User: Résumé für Zoë");
+    let text = serde_json::to_string(&doc).unwrap();
+    assert_eq!(serde_json::from_str::<LexicalDoc>(&text).unwrap(), doc);
+    assert_eq!(doc.words.get("résumé"), Some(&1));
+}
@@ -14,6 +14,7 @@ mod browser;
 mod clipboard;
 mod controller;
 mod coord_test;
+mod edit_journal;
 mod effect_receipt;
 mod engine;
 mod executor;
@@ -23,15 +24,19 @@ mod mcp;
 mod memory;
 mod orb;
 mod overlay;
+mod permission_policy;
 mod playback;
 mod probe;
 mod protocol;
 mod research;
+mod routines;
 mod runtime;
 mod session;
 mod system_query;
 mod telemetry;
+mod text_session;
 mod trace;
+mod triggers;
 mod turn_policy;
 mod uia;
 mod uia_task;
@@ -41,6 +46,11 @@ mod vision_reader;
 /// MCP capability-store hooks for the Downloaded Tools settings UI (list/install/remove).
 pub(crate) use mcp::{ui_install, ui_installing, ui_list, ui_remove, ui_remove_all};
 pub use overlay::{is_active, show_overlay, stop_overlay};
+/// Saved-routine hotkeys, registered by the global hotkey listener.
+pub(crate) use routines::{hotkeys as routine_hotkeys, run_hotkey as run_routine_hotkey};
+/// Background loop for `cc_triggers.toml`, started once at app launch.
+pub(crate) use triggers::run_scheduler as run_trigger_scheduler;
+pub(crate) use triggers::cancel_running as cancel_trigger_run;
 
 pub(crate) fn remove_downloaded_engine() -> anyhow::Result<()> {
     stop_overlay();
@@ -84,7 +94,7 @@ pub fn run_vision_test_cli(target: Option<&str>, question: &str) -> Result<(), S
     run_cli_with_engine(|| uia_task::run_vision_test(target, question))
 }
 
-fn run_cli_with_engine(operation: impl FnOnce() -> anyhow::Result<()>) -> Result<(), String> {
+fn run_cli_with_engine<T>(operation: impl FnOnce() -> anyhow::Result<T>) -> Result<T, String> {
     use std::sync::atomic::AtomicBool;
 
     session::load_key().map_err(|error| format!("{error:?}"))?;
@@ -136,6 +146,123 @@ pub fn run_system_query_test_cli(spec: &str, args_json: Option<&str>) -> Result<
     }
 }
 
+/// CLI entry for offline session replay:
+/// `--cc-trace-replay <events.jsonl> [--cc-policy <file>] [--cc-replay-report <file>]`.
+/// Re-runs the recorded tool calls through the deterministic gates with a fake
+/// executor and fails when any decision differs from the recording.
+pub fn run_trace_replay_cli(
+    trace: &str,
+    policy: Option<&str>,
+    report_path: Option<&str>,
+) -> Result<(), String> {
+    let report = runtime::replay_file(
+        std::path::Path::new(trace),
+        policy.map(std::path::Path::new),
+    )?;
+    let json = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    if let Some(path) = report_path {
+        std::fs::write(path, &json).map_err(|e| format!("cannot write {path}: {e}"))?;
+    }
+    println!("{json}");
+    eprintln!(
+        "[trace-replay] calls={} matched={} diffs={}",
+        report.calls,
+        report.matched,
+        report.diffs.len()
+    );
+    if report.diffs.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} replayed decision(s) differ from the recording",
+            report.diffs.len()
+        ))
+    }
+}
+
+/// CLI entry for saving a recorded session as a routine:
+/// `--cc-routine-save <events.jsonl> --cc-routine-name <name>`.
+pub fn run_routine_save_cli(
+    trace: &str,
+    name: &str,
+    description: Option<&str>,
+) -> Result<(), String> {
+    let path =
+        routines::save_from_trace(std::path::Path::new(trace), name, description.unwrap_or(""))?;
+    println!("{}", path.display());
+    Ok(())
+}
+
+/// CLI entry listing saved routines and their parameters: `--cc-routine-list`.
+pub fn run_routine_list_cli() -> Result<(), String> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&routines::list()).map_err(|e| e.to_string())?
+    );
+    Ok(())
+}
+
+/// CLI entry for running a saved routine:
+/// `--cc-routine-run <name> [--cc-routine-params-json '{"url_1":"..."}']`.
+pub fn run_routine_cli(name: &str, params_json: Option<&str>) -> Result<(), String> {
+    let params = match params_json {
+        Some(raw) => serde_json::from_str(raw).map_err(|e| format!("invalid params JSON: {e}"))?,
+        None => std::collections::BTreeMap::new(),
+    };
+    let cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    run_cli_with_engine(|| {
+        let report = routines::run(name, &params, &cancel)?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        Ok(())
+    })
+}
+
+/// CLI entry for a typed task over a turn-based text provider:
+/// `--cc-text-task "..." [--cc-text-provider gemini|openai] [--cc-text-model <id>]
+/// [--cc-text-endpoint <url>] [--cc-text-scripted <replies.json>]`. Needs the
+/// engine but, unlike the Live session, no Gemini key for OpenAI-compatible
+/// or scripted providers.
+pub fn run_text_task_cli(
+    task: &str,
+    provider: Option<&str>,
+    model: Option<&str>,
+    endpoint: Option<&str>,
+    scripted: Option<&str>,
+) -> Result<(), String> {
+    use std::sync::atomic::AtomicBool;
+
+    let options = text_session::TextTaskOptions {
+        provider: provider.unwrap_or("gemini"),
+        model,
+        endpoint,
+        scripted: scripted.map(std::path::Path::new),
+        cancel: None,
+    };
+    let cancelled = AtomicBool::new(false);
+    let _engine = engine::SessionGuard::start(&cancelled).map_err(|error| format!("{error:?}"))?;
+    text_session::run(task, &options)
+        .map(|_| ())
+        .map_err(|error| format!("{error:?}"))
+}
+
+/// CLI entry listing configured triggers and when schedules next fire:
+/// `--cc-trigger-list`.
+pub fn run_trigger_list_cli() -> Result<(), String> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&triggers::list()?).map_err(|e| e.to_string())?
+    );
+    Ok(())
+}
+
+/// CLI entry running one trigger's action now, under the same unattended
+/// policy as the scheduler: `--cc-trigger-fire <name>`.
+pub fn run_trigger_fire_cli(name: &str) -> Result<(), String> {
+    let summary = triggers::fire_now(name)?;
+    println!("{summary}");
+    Ok(())
+}
+
 /// CLI entry for the model-free human-cursor demo: `--cc-cursor-demo`.
 pub fn run_cursor_demo_cli() {
     executor::cursor_demo();
@@ -18,7 +18,13 @@ pub(super) fn generate_orb_html() -> String {
         "vi" => "Nhập lệnh…",
         _ => "Type a command…",
     };
+    let undo = match lang.as_str() {
+        "ko" => "되돌리기",
+        "vi" => "Hoàn tác",
+        _ => "Undo",
+    };
     include_str!("orb.html")
         .replace("/*FONT_CSS*/", &font_css)
         .replace("/*CMD_PLACEHOLDER*/", placeholder)
+        .replace("/*UNDO_LABEL*/", undo)
 }
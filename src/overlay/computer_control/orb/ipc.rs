@@ -5,6 +5,7 @@
 //! - `orbDrag` — full window while flying in/out or being dragged (visible across its whole path);
 //! - `orbMoved` — the new placement after a drag (persisted across sessions);
 //! - `orbReady` — first-paint readiness, so `show_orb` reveals the window without a white flash.
+//! - `undoEdit` — the edit chip's Undo button: restore the newest agent file edit.
 
 use std::sync::atomic::Ordering;
 
@@ -70,6 +71,22 @@ pub(super) fn handle_orb_ipc(hwnd: HWND, body: &str) {
         }
         // The box dismissed (Enter / Esc) → hand focus back to the user's window.
         "closeCommand" => end_text_mode(hwnd),
+        // Off the UI thread: the restore takes the file's edit lock and syncs to disk.
+        "undoEdit" => {
+            std::thread::spawn(|| {
+                let result = super::super::executor::execute(
+                    "undo_file_edit",
+                    &serde_json::json!({"scope": "last"}),
+                );
+                if result.get("ok").and_then(serde_json::Value::as_bool) != Some(true) {
+                    let error = result
+                        .get("error")
+                        .and_then(serde_json::Value::as_str)
+                        .unwrap_or("undo failed");
+                    super::super::overlay::push_log(format!("undo edit: {error}"));
+                }
+            });
+        }
         _ => {}
     }
 }
@@ -112,9 +112,11 @@ pub(super) fn show_startup_credential_error(error: &anyhow::Error) -> bool {
     true
 }
 
-/// Signal the running session to stop (the runtime thread clears CC_ACTIVE).
+/// Signal the running session, or a triggered run, to stop (the runtime
+/// thread clears CC_ACTIVE).
 pub fn stop_overlay() {
     CC_STOP.store(true, Ordering::SeqCst);
+    super::triggers::cancel_running();
     super::orb::hide_orb();
 }
 
@@ -247,6 +249,7 @@ pub(super) fn set_orb_tool(name: &str, args: &serde_json::Value) {
         | "artifact_info"
         | "extract_artifact"
         | "read_text_file"
+        | "read_office_file"
         | "save_artifact"
         | "list_windows"
         | "read_clipboard"
@@ -267,7 +270,9 @@ pub(super) fn set_orb_tool(name: &str, args: &serde_json::Value) {
         | "browser_eval"
         | "paste_artifact"
         | "edit_text_file"
-        | "edit_text_file_structure" => OrbState::Type,
+        | "edit_text_file_structure"
+        | "edit_office_file"
+        | "undo_file_edit" => OrbState::Type,
         "open_url" | "browser_navigate" | "browser_open_tab" | "browser_switch_tab"
         | "focus_window" => OrbState::Navigate,
         "launch_app" | "browser_setup" => OrbState::Launch,
@@ -0,0 +1,338 @@
+//! User-editable permission policy for Computer Control.
+//!
+//! `cc_policy.toml` (or `cc_policy.json`) in the app config directory declares
+//! per-tool allow/deny/confirm rules, filesystem path scopes, browser domain
+//! lists, shell command patterns and per-MCP-tool overrides. It is evaluated
+//! deterministically before every dispatch, ahead of the built-in guards, and
+//! each verdict is written to telemetry with the policy digest so an audit can
+//! tie an action to the exact policy text that allowed it. No file means no
+//! policy; a file that does not parse blocks every tool until it is fixed.
+//!
+//! Scheduled and event-triggered runs switch the gate to unattended mode for
+//! their duration: nobody can answer a confirmation, so calls that need one
+//! are denied, and anything outside the trigger's own `allow_tools` list, or
+//! any tool that changes state when the trigger sets none, is refused before
+//! the policy file is consulted. The mode belongs to the thread running the
+//! triggered action, which is where its tools dispatch, so a Live session or
+//! hotkey routine on another thread keeps the interactive gate.
+
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use super::telemetry::{self, ActionTrace, Privacy};
+
+mod rules;
+
+use rules::{CONFIRM_ARG, PermissionPolicy, PolicyAction};
+
+const SUPPORTED_VERSION: u32 = 1;
+/// Tools the model needs to finish or report a blocked turn.
+const ALWAYS_ALLOWED: [&str; 1] = ["done"];
+thread_local! {
+    /// `Some(allow_tools)` while this thread runs an unattended action.
+    static UNATTENDED: RefCell<Option<Option<Vec<String>>>> = const { RefCell::new(None) };
+}
+
+/// Keeps the current thread's gate in unattended mode until dropped. Not
+/// `Send`, so it is dropped on the thread it switched.
+pub(super) struct UnattendedGuard(PhantomData<*const ()>);
+
+/// Enters unattended mode on this thread. `allow_tools`, when set, is the
+/// complete list of tools the run may call besides `done`; unset, the run
+/// may only call tools that read.
+pub(super) fn unattended(allow_tools: Option<Vec<String>>) -> UnattendedGuard {
+    UNATTENDED.with(|unattended| *unattended.borrow_mut() = Some(allow_tools));
+    UnattendedGuard(PhantomData)
+}
+
+impl Drop for UnattendedGuard {
+    fn drop(&mut self) {
+        UNATTENDED.with(|unattended| *unattended.borrow_mut() = None);
+    }
+}
+
+/// A policy file as read from disk.
+pub(super) enum Loaded {
+    None,
+    Policy {
+        policy: PermissionPolicy,
+        digest: String,
+    },
+    Invalid {
+        error: String,
+        digest: String,
+    },
+}
+
+struct CachedPolicy {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    loaded: std::sync::Arc<Loaded>,
+}
+
+static CACHE: Mutex<Option<CachedPolicy>> = Mutex::new(None);
+
+fn policy_paths() -> [PathBuf; 2] {
+    let dir = crate::paths::app_config_dir();
+    [dir.join("cc_policy.toml"), dir.join("cc_policy.json")]
+}
+
+/// Current policy, re-read whenever the file appears, disappears or changes.
+pub(super) fn current() -> std::sync::Arc<Loaded> {
+    let path = policy_paths().into_iter().find(|path| path.is_file());
+    let modified = path
+        .as_ref()
+        .and_then(|path| std::fs::metadata(path).ok())
+        .and_then(|metadata| metadata.modified().ok());
+    let mut cache = CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(cached) = cache.as_ref()
+        && cached.path == path
+        && cached.modified == modified
+    {
+        return cached.loaded.clone();
+    }
+    let loaded = std::sync::Arc::new(path.as_ref().map_or(Loaded::None, |path| load(path)));
+    if let Loaded::Invalid { error, .. } = loaded.as_ref() {
+        telemetry::typed_error(
+            "ERR_PERMISSION_POLICY_INVALID",
+            "permission_policy",
+            error,
+            json!({}),
+        );
+    }
+    *cache = Some(CachedPolicy {
+        path,
+        modified,
+        loaded: loaded.clone(),
+    });
+    loaded
+}
+
+pub(super) fn load(path: &std::path::Path) -> Loaded {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) => {
+            return Loaded::Invalid {
+                error: format!("cannot read {}: {error}", path.display()),
+                digest: String::new(),
+            };
+        }
+    };
+    let digest = format!("{:x}", Sha256::digest(text.as_bytes()));
+    let parsed = if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str::<PermissionPolicy>(&text).map_err(|error| error.to_string())
+    } else {
+        serde_json::from_str::<PermissionPolicy>(&text).map_err(|error| error.to_string())
+    };
+    match parsed {
+        Ok(policy) if policy.version == SUPPORTED_VERSION => Loaded::Policy { policy, digest },
+        Ok(policy) => Loaded::Invalid {
+            error: format!(
+                "unsupported policy version {} (expected {SUPPORTED_VERSION})",
+                policy.version
+            ),
+            digest,
+        },
+        Err(error) => Loaded::Invalid {
+            error: format!("{}: {error}", path.display()),
+            digest,
+        },
+    }
+}
+
+/// Checks one tool call against the policy. Returns the refusal to hand back
+/// to the model, or `None` when the call may proceed.
+pub(super) fn gate(tool: &str, args: &Value, action: ActionTrace) -> Option<Value> {
+    let unattended = UNATTENDED.with(|unattended| unattended.borrow().clone());
+    let Some(allow_tools) = unattended else {
+        return gate_with(&current(), tool, args, action);
+    };
+    if !unattended_allows(tool, allow_tools.as_deref()) {
+        return Some(unattended_refusal(tool, "unattended_tool", action));
+    }
+    // A confirmation flag in an unattended run came from the model, not a user.
+    let args = without_confirmation(args).unwrap_or_else(|| args.clone());
+    let refusal = gate_with(&current(), tool, &args, action)?;
+    if refusal["code"] == "ERR_POLICY_CONFIRM_REQUIRED" {
+        return Some(unattended_refusal(tool, "unattended_confirm", action));
+    }
+    Some(refusal)
+}
+
+fn unattended_allows(tool: &str, allow_tools: Option<&[String]>) -> bool {
+    if ALWAYS_ALLOWED.contains(&tool) {
+        return true;
+    }
+    match allow_tools {
+        Some(tools) => tools.iter().any(|allowed| allowed == tool),
+        None => !super::turn_policy::is_mutating_tool(tool),
+    }
+}
+
+fn unattended_refusal(tool: &str, reason: &str, action: ActionTrace) -> Value {
+    telemetry::event_for_action(
+        "permission_policy",
+        "permission_policy",
+        Privacy::Safe,
+        action,
+        json!({
+            "tool": tool,
+            "verdict": "deny",
+            "outcome": "denied",
+            "reason": reason,
+        }),
+    );
+    json!({
+        "ok": false,
+        "code": "ERR_POLICY_UNATTENDED",
+        "reason": reason,
+        "error": "this call is not allowed in an unattended run, where nobody can confirm it",
+        "instruction": "Do not retry or work around this with another tool. Finish with done and say what was blocked.",
+        "effect_may_have_occurred": false,
+    })
+}
+
+/// What the policy says about one call, before anything is recorded.
+pub(super) struct PolicyDecision {
+    verdict: PolicyAction,
+    rule: String,
+    reason: &'static str,
+    outcome: &'static str,
+    digest: String,
+    /// The refusal to hand back to the model, or `None` when the call may
+    /// proceed.
+    pub(super) refusal: Option<Value>,
+}
+
+/// Side-effect-free decision for one call against an explicit policy, shared
+/// by the live [`gate`] and offline trace replay. `None` when no policy applies.
+pub(super) fn evaluate(loaded: &Loaded, tool: &str, args: &Value) -> Option<PolicyDecision> {
+    if ALWAYS_ALLOWED.contains(&tool) {
+        return None;
+    }
+    let (verdict, rule, reason, digest) = match loaded {
+        Loaded::None => return None,
+        Loaded::Invalid { digest, .. } => (
+            PolicyAction::Deny,
+            "file".to_string(),
+            "policy_invalid",
+            digest,
+        ),
+        Loaded::Policy { policy, digest } => {
+            let verdict = policy.evaluate(tool, args);
+            (verdict.action, verdict.rule, verdict.reason, digest)
+        }
+    };
+    let confirmed = args.get(CONFIRM_ARG).and_then(Value::as_bool) == Some(true);
+    let outcome = match verdict {
+        PolicyAction::Allow => "allowed",
+        PolicyAction::Confirm if confirmed => "allowed_confirmed",
+        PolicyAction::Confirm => "confirm_required",
+        PolicyAction::Deny => "denied",
+    };
+    let refusal = match verdict {
+        PolicyAction::Allow => None,
+        PolicyAction::Confirm if confirmed => None,
+        PolicyAction::Confirm => Some(json!({
+            "ok": false,
+            "code": "ERR_POLICY_CONFIRM_REQUIRED",
+            "need_confirm": true,
+            "policy_rule": rule,
+            "error": "the user's Computer Control policy requires explicit confirmation for this call",
+            "instruction": format!(
+                "Tell the user exactly what this call will do and ask them. Only if they agree just now, retry the SAME call with {CONFIRM_ARG}:true."
+            ),
+            "effect_may_have_occurred": false,
+        })),
+        PolicyAction::Deny => {
+            let (code, error) = if reason == "policy_invalid" {
+                (
+                    "ERR_POLICY_INVALID",
+                    "the Computer Control policy file could not be loaded, so every action is blocked until it is fixed",
+                )
+            } else {
+                (
+                    "ERR_POLICY_DENIED",
+                    "the user's Computer Control policy does not allow this call",
+                )
+            };
+            Some(json!({
+            "ok": false,
+            "code": code,
+            "policy_rule": rule,
+            "reason": reason,
+            "error": error,
+            "instruction": "Do not retry or work around this with another tool. Tell the user the policy blocked it.",
+            "effect_may_have_occurred": false,
+            }))
+        }
+    };
+    Some(PolicyDecision {
+        verdict,
+        rule,
+        reason,
+        outcome,
+        digest: digest.clone(),
+        refusal,
+    })
+}
+
+/// [`evaluate`] plus the telemetry record that ties a live verdict to the
+/// policy digest. Only live calls come through here.
+fn gate_with(loaded: &Loaded, tool: &str, args: &Value, action: ActionTrace) -> Option<Value> {
+    let decision = evaluate(loaded, tool, args)?;
+    telemetry::event_for_action(
+        "permission_policy",
+        "permission_policy",
+        Privacy::Safe,
+        action,
+        json!({
+            "tool": tool,
+            "verdict": decision.verdict.as_str(),
+            "outcome": decision.outcome,
+            "policy_rule": decision.rule,
+            "reason": decision.reason,
+            "policy_sha256": decision.digest,
+        }),
+    );
+    decision.refusal
+}
+
+/// `args` without the confirmation flag, so tools and MCP servers with strict
+/// schemas never see it. `None` when the flag was not present.
+pub(super) fn without_confirmation(args: &Value) -> Option<Value> {
+    let mut args = args.clone();
+    args.as_object_mut()?.remove(CONFIRM_ARG)?;
+    Some(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unattended_mode_stays_on_its_thread_and_defaults_to_reads() {
+        let _guard = unattended(None);
+        let elsewhere = std::thread::spawn(|| UNATTENDED.with(|mode| mode.borrow().is_some()))
+            .join()
+            .unwrap();
+        assert!(!elsewhere);
+        assert!(UNATTENDED.with(|mode| mode.borrow().is_some()));
+
+        assert!(unattended_allows("read_text_file", None));
+        assert!(unattended_allows("done", None));
+        assert!(!unattended_allows("edit_text_file", None));
+        assert!(!unattended_allows("run_command", None));
+        let listed = ["run_command".to_string()];
+        assert!(unattended_allows("run_command", Some(&listed)));
+        assert!(!unattended_allows("read_text_file", Some(&listed)));
+    }
+}
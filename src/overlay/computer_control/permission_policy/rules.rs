@@ -0,0 +1,404 @@
+//! Pure policy evaluation. Every dimension that applies to a call yields a
+//! verdict and the most restrictive one wins, so rule order inside a section
+//! only decides which rule is reported, never whether a call is allowed.
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Argument keys that name a local filesystem location. Keys ending in
+/// `_path`, `_paths` or `_dir` count too, at any depth.
+const PATH_KEYS: [&str; 7] = [
+    "path",
+    "paths",
+    "cwd",
+    "directory",
+    "destination",
+    "file_path",
+    "folder",
+];
+/// Where `run_command` runs when the call names no `cwd`.
+const COMMAND_WORK_DIR: &str = "cc-command-work";
+/// Explicit user approval for a `confirm` verdict, set by the model only after
+/// the user agreed to this exact call.
+pub(super) const CONFIRM_ARG: &str = "policy_confirmed";
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum PolicyAction {
+    #[default]
+    Allow,
+    Confirm,
+    Deny,
+}
+
+impl PolicyAction {
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            Self::Allow => "allow",
+            Self::Confirm => "confirm",
+            Self::Deny => "deny",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub(super) struct PermissionPolicy {
+    pub(super) version: u32,
+    /// Verdict for tools no rule mentions.
+    pub(super) default: PolicyAction,
+    pub(super) tools: Vec<ToolRule>,
+    /// Per-MCP-tool overrides keyed by `server__tool` (the `mcp__` prefix is
+    /// optional); `*` wildcards are allowed. Checked before `tools`.
+    pub(super) mcp: std::collections::BTreeMap<String, PolicyAction>,
+    pub(super) filesystem: Scope,
+    pub(super) browser: Scope,
+    pub(super) shell: ShellRules,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ToolRule {
+    pub(super) tool: String,
+    pub(super) action: PolicyAction,
+}
+
+/// Allow/deny lists. A non-empty `allow` list turns the scope into an
+/// allowlist; `deny` always wins.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub(super) struct Scope {
+    pub(super) allow: Vec<String>,
+    pub(super) deny: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields)]
+pub(super) struct ShellRules {
+    pub(super) allow: Vec<String>,
+    pub(super) confirm: Vec<String>,
+    pub(super) deny: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Verdict {
+    pub(super) action: PolicyAction,
+    /// Policy section and entry that produced the verdict, e.g. `tools[2]`.
+    pub(super) rule: String,
+    pub(super) reason: &'static str,
+}
+
+impl Verdict {
+    fn new(action: PolicyAction, rule: String, reason: &'static str) -> Self {
+        Self {
+            action,
+            rule,
+            reason,
+        }
+    }
+}
+
+impl PermissionPolicy {
+    pub(super) fn evaluate(&self, tool: &str, args: &Value) -> Verdict {
+        let mut verdict = self.tool_verdict(tool);
+        let mut tighten = |candidate: Option<Verdict>| {
+            if let Some(candidate) = candidate
+                && candidate.action > verdict.action
+            {
+                verdict = candidate;
+            }
+        };
+        // Every string counts wherever it sits, so a location cannot slip past
+        // the scopes inside an array, a nested step or a relative path.
+        let cwd = effective_cwd(tool, args);
+        let mut strings = Vec::new();
+        collect_strings(None, args, &mut strings);
+        for (key, text) in strings {
+            if key.is_some_and(is_url_key) || looks_like_url(text) {
+                tighten(self.url_verdict(text));
+            } else if key.is_some_and(is_path_key) || looks_like_path(text) {
+                tighten(self.path_verdict(&resolve(text, &cwd)));
+            }
+        }
+        if tool == "run_command" {
+            tighten(self.shell_verdict(&command_line(args)));
+        }
+        verdict
+    }
+
+    fn tool_verdict(&self, tool: &str) -> Verdict {
+        if let Some(name) = tool.strip_prefix("mcp__")
+            && let Some((key, action)) = self
+                .mcp
+                .iter()
+                .find(|(key, _)| glob_match(key.strip_prefix("mcp__").unwrap_or(key), name))
+        {
+            return Verdict::new(*action, format!("mcp.{key}"), "mcp_override");
+        }
+        self.tools
+            .iter()
+            .enumerate()
+            .find(|(_, rule)| glob_match(&rule.tool, tool))
+            .map(|(index, rule)| Verdict::new(rule.action, format!("tools[{index}]"), "tool_rule"))
+            .unwrap_or_else(|| Verdict::new(self.default, "default".to_string(), "default"))
+    }
+
+    fn path_verdict(&self, raw: &str) -> Option<Verdict> {
+        let path = normalize_path(raw);
+        let inside = |pattern: &String| {
+            let pattern = normalize_path(pattern);
+            if pattern.contains(['*', '?']) {
+                glob_match(&pattern, &path)
+            } else {
+                path == pattern || path.starts_with(&format!("{}/", pattern.trim_end_matches('/')))
+            }
+        };
+        scope_verdict(&self.filesystem, "filesystem", inside, "path")
+    }
+
+    fn url_verdict(&self, raw: &str) -> Option<Verdict> {
+        let host = url::Url::parse(raw)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase));
+        let Some(host) = host else {
+            // Non-web schemes cannot be scoped by domain, so an allowlist
+            // rejects them outright.
+            return (!self.browser.allow.is_empty()).then(|| {
+                Verdict::new(
+                    PolicyAction::Deny,
+                    "browser.allow".to_string(),
+                    "url_not_web",
+                )
+            });
+        };
+        let matches = |pattern: &String| {
+            let pattern = pattern.trim().trim_start_matches("*.").to_ascii_lowercase();
+            host == pattern || host.ends_with(&format!(".{pattern}"))
+        };
+        scope_verdict(&self.browser, "browser", matches, "domain")
+    }
+
+    fn shell_verdict(&self, command: &str) -> Option<Verdict> {
+        let command = command.trim();
+        let find = |patterns: &[String]| {
+            patterns.iter().position(|pattern| {
+                glob_match(&pattern.to_ascii_lowercase(), &command.to_ascii_lowercase())
+            })
+        };
+        if let Some(index) = find(&self.shell.deny) {
+            return Some(Verdict::new(
+                PolicyAction::Deny,
+                format!("shell.deny[{index}]"),
+                "command_denied",
+            ));
+        }
+        if let Some(index) = find(&self.shell.confirm) {
+            return Some(Verdict::new(
+                PolicyAction::Confirm,
+                format!("shell.confirm[{index}]"),
+                "command_confirm",
+            ));
+        }
+        (!self.shell.allow.is_empty() && find(&self.shell.allow).is_none()).then(|| {
+            Verdict::new(
+                PolicyAction::Deny,
+                "shell.allow".to_string(),
+                "command_not_allowed",
+            )
+        })
+    }
+}
+
+fn scope_verdict(
+    scope: &Scope,
+    section: &str,
+    matches: impl Fn(&String) -> bool,
+    subject: &str,
+) -> Option<Verdict> {
+    if let Some(index) = scope.deny.iter().position(&matches) {
+        let reason = if subject == "path" {
+            "path_denied"
+        } else {
+            "domain_denied"
+        };
+        return Some(Verdict::new(
+            PolicyAction::Deny,
+            format!("{section}.deny[{index}]"),
+            reason,
+        ));
+    }
+    (!scope.allow.is_empty() && !scope.allow.iter().any(&matches)).then(|| {
+        let reason = if subject == "path" {
+            "path_outside_scope"
+        } else {
+            "domain_not_allowed"
+        };
+        Verdict::new(PolicyAction::Deny, format!("{section}.allow"), reason)
+    })
+}
+
+/// Every non-empty string in `value` with the object key it sits under; array
+/// items inherit the key of their array.
+fn collect_strings<'a>(
+    key: Option<&'a str>,
+    value: &'a Value,
+    out: &mut Vec<(Option<&'a str>, &'a str)>,
+) {
+    match value {
+        Value::String(text) if !text.trim().is_empty() => out.push((key, text.trim())),
+        Value::Array(items) => {
+            for item in items {
+                collect_strings(key, item, out);
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map {
+                collect_strings(Some(key), item, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_path_key(key: &str) -> bool {
+    PATH_KEYS.contains(&key)
+        || key.ends_with("_path")
+        || key.ends_with("_paths")
+        || key.ends_with("_dir")
+}
+
+fn is_url_key(key: &str) -> bool {
+    key == "url" || key == "urls" || key.ends_with("_url") || key.ends_with("_urls")
+}
+
+/// A single token with a scheme, e.g. `https://host/x` or `file:///C:/a`.
+fn looks_like_url(text: &str) -> bool {
+    !text.contains(char::is_whitespace) && text.contains("://") && url::Url::parse(text).is_ok()
+}
+
+/// An absolute or explicitly relative filesystem path outside a path key,
+/// such as a program argument.
+fn looks_like_path(text: &str) -> bool {
+    !text.contains('\n')
+        && (is_absolute(text)
+            || ["./", "../", ".\\", "..\\", "~/", "~\\"]
+                .iter()
+                .any(|prefix| text.starts_with(prefix)))
+}
+
+/// Drive-rooted (`C:/x`) or UNC (`\\server\x`), independent of the host.
+fn is_absolute(path: &str) -> bool {
+    drive(path).is_some_and(|_| matches!(path.as_bytes().get(2), Some(b'/' | b'\\')))
+        || path.starts_with("\\\\")
+        || path.starts_with("//")
+}
+
+/// The `C:` prefix of a drive path.
+fn drive(path: &str) -> Option<&str> {
+    let bytes = path.as_bytes();
+    (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':').then(|| &path[..2])
+}
+
+/// Resolves `path` the way Windows would from `cwd`: absolute paths stay,
+/// rooted ones (`\x`) land on the cwd's drive and the rest join onto it.
+fn resolve(path: &str, cwd: &str) -> String {
+    if is_absolute(path) || cwd.is_empty() {
+        path.to_string()
+    } else if path.starts_with(['/', '\\']) {
+        format!("{}{path}", drive(cwd).unwrap_or_default())
+    } else {
+        format!("{cwd}/{path}")
+    }
+}
+
+/// The directory relative paths in a call resolve against: the call's own
+/// `cwd`, else the directory the tool itself falls back to.
+fn effective_cwd(tool: &str, args: &Value) -> String {
+    let fallback = if tool == "run_command" {
+        crate::paths::app_temp_dir().join(COMMAND_WORK_DIR)
+    } else {
+        std::env::current_dir().unwrap_or_default()
+    };
+    let fallback = fallback.to_string_lossy().into_owned();
+    match args
+        .get("cwd")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|cwd| !cwd.is_empty())
+    {
+        Some(cwd) => resolve(cwd, &fallback),
+        None => fallback,
+    }
+}
+
+/// The command line a shell pattern is matched against: the raw PowerShell
+/// command, or the exact program followed by its arguments.
+fn command_line(args: &Value) -> String {
+    if let Some(command) = args.get("command").and_then(Value::as_str) {
+        return command.to_string();
+    }
+    let program = args.get("program").and_then(Value::as_str).unwrap_or("");
+    let argv = args
+        .get("args")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str);
+    std::iter::once(program)
+        .chain(argv)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Lexically resolves `.`/`..`, unifies separators and case so `C:\Work\..\X`
+/// and `c:/x` compare equal. Symlinks are not followed: the policy governs the
+/// path the agent names.
+fn normalize_path(raw: &str) -> String {
+    let raw = raw.trim().replace('\\', "/");
+    let mut resolved = PathBuf::new();
+    for component in Path::new(&raw).components() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            other => resolved.push(other.as_os_str()),
+        }
+    }
+    resolved.to_string_lossy().replace('\\', "/").to_lowercase()
+}
+
+/// `*` matches any run of characters (including separators), `?` exactly one.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests;
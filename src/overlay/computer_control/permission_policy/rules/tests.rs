@@ -0,0 +1,201 @@
+use super::*;
+use serde_json::json;
+
+fn policy(value: Value) -> PermissionPolicy {
+    serde_json::from_value(value).expect("policy parses")
+}
+
+#[test]
+fn most_restrictive_dimension_wins() {
+    let policy = policy(json!({
+        "version": 1,
+        "tools": [
+            { "tool": "run_command", "action": "allow" },
+            { "tool": "browser_*", "action": "allow" },
+            { "tool": "edit_text_file*", "action": "confirm" }
+        ],
+        "default": "deny",
+        "filesystem": { "allow": ["C:\\Work"], "deny": ["C:/Work/secrets"] },
+        "browser": { "allow": ["example.com"] },
+        "shell": { "allow": ["git *"], "confirm": ["git push*"], "deny": ["*--force*"] }
+    }));
+    let check = |tool: &str, args: Value| policy.evaluate(tool, &args).action;
+
+    assert_eq!(check("launch_app", json!({})), PolicyAction::Deny);
+    assert_eq!(
+        check("edit_text_file", json!({ "path": "c:/work/a.txt" })),
+        PolicyAction::Confirm
+    );
+    assert_eq!(
+        check(
+            "read_text_file",
+            json!({ "path": "C:/Work/../Other/a.txt" })
+        ),
+        PolicyAction::Deny
+    );
+    assert_eq!(
+        check("edit_text_file", json!({ "path": "C:/Work/secrets/key" })),
+        PolicyAction::Deny
+    );
+    assert_eq!(
+        check(
+            "browser_navigate",
+            json!({ "url": "https://docs.example.com/x" })
+        ),
+        PolicyAction::Allow
+    );
+    assert_eq!(
+        check(
+            "browser_navigate",
+            json!({ "url": "https://example.com.evil.net" })
+        ),
+        PolicyAction::Deny
+    );
+    assert_eq!(
+        check("browser_navigate", json!({ "url": "file:///C:/Work/a" })),
+        PolicyAction::Deny
+    );
+    assert_eq!(
+        check("run_command", json!({ "command": "git status" })),
+        PolicyAction::Allow
+    );
+    assert_eq!(
+        check(
+            "run_command",
+            json!({ "program": "git", "args": ["push", "origin"] })
+        ),
+        PolicyAction::Confirm
+    );
+    assert_eq!(
+        check("run_command", json!({ "command": "git push --force" })),
+        PolicyAction::Deny
+    );
+    assert_eq!(
+        check("run_command", json!({ "command": "Remove-Item C:/Work" })),
+        PolicyAction::Deny
+    );
+
+    let verdict = policy.evaluate("run_command", &json!({ "command": "git push --force" }));
+    assert_eq!(verdict.rule, "shell.deny[0]");
+    assert_eq!(verdict.reason, "command_denied");
+}
+
+#[test]
+fn scopes_apply_to_nested_urls_and_relative_paths() {
+    let policy = policy(json!({
+        "filesystem": { "allow": ["C:/Work"], "deny": ["C:/Work/secrets"] },
+        "browser": { "deny": ["evil.net"] }
+    }));
+    let check = |tool: &str, args: Value| policy.evaluate(tool, &args);
+
+    let verdict = check(
+        "research_web",
+        json!({ "query": "x", "source_urls": ["https://example.com", "https://cdn.evil.net/a"] }),
+    );
+    assert_eq!(verdict.action, PolicyAction::Deny);
+    assert_eq!(verdict.rule, "browser.deny[0]");
+    assert_eq!(
+        check(
+            "run_routine",
+            json!({ "steps": [{ "args": { "target": "https://evil.net" } }] })
+        )
+        .action,
+        PolicyAction::Deny
+    );
+
+    let verdict = check(
+        "read_text_file",
+        json!({ "cwd": "C:/Work/notes", "path": "../secrets/key" }),
+    );
+    assert_eq!(verdict.reason, "path_denied");
+    assert_eq!(
+        check(
+            "read_text_file",
+            json!({ "cwd": "C:/Work/notes", "path": "today.txt" })
+        )
+        .action,
+        PolicyAction::Allow
+    );
+    // Without a cwd the path resolves against the process directory, which
+    // is outside the allowlist.
+    assert_eq!(
+        check("read_text_file", json!({ "path": "today.txt" })).reason,
+        "path_outside_scope"
+    );
+    assert_eq!(
+        check(
+            "run_command",
+            json!({ "program": "type", "args": ["..\\Work\\secrets\\key"], "cwd": "C:/Other" })
+        )
+        .action,
+        PolicyAction::Deny
+    );
+    assert_eq!(
+        check(
+            "run_command",
+            json!({ "program": "type", "args": ["C:\\Work\\secrets\\key"], "cwd": "C:/Work" })
+        )
+        .reason,
+        "path_denied"
+    );
+}
+
+#[test]
+fn toml_policy_uses_the_same_schema() {
+    let policy: PermissionPolicy = toml::from_str(
+        r#"
+        version = 1
+        default = "confirm"
+
+        [[tools]]
+        tool = "read_*"
+        action = "allow"
+
+        [mcp]
+        "github__*" = "deny"
+
+        [filesystem]
+        allow = ["D:/Projects"]
+        "#,
+    )
+    .expect("toml policy parses");
+    assert_eq!(
+        policy
+            .evaluate("read_text_file", &json!({ "path": "D:/Projects/a" }))
+            .action,
+        PolicyAction::Allow
+    );
+    assert_eq!(
+        policy.evaluate("launch_app", &json!({})).action,
+        PolicyAction::Confirm
+    );
+    assert_eq!(
+        policy.evaluate("mcp__github__get_issue", &json!({})).action,
+        PolicyAction::Deny
+    );
+    assert!(toml::from_str::<PermissionPolicy>("version = 1\nunknown = true").is_err());
+}
+
+#[test]
+fn mcp_overrides_take_precedence_over_tool_rules() {
+    let policy = policy(json!({
+        "tools": [{ "tool": "mcp__*", "action": "deny" }],
+        "mcp": { "github__get_*": "allow", "mcp__github__create_issue": "confirm" }
+    }));
+    assert_eq!(
+        policy.evaluate("mcp__github__get_issue", &json!({})).action,
+        PolicyAction::Allow
+    );
+    assert_eq!(
+        policy
+            .evaluate("mcp__github__create_issue", &json!({}))
+            .action,
+        PolicyAction::Confirm
+    );
+    assert_eq!(
+        policy.evaluate("mcp__slack__post", &json!({})).rule,
+        "tools[0]"
+    );
+    assert!(glob_match("a*c?e", "abxxcde"));
+    assert!(!glob_match("a*c", "abd"));
+}
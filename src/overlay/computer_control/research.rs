@@ -14,7 +14,9 @@ mod followups;
 mod link_ranking;
 mod readiness;
 mod result;
+mod search_api;
 mod search_provider;
+mod search_registry;
 mod source_policy;
 mod temporary_tab;
 use diagnostics::{FailureClass, SourceDiagnostics};
@@ -23,6 +25,7 @@ use result::{
     ResearchSource, page_url, research_result, source_content_hash, source_from_page,
     source_is_usable,
 };
+use search_registry::Discovery;
 use source_policy::SourcePolicy;
 use temporary_tab::TemporaryTab;
 
@@ -90,8 +93,25 @@ pub(super) fn research_web(args: &Value) -> Value {
         .and_then(Value::as_u64)
         .unwrap_or(5)
         .clamp(1, 5) as usize;
+    let registry = search_registry::current();
+    let registry = match registry.as_ref() {
+        Ok(registry) => registry,
+        Err(error) => {
+            return json!({
+                "ok": false,
+                "code": "ERR_RESEARCH_SEARCH_CONFIG_INVALID",
+                "error": format!("the research search configuration could not be loaded: {error}"),
+                "read_only": true,
+                "effect_may_have_occurred": false,
+                "executed": false,
+                "instruction": "Tell the user research is unavailable until cc_search.toml or cc_search.json is fixed.",
+            });
+        }
+    };
     let policy = match SourcePolicy::parse(args, max_sources) {
-        Ok(policy) => policy,
+        Ok(policy) => {
+            policy.with_domain_preferences(&registry.preferred_domains, &registry.blocked_domains)
+        }
         Err(error) => return error,
     };
     let direct_links = match direct_sources::parse(args, &policy, max_sources) {
@@ -109,6 +129,7 @@ pub(super) fn research_web(args: &Value) -> Value {
             "source_policy": policy.name(),
             "max_sources": max_sources,
             "direct_source_count": direct_links.len(),
+            "search_provider_count": registry.providers.len(),
         }),
     );
 
@@ -134,7 +155,7 @@ pub(super) fn research_web(args: &Value) -> Value {
         .into_iter()
         .map(DiscoveredLink::direct)
         .collect::<Vec<_>>();
-    match discover_links(&search_query, &mut diagnostics) {
+    match discover_links(&search_query, &registry.providers, &mut diagnostics) {
         Ok(links) => discovered_links.extend(links),
         Err(error) => {
             if discovered_links.is_empty() {
@@ -157,7 +178,7 @@ pub(super) fn research_web(args: &Value) -> Value {
         &discovery_query,
         discovered_links.iter().map(|link| link.url.as_str()),
     ) {
-        match discover_links(&recovery_query, &mut diagnostics) {
+        match discover_links(&recovery_query, &registry.providers, &mut diagnostics) {
             Ok(links) => discovered_links.extend(links),
             Err(error) => diagnostics.failed(
                 FailureClass::Discovery,
@@ -434,10 +455,11 @@ fn evidence_query(query: &str, purpose: &str) -> String {
 
 fn discover_links(
     query: &str,
+    providers: &[Discovery],
     diagnostics: &mut SourceDiagnostics,
 ) -> anyhow::Result<Vec<DiscoveredLink>> {
     let mut failures = Vec::new();
-    for (index, provider) in search_provider::providers().iter().enumerate() {
+    for (index, provider) in providers.iter().enumerate() {
         match discover_links_with(query, provider, diagnostics) {
             Ok(links) => {
                 if index > 0 {
@@ -445,7 +467,7 @@ fn discover_links(
                         "research_search_provider_recovered",
                         "research",
                         super::telemetry::Privacy::Safe,
-                        json!({"provider": provider.name(), "fallback_index": index}),
+                        json!({"provider": provider.telemetry_name(), "fallback_index": index}),
                     );
                 }
                 return Ok(links);
@@ -456,7 +478,7 @@ fn discover_links(
                     "research",
                     super::telemetry::Privacy::Safe,
                     json!({
-                        "provider": provider.name(),
+                        "provider": provider.telemetry_name(),
                         "fallback_index": index,
                         "error": super::telemetry::value_metadata(&json!(error.to_string())),
                     }),
@@ -473,9 +495,13 @@ fn discover_links(
 
 fn discover_links_with(
     query: &str,
-    provider: &search_provider::SearchProvider,
+    provider: &Discovery,
     diagnostics: &mut SourceDiagnostics,
 ) -> anyhow::Result<Vec<DiscoveredLink>> {
+    let provider = match provider {
+        Discovery::Browser(provider) => provider,
+        Discovery::Api(provider) => return provider.search(query, MAX_SEARCH_CANDIDATES),
+    };
     let search_url = provider
         .search_url(query)
         .map_err(|error| anyhow::anyhow!("search provider URL construction failed: {error}"))?;
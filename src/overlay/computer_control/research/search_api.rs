@@ -0,0 +1,563 @@
+//! Search discovery through a JSON API instead of a browser tab.
+//!
+//! A SearXNG instance answers `GET {base_url}/search?q=..&format=json` with a
+//! `results` array. A generic JSON API declares its endpoint, query parameter,
+//! where the result array lives, and which fields hold each result's URL and
+//! title. Either way the response becomes the same discovered links a search
+//! page would yield, so ranking and source policy stay provider-agnostic.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::Value;
+use url::Url;
+
+use super::link_ranking::DiscoveredLink;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_RESPONSE_BYTES: u64 = 2 * 1024 * 1024;
+const MAX_PARAMETERS: usize = 16;
+const MAX_NAME_CHARS: usize = 64;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum EntryKind {
+    Builtin,
+    Searxng,
+    JsonApi,
+}
+
+/// One `[[providers]]` entry as written by the user. Which fields apply
+/// depends on `kind`; [`ApiProvider::from_entry`] rejects the rest.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ProviderEntry {
+    pub(super) kind: EntryKind,
+    /// Built-in engine for `builtin`; a display label otherwise.
+    #[serde(default)]
+    pub(super) name: Option<String>,
+    #[serde(default)]
+    base_url: Option<String>,
+    #[serde(default)]
+    endpoint: Option<String>,
+    #[serde(default)]
+    query_parameter: Option<String>,
+    #[serde(default)]
+    parameters: BTreeMap<String, String>,
+    #[serde(default)]
+    results_pointer: Option<String>,
+    #[serde(default)]
+    url_field: Option<String>,
+    #[serde(default)]
+    title_field: Option<String>,
+    #[serde(default)]
+    api_key: Option<String>,
+    #[serde(default)]
+    api_key_env: Option<String>,
+    #[serde(default)]
+    api_key_header: Option<String>,
+    #[serde(default)]
+    api_key_prefix: Option<String>,
+    #[serde(default)]
+    api_key_parameter: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ApiKind {
+    Searxng,
+    JsonApi,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum KeySource {
+    Literal(String),
+    Env(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum KeyPlacement {
+    Header { name: String, prefix: String },
+    Parameter(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Credential {
+    source: KeySource,
+    placement: KeyPlacement,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct ApiProvider {
+    name: String,
+    kind: ApiKind,
+    endpoint: Url,
+    query_parameter: String,
+    parameters: Vec<(String, String)>,
+    results_pointer: String,
+    url_field: String,
+    title_field: String,
+    credential: Option<Credential>,
+}
+
+impl ApiProvider {
+    pub(super) fn from_entry(entry: &ProviderEntry) -> Result<Self, String> {
+        let kind = match entry.kind {
+            EntryKind::Searxng => ApiKind::Searxng,
+            EntryKind::JsonApi => ApiKind::JsonApi,
+            EntryKind::Builtin => return Err("builtin providers have no API endpoint".into()),
+        };
+        let name = match entry.name.as_deref().map(str::trim) {
+            Some(name) if name.is_empty() || name.chars().count() > MAX_NAME_CHARS => {
+                return Err(format!(
+                    "provider name must be 1 to {MAX_NAME_CHARS} characters"
+                ));
+            }
+            Some(name) => name.to_string(),
+            None => kind.as_str().to_string(),
+        };
+        let (endpoint, defaults) = match kind {
+            ApiKind::Searxng => {
+                if entry.endpoint.is_some()
+                    || entry.results_pointer.is_some()
+                    || entry.url_field.is_some()
+                    || entry.title_field.is_some()
+                {
+                    return Err(format!(
+                        "{name}: searxng providers take base_url; endpoint and result fields are fixed"
+                    ));
+                }
+                let base = entry
+                    .base_url
+                    .as_deref()
+                    .ok_or_else(|| format!("{name}: searxng providers need base_url"))?;
+                let mut base = http_url(base).map_err(|error| format!("{name}: {error}"))?;
+                if !base.path().ends_with('/') {
+                    let path = format!("{}/", base.path());
+                    base.set_path(&path);
+                }
+                let endpoint = base
+                    .join("search")
+                    .map_err(|error| format!("{name}: invalid base_url: {error}"))?;
+                (endpoint, ("/results", "url", "title"))
+            }
+            ApiKind::JsonApi => {
+                if entry.base_url.is_some() {
+                    return Err(format!("{name}: json_api providers take endpoint"));
+                }
+                let endpoint = entry
+                    .endpoint
+                    .as_deref()
+                    .ok_or_else(|| format!("{name}: json_api providers need endpoint"))?;
+                let endpoint = http_url(endpoint).map_err(|error| format!("{name}: {error}"))?;
+                (endpoint, ("", "url", "title"))
+            }
+        };
+        let results_pointer = entry
+            .results_pointer
+            .clone()
+            .unwrap_or_else(|| defaults.0.to_string());
+        if kind == ApiKind::JsonApi && results_pointer.is_empty() {
+            return Err(format!(
+                "{name}: json_api providers need results_pointer, e.g. \"/web/results\""
+            ));
+        }
+        if !results_pointer.starts_with('/') {
+            return Err(format!(
+                "{name}: results_pointer must be a JSON pointer starting with '/'"
+            ));
+        }
+        let mut parameters: Vec<(String, String)> = entry
+            .parameters
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        if kind == ApiKind::Searxng {
+            if parameters.iter().any(|(key, _)| key == "format") {
+                return Err(format!("{name}: searxng format is always json"));
+            }
+            parameters.push(("format".to_string(), "json".to_string()));
+        }
+        if parameters.len() > MAX_PARAMETERS {
+            return Err(format!(
+                "{name}: at most {MAX_PARAMETERS} extra parameters are allowed"
+            ));
+        }
+        let query_parameter = non_empty(entry.query_parameter.as_deref(), "q");
+        if parameters.iter().any(|(key, _)| *key == query_parameter) {
+            return Err(format!(
+                "{name}: parameters must not set the query parameter '{query_parameter}'"
+            ));
+        }
+        Ok(Self {
+            credential: credential(entry).map_err(|error| format!("{name}: {error}"))?,
+            name,
+            kind,
+            endpoint,
+            query_parameter,
+            parameters,
+            results_pointer,
+            url_field: non_empty(entry.url_field.as_deref(), defaults.1),
+            title_field: non_empty(entry.title_field.as_deref(), defaults.2),
+        })
+    }
+
+    pub(super) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Provider family for telemetry; the configured name and endpoint stay
+    /// out of safe events.
+    pub(super) fn kind_name(&self) -> &'static str {
+        self.kind.as_str()
+    }
+
+    fn request_url(&self, query: &str, key: Option<&str>) -> Url {
+        let mut url = self.endpoint.clone();
+        {
+            let mut pairs = url.query_pairs_mut();
+            for (name, value) in &self.parameters {
+                pairs.append_pair(name, value);
+            }
+            pairs.append_pair(&self.query_parameter, query);
+            if let (Some(key), Some(Credential { placement, .. })) = (key, &self.credential)
+                && let KeyPlacement::Parameter(name) = placement
+            {
+                pairs.append_pair(name, key);
+            }
+        }
+        url
+    }
+
+    /// Result links in response order, bounded to `limit`. Entries without an
+    /// HTTP(S) URL are skipped; a missing or non-array result list is an error
+    /// so a misconfigured pointer does not look like an empty result page.
+    pub(super) fn parse_results(
+        &self,
+        body: &Value,
+        limit: usize,
+    ) -> anyhow::Result<Vec<DiscoveredLink>> {
+        let results = body
+            .pointer(&self.results_pointer)
+            .and_then(Value::as_array)
+            .ok_or_else(|| {
+                anyhow::anyhow!("response has no result array at {}", self.results_pointer)
+            })?;
+        Ok(results
+            .iter()
+            .filter_map(|result| {
+                let url = result.get(&self.url_field).and_then(Value::as_str)?;
+                let parsed = Url::parse(url).ok()?;
+                if !matches!(parsed.scheme(), "http" | "https") {
+                    return None;
+                }
+                let label = result
+                    .get(&self.title_field)
+                    .and_then(Value::as_str)
+                    .unwrap_or("")
+                    .to_string();
+                Some(DiscoveredLink::search(url.to_string(), label))
+            })
+            .take(limit)
+            .collect())
+    }
+
+    pub(super) fn search(&self, query: &str, limit: usize) -> anyhow::Result<Vec<DiscoveredLink>> {
+        let key = match &self.credential {
+            Some(credential) => Some(credential.source.resolve()?),
+            None => None,
+        };
+        let redact = |error: String| match key.as_deref() {
+            Some(key) => error.replace(key, "[redacted]"),
+            None => error,
+        };
+        let url = self.request_url(query, key.as_deref());
+        let mut request = crate::api::client::UREQ_RESPONSE_AGENT
+            .get(url.as_str())
+            .header("Accept", "application/json")
+            .header(
+                "User-Agent",
+                concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")),
+            );
+        if let (Some(key), Some(Credential { placement, .. })) = (&key, &self.credential)
+            && let KeyPlacement::Header { name, prefix } = placement
+        {
+            request = request.header(name.as_str(), format!("{prefix}{key}").as_str());
+        }
+        let mut response = crate::api::client::with_request_timeout(request, Some(REQUEST_TIMEOUT))
+            .call()
+            .map_err(|error| anyhow::anyhow!(redact(format!("transport error: {error}"))))?;
+        let status = response.status().as_u16();
+        if matches!(status, 401 | 403) {
+            anyhow::bail!("search API rejected the configured key ({status})");
+        }
+        if !(200..300).contains(&status) {
+            anyhow::bail!("search API returned HTTP {status}");
+        }
+        let body: Value = response
+            .body_mut()
+            .with_config()
+            .limit(MAX_RESPONSE_BYTES)
+            .read_json()
+            .map_err(|error| anyhow::anyhow!(redact(format!("invalid JSON response: {error}"))))?;
+        self.parse_results(&body, limit)
+    }
+}
+
+impl ApiKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Searxng => "searxng",
+            Self::JsonApi => "json_api",
+        }
+    }
+}
+
+impl KeySource {
+    fn resolve(&self) -> anyhow::Result<String> {
+        let key = match self {
+            Self::Literal(key) => key.clone(),
+            Self::Env(name) => {
+                std::env::var(name).map_err(|_| anyhow::anyhow!("api_key_env {name} is not set"))?
+            }
+        };
+        if key.trim().is_empty() {
+            anyhow::bail!("the configured search API key is empty");
+        }
+        Ok(key)
+    }
+}
+
+fn credential(entry: &ProviderEntry) -> Result<Option<Credential>, String> {
+    let source = match (&entry.api_key, &entry.api_key_env) {
+        (Some(_), Some(_)) => return Err("set api_key or api_key_env, not both".into()),
+        (Some(key), None) => KeySource::Literal(key.clone()),
+        (None, Some(name)) if !name.trim().is_empty() => KeySource::Env(name.trim().to_string()),
+        (None, Some(_)) => return Err("api_key_env must name an environment variable".into()),
+        (None, None) => {
+            if entry.api_key_header.is_some()
+                || entry.api_key_prefix.is_some()
+                || entry.api_key_parameter.is_some()
+            {
+                return Err("api_key placement is set without api_key or api_key_env".into());
+            }
+            return Ok(None);
+        }
+    };
+    let placement = match (&entry.api_key_parameter, &entry.api_key_header) {
+        (Some(_), Some(_)) => {
+            return Err("set api_key_header or api_key_parameter, not both".into());
+        }
+        (Some(_), None) if entry.api_key_prefix.is_some() => {
+            return Err("api_key_prefix applies only to api_key_header".into());
+        }
+        (Some(parameter), None) => KeyPlacement::Parameter(non_empty(Some(parameter), "key")),
+        (None, header) => {
+            let name = non_empty(header.as_deref(), "Authorization");
+            if ureq::http::HeaderName::from_bytes(name.as_bytes()).is_err() {
+                return Err(format!(
+                    "api_key_header '{name}' is not a valid header name"
+                ));
+            }
+            let default_prefix = if name.eq_ignore_ascii_case("authorization") {
+                "Bearer "
+            } else {
+                ""
+            };
+            KeyPlacement::Header {
+                name,
+                prefix: entry
+                    .api_key_prefix
+                    .clone()
+                    .unwrap_or_else(|| default_prefix.to_string()),
+            }
+        }
+    };
+    Ok(Some(Credential { source, placement }))
+}
+
+fn http_url(raw: &str) -> Result<Url, String> {
+    let url = Url::parse(raw.trim()).map_err(|error| format!("invalid URL: {error}"))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err("provider URLs must be http or https with a host".into());
+    }
+    if !url.username().is_empty() || url.password().is_some() {
+        return Err("provider URLs must not embed credentials; use api_key".into());
+    }
+    if url.fragment().is_some() {
+        return Err("provider URLs must not have a fragment".into());
+    }
+    Ok(url)
+}
+
+fn non_empty(value: Option<&str>, default: &str) -> String {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .unwrap_or(default)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(value: Value) -> ProviderEntry {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn searxng_provider_targets_the_json_search_endpoint() {
+        let provider = ApiProvider::from_entry(&entry(json!({
+            "kind": "searxng",
+            "name": "corp",
+            "base_url": "https://searx.corp.example/instance",
+            "parameters": {"categories": "general"},
+        })))
+        .unwrap();
+        assert_eq!(provider.name(), "corp");
+        assert_eq!(provider.kind_name(), "searxng");
+        assert_eq!(
+            provider.request_url("two words & more", None).as_str(),
+            "https://searx.corp.example/instance/search?categories=general&format=json&q=two+words+%26+more"
+        );
+        let links = provider
+            .parse_results(
+                &json!({"results": [
+                    {"url": "https://docs.example.com/a", "title": "A"},
+                    {"url": "javascript:alert(1)", "title": "skipped"},
+                    {"title": "no url"},
+                    {"url": "http://wiki.corp.example/b"},
+                ]}),
+                10,
+            )
+            .unwrap();
+        assert_eq!(
+            links,
+            vec![
+                DiscoveredLink::search("https://docs.example.com/a".into(), "A".into()),
+                DiscoveredLink::search("http://wiki.corp.example/b".into(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn json_api_provider_uses_declared_fields_and_key_placement() {
+        let provider = ApiProvider::from_entry(&entry(json!({
+            "kind": "json_api",
+            "endpoint": "https://api.search.example/v1/web",
+            "query_parameter": "query",
+            "results_pointer": "/web/results",
+            "url_field": "link",
+            "title_field": "heading",
+            "api_key": "secret",
+            "api_key_parameter": "token",
+        })))
+        .unwrap();
+        assert_eq!(provider.name(), "json_api");
+        assert_eq!(
+            provider.request_url("rust", Some("secret")).as_str(),
+            "https://api.search.example/v1/web?query=rust&token=secret"
+        );
+        let links = provider
+            .parse_results(
+                &json!({"web": {"results": [
+                    {"link": "https://a.example/1", "heading": "One"},
+                    {"link": "https://a.example/2", "heading": "Two"},
+                ]}}),
+                1,
+            )
+            .unwrap();
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].label, "One");
+        assert!(provider.parse_results(&json!({"web": {}}), 5).is_err());
+
+        let header = ApiProvider::from_entry(&entry(json!({
+            "kind": "searxng",
+            "base_url": "http://searx.internal:8080",
+            "api_key_env": "SEARX_TOKEN",
+        })))
+        .unwrap();
+        assert_eq!(
+            header.credential,
+            Some(Credential {
+                source: KeySource::Env("SEARX_TOKEN".into()),
+                placement: KeyPlacement::Header {
+                    name: "Authorization".into(),
+                    prefix: "Bearer ".into(),
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn search_sends_the_configured_key_and_reads_the_json_results() {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut head = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                head.push(line.trim().to_string());
+            }
+            let body = r#"{"results":[{"url":"https://docs.example.com/a","title":"A"}]}"#;
+            write!(
+                &stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            head
+        });
+        let provider = ApiProvider::from_entry(&entry(json!({
+            "kind": "searxng",
+            "base_url": format!("http://127.0.0.1:{port}"),
+            "api_key": "secret",
+            "api_key_header": "X-Search-Key",
+        })))
+        .unwrap();
+        let links = provider.search("rust", 5).unwrap();
+        let head = server.join().unwrap();
+        assert_eq!(head[0], "GET /search?format=json&q=rust HTTP/1.1");
+        assert!(
+            head.iter()
+                .any(|line| line.eq_ignore_ascii_case("x-search-key: secret"))
+        );
+        assert_eq!(links[0].url, "https://docs.example.com/a");
+    }
+
+    #[test]
+    fn provider_entries_reject_fields_that_do_not_apply() {
+        for value in [
+            json!({"kind": "searxng"}),
+            json!({"kind": "searxng", "base_url": "ftp://searx.example"}),
+            json!({"kind": "searxng", "base_url": "https://u:p@searx.example"}),
+            json!({"kind": "searxng", "base_url": "https://searx.example", "endpoint": "https://x.example"}),
+            json!({"kind": "searxng", "base_url": "https://searx.example", "parameters": {"format": "html"}}),
+            json!({"kind": "json_api", "endpoint": "https://api.example/search"}),
+            json!({"kind": "json_api", "endpoint": "https://api.example/search", "results_pointer": "results"}),
+            json!({"kind": "json_api", "endpoint": "https://api.example", "results_pointer": "/r", "parameters": {"q": "x"}}),
+            json!({"kind": "searxng", "base_url": "https://searx.example", "api_key": "k", "api_key_env": "K"}),
+            json!({"kind": "searxng", "base_url": "https://searx.example", "api_key_header": "X-Key"}),
+            json!({"kind": "searxng", "base_url": "https://searx.example", "api_key": "k", "api_key_header": "bad header"}),
+        ] {
+            assert!(
+                ApiProvider::from_entry(&entry(value.clone())).is_err(),
+                "{value}"
+            );
+        }
+        assert!(
+            serde_json::from_value::<ProviderEntry>(json!({"kind": "searxng", "url": "x"}))
+                .is_err()
+        );
+    }
+}
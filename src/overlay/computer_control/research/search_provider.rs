@@ -57,11 +57,20 @@ const RECOVERY_PROVIDER: SearchProvider = SearchProvider {
 };
 
 const PROVIDERS: [SearchProvider; 3] = [DEFAULT_PROVIDER, FALLBACK_PROVIDER, RECOVERY_PROVIDER];
+/// Names a search configuration uses for [`PROVIDERS`], in the same order.
+pub(super) const BUILTIN_NAMES: [&str; 3] = ["google", "bing", "duckduckgo"];
 
 pub(super) fn providers() -> &'static [SearchProvider] {
     &PROVIDERS
 }
 
+pub(super) fn builtin(name: &str) -> Option<&'static SearchProvider> {
+    BUILTIN_NAMES
+        .iter()
+        .position(|builtin| builtin.eq_ignore_ascii_case(name.trim()))
+        .map(|index| &PROVIDERS[index])
+}
+
 impl SearchProvider {
     pub(super) fn name(&self) -> &'static str {
         self.name
@@ -149,6 +158,8 @@ mod tests {
     fn fallback_provider_is_bounded_and_keeps_direct_external_candidates() {
         assert_eq!(providers().len(), 3);
         assert_eq!(providers()[1].name(), "fallback");
+        assert!(builtin("Bing").is_some_and(|provider| provider.name() == "fallback"));
+        assert!(builtin("altavista").is_none());
         assert_eq!(
             providers()[1].search_url("two words").unwrap(),
             "https://www.bing.com/search?q=two+words"
@@ -0,0 +1,284 @@
+//! User-configured search discovery for research.
+//!
+//! `cc_search.toml` (or `cc_search.json`) in the app config directory lists
+//! the providers research tries, in order, and the domains it should prefer
+//! or never read. Without a file research uses the built-in browser
+//! providers. A file that does not load stops research instead of falling
+//! back to providers the user may have excluded on purpose.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use serde::Deserialize;
+
+use super::search_api::{ApiProvider, EntryKind, ProviderEntry};
+use super::search_provider::{self, SearchProvider};
+
+const SUPPORTED_VERSION: u32 = 1;
+const MAX_PROVIDERS: usize = 8;
+const MAX_DOMAIN_RULES: usize = 64;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SearchFile {
+    version: u32,
+    #[serde(default)]
+    providers: Vec<ProviderEntry>,
+    #[serde(default)]
+    domains: DomainRules,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct DomainRules {
+    #[serde(default)]
+    prefer: Vec<String>,
+    #[serde(default)]
+    block: Vec<String>,
+}
+
+/// One way to turn a query into candidate links.
+pub(super) enum Discovery {
+    /// A search page scraped through a temporary browser tab.
+    Browser(&'static SearchProvider),
+    /// A JSON search API called directly.
+    Api(Box<ApiProvider>),
+}
+
+impl Discovery {
+    pub(super) fn name(&self) -> &str {
+        match self {
+            Self::Browser(provider) => provider.name(),
+            Self::Api(provider) => provider.name(),
+        }
+    }
+
+    /// Name safe for telemetry: built-in role or API family.
+    pub(super) fn telemetry_name(&self) -> &'static str {
+        match self {
+            Self::Browser(provider) => provider.name(),
+            Self::Api(provider) => provider.kind_name(),
+        }
+    }
+}
+
+pub(super) struct SearchRegistry {
+    pub(super) providers: Vec<Discovery>,
+    pub(super) preferred_domains: Vec<String>,
+    pub(super) blocked_domains: Vec<String>,
+}
+
+impl SearchRegistry {
+    fn builtin() -> Self {
+        Self {
+            providers: search_provider::providers()
+                .iter()
+                .map(Discovery::Browser)
+                .collect(),
+            preferred_domains: Vec::new(),
+            blocked_domains: Vec::new(),
+        }
+    }
+}
+
+struct CachedRegistry {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    loaded: Arc<Result<SearchRegistry, String>>,
+}
+
+static CACHE: Mutex<Option<CachedRegistry>> = Mutex::new(None);
+
+fn config_paths() -> [PathBuf; 2] {
+    let dir = crate::paths::app_config_dir();
+    [dir.join("cc_search.toml"), dir.join("cc_search.json")]
+}
+
+/// Current registry, re-read whenever the file appears, disappears or changes.
+pub(super) fn current() -> Arc<Result<SearchRegistry, String>> {
+    let path = config_paths().into_iter().find(|path| path.is_file());
+    let modified = path
+        .as_ref()
+        .and_then(|path| std::fs::metadata(path).ok())
+        .and_then(|metadata| metadata.modified().ok());
+    let mut cache = CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(cached) = cache.as_ref()
+        && cached.path == path
+        && cached.modified == modified
+    {
+        return cached.loaded.clone();
+    }
+    let loaded = Arc::new(
+        path.as_deref()
+            .map_or_else(|| Ok(SearchRegistry::builtin()), load),
+    );
+    *cache = Some(CachedRegistry {
+        path,
+        modified,
+        loaded: loaded.clone(),
+    });
+    loaded
+}
+
+fn load(path: &Path) -> Result<SearchRegistry, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|error| format!("cannot read {}: {error}", path.display()))?;
+    let is_toml = path.extension().is_some_and(|ext| ext == "toml");
+    parse(&text, is_toml).map_err(|error| format!("{}: {error}", path.display()))
+}
+
+fn parse(text: &str, is_toml: bool) -> Result<SearchRegistry, String> {
+    let file = if is_toml {
+        toml::from_str::<SearchFile>(text).map_err(|error| error.to_string())?
+    } else {
+        serde_json::from_str::<SearchFile>(text).map_err(|error| error.to_string())?
+    };
+    if file.version != SUPPORTED_VERSION {
+        return Err(format!(
+            "unsupported search configuration version {} (expected {SUPPORTED_VERSION})",
+            file.version
+        ));
+    }
+    if file.providers.len() > MAX_PROVIDERS {
+        return Err(format!("at most {MAX_PROVIDERS} providers are allowed"));
+    }
+    let mut registry = SearchRegistry::builtin();
+    if !file.providers.is_empty() {
+        registry.providers = file
+            .providers
+            .iter()
+            .map(discovery)
+            .collect::<Result<_, _>>()?;
+    }
+    registry.preferred_domains = domain_rules(&file.domains.prefer, "prefer")?;
+    registry.blocked_domains = domain_rules(&file.domains.block, "block")?;
+    if let Some(domain) = registry
+        .preferred_domains
+        .iter()
+        .find(|domain| registry.blocked_domains.contains(domain))
+    {
+        return Err(format!("{domain} is both preferred and blocked"));
+    }
+    Ok(registry)
+}
+
+fn discovery(entry: &ProviderEntry) -> Result<Discovery, String> {
+    if entry.kind != EntryKind::Builtin {
+        return ApiProvider::from_entry(entry).map(|provider| Discovery::Api(Box::new(provider)));
+    }
+    let name = entry.name.as_deref().unwrap_or("");
+    search_provider::builtin(name)
+        .map(Discovery::Browser)
+        .ok_or_else(|| {
+            format!(
+                "unknown builtin provider '{name}' (expected one of {})",
+                search_provider::BUILTIN_NAMES.join(", ")
+            )
+        })
+}
+
+/// Host names matched with their subdomains. A leading `*.` is accepted and
+/// means the same thing.
+fn domain_rules(raw: &[String], list: &str) -> Result<Vec<String>, String> {
+    if raw.len() > MAX_DOMAIN_RULES {
+        return Err(format!(
+            "domains.{list} allows at most {MAX_DOMAIN_RULES} entries"
+        ));
+    }
+    let mut domains = Vec::with_capacity(raw.len());
+    for entry in raw {
+        let value = entry.trim().trim_end_matches('.').to_ascii_lowercase();
+        let value = value.strip_prefix("*.").unwrap_or(&value);
+        let parsed = url::Url::parse(&format!("https://{value}/"))
+            .ok()
+            .and_then(|url| url.domain().map(str::to_string));
+        if value.is_empty() || parsed.as_deref() != Some(value) {
+            return Err(format!(
+                "domains.{list} entry '{entry}' is not a bare host name"
+            ));
+        }
+        if !domains.iter().any(|domain| domain == value) {
+            domains.push(value.to_string());
+        }
+    }
+    Ok(domains)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn providers_and_domains_load_in_declared_order() {
+        let registry = parse(
+            r#"
+version = 1
+
+[[providers]]
+kind = "searxng"
+name = "corp"
+base_url = "https://searx.corp.example"
+
+[[providers]]
+kind = "builtin"
+name = "duckduckgo"
+
+[domains]
+prefer = ["Docs.Corp.Example.", "*.wiki.corp.example"]
+block = ["pinterest.com", "pinterest.com"]
+"#,
+            true,
+        )
+        .unwrap();
+        let names: Vec<_> = registry.providers.iter().map(Discovery::name).collect();
+        assert_eq!(names, ["corp", "recovery"]);
+        assert_eq!(registry.providers[0].telemetry_name(), "searxng");
+        assert_eq!(
+            registry.preferred_domains,
+            ["docs.corp.example", "wiki.corp.example"]
+        );
+        assert_eq!(registry.blocked_domains, ["pinterest.com"]);
+    }
+
+    #[test]
+    fn a_file_without_providers_keeps_the_builtin_order() {
+        let registry = parse(
+            r#"{"version": 1, "domains": {"block": ["example.net"]}}"#,
+            false,
+        )
+        .unwrap();
+        let names: Vec<_> = registry.providers.iter().map(Discovery::name).collect();
+        assert_eq!(names, ["primary", "fallback", "recovery"]);
+        assert_eq!(registry.blocked_domains, ["example.net"]);
+    }
+
+    #[test]
+    fn invalid_configurations_are_rejected_with_a_reason() {
+        for (text, reason) in [
+            (r#"{"version": 2}"#, "version"),
+            (r#"{"version": 1, "engines": []}"#, "unknown field"),
+            (
+                r#"{"version": 1, "providers": [{"kind": "builtin", "name": "altavista"}]}"#,
+                "unknown builtin provider",
+            ),
+            (
+                r#"{"version": 1, "providers": [{"kind": "searxng"}]}"#,
+                "base_url",
+            ),
+            (
+                r#"{"version": 1, "domains": {"block": ["https://example.com/x"]}}"#,
+                "bare host name",
+            ),
+            (
+                r#"{"version": 1, "domains": {"prefer": ["a.example"], "block": ["A.example"]}}"#,
+                "both preferred and blocked",
+            ),
+        ] {
+            let error = parse(text, false).err().unwrap();
+            assert!(error.contains(reason), "{text}: {error}");
+        }
+    }
+}
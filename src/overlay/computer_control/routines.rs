@@ -0,0 +1,278 @@
+//! Saved Computer Control routines: a successful session's tool sequence kept
+//! as `cc_routines/<name>.json` with typed parameter slots, replayed later
+//! with new inputs from the CLI or a routine hotkey. Every step goes through
+//! `Brain::dispatch` (permission policy and all dispatch guards) and is then
+//! re-grounded; a step whose postcondition is not satisfied stops the run.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Result, anyhow};
+use serde_json::{Value, json};
+
+use super::effect_receipt::EffectStatus;
+use super::telemetry::{self, Privacy};
+use super::uia_task::Brain;
+use crate::config::Hotkey;
+
+mod model;
+
+use model::Routine;
+
+/// Cancel flag of the routine started from a hotkey, if one is running.
+static HOTKEY_RUN: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+/// Routine names bound to the routine hotkey ids at the last registration,
+/// in id order.
+static HOTKEY_BINDINGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn routines_dir() -> PathBuf {
+    crate::paths::app_config_dir().join("cc_routines")
+}
+
+fn routine_path(name: &str) -> Result<PathBuf, String> {
+    model::validate_name(name)?;
+    Ok(routines_dir().join(format!("{name}.json")))
+}
+
+fn load(name: &str) -> Result<Routine, String> {
+    let path = routine_path(name)?;
+    let text = std::fs::read_to_string(&path)
+        .map_err(|error| format!("cannot read {}: {error}", path.display()))?;
+    let routine: Routine =
+        serde_json::from_str(&text).map_err(|error| format!("{}: {error}", path.display()))?;
+    if routine.name != name {
+        return Err(format!(
+            "{} declares name {:?}; rename the file or the routine",
+            path.display(),
+            routine.name
+        ));
+    }
+    routine.validate()?;
+    Ok(routine)
+}
+
+/// Valid routines sorted by name. Files that fail to parse are reported and
+/// skipped so one bad edit does not hide the rest.
+fn load_all() -> Vec<Routine> {
+    let Ok(entries) = std::fs::read_dir(routines_dir()) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            path.file_stem()?.to_str().map(str::to_string)
+        })
+        .collect();
+    names.sort();
+    names
+        .into_iter()
+        .filter_map(|name| {
+            load(&name)
+                .inspect_err(|error| eprintln!("[cc-routine] skipping {name}: {error}"))
+                .ok()
+        })
+        .collect()
+}
+
+/// Saves the successful actions of a recorded session as a routine.
+pub(super) fn save_from_trace(
+    trace: &std::path::Path,
+    name: &str,
+    description: &str,
+) -> Result<PathBuf, String> {
+    let path = routine_path(name)?;
+    if path.exists() {
+        return Err(format!(
+            "routine {name} already exists at {}",
+            path.display()
+        ));
+    }
+    let calls = super::runtime::successful_calls(trace)?
+        .into_iter()
+        .filter(|(tool, _)| super::turn_policy::requires_post_dispatch_grounding(tool))
+        .map(|(tool, args)| {
+            // A confirmation covered the original call only; a replay must
+            // ask again.
+            let args = super::permission_policy::without_confirmation(&args).unwrap_or(args);
+            (tool, args)
+        })
+        .collect();
+    let routine = Routine::capture(name, description, calls)?;
+    routine.validate()?;
+    crate::atomic_json::write_json_atomic(&path, &routine)
+        .map_err(|error| format!("cannot write {}: {error}", path.display()))?;
+    Ok(path)
+}
+
+pub(super) fn list() -> Vec<Value> {
+    load_all()
+        .into_iter()
+        .map(|routine| {
+            json!({
+                "name": routine.name,
+                "description": routine.description,
+                "hotkey": routine.hotkey.map(|hotkey| hotkey.name),
+                "params": routine.params.iter().map(|param| json!({
+                    "name": param.name,
+                    "kind": param.kind,
+                    "required": param.default.is_none(),
+                })).collect::<Vec<_>>(),
+                "steps": routine.steps.len(),
+            })
+        })
+        .collect()
+}
+
+/// Runs one routine. Returns the per-step report; the first step that fails
+/// or whose postcondition shows no effect ends the run with an error.
+pub(super) fn run(
+    name: &str,
+    values: &BTreeMap<String, String>,
+    cancel: &Arc<AtomicBool>,
+) -> Result<Vec<Value>> {
+    let routine = load(name).map_err(|error| anyhow!(error))?;
+    let steps = routine.bind(values).map_err(|error| anyhow!(error))?;
+    telemetry::event(
+        "routine_start",
+        "routines",
+        Privacy::Safe,
+        json!({ "routine": routine.name, "steps": steps.len() }),
+    );
+    let mut brain = Brain::new(None);
+    let request = format!("Run saved routine {}", routine.name);
+    brain.record_user_request(0, &request);
+    brain.initial()?;
+    let mut report = Vec::with_capacity(steps.len());
+    for (index, step) in steps.iter().enumerate() {
+        if cancel.load(Ordering::SeqCst) {
+            finish(&routine.name, "cancelled", index);
+            anyhow::bail!(
+                "routine {} cancelled before step {}",
+                routine.name,
+                index + 1
+            );
+        }
+        let ctx = format!(
+            "routine: {}; step {} of {}",
+            routine.name,
+            index + 1,
+            steps.len()
+        );
+        let result = brain.dispatch(&step.tool, &step.args, &ctx, cancel, None, false);
+        let grounded = brain.ground(&step.tool, &step.args)?;
+        let execution_ok = result.get("ok").and_then(Value::as_bool);
+        let mutating = super::turn_policy::is_mutating_tool(&step.tool);
+        let effect_status = EffectStatus::after_dispatch(&result, mutating);
+        let postcondition =
+            grounded
+                .postcondition
+                .response(execution_ok, mutating, effect_status, None);
+        let satisfied = execution_ok != Some(false)
+            && !(grounded.postcondition.detected_no_effect() && !effect_status.is_verified());
+        eprintln!(
+            "[cc-routine] step {}/{} {} -> {}",
+            index + 1,
+            steps.len(),
+            step.tool,
+            if satisfied { "ok" } else { "FAILED" }
+        );
+        report.push(json!({
+            "step": index + 1,
+            "tool": step.tool,
+            "ok": satisfied,
+            "action_result": result,
+            "postcondition": postcondition,
+        }));
+        if !satisfied {
+            finish(&routine.name, "step_failed", index + 1);
+            anyhow::bail!(
+                "routine {} stopped at step {} ({}): {}",
+                routine.name,
+                index + 1,
+                step.tool,
+                serde_json::to_string(&report[index]).unwrap_or_default()
+            );
+        }
+    }
+    brain.retire_session_completed();
+    finish(&routine.name, "completed", steps.len());
+    Ok(report)
+}
+
+fn finish(routine: &str, outcome: &str, steps: usize) {
+    telemetry::event(
+        "routine_end",
+        "routines",
+        Privacy::Safe,
+        json!({ "routine": routine, "outcome": outcome, "steps": steps }),
+    );
+}
+
+/// Hotkeys declared by saved routines, in the order the registration ids use.
+/// Each id is bound to its routine's name here, so routine files added or
+/// removed before the key is pressed cannot move it onto another routine.
+/// Mouse buttons cannot be registered as global hotkeys; those are logged and
+/// left out.
+pub(crate) fn hotkeys() -> Vec<Hotkey> {
+    let mut names = Vec::new();
+    let mut hotkeys = Vec::new();
+    for routine in load_all() {
+        let Some(hotkey) = routine.hotkey else {
+            continue;
+        };
+        if [0x04, 0x05, 0x06].contains(&hotkey.code) {
+            telemetry::human(
+                "routines",
+                format!(
+                    "routine {} hotkey {} not registered: mouse buttons cannot start routines",
+                    routine.name, hotkey.name
+                ),
+            );
+            continue;
+        }
+        names.push(routine.name);
+        hotkeys.push(hotkey);
+    }
+    *HOTKEY_BINDINGS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = names;
+    hotkeys
+}
+
+/// Starts the routine bound to the `index`-th routine hotkey with its default
+/// parameters. Pressing a routine hotkey while one is running cancels it.
+pub(crate) fn run_hotkey(index: usize) {
+    let mut running = HOTKEY_RUN
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(cancel) = running.as_ref() {
+        cancel.store(true, Ordering::SeqCst);
+        return;
+    }
+    let Some(name) = HOTKEY_BINDINGS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(index)
+        .cloned()
+    else {
+        return;
+    };
+    let cancel = Arc::new(AtomicBool::new(false));
+    *running = Some(cancel.clone());
+    std::thread::spawn(move || {
+        let result =
+            super::run_cli_with_engine(|| run(&name, &BTreeMap::new(), &cancel).map(|_| ()));
+        if let Err(error) = result {
+            telemetry::human("routines", format!("routine {name} failed: {error}"));
+        }
+        *HOTKEY_RUN
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    });
+}
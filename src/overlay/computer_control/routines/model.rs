@@ -0,0 +1,395 @@
+//! Routine file format, capture from a recorded session and parameter binding.
+//! Pure data handling; running a routine lives in the parent module.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::Hotkey;
+
+pub(super) const ROUTINE_VERSION: u32 = 1;
+const MAX_NAME_CHARS: usize = 64;
+const MAX_TEXT_CHARS: usize = 8_000;
+/// Argument keys whose string values become typed parameter slots on capture.
+const PATH_KEYS: [&str; 7] = [
+    "path",
+    "cwd",
+    "directory",
+    "destination",
+    "file_path",
+    "folder",
+    "source",
+];
+const URL_KEYS: [&str; 2] = ["url", "href"];
+const TEXT_KEYS: [&str; 4] = ["text", "content", "query", "value"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum ParamKind {
+    FilePath,
+    Url,
+    Text,
+}
+
+impl ParamKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::FilePath => "file_path",
+            Self::Url => "url",
+            Self::Text => "text",
+        }
+    }
+
+    fn validate(self, value: &str) -> Result<(), String> {
+        match self {
+            Self::FilePath => {
+                if value.contains('\0') || !std::path::Path::new(value).is_absolute() {
+                    return Err("expected an absolute file path".to_string());
+                }
+            }
+            Self::Url => {
+                let url = url::Url::parse(value).map_err(|error| error.to_string())?;
+                if !matches!(url.scheme(), "http" | "https") {
+                    return Err("expected an http or https URL".to_string());
+                }
+            }
+            Self::Text => {
+                if value.chars().count() > MAX_TEXT_CHARS {
+                    return Err(format!("text is longer than {MAX_TEXT_CHARS} characters"));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(super) struct ParamSpec {
+    pub(super) name: String,
+    pub(super) kind: ParamKind,
+    #[serde(default)]
+    pub(super) description: String,
+    /// Used when the caller does not supply the parameter; a parameter without
+    /// a default is required.
+    #[serde(default)]
+    pub(super) default: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub(super) struct RoutineStep {
+    pub(super) tool: String,
+    /// Tool arguments; any string may reference parameters as `{{name}}`.
+    pub(super) args: Value,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(super) struct Routine {
+    pub(super) version: u32,
+    pub(super) name: String,
+    #[serde(default)]
+    pub(super) description: String,
+    #[serde(default)]
+    pub(super) hotkey: Option<Hotkey>,
+    #[serde(default)]
+    pub(super) params: Vec<ParamSpec>,
+    pub(super) steps: Vec<RoutineStep>,
+}
+
+pub(super) fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.chars().count() <= MAX_NAME_CHARS
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "routine name must be 1-{MAX_NAME_CHARS} ASCII letters, digits, '-' or '_'"
+        ))
+    }
+}
+
+impl Routine {
+    /// Builds a routine from successful recorded calls. Every distinct path,
+    /// URL or text argument becomes a parameter whose default is the recorded
+    /// value, so the routine replays unchanged until the user edits it.
+    pub(super) fn capture(
+        name: &str,
+        description: &str,
+        calls: Vec<(String, Value)>,
+    ) -> Result<Self, String> {
+        validate_name(name)?;
+        let mut slots = Slots::default();
+        let steps: Vec<RoutineStep> = calls
+            .into_iter()
+            .map(|(tool, mut args)| {
+                slots.parameterize(None, &mut args);
+                RoutineStep { tool, args }
+            })
+            .collect();
+        if steps.is_empty() {
+            return Err("the session has no successful actions to save".to_string());
+        }
+        Ok(Self {
+            version: ROUTINE_VERSION,
+            name: name.to_string(),
+            description: description.to_string(),
+            hotkey: None,
+            params: slots.params,
+            steps,
+        })
+    }
+
+    /// Checks the file against its own parameter list.
+    pub(super) fn validate(&self) -> Result<(), String> {
+        if self.version != ROUTINE_VERSION {
+            return Err(format!(
+                "unsupported routine version {} (expected {ROUTINE_VERSION})",
+                self.version
+            ));
+        }
+        validate_name(&self.name)?;
+        if self.steps.is_empty() {
+            return Err("routine has no steps".to_string());
+        }
+        for (index, param) in self.params.iter().enumerate() {
+            validate_name(&param.name).map_err(|error| format!("parameter {index}: {error}"))?;
+            if self.params[..index].iter().any(|p| p.name == param.name) {
+                return Err(format!("parameter {} is declared twice", param.name));
+            }
+            if let Some(default) = &param.default {
+                param
+                    .kind
+                    .validate(default)
+                    .map_err(|error| format!("default for {}: {error}", param.name))?;
+            }
+        }
+        let declared: HashMap<&str, &str> = self
+            .params
+            .iter()
+            .map(|param| (param.name.as_str(), ""))
+            .collect();
+        for (index, step) in self.steps.iter().enumerate() {
+            let mut args = step.args.clone();
+            substitute(&mut args, &declared)
+                .map_err(|error| format!("step {} ({}): {error}", index + 1, step.tool))?;
+        }
+        Ok(())
+    }
+
+    /// Concrete steps for one run. Supplied values are checked against their
+    /// parameter kinds; unknown and missing parameters are errors.
+    pub(super) fn bind(
+        &self,
+        values: &BTreeMap<String, String>,
+    ) -> Result<Vec<RoutineStep>, String> {
+        self.validate()?;
+        if let Some(unknown) = values
+            .keys()
+            .find(|key| !self.params.iter().any(|param| &param.name == *key))
+        {
+            return Err(format!("routine {} has no parameter {unknown}", self.name));
+        }
+        let mut bound = HashMap::new();
+        for param in &self.params {
+            let value = values
+                .get(&param.name)
+                .or(param.default.as_ref())
+                .ok_or_else(|| format!("missing required parameter {}", param.name))?;
+            param
+                .kind
+                .validate(value)
+                .map_err(|error| format!("{} ({}): {error}", param.name, param.kind.as_str()))?;
+            bound.insert(param.name.as_str(), value.as_str());
+        }
+        self.steps
+            .iter()
+            .map(|step| {
+                let mut args = step.args.clone();
+                substitute(&mut args, &bound)?;
+                Ok(RoutineStep {
+                    tool: step.tool.clone(),
+                    args,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct Slots {
+    params: Vec<ParamSpec>,
+}
+
+impl Slots {
+    fn parameterize(&mut self, key: Option<&str>, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    self.parameterize(Some(key), value);
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.parameterize(key, item);
+                }
+            }
+            Value::String(text) if !text.is_empty() => {
+                // Values that would not pass their own kind check (a relative
+                // path, `about:blank`) stay literal.
+                if let Some(kind) = key.and_then(kind_for_key)
+                    && kind.validate(text).is_ok()
+                {
+                    *text = format!("{{{{{}}}}}", self.slot(kind, text));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn slot(&mut self, kind: ParamKind, recorded: &str) -> String {
+        if let Some(existing) = self
+            .params
+            .iter()
+            .find(|param| param.kind == kind && param.default.as_deref() == Some(recorded))
+        {
+            return existing.name.clone();
+        }
+        let ordinal = self
+            .params
+            .iter()
+            .filter(|param| param.kind == kind)
+            .count()
+            + 1;
+        let name = format!("{}_{ordinal}", kind.as_str());
+        self.params.push(ParamSpec {
+            name: name.clone(),
+            kind,
+            description: String::new(),
+            default: Some(recorded.to_string()),
+        });
+        name
+    }
+}
+
+fn kind_for_key(key: &str) -> Option<ParamKind> {
+    if PATH_KEYS.contains(&key) {
+        Some(ParamKind::FilePath)
+    } else if URL_KEYS.contains(&key) {
+        Some(ParamKind::Url)
+    } else if TEXT_KEYS.contains(&key) {
+        Some(ParamKind::Text)
+    } else {
+        None
+    }
+}
+
+/// Replaces every `{{name}}` in the strings of `value`.
+fn substitute(value: &mut Value, bound: &HashMap<&str, &str>) -> Result<(), String> {
+    match value {
+        Value::Object(map) => map
+            .values_mut()
+            .try_for_each(|value| substitute(value, bound)),
+        Value::Array(items) => items
+            .iter_mut()
+            .try_for_each(|value| substitute(value, bound)),
+        Value::String(text) => {
+            let mut out = String::with_capacity(text.len());
+            let mut rest = text.as_str();
+            while let Some(start) = rest.find("{{") {
+                let end = rest[start..]
+                    .find("}}")
+                    .ok_or_else(|| "unclosed {{ placeholder".to_string())?;
+                let name = rest[start + 2..start + end].trim();
+                let replacement = bound
+                    .get(name)
+                    .ok_or_else(|| format!("undeclared parameter {{{{{name}}}}}"))?;
+                out.push_str(&rest[..start]);
+                out.push_str(replacement);
+                rest = &rest[start + end + 2..];
+            }
+            out.push_str(rest);
+            *text = out;
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn recorded() -> Vec<(String, Value)> {
+        vec![
+            (
+                "browser_navigate".to_string(),
+                json!({ "url": "https://example.com/report" }),
+            ),
+            (
+                "write_text_file".to_string(),
+                json!({ "path": "C:/Reports/today.txt", "content": "Weekly numbers" }),
+            ),
+            (
+                "run_command".to_string(),
+                json!({ "program": "notepad.exe", "args": ["C:/Reports/today.txt"], "cwd": "C:/Reports/today.txt" }),
+            ),
+        ]
+    }
+
+    #[test]
+    fn capture_turns_each_distinct_recorded_value_into_one_typed_slot() {
+        let routine = Routine::capture("daily-report", "", recorded()).unwrap();
+        let names: Vec<_> = routine
+            .params
+            .iter()
+            .map(|param| (param.name.as_str(), param.kind))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("url_1", ParamKind::Url),
+                ("text_1", ParamKind::Text),
+                ("file_path_1", ParamKind::FilePath),
+            ]
+        );
+        assert_eq!(routine.steps[1].args["path"], "{{file_path_1}}");
+        assert_eq!(routine.steps[2].args["cwd"], "{{file_path_1}}");
+        // Positional process arguments are not keyed, so they stay literal.
+        assert_eq!(routine.steps[2].args["args"][0], "C:/Reports/today.txt");
+
+        let steps = routine.bind(&BTreeMap::new()).unwrap();
+        assert_eq!(steps[0].args, recorded()[0].1);
+        assert_eq!(steps[1].args, recorded()[1].1);
+    }
+
+    #[test]
+    fn bind_checks_parameter_kinds_and_names() {
+        let mut routine = Routine::capture("daily-report", "", recorded()).unwrap();
+        let with = |key: &str, value: &str| BTreeMap::from([(key.to_string(), value.to_string())]);
+
+        let steps = routine
+            .bind(&with("url_1", "https://example.org/q?x={{y}}"))
+            .unwrap();
+        assert_eq!(steps[0].args["url"], "https://example.org/q?x={{y}}");
+        assert!(routine.bind(&with("url_1", "file:///C:/x")).is_err());
+        assert!(
+            routine
+                .bind(&with("file_path_1", "relative/x.txt"))
+                .is_err()
+        );
+        assert!(routine.bind(&with("nope", "x")).is_err());
+
+        routine.params[0].default = None;
+        assert!(routine.bind(&BTreeMap::new()).is_err());
+
+        routine.steps[0].args["url"] = json!("{{missing}}");
+        assert!(routine.validate().is_err());
+    }
+}
@@ -25,14 +25,14 @@ use super::uia_task;
 mod action_worker;
 mod action_worker_receive;
 mod cleanup;
-mod completion_responses;
+pub(super) mod completion_responses;
 mod control;
 mod effect_reporting;
 mod frames;
 mod mic;
 mod outcomes;
 mod reader;
-mod reader_policy;
+pub(super) mod reader_policy;
 mod reader_state;
 mod reconnect_gate;
 mod repeat_failure;
@@ -44,6 +44,8 @@ mod scripted_snapshots;
 mod session_control;
 mod speech_events;
 mod terminal_drain;
+mod trace_replay;
+mod usage_meter;
 use action_worker::executor_loop;
 use cleanup::SessionCleanup;
 pub(super) use control::{run, run_scripted, submit_text_command};
@@ -63,6 +65,7 @@ use session_control::{
     reconnect_session,
 };
 use speech_events::{PlaybackTracker, UserAudioTracker};
+pub(super) use trace_replay::{replay_file, successful_calls};
 /// Frame cadence while talking/working; speech onset also pushes a leading frame.
 const FRAME_INTERVAL: Duration = Duration::from_millis(1800);
 const CAPTURE_CACHE_INTERVAL: Duration = Duration::from_millis(500);
@@ -107,6 +110,7 @@ fn run_inner(stop: &Arc<AtomicBool>, scripted_turns: Option<Vec<String>>) -> any
         .transpose()?;
     super::browser::ensure_started();
     let key = session::load_key()?;
+    state.usage = usage_meter::UsageMeter::start(super::protocol::MODEL)?;
     let _engine = super::engine::SessionGuard::start(stop)?;
     let target = configured_target()?;
     overlay::set_status("connecting...");
@@ -222,7 +226,7 @@ fn run_inner(stop: &Arc<AtomicBool>, scripted_turns: Option<Vec<String>>) -> any
     let mut last_mem_check = Instant::now();
     // Set CC_MIC_GATE=1 when speaker echo would otherwise self-interrupt replies.
     let echo_gate = std::env::var("CC_MIC_GATE").is_ok();
-    while !stop.load(Ordering::SeqCst) {
+    while !stop.load(Ordering::SeqCst) && !state.usage.exhausted() {
         cleanup::drain_turn_cleanup_acks(&cleanup_ack_rx, &mut state);
         let sink_failed = sink.as_ref().is_some_and(AudioSink::needs_rebuild);
         let retry_missing_sink =
@@ -584,6 +588,10 @@ fn run_inner(stop: &Arc<AtomicBool>, scripted_turns: Option<Vec<String>>) -> any
     speech_events::discard_generation_audio(&mut state, "session_stopped");
     flush_reply(&mut state); // close the final spoken reply into the transcript
     emit_turn_summary(&mut state, "session_stop");
+    if state.usage.exhausted() {
+        exit_reason = "session_budget_exhausted";
+    }
+    state.usage.finish(&mut state.history);
     if !scripted_mode {
         super::memory::save(state.history.clone(), std::mem::take(&mut mem_frames));
     }
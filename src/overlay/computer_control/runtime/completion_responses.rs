@@ -2,7 +2,7 @@
 
 use serde_json::{Value, json};
 
-pub(super) fn accepted_done_response(summary: &str) -> Value {
+pub(in crate::overlay::computer_control) fn accepted_done_response(summary: &str) -> Value {
     let summary = summary.trim().chars().take(320).collect::<String>();
     json!({
         "ok": true,
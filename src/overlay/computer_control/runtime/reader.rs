@@ -117,6 +117,7 @@ pub(super) fn emit_turn_summary(state: &mut Reader, outcome: &str) {
             "research_count": state.turn_research_count,
             "generation_count": state.model_generation_index,
             "usage_event_count": state.usage_event_index,
+            "usage": state.usage.turn_json(),
             "tool_response_bytes": state.turn_tool_response_bytes,
             "element_chars_delivered": state.turn_element_chars,
             "user_char_count": state.last_user_text.chars().count(),
@@ -217,6 +218,7 @@ pub(super) fn handle_event(
                     state.turn_research_count = 0;
                     state.model_generation_index = 1;
                     state.usage_event_index = 0;
+                    state.usage.start_turn();
                     state.turn_tool_response_bytes = 0;
                     state.turn_element_chars = 0;
                     state.turn_stall_count = 0;
@@ -575,6 +577,12 @@ pub(super) fn handle_event(
                     "tool_cycle_index": state.model_generation_index.saturating_sub(1),
                 }),
             );
+            if let Some(alert) = state.usage.record(&usage) {
+                super::usage_meter::report(alert);
+                if alert == super::usage_meter::BudgetAlert::Exhausted {
+                    state.pending.request_cancel();
+                }
+            }
         }
         _ => {}
     }
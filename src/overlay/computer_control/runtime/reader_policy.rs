@@ -37,16 +37,11 @@ pub(super) fn apply_user_turn_policy(state: &mut Reader, _user_text: &str) -> bo
 }
 
 pub(super) fn mark_pending_interruption(state: &mut Reader) -> bool {
-    let Some(tool) = state.pending.tool.clone() else {
+    let Some(newly_required) = require_reconciliation(state) else {
         return false;
     };
-    if !turn_policy::is_mutating_tool(&tool) {
-        return false;
-    }
-    let newly_required = !state.reconciliation_required;
-    state.reconciliation_required = true;
     if newly_required {
-        state.turn_outcomes.record_interrupted_effect(&tool);
+        let tool = state.pending.tool.clone();
         telemetry::event(
             "interrupted_effect_reconciliation_required",
             "turn_policy",
@@ -60,6 +55,22 @@ pub(super) fn mark_pending_interruption(state: &mut Reader) -> bool {
     true
 }
 
+/// Flags an interrupted mutating call for reconciliation without telemetry.
+/// `None` when the pending call does not mutate; otherwise whether the flag
+/// was newly raised.
+pub(super) fn require_reconciliation(state: &mut Reader) -> Option<bool> {
+    let tool = state.pending.tool.clone()?;
+    if !turn_policy::is_mutating_tool(&tool) {
+        return None;
+    }
+    let newly_required = !state.reconciliation_required;
+    state.reconciliation_required = true;
+    if newly_required {
+        state.turn_outcomes.record_interrupted_effect(&tool);
+    }
+    Some(newly_required)
+}
+
 pub(super) fn refine_turn_mode(state: &mut Reader, _intent: &str, tool: &str) {
     if turn_policy::is_mutating_tool(tool) {
         state.turn_mode = turn_policy::TurnMode::Action;
@@ -145,6 +156,82 @@ pub(super) fn consume_stale_boundary(state: &mut Reader) -> bool {
     std::mem::take(&mut state.ignore_stale_boundary)
 }
 
+/// Why the reader answers a tool call itself instead of dispatching it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(in crate::overlay::computer_control) enum ToolCallBlock {
+    InFlight { prior_turn: bool },
+    ReconciliationRequired,
+}
+
+impl ToolCallBlock {
+    fn status(self) -> &'static str {
+        match self {
+            Self::InFlight { prior_turn: true } => "blocked_prior_action_settling",
+            Self::InFlight { prior_turn: false } => "blocked_tool_call_in_flight",
+            Self::ReconciliationRequired => "blocked_interrupted_effect_reconciliation_required",
+        }
+    }
+
+    fn code(self) -> &'static str {
+        match self {
+            Self::InFlight { prior_turn: true } => "prior_action_settling",
+            Self::InFlight { prior_turn: false } => "tool_call_in_flight",
+            Self::ReconciliationRequired => "interrupted_effect_reconciliation_required",
+        }
+    }
+
+    /// The not-run response the model receives for the blocked call.
+    pub(in crate::overlay::computer_control) fn response(self) -> Value {
+        match self {
+            Self::InFlight { prior_turn } => {
+                let message = if prior_turn {
+                    "The interrupted prior action is still settling. This call did not run. After its receipt arrives, inspect fresh state before any completion or mutation claim."
+                } else {
+                    "Only one computer tool executes at a time. This call did not run. After the in-flight result arrives, retry it only if still needed against fresh state."
+                };
+                serde_json::json!({
+                    "ok": false,
+                    "status": self.status(),
+                    "executed": false,
+                    "effect_may_have_occurred": false,
+                    "error": {
+                        "code": self.code(),
+                        "message": message,
+                    }
+                })
+            }
+            Self::ReconciliationRequired => serde_json::json!({
+                "ok": false,
+                "status": self.status(),
+                "executed": false,
+                "error": {
+                    "code": self.code(),
+                    "message": "An interrupted mutation still needs fresh observed state. Use a read or observation capability before any mutation or completion claim."
+                }
+            }),
+        }
+    }
+}
+
+/// The gate's decision alone, with no telemetry and no reader changes, so an
+/// offline replay can ask the same question the live reader does.
+pub(super) fn tool_call_block(state: &Reader, name: &str, turn_id: u64) -> Option<ToolCallBlock> {
+    // One local action owns the executor at a time. Keep later calls from the
+    // same model generation pending until the owner's result has been delivered;
+    // replying early would start a competing generation without that result.
+    if state.pending.id.is_some() {
+        let prior_turn = state
+            .pending
+            .turn_id
+            .is_some_and(|pending_turn| pending_turn != turn_id);
+        return Some(ToolCallBlock::InFlight { prior_turn });
+    }
+    if state.reconciliation_required && (turn_policy::is_mutating_tool(name) || name == "done") {
+        return Some(ToolCallBlock::ReconciliationRequired);
+    }
+    None
+}
+
 /// Return true when policy handled the call locally, so the caller must not send
 /// it to the executor.
 pub(super) fn guard_tool_call(
@@ -154,45 +241,12 @@ pub(super) fn guard_tool_call(
     _args: &Value,
     action: telemetry::ActionTrace,
 ) -> bool {
-    // One local action owns the executor at a time. Keep later calls from the
-    // same model generation pending until the owner's result has been delivered;
-    // replying early would start a competing generation without that result.
-    if state.pending.id.is_some() {
-        let prior_turn = state
-            .pending
-            .turn_id
-            .is_some_and(|pending_turn| pending_turn != action.turn_id);
-        let status = if prior_turn {
-            "blocked_prior_action_settling"
-        } else {
-            "blocked_tool_call_in_flight"
-        };
-        let code = if prior_turn {
-            "prior_action_settling"
-        } else {
-            "tool_call_in_flight"
-        };
-        let message = if prior_turn {
-            "The interrupted prior action is still settling. This call did not run. After its receipt arrives, inspect fresh state before any completion or mutation claim."
-        } else {
-            "Only one computer tool executes at a time. This call did not run. After the in-flight result arrives, retry it only if still needed against fresh state."
-        };
-        queue(
-            state,
-            id,
-            name,
-            serde_json::json!({
-                "ok": false,
-                "status": status,
-                "executed": false,
-                "effect_may_have_occurred": false,
-                "error": {
-                    "code": code,
-                    "message": message,
-                }
-            }),
-        );
-        telemetry::typed_error(
+    let Some(block) = tool_call_block(state, name, action.turn_id) else {
+        return false;
+    };
+    queue(state, id, name, block.response());
+    match block {
+        ToolCallBlock::InFlight { prior_turn } => telemetry::typed_error(
             if prior_turn {
                 "ERR_PRIOR_ACTION_SETTLING"
             } else {
@@ -205,68 +259,35 @@ pub(super) fn guard_tool_call(
                 "pending_turn_id": state.pending.turn_id,
                 "requested_turn_id": action.turn_id,
             }),
-        );
-        telemetry::event_for_action(
-            "action_outcome",
-            "turn_policy",
-            telemetry::Privacy::Safe,
-            action,
-            serde_json::json!({
-                "tool_call_id": id,
-                "requested_tool": name,
-                "executed": false,
-                "effect_may_have_occurred": false,
-                "effect_status": "proven_no_effect",
-                "status": status,
-                "error_code": code,
-            }),
-        );
-        return true;
+        ),
+        ToolCallBlock::ReconciliationRequired => {
+            state.awaiting = true;
+            state.recovery_owed = true;
+            state.think_start = Some(Instant::now());
+            telemetry::typed_error(
+                "ERR_TOOL_BEFORE_EFFECT_RECONCILIATION",
+                "turn_policy",
+                "blocked a mutation before fresh state reconciled an interrupted action",
+                serde_json::json!({"tool": name}),
+            );
+        }
     }
-
-    if state.reconciliation_required && (turn_policy::is_mutating_tool(name) || name == "done") {
-        queue(
-            state,
-            id,
-            name,
-            serde_json::json!({
-                "ok": false,
-                "status": "blocked_interrupted_effect_reconciliation_required",
-                "executed": false,
-                "error": {
-                    "code": "interrupted_effect_reconciliation_required",
-                    "message": "An interrupted mutation still needs fresh observed state. Use a read or observation capability before any mutation or completion claim."
-                }
-            }),
-        );
-        state.awaiting = true;
-        state.recovery_owed = true;
-        state.think_start = Some(Instant::now());
-        telemetry::typed_error(
-            "ERR_TOOL_BEFORE_EFFECT_RECONCILIATION",
-            "turn_policy",
-            "blocked a mutation before fresh state reconciled an interrupted action",
-            serde_json::json!({"tool": name}),
-        );
-        telemetry::event_for_action(
-            "action_outcome",
-            "turn_policy",
-            telemetry::Privacy::Safe,
-            action,
-            serde_json::json!({
-                "tool_call_id": id,
-                "requested_tool": name,
-                "executed": false,
-                "effect_status": "proven_no_effect",
-                "effect_may_have_occurred": false,
-                "status": "blocked_interrupted_effect_reconciliation_required",
-                "error_code": "interrupted_effect_reconciliation_required",
-            }),
-        );
-        return true;
-    }
-
-    false
+    telemetry::event_for_action(
+        "action_outcome",
+        "turn_policy",
+        telemetry::Privacy::Safe,
+        action,
+        serde_json::json!({
+            "tool_call_id": id,
+            "requested_tool": name,
+            "executed": false,
+            "effect_may_have_occurred": false,
+            "effect_status": "proven_no_effect",
+            "status": block.status(),
+            "error_code": block.code(),
+        }),
+    );
+    true
 }
 
 fn queue(state: &mut Reader, id: &str, name: &str, response: Value) {
@@ -332,3 +332,21 @@ fn cancellation_is_monotonic_and_scoped_to_one_job() {
     assert!(!second.matches_result("shared-id", &first_cancel));
     assert!(second.matches_result("shared-id", &second_cancel));
 }
+
+#[test]
+fn gate_decision_alone_leaves_the_reader_untouched() {
+    let state = Reader {
+        reconciliation_required: true,
+        ..Reader::default()
+    };
+
+    assert_eq!(tool_call_block(&state, "observe", 1), None);
+    let block = tool_call_block(&state, "edit_text_file", 1).unwrap();
+    assert_eq!(block, ToolCallBlock::ReconciliationRequired);
+    assert_eq!(
+        block.response()["error"]["code"],
+        "interrupted_effect_reconciliation_required"
+    );
+    assert!(state.immediate_tool_responses.is_empty());
+    assert!(!state.awaiting);
+}
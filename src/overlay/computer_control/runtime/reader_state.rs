@@ -134,6 +134,8 @@ pub(super) struct Reader {
     pub(super) turn_research_count: u32,
     pub(super) model_generation_index: u32,
     pub(super) usage_event_index: u32,
+    /// Per-turn and per-session token and cost accounting against the budget.
+    pub(super) usage: super::usage_meter::UsageMeter,
     pub(super) turn_tool_response_bytes: usize,
     pub(super) turn_element_chars: usize,
     pub(super) turn_stall_count: u32,
@@ -366,15 +366,10 @@ fn settle_transport_receipt(state: &mut Reader, tool: &str, response: &Value) {
     }
 }
 
-fn reconcile_from_observation(state: &mut Reader, tool: &str, response_ok: bool) {
-    if !state.reconciliation_required
-        || !response_ok
-        || !super::super::turn_policy::provides_reconciliation_evidence(tool)
-    {
+pub(super) fn reconcile_from_observation(state: &mut Reader, tool: &str, response_ok: bool) {
+    if !clear_reconciliation(state, tool, response_ok) {
         return;
     }
-    state.reconciliation_required = false;
-    state.turn_outcomes.clear_interruption_uncertainty();
     telemetry::event(
         "interrupted_effect_reconciled",
         "runtime",
@@ -386,6 +381,20 @@ fn reconcile_from_observation(state: &mut Reader, tool: &str, response_ok: bool)
     );
 }
 
+/// Clears a pending reconciliation once a fresh observation succeeded, without
+/// telemetry. Returns whether anything was cleared.
+pub(super) fn clear_reconciliation(state: &mut Reader, tool: &str, response_ok: bool) -> bool {
+    if !state.reconciliation_required
+        || !response_ok
+        || !super::super::turn_policy::provides_reconciliation_evidence(tool)
+    {
+        return false;
+    }
+    state.reconciliation_required = false;
+    state.turn_outcomes.clear_interruption_uncertainty();
+    true
+}
+
 #[cfg(test)]
 #[path = "results_claim_tests.rs"]
 mod claim_tests;
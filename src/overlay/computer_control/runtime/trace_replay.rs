@@ -0,0 +1,218 @@
+//! Offline replay of a recorded Computer Control session.
+//!
+//! The recorded model tool calls are streamed back through the same
+//! deterministic decisions the live runtime makes before and after dispatch:
+//! the reader's in-flight and reconciliation gate, explicit completion, the
+//! repeat-outcome circuit and the user's permission policy. A fake executor
+//! answers with the receipt the trace recorded instead of touching the
+//! desktop, and every replayed decision is diffed against the original.
+//!
+//! Guards that need a live surface (UIA enumeration, grounding, vision) are
+//! not replayed; their recorded results pass through unchanged. The trace
+//! keeps no frame identity, so the repeat circuit runs without surface keys.
+
+use std::path::Path;
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use super::super::permission_policy::{self, Loaded};
+use super::completion_responses::accepted_done_response;
+use super::reader::{Pending, Reader};
+use super::reader_policy;
+use super::repeat_failure::RepeatFailureGuard;
+use trace::{Decision, RecordedCall, RecordedSession, Step};
+
+mod trace;
+
+#[cfg(test)]
+#[path = "trace_replay_tests.rs"]
+mod tests;
+
+#[derive(Serialize)]
+pub(crate) struct DecisionDiff {
+    tool_call_id: String,
+    action_id: u64,
+    turn_id: u64,
+    tool: String,
+    recorded: Decision,
+    replayed: Decision,
+}
+
+#[derive(Serialize)]
+pub(crate) struct ReplayReport {
+    pub(crate) calls: usize,
+    pub(crate) matched: usize,
+    pub(crate) diffs: Vec<DecisionDiff>,
+}
+
+/// Replays `trace_path` against `policy_path`, or the user's current policy
+/// when none is given.
+pub(crate) fn replay_file(
+    trace_path: &Path,
+    policy_path: Option<&Path>,
+) -> Result<ReplayReport, String> {
+    let session = read_session(trace_path)?;
+    let policy = match policy_path {
+        Some(path) => {
+            let loaded = permission_policy::load(path);
+            if let Loaded::Invalid { error, .. } = &loaded {
+                return Err(error.clone());
+            }
+            std::sync::Arc::new(loaded)
+        }
+        None => permission_policy::current(),
+    };
+    Ok(replay(&session, &policy))
+}
+
+/// Tool calls in `trace_path` that ran and reported success, in call order.
+/// Saved routines are built from these.
+pub(crate) fn successful_calls(trace_path: &Path) -> Result<Vec<(String, Value)>, String> {
+    let session = read_session(trace_path)?;
+    Ok(session
+        .calls
+        .into_iter()
+        .filter(|call| matches!(call.decision, Decision::Executed { ok: Some(true), .. }))
+        .map(|call| (call.name, call.args))
+        .collect())
+}
+
+fn read_session(trace_path: &Path) -> Result<RecordedSession, String> {
+    let text = std::fs::read_to_string(trace_path)
+        .map_err(|error| format!("cannot read {}: {error}", trace_path.display()))?;
+    trace::parse(&text)
+}
+
+fn replay(session: &RecordedSession, policy: &Loaded) -> ReplayReport {
+    let mut reader = Reader::default();
+    let mut repeat_failures = RepeatFailureGuard::default();
+    let mut replayed: Vec<Option<Decision>> = vec![None; session.calls.len()];
+    for step in &session.steps {
+        match step {
+            Step::TurnStart => {
+                // What a new user turn does to the gate, without the live
+                // turn telemetry.
+                if reader.pending.request_cancel() {
+                    reader_policy::require_reconciliation(&mut reader);
+                }
+                reader.pending = Pending::default();
+            }
+            Step::Call(index) => {
+                let call = &session.calls[*index];
+                let decision = decide(call, &reader, &mut repeat_failures, policy);
+                if matches!(decision, Decision::Executed { .. } | Decision::Completed) {
+                    reader.pending = Pending {
+                        id: Some(call.tool_call_id.clone()),
+                        tool: Some(call.name.clone()),
+                        turn_id: Some(call.turn_id),
+                        cancelled: false,
+                        cancel: None,
+                    };
+                }
+                replayed[*index] = Some(decision);
+            }
+            Step::Cancel(ids) => {
+                let owns_pending = reader
+                    .pending
+                    .id
+                    .as_ref()
+                    .is_some_and(|pending| ids.contains(pending));
+                if owns_pending {
+                    reader.pending.request_cancel();
+                    reader_policy::require_reconciliation(&mut reader);
+                }
+            }
+            Step::Settled(index) => {
+                let call = &session.calls[*index];
+                if reader.pending.id.as_deref() != Some(call.tool_call_id.as_str()) {
+                    continue;
+                }
+                let delivered = !reader.pending.cancelled;
+                reader.pending = Pending::default();
+                if delivered
+                    && let Some(Decision::Executed { ok: Some(true), .. }) = &replayed[*index]
+                {
+                    super::results::clear_reconciliation(&mut reader, &call.name, true);
+                }
+            }
+        }
+    }
+
+    let mut report = ReplayReport {
+        calls: session.calls.len(),
+        matched: 0,
+        diffs: Vec::new(),
+    };
+    for (call, replayed) in session.calls.iter().zip(replayed) {
+        let replayed = replayed.unwrap_or(Decision::Unsettled);
+        if replayed == call.decision || call.decision == Decision::Unsettled {
+            report.matched += 1;
+            continue;
+        }
+        report.diffs.push(DecisionDiff {
+            tool_call_id: call.tool_call_id.clone(),
+            action_id: call.action_id,
+            turn_id: call.turn_id,
+            tool: call.name.clone(),
+            recorded: call.decision.clone(),
+            replayed,
+        });
+    }
+    report
+}
+
+/// One call through the gates in live pipeline order: reader, completion,
+/// repeat circuit, permission policy, then the fake executor.
+fn decide(
+    call: &RecordedCall,
+    reader: &Reader,
+    repeat_failures: &mut RepeatFailureGuard,
+    policy: &Loaded,
+) -> Decision {
+    // Like the policy check below, the reader gate is asked for its decision
+    // only, so a replay never writes to the live event log or session trace.
+    if let Some(block) = reader_policy::tool_call_block(reader, &call.name, call.turn_id) {
+        return blocked(&block.response());
+    }
+    if call.name == "done" {
+        let summary = call.args["summary"].as_str().unwrap_or("");
+        let response = accepted_done_response(summary);
+        return if response["ok"] == true {
+            Decision::Completed
+        } else {
+            blocked(&response)
+        };
+    }
+    if let Some(response) =
+        repeat_failures.blocked_result(call.turn_id, &call.name, &call.args, None)
+    {
+        return blocked(&response);
+    }
+    // The decision alone: a replay must not add to the live policy telemetry.
+    if let Some(response) =
+        permission_policy::evaluate(policy, &call.name, &call.args).and_then(|d| d.refusal)
+    {
+        return blocked(&response);
+    }
+    let result = call.result.clone().unwrap_or_else(|| {
+        // The original run never executed this call, so there is no receipt
+        // to hand back; treat it as an effect of unknown outcome.
+        json!({ "ok": Value::Null, "code": "replay_no_recorded_result" })
+    });
+    repeat_failures.observe(call.turn_id, &call.name, &call.args, None, &result);
+    Decision::Executed {
+        ok: result["ok"].as_bool(),
+        code: result["code"].as_str().map(str::to_string),
+    }
+}
+
+fn blocked(response: &Value) -> Decision {
+    let code = ["/code", "/error/code", "/status"]
+        .into_iter()
+        .find_map(|pointer| response.pointer(pointer).and_then(Value::as_str))
+        .unwrap_or("blocked");
+    Decision::Blocked {
+        code: code.to_string(),
+    }
+}
@@ -0,0 +1,184 @@
+//! Reads a session `events.jsonl` back into the ordered stream the replay
+//! needs: user-turn starts, model tool calls with their recorded decisions,
+//! server cancellations, and the point where each call settled.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use serde_json::{Value, json};
+
+/// What the runtime did with one model tool call.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub(super) enum Decision {
+    Executed {
+        ok: Option<bool>,
+        code: Option<String>,
+    },
+    Blocked {
+        code: String,
+    },
+    Completed,
+    /// The trace ends before the call produced any outcome.
+    Unsettled,
+}
+
+pub(super) struct RecordedCall {
+    pub(super) turn_id: u64,
+    pub(super) action_id: u64,
+    pub(super) tool_call_id: String,
+    pub(super) name: String,
+    pub(super) args: Value,
+    pub(super) decision: Decision,
+    /// The executor result as far as the trace preserves it; the fake
+    /// executor hands this back instead of touching the desktop.
+    pub(super) result: Option<Value>,
+}
+
+pub(super) enum Step {
+    TurnStart,
+    Call(usize),
+    Cancel(Vec<String>),
+    Settled(usize),
+}
+
+pub(super) struct RecordedSession {
+    pub(super) calls: Vec<RecordedCall>,
+    pub(super) steps: Vec<Step>,
+}
+
+pub(super) fn parse(text: &str) -> Result<RecordedSession, String> {
+    let mut session = RecordedSession {
+        calls: Vec::new(),
+        steps: Vec::new(),
+    };
+    let mut by_action: HashMap<u64, usize> = HashMap::new();
+    let mut by_call_id: HashMap<String, usize> = HashMap::new();
+    let mut settled = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: Value = serde_json::from_str(line)
+            .map_err(|error| format!("trace line {}: {error}", line_index + 1))?;
+        let fields = &record["fields"];
+        let event = record["event"].as_str().unwrap_or("");
+        let call = record["action_id"]
+            .as_u64()
+            .or_else(|| fields["step_id"].as_u64())
+            .and_then(|id| by_action.get(&id).copied())
+            .or_else(|| {
+                fields["tool_call_id"]
+                    .as_str()
+                    .and_then(|id| by_call_id.get(id).copied())
+            });
+        match event {
+            "turn_start" => session.steps.push(Step::TurnStart),
+            "tool_call_payload" => {
+                let (Some(action_id), Some(id), Some(name)) = (
+                    fields["step_id"].as_u64(),
+                    fields["tool_call_id"].as_str(),
+                    fields["name"].as_str(),
+                ) else {
+                    continue;
+                };
+                let index = session.calls.len();
+                session.calls.push(RecordedCall {
+                    turn_id: record["turn_id"].as_u64().unwrap_or(0),
+                    action_id,
+                    tool_call_id: id.to_string(),
+                    name: name.to_string(),
+                    args: fields["args"].clone(),
+                    decision: Decision::Unsettled,
+                    result: None,
+                });
+                settled.push(false);
+                by_action.insert(action_id, index);
+                by_call_id.insert(id.to_string(), index);
+                session.steps.push(Step::Call(index));
+            }
+            "typed_error" if fields["code"] == "ERR_TOOL_CANCELLED" => {
+                let ids = fields["fields"]["ids"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect();
+                session.steps.push(Step::Cancel(ids));
+            }
+            _ => {
+                let Some(index) = call else {
+                    continue;
+                };
+                let worker = record["component"] == "action_worker";
+                let Some(decision) = recorded_decision(event, worker, fields) else {
+                    continue;
+                };
+                let call = &mut session.calls[index];
+                // The worker's closing outcome only fills in a call that left
+                // no earlier evidence, e.g. one cancelled before dispatch.
+                if worker && event == "action_outcome" && call.decision != Decision::Unsettled {
+                    continue;
+                }
+                // Explicit guard evidence outranks the generic tool result
+                // that `finish_dispatch` also writes for a refused call.
+                if !matches!(call.decision, Decision::Blocked { .. }) {
+                    if let Decision::Executed { ok, code } = &decision {
+                        call.result = Some(json!({
+                            "ok": ok,
+                            "code": code,
+                            "error": fields["fields"]["error"],
+                        }));
+                    }
+                    call.decision = decision;
+                }
+                if !std::mem::replace(&mut settled[index], true) {
+                    session.steps.push(Step::Settled(index));
+                }
+            }
+        }
+    }
+    Ok(session)
+}
+
+fn recorded_decision(event: &str, worker: bool, fields: &Value) -> Option<Decision> {
+    let code = |value: &Value| {
+        value
+            .as_str()
+            .filter(|code| !code.is_empty())
+            .map(str::to_string)
+    };
+    match event {
+        "completion_declared" => Some(Decision::Completed),
+        "action_guard_blocked" => Some(Decision::Blocked {
+            code: code(&fields["code"]).unwrap_or_else(|| "guard_blocked".to_string()),
+        }),
+        "permission_policy" => match fields["outcome"].as_str() {
+            Some("denied") if fields["reason"] == "policy_invalid" => Some(Decision::Blocked {
+                code: "ERR_POLICY_INVALID".to_string(),
+            }),
+            Some("denied") => Some(Decision::Blocked {
+                code: "ERR_POLICY_DENIED".to_string(),
+            }),
+            Some("confirm_required") => Some(Decision::Blocked {
+                code: "ERR_POLICY_CONFIRM_REQUIRED".to_string(),
+            }),
+            _ => None,
+        },
+        "action_outcome" if worker => Some(Decision::Executed {
+            ok: fields["ok"].as_bool(),
+            code: code(&fields["error_code"]),
+        }),
+        "action_outcome" if fields["executed"] == false => Some(Decision::Blocked {
+            code: code(&fields["error_code"])
+                .or_else(|| code(&fields["status"]))
+                .unwrap_or_else(|| "not_executed".to_string()),
+        }),
+        "tool_result" => Some(Decision::Executed {
+            ok: fields["ok"].as_bool(),
+            code: code(&fields["fields"]["code"]),
+        }),
+        _ => None,
+    }
+}
@@ -0,0 +1,112 @@
+use super::*;
+
+fn record(action_id: u64, event: &str, component: &str, fields: Value) -> String {
+    json!({
+        "session_id": "s",
+        "turn_id": 1,
+        "action_id": action_id,
+        "event": event,
+        "component": component,
+        "fields": fields,
+    })
+    .to_string()
+}
+
+fn call(step_id: u64, name: &str, args: Value) -> String {
+    record(
+        0,
+        "tool_call_payload",
+        "runtime",
+        json!({
+            "step_id": step_id,
+            "tool_call_id": format!("call-{step_id}"),
+            "name": name,
+            "args": args,
+        }),
+    )
+}
+
+fn result(step_id: u64, ok: bool, code: &str) -> String {
+    record(
+        step_id,
+        "tool_result",
+        "action_worker",
+        json!({ "tool": "x", "ok": ok, "fields": { "code": code } }),
+    )
+}
+
+fn session(lines: &[String]) -> RecordedSession {
+    let mut text = record(0, "turn_start", "runtime", json!({}));
+    for line in lines {
+        text.push('\n');
+        text.push_str(line);
+    }
+    trace::parse(&text).expect("trace parses")
+}
+
+#[test]
+fn unchanged_gates_reproduce_every_recorded_decision() {
+    let args = json!({ "target": 7 });
+    let session = session(&[
+        call(1, "click_element", args.clone()),
+        call(2, "read_screen", json!({})),
+        record(
+            2,
+            "action_outcome",
+            "turn_policy",
+            json!({ "executed": false, "error_code": "tool_call_in_flight" }),
+        ),
+        result(1, false, "ERR_TEMPORARY"),
+        call(3, "click_element", args.clone()),
+        result(3, false, "ERR_TEMPORARY"),
+        call(4, "click_element", args),
+        record(
+            4,
+            "action_guard_blocked",
+            "action_worker",
+            json!({ "code": "ERR_EQUIVALENT_FAILURE_LIMIT" }),
+        ),
+        call(5, "done", json!({ "summary": "stopped" })),
+        record(5, "completion_declared", "action_worker", json!({})),
+    ]);
+
+    let report = replay(&session, &Loaded::None);
+
+    assert_eq!(report.calls, 5);
+    assert_eq!(report.matched, 5);
+    assert!(report.diffs.is_empty());
+}
+
+#[test]
+fn stricter_policy_reports_the_calls_it_would_have_refused() {
+    let path = std::env::temp_dir().join(format!("cc_policy_replay_{}.toml", std::process::id()));
+    std::fs::write(&path, "version = 1\n[shell]\ndeny = [\"del *\"]\n").unwrap();
+    let policy = permission_policy::load(&path);
+    let _ = std::fs::remove_file(&path);
+    let session = session(&[
+        call(1, "run_command", json!({ "command": "del notes.txt" })),
+        result(1, true, ""),
+        call(2, "run_command", json!({ "command": "dir" })),
+        result(2, true, ""),
+    ]);
+
+    let report = replay(&session, &policy);
+
+    assert_eq!(report.matched, 1);
+    assert_eq!(report.diffs.len(), 1);
+    let diff = &report.diffs[0];
+    assert_eq!(diff.tool_call_id, "call-1");
+    assert_eq!(
+        diff.recorded,
+        Decision::Executed {
+            ok: Some(true),
+            code: None
+        }
+    );
+    assert_eq!(
+        diff.replayed,
+        Decision::Blocked {
+            code: "ERR_POLICY_DENIED".to_string()
+        }
+    );
+}
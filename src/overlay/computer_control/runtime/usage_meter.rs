@@ -0,0 +1,347 @@
+//! Token and cost accounting for one Live session.
+//!
+//! Provider `usageMetadata` frames are split by modality, tallied per turn and
+//! per session, and priced from the model catalog. `cc_budget.toml` (or
+//! `cc_budget.json`) in the app config directory caps a session by tokens,
+//! estimated cost, or both: crossing `warn_at_percent` logs a warning once and
+//! reaching the cap cancels the pending job and ends the session.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use super::super::overlay;
+use super::super::telemetry::{self, Privacy};
+use crate::model_config::TokenPricing;
+
+const SUPPORTED_VERSION: u32 = 1;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BudgetFile {
+    version: u32,
+    #[serde(default)]
+    max_session_tokens: Option<u64>,
+    #[serde(default)]
+    max_session_cost_usd: Option<f64>,
+    #[serde(default = "default_warn_at_percent")]
+    warn_at_percent: u8,
+}
+
+fn default_warn_at_percent() -> u8 {
+    80
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Budget {
+    max_tokens: Option<u64>,
+    max_cost_usd: Option<f64>,
+    warn_at_percent: u8,
+}
+
+impl BudgetFile {
+    fn validate(self) -> Result<Budget, String> {
+        if self.version != SUPPORTED_VERSION {
+            return Err(format!(
+                "unsupported budget file version {} (expected {SUPPORTED_VERSION})",
+                self.version
+            ));
+        }
+        if self.max_session_tokens == Some(0) {
+            return Err("max_session_tokens must be positive".into());
+        }
+        if self
+            .max_session_cost_usd
+            .is_some_and(|cost| !cost.is_finite() || cost <= 0.0)
+        {
+            return Err("max_session_cost_usd must be a positive number".into());
+        }
+        if !(1..=100).contains(&self.warn_at_percent) {
+            return Err("warn_at_percent must be between 1 and 100".into());
+        }
+        Ok(Budget {
+            max_tokens: self.max_session_tokens,
+            max_cost_usd: self.max_session_cost_usd,
+            warn_at_percent: self.warn_at_percent,
+        })
+    }
+}
+
+/// The configured budget; `None` when no budget file exists.
+fn load_budget() -> Result<Option<Budget>, String> {
+    let dir = crate::paths::app_config_dir();
+    let Some(path) = [dir.join("cc_budget.toml"), dir.join("cc_budget.json")]
+        .into_iter()
+        .find(|path| path.is_file())
+    else {
+        return Ok(None);
+    };
+    let text = std::fs::read_to_string(&path)
+        .map_err(|error| format!("cannot read {}: {error}", path.display()))?;
+    parse_budget(&path, &text).map(Some)
+}
+
+fn parse_budget(path: &Path, text: &str) -> Result<Budget, String> {
+    let file = if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str::<BudgetFile>(text).map_err(|error| error.to_string())
+    } else {
+        serde_json::from_str::<BudgetFile>(text).map_err(|error| error.to_string())
+    };
+    file.and_then(BudgetFile::validate)
+        .map_err(|error| format!("{}: {error}", path.display()))
+}
+
+/// Tokens by direction and modality.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub(super) struct TokenTally {
+    pub(super) input_text: u64,
+    pub(super) input_audio: u64,
+    pub(super) input_image: u64,
+    pub(super) output_text: u64,
+    pub(super) output_audio: u64,
+}
+
+impl TokenTally {
+    /// Splits one Gemini `usageMetadata` object. Input tokens the provider
+    /// does not itemize by modality count as text; thinking counts as output
+    /// text.
+    pub(super) fn from_usage(usage: &Value) -> Self {
+        let count = |key: &str| usage.get(key).and_then(Value::as_u64).unwrap_or(0);
+        let mut tally = Self::default();
+        let mut itemized = 0;
+        for key in ["promptTokensDetails", "toolUsePromptTokensDetails"] {
+            for (modality, tokens) in modality_counts(usage, key) {
+                itemized += tokens;
+                match modality {
+                    "AUDIO" => tally.input_audio += tokens,
+                    "IMAGE" | "VIDEO" => tally.input_image += tokens,
+                    _ => tally.input_text += tokens,
+                }
+            }
+        }
+        let input = count("promptTokenCount") + count("toolUsePromptTokenCount");
+        tally.input_text += input.saturating_sub(itemized);
+        let mut response_itemized = 0;
+        for (modality, tokens) in modality_counts(usage, "responseTokensDetails") {
+            response_itemized += tokens;
+            match modality {
+                "AUDIO" => tally.output_audio += tokens,
+                _ => tally.output_text += tokens,
+            }
+        }
+        tally.output_text += count("responseTokenCount").saturating_sub(response_itemized);
+        tally.output_text += count("thoughtsTokenCount");
+        tally
+    }
+
+    pub(super) fn input(&self) -> u64 {
+        self.input_text + self.input_audio + self.input_image
+    }
+
+    pub(super) fn output(&self) -> u64 {
+        self.output_text + self.output_audio
+    }
+
+    pub(super) fn total(&self) -> u64 {
+        self.input() + self.output()
+    }
+
+    fn add(&mut self, other: &Self) {
+        self.input_text += other.input_text;
+        self.input_audio += other.input_audio;
+        self.input_image += other.input_image;
+        self.output_text += other.output_text;
+        self.output_audio += other.output_audio;
+    }
+
+    pub(super) fn cost_usd(&self, pricing: &TokenPricing) -> f64 {
+        let priced = [
+            (self.input_text, pricing.input_text),
+            (self.input_audio, pricing.input_audio),
+            (self.input_image, pricing.input_image),
+            (self.output_text, pricing.output_text),
+            (self.output_audio, pricing.output_audio),
+        ];
+        priced
+            .iter()
+            .map(|(tokens, rate)| *tokens as f64 * rate / 1_000_000.0)
+            .sum()
+    }
+}
+
+fn modality_counts<'a>(usage: &'a Value, key: &str) -> impl Iterator<Item = (&'a str, u64)> {
+    usage
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|entry| {
+            (
+                entry.get("modality").and_then(Value::as_str).unwrap_or(""),
+                entry.get("tokenCount").and_then(Value::as_u64).unwrap_or(0),
+            )
+        })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum BudgetAlert {
+    Warn { percent: u8 },
+    Exhausted,
+}
+
+#[derive(Default)]
+pub(super) struct UsageMeter {
+    session: TokenTally,
+    turn: TokenTally,
+    turns: u32,
+    pricing: Option<TokenPricing>,
+    budget: Option<Budget>,
+    warned: bool,
+    exhausted: bool,
+}
+
+impl UsageMeter {
+    /// A meter for `model` under the user's budget file. A cost cap on a model
+    /// the catalog does not price is refused rather than silently ignored.
+    pub(super) fn start(model: &str) -> anyhow::Result<Self> {
+        load_budget()
+            .and_then(|budget| {
+                Self::with(crate::model_config::endpoint_pricing(model), budget, model)
+            })
+            .map_err(anyhow::Error::msg)
+    }
+
+    fn with(
+        pricing: Option<TokenPricing>,
+        budget: Option<Budget>,
+        model: &str,
+    ) -> Result<Self, String> {
+        if budget.is_some_and(|budget| budget.max_cost_usd.is_some()) && pricing.is_none() {
+            return Err(format!(
+                "max_session_cost_usd is set but the catalog has no pricing for {model}"
+            ));
+        }
+        Ok(Self {
+            pricing,
+            budget,
+            ..Self::default()
+        })
+    }
+
+    pub(super) fn start_turn(&mut self) {
+        self.turn = TokenTally::default();
+        self.turns = self.turns.saturating_add(1);
+    }
+
+    /// Adds one usage frame. Returns an alert the first time the session
+    /// crosses the warning threshold and when it reaches the cap.
+    pub(super) fn record(&mut self, usage: &Value) -> Option<BudgetAlert> {
+        let tally = TokenTally::from_usage(usage);
+        self.turn.add(&tally);
+        self.session.add(&tally);
+        let budget = self.budget?;
+        if self.exhausted {
+            return None;
+        }
+        let token_fraction = budget
+            .max_tokens
+            .map_or(0.0, |max| self.session.total() as f64 / max as f64);
+        let cost_fraction = budget
+            .max_cost_usd
+            .zip(self.cost_usd())
+            .map_or(0.0, |(max, cost)| cost / max);
+        let fraction = token_fraction.max(cost_fraction);
+        if fraction >= 1.0 {
+            self.exhausted = true;
+            return Some(BudgetAlert::Exhausted);
+        }
+        if !self.warned && fraction * 100.0 >= f64::from(budget.warn_at_percent) {
+            self.warned = true;
+            return Some(BudgetAlert::Warn {
+                percent: (fraction * 100.0) as u8,
+            });
+        }
+        None
+    }
+
+    pub(super) fn exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    fn cost_usd(&self) -> Option<f64> {
+        self.pricing
+            .as_ref()
+            .map(|pricing| self.session.cost_usd(pricing))
+    }
+
+    pub(super) fn turn_json(&self) -> Value {
+        json!({
+            "tokens": self.turn,
+            "cost_usd": self.pricing.as_ref().map(|pricing| self.turn.cost_usd(pricing)),
+        })
+    }
+
+    fn session_json(&self) -> Value {
+        json!({
+            "tokens": self.session,
+            "total_tokens": self.session.total(),
+            "turns": self.turns,
+            "cost_usd": self.cost_usd(),
+            "budget_exhausted": self.exhausted,
+        })
+    }
+
+    /// Reports the session total to telemetry and appends a one-line summary
+    /// to the transcript saved as the session's memory entry.
+    pub(super) fn finish(&self, history: &mut Vec<String>) {
+        telemetry::event(
+            "session_usage",
+            "runtime",
+            Privacy::Safe,
+            self.session_json(),
+        );
+        if self.session.total() == 0 {
+            return;
+        }
+        let mut line = format!(
+            "Usage: {} tokens over {} turns (input {}, output {})",
+            self.session.total(),
+            self.turns,
+            self.session.input(),
+            self.session.output()
+        );
+        if let Some(cost) = self.cost_usd() {
+            line.push_str(&format!(", estimated ${cost:.4}"));
+        }
+        history.push(line);
+    }
+}
+
+/// Logs a budget alert to the overlay and telemetry.
+pub(super) fn report(alert: BudgetAlert) {
+    match alert {
+        BudgetAlert::Warn { percent } => {
+            overlay::push_log(format!("session budget {percent}% used"));
+            telemetry::event(
+                "session_budget_warning",
+                "runtime",
+                Privacy::Safe,
+                json!({ "percent": percent }),
+            );
+        }
+        BudgetAlert::Exhausted => {
+            overlay::push_log("session budget reached - stopping");
+            telemetry::event(
+                "session_budget_exhausted",
+                "runtime",
+                Privacy::Safe,
+                json!({}),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "usage_meter_tests.rs"]
+mod tests;
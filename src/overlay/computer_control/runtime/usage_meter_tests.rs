@@ -0,0 +1,117 @@
+use super::*;
+
+const PRICING: TokenPricing = TokenPricing {
+    input_text: 0.5,
+    input_audio: 3.0,
+    input_image: 3.0,
+    output_text: 2.0,
+    output_audio: 12.0,
+};
+
+fn budget(max_tokens: Option<u64>, max_cost_usd: Option<f64>) -> Option<Budget> {
+    Some(Budget {
+        max_tokens,
+        max_cost_usd,
+        warn_at_percent: 80,
+    })
+}
+
+#[test]
+fn usage_metadata_splits_by_modality() {
+    let tally = TokenTally::from_usage(&json!({
+        "promptTokenCount": 1000,
+        "promptTokensDetails": [
+            {"modality": "AUDIO", "tokenCount": 600},
+            {"modality": "IMAGE", "tokenCount": 300},
+        ],
+        "toolUsePromptTokenCount": 50,
+        "responseTokenCount": 200,
+        "responseTokensDetails": [{"modality": "AUDIO", "tokenCount": 150}],
+        "thoughtsTokenCount": 20,
+    }));
+    assert_eq!(
+        tally,
+        TokenTally {
+            input_text: 150,
+            input_audio: 600,
+            input_image: 300,
+            output_text: 70,
+            output_audio: 150,
+        }
+    );
+    assert_eq!(tally.total(), 1270);
+    let expected = (150.0 * 0.5 + 600.0 * 3.0 + 300.0 * 3.0 + 70.0 * 2.0 + 150.0 * 12.0) / 1e6;
+    assert!((tally.cost_usd(&PRICING) - expected).abs() < 1e-12);
+}
+
+#[test]
+fn token_budget_warns_once_then_stops() {
+    let mut meter = UsageMeter::with(Some(PRICING), budget(Some(1000), None), "m").unwrap();
+    meter.start_turn();
+    assert_eq!(meter.record(&json!({"promptTokenCount": 500})), None);
+    assert_eq!(
+        meter.record(&json!({"promptTokenCount": 300})),
+        Some(BudgetAlert::Warn { percent: 80 })
+    );
+    assert_eq!(meter.record(&json!({"promptTokenCount": 100})), None);
+    assert!(!meter.exhausted());
+    assert_eq!(
+        meter.record(&json!({"responseTokenCount": 100})),
+        Some(BudgetAlert::Exhausted)
+    );
+    assert!(meter.exhausted());
+    assert_eq!(meter.record(&json!({"promptTokenCount": 100})), None);
+}
+
+#[test]
+fn cost_budget_uses_catalog_pricing() {
+    let mut meter = UsageMeter::with(Some(PRICING), budget(None, Some(0.01)), "m").unwrap();
+    assert_eq!(
+        meter.record(&json!({"responseTokenCount": 1000, "responseTokensDetails": [{"modality": "AUDIO", "tokenCount": 1000}]})),
+        Some(BudgetAlert::Exhausted)
+    );
+    let error = UsageMeter::with(None, budget(None, Some(1.0)), "unpriced")
+        .err()
+        .unwrap();
+    assert!(error.contains("unpriced"), "{error}");
+}
+
+#[test]
+fn finish_appends_a_summary_line_and_turns_reset() {
+    let mut meter = UsageMeter::with(Some(PRICING), None, "m").unwrap();
+    meter.start_turn();
+    meter.record(&json!({"promptTokenCount": 400, "responseTokenCount": 100}));
+    meter.start_turn();
+    assert_eq!(meter.turn_json()["tokens"]["input_text"], 0);
+    meter.record(&json!({"promptTokenCount": 200}));
+    let mut history = vec!["User: hi".to_string()];
+    meter.finish(&mut history);
+    assert_eq!(
+        history[1],
+        "Usage: 700 tokens over 2 turns (input 600, output 100), estimated $0.0005"
+    );
+}
+
+#[test]
+fn budget_files_reject_bad_values() {
+    let toml_path = Path::new("cc_budget.toml");
+    assert_eq!(
+        parse_budget(toml_path, "version = 1\nmax_session_tokens = 5000").unwrap(),
+        budget(Some(5000), None).unwrap()
+    );
+    for (text, reason) in [
+        ("version = 2", "version"),
+        ("version = 1\nmax_session_tokens = 0", "max_session_tokens"),
+        (
+            "version = 1\nmax_session_cost_usd = -1.0",
+            "max_session_cost_usd",
+        ),
+        ("version = 1\nwarn_at_percent = 0", "warn_at_percent"),
+        ("version = 1\nmax_tokens = 5", "unknown field"),
+    ] {
+        let error = parse_budget(toml_path, text).err().unwrap();
+        assert!(error.contains(reason), "{text}: {error}");
+    }
+    let json_path = Path::new("cc_budget.json");
+    assert!(parse_budget(json_path, r#"{"version": 1, "max_session_cost_usd": 2.5}"#).is_ok());
+}
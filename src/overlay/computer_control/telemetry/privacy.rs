@@ -68,6 +68,7 @@ fn safe_identifier_key(key: Option<&str>) -> bool {
                 | "outcome"
                 | "observation_status"
                 | "phase"
+                | "policy_rule"
                 | "privacy"
                 | "proof"
                 | "provider"
@@ -87,6 +88,7 @@ fn safe_identifier_key(key: Option<&str>) -> bool {
                 | "tools"
                 | "trigger"
                 | "turn_mode"
+                | "verdict"
                 | "worker"
         )
     )
@@ -95,7 +97,13 @@ fn safe_identifier_key(key: Option<&str>) -> bool {
 fn safe_sha256_key(key: Option<&str>) -> bool {
     matches!(
         key,
-        Some("bundle_sha256" | "input_sha256" | "proof_bundle_sha256" | "vision_bundle_sha256")
+        Some(
+            "bundle_sha256"
+                | "input_sha256"
+                | "policy_sha256"
+                | "proof_bundle_sha256"
+                | "vision_bundle_sha256"
+        )
     )
 }
 
@@ -0,0 +1,331 @@
+//! Turn-based, text-only Computer Control. The same engine-validated system
+//! instruction and tool catalog as the Live session are driven through an
+//! ordinary request/response function-calling API (Gemini `generateContent`
+//! or an OpenAI-compatible `/chat/completions`) instead of the Live websocket.
+//! There is no audio and no screen image: each tool response carries the
+//! grounded state text and typed postcondition, exactly as the Live harness
+//! returns them. `--cc-text-task "..."`.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::{Result, anyhow, bail};
+use serde_json::{Value, json};
+
+use super::runtime::completion_responses;
+use super::runtime::reader_policy::ToolCallBlock;
+use super::telemetry::{self, Privacy};
+use super::uia_task::{self, Brain};
+
+mod scripted_server;
+mod wire;
+
+use scripted_server::ScriptedModelServer;
+use wire::{ModelReply, ProviderKind, ToolCall, Turn};
+
+const DEFAULT_GEMINI_MODEL: &str = "gemini-3-flash-preview";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+const TEXT_ONLY_NOTE: &str = "\n\nTEXT SESSION: there is no audio and no screenshot in this session. The user reads your text replies. Judge the screen only from the state text in the task message and in each tool response's new_state and postcondition.";
+
+/// Where the model lives and how to reach it.
+struct ModelEndpoint {
+    kind: ProviderKind,
+    url: String,
+    model: String,
+    api_key: String,
+}
+
+impl ModelEndpoint {
+    /// Gemini uses the Computer Control key; an OpenAI-compatible endpoint
+    /// takes its key from `CC_TEXT_API_KEY`.
+    fn resolve(kind: ProviderKind, endpoint: Option<&str>, model: Option<&str>) -> Result<Self> {
+        match kind {
+            ProviderKind::Gemini => {
+                let model = model.unwrap_or(DEFAULT_GEMINI_MODEL).to_string();
+                Ok(Self {
+                    kind,
+                    url: endpoint.map(str::to_string).unwrap_or_else(|| {
+                        crate::api::gemini_generate::gemini_content_url(&model, false)
+                    }),
+                    model,
+                    api_key: super::session::load_key()?,
+                })
+            }
+            ProviderKind::OpenAiCompatible => Ok(Self {
+                kind,
+                url: endpoint
+                    .ok_or_else(|| anyhow!("an OpenAI-compatible provider needs an endpoint URL"))?
+                    .to_string(),
+                model: model
+                    .ok_or_else(|| anyhow!("an OpenAI-compatible provider needs a model id"))?
+                    .to_string(),
+                api_key: crate::api::provider_credentials::resolve("CC_TEXT_API_KEY", ""),
+            }),
+        }
+    }
+}
+
+/// One function-calling conversation with a fixed system prompt and catalog.
+struct ModelClient {
+    endpoint: ModelEndpoint,
+    system: String,
+    declarations: Vec<Value>,
+}
+
+impl ModelClient {
+    fn new(endpoint: ModelEndpoint, system: String, declarations: Vec<Value>) -> Self {
+        Self {
+            endpoint,
+            system,
+            declarations,
+        }
+    }
+
+    fn complete(&self, turns: &[Turn], ordinal: usize) -> Result<ModelReply> {
+        let body = wire::request_body(
+            self.endpoint.kind,
+            &self.endpoint.model,
+            &self.system,
+            &self.declarations,
+            turns,
+        );
+        let mut request = crate::api::client::UREQ_RESPONSE_AGENT.post(&self.endpoint.url);
+        if !self.endpoint.api_key.is_empty() {
+            request = match self.endpoint.kind {
+                ProviderKind::Gemini => request.header("x-goog-api-key", &self.endpoint.api_key),
+                ProviderKind::OpenAiCompatible => request.header(
+                    "Authorization",
+                    &format!("Bearer {}", self.endpoint.api_key),
+                ),
+            };
+        }
+        let mut response = crate::api::client::with_request_timeout(request, Some(REQUEST_TIMEOUT))
+            .send_json(&body)
+            .map_err(|error| anyhow!("text provider transport error: {error}"))?;
+        let status = response.status().as_u16();
+        if matches!(status, 401 | 403) {
+            bail!("INVALID_API_KEY");
+        }
+        let reply: Value = response
+            .body_mut()
+            .read_json()
+            .map_err(|error| anyhow!("text provider returned invalid JSON ({status}): {error}"))?;
+        if !(200..300).contains(&status) {
+            let message = reply
+                .pointer("/error/message")
+                .and_then(Value::as_str)
+                .unwrap_or("request failed");
+            bail!("text provider error {status}: {message}");
+        }
+        wire::parse_reply(self.endpoint.kind, &reply, ordinal)
+    }
+}
+
+/// What the conversation acts on. The real host is a [`Brain`]; tests use a
+/// recorded stand-in.
+trait ToolHost {
+    /// State text for the opening user message.
+    fn observe(&mut self) -> Result<String>;
+    /// Runs one non-terminal tool call and returns its tool response.
+    fn call(&mut self, call: &ToolCall) -> Result<Value>;
+    fn finish(&mut self);
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Ending {
+    /// The model called `done`.
+    Done(String),
+    /// The model answered in text without calling a tool.
+    Answered(String),
+    StepLimit,
+}
+
+/// Runs the request/response loop until the model finishes or `max_steps`
+/// tool calls have run.
+fn drive(
+    client: &ModelClient,
+    host: &mut dyn ToolHost,
+    task: &str,
+    max_steps: usize,
+) -> Result<Ending> {
+    let state = host.observe()?;
+    let mut turns = vec![Turn::User(format!("{state}\n\nYOUR TASK: {task}\nBegin."))];
+    let mut steps = 0usize;
+    for ordinal in 0.. {
+        let reply = client.complete(&turns, ordinal)?;
+        let text = reply.text.trim().to_string();
+        let calls = reply.calls.clone();
+        turns.push(Turn::Model(reply));
+        if !text.is_empty() {
+            eprintln!(
+                "[cc-text] model: {}",
+                text.chars().take(240).collect::<String>()
+            );
+        }
+        if calls.is_empty() {
+            host.finish();
+            return Ok(Ending::Answered(text));
+        }
+        let mut results = Vec::with_capacity(calls.len());
+        for call in calls {
+            if call.name == "done" {
+                // The live runtime's checks: a done sent beside calls whose
+                // results the model has not seen is refused as in flight, and
+                // an accepted one carries the bounded summary.
+                let summary = call.args["summary"].as_str().unwrap_or("");
+                let response = if results.is_empty() {
+                    completion_responses::accepted_done_response(summary)
+                } else {
+                    ToolCallBlock::InFlight { prior_turn: false }.response()
+                };
+                if response["ok"] == true {
+                    host.finish();
+                    let summary = response["summary"].as_str().unwrap_or("");
+                    return Ok(Ending::Done(summary.to_string()));
+                }
+                results.push((call, response));
+                continue;
+            }
+            if steps >= max_steps {
+                host.finish();
+                return Ok(Ending::StepLimit);
+            }
+            steps += 1;
+            let response = if call.args.is_object() {
+                host.call(&call)?
+            } else {
+                json!({
+                    "ok": false,
+                    "executed": false,
+                    "error": "tool arguments must be a JSON object",
+                })
+            };
+            results.push((call, response));
+        }
+        turns.push(Turn::ToolResults(results));
+    }
+    unreachable!("the conversation loop only exits by returning")
+}
+
+struct BrainHost {
+    brain: Brain,
+    task: String,
+    cancel: Arc<AtomicBool>,
+}
+
+impl ToolHost for BrainHost {
+    fn observe(&mut self) -> Result<String> {
+        let (_frame, state) = self.brain.initial()?;
+        Ok(state)
+    }
+
+    fn call(&mut self, call: &ToolCall) -> Result<Value> {
+        if self.cancel.load(Ordering::SeqCst) {
+            bail!("text session cancelled");
+        }
+        eprintln!("[cc-text] step {:02} {}", self.brain.step + 1, call.name);
+        let ctx = format!("task: {}; text session", self.task);
+        let (response, _) = self.brain.grounded_tool_response(
+            &call.name,
+            &call.args,
+            &ctx,
+            &self.task,
+            &self.cancel,
+        )?;
+        Ok(response)
+    }
+
+    fn finish(&mut self) {
+        self.brain.retire_session_completed();
+    }
+}
+
+/// Options of one `--cc-text-task` run.
+pub(super) struct TextTaskOptions<'a> {
+    pub(super) provider: &'a str,
+    pub(super) model: Option<&'a str>,
+    pub(super) endpoint: Option<&'a str>,
+    /// JSON array of provider replies served by a local [`ScriptedModelServer`]
+    /// instead of a real model.
+    pub(super) scripted: Option<&'a std::path::Path>,
+    /// Stops the run before its next tool call once set.
+    pub(super) cancel: Option<&'a Arc<AtomicBool>>,
+}
+
+/// Runs `task` against the desktop through a turn-based provider and returns
+/// the model's closing summary.
+pub(super) fn run(task: &str, options: &TextTaskOptions) -> Result<String> {
+    let kind = ProviderKind::parse(options.provider)?;
+    let scripted = match options.scripted {
+        Some(path) => {
+            let text = std::fs::read_to_string(path)
+                .map_err(|error| anyhow!("cannot read {}: {error}", path.display()))?;
+            let replies: Vec<Value> = serde_json::from_str(&text)
+                .map_err(|error| anyhow!("{}: {error}", path.display()))?;
+            Some(ScriptedModelServer::start(replies)?)
+        }
+        None => None,
+    };
+    let endpoint = match &scripted {
+        Some(server) => ModelEndpoint {
+            kind,
+            url: server.url(),
+            model: options.model.unwrap_or("scripted").to_string(),
+            api_key: String::new(),
+        },
+        None => ModelEndpoint::resolve(kind, options.endpoint, options.model)?,
+    };
+    let cancel = options.cancel.cloned().unwrap_or_default();
+    run_with(task, endpoint, cancel)
+}
+
+fn run_with(task: &str, endpoint: ModelEndpoint, cancel: Arc<AtomicBool>) -> Result<String> {
+    let max_steps: usize = std::env::var("CC_MAX_STEPS")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(16);
+    let (system, declarations) = uia_task::turn_based_catalog()?;
+    telemetry::event(
+        "text_session_start",
+        "text_session",
+        Privacy::Safe,
+        json!({
+            "provider": match endpoint.kind {
+                ProviderKind::Gemini => "gemini",
+                ProviderKind::OpenAiCompatible => "openai_compatible",
+            },
+            "model": endpoint.model,
+            "tools": declarations.len(),
+        }),
+    );
+    let client = ModelClient::new(endpoint, format!("{system}{TEXT_ONLY_NOTE}"), declarations);
+    let mut brain = Brain::new(std::env::var("CC_UIA_WINDOW").ok());
+    brain.record_user_request(0, task);
+    let mut host = BrainHost {
+        brain,
+        task: task.to_string(),
+        cancel,
+    };
+    let ending = drive(&client, &mut host, task, max_steps)?;
+    let (outcome, summary) = match &ending {
+        Ending::Done(summary) => ("done", summary.as_str()),
+        Ending::Answered(text) => ("answered", text.as_str()),
+        Ending::StepLimit => ("step_limit", ""),
+    };
+    telemetry::event(
+        "turn_summary",
+        "text_session",
+        Privacy::Safe,
+        json!({ "outcome": outcome, "steps": host.brain.step }),
+    );
+    eprintln!("[cc-text] {outcome}: {summary}");
+    if ending == Ending::StepLimit {
+        bail!("text session stopped after {max_steps} tool calls");
+    }
+    Ok(summary.to_string())
+}
+
+#[cfg(test)]
+#[path = "text_session_tests.rs"]
+mod tests;
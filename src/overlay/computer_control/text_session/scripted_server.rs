@@ -0,0 +1,92 @@
+//! Local stand-in for a chat API: answers each POST with the next scripted
+//! JSON body and keeps every request it received. Used by the tests and by
+//! `--cc-text-scripted` to exercise the text session without a real model.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+const MAX_REQUEST_BYTES: usize = 16 * 1024 * 1024;
+
+pub(super) struct ScriptedModelServer {
+    port: u16,
+    requests: Arc<Mutex<Vec<Value>>>,
+}
+
+impl ScriptedModelServer {
+    pub(super) fn start(replies: Vec<Value>) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").context("bind scripted model server")?;
+        let port = listener.local_addr()?.port();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let seen = requests.clone();
+        std::thread::spawn(move || {
+            let mut replies = replies.into_iter();
+            for stream in listener.incoming().flatten() {
+                let reply = replies.next();
+                let done = reply.is_none();
+                if let Err(error) = serve(stream, reply, &seen) {
+                    eprintln!("[cc-text] scripted server: {error:#}");
+                }
+                if done {
+                    break;
+                }
+            }
+        });
+        Ok(Self { port, requests })
+    }
+
+    /// Base URL; the request path is ignored, so any provider URL shape works.
+    pub(super) fn url(&self) -> String {
+        format!("http://127.0.0.1:{}/v1/chat/completions", self.port)
+    }
+
+    pub(super) fn requests(&self) -> Vec<Value> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+fn serve(stream: TcpStream, reply: Option<Value>, seen: &Mutex<Vec<Value>>) -> Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut content_length = 0usize;
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().context("content-length")?;
+        }
+    }
+    anyhow::ensure!(content_length <= MAX_REQUEST_BYTES, "request too large");
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    seen.lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push(serde_json::from_slice(&body).unwrap_or(Value::Null));
+
+    let (status, body) = match reply {
+        Some(reply) => ("200 OK", reply.to_string()),
+        None => (
+            "500 Internal Server Error",
+            r#"{"error":{"message":"scripted replies exhausted"}}"#.to_string(),
+        ),
+    };
+    let mut stream = stream;
+    write!(
+        stream,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()?;
+    Ok(())
+}
@@ -0,0 +1,252 @@
+//! Request and response shapes for the two function-calling chat APIs the
+//! text session speaks: Gemini `generateContent` and OpenAI-compatible
+//! `/chat/completions`. The conversation is kept provider-neutral except for
+//! each model message, which is echoed back verbatim so provider-specific
+//! fields (Gemini thought signatures, OpenAI tool-call ids) survive the turn.
+
+use anyhow::{Result, anyhow, bail};
+use serde_json::{Map, Value, json};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ProviderKind {
+    Gemini,
+    OpenAiCompatible,
+}
+
+impl ProviderKind {
+    pub(super) fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "gemini" => Ok(Self::Gemini),
+            "openai" | "openai-compatible" => Ok(Self::OpenAiCompatible),
+            other => bail!("unknown text provider {other:?} (expected gemini or openai)"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct ToolCall {
+    pub(super) id: String,
+    pub(super) name: String,
+    /// `Value::Null` when the provider sent arguments that are not JSON.
+    pub(super) args: Value,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct ModelReply {
+    /// The provider's own message object, replayed unchanged in later requests.
+    pub(super) raw: Value,
+    pub(super) text: String,
+    pub(super) calls: Vec<ToolCall>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) enum Turn {
+    User(String),
+    Model(ModelReply),
+    ToolResults(Vec<(ToolCall, Value)>),
+}
+
+pub(super) fn request_body(
+    kind: ProviderKind,
+    model: &str,
+    system: &str,
+    declarations: &[Value],
+    turns: &[Turn],
+) -> Value {
+    match kind {
+        ProviderKind::Gemini => json!({
+            "systemInstruction": { "parts": [{ "text": system }] },
+            "contents": turns.iter().map(gemini_content).collect::<Vec<_>>(),
+            "tools": [{ "functionDeclarations": declarations }],
+            "toolConfig": { "functionCallingConfig": { "mode": "AUTO" } },
+        }),
+        ProviderKind::OpenAiCompatible => {
+            let mut messages = vec![json!({ "role": "system", "content": system })];
+            for turn in turns {
+                match turn {
+                    Turn::User(text) => messages.push(json!({ "role": "user", "content": text })),
+                    Turn::Model(reply) => messages.push(reply.raw.clone()),
+                    Turn::ToolResults(results) => {
+                        messages.extend(results.iter().map(|(call, response)| {
+                            json!({
+                                "role": "tool",
+                                "tool_call_id": call.id,
+                                "content": response.to_string(),
+                            })
+                        }));
+                    }
+                }
+            }
+            json!({
+                "model": model,
+                "messages": messages,
+                "tools": declarations.iter().map(openai_tool).collect::<Vec<_>>(),
+                "tool_choice": "auto",
+                "stream": false,
+            })
+        }
+    }
+}
+
+fn gemini_content(turn: &Turn) -> Value {
+    match turn {
+        Turn::User(text) => json!({ "role": "user", "parts": [{ "text": text }] }),
+        Turn::Model(reply) => reply.raw.clone(),
+        Turn::ToolResults(results) => json!({
+            "role": "user",
+            "parts": results.iter().map(|(call, response)| {
+                let mut part = json!({ "name": call.name, "response": response });
+                if !call.id.starts_with(SYNTHETIC_ID_PREFIX) {
+                    part["id"] = json!(call.id);
+                }
+                json!({ "functionResponse": part })
+            }).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+/// Gemini declarations use upper-case OpenAPI type names; OpenAI-compatible
+/// servers expect JSON Schema's lower-case ones.
+fn openai_tool(declaration: &Value) -> Value {
+    let mut parameters = declaration
+        .get("parameters")
+        .cloned()
+        .unwrap_or_else(|| json!({ "type": "object", "properties": {} }));
+    lower_schema_types(&mut parameters);
+    json!({
+        "type": "function",
+        "function": {
+            "name": declaration["name"],
+            "description": declaration.get("description").cloned().unwrap_or(json!("")),
+            "parameters": parameters,
+        }
+    })
+}
+
+fn lower_schema_types(schema: &mut Value) {
+    match schema {
+        Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if key == "type"
+                    && let Value::String(name) = value
+                {
+                    *name = name.to_ascii_lowercase();
+                } else {
+                    lower_schema_types(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(lower_schema_types),
+        _ => {}
+    }
+}
+
+const SYNTHETIC_ID_PREFIX: &str = "text-call-";
+
+pub(super) fn parse_reply(kind: ProviderKind, body: &Value, ordinal: usize) -> Result<ModelReply> {
+    match kind {
+        ProviderKind::Gemini => parse_gemini(body, ordinal),
+        ProviderKind::OpenAiCompatible => parse_openai(body),
+    }
+}
+
+fn parse_gemini(body: &Value, ordinal: usize) -> Result<ModelReply> {
+    let content = body
+        .pointer("/candidates/0/content")
+        .filter(|content| content.is_object())
+        .ok_or_else(|| {
+            let reason = body
+                .pointer("/promptFeedback/blockReason")
+                .or_else(|| body.pointer("/candidates/0/finishReason"))
+                .and_then(Value::as_str)
+                .unwrap_or("no candidates");
+            anyhow!("model returned no content ({reason})")
+        })?;
+    let mut raw = content.clone();
+    if raw.get("role").is_none() {
+        raw["role"] = json!("model");
+    }
+    let mut text = String::new();
+    let mut calls = Vec::new();
+    for part in content["parts"].as_array().into_iter().flatten() {
+        if let Some(call) = part.get("functionCall") {
+            let index = ordinal * 100 + calls.len();
+            calls.push(ToolCall {
+                id: call["id"]
+                    .as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("{SYNTHETIC_ID_PREFIX}{index}")),
+                name: tool_name(&call["name"])?,
+                args: call.get("args").cloned().unwrap_or_else(|| json!({})),
+            });
+        } else if part["thought"] != true
+            && let Some(chunk) = part["text"].as_str()
+        {
+            text.push_str(chunk);
+        }
+    }
+    Ok(ModelReply { raw, text, calls })
+}
+
+fn parse_openai(body: &Value) -> Result<ModelReply> {
+    let message = body
+        .pointer("/choices/0/message")
+        .and_then(Value::as_object)
+        .ok_or_else(|| {
+            let error = body
+                .pointer("/error/message")
+                .and_then(Value::as_str)
+                .unwrap_or("no choices");
+            anyhow!("model returned no message ({error})")
+        })?;
+    let mut calls = Vec::new();
+    for call in message
+        .get("tool_calls")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        let function = &call["function"];
+        let args = match &function["arguments"] {
+            Value::String(raw) if raw.trim().is_empty() => json!({}),
+            Value::String(raw) => serde_json::from_str(raw).unwrap_or(Value::Null),
+            Value::Object(_) => function["arguments"].clone(),
+            _ => json!({}),
+        };
+        calls.push(ToolCall {
+            id: call["id"]
+                .as_str()
+                .filter(|id| !id.is_empty())
+                .ok_or_else(|| anyhow!("tool call without an id"))?
+                .to_string(),
+            name: tool_name(&function["name"])?,
+            args,
+        });
+    }
+    let mut raw = Map::new();
+    raw.insert("role".to_string(), json!("assistant"));
+    raw.insert(
+        "content".to_string(),
+        message.get("content").cloned().unwrap_or(Value::Null),
+    );
+    if let Some(tool_calls) = message.get("tool_calls") {
+        raw.insert("tool_calls".to_string(), tool_calls.clone());
+    }
+    Ok(ModelReply {
+        raw: Value::Object(raw),
+        text: message
+            .get("content")
+            .and_then(Value::as_str)
+            .unwrap_or("")
+            .to_string(),
+        calls,
+    })
+}
+
+fn tool_name(value: &Value) -> Result<String> {
+    value
+        .as_str()
+        .filter(|name| !name.is_empty() && name.len() <= 128 && !name.contains('\0'))
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("model returned an invalid tool name"))
+}
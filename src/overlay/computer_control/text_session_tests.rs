@@ -0,0 +1,205 @@
+use super::*;
+
+#[derive(Default)]
+struct RecordingHost {
+    calls: Vec<(String, Value)>,
+    finished: bool,
+}
+
+impl ToolHost for RecordingHost {
+    fn observe(&mut self) -> Result<String> {
+        Ok("WINDOW: Notepad\n[1] Edit \"Text editor\"".to_string())
+    }
+
+    fn call(&mut self, call: &ToolCall) -> Result<Value> {
+        self.calls.push((call.name.clone(), call.args.clone()));
+        Ok(json!({ "ok": true, "execution_ok": true, "new_state": "typed" }))
+    }
+
+    fn finish(&mut self) {
+        self.finished = true;
+    }
+}
+
+fn declarations() -> Vec<Value> {
+    vec![json!({
+        "name": "type_text",
+        "description": "Types text.",
+        "parameters": {
+            "type": "OBJECT",
+            "properties": { "text": { "type": "STRING" } },
+            "required": ["text"],
+        },
+    })]
+}
+
+fn client(server: &ScriptedModelServer, kind: ProviderKind) -> ModelClient {
+    let endpoint = ModelEndpoint {
+        kind,
+        url: server.url(),
+        model: "stand-in".to_string(),
+        api_key: String::new(),
+    };
+    ModelClient::new(endpoint, "system prompt".to_string(), declarations())
+}
+
+fn openai_call(id: &str, name: &str, arguments: &str) -> Value {
+    json!({ "choices": [{ "message": {
+        "role": "assistant",
+        "content": null,
+        "tool_calls": [{
+            "id": id,
+            "type": "function",
+            "function": { "name": name, "arguments": arguments },
+        }],
+    }}]})
+}
+
+#[test]
+fn openai_compatible_session_returns_tool_results_and_finishes_on_done() {
+    let server = ScriptedModelServer::start(vec![
+        openai_call("c1", "type_text", r#"{"text":"hello"}"#),
+        openai_call("c2", "type_text", "not json"),
+        openai_call("c3", "done", r#"{"summary":"Typed hello."}"#),
+    ])
+    .unwrap();
+    let mut host = RecordingHost::default();
+
+    let ending = drive(
+        &client(&server, ProviderKind::OpenAiCompatible),
+        &mut host,
+        "type hello",
+        8,
+    )
+    .unwrap();
+
+    assert_eq!(ending, Ending::Done("Typed hello.".to_string()));
+    assert!(host.finished);
+    // Unparseable arguments are refused without reaching the host.
+    assert_eq!(
+        host.calls,
+        [("type_text".to_string(), json!({ "text": "hello" }))]
+    );
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0]["model"], "stand-in");
+    assert_eq!(
+        requests[0]["tools"][0]["function"]["parameters"]["properties"]["text"]["type"],
+        "string"
+    );
+    assert!(
+        requests[0]["messages"][1]["content"]
+            .as_str()
+            .unwrap()
+            .ends_with("YOUR TASK: type hello\nBegin.")
+    );
+    let second = requests[1]["messages"].as_array().unwrap();
+    assert_eq!(second[2]["tool_calls"][0]["id"], "c1");
+    assert_eq!(second[3]["role"], "tool");
+    assert_eq!(second[3]["tool_call_id"], "c1");
+    let result: Value = serde_json::from_str(second[3]["content"].as_str().unwrap()).unwrap();
+    assert_eq!(result["new_state"], "typed");
+    let third = requests[2]["messages"].as_array().unwrap();
+    assert!(
+        third[5]["content"]
+            .as_str()
+            .unwrap()
+            .contains("JSON object")
+    );
+}
+
+#[test]
+fn gemini_session_replays_model_turns_and_stops_at_the_step_limit() {
+    let call = |text: &str| {
+        json!({ "candidates": [{ "content": { "role": "model", "parts": [
+            { "text": "thinking", "thought": true },
+            { "functionCall": { "name": "type_text", "args": { "text": text } } },
+        ]}}]})
+    };
+    let server = ScriptedModelServer::start(vec![call("a"), call("b")]).unwrap();
+    let mut host = RecordingHost::default();
+
+    let ending = drive(&client(&server, ProviderKind::Gemini), &mut host, "type", 1).unwrap();
+
+    assert_eq!(ending, Ending::StepLimit);
+    assert_eq!(host.calls.len(), 1);
+    let requests = server.requests();
+    assert_eq!(
+        requests[0]["systemInstruction"]["parts"][0]["text"],
+        "system prompt"
+    );
+    assert_eq!(
+        requests[0]["tools"][0]["functionDeclarations"][0]["parameters"]["type"],
+        "OBJECT"
+    );
+    let contents = requests[1]["contents"].as_array().unwrap();
+    assert_eq!(contents[1]["parts"][1]["functionCall"]["args"]["text"], "a");
+    let response = &contents[2]["parts"][0]["functionResponse"];
+    assert_eq!(response["name"], "type_text");
+    assert!(response.get("id").is_none());
+    assert_eq!(response["response"]["ok"], true);
+}
+
+#[test]
+fn a_plain_text_reply_ends_the_session_as_an_answer() {
+    let server = ScriptedModelServer::start(vec![json!({ "choices": [{ "message": {
+        "role": "assistant",
+        "content": "The editor is already empty.",
+    }}]})])
+    .unwrap();
+    let mut host = RecordingHost::default();
+
+    let ending = drive(
+        &client(&server, ProviderKind::OpenAiCompatible),
+        &mut host,
+        "clear it",
+        8,
+    )
+    .unwrap();
+
+    assert_eq!(
+        ending,
+        Ending::Answered("The editor is already empty.".to_string())
+    );
+    assert!(host.calls.is_empty());
+    assert!(host.finished);
+}
+
+#[test]
+fn done_beside_an_unanswered_call_is_refused_like_the_live_runtime() {
+    let server = ScriptedModelServer::start(vec![
+        json!({ "choices": [{ "message": {
+            "role": "assistant",
+            "content": null,
+            "tool_calls": [
+                { "id": "c1", "type": "function",
+                  "function": { "name": "type_text", "arguments": r#"{"text":"hi"}"# } },
+                { "id": "c2", "type": "function",
+                  "function": { "name": "done", "arguments": r#"{"summary":"early"}"# } },
+            ],
+        }}]}),
+        openai_call(
+            "c3",
+            "done",
+            &json!({ "summary": "x".repeat(400) }).to_string(),
+        ),
+    ])
+    .unwrap();
+    let mut host = RecordingHost::default();
+
+    let ending = drive(
+        &client(&server, ProviderKind::OpenAiCompatible),
+        &mut host,
+        "type hi",
+        8,
+    )
+    .unwrap();
+
+    assert_eq!(ending, Ending::Done("x".repeat(320)));
+    assert_eq!(host.calls.len(), 1);
+    let second = server.requests()[1]["messages"].as_array().unwrap().clone();
+    let refused: Value = serde_json::from_str(second[4]["content"].as_str().unwrap()).unwrap();
+    assert_eq!(second[4]["tool_call_id"], "c2");
+    assert_eq!(refused["error"]["code"], "tool_call_in_flight");
+}
@@ -0,0 +1,369 @@
+//! Scheduled and event-triggered Computer Control runs.
+//!
+//! `cc_triggers.toml` (or `cc_triggers.json`) in the app config directory
+//! pairs an event — a cron schedule, a new file in a folder, or a window
+//! whose title appears — with a typed text task or a saved routine. A
+//! background thread polls the events and hands each firing to one runner,
+//! which waits while a Live session is open and runs the action under the
+//! unattended permission policy. The Computer Control stop request cancels a
+//! run in progress. Outcomes go to history and a notification.
+//! `--cc-trigger-list` / `--cc-trigger-fire <name>`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use chrono::NaiveDateTime;
+use serde_json::{Value, json};
+
+use super::telemetry::{self, Privacy};
+
+mod cron;
+mod model;
+
+use model::{
+    Action, Event, EventContext, FolderWatch, ScheduleWatch, Trigger, TriggerFile, WindowWatch,
+};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Firings waiting for the runner; more are dropped and logged.
+const QUEUE_LIMIT: usize = 4;
+const MAX_FOLDER_FILES: usize = 2000;
+const NOTIFICATION_CHARS: usize = 200;
+
+struct CachedTriggers {
+    path: Option<PathBuf>,
+    modified: Option<SystemTime>,
+    loaded: Arc<Result<Vec<Trigger>, String>>,
+}
+
+static CACHE: Mutex<Option<CachedTriggers>> = Mutex::new(None);
+/// Cancel flag of the action running now.
+static RUNNING: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+
+fn config_paths() -> [PathBuf; 2] {
+    let dir = crate::paths::app_config_dir();
+    [dir.join("cc_triggers.toml"), dir.join("cc_triggers.json")]
+}
+
+/// Enabled triggers, re-read whenever the file appears, disappears or changes.
+fn current() -> Arc<Result<Vec<Trigger>, String>> {
+    let path = config_paths().into_iter().find(|path| path.is_file());
+    let modified = path
+        .as_ref()
+        .and_then(|path| std::fs::metadata(path).ok())
+        .and_then(|metadata| metadata.modified().ok());
+    let mut cache = CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some(cached) = cache.as_ref()
+        && cached.path == path
+        && cached.modified == modified
+    {
+        return cached.loaded.clone();
+    }
+    let loaded = Arc::new(path.as_deref().map_or_else(|| Ok(Vec::new()), load));
+    if let Err(error) = loaded.as_ref() {
+        telemetry::typed_error("ERR_TRIGGERS_INVALID", "triggers", error, json!({}));
+    }
+    *cache = Some(CachedTriggers {
+        path,
+        modified,
+        loaded: loaded.clone(),
+    });
+    loaded
+}
+
+fn load(path: &Path) -> Result<Vec<Trigger>, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|error| format!("cannot read {}: {error}", path.display()))?;
+    let file = if path.extension().is_some_and(|ext| ext == "toml") {
+        toml::from_str::<TriggerFile>(&text).map_err(|error| error.to_string())
+    } else {
+        serde_json::from_str::<TriggerFile>(&text).map_err(|error| error.to_string())
+    };
+    file.and_then(TriggerFile::triggers)
+        .map_err(|error| format!("{}: {error}", path.display()))
+}
+
+/// Per-trigger event state, kept across reloads while the event is unchanged.
+#[derive(Default)]
+struct Watch {
+    schedule: ScheduleWatch,
+    folder: FolderWatch,
+    window: WindowWatch,
+}
+
+impl Watch {
+    fn poll(
+        &mut self,
+        event: &Event,
+        now: NaiveDateTime,
+        windows: &[(String, String)],
+    ) -> Vec<EventContext> {
+        match event {
+            Event::Schedule(schedule) => {
+                if self.schedule.due(schedule, now) {
+                    vec![EventContext::new()]
+                } else {
+                    Vec::new()
+                }
+            }
+            Event::Folder { dir, pattern } => {
+                // An unreadable folder keeps its state, so a share that drops
+                // for a moment does not replay every file when it returns.
+                let Some(files) = folder_files(dir, pattern.as_deref()) else {
+                    return Vec::new();
+                };
+                self.folder
+                    .observe(files)
+                    .into_iter()
+                    .map(|path| {
+                        let file_name = path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        EventContext::from([
+                            ("path", path.display().to_string()),
+                            ("file_name", file_name),
+                        ])
+                    })
+                    .collect()
+            }
+            Event::Window { title } => self
+                .window
+                .observe(title, windows.to_vec())
+                .into_iter()
+                .map(|title| EventContext::from([("window_title", title)]))
+                .collect(),
+        }
+    }
+}
+
+fn folder_files(dir: &Path, pattern: Option<&str>) -> Option<Vec<(PathBuf, u64)>> {
+    let entries = std::fs::read_dir(dir).ok()?;
+    Some(
+        entries
+            .filter_map(Result::ok)
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                metadata.is_file().then(|| (entry.path(), metadata.len()))
+            })
+            .filter(|(path, _)| model::name_matches(pattern, path))
+            .take(MAX_FOLDER_FILES)
+            .collect(),
+    )
+}
+
+fn open_windows() -> Vec<(String, String)> {
+    super::uia::list_windows()
+        .into_iter()
+        .map(|window| (window.target().to_string(), window.title().to_string()))
+        .collect()
+}
+
+struct Firing {
+    trigger: Trigger,
+    action: Action,
+}
+
+/// Polls the configured events for the lifetime of the app. Started once,
+/// next to the hotkey listener.
+pub(crate) fn run_scheduler() {
+    let (sender, receiver) = std::sync::mpsc::sync_channel(QUEUE_LIMIT);
+    std::thread::spawn(move || run_queue(receiver));
+    let mut watches: HashMap<String, (Event, Watch)> = HashMap::new();
+    loop {
+        match current().as_ref() {
+            Ok(triggers) => poll(triggers, &mut watches, &sender),
+            Err(_) => watches.clear(),
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn poll(
+    triggers: &[Trigger],
+    watches: &mut HashMap<String, (Event, Watch)>,
+    sender: &SyncSender<Firing>,
+) {
+    watches.retain(|name, (event, _)| {
+        triggers
+            .iter()
+            .any(|trigger| trigger.name == *name && trigger.event == *event)
+    });
+    let now = chrono::Local::now().naive_local();
+    let windows = if triggers
+        .iter()
+        .any(|trigger| matches!(trigger.event, Event::Window { .. }))
+    {
+        open_windows()
+    } else {
+        Vec::new()
+    };
+    for trigger in triggers {
+        let (_, watch) = watches
+            .entry(trigger.name.clone())
+            .or_insert_with(|| (trigger.event.clone(), Watch::default()));
+        for context in watch.poll(&trigger.event, now, &windows) {
+            let firing = Firing {
+                trigger: trigger.clone(),
+                action: trigger.action.bind(&context),
+            };
+            if let Err(TrySendError::Full(firing)) = sender.try_send(firing) {
+                telemetry::human(
+                    "triggers",
+                    format!("trigger {} dropped: runner busy", firing.trigger.name),
+                );
+            }
+        }
+    }
+}
+
+/// Runs firings one at a time, never alongside a Live session.
+fn run_queue(receiver: Receiver<Firing>) {
+    for firing in receiver {
+        while super::overlay::is_active() {
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        let result = execute(&firing.trigger, &firing.action);
+        publish(&firing.trigger.name, &firing.action, &result);
+    }
+}
+
+fn running() -> std::sync::MutexGuard<'static, Option<Arc<AtomicBool>>> {
+    RUNNING
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Cancels the trigger action in progress. Returns false when none runs.
+pub(crate) fn cancel_running() -> bool {
+    let running = running();
+    if let Some(cancel) = running.as_ref() {
+        cancel.store(true, Ordering::SeqCst);
+    }
+    running.is_some()
+}
+
+/// Runs one action under the unattended policy and returns its summary.
+fn execute(trigger: &Trigger, action: &Action) -> Result<String, String> {
+    let kind = match action {
+        Action::Task { .. } => "task",
+        Action::Routine { .. } => "routine",
+    };
+    telemetry::event(
+        "trigger_start",
+        "triggers",
+        Privacy::Safe,
+        json!({ "action": kind, "allow_tools": trigger.allow_tools.is_some() }),
+    );
+    telemetry::human("triggers", format!("trigger {} started", trigger.name));
+    let _unattended = super::permission_policy::unattended(trigger.allow_tools.clone());
+    let cancel = Arc::new(AtomicBool::new(false));
+    *running() = Some(cancel.clone());
+    let result = match action {
+        Action::Task {
+            text,
+            provider,
+            model,
+            endpoint,
+        } => {
+            let options = super::text_session::TextTaskOptions {
+                provider,
+                model: model.as_deref(),
+                endpoint: endpoint.as_deref(),
+                scripted: None,
+                cancel: Some(&cancel),
+            };
+            super::engine::SessionGuard::start(&cancel)
+                .and_then(|_engine| super::text_session::run(text, &options))
+                .map_err(|error| format!("{error:?}"))
+        }
+        Action::Routine { name, params } => {
+            super::run_cli_with_engine(|| super::routines::run(name, params, &cancel))
+                .map(|report| format!("{} steps completed", report.len()))
+        }
+    };
+    *running() = None;
+    telemetry::event(
+        "trigger_end",
+        "triggers",
+        Privacy::Safe,
+        json!({ "action": kind, "ok": result.is_ok() }),
+    );
+    result
+}
+
+fn publish(name: &str, action: &Action, result: &Result<String, String>) {
+    use crate::overlay::auto_copy_badge::{self, NotificationType};
+
+    let locale = auto_copy_badge::locale_text();
+    let (template, kind, detail) = match result {
+        Ok(summary) => (
+            locale.cc_trigger_finished_fmt,
+            NotificationType::Success,
+            summary,
+        ),
+        Err(error) => (locale.cc_trigger_failed_fmt, NotificationType::Error, error),
+    };
+    let title = auto_copy_badge::format_locale(template, &[("name", name)]);
+    let detail = if detail.trim().is_empty() {
+        title.clone()
+    } else {
+        detail.trim().to_string()
+    };
+    if let Ok(app) = crate::APP.lock() {
+        app.history
+            .save_text(detail.clone(), format!("{name}: {}", action.describe()));
+    }
+    let snippet: String = detail.chars().take(NOTIFICATION_CHARS).collect();
+    auto_copy_badge::show_detailed_notification(&title, &snippet, kind);
+}
+
+/// Enabled triggers with their events and, for schedules, the next run.
+pub(super) fn list() -> Result<Vec<Value>, String> {
+    let loaded = current();
+    let triggers = loaded.as_ref().as_ref().map_err(Clone::clone)?;
+    let now = chrono::Local::now().naive_local();
+    Ok(triggers
+        .iter()
+        .map(|trigger| {
+            let event = match &trigger.event {
+                Event::Schedule(schedule) => json!({
+                    "kind": "schedule",
+                    "next_run": schedule
+                        .next_after(&now)
+                        .map(|time| time.format("%Y-%m-%d %H:%M").to_string()),
+                }),
+                Event::Folder { dir, pattern } => json!({
+                    "kind": "folder",
+                    "folder": dir.display().to_string(),
+                    "pattern": pattern,
+                }),
+                Event::Window { title } => json!({ "kind": "window", "window_title": title }),
+            };
+            json!({
+                "name": trigger.name,
+                "event": event,
+                "action": trigger.action.describe(),
+                "allow_tools": trigger.allow_tools,
+            })
+        })
+        .collect())
+}
+
+/// Runs the named trigger's action immediately. Event placeholders stay
+/// unfilled because no event happened.
+pub(super) fn fire_now(name: &str) -> Result<String, String> {
+    let loaded = current();
+    let triggers = loaded.as_ref().as_ref().map_err(Clone::clone)?;
+    let trigger = triggers
+        .iter()
+        .find(|trigger| trigger.name.eq_ignore_ascii_case(name.trim()))
+        .ok_or_else(|| format!("no enabled trigger named {name:?}"))?;
+    execute(trigger, &trigger.action)
+}
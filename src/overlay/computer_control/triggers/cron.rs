@@ -0,0 +1,264 @@
+//! Five-field cron schedules (`minute hour day-of-month month day-of-week`)
+//! matched against local time at minute granularity. Fields take `*`, single
+//! values, `a-b` ranges, `,` lists and `/n` steps; months and weekdays also
+//! take three-letter English names. When both day fields are restricted a
+//! day matches either one, as in classic cron.
+
+use chrono::{Datelike, Duration, NaiveDateTime, Timelike};
+
+const MONTH_NAMES: [&str; 12] = [
+    "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+/// How far [`Schedule::next_after`] looks; covers leap-day schedules.
+const SEARCH_DAYS: i64 = 366 * 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+struct Field {
+    min: u32,
+    max: u32,
+    names: &'static [&'static str],
+    /// First value of `names`.
+    name_base: u32,
+}
+
+impl Schedule {
+    pub(super) fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        let expanded = match spec.to_ascii_lowercase().as_str() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            _ => spec,
+        };
+        let fields: Vec<&str> = expanded.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields.as_slice() else {
+            return Err(format!(
+                "schedule {spec:?} must have five fields: minute hour day month weekday"
+            ));
+        };
+        let plain = |min, max| Field {
+            min,
+            max,
+            names: &[],
+            name_base: 0,
+        };
+        let (minutes, _) = parse_field(minute, &plain(0, 59), "minute")?;
+        let (hours, _) = parse_field(hour, &plain(0, 23), "hour")?;
+        let (days, days_restricted) = parse_field(day, &plain(1, 31), "day")?;
+        let month_field = Field {
+            min: 1,
+            max: 12,
+            names: &MONTH_NAMES,
+            name_base: 1,
+        };
+        let (months, _) = parse_field(month, &month_field, "month")?;
+        let weekday_field = Field {
+            min: 0,
+            max: 7,
+            names: &WEEKDAY_NAMES,
+            name_base: 0,
+        };
+        let (mut weekdays, weekdays_restricted) = parse_field(weekday, &weekday_field, "weekday")?;
+        // 7 is Sunday as well as 0.
+        if weekdays & (1 << 7) != 0 {
+            weekdays = (weekdays & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            minutes,
+            hours,
+            days,
+            months,
+            weekdays,
+            days_restricted,
+            weekdays_restricted,
+        })
+    }
+
+    pub(super) fn matches(&self, time: &NaiveDateTime) -> bool {
+        bit(self.minutes, time.minute()) && bit(self.hours, time.hour()) && self.matches_day(time)
+    }
+
+    fn matches_day(&self, time: &NaiveDateTime) -> bool {
+        if !bit(self.months, time.month()) {
+            return false;
+        }
+        let day = bit(self.days, time.day());
+        let weekday = bit(self.weekdays, time.weekday().num_days_from_sunday());
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    /// First matching minute strictly after `time`, if one exists within
+    /// eight years.
+    pub(super) fn next_after(&self, time: &NaiveDateTime) -> Option<NaiveDateTime> {
+        let start = time.date().and_hms_opt(time.hour(), time.minute(), 0)? + Duration::minutes(1);
+        let mut day = start.date();
+        for _ in 0..SEARCH_DAYS {
+            let probe = day.and_hms_opt(0, 0, 0)?;
+            if self.matches_day(&probe) {
+                let first = if day == start.date() {
+                    start.hour() * 60 + start.minute()
+                } else {
+                    0
+                };
+                for minute_of_day in first..24 * 60 {
+                    let candidate = day.and_hms_opt(minute_of_day / 60, minute_of_day % 60, 0)?;
+                    if self.matches(&candidate) {
+                        return Some(candidate);
+                    }
+                }
+            }
+            day = day.succ_opt()?;
+        }
+        None
+    }
+}
+
+fn bit(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+/// Bit set of the values a field allows and whether it restricts anything.
+fn parse_field(raw: &str, field: &Field, label: &str) -> Result<(u64, bool), String> {
+    let invalid = || format!("invalid {label} field {raw:?}");
+    let mut mask = 0u64;
+    for part in raw.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| invalid())?;
+                if step == 0 {
+                    return Err(invalid());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (field.min, field.max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (
+                value(start, field).ok_or_else(invalid)?,
+                value(end, field).ok_or_else(invalid)?,
+            )
+        } else {
+            let start = value(range, field).ok_or_else(invalid)?;
+            // `5/15` means from 5 to the end in steps of 15.
+            (start, if step > 1 { field.max } else { start })
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok((mask, raw != "*"))
+}
+
+fn value(raw: &str, field: &Field) -> Option<u32> {
+    let value = match raw.parse::<u32>() {
+        Ok(value) => value,
+        Err(_) => {
+            let lower = raw.to_ascii_lowercase();
+            field.names.iter().position(|name| *name == lower)? as u32 + field.name_base
+        }
+    };
+    (field.min..=field.max).contains(&value).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn fields_accept_lists_ranges_steps_and_names() {
+        let nightly = Schedule::parse("30 2 * * mon-fri").unwrap();
+        // 2026-10-19 is a Monday.
+        assert!(nightly.matches(&at(2026, 10, 19, 2, 30)));
+        assert!(!nightly.matches(&at(2026, 10, 18, 2, 30)));
+        assert!(!nightly.matches(&at(2026, 10, 19, 2, 31)));
+
+        let quarter = Schedule::parse("*/15 9-17 * jan,jul *").unwrap();
+        assert!(quarter.matches(&at(2026, 7, 3, 9, 45)));
+        assert!(!quarter.matches(&at(2026, 7, 3, 18, 0)));
+        assert!(!quarter.matches(&at(2026, 8, 3, 9, 45)));
+
+        assert_eq!(Schedule::parse("@daily"), Schedule::parse("0 0 * * *"));
+        assert_eq!(Schedule::parse("0 0 * * 7"), Schedule::parse("0 0 * * sun"));
+    }
+
+    #[test]
+    fn restricted_day_fields_match_either_one() {
+        let schedule = Schedule::parse("0 8 1 * sun").unwrap();
+        // The first of the month (a Thursday) and any Sunday.
+        assert!(schedule.matches(&at(2026, 10, 1, 8, 0)));
+        assert!(schedule.matches(&at(2026, 10, 18, 8, 0)));
+        assert!(!schedule.matches(&at(2026, 10, 19, 8, 0)));
+    }
+
+    #[test]
+    fn next_after_finds_the_following_matching_minute() {
+        let schedule = Schedule::parse("0 2 * * *").unwrap();
+        assert_eq!(
+            schedule.next_after(&at(2026, 10, 18, 1, 59)),
+            Some(at(2026, 10, 18, 2, 0))
+        );
+        assert_eq!(
+            schedule.next_after(&at(2026, 10, 18, 2, 0)),
+            Some(at(2026, 10, 19, 2, 0))
+        );
+        let leap = Schedule::parse("0 0 29 feb *").unwrap();
+        assert_eq!(
+            leap.next_after(&at(2026, 10, 18, 0, 0)),
+            Some(at(2028, 2, 29, 0, 0))
+        );
+        assert_eq!(
+            Schedule::parse("0 0 31 feb *")
+                .unwrap()
+                .next_after(&at(2026, 1, 1, 0, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn malformed_schedules_are_rejected() {
+        for spec in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "* * * foo *",
+        ] {
+            assert!(Schedule::parse(spec).is_err(), "{spec:?}");
+        }
+    }
+}
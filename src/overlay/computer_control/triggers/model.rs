@@ -0,0 +1,406 @@
+//! `cc_triggers.toml` / `cc_triggers.json`: what starts an unattended run
+//! and what it runs, plus the pure watchers that decide when an event fired.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDateTime, Timelike};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::cron::Schedule;
+
+const SUPPORTED_VERSION: u32 = 1;
+const MAX_TRIGGERS: usize = 32;
+const MAX_NAME_CHARS: usize = 64;
+const MAX_TASK_CHARS: usize = 4000;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct TriggerFile {
+    version: u32,
+    #[serde(default)]
+    triggers: Vec<TriggerEntry>,
+}
+
+/// One `[[triggers]]` entry: exactly one of `schedule`, `folder` or
+/// `window_title`, and exactly one of `task` or `routine`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TriggerEntry {
+    name: String,
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+    #[serde(default)]
+    schedule: Option<String>,
+    #[serde(default)]
+    folder: Option<PathBuf>,
+    /// `*`/`?` file-name pattern for `folder`, e.g. `*.pdf`.
+    #[serde(default)]
+    pattern: Option<String>,
+    #[serde(default)]
+    window_title: Option<String>,
+    #[serde(default)]
+    task: Option<String>,
+    #[serde(default)]
+    routine: Option<String>,
+    #[serde(default)]
+    params: BTreeMap<String, String>,
+    #[serde(default)]
+    provider: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    endpoint: Option<String>,
+    /// When set, the only tools the run may call besides `done`. Unset, the
+    /// run may only call tools that read.
+    #[serde(default)]
+    allow_tools: Option<Vec<String>>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Event {
+    Schedule(Schedule),
+    Folder {
+        dir: PathBuf,
+        pattern: Option<String>,
+    },
+    Window {
+        title: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum Action {
+    Task {
+        text: String,
+        provider: String,
+        model: Option<String>,
+        endpoint: Option<String>,
+    },
+    Routine {
+        name: String,
+        params: BTreeMap<String, String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Trigger {
+    pub(super) name: String,
+    pub(super) event: Event,
+    pub(super) action: Action,
+    pub(super) allow_tools: Option<Vec<String>>,
+}
+
+/// Values an event contributes to `{placeholder}`s in the task text and
+/// routine parameters.
+pub(super) type EventContext = BTreeMap<&'static str, String>;
+
+impl TriggerFile {
+    /// Enabled triggers; any invalid entry rejects the whole file so a typo
+    /// never silently drops a nightly job.
+    pub(super) fn triggers(self) -> Result<Vec<Trigger>, String> {
+        if self.version != SUPPORTED_VERSION {
+            return Err(format!(
+                "unsupported trigger file version {} (expected {SUPPORTED_VERSION})",
+                self.version
+            ));
+        }
+        if self.triggers.len() > MAX_TRIGGERS {
+            return Err(format!("at most {MAX_TRIGGERS} triggers are allowed"));
+        }
+        let mut names = HashSet::new();
+        let mut triggers = Vec::new();
+        for entry in self.triggers {
+            let name = entry.name.trim().to_string();
+            if !names.insert(name.to_lowercase()) {
+                return Err(format!("trigger {name:?} is declared twice"));
+            }
+            let enabled = entry.enabled;
+            let trigger = entry
+                .validate(name.clone())
+                .map_err(|error| format!("trigger {name:?}: {error}"))?;
+            if enabled {
+                triggers.push(trigger);
+            }
+        }
+        Ok(triggers)
+    }
+}
+
+impl TriggerEntry {
+    fn validate(self, name: String) -> Result<Trigger, String> {
+        if name.is_empty()
+            || name.chars().count() > MAX_NAME_CHARS
+            || name.chars().any(char::is_control)
+        {
+            return Err(format!(
+                "name must be 1 to {MAX_NAME_CHARS} printable characters"
+            ));
+        }
+        let event = match (self.schedule, self.folder, self.window_title) {
+            (Some(schedule), None, None) => {
+                if self.pattern.is_some() {
+                    return Err("pattern applies only to folder triggers".into());
+                }
+                Event::Schedule(Schedule::parse(&schedule)?)
+            }
+            (None, Some(dir), None) => {
+                if !dir.is_absolute() {
+                    return Err("folder must be an absolute path".into());
+                }
+                Event::Folder {
+                    dir,
+                    pattern: self.pattern.filter(|pattern| !pattern.trim().is_empty()),
+                }
+            }
+            (None, None, Some(title)) if !title.trim().is_empty() => {
+                if self.pattern.is_some() {
+                    return Err("pattern applies only to folder triggers".into());
+                }
+                Event::Window {
+                    title: title.trim().to_string(),
+                }
+            }
+            _ => {
+                return Err(
+                    "set exactly one of schedule, folder or a non-empty window_title".into(),
+                );
+            }
+        };
+        let action = match (self.task, self.routine) {
+            (Some(text), None) => {
+                let text = text.trim().to_string();
+                if text.is_empty() || text.chars().count() > MAX_TASK_CHARS {
+                    return Err(format!("task must be 1 to {MAX_TASK_CHARS} characters"));
+                }
+                if !self.params.is_empty() {
+                    return Err("params apply only to routine triggers".into());
+                }
+                Action::Task {
+                    text,
+                    provider: self.provider.unwrap_or_else(|| "gemini".to_string()),
+                    model: self.model,
+                    endpoint: self.endpoint,
+                }
+            }
+            (None, Some(routine)) => {
+                if self.provider.is_some() || self.model.is_some() || self.endpoint.is_some() {
+                    return Err("provider, model and endpoint apply only to task triggers".into());
+                }
+                Action::Routine {
+                    name: routine.trim().to_string(),
+                    params: self.params,
+                }
+            }
+            _ => return Err("set exactly one of task or routine".into()),
+        };
+        let allow_tools = match self.allow_tools {
+            Some(tools) if tools.iter().any(|tool| tool.trim().is_empty()) => {
+                return Err("allow_tools entries must be tool names".into());
+            }
+            tools => tools,
+        };
+        Ok(Trigger {
+            name,
+            event,
+            action,
+            allow_tools,
+        })
+    }
+}
+
+impl Action {
+    /// The action with `{placeholder}`s filled from the event. Unknown
+    /// placeholders are left as written. Event values come from file names
+    /// and window titles anyone can choose, so in task text each one goes in
+    /// as a quoted JSON string the model reads as data; routine parameters
+    /// take the raw value.
+    pub(super) fn bind(&self, context: &EventContext) -> Self {
+        match self {
+            Self::Task {
+                text,
+                provider,
+                model,
+                endpoint,
+            } => Self::Task {
+                text: fill(text, context, |value| Value::from(value).to_string()),
+                provider: provider.clone(),
+                model: model.clone(),
+                endpoint: endpoint.clone(),
+            },
+            Self::Routine { name, params } => Self::Routine {
+                name: name.clone(),
+                params: params
+                    .iter()
+                    .map(|(key, value)| (key.clone(), fill(value, context, str::to_string)))
+                    .collect(),
+            },
+        }
+    }
+
+    pub(super) fn describe(&self) -> String {
+        match self {
+            Self::Task { text, .. } => text.clone(),
+            Self::Routine { name, .. } => format!("routine {name}"),
+        }
+    }
+}
+
+/// Replaces each `{key}` in one pass, so a value that itself contains a
+/// placeholder is never expanded again.
+fn fill(text: &str, context: &EventContext, render: impl Fn(&str) -> String) -> String {
+    let mut filled = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let found = context.iter().find(|(key, _)| {
+            after
+                .strip_prefix(**key)
+                .is_some_and(|tail| tail.starts_with('}'))
+        });
+        match found {
+            Some((key, value)) => {
+                filled.push_str(&render(value));
+                rest = &after[key.len() + 1..];
+            }
+            None => {
+                filled.push('{');
+                rest = after;
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+/// Fires once per matching minute. Minutes missed while the app was not
+/// running, or the machine was asleep, are not caught up.
+#[derive(Default)]
+pub(super) struct ScheduleWatch {
+    last_fired: Option<NaiveDateTime>,
+}
+
+impl ScheduleWatch {
+    pub(super) fn due(&mut self, schedule: &Schedule, now: NaiveDateTime) -> bool {
+        let Some(minute) = now.with_second(0).and_then(|time| time.with_nanosecond(0)) else {
+            return false;
+        };
+        if self.last_fired == Some(minute) || !schedule.matches(&minute) {
+            return false;
+        }
+        self.last_fired = Some(minute);
+        true
+    }
+}
+
+/// New files in a folder. Files present when watching starts never fire; a
+/// new file fires once its size has held still for one poll, so a file still
+/// being copied is not handed to a task half-written.
+#[derive(Default)]
+pub(super) struct FolderWatch {
+    primed: bool,
+    known: HashSet<PathBuf>,
+    pending: HashMap<PathBuf, u64>,
+}
+
+impl FolderWatch {
+    pub(super) fn observe(&mut self, files: Vec<(PathBuf, u64)>) -> Vec<PathBuf> {
+        let present: HashSet<PathBuf> = files.iter().map(|(path, _)| path.clone()).collect();
+        self.known.retain(|path| present.contains(path));
+        self.pending.retain(|path, _| present.contains(path));
+        if !self.primed {
+            self.primed = true;
+            self.known = present;
+            return Vec::new();
+        }
+        let mut ready = Vec::new();
+        for (path, size) in files {
+            if self.known.contains(&path) {
+                continue;
+            }
+            match self.pending.insert(path.clone(), size) {
+                Some(previous) if previous == size => {
+                    self.pending.remove(&path);
+                    self.known.insert(path.clone());
+                    ready.push(path);
+                }
+                _ => {}
+            }
+        }
+        ready.sort();
+        ready
+    }
+}
+
+/// Windows whose title contains the configured text, case-insensitively.
+/// Windows already open when watching starts never fire; each newly opened
+/// window fires once.
+#[derive(Default)]
+pub(super) struct WindowWatch {
+    primed: bool,
+    open: HashSet<String>,
+}
+
+impl WindowWatch {
+    /// `windows` are `(stable target, title)` pairs of every top-level
+    /// window. Returns the titles of matching windows that just opened.
+    pub(super) fn observe(&mut self, wanted: &str, windows: Vec<(String, String)>) -> Vec<String> {
+        let wanted = wanted.to_lowercase();
+        let matching: Vec<(String, String)> = windows
+            .into_iter()
+            .filter(|(_, title)| title.to_lowercase().contains(&wanted))
+            .collect();
+        let opened = if self.primed {
+            matching
+                .iter()
+                .filter(|(target, _)| !self.open.contains(target))
+                .map(|(_, title)| title.clone())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        self.primed = true;
+        self.open = matching.into_iter().map(|(target, _)| target).collect();
+        opened
+    }
+}
+
+/// Case-insensitive `*`/`?` match of a file name; no pattern matches all.
+pub(super) fn name_matches(pattern: Option<&str>, path: &Path) -> bool {
+    let Some(pattern) = pattern else {
+        return true;
+    };
+    let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+        return false;
+    };
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, n));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            n = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+#[path = "model_tests.rs"]
+mod tests;
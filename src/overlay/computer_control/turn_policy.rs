@@ -81,6 +81,7 @@ fn is_mutating_tool_with_integration_metadata(
             | "system_query"
             | "list_files"
             | "read_text_file"
+            | "read_office_file"
             | "list_windows"
             | "read_clipboard"
             | "wait"
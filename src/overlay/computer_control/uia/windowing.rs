@@ -47,6 +47,17 @@ pub(crate) struct WindowDescriptor {
     target: String,
 }
 
+impl WindowDescriptor {
+    pub(crate) fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// `@hwnd:<handle>:<pid>` identity that survives title changes.
+    pub(crate) fn target(&self) -> &str {
+        &self.target
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum WindowError {
     EmptyTarget,
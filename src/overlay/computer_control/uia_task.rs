@@ -34,6 +34,7 @@ mod dispatch_telemetry;
 mod exact_edit_guard;
 mod executable_provenance;
 mod frame_identity;
+mod grounded_step;
 mod harness_options;
 mod keyboard_target_gate;
 mod perception;
@@ -56,7 +57,7 @@ use anchors::*;
 pub(in crate::overlay::computer_control) use frame_identity::FrameSource;
 use perception::*;
 use postcondition::*;
-pub(crate) use prompt::build_setup;
+pub(crate) use prompt::{build_setup, turn_based_catalog};
 use render::*;
 use review::*;
 pub(super) use snapshot::{SnapshotFrame, snapshot};
@@ -368,38 +369,8 @@ state shown below.\n{}",
                         return Ok(());
                     }
 
-                    let action_result = brain.dispatch(&name, &args, &ctx, &cancel, None, false);
-                    let g = brain.ground(&name, &args)?;
-                    let execution_ok = action_result.get("ok").and_then(Value::as_bool);
-                    let mutating = super::turn_policy::is_mutating_tool(&name);
-                    let effect_status = super::effect_receipt::EffectStatus::after_dispatch(
-                        &action_result,
-                        mutating,
-                    );
-                    let recovery_advice = (execution_ok != Some(false)
-                        && g.postcondition.request_advice())
-                    .then(|| brain.stuck_advice(task, &cancel))
-                    .flatten();
-                    let postcondition = g.postcondition.response(
-                        execution_ok,
-                        mutating,
-                        effect_status,
-                        recovery_advice,
-                    );
-                    let mut resp = json!({
-                        "action_result": action_result,
-                        "execution_ok": execution_ok,
-                        "new_state": g.state_text,
-                        "postcondition": postcondition,
-                    });
-                    if execution_ok == Some(false)
-                        || (g.postcondition.detected_no_effect() && !effect_status.is_verified())
-                    {
-                        resp["ok"] = json!(false);
-                    } else if let Some(ok) = execution_ok {
-                        resp["ok"] = json!(ok);
-                    }
-                    effect_status.annotate(&mut resp);
+                    let (resp, g) =
+                        brain.grounded_tool_response(&name, &args, &ctx, task, &cancel)?;
                     send(&mut socket, tool_response(&id, &name, resp))?; // answer first
                     send(&mut socket, realtime_video_jpeg_b64(&g.frame_b64))?; // then the new frame
                 }
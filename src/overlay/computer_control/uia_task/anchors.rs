@@ -325,6 +325,7 @@ pub(super) fn action_invalidates_anchors(name: &str) -> bool {
             | "open_memory"
             | "list_files"
             | "read_text_file"
+            | "read_office_file"
             | "system_query"
             | "artifact_info"
             | "extract_artifact"
@@ -104,6 +104,7 @@ impl Brain {
 
     pub fn new(target: Option<String>) -> Self {
         super::super::telemetry::begin_session();
+        super::super::edit_journal::begin_session();
         let trace_dir = super::super::telemetry::trace_dir();
         if let Err(error) = std::fs::create_dir_all(&trace_dir) {
             super::super::telemetry::artifact_write_failed(
@@ -23,6 +23,11 @@ impl Brain {
         let action = trace.unwrap_or_else(|| super::super::telemetry::claim_action(name));
         self.active_action = Some(action);
         let t0 = Instant::now();
+        if let Some(blocked) = super::super::permission_policy::gate(name, args, action) {
+            return self.finish_dispatch(action, name, args, blocked, t0);
+        }
+        let unconfirmed = super::super::permission_policy::without_confirmation(args);
+        let args = unconfirmed.as_ref().unwrap_or(args);
         if action_invalidates_anchors(name) {
             self.clear_anchors(&format!("before_{name}"));
         }
@@ -430,7 +435,7 @@ impl Brain {
                     }
                 }
             }
-            "open_url" | "launch_app" => {
+            "open_url" | "launch_app" | "undo_file_edit" => {
                 if self.dry {
                     json!({"ok": true, "note": "dry"})
                 } else {
@@ -440,12 +445,16 @@ impl Brain {
             "run_command" => {
                 self.dispatch_exact_process(args, cancel, action, authorize_repair_process)
             }
-            "edit_text_file" => self.dispatch_text_edit(args, cancel, action),
+            "edit_text_file" | "edit_office_file" => {
+                self.dispatch_text_edit(name, args, cancel, action)
+            }
             "edit_text_file_structure" => self.dispatch_structural_edit(args, cancel, action),
             "save_artifact" => self.dispatch_artifact_save(args, cancel, action),
             "system_query" => super::super::system_query::query(args),
             "list_files" => super::super::system_query::list_files(args),
-            "read_text_file" => executor::execute_ex(name, args, &self.profile, cancel),
+            "read_text_file" | "read_office_file" => {
+                executor::execute_ex(name, args, &self.profile, cancel)
+            }
             "scroll" => {
                 // Real mouse-wheel scroll. Resolve where to scroll: a given grid
                 // cell, else the centre of the current view (the wheel acts on the
@@ -586,9 +595,5 @@ impl Brain {
 }
 
 fn window_error(error: super::super::uia::WindowError) -> Value {
-    json!({
-        "ok": false,
-        "code": error.code(),
-        "error": error.to_string(),
-    })
+    json!({"ok": false, "code": error.code(), "error": error.to_string()})
 }
@@ -72,7 +72,7 @@ impl ExactEditGuard {
     }
 
     pub(super) fn record_result(&mut self, name: &str, args: &Value, result: &mut Value) {
-        if name == "read_text_file" {
+        if matches!(name, "read_text_file" | "read_office_file") {
             if result.get("ok").and_then(Value::as_bool) == Some(true)
                 && let Some(resource) = result
                     .get("path")
@@ -215,7 +215,7 @@ fn normalize_resource(value: &str) -> String {
 fn is_exact_edit_tool(name: &str) -> bool {
     matches!(
         name,
-        "edit_text_file" | "edit_text_file_structure" | "save_artifact"
+        "edit_text_file" | "edit_text_file_structure" | "edit_office_file" | "save_artifact"
     )
 }
 
@@ -0,0 +1,50 @@
+//! One model tool call end to end for a host that answers in a single tool
+//! response: dispatch, re-ground, then the typed postcondition with any
+//! recovery advice. Shared by the Live task harness and the turn-based text
+//! provider.
+
+use std::sync::{Arc, atomic::AtomicBool};
+
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use super::{Brain, Grounded};
+
+impl Brain {
+    pub(in crate::overlay::computer_control) fn grounded_tool_response(
+        &mut self,
+        name: &str,
+        args: &Value,
+        ctx: &str,
+        task: &str,
+        cancel: &Arc<AtomicBool>,
+    ) -> Result<(Value, Grounded)> {
+        let action_result = self.dispatch(name, args, ctx, cancel, None, false);
+        let g = self.ground(name, args)?;
+        let execution_ok = action_result.get("ok").and_then(Value::as_bool);
+        let mutating = super::super::turn_policy::is_mutating_tool(name);
+        let effect_status =
+            super::super::effect_receipt::EffectStatus::after_dispatch(&action_result, mutating);
+        let recovery_advice = (execution_ok != Some(false) && g.postcondition.request_advice())
+            .then(|| self.stuck_advice(task, cancel))
+            .flatten();
+        let postcondition =
+            g.postcondition
+                .response(execution_ok, mutating, effect_status, recovery_advice);
+        let mut resp = json!({
+            "action_result": action_result,
+            "execution_ok": execution_ok,
+            "new_state": g.state_text,
+            "postcondition": postcondition,
+        });
+        if execution_ok == Some(false)
+            || (g.postcondition.detected_no_effect() && !effect_status.is_verified())
+        {
+            resp["ok"] = json!(false);
+        } else if let Some(ok) = execution_ok {
+            resp["ok"] = json!(ok);
+        }
+        effect_status.annotate(&mut resp);
+        Ok((resp, g))
+    }
+}
@@ -13,9 +13,9 @@ use crate::api::gemini_live::setup::{LiveSetupBuilder, MediaResolution, Transcri
 
 use super::super::{engine, executor, protocol};
 
-const STATIC_TOOL_COUNT: usize = 62;
+const STATIC_TOOL_COUNT: usize = 65;
 const STATIC_TOOLS_SHA256: &str =
-    "9794b2eadf11e44d90bb4d3891ee75677682a1a6b54fe60aa11bd08c4db55e75";
+    "bea5c9876edd7a3e5ad9d36913ce1acf3f5c305371c7621e2f53fe4c6d284fad";
 const ELEVATED_PROMPT_SHA256: &str =
     "55f2891a7a90fd6fbb5ab6e3a39f9cec5facdec46fdae7ed232daa7885e6c143";
 const STANDARD_PROMPT_SHA256: &str =
@@ -50,11 +50,7 @@ fn build_setup_with_declarations(
     reconnect_context: Option<&str>,
     integration_declarations: Vec<Value>,
 ) -> Result<Value> {
-    let privilege = if executor::is_elevated() {
-        HostPrivilege::Elevated
-    } else {
-        HostPrivilege::Standard
-    };
+    let privilege = host_privilege();
     let plan = engine::build_setup(SetupRequest {
         privilege,
         voice_mode: voice,
@@ -72,6 +68,33 @@ fn build_setup_with_declarations(
     )
 }
 
+/// The validated system instruction and function declarations for a
+/// turn-based text provider: no voice, no Google Search tool.
+pub(crate) fn turn_based_catalog() -> Result<(String, Vec<Value>)> {
+    let integration_declarations = super::super::mcp::active_tool_declarations()?;
+    let privilege = host_privilege();
+    let plan = engine::build_setup(SetupRequest {
+        privilege,
+        voice_mode: false,
+        search_enabled: false,
+        integration_declarations: integration_declarations.clone(),
+    })?;
+    validate_plan(&plan, false, false, privilege, &integration_declarations)?;
+    let declarations = plan.tools[0]["functionDeclarations"]
+        .as_array()
+        .cloned()
+        .ok_or_else(|| anyhow!("Computer Control engine returned invalid declarations"))?;
+    Ok((plan.system_instruction, declarations))
+}
+
+fn host_privilege() -> HostPrivilege {
+    if executor::is_elevated() {
+        HostPrivilege::Elevated
+    } else {
+        HostPrivilege::Standard
+    }
+}
+
 fn assemble_setup(
     plan: SetupPlan,
     resume: Option<&str>,
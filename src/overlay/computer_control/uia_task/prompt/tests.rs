@@ -189,11 +189,11 @@ fn setup_catalog_has_unique_named_tools() {
     );
     assert_eq!(
         declarations.len(),
-        62,
+        65,
         "built-in capability was added or lost"
     );
     assert!(
-        serde_json::to_string(declarations).unwrap().len() <= 22_500,
+        serde_json::to_string(declarations).unwrap().len() <= 24_250,
         "function catalog exceeded its reviewed prompt budget"
     );
     assert!(
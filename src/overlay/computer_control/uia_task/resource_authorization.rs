@@ -229,6 +229,7 @@ fn target_proposal(tool: &str, args: &Value) -> Result<Value, String> {
     let existed_before = cleaned.is_file();
     let (operation, require_existing) = match tool {
         "edit_text_file" | "edit_text_file_structure" => ("modify_existing_text_file", true),
+        "edit_office_file" => ("modify_existing_office_file", true),
         "save_artifact" => (
             if existed_before {
                 "replace_existing_file"
@@ -251,12 +252,21 @@ fn target_proposal(tool: &str, args: &Value) -> Result<Value, String> {
         {
             return Err("the proposed existing-file mutation has no valid expected hash".into());
         }
-        let replacements = args
-            .get("replacements")
-            .and_then(Value::as_array)
-            .filter(|items| !items.is_empty())
-            .ok_or_else(|| "the proposed existing-file mutation has no replacements".to_string())?;
-        if replacements.len() > 64 {
+        let edit_list = |field: &str| {
+            args.get(field)
+                .and_then(Value::as_array)
+                .map_or(0, Vec::len)
+        };
+        // Office edits may carry cell writes instead of text replacements.
+        let cell_edits = if tool == "edit_office_file" {
+            edit_list("cells")
+        } else {
+            0
+        };
+        if edit_list("replacements") == 0 && cell_edits == 0 {
+            return Err("the proposed existing-file mutation has no replacements".to_string());
+        }
+        if edit_list("replacements") > 64 || cell_edits > 256 {
             return Err("the proposed mutation has too many replacement groups".to_string());
         }
     }
@@ -95,6 +95,32 @@ fn ordinary_and_structural_edits_share_one_target_scope_identity() {
     std::fs::remove_file(file).unwrap();
 }
 
+#[test]
+fn office_cell_edits_are_an_existing_file_mutation_without_cell_values() {
+    let mut authorization = ResourceAuthorization::default();
+    authorization.record_request(4, "Fill in the budget workbook.");
+    let file = std::env::temp_dir().join(format!(
+        "cc-resource-office-{}-{}.xlsx",
+        std::process::id(),
+        super::super::super::telemetry::next_artifact_id()
+    ));
+    std::fs::write(&file, "PK").unwrap();
+    let args = json!({
+        "path": file,
+        "expected_sha256": "0".repeat(64),
+        "cells": [{ "cell": "B2", "text": "private-value" }]
+    });
+    let context = authorization.context("edit_office_file", &args).unwrap();
+    assert!(context.contains("modify_existing_office_file"));
+    assert!(!context.contains("private-value"));
+    assert!(
+        authorization
+            .context("edit_text_file", &args)
+            .is_err_and(|error| error.contains("no replacements"))
+    );
+    std::fs::remove_file(file).unwrap();
+}
+
 #[test]
 fn artifact_target_context_is_payload_type_agnostic() {
     let mut authorization = ResourceAuthorization::default();
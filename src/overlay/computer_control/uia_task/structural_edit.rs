@@ -32,8 +32,11 @@ impl Brain {
         result
     }
 
+    /// `edit_text_file` and `edit_office_file`: an exact, hash-guarded change
+    /// to one existing file, scoped by resource authorization first.
     pub(super) fn dispatch_text_edit(
         &mut self,
+        name: &str,
         args: &Value,
         cancel: &AtomicBool,
         action: super::super::telemetry::ActionTrace,
@@ -41,13 +44,13 @@ impl Brain {
         if self.dry {
             return json!({"ok": true, "note": "dry"});
         }
-        let scope =
-            self.resource_authorization
-                .evaluate("edit_text_file", args, cancel, Some(action));
+        let scope = self
+            .resource_authorization
+            .evaluate(name, args, cancel, Some(action));
         if !scope.authorized {
             return scope.result;
         }
-        let mut result = executor::execute_ex("edit_text_file", args, &self.profile, cancel);
+        let mut result = executor::execute_ex(name, args, &self.profile, cancel);
         result["resource_scope_authorization"] = scope.result;
         result
     }
@@ -1,24 +1,21 @@
 // --- CHAIN BLOCK EXECUTION ---
 // API execution with retry logic for chain processing blocks.
 
-use crate::api::{
-    TranslateImageRequest, TranslateTextRequest, translate_image_streaming,
-    translate_text_streaming,
-};
+mod calls;
+
+use crate::config::types::{DEFAULT_QUALITY_FLOOR, RoutingObjective};
 use crate::config::{Config, ProcessingBlock};
-use crate::gui::settings_ui::get_localized_preset_name;
 use crate::overlay::result::{ChainCancelToken, RefineContext, WINDOW_STATES, update_window_text};
 use crate::retry_model_chain::{
     RetryChainKind, claim_model_attempt, interactive_request_timeout, preflight_skip_reason,
-    record_model_failure, record_model_success, release_model_probe, resolve_next_retry_model,
+    record_model_failure, record_model_success, release_model_probe, resolve_next_routed_model,
 };
 use crate::win_types::SendHwnd;
-use std::collections::HashSet;
-use std::sync::{
-    Arc, Mutex,
-    atomic::{AtomicBool, Ordering},
+use calls::{
+    ExecuteImageBlockRequest, ExecuteTextBlockRequest, execute_image_block, execute_text_block,
 };
-use std::time::Duration;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
 use windows::Win32::Foundation::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
 
@@ -44,10 +41,80 @@ pub struct ExecuteBlockRequest<'a> {
 
 /// Execute the block's API call and return the result text.
 pub fn execute_block(request: ExecuteBlockRequest<'_>) -> String {
+    if request.block.block_type == "input_adapter" {
+        return request.input_text.to_string();
+    }
+
+    if request.skip_execution {
+        if let Some(h) = request.my_hwnd {
+            update_window_text(h, request.input_text);
+        }
+        return request.input_text.to_string();
+    }
+
+    let my_hwnd = request.my_hwnd;
+    let config = request.config;
+    let window_shown = Arc::new(Mutex::new(request.block.block_type != "image"));
+    let processing_hwnd_arc = Arc::new(Mutex::new(request.processing_hwnd_shared));
+    let cache_key = response_cache_key(&request);
+    if let Some(key) = &cache_key
+        && let Some(cached) = crate::response_cache::lookup(key, &config.response_cache)
+    {
+        if let Some(h) = my_hwnd {
+            show_cached_result(h, &window_shown, &processing_hwnd_arc);
+        }
+        return handle_execution_result(
+            Ok(cached.text),
+            my_hwnd,
+            &window_shown,
+            &processing_hwnd_arc,
+            config,
+            request.model_full_name,
+        );
+    }
+    let cancel_token = request.cancel_token.clone();
+    let keyed_model = request.model_full_name.to_string();
+    let (res, model_full_name) = run_with_retries(request, &window_shown, &processing_hwnd_arc);
+    if let (Some(key), Ok(text)) = (&cache_key, &res)
+        && may_cache(&keyed_model, &model_full_name, cancel_token.is_cancelled())
+    {
+        crate::response_cache::store(key, &config.response_cache, text);
+    }
+    handle_execution_result(
+        res,
+        my_hwnd,
+        &window_shown,
+        &processing_hwnd_arc,
+        config,
+        &model_full_name,
+    )
+}
+
+/// Like [`execute_block`], but hands back the error that ended the retries
+/// instead of showing it, for callers with no window (golden-set evaluation),
+/// along with the model that answered, which is a fallback when it differs
+/// from the request's.
+pub fn try_execute_block(request: ExecuteBlockRequest<'_>) -> (anyhow::Result<String>, String) {
+    if request.block.block_type == "input_adapter" || request.skip_execution {
+        return (
+            Ok(request.input_text.to_string()),
+            request.model_full_name.to_string(),
+        );
+    }
+    let window_shown = Arc::new(Mutex::new(true));
+    let processing_hwnd_arc = Arc::new(Mutex::new(None));
+    run_with_retries(request, &window_shown, &processing_hwnd_arc)
+}
+
+/// The retry loop: the block's result and the model that produced it.
+fn run_with_retries(
+    request: ExecuteBlockRequest<'_>,
+    window_shown: &Arc<Mutex<bool>>,
+    processing_hwnd_arc: &Arc<Mutex<Option<SendHwnd>>>,
+) -> (anyhow::Result<String>, String) {
+    let sends_image = sends_image(&request);
     let ExecuteBlockRequest {
         block,
-        block_idx,
-        blocks,
         my_hwnd,
         input_text,
         context,
@@ -55,34 +122,14 @@ pub fn execute_block(request: ExecuteBlockRequest<'_>) -> String {
         provider,
         model_full_name,
         final_prompt,
-        skip_execution,
         config,
         preset_id,
-        processing_hwnd_shared,
         cancel_token,
+        ..
     } = request;
-    if block.block_type == "input_adapter" {
-        return input_text.to_string();
-    }
-
-    if skip_execution {
-        if let Some(h) = my_hwnd {
-            update_window_text(h, input_text);
-        }
-        return input_text.to_string();
-    }
-
-    let groq_key = config.api_key.clone();
-    let gemini_key = config.gemini_api_key.clone();
-
     let actual_streaming_enabled = block.streaming_enabled;
 
     let accumulated = Arc::new(Mutex::new(String::new()));
-    let is_first_processing_block = blocks
-        .iter()
-        .position(|b| b.block_type != "input_adapter")
-        .map(|pos| pos == block_idx)
-        .unwrap_or(false);
 
     // Retry variables
     let mut current_model_id = model_id.to_string();
@@ -93,6 +140,12 @@ pub fn execute_block(request: ExecuteBlockRequest<'_>) -> String {
     let mut provider_attempts = 0;
     let retry_chain_kind = RetryChainKind::from_block_type(&block.block_type)
         .filter(|_| !crate::model_config::model_is_non_llm(model_id));
+    let (routing_objective, quality_floor) = config
+        .presets
+        .iter()
+        .find(|preset| preset.id == preset_id)
+        .map(|preset| (preset.routing_objective, preset.routing_quality_floor))
+        .unwrap_or((RoutingObjective::default(), DEFAULT_QUALITY_FLOOR));
 
     // Area of the image this block would send, where there is one. An endpoint
     // that declares a reliable floor is passed over below it, and the chain moves
@@ -104,9 +157,6 @@ pub fn execute_block(request: ExecuteBlockRequest<'_>) -> String {
         _ => None,
     };
 
-    let window_shown = Arc::new(Mutex::new(block.block_type != "image"));
-    let processing_hwnd_arc = Arc::new(Mutex::new(processing_hwnd_shared));
-
     // Retry loop
     let res = loop {
         let acc_clone = accumulated.clone();
@@ -135,12 +185,14 @@ pub fn execute_block(request: ExecuteBlockRequest<'_>) -> String {
 
             failed_model_ids.push(current_model_id.clone());
 
-            if let Some(next_model) = resolve_next_retry_model(
+            if let Some(next_model) = resolve_next_routed_model(
                 &current_model_id,
                 &failed_model_ids,
                 &blocked_providers,
                 chain_kind,
                 config,
+                routing_objective,
+                quality_floor,
             ) {
                 current_model_id = next_model.id;
                 current_provider = next_model.provider;
@@ -158,12 +210,11 @@ pub fn execute_block(request: ExecuteBlockRequest<'_>) -> String {
         }
 
         provider_attempts += 1;
+        let (groq_key, gemini_key) =
+            crate::api::provider_credentials::for_attempt(&current_provider, config);
         let request_timeout =
             interactive_request_timeout(&current_model_id, config, actual_streaming_enabled);
-        let res_inner = if is_first_processing_block
-            && block.block_type == "image"
-            && matches!(context, RefineContext::Image(_))
-        {
+        let res_inner = if sends_image {
             execute_image_block(ExecuteImageBlockRequest {
                 context,
                 groq_key: &groq_key,
@@ -201,6 +252,14 @@ pub fn execute_block(request: ExecuteBlockRequest<'_>) -> String {
         match res_inner {
             Ok(val) => {
                 record_model_success(&current_model_id);
+                let sent_text = if sends_image { "" } else { input_text };
+                crate::usage_stats::spend::record_request(
+                    &current_provider,
+                    &current_model_full_name,
+                    final_prompt.chars().count() + sent_text.chars().count(),
+                    u32::from(sends_image),
+                    val.chars().count(),
+                );
                 break Ok(val);
             }
             Err(e) => {
@@ -210,7 +269,16 @@ pub fn execute_block(request: ExecuteBlockRequest<'_>) -> String {
                     break Err(e);
                 }
 
-                record_model_failure(&current_model_id, &e.to_string());
+                let key_rotated =
+                    record_model_failure(&current_model_id, &current_provider, &e.to_string());
+                // Only the API key hit its quota: the same model goes again on
+                // the next key from the provider's pool.
+                if key_rotated && may_retry_provider(provider_attempts) {
+                    if let Ok(mut lock) = accumulated.lock() {
+                        lock.clear();
+                    }
+                    continue;
+                }
 
                 if may_retry_provider(provider_attempts)
                     && let Some(chain_kind) = retry_chain_kind
@@ -222,12 +290,14 @@ pub fn execute_block(request: ExecuteBlockRequest<'_>) -> String {
 
                     failed_model_ids.push(current_model_id.clone());
 
-                    if let Some(next_model) = resolve_next_retry_model(
+                    if let Some(next_model) = resolve_next_routed_model(
                         &current_model_id,
                         &failed_model_ids,
                         &blocked_providers,
                         chain_kind,
                         config,
+                        routing_objective,
+                        quality_floor,
                     ) {
                         current_model_id = next_model.id;
                         current_provider = next_model.provider;
@@ -246,178 +316,57 @@ pub fn execute_block(request: ExecuteBlockRequest<'_>) -> String {
         }
     };
 
-    // Handle result
-    handle_execution_result(
-        res,
-        my_hwnd,
-        &window_shown,
-        &processing_hwnd_arc,
-        config,
-        &current_model_full_name,
-    )
+    (res, current_model_full_name)
 }
 
-/// Execute an image processing block.
-struct ExecuteImageBlockRequest<'a> {
-    context: &'a RefineContext,
-    groq_key: &'a str,
-    gemini_key: &'a str,
-    final_prompt: &'a str,
-    model_full_name: &'a str,
-    provider: &'a str,
-    streaming_enabled: bool,
-    request_timeout: Option<Duration>,
-    accumulated: Arc<Mutex<String>>,
-    my_hwnd: Option<HWND>,
-    window_shown: Arc<Mutex<bool>>,
-    processing_hwnd: Arc<Mutex<Option<SendHwnd>>>,
-    cancel_token: &'a Arc<ChainCancelToken>,
+/// Whether this block sends the captured image rather than text: only the
+/// first processing block of an image preset sees the capture itself.
+fn sends_image(request: &ExecuteBlockRequest<'_>) -> bool {
+    let is_first_processing_block = request
+        .blocks
+        .iter()
+        .position(|b| b.block_type != "input_adapter")
+        .map(|pos| pos == request.block_idx)
+        .unwrap_or(false);
+    is_first_processing_block
+        && request.block.block_type == "image"
+        && matches!(request.context, RefineContext::Image(_))
 }
 
-fn execute_image_block(request: ExecuteImageBlockRequest<'_>) -> anyhow::Result<String> {
-    let ExecuteImageBlockRequest {
-        context,
-        groq_key,
-        gemini_key,
-        final_prompt,
-        model_full_name,
-        provider,
-        streaming_enabled,
-        request_timeout,
-        accumulated,
-        my_hwnd,
-        window_shown,
-        processing_hwnd,
-        cancel_token,
-    } = request;
-    if let RefineContext::Image(img_data) = context {
-        let img = image::load_from_memory(img_data)
-            .expect("Failed to load png")
-            .to_rgba8();
-
-        // Bridge: chain token → API-level AtomicBool
-        let api_cancel = Arc::new(AtomicBool::new(false));
-        let api_cancel_cb = api_cancel.clone();
-        let chain_token_cb = cancel_token.clone();
-
-        translate_image_streaming(
-            TranslateImageRequest {
-                groq_api_key: groq_key,
-                gemini_api_key: gemini_key,
-                prompt: final_prompt.to_string(),
-                model: model_full_name.to_string(),
-                provider: provider.to_string(),
-                image: img,
-                original_bytes: Some(img_data.clone()),
-                streaming_enabled,
-                response_schema: None,
-                cancel_token: Some(api_cancel),
-                request_timeout,
-            },
-            move |chunk| {
-                if chain_token_cb.is_cancelled() {
-                    api_cancel_cb.store(true, Ordering::SeqCst);
-                    return;
-                }
-                handle_streaming_chunk(
-                    chunk,
-                    &accumulated,
-                    my_hwnd,
-                    &window_shown,
-                    &processing_hwnd,
-                );
-            },
+/// The response-cache key for this block, or `None` when caching is off or the
+/// answer depends on more than the request: search-grounded models read the
+/// live web, so a stored answer would silently go stale.
+fn response_cache_key(request: &ExecuteBlockRequest<'_>) -> Option<String> {
+    let config = request.config;
+    if !config.response_cache.enabled
+        || crate::model_config::model_supports_search_by_id_with_custom(
+            request.model_id,
+            &config.custom_models,
         )
-    } else {
-        Err(anyhow::anyhow!("Missing image context"))
+    {
+        return None;
     }
+    let input = match request.context {
+        RefineContext::Image(bytes) if sends_image(request) => bytes.as_slice(),
+        _ => request.input_text.as_bytes(),
+    };
+    let target_language = gtx_target_language(request.block);
+    let key = crate::response_cache::CacheRequest {
+        block_type: &request.block.block_type,
+        provider: request.provider,
+        model: request.model_full_name,
+        prompt: request.final_prompt,
+        target_language: target_language.as_deref(),
+        input,
+    }
+    .key();
+    Some(key)
 }
 
-/// Execute a text processing block.
-struct ExecuteTextBlockRequest<'a> {
-    input_text: &'a str,
-    groq_key: &'a str,
-    gemini_key: &'a str,
-    final_prompt: &'a str,
-    target_language: Option<String>,
-    model_full_name: &'a str,
-    provider: &'a str,
-    streaming_enabled: bool,
-    request_timeout: Option<Duration>,
-    preset_id: &'a str,
-    config: &'a Config,
-    accumulated: Arc<Mutex<String>>,
-    my_hwnd: Option<HWND>,
-    cancel_token: &'a Arc<ChainCancelToken>,
-}
-
-fn execute_text_block(request: ExecuteTextBlockRequest<'_>) -> anyhow::Result<String> {
-    let ExecuteTextBlockRequest {
-        input_text,
-        groq_key,
-        gemini_key,
-        final_prompt,
-        target_language,
-        model_full_name,
-        provider,
-        streaming_enabled,
-        request_timeout,
-        preset_id,
-        config,
-        accumulated,
-        my_hwnd,
-        cancel_token,
-    } = request;
-    let search_label = Some(get_localized_preset_name(preset_id, &config.ui_language));
-
-    // Bridge: chain token → API-level AtomicBool
-    let api_cancel = Arc::new(AtomicBool::new(false));
-    let api_cancel_cb = api_cancel.clone();
-    let chain_token_cb = cancel_token.clone();
-
-    translate_text_streaming(
-        TranslateTextRequest {
-            groq_api_key: groq_key,
-            gemini_api_key: gemini_key,
-            text: input_text.to_string(),
-            instruction: final_prompt.to_string(),
-            model: model_full_name.to_string(),
-            provider: provider.to_string(),
-            streaming_enabled,
-            use_json_format: false,
-            response_schema: None,
-            search_label,
-            ui_language: &config.ui_language,
-            cancel_token: Some(api_cancel),
-            request_timeout,
-            target_language,
-        },
-        move |chunk| {
-            if chain_token_cb.is_cancelled() {
-                api_cancel_cb.store(true, Ordering::SeqCst);
-                return;
-            }
-
-            let mut t = accumulated.lock().unwrap();
-            if let Some(wiped) = chunk.strip_prefix(crate::api::WIPE_SIGNAL) {
-                t.clear();
-                t.push_str(wiped);
-            } else {
-                t.push_str(chunk);
-            }
-
-            if let Some(h) = my_hwnd {
-                crate::overlay::result::latency::mark_window(h, "provider_first_output");
-                {
-                    let mut s = WINDOW_STATES.lock().unwrap();
-                    if let Some(st) = s.get_mut(&(h.0 as isize)) {
-                        st.is_refining = false;
-                    }
-                }
-                update_window_text(h, &t);
-            }
-        },
-    )
+/// Streaming results are stored only once complete, so a cancelled run never
+/// is; neither is an answer from a fallback model, which the key does not name.
+fn may_cache(keyed_model: &str, answered_by: &str, cancelled: bool) -> bool {
+    !cancelled && answered_by == keyed_model
 }
 
 fn may_retry_provider(completed_attempts: usize) -> bool {
@@ -440,50 +389,6 @@ fn gtx_target_language(block: &ProcessingBlock) -> Option<String> {
         .filter(|lang| !lang.is_empty())
 }
 
-/// Handle a streaming chunk for image blocks.
-fn handle_streaming_chunk(
-    chunk: &str,
-    accumulated: &Arc<Mutex<String>>,
-    my_hwnd: Option<HWND>,
-    window_shown: &Arc<Mutex<bool>>,
-    processing_hwnd: &Arc<Mutex<Option<SendHwnd>>>,
-) {
-    let mut t = accumulated.lock().unwrap();
-    if let Some(wiped) = chunk.strip_prefix(crate::api::WIPE_SIGNAL) {
-        t.clear();
-        t.push_str(wiped);
-    } else {
-        t.push_str(chunk);
-    }
-
-    if let Some(h) = my_hwnd {
-        crate::overlay::result::latency::mark_window(h, "provider_first_output");
-        // Show window on first chunk for image blocks
-        {
-            let mut shown = window_shown.lock().unwrap();
-            if !*shown {
-                *shown = true;
-                unsafe {
-                    let _ = ShowWindow(h, SW_SHOW);
-                }
-                let mut proc_hwnd = processing_hwnd.lock().unwrap();
-                if let Some(ph) = proc_hwnd.take() {
-                    unsafe {
-                        let _ = PostMessageW(Some(ph.0), WM_CLOSE, WPARAM(0), LPARAM(0));
-                    }
-                }
-            }
-        }
-        {
-            let mut s = WINDOW_STATES.lock().unwrap();
-            if let Some(st) = s.get_mut(&(h.0 as isize)) {
-                st.is_refining = false;
-            }
-        }
-        update_window_text(h, &t);
-    }
-}
-
 /// Get localized retry message.
 fn get_retry_message(lang: &str, model_name: &str) -> String {
     match lang {
@@ -531,22 +436,7 @@ fn handle_execution_result(
             );
             if let Some(h) = my_hwnd {
                 crate::overlay::result::latency::mark_window(h, "provider_first_output");
-                // Show window if hidden (image blocks)
-                {
-                    let mut shown = window_shown.lock().unwrap();
-                    if !*shown {
-                        *shown = true;
-                        unsafe {
-                            let _ = ShowWindow(h, SW_SHOW);
-                        }
-                        let mut proc_hwnd = processing_hwnd_arc.lock().unwrap();
-                        if let Some(ph) = proc_hwnd.take() {
-                            unsafe {
-                                let _ = PostMessageW(Some(ph.0), WM_CLOSE, WPARAM(0), LPARAM(0));
-                            }
-                        }
-                    }
-                }
+                reveal_window(h, window_shown, processing_hwnd_arc);
                 {
                     let mut s = WINDOW_STATES.lock().unwrap();
                     if let Some(st) = s.get_mut(&(h.0 as isize)) {
@@ -561,13 +451,58 @@ fn handle_execution_result(
     }
 }
 
+/// Shows a block window that stays hidden until its first output (image
+/// blocks) and closes the processing indicator that stood in for it.
+fn reveal_window(
+    h: HWND,
+    window_shown: &Arc<Mutex<bool>>,
+    processing_hwnd_arc: &Arc<Mutex<Option<SendHwnd>>>,
+) {
+    let mut shown = window_shown.lock().unwrap();
+    if !*shown {
+        *shown = true;
+        unsafe {
+            let _ = ShowWindow(h, SW_SHOW);
+        }
+        let mut proc_hwnd = processing_hwnd_arc.lock().unwrap();
+        if let Some(ph) = proc_hwnd.take() {
+            unsafe {
+                let _ = PostMessageW(Some(ph.0), WM_CLOSE, WPARAM(0), LPARAM(0));
+            }
+        }
+    }
+}
+
+/// Marks a window as answered from the response cache, so its controls say so.
+fn show_cached_result(
+    h: HWND,
+    window_shown: &Arc<Mutex<bool>>,
+    processing_hwnd_arc: &Arc<Mutex<Option<SendHwnd>>>,
+) {
+    {
+        let mut s = WINDOW_STATES.lock().unwrap();
+        if let Some(st) = s.get_mut(&(h.0 as isize)) {
+            st.from_cache = true;
+        }
+    }
+    reveal_window(h, window_shown, processing_hwnd_arc);
+    crate::overlay::result::button_canvas::update_window_position(h);
+}
+
 #[cfg(test)]
 mod tests {
-    use super::may_retry_provider;
+    use super::{may_cache, may_retry_provider};
 
     #[test]
     fn interactive_retry_is_limited_to_one_fallback() {
         assert!(may_retry_provider(1));
         assert!(!may_retry_provider(2));
     }
+
+    #[test]
+    fn only_the_keyed_model_answering_in_full_is_cached() {
+        assert!(may_cache("Groq Llama", "Groq Llama", false));
+        assert!(!may_cache("Groq Llama", "Gemini Flash", false));
+        assert!(!may_cache("Groq Llama", "Groq Llama", true));
+    }
 }
@@ -0,0 +1,225 @@
+// --- CHAIN BLOCK PROVIDER CALLS ---
+// One provider request per block attempt, streaming into the block's window.
+
+use crate::api::{
+    TranslateImageRequest, TranslateTextRequest, translate_image_streaming,
+    translate_text_streaming,
+};
+use crate::config::Config;
+use crate::gui::settings_ui::get_localized_preset_name;
+use crate::overlay::result::{ChainCancelToken, RefineContext, WINDOW_STATES, update_window_text};
+use crate::win_types::SendHwnd;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, Ordering},
+};
+use std::time::Duration;
+use windows::Win32::Foundation::*;
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+/// Execute an image processing block.
+pub(super) struct ExecuteImageBlockRequest<'a> {
+    pub(super) context: &'a RefineContext,
+    pub(super) groq_key: &'a str,
+    pub(super) gemini_key: &'a str,
+    pub(super) final_prompt: &'a str,
+    pub(super) model_full_name: &'a str,
+    pub(super) provider: &'a str,
+    pub(super) streaming_enabled: bool,
+    pub(super) request_timeout: Option<Duration>,
+    pub(super) accumulated: Arc<Mutex<String>>,
+    pub(super) my_hwnd: Option<HWND>,
+    pub(super) window_shown: Arc<Mutex<bool>>,
+    pub(super) processing_hwnd: Arc<Mutex<Option<SendHwnd>>>,
+    pub(super) cancel_token: &'a Arc<ChainCancelToken>,
+}
+
+pub(super) fn execute_image_block(request: ExecuteImageBlockRequest<'_>) -> anyhow::Result<String> {
+    let ExecuteImageBlockRequest {
+        context,
+        groq_key,
+        gemini_key,
+        final_prompt,
+        model_full_name,
+        provider,
+        streaming_enabled,
+        request_timeout,
+        accumulated,
+        my_hwnd,
+        window_shown,
+        processing_hwnd,
+        cancel_token,
+    } = request;
+    if let RefineContext::Image(img_data) = context {
+        let img = image::load_from_memory(img_data)
+            .expect("Failed to load png")
+            .to_rgba8();
+
+        // Bridge: chain token → API-level AtomicBool
+        let api_cancel = Arc::new(AtomicBool::new(false));
+        let api_cancel_cb = api_cancel.clone();
+        let chain_token_cb = cancel_token.clone();
+
+        translate_image_streaming(
+            TranslateImageRequest {
+                groq_api_key: groq_key,
+                gemini_api_key: gemini_key,
+                prompt: final_prompt.to_string(),
+                model: model_full_name.to_string(),
+                provider: provider.to_string(),
+                image: img,
+                original_bytes: Some(img_data.clone()),
+                streaming_enabled,
+                response_schema: None,
+                cancel_token: Some(api_cancel),
+                request_timeout,
+            },
+            move |chunk| {
+                if chain_token_cb.is_cancelled() {
+                    api_cancel_cb.store(true, Ordering::SeqCst);
+                    return;
+                }
+                handle_streaming_chunk(
+                    chunk,
+                    &accumulated,
+                    my_hwnd,
+                    &window_shown,
+                    &processing_hwnd,
+                );
+            },
+        )
+    } else {
+        Err(anyhow::anyhow!("Missing image context"))
+    }
+}
+
+/// Execute a text processing block.
+pub(super) struct ExecuteTextBlockRequest<'a> {
+    pub(super) input_text: &'a str,
+    pub(super) groq_key: &'a str,
+    pub(super) gemini_key: &'a str,
+    pub(super) final_prompt: &'a str,
+    pub(super) target_language: Option<String>,
+    pub(super) model_full_name: &'a str,
+    pub(super) provider: &'a str,
+    pub(super) streaming_enabled: bool,
+    pub(super) request_timeout: Option<Duration>,
+    pub(super) preset_id: &'a str,
+    pub(super) config: &'a Config,
+    pub(super) accumulated: Arc<Mutex<String>>,
+    pub(super) my_hwnd: Option<HWND>,
+    pub(super) cancel_token: &'a Arc<ChainCancelToken>,
+}
+
+pub(super) fn execute_text_block(request: ExecuteTextBlockRequest<'_>) -> anyhow::Result<String> {
+    let ExecuteTextBlockRequest {
+        input_text,
+        groq_key,
+        gemini_key,
+        final_prompt,
+        target_language,
+        model_full_name,
+        provider,
+        streaming_enabled,
+        request_timeout,
+        preset_id,
+        config,
+        accumulated,
+        my_hwnd,
+        cancel_token,
+    } = request;
+    let search_label = Some(get_localized_preset_name(preset_id, &config.ui_language));
+
+    // Bridge: chain token → API-level AtomicBool
+    let api_cancel = Arc::new(AtomicBool::new(false));
+    let api_cancel_cb = api_cancel.clone();
+    let chain_token_cb = cancel_token.clone();
+
+    translate_text_streaming(
+        TranslateTextRequest {
+            groq_api_key: groq_key,
+            gemini_api_key: gemini_key,
+            text: input_text.to_string(),
+            instruction: final_prompt.to_string(),
+            model: model_full_name.to_string(),
+            provider: provider.to_string(),
+            streaming_enabled,
+            use_json_format: false,
+            response_schema: None,
+            search_label,
+            ui_language: &config.ui_language,
+            cancel_token: Some(api_cancel),
+            request_timeout,
+            target_language,
+        },
+        move |chunk| {
+            if chain_token_cb.is_cancelled() {
+                api_cancel_cb.store(true, Ordering::SeqCst);
+                return;
+            }
+
+            let mut t = accumulated.lock().unwrap();
+            if let Some(wiped) = chunk.strip_prefix(crate::api::WIPE_SIGNAL) {
+                t.clear();
+                t.push_str(wiped);
+            } else {
+                t.push_str(chunk);
+            }
+
+            if let Some(h) = my_hwnd {
+                crate::overlay::result::latency::mark_window(h, "provider_first_output");
+                {
+                    let mut s = WINDOW_STATES.lock().unwrap();
+                    if let Some(st) = s.get_mut(&(h.0 as isize)) {
+                        st.is_refining = false;
+                    }
+                }
+                update_window_text(h, &t);
+            }
+        },
+    )
+}
+
+/// Handle a streaming chunk for image blocks.
+fn handle_streaming_chunk(
+    chunk: &str,
+    accumulated: &Arc<Mutex<String>>,
+    my_hwnd: Option<HWND>,
+    window_shown: &Arc<Mutex<bool>>,
+    processing_hwnd: &Arc<Mutex<Option<SendHwnd>>>,
+) {
+    let mut t = accumulated.lock().unwrap();
+    if let Some(wiped) = chunk.strip_prefix(crate::api::WIPE_SIGNAL) {
+        t.clear();
+        t.push_str(wiped);
+    } else {
+        t.push_str(chunk);
+    }
+
+    if let Some(h) = my_hwnd {
+        crate::overlay::result::latency::mark_window(h, "provider_first_output");
+        // Show window on first chunk for image blocks
+        {
+            let mut shown = window_shown.lock().unwrap();
+            if !*shown {
+                *shown = true;
+                unsafe {
+                    let _ = ShowWindow(h, SW_SHOW);
+                }
+                let mut proc_hwnd = processing_hwnd.lock().unwrap();
+                if let Some(ph) = proc_hwnd.take() {
+                    unsafe {
+                        let _ = PostMessageW(Some(ph.0), WM_CLOSE, WPARAM(0), LPARAM(0));
+                    }
+                }
+            }
+        }
+        {
+            let mut s = WINDOW_STATES.lock().unwrap();
+            if let Some(st) = s.get_mut(&(h.0 as isize)) {
+                st.is_refining = false;
+            }
+        }
+        update_window_text(h, &t);
+    }
+}
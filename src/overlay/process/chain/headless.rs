@@ -0,0 +1,131 @@
+// --- HEADLESS CHAIN RUN ---
+// Runs a preset's blocks without any window and returns the last block's text
+// together with the models that answered.
+
+use crate::config::{Config, Preset};
+use crate::overlay::result::{ChainCancelToken, RefineContext};
+
+use super::execution::{ExecuteBlockRequest, try_execute_block};
+use super::step::{block_prompt, resolve_block_model};
+
+/// What the preset would have been given by its hotkey.
+pub enum HeadlessInput {
+    Text(String),
+    /// PNG bytes, as a capture would hand them over.
+    Image(Vec<u8>),
+    /// WAV bytes, transcribed by the preset's audio block first.
+    Audio(Vec<u8>),
+}
+
+/// A headless run that reached its end.
+pub struct HeadlessRun {
+    /// The last block's text.
+    pub output: String,
+    /// The model that answered each block that called one, in order.
+    pub answered_by: Vec<String>,
+    /// Blocks a fallback model answered in place of the block's own.
+    pub fallbacks: Vec<String>,
+}
+
+/// Walks the chain the way `continue_chain` does, but follows only the first
+/// downstream block where the graph forks, and skips auto-copy, auto-speak
+/// and history. The first failing block ends the run with its error.
+pub fn run_preset_headless(
+    preset: &Preset,
+    config: &Config,
+    input: HeadlessInput,
+) -> Result<HeadlessRun, String> {
+    let (mut text, mut context, mut skip_execution) = match input {
+        HeadlessInput::Text(text) => (text, RefineContext::None, false),
+        HeadlessInput::Image(png) => (String::new(), RefineContext::Image(png), false),
+        HeadlessInput::Audio(wav) => {
+            // Block 0 already ran as the transcription, as in `show_audio_result`.
+            let transcript = crate::api::audio::execute_audio_processing_logic(preset, wav.clone())
+                .map_err(|error| format!("audio block: {error}"))?;
+            (transcript, RefineContext::Audio(wav), true)
+        }
+    };
+    let blocks = &preset.blocks;
+    let cancel_token = ChainCancelToken::new();
+    let mut block_idx = 0;
+    let mut answered_by = Vec::new();
+    let mut fallbacks = Vec::new();
+    // A graph with a cycle would otherwise never finish.
+    for _ in 0..blocks.len() {
+        let Some(block) = blocks.get(block_idx) else {
+            break;
+        };
+        let (model_id, provider, model_full_name) = resolve_block_model(block, config);
+        let final_prompt = block_prompt(block);
+        let calls_model = block.block_type != "input_adapter" && !skip_execution;
+        let (result, answered) = try_execute_block(ExecuteBlockRequest {
+            block,
+            block_idx,
+            blocks,
+            my_hwnd: None,
+            input_text: &text,
+            context: &context,
+            model_id: &model_id,
+            provider: &provider,
+            model_full_name: &model_full_name,
+            final_prompt: &final_prompt,
+            skip_execution,
+            config,
+            preset_id: &preset.id,
+            processing_hwnd_shared: None,
+            cancel_token: &cancel_token,
+        });
+        let result = result.map_err(|error| format!("block {block_idx} ({answered}): {error}"))?;
+        if calls_model {
+            if answered != model_full_name {
+                fallbacks.push(format!(
+                    "block {block_idx}: {answered} answered for {model_full_name}"
+                ));
+            }
+            answered_by.push(answered);
+        }
+
+        let is_input_adapter = block.block_type == "input_adapter";
+        text = result;
+        if text.trim().is_empty() && !is_input_adapter {
+            break;
+        }
+        if !is_input_adapter {
+            context = RefineContext::None;
+        }
+        skip_execution &= is_input_adapter;
+        let Some(next) = next_block(block_idx, blocks.len(), &preset.block_connections) else {
+            break;
+        };
+        block_idx = next;
+    }
+    Ok(HeadlessRun {
+        output: text,
+        answered_by,
+        fallbacks,
+    })
+}
+
+fn next_block(block_idx: usize, count: usize, connections: &[(usize, usize)]) -> Option<usize> {
+    if connections.is_empty() {
+        (block_idx + 1 < count).then_some(block_idx + 1)
+    } else {
+        connections
+            .iter()
+            .find(|(from, _)| *from == block_idx)
+            .map(|(_, to)| *to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::next_block;
+
+    #[test]
+    fn the_walk_follows_the_main_branch_of_a_graph() {
+        assert_eq!(next_block(0, 3, &[]), Some(1));
+        assert_eq!(next_block(2, 3, &[]), None);
+        assert_eq!(next_block(0, 3, &[(0, 2), (0, 1)]), Some(2));
+        assert_eq!(next_block(1, 3, &[(0, 2), (0, 1)]), None);
+    }
+}
@@ -2,10 +2,12 @@
 // Processing chain execution with graph-based block connections.
 
 mod execution;
+mod headless;
 mod post_process;
 mod step;
 mod templates;
 
+pub use headless::{HeadlessInput, HeadlessRun, run_preset_headless};
 pub use step::{ChainStepRequest, run_chain_step};
 
 use crate::config::{Config, Preset};
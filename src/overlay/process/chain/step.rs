@@ -79,45 +79,8 @@ pub fn run_chain_step(request: ChainStepRequest) {
     crate::overlay::result::latency::begin(&trace_id);
 
     // 1. Resolve Model & Prompt
-    //
-    // A pinned model can stop resolving: a catalog row retired by an update, a
-    // custom definition deleted, an Ollama model uninstalled, or a discovered one
-    // the feed no longer offers. Keep the preset unchanged and silently enter the
-    // same priority/fallback machinery used after a normal provider failure.
-    let block_model_id = block.model.clone();
-    let resolved = crate::model_config::get_model_by_id(&block_model_id)
-        .filter(|model| model.enabled)
-        .or_else(|| {
-            crate::retry_model_chain::resolve_unavailable_pinned_model(
-                &block.block_type,
-                &block_model_id,
-                &config,
-            )
-        });
-    if let Some(fallback) = resolved.as_ref().filter(|model| model.id != block_model_id) {
-        crate::log_info!(
-            "[Chain] Preset model {block_model_id:?} is unavailable; continuing with priority model {:?}",
-            fallback.id
-        );
-    }
-    let model_id = resolved
-        .as_ref()
-        .map_or_else(|| block_model_id.clone(), |model| model.id.clone());
-    let model_conf = resolved;
-    let provider = model_conf
-        .clone()
-        .map(|m| m.provider)
-        .unwrap_or_else(|| "groq".to_string());
-    let model_full_name = model_conf.map(|m| m.full_name).unwrap_or(model_id.clone());
-
-    let mut final_prompt = block.prompt.clone();
-    for (key, value) in &block.language_vars {
-        final_prompt = final_prompt.replace(&format!("{{{}}}", key), value);
-    }
-    if final_prompt.contains("{language1}") && !block.language_vars.contains_key("language1") {
-        final_prompt = final_prompt.replace("{language1}", &block.selected_language);
-    }
-    final_prompt = final_prompt.replace("{language}", &block.selected_language);
+    let (model_id, provider, model_full_name) = resolve_block_model(&block, &config);
+    let final_prompt = block_prompt(&block);
 
     // 2. Determine Visibility & Position
     let visible_count_before = blocks
@@ -455,3 +418,53 @@ fn create_block_window(request: CreateBlockWindowRequest<'_>) -> (Option<HWND>,
 
     (my_hwnd, processing_indicator_hwnd)
 }
+
+/// The model a block runs on, as `(id, provider, full name)`.
+///
+/// A pinned model can stop resolving: a catalog row retired by an update, a
+/// custom definition deleted, an Ollama model uninstalled, or a discovered one
+/// the feed no longer offers. Keep the preset unchanged and silently enter the
+/// same priority/fallback machinery used after a normal provider failure.
+pub(super) fn resolve_block_model(
+    block: &ProcessingBlock,
+    config: &Config,
+) -> (String, String, String) {
+    let block_model_id = block.model.clone();
+    let resolved = crate::model_config::get_model_by_id(&block_model_id)
+        .filter(|model| model.enabled)
+        .or_else(|| {
+            crate::retry_model_chain::resolve_unavailable_pinned_model(
+                &block.block_type,
+                &block_model_id,
+                config,
+            )
+        });
+    if let Some(fallback) = resolved.as_ref().filter(|model| model.id != block_model_id) {
+        crate::log_info!(
+            "[Chain] Preset model {block_model_id:?} is unavailable; continuing with priority model {:?}",
+            fallback.id
+        );
+    }
+    let model_id = resolved
+        .as_ref()
+        .map_or_else(|| block_model_id.clone(), |model| model.id.clone());
+    let model_conf = resolved;
+    let provider = model_conf
+        .clone()
+        .map(|m| m.provider)
+        .unwrap_or_else(|| "groq".to_string());
+    let model_full_name = model_conf.map(|m| m.full_name).unwrap_or(model_id.clone());
+    (model_id, provider, model_full_name)
+}
+
+/// The block's prompt with its language variables filled in.
+pub(super) fn block_prompt(block: &ProcessingBlock) -> String {
+    let mut final_prompt = block.prompt.clone();
+    for (key, value) in &block.language_vars {
+        final_prompt = final_prompt.replace(&format!("{{{}}}", key), value);
+    }
+    if final_prompt.contains("{language1}") && !block.language_vars.contains_key("language1") {
+        final_prompt = final_prompt.replace("{language1}", &block.selected_language);
+    }
+    final_prompt.replace("{language}", &block.selected_language)
+}
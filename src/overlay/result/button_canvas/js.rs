@@ -254,7 +254,8 @@ function generateButtonsHTML(hwnd, state, isVertical) {
     }
 
     if (!state.groupActions && state.modelLabel) {
-        buttons += `<div class="model-badge ${hideClass}" title="${escapeAttribute(state.modelLabel)}">${escapeText(state.modelLabel)}</div>`;
+        const cachedLabel = state.fromCache ? ` · ${escapeText(window.L10N.cached)}` : '';
+        buttons += `<div class="model-badge ${hideClass}" title="${escapeAttribute(state.modelLabel)}">${escapeText(state.modelLabel)}${cachedLabel}</div>`;
     }
 
     const opacityValue = state.opacityPercent || 100;
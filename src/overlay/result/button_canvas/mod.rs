@@ -26,6 +26,7 @@ pub(crate) fn document_script() -> String {
         "back": locale.overlay.overlay_back_tooltip,
         "forward": locale.overlay.overlay_forward_tooltip,
         "opacity": locale.overlay.overlay_opacity_tooltip,
+        "cached": locale.overlay.overlay_cached_badge,
         "cancel": locale.overlay.overlay_cancel_tooltip,
         "overlay_refine_placeholder": locale.overlay.overlay_refine_placeholder,
     })
@@ -138,6 +139,8 @@ mod tests {
         assert!(buttons.contains("!state.groupActions && state.modelLabel"));
         assert!(script.contains("function escapeText"));
         assert!(buttons.contains("escapeText(state.modelLabel)"));
+        assert!(buttons.contains("state.fromCache ?"));
+        assert!(buttons.contains("escapeText(window.L10N.cached)"));
 
         // Readable surface, but not a control: no hover and no pointer affordance.
         assert!(!buttons.contains("btn model-badge"));
@@ -48,6 +48,7 @@ pub fn trigger_refine_submit(hwnd: HWND, text: &str) {
             state.is_editing = false;
             state.is_refining = true;
             state.is_streaming_active = true;
+            state.from_cache = false;
         }
         drop(states);
         super::update_window_text(hwnd, "");
@@ -184,6 +184,7 @@ fn from_state(
         input_text: state.input_text.clone(),
         opacity_percent: state.opacity_percent,
         model_label: model_label(&state.model_id, &state.provider),
+        from_cache: state.from_cache,
         group_ids: connected_ids(id, states),
         onboarding_pulse_token: state.onboarding_pulse_token,
     }
@@ -261,6 +262,7 @@ mod tests {
             model_id: String::new(),
             provider: String::new(),
             streaming_enabled: false,
+            from_cache: false,
             preset_prompt: String::new(),
             input_text: String::new(),
             bg_color: 0,
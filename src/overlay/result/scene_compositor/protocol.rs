@@ -70,6 +70,8 @@ pub struct SceneControls {
     pub opacity_percent: u8,
     /// API model name that produced this result, shown beside the controls.
     pub model_label: String,
+    /// The result was served from the response cache.
+    pub from_cache: bool,
     pub group_ids: Vec<isize>,
     pub onboarding_pulse_token: u8,
 }
@@ -95,6 +97,7 @@ impl Default for SceneControls {
             input_text: String::new(),
             opacity_percent: 100,
             model_label: String::new(),
+            from_cache: false,
             group_ids: Vec::new(),
             onboarding_pulse_token: 0,
         }
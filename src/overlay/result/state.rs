@@ -143,6 +143,8 @@ pub struct WindowState {
     pub model_id: String,
     pub provider: String,
     pub streaming_enabled: bool,
+    // True when the text came from the response cache instead of the provider
+    pub from_cache: bool,
 
     // NEW: Preset Prompt for "Type" mode logic
     pub preset_prompt: String,
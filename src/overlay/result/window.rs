@@ -165,6 +165,7 @@ pub(crate) fn create_result_window_shell(params: ResultWindowParams) -> HWND {
                     model_id,
                     provider,
                     streaming_enabled,
+                    from_cache: false,
                     bg_color: custom_bg_color,
                     linked_windows: Vec::new(),
                     preset_prompt,
@@ -1,15 +1,17 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-pub fn delete_recording_file(path: &str) -> Result<(), String> {
+/// Directories the app writes recordings and their snapshots into.
+pub(super) fn managed_roots() -> Vec<PathBuf> {
     let local = crate::paths::app_local_data_dir();
-    delete_confined_file(
-        Path::new(path),
-        &[
-            local.join("recordings"),
-            local.join("composition-snapshots"),
-        ],
-    )
+    vec![
+        local.join("recordings"),
+        local.join("composition-snapshots"),
+    ]
+}
+
+pub fn delete_recording_file(path: &str) -> Result<(), String> {
+    delete_confined_file(Path::new(path), &managed_roots())
 }
 
 fn delete_confined_file(path: &Path, approved_roots: &[PathBuf]) -> Result<(), String> {
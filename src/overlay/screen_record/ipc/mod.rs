@@ -15,7 +15,9 @@ mod managed_files;
 pub mod media_server;
 mod narration;
 mod path_validation;
+mod project_archive;
 mod recording;
+mod recording_pause;
 pub(super) mod recording_state;
 mod s2s_narration;
 mod stage_export;
@@ -177,6 +179,9 @@ pub fn handle_ipc_command(
             result
         }
         "start_audio_download" => native_export::start_audio_download(args),
+        "export_timeline_interchange" => native_export::start_timeline_interchange_export(args),
+        "export_project_archive" => project_archive::handle_export_project_archive(&args),
+        "import_project_archive" => project_archive::handle_import_project_archive(&args),
         "get_export_capabilities" => Ok(native_export::get_export_capabilities()),
         "cancel_export" => {
             println!("[Cancel] IPC cancel_export received");
@@ -443,8 +448,10 @@ pub fn handle_ipc_command(
             super::audio_source_selection::show_audio_app_selector(is_dark, lang);
             Ok(serde_json::Value::Null)
         }
-        "start_recording" => recording::handle_start_recording(&args),
-        "stop_recording" => recording::handle_stop_recording(),
+        "start_recording" => recording_pause::handle_start_recording(&args),
+        "pause_recording" => recording_pause::handle_pause_recording(),
+        "resume_recording" => recording_pause::handle_resume_recording(),
+        "stop_recording" => recording_pause::handle_stop_recording(),
         "start_subtitle_generation" => subtitles::handle_start_subtitle_generation(&args),
         "get_subtitle_generation_capabilities" => {
             subtitles::handle_get_subtitle_generation_capabilities(&args)
@@ -461,6 +468,7 @@ pub fn handle_ipc_command(
             subtitles::handle_get_subtitle_translation_status(&args)
         }
         "cancel_subtitle_translation" => subtitles::handle_cancel_subtitle_translation(&args),
+        "propose_subtitle_chapters" => subtitles::handle_propose_subtitle_chapters(&args),
         "start_subtitle_narration" => narration::handle_start_subtitle_narration(&args),
         "get_subtitle_narration_status" => narration::handle_get_subtitle_narration_status(&args),
         "cancel_subtitle_narration" => narration::handle_cancel_subtitle_narration(&args),
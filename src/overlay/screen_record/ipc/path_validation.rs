@@ -1,4 +1,5 @@
 use std::fs;
+use std::path::{Path, PathBuf};
 
 pub(super) fn validate_file_name(value: &str) -> Result<String, String> {
     let value = value.trim();
@@ -67,6 +68,20 @@ pub(super) fn safe_suggested_file_name(
     }
 }
 
+/// `path` resolved, when it is a regular file strictly below one of `roots`.
+pub(super) fn confined_file(path: &Path, roots: &[PathBuf]) -> Option<PathBuf> {
+    let metadata = fs::symlink_metadata(path).ok()?;
+    if !metadata.is_file() || metadata_is_reparse(&metadata) {
+        return None;
+    }
+    let resolved = fs::canonicalize(path).ok()?;
+    roots
+        .iter()
+        .filter_map(|root| fs::canonicalize(root).ok())
+        .any(|root| resolved.starts_with(&root) && resolved != root)
+        .then_some(resolved)
+}
+
 #[cfg(windows)]
 pub(super) fn metadata_is_reparse(metadata: &fs::Metadata) -> bool {
     use std::os::windows::fs::MetadataExt;
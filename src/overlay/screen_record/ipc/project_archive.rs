@@ -0,0 +1,520 @@
+// Portable `.sgtproj` project archives. An archive is a zip holding the
+// frontend's project JSON (composition, cursor and keystroke data, subtitle
+// tracks), a manifest and every media file the project references from
+// app-managed recordings storage; a path pointing anywhere else is left out,
+// so a crafted project cannot pack arbitrary files from the disk. Media paths
+// in the stored JSON are replaced by `sgtproj://media/<name>` tokens so the
+// archive carries nothing machine-specific; import extracts the media into
+// managed recordings storage and maps the tokens back to the new paths.
+// Downloaded catalog backgrounds are referenced by relative URLs and fetched
+// again on demand, so they are not packed.
+
+mod proxy;
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::{Config, secrets};
+
+use super::managed_files::managed_roots;
+use super::media_server::recordings_dir;
+use super::path_validation::{confined_file, safe_suggested_file_name, validate_file_name};
+use super::subtitle_export::sanitize_dir_path;
+
+const ARCHIVE_FORMAT: &str = "sgtproj";
+const ARCHIVE_VERSION: u32 = 1;
+const ARCHIVE_EXTENSION: &str = "sgtproj";
+const MEDIA_TOKEN_PREFIX: &str = "sgtproj://media/";
+const MANIFEST_ENTRY: &str = "manifest.json";
+const PROJECT_ENTRY: &str = "project.json";
+const MEDIA_DIR: &str = "media/";
+const MAX_MANIFEST_BYTES: u64 = 4 * 1024 * 1024;
+// Long recordings carry dense cursor paths; keep well above what a real
+// project serializes to while still refusing a zip bomb.
+const MAX_PROJECT_BYTES: u64 = 512 * 1024 * 1024;
+const MAX_MEDIA_BYTES: u64 = 32 * 1024 * 1024 * 1024;
+const MAX_MEDIA_FILES: usize = 4_096;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveManifest {
+    format: String,
+    version: u32,
+    created_at: u64,
+    name: String,
+    media: Vec<ArchiveMedia>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ArchiveMedia {
+    file_name: String,
+    bytes: u64,
+    #[serde(default)]
+    proxy: bool,
+}
+
+/// A referenced media file and the archive name it is stored under.
+struct PackedMedia {
+    source: PathBuf,
+    file_name: String,
+    proxy: bool,
+}
+
+pub fn handle_export_project_archive(args: &Value) -> Result<Value, String> {
+    let config = crate::APP
+        .lock()
+        .map_err(|_| "App lock poisoned".to_string())?
+        .config
+        .clone();
+    export_project_archive(args, &config)
+}
+
+fn export_project_archive(args: &Value, config: &Config) -> Result<Value, String> {
+    let project = args
+        .get("project")
+        .filter(|value| value.is_object())
+        .ok_or("Missing project")?;
+    // A key pasted anywhere into the project must not leave with the archive.
+    let mut project = secrets::without_secrets(project, config)?;
+    let proxy_media = args["proxyMedia"].as_bool().unwrap_or(false);
+    let output_dir = sanitize_dir_path(
+        &args["outputDir"]
+            .as_str()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(super::native_export::get_default_export_dir),
+    )?;
+
+    let name = project["name"].as_str().unwrap_or_default().to_string();
+    let (mut media, missing) = collect_media(&mut project, &managed_roots());
+    let proxy_dir = crate::paths::app_temp_dir().join(format!("sgtproj-{}", now_millis()));
+    let result = (|| -> Result<Value, String> {
+        if proxy_media {
+            let renamed: HashMap<String, String> =
+                proxy::replace_videos_with_proxies(&mut media, &proxy_dir)?
+                    .into_iter()
+                    .map(|(old, new)| {
+                        (
+                            format!("{MEDIA_TOKEN_PREFIX}{old}"),
+                            format!("{MEDIA_TOKEN_PREFIX}{new}"),
+                        )
+                    })
+                    .collect();
+            for_each_media_path(&mut project, &mut |path| {
+                if let Some(token) = renamed.get(path.as_str()) {
+                    *path = token.clone();
+                }
+            });
+        }
+        let file_name = safe_suggested_file_name(&name, "project", ARCHIVE_EXTENSION);
+        let destination = super::subtitle_export::unique_destination(&output_dir, &file_name);
+        let bytes = write_archive(&destination, &name, &project, &media)?;
+        Ok(serde_json::json!({
+            "path": destination.to_string_lossy(),
+            "bytes": bytes,
+            "mediaCount": media.len(),
+            "missing": missing,
+        }))
+    })();
+    if proxy_dir.exists() {
+        let _ = fs::remove_dir_all(&proxy_dir);
+    }
+    result
+}
+
+pub fn handle_import_project_archive(args: &Value) -> Result<Value, String> {
+    let path = args["path"].as_str().ok_or("Missing path")?;
+    let path = Path::new(path.trim());
+    if !path
+        .extension()
+        .and_then(|value| value.to_str())
+        .is_some_and(|value| value.eq_ignore_ascii_case(ARCHIVE_EXTENSION))
+    {
+        return Err("Only .sgtproj project archives can be imported".to_string());
+    }
+    let media_dir = recordings_dir();
+    fs::create_dir_all(&media_dir).map_err(|error| format!("Create recordings dir: {error}"))?;
+    let (project, media_count) = extract_archive(path, &media_dir)?;
+    Ok(serde_json::json!({ "project": project, "mediaCount": media_count }))
+}
+
+/// Visits every string stored under a `...Path` key, which is how the project
+/// model names its media references (`rawVideoPath`, `rawAudioPath`, ...).
+fn for_each_media_path(value: &mut Value, visit: &mut dyn FnMut(&mut String)) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                match child {
+                    Value::String(path) if key.ends_with("Path") && !path.is_empty() => visit(path),
+                    _ => for_each_media_path(child, visit),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                for_each_media_path(item, visit);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replaces every media path below `roots` with an archive token. Paths that
+/// are not regular files there stay untouched and are reported as missing.
+fn collect_media(project: &mut Value, roots: &[PathBuf]) -> (Vec<PackedMedia>, Vec<String>) {
+    let mut media: Vec<PackedMedia> = Vec::new();
+    let mut by_path: HashMap<String, String> = HashMap::new();
+    let mut missing = Vec::new();
+    for_each_media_path(project, &mut |path| {
+        if let Some(token) = by_path.get(path.as_str()) {
+            *path = token.clone();
+            return;
+        }
+        let Some(source) = confined_file(Path::new(path.as_str()), roots) else {
+            if !missing.contains(path) {
+                missing.push(path.clone());
+            }
+            return;
+        };
+        let leaf = path.rsplit(['/', '\\']).next().unwrap_or_default();
+        let (stem, extension) = leaf.rsplit_once('.').unwrap_or((leaf, "bin"));
+        let file_name = safe_suggested_file_name(
+            &format!("{:03}-{stem}", media.len() + 1),
+            &format!("{:03}-media", media.len() + 1),
+            &extension.to_ascii_lowercase(),
+        );
+        let token = format!("{MEDIA_TOKEN_PREFIX}{file_name}");
+        media.push(PackedMedia {
+            source,
+            file_name,
+            proxy: false,
+        });
+        by_path.insert(path.clone(), token.clone());
+        *path = token;
+    });
+    (media, missing)
+}
+
+fn write_archive(
+    destination: &Path,
+    name: &str,
+    project: &Value,
+    media: &[PackedMedia],
+) -> Result<u64, String> {
+    let partial = destination.with_extension("sgtproj.partial");
+    let result = (|| -> Result<(), String> {
+        let file = File::create(&partial)
+            .map_err(|error| format!("Failed to create project archive: {error}"))?;
+        let mut zip = zip::ZipWriter::new(file);
+        let stored = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored)
+            .large_file(true);
+        let deflated = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .large_file(true);
+        let zip_error = |error: zip::result::ZipError| format!("Write project archive: {error}");
+        let io_error = |error: io::Error| format!("Write project archive: {error}");
+
+        let mut entries = Vec::with_capacity(media.len());
+        for item in media {
+            let mut source = File::open(&item.source)
+                .map_err(|error| format!("Failed to read {}: {error}", item.source.display()))?;
+            zip.start_file(format!("{MEDIA_DIR}{}", item.file_name), stored)
+                .map_err(zip_error)?;
+            let bytes = io::copy(&mut source, &mut zip).map_err(io_error)?;
+            entries.push(ArchiveMedia {
+                file_name: item.file_name.clone(),
+                bytes,
+                proxy: item.proxy,
+            });
+        }
+        let manifest = ArchiveManifest {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: ARCHIVE_VERSION,
+            created_at: now_millis(),
+            name: name.to_string(),
+            media: entries,
+        };
+        for (entry, value) in [
+            (MANIFEST_ENTRY, serde_json::to_vec_pretty(&manifest)),
+            (PROJECT_ENTRY, serde_json::to_vec(project)),
+        ] {
+            let bytes = value.map_err(|error| format!("Serialize {entry}: {error}"))?;
+            zip.start_file(entry, deflated).map_err(zip_error)?;
+            zip.write_all(&bytes).map_err(io_error)?;
+        }
+        let file = zip.finish().map_err(zip_error)?;
+        file.sync_all().map_err(io_error)
+    })();
+    if let Err(error) = result.and_then(|_| {
+        fs::rename(&partial, destination)
+            .map_err(|error| format!("Failed to publish project archive: {error}"))
+    }) {
+        let _ = fs::remove_file(&partial);
+        return Err(error);
+    }
+    Ok(fs::metadata(destination).map(|m| m.len()).unwrap_or(0))
+}
+
+/// Extracts an archive's media into `media_dir` and returns the project JSON
+/// with its tokens mapped to the extracted files. Nothing is left behind on
+/// failure.
+fn extract_archive(archive_path: &Path, media_dir: &Path) -> Result<(Value, usize), String> {
+    let file = File::open(archive_path)
+        .map_err(|error| format!("Failed to open project archive: {error}"))?;
+    let mut zip = zip::ZipArchive::new(file)
+        .map_err(|error| format!("Project archive is not a valid zip: {error}"))?;
+    let manifest: ArchiveManifest = serde_json::from_slice(&read_small_entry(
+        &mut zip,
+        MANIFEST_ENTRY,
+        MAX_MANIFEST_BYTES,
+    )?)
+    .map_err(|error| format!("Invalid project archive manifest: {error}"))?;
+    if manifest.format != ARCHIVE_FORMAT || manifest.version > ARCHIVE_VERSION {
+        return Err(format!(
+            "Unsupported project archive (format {}, version {})",
+            manifest.format, manifest.version
+        ));
+    }
+    if manifest.media.len() > MAX_MEDIA_FILES || zip.len() != manifest.media.len() + 2 {
+        return Err("Project archive contents do not match its manifest".to_string());
+    }
+    let mut project: Value = serde_json::from_slice(&read_small_entry(
+        &mut zip,
+        PROJECT_ENTRY,
+        MAX_PROJECT_BYTES,
+    )?)
+    .map_err(|error| format!("Invalid project data: {error}"))?;
+    if !project.is_object() {
+        return Err("Project archive does not contain a project".to_string());
+    }
+
+    let import_id = now_millis();
+    let mut extracted: Vec<PathBuf> = Vec::new();
+    let mut tokens: HashMap<String, String> = HashMap::new();
+    let result = (|| -> Result<(), String> {
+        for item in &manifest.media {
+            let file_name = validate_file_name(&item.file_name)?;
+            if item.bytes > MAX_MEDIA_BYTES {
+                return Err(format!("{file_name} exceeds the media size limit"));
+            }
+            let mut entry = zip
+                .by_name(&format!("{MEDIA_DIR}{file_name}"))
+                .map_err(|_| format!("Project archive is missing {file_name}"))?;
+            let is_link = entry
+                .unix_mode()
+                .is_some_and(|mode| mode & 0o170000 != 0 && mode & 0o170000 != 0o100000);
+            if entry.is_dir() || is_link || entry.size() != item.bytes {
+                return Err(format!(
+                    "Project archive entry {file_name} is not a media file"
+                ));
+            }
+            let target = media_dir.join(format!("project-{import_id}-{file_name}"));
+            let mut output = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&target)
+                .map_err(|error| format!("Failed to create {}: {error}", target.display()))?;
+            extracted.push(target.clone());
+            let copied = io::copy(&mut (&mut entry).take(item.bytes + 1), &mut output)
+                .map_err(|error| format!("Failed to extract {file_name}: {error}"))?;
+            if copied != item.bytes {
+                return Err(format!("Project archive entry {file_name} is truncated"));
+            }
+            output
+                .sync_all()
+                .map_err(|error| format!("Failed to extract {file_name}: {error}"))?;
+            tokens.insert(
+                format!("{MEDIA_TOKEN_PREFIX}{file_name}"),
+                target.to_string_lossy().to_string(),
+            );
+        }
+
+        let mut unknown = None;
+        for_each_media_path(&mut project, &mut |path| {
+            if !path.starts_with(MEDIA_TOKEN_PREFIX) {
+                return;
+            }
+            match tokens.get(path.as_str()) {
+                Some(target) => *path = target.clone(),
+                None => unknown = Some(path.clone()),
+            }
+        });
+        match unknown {
+            Some(token) => Err(format!(
+                "Project references media missing from the archive: {token}"
+            )),
+            None => Ok(()),
+        }
+    })();
+    if let Err(error) = result {
+        for path in extracted {
+            let _ = fs::remove_file(path);
+        }
+        return Err(error);
+    }
+    Ok((project, manifest.media.len()))
+}
+
+fn read_small_entry(
+    zip: &mut zip::ZipArchive<File>,
+    name: &str,
+    limit: u64,
+) -> Result<Vec<u8>, String> {
+    let entry = zip
+        .by_name(name)
+        .map_err(|_| format!("Project archive is missing {name}"))?;
+    if entry.size() > limit {
+        return Err(format!("{name} in the project archive is too large"));
+    }
+    let mut bytes = Vec::with_capacity(entry.size() as usize);
+    entry
+        .take(limit + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|error| format!("Failed to read {name}: {error}"))?;
+    if bytes.len() as u64 > limit {
+        return Err(format!("{name} in the project archive is too large"));
+    }
+    Ok(bytes)
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(label: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("sgt-sgtproj-{label}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn archives_round_trip_media_through_managed_storage() {
+        let root = fixture("round-trip");
+        let recordings = root.join("recordings");
+        fs::create_dir_all(&recordings).unwrap();
+        let video = recordings.join("screen take.mp4");
+        let audio = recordings.join("music.wav");
+        let outside = root.join("notes.txt");
+        fs::write(&video, b"video-bytes").unwrap();
+        fs::write(&audio, b"audio-bytes").unwrap();
+        fs::write(&outside, b"not media").unwrap();
+        let video_path = video.to_string_lossy().to_string();
+        let mut project = serde_json::json!({
+            "id": "p1",
+            "name": "Demo: take 1",
+            "rawVideoPath": video_path,
+            "mousePositions": [{ "x": 1.0, "y": 2.0, "timestamp": 0.0 }],
+            "composition": {
+                "clips": [{ "id": "root", "rawVideoPath": video_path, "rawMicAudioPath": "" }],
+                "audioSegments": [{ "rawAudioPath": audio.to_string_lossy() }],
+                "narrationSegments": [
+                    { "rawAudioPath": recordings.join("gone.wav").to_string_lossy() },
+                    { "rawAudioPath": outside.to_string_lossy() }
+                ]
+            }
+        });
+
+        let (media, missing) = collect_media(&mut project, std::slice::from_ref(&recordings));
+        assert_eq!(media.len(), 2);
+        assert_eq!(missing.len(), 2, "outside storage counts as missing");
+        let token = project["rawVideoPath"].as_str().unwrap();
+        assert!(token.starts_with(MEDIA_TOKEN_PREFIX) && token.ends_with("-screen take.mp4"));
+        assert_eq!(
+            project["composition"]["clips"][0]["rawVideoPath"],
+            project["rawVideoPath"]
+        );
+
+        let archive = root.join("Demo.sgtproj");
+        write_archive(&archive, "Demo: take 1", &project, &media).unwrap();
+        let imported_dir = root.join("imported");
+        fs::create_dir_all(&imported_dir).unwrap();
+        let (restored, count) = extract_archive(&archive, &imported_dir).unwrap();
+        assert_eq!(count, 2);
+        let restored_video = restored["rawVideoPath"].as_str().unwrap();
+        assert!(Path::new(restored_video).starts_with(&imported_dir));
+        assert_eq!(fs::read(restored_video).unwrap(), b"video-bytes");
+        let restored_audio = restored["composition"]["audioSegments"][0]["rawAudioPath"]
+            .as_str()
+            .unwrap();
+        assert_eq!(fs::read(restored_audio).unwrap(), b"audio-bytes");
+        assert_eq!(restored["mousePositions"], project["mousePositions"]);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn exported_archives_never_carry_an_api_key() {
+        let root = fixture("secrets");
+        let key = "gsk_project_archive_key";
+        let config = Config {
+            api_key: key.to_string(),
+            ..Config::default()
+        };
+        let args = serde_json::json!({
+            "outputDir": root.to_string_lossy(),
+            "project": {
+                "name": "Keys",
+                "subtitleSettings": { "provider": "groq", "apiKey": key },
+                "notes": format!("pasted {key} by mistake"),
+            }
+        });
+
+        let exported = export_project_archive(&args, &config).unwrap();
+        let mut archive =
+            zip::ZipArchive::new(File::open(exported["path"].as_str().unwrap()).unwrap()).unwrap();
+        for index in 0..archive.len() {
+            let mut contents = String::new();
+            archive
+                .by_index(index)
+                .unwrap()
+                .read_to_string(&mut contents)
+                .unwrap();
+            assert!(!contents.contains(key));
+        }
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn imports_reject_entries_outside_the_manifest() {
+        let root = fixture("reject");
+        let archive = root.join("bad.sgtproj");
+        let mut zip = zip::ZipWriter::new(File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        let manifest = serde_json::json!({
+            "format": "sgtproj",
+            "version": 1,
+            "createdAt": 0,
+            "name": "bad",
+            "media": [{ "fileName": "../escape.mp4", "bytes": 1 }]
+        });
+        zip.start_file(MANIFEST_ENTRY, options).unwrap();
+        zip.write_all(manifest.to_string().as_bytes()).unwrap();
+        zip.start_file(PROJECT_ENTRY, options).unwrap();
+        zip.write_all(b"{}").unwrap();
+        zip.start_file("media/../escape.mp4", options).unwrap();
+        zip.write_all(b"x").unwrap();
+        zip.finish().unwrap();
+
+        let target = root.join("imported");
+        fs::create_dir_all(&target).unwrap();
+        assert!(extract_archive(&archive, &target).is_err());
+        assert_eq!(fs::read_dir(&target).unwrap().count(), 0);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
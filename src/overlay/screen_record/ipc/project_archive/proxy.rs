@@ -0,0 +1,123 @@
+// Optional proxy media for project archives. Recordings are re-encoded to a
+// smaller H.264 file at their original resolution and frame timing, so cursor
+// coordinates, zoom regions and trim points in the project stay valid.
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use super::PackedMedia;
+
+const PROXY_CRF: &str = "30";
+const VIDEO_EXTENSIONS: [&str; 4] = ["mp4", "mov", "mkv", "webm"];
+
+/// Swaps every packed video for a proxy written into `work_dir` and returns
+/// the archive names that changed as `(old, new)`. A proxy that does not come
+/// out smaller than its source is discarded.
+pub(super) fn replace_videos_with_proxies(
+    media: &mut [PackedMedia],
+    work_dir: &Path,
+) -> Result<Vec<(String, String)>, String> {
+    let videos: Vec<usize> = media
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| {
+            item.source
+                .extension()
+                .and_then(|value| value.to_str())
+                .is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        })
+        .map(|(index, _)| index)
+        .collect();
+    let mut renamed = Vec::new();
+    if videos.is_empty() {
+        return Ok(renamed);
+    }
+    fs::create_dir_all(work_dir)
+        .map_err(|error| format!("Failed to create proxy directory: {error}"))?;
+
+    let download_message = archive_ffmpeg_download_message();
+    #[cfg(not(feature = "recorder-worker"))]
+    let ffmpeg_component = crate::gui::settings_ui::download_manager::ffmpeg_dependency::acquire_ffmpeg_with_badge_message(
+        &download_message,
+    )?;
+    #[cfg(not(feature = "recorder-worker"))]
+    let ffmpeg = ffmpeg_component.executable();
+    #[cfg(feature = "recorder-worker")]
+    let ffmpeg = crate::gui::settings_ui::download_manager::ffmpeg_dependency::ensure_ffmpeg_with_badge_message(
+        &download_message,
+    )?;
+
+    for index in videos {
+        let item = &mut media[index];
+        // Proxies are always MP4 regardless of the source container.
+        let proxy_name = match item.file_name.rsplit_once('.') {
+            Some((stem, _)) => format!("{stem}.mp4"),
+            None => format!("{}.mp4", item.file_name),
+        };
+        let proxy_path = work_dir.join(&proxy_name);
+        println!(
+            "[ProjectArchive] Proxy {} → {}",
+            item.source.display(),
+            proxy_path.display()
+        );
+        let output = Command::new(&ffmpeg)
+            .args([
+                "-hide_banner",
+                "-loglevel",
+                "error",
+                "-y",
+                "-i",
+                item.source.to_str().unwrap_or(""),
+                "-map",
+                "0",
+                "-c:v",
+                "libx264",
+                "-preset",
+                "veryfast",
+                "-crf",
+                PROXY_CRF,
+                "-pix_fmt",
+                "yuv420p",
+                "-fps_mode",
+                "passthrough",
+                "-c:a",
+                "aac",
+                "-b:a",
+                "128k",
+                "-movflags",
+                "+faststart",
+                proxy_path.to_str().unwrap_or(""),
+            ])
+            .output()
+            .map_err(|error| format!("Failed to launch FFmpeg proxy encode: {error}"))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("FFmpeg proxy encode failed: {stderr}"));
+        }
+
+        let size = |path: &Path| fs::metadata(path).map(|m| m.len()).unwrap_or(u64::MAX);
+        if size(&proxy_path) < size(&item.source) {
+            item.source = proxy_path;
+            if proxy_name != item.file_name {
+                let original = std::mem::replace(&mut item.file_name, proxy_name.clone());
+                renamed.push((original, proxy_name));
+            }
+            item.proxy = true;
+        } else {
+            let _ = fs::remove_file(&proxy_path);
+        }
+    }
+    Ok(renamed)
+}
+
+fn archive_ffmpeg_download_message() -> String {
+    let ui_language = crate::APP
+        .lock()
+        .map(|app| app.config.ui_language.clone())
+        .unwrap_or_else(|_| "en".to_string());
+    crate::gui::locale::LocaleText::get(&ui_language)
+        .tts_playground
+        .screen_record_archive_ffmpeg_downloading
+        .to_string()
+}
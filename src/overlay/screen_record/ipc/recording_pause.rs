@@ -0,0 +1,328 @@
+// --- PAUSE / RESUME ---
+// A paused recording is a series of ordinary recording segments. Pausing
+// stops the active segment through the normal stop path (screen, device
+// audio, mic, webcam and input capture all finalize together); resuming
+// starts a fresh segment with the original arguments. Stopping merges the
+// segments into one continuous recording with rebased cursor/input
+// timestamps and the pause points kept as optional markers. Segments are laid
+// out by the length of their encoded video rather than wall-clock time, which
+// stop latency and the encoder flush would skew at every pause.
+
+mod merge;
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
+
+use merge::RecordedSegment;
+
+use super::super::mf_decode::{probe_media_duration_seconds, probe_video_dimensions};
+use super::super::native_export::native_concat::{ConcatClip, concat_clips_to_mp4};
+use super::super::native_export::native_stitch::{StitchClip, StitchConfig, stitch_clips_to_mp4};
+use super::super::{MEDIA_SERVER_TOKEN, SERVER_PORT};
+use super::media_server::start_global_media_server;
+use super::recording;
+
+struct PauseSession {
+    start_args: serde_json::Value,
+    segments: Vec<RecordedSegment>,
+    segment_started: Instant,
+    paused_at: Option<Instant>,
+}
+
+impl PauseSession {
+    fn recorded_sec(&self) -> f64 {
+        let finished: f64 = self.segments.iter().map(|s| s.duration_sec).sum();
+        match self.paused_at {
+            Some(_) => finished,
+            None => finished + self.segment_started.elapsed().as_secs_f64(),
+        }
+    }
+}
+
+/// Held across every pause/resume step and the stop of the last segment so
+/// the IPC threads that serve those commands cannot interleave. Joining the
+/// segments happens after the session is taken out, without the lock.
+static SESSION: Mutex<Option<PauseSession>> = Mutex::new(None);
+
+fn session() -> Result<MutexGuard<'static, Option<PauseSession>>, String> {
+    SESSION
+        .lock()
+        .map_err(|_| "Recording pause lock poisoned".to_string())
+}
+
+/// Stops the active segment and times it by its encoded video, falling back
+/// to wall-clock time when the file cannot be probed.
+fn stop_segment(session: &PauseSession) -> Result<RecordedSegment, String> {
+    let wall_clock_sec = session.segment_started.elapsed().as_secs_f64();
+    let result = recording::handle_stop_recording()?;
+    let duration_sec = match result["videoFilePath"]
+        .as_str()
+        .map(probe_media_duration_seconds)
+    {
+        Some(Ok(duration_sec)) => duration_sec,
+        Some(Err(error)) => {
+            eprintln!("[CaptureBackend][Pause] segment duration probe failed: {error}");
+            wall_clock_sec
+        }
+        None => wall_clock_sec,
+    };
+    Ok(RecordedSegment {
+        result,
+        duration_sec,
+        paused_after_sec: 0.0,
+    })
+}
+
+pub(super) fn handle_start_recording(
+    args: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let mut session = session()?;
+    if session.is_some() {
+        return Err("A paused recording is still open; resume or stop it first".to_string());
+    }
+    let result = recording::handle_start_recording(args)?;
+    if !super::super::compatibility_capture::is_active() {
+        *session = Some(PauseSession {
+            start_args: args.clone(),
+            segments: Vec::new(),
+            segment_started: Instant::now(),
+            paused_at: None,
+        });
+    }
+    Ok(result)
+}
+
+pub(super) fn handle_pause_recording() -> Result<serde_json::Value, String> {
+    let mut guard = session()?;
+    let Some(session) = guard.as_mut() else {
+        return Err("No recording to pause".to_string());
+    };
+    if session.paused_at.is_some() {
+        return Err("Recording is already paused".to_string());
+    }
+    let segment = stop_segment(session)?;
+    session.segments.push(segment);
+    session.paused_at = Some(Instant::now());
+    Ok(serde_json::json!({
+        "paused": true,
+        "segmentCount": session.segments.len(),
+        "recordedSec": session.recorded_sec(),
+    }))
+}
+
+pub(super) fn handle_resume_recording() -> Result<serde_json::Value, String> {
+    let mut guard = session()?;
+    let Some(session) = guard.as_mut() else {
+        return Err("No recording to resume".to_string());
+    };
+    let Some(paused_at) = session.paused_at else {
+        return Err("Recording is not paused".to_string());
+    };
+    let result = recording::handle_start_recording(&session.start_args)?;
+    if let Some(last) = session.segments.last_mut() {
+        last.paused_after_sec = paused_at.elapsed().as_secs_f64();
+    }
+    session.segment_started = Instant::now();
+    session.paused_at = None;
+    Ok(result)
+}
+
+pub(super) fn handle_stop_recording() -> Result<serde_json::Value, String> {
+    let mut segments = {
+        // Taken out first so a failed stop still closes the session instead
+        // of blocking every later start.
+        let mut guard = session()?;
+        let Some(mut session) = guard.take() else {
+            return recording::handle_stop_recording();
+        };
+        if session.paused_at.is_none() {
+            match stop_segment(&session) {
+                Ok(segment) => session.segments.push(segment),
+                Err(error) if session.segments.is_empty() => return Err(error),
+                Err(error) => {
+                    eprintln!("[CaptureBackend][Pause] last segment stop failed: {error}");
+                }
+            }
+        }
+        session.segments
+    };
+    if segments.len() <= 1 {
+        return segments
+            .pop()
+            .map(|segment| segment.result)
+            .ok_or_else(|| "Recording produced no segments".to_string());
+    }
+    match merge_segments(&segments) {
+        Ok(result) => {
+            for segment in &segments {
+                remove_segment_files(segment);
+            }
+            Ok(result)
+        }
+        Err(error) => {
+            // Keep every segment on disk and hand the editor the first one so
+            // nothing recorded is lost.
+            eprintln!("[CaptureBackend][Pause] merge failed: {error}");
+            let mut result = segments[0].result.clone();
+            result["additionalSegments"] = segments[1..]
+                .iter()
+                .map(|segment| segment.result["videoFilePath"].clone())
+                .collect();
+            result["warnings"] =
+                serde_json::json!([format!("Paused segments could not be joined: {error}")]);
+            Ok(result)
+        }
+    }
+}
+
+fn merge_segments(segments: &[RecordedSegment]) -> Result<serde_json::Value, String> {
+    let started_at = Instant::now();
+    let starts = merge::segment_starts(segments);
+    let videos: Vec<PathBuf> = segments
+        .iter()
+        .map(|segment| {
+            segment
+                .path("videoFilePath")
+                .ok_or_else(|| "A recording segment has no video".to_string())
+        })
+        .collect::<Result<_, _>>()?;
+    let stem = videos[0]
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or_else(|| "Invalid recording file name".to_string())?;
+    let merged_stem = format!("{stem}_joined");
+    let directory = videos[0].parent().unwrap_or(Path::new("."));
+    let video_path = directory.join(format!("{merged_stem}.mp4"));
+    let mic_path = directory.join(format!("{merged_stem}_mic.wav"));
+    let webcam_path = directory.join(format!("{merged_stem}_webcam.mp4"));
+
+    let framerate = segments[0].result["capturedFps"]
+        .as_u64()
+        .and_then(|fps| u32::try_from(fps).ok())
+        .filter(|fps| *fps > 0)
+        .unwrap_or(60);
+    let video_clips: Vec<(PathBuf, f64)> = videos
+        .iter()
+        .zip(segments)
+        .map(|(path, segment)| (path.clone(), segment.duration_sec))
+        .collect();
+    join(&video_clips, &video_path, framerate)?;
+
+    let mic_offset = merge::splice_mic_audio(segments, &starts, &mic_path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&video_path);
+        let _ = std::fs::remove_file(&mic_path);
+    })?;
+    let webcam = match merge::webcam_layout(segments, &starts) {
+        Some((clips, offset)) => match stitch(&clips, &webcam_path, framerate) {
+            Ok(()) => Some(offset),
+            Err(error) => {
+                // Screen and audio are intact; losing the webcam track is
+                // better than failing the whole merge.
+                eprintln!("[CaptureBackend][Pause] webcam join failed: {error}");
+                let _ = std::fs::remove_file(&webcam_path);
+                None
+            }
+        },
+        None => None,
+    };
+
+    eprintln!(
+        "[CaptureBackend][Pause] joined segments={} elapsed_ms={} path={}",
+        segments.len(),
+        started_at.elapsed().as_millis(),
+        video_path.display()
+    );
+    let video = video_path.to_string_lossy().to_string();
+    let mic = mic_offset.map(|_| mic_path.to_string_lossy().to_string());
+    let webcam_video = webcam.map(|_| webcam_path.to_string_lossy().to_string());
+    Ok(serde_json::json!({
+        "videoUrl": media_url(&video),
+        "deviceAudioUrl": media_url(&video),
+        "micAudioUrl": mic.as_deref().map(media_url).unwrap_or_default(),
+        "webcamVideoUrl": webcam_video.as_deref().map(media_url).unwrap_or_default(),
+        "mouseData": merge::rebase_events(segments, &starts, "mouseData"),
+        "deviceAudioPath": video,
+        "micAudioPath": mic.unwrap_or_default(),
+        "webcamVideoPath": webcam_video.unwrap_or_default(),
+        "micAudioOffsetSec": mic_offset.unwrap_or(0.0),
+        "webcamVideoOffsetSec": webcam.unwrap_or(0.0),
+        "videoFilePath": video,
+        "inputEvents": merge::rebase_events(segments, &starts, "inputEvents"),
+        "capturedFps": framerate,
+        "pauseMarkers": merge::pause_markers(segments, &starts),
+    }))
+}
+
+/// Joins the screen segments by copying their encoded samples, which is
+/// lossless and fast because every segment came from one encoder config.
+/// Re-encodes only when that fails, e.g. after a capture size change.
+fn join(clips: &[(PathBuf, f64)], output: &Path, framerate: u32) -> Result<(), String> {
+    let concat_clips: Vec<ConcatClip<'_>> = clips
+        .iter()
+        .map(|(path, duration_sec)| ConcatClip {
+            path,
+            duration_sec: *duration_sec,
+        })
+        .collect();
+    match concat_clips_to_mp4(&concat_clips, output) {
+        Ok(()) => Ok(()),
+        Err(error) => {
+            eprintln!("[CaptureBackend][Pause] stream-copy join failed; re-encoding: {error}");
+            let _ = std::fs::remove_file(output);
+            stitch(clips, output, framerate)
+        }
+    }
+}
+
+fn stitch(clips: &[(PathBuf, f64)], output: &Path, framerate: u32) -> Result<(), String> {
+    let (width, height) = probe_video_dimensions(&clips[0].0.to_string_lossy())?;
+    for (path, _) in &clips[1..] {
+        if probe_video_dimensions(&path.to_string_lossy())? != (width, height) {
+            return Err("Capture size changed between paused segments".to_string());
+        }
+    }
+    let stitch_clips: Vec<StitchClip<'_>> = clips
+        .iter()
+        .map(|(path, duration_sec)| StitchClip {
+            path,
+            trim_start_sec: 0.0,
+            duration_sec: *duration_sec,
+        })
+        .collect();
+    stitch_clips_to_mp4(
+        &stitch_clips,
+        output,
+        &StitchConfig {
+            width,
+            height,
+            framerate,
+            bitrate_kbps: 0,
+        },
+    )
+    .inspect_err(|_| {
+        let _ = std::fs::remove_file(output);
+    })
+}
+
+fn remove_segment_files(segment: &RecordedSegment) {
+    for key in ["videoFilePath", "micAudioPath", "webcamVideoPath"] {
+        if let Some(path) = segment.path(key) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn media_url(path: &str) -> String {
+    let mut port = SERVER_PORT.load(std::sync::atomic::Ordering::SeqCst);
+    if port == 0 {
+        port = start_global_media_server().unwrap_or(0);
+    }
+    let token = MEDIA_SERVER_TOKEN.get().cloned().unwrap_or_default();
+    format!(
+        "http://localhost:{}/?path={}&token={}",
+        port,
+        urlencoding::encode(path),
+        urlencoding::encode(&token)
+    )
+}
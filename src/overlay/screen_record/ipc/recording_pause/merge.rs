@@ -0,0 +1,276 @@
+// Pure merge steps for paused recordings: laying segments out on one
+// continuous timeline, rebasing cursor/input timestamps, and splicing the
+// per-segment microphone WAVs at their recorded offsets.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+/// One finished recording segment as returned by `handle_stop_recording`,
+/// plus how long it ran and how long the user paused after it.
+pub(super) struct RecordedSegment {
+    pub(super) result: Value,
+    pub(super) duration_sec: f64,
+    pub(super) paused_after_sec: f64,
+}
+
+impl RecordedSegment {
+    pub(super) fn path(&self, key: &str) -> Option<PathBuf> {
+        self.result[key]
+            .as_str()
+            .filter(|path| !path.is_empty())
+            .map(PathBuf::from)
+            .filter(|path| {
+                std::fs::metadata(path)
+                    .map(|metadata| metadata.len() > 0)
+                    .unwrap_or(false)
+            })
+    }
+
+    pub(super) fn offset_sec(&self, key: &str) -> f64 {
+        self.result[key].as_f64().unwrap_or(0.0).max(0.0)
+    }
+}
+
+/// Start of every segment on the merged timeline.
+pub(super) fn segment_starts(segments: &[RecordedSegment]) -> Vec<f64> {
+    let mut start = 0.0;
+    segments
+        .iter()
+        .map(|segment| {
+            let current = start;
+            start += segment.duration_sec;
+            current
+        })
+        .collect()
+}
+
+/// Concatenates one per-segment event array (`mouseData`, `inputEvents`),
+/// shifting each entry's `timestamp` by its segment start.
+pub(super) fn rebase_events(segments: &[RecordedSegment], starts: &[f64], key: &str) -> Vec<Value> {
+    let mut merged = Vec::new();
+    for (segment, &start) in segments.iter().zip(starts) {
+        let Some(events) = segment.result[key].as_array() else {
+            continue;
+        };
+        for event in events {
+            let mut event = event.clone();
+            if let Some(timestamp) = event["timestamp"].as_f64() {
+                event["timestamp"] = serde_json::json!(timestamp + start);
+            }
+            merged.push(event);
+        }
+    }
+    merged
+}
+
+/// Pause points on the merged timeline, for the editor to offer as markers.
+pub(super) fn pause_markers(segments: &[RecordedSegment], starts: &[f64]) -> Vec<Value> {
+    segments
+        .iter()
+        .zip(starts.iter().skip(1))
+        .map(|(segment, &time)| {
+            serde_json::json!({
+                "time": time,
+                "pausedSec": segment.paused_after_sec,
+            })
+        })
+        .collect()
+}
+
+/// Writes every segment's microphone WAV into `output` so each lands at its
+/// own segment start plus recorded offset, padding gaps with silence and
+/// cutting anything that runs past its segment. Returns the merged file's
+/// offset on the timeline, or `None` when no segment recorded the mic.
+pub(super) fn splice_mic_audio(
+    segments: &[RecordedSegment],
+    starts: &[f64],
+    output: &Path,
+) -> Result<Option<f64>, String> {
+    let parts: Vec<(usize, PathBuf)> = segments
+        .iter()
+        .enumerate()
+        .filter_map(|(index, segment)| Some((index, segment.path("micAudioPath")?)))
+        .collect();
+    let Some((first_index, _)) = parts.first() else {
+        return Ok(None);
+    };
+    let origin = starts[*first_index] + segments[*first_index].offset_sec("micAudioOffsetSec");
+
+    let mut writer = None;
+    let mut written_frames = 0u64;
+    for (index, path) in &parts {
+        let mut reader =
+            hound::WavReader::open(path).map_err(|e| format!("Open microphone WAV: {e}"))?;
+        let spec = reader.spec();
+        if spec.sample_format != hound::SampleFormat::Int || spec.bits_per_sample != 16 {
+            return Err("Microphone WAV is not 16-bit PCM".to_string());
+        }
+        let writer = match &mut writer {
+            Some((writer, existing)) if *existing == spec => writer,
+            Some(_) => return Err("Microphone format changed between segments".to_string()),
+            None => {
+                let created = hound::WavWriter::create(output, spec)
+                    .map_err(|e| format!("Create merged microphone WAV: {e}"))?;
+                &mut writer.insert((created, spec)).0
+            }
+        };
+        let rate = f64::from(spec.sample_rate);
+        let channels = u64::from(spec.channels);
+        let placed_at = starts[*index] + segments[*index].offset_sec("micAudioOffsetSec");
+        let segment_end = starts[*index] + segments[*index].duration_sec;
+        let start_frame = ((placed_at - origin).max(0.0) * rate).round() as u64;
+        let end_frame = ((segment_end - origin).max(0.0) * rate).round() as u64;
+
+        while written_frames < start_frame {
+            for _ in 0..channels {
+                writer
+                    .write_sample(0i16)
+                    .map_err(|e| format!("Write merged microphone WAV: {e}"))?;
+            }
+            written_frames += 1;
+        }
+        let budget = end_frame.saturating_sub(written_frames) * channels;
+        let mut samples = 0u64;
+        for sample in reader.samples::<i16>().take(budget as usize) {
+            writer
+                .write_sample(sample.map_err(|e| format!("Read microphone WAV: {e}"))?)
+                .map_err(|e| format!("Write merged microphone WAV: {e}"))?;
+            samples += 1;
+        }
+        written_frames += samples / channels.max(1);
+    }
+    if let Some((writer, _)) = writer {
+        writer
+            .finalize()
+            .map_err(|e| format!("Finalize merged microphone WAV: {e}"))?;
+    }
+    Ok(Some(origin))
+}
+
+/// Webcam clips for the stitcher as `(path, duration)`. Each clip runs until
+/// the next one starts so the webcam stays aligned at every segment start.
+/// Returns `None` unless every segment recorded the webcam.
+pub(super) fn webcam_layout(
+    segments: &[RecordedSegment],
+    starts: &[f64],
+) -> Option<(Vec<(PathBuf, f64)>, f64)> {
+    let placed: Vec<(PathBuf, f64)> = segments
+        .iter()
+        .zip(starts)
+        .map(|(segment, start)| {
+            Some((
+                segment.path("webcamVideoPath")?,
+                start + segment.offset_sec("webcamVideoOffsetSec"),
+            ))
+        })
+        .collect::<Option<_>>()?;
+    let total = starts.last()? + segments.last()?.duration_sec;
+    let origin = placed.first()?.1;
+    let clips = placed
+        .iter()
+        .enumerate()
+        .map(|(index, (path, at))| {
+            let until = placed.get(index + 1).map_or(total, |next| next.1);
+            (path.clone(), (until - at).max(0.0))
+        })
+        .collect();
+    Some((clips, origin))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(result: Value, duration_sec: f64, paused_after_sec: f64) -> RecordedSegment {
+        RecordedSegment {
+            result,
+            duration_sec,
+            paused_after_sec,
+        }
+    }
+
+    #[test]
+    fn events_and_markers_use_one_continuous_timeline() {
+        let segments = [
+            segment(
+                serde_json::json!({
+                    "mouseData": [{ "x": 1, "timestamp": 0.5 }],
+                    "inputEvents": [{ "type": "keydown", "timestamp": 1.0 }]
+                }),
+                2.0,
+                7.0,
+            ),
+            segment(
+                serde_json::json!({
+                    "mouseData": [{ "x": 2, "timestamp": 0.25 }],
+                    "inputEvents": []
+                }),
+                3.0,
+                0.0,
+            ),
+        ];
+        let starts = segment_starts(&segments);
+        assert_eq!(starts, [0.0, 2.0]);
+        let mouse = rebase_events(&segments, &starts, "mouseData");
+        assert_eq!(mouse[1]["timestamp"], 2.25);
+        assert_eq!(mouse[1]["x"], 2);
+        assert_eq!(rebase_events(&segments, &starts, "inputEvents").len(), 1);
+        assert_eq!(
+            pause_markers(&segments, &starts),
+            [serde_json::json!({ "time": 2.0, "pausedSec": 7.0 })]
+        );
+    }
+
+    #[test]
+    fn microphone_segments_land_at_their_offsets() {
+        let root = std::env::temp_dir().join(format!("sgt-pause-mic-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 10,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let write = |name: &str, value: i16, frames: usize| {
+            let path = root.join(name);
+            let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+            for _ in 0..frames {
+                writer.write_sample(value).unwrap();
+            }
+            writer.finalize().unwrap();
+            path.to_string_lossy().to_string()
+        };
+        // Segment 1: 2 s long, mic starts 0.5 s in and overruns by 1 s.
+        // Segment 2: mic starts 0.2 s in.
+        let first = write("a.wav", 1, 25);
+        let second = write("b.wav", 2, 10);
+        let segments = [
+            segment(
+                serde_json::json!({ "micAudioPath": first, "micAudioOffsetSec": 0.5 }),
+                2.0,
+                1.0,
+            ),
+            segment(
+                serde_json::json!({ "micAudioPath": second, "micAudioOffsetSec": 0.2 }),
+                2.0,
+                0.0,
+            ),
+        ];
+        let output = root.join("merged.wav");
+        let origin = splice_mic_audio(&segments, &segment_starts(&segments), &output).unwrap();
+        assert_eq!(origin, Some(0.5));
+        let samples: Vec<i16> = hound::WavReader::open(&output)
+            .unwrap()
+            .samples::<i16>()
+            .map(Result::unwrap)
+            .collect();
+        // 15 frames of segment 1 (0.5 s..2.0 s), 2 frames of silence, then
+        // segment 2 from 2.2 s.
+        assert_eq!(samples.len(), 27);
+        assert!(samples[..15].iter().all(|&sample| sample == 1));
+        assert_eq!(&samples[15..17], &[0, 0]);
+        assert!(samples[17..].iter().all(|&sample| sample == 2));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
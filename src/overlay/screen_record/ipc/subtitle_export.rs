@@ -47,7 +47,7 @@ fn save_subtitle_srt_file(
     Ok(destination.to_string_lossy().to_string())
 }
 
-fn sanitize_dir_path(path: &str) -> Result<PathBuf, String> {
+pub(super) fn sanitize_dir_path(path: &str) -> Result<PathBuf, String> {
     let trimmed = path.trim();
     if trimmed.is_empty() {
         return Err("Target directory is empty".to_string());
@@ -69,7 +69,7 @@ fn sanitize_dir_path(path: &str) -> Result<PathBuf, String> {
     Ok(dir)
 }
 
-fn unique_destination(dir: &Path, file_name: &str) -> PathBuf {
+pub(super) fn unique_destination(dir: &Path, file_name: &str) -> PathBuf {
     let base = Path::new(file_name);
     let stem = base
         .file_stem()
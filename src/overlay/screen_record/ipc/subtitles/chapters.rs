@@ -0,0 +1,318 @@
+// Automatic chapter proposals. The generated subtitles are sent to the
+// configured text model, which splits them into topics; proposals come back in
+// the cues' own compact project time so the timeline can offer them as
+// markers the user accepts or edits before export.
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::provider_credentials;
+use crate::api::{TranslateTextRequest, translate_text_streaming};
+use crate::model_config::{ModelConfig, get_model_by_id_with_custom};
+
+use super::translation::{collect_translation_models, current_config};
+
+// Keeps long tutorials inside every provider's context window: past
+// MAX_BLOCKS cues neighbours are merged, and every block shares the character
+// budget evenly.
+const MAX_TRANSCRIPT_CHARS: usize = 60_000;
+const MAX_BLOCKS: usize = 600;
+const MAX_CUE_CHARS: usize = 300;
+const DEFAULT_MAX_CHAPTERS: usize = 12;
+const MAX_REQUESTED_CHAPTERS: usize = 50;
+const MAX_TITLE_CHARS: usize = 80;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChapterProposalRequest {
+    cues: Vec<ChapterProposalCue>,
+    #[serde(default)]
+    model_id: Option<String>,
+    #[serde(default)]
+    max_chapters: Option<usize>,
+    #[serde(default)]
+    instructions: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChapterProposalCue {
+    start_time: f64,
+    text: String,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct ChapterProposal {
+    time: f64,
+    title: String,
+}
+
+#[derive(Deserialize)]
+struct ModelPayload {
+    chapters: Vec<ModelChapter>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelChapter {
+    start_block: usize,
+    title: String,
+}
+
+#[derive(Debug)]
+struct CueBlock {
+    start: f64,
+    text: String,
+}
+
+pub fn handle_propose_subtitle_chapters(
+    args: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let request: ChapterProposalRequest = serde_json::from_value(args.clone())
+        .map_err(|error| format!("Invalid chapter proposal request: {error}"))?;
+    let blocks = cue_blocks(&request.cues);
+    if blocks.is_empty() {
+        return Err("Generate subtitles before proposing chapters".to_string());
+    }
+    let max_chapters = request
+        .max_chapters
+        .unwrap_or(DEFAULT_MAX_CHAPTERS)
+        .clamp(1, MAX_REQUESTED_CHAPTERS);
+
+    let config = current_config()?;
+    let mut models: Vec<ModelConfig> = Vec::new();
+    if let Some(model_id) = request
+        .model_id
+        .as_deref()
+        .filter(|value| !value.trim().is_empty())
+    {
+        let model = get_model_by_id_with_custom(model_id, &config.custom_models)
+            .ok_or_else(|| format!("Unknown chapter proposal model: {model_id}"))?;
+        models.push(model);
+    }
+    for model in collect_translation_models(&config) {
+        if !models.iter().any(|existing| existing.id == model.id) {
+            models.push(model);
+        }
+    }
+    if models.is_empty() {
+        return Err("No text model is available for chapter proposals".to_string());
+    }
+
+    let groq_api_key = provider_credentials::resolve("GROQ_API_KEY", &config.api_key);
+    let gemini_api_key = provider_credentials::resolve("GEMINI_API_KEY", &config.gemini_api_key);
+    let instruction = build_instruction(max_chapters, request.instructions.as_deref());
+    let transcript = build_transcript(&blocks);
+    let mut last_error = String::new();
+    for model in models {
+        let response = translate_text_streaming(
+            TranslateTextRequest {
+                groq_api_key: &groq_api_key,
+                gemini_api_key: &gemini_api_key,
+                text: transcript.clone(),
+                instruction: instruction.clone(),
+                model: model.full_name.clone(),
+                provider: model.provider.clone(),
+                streaming_enabled: false,
+                use_json_format: true,
+                response_schema: None,
+                search_label: None,
+                ui_language: &config.ui_language,
+                cancel_token: None,
+                request_timeout: None,
+                target_language: None,
+            },
+            |_| {},
+        );
+        let outcome = response
+            .map_err(|error| error.to_string())
+            .and_then(|raw| parse_proposals(&raw, &blocks, max_chapters));
+        match outcome {
+            Ok(chapters) => {
+                return Ok(serde_json::json!({
+                    "chapters": chapters,
+                    "modelId": model.id,
+                }));
+            }
+            Err(error) => {
+                eprintln!("[SubtitleChapters] {} failed: {error}", model.id);
+                last_error = format!("{}: {error}", model.id);
+            }
+        }
+    }
+    Err(format!("Chapter proposal failed ({last_error})"))
+}
+
+/// Cues in time order, merged and trimmed so the transcript fits the prompt
+/// budget.
+fn cue_blocks(cues: &[ChapterProposalCue]) -> Vec<CueBlock> {
+    let mut cues: Vec<(f64, String)> = cues
+        .iter()
+        .filter(|cue| cue.start_time.is_finite() && cue.start_time >= 0.0)
+        .map(|cue| {
+            let text: String = cue
+                .text
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ")
+                .chars()
+                .take(MAX_CUE_CHARS)
+                .collect();
+            (cue.start_time, text)
+        })
+        .filter(|(_, text)| !text.is_empty())
+        .collect();
+    cues.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let group = cues.len().div_ceil(MAX_BLOCKS).max(1);
+    let block_chars = MAX_TRANSCRIPT_CHARS / cues.len().div_ceil(group).max(1);
+    cues.chunks(group)
+        .map(|chunk| CueBlock {
+            start: chunk[0].0,
+            text: chunk
+                .iter()
+                .map(|(_, text)| text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+                .chars()
+                .take(block_chars)
+                .collect(),
+        })
+        .collect()
+}
+
+fn build_instruction(max_chapters: usize, instructions: Option<&str>) -> String {
+    let mut prompt = format!(
+        "You split a video transcript into chapters for viewers to navigate.\n\
+Each transcript line is `[block] m:ss text`.\n\
+Return JSON only with this exact shape: {{\"chapters\":[{{\"startBlock\":0,\"title\":\"...\"}}]}}.\n\
+Rules:\n\
+- The first chapter starts at block 0.\n\
+- Start a new chapter only where the topic clearly changes.\n\
+- Return at most {max_chapters} chapters in ascending block order.\n\
+- Titles are short (2 to 6 words), in the transcript's language, without timestamps or numbering.\n\
+- Do not add commentary or markdown."
+    );
+    if let Some(instructions) = instructions
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+    {
+        prompt.push_str("\n\nUser instructions:\n");
+        prompt.push_str(instructions);
+    }
+    prompt
+}
+
+fn build_transcript(blocks: &[CueBlock]) -> String {
+    blocks
+        .iter()
+        .enumerate()
+        .map(|(index, block)| {
+            let seconds = block.start as u64;
+            format!(
+                "[{index}] {}:{:02} {}",
+                seconds / 60,
+                seconds % 60,
+                block.text
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parse_proposals(
+    raw: &str,
+    blocks: &[CueBlock],
+    max_chapters: usize,
+) -> Result<Vec<ChapterProposal>, String> {
+    let trimmed = raw.trim();
+    let cleaned = trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .unwrap_or(trimmed);
+    let cleaned = cleaned.strip_suffix("```").unwrap_or(cleaned).trim();
+    let payload: ModelPayload = serde_json::from_str(cleaned)
+        .map_err(|error| format!("Failed to parse chapter JSON: {error}"))?;
+
+    let mut chapters = payload.chapters;
+    chapters.sort_by_key(|chapter| chapter.start_block);
+    chapters.dedup_by_key(|chapter| chapter.start_block);
+    let proposals: Vec<ChapterProposal> = chapters
+        .into_iter()
+        .filter_map(|chapter| {
+            let block = blocks.get(chapter.start_block)?;
+            let title: String = chapter
+                .title
+                .chars()
+                .map(|ch| if ch.is_control() { ' ' } else { ch })
+                .take(MAX_TITLE_CHARS)
+                .collect();
+            let title = title.trim();
+            (!title.is_empty()).then(|| ChapterProposal {
+                time: block.start,
+                title: title.to_string(),
+            })
+        })
+        .take(max_chapters)
+        .collect();
+    if proposals.is_empty() {
+        return Err("Model returned no usable chapters".to_string());
+    }
+    Ok(proposals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cue(start_time: f64, text: &str) -> ChapterProposalCue {
+        ChapterProposalCue {
+            start_time,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn cues_are_ordered_and_condensed_to_the_budget() {
+        let blocks = cue_blocks(&[
+            cue(5.0, "second"),
+            cue(1.0, " first\n line "),
+            cue(2.0, "  "),
+        ]);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].text, "first line");
+        assert_eq!(
+            build_transcript(&blocks),
+            "[0] 0:01 first line\n[1] 0:05 second"
+        );
+
+        let long_text = "word ".repeat(60);
+        let many: Vec<ChapterProposalCue> = (0..2_000)
+            .map(|index| cue(index as f64, &long_text))
+            .collect();
+        let condensed = cue_blocks(&many);
+        assert!(condensed.len() < 2_000);
+        assert!(build_transcript(&condensed).len() <= MAX_TRANSCRIPT_CHARS + condensed.len() * 16);
+    }
+
+    #[test]
+    fn proposals_map_blocks_to_cue_times() {
+        let blocks = cue_blocks(&[cue(0.5, "hello"), cue(40.0, "setup"), cue(95.0, "demo")]);
+        let raw = "```json\n{\"chapters\":[{\"startBlock\":2,\"title\":\"Demo\"},{\"startBlock\":0,\"title\":\"Intro\\n\"},{\"startBlock\":9,\"title\":\"Gone\"},{\"startBlock\":1,\"title\":\"  \"}]}\n```";
+        let proposals = parse_proposals(raw, &blocks, 10).expect("proposals parse");
+        assert_eq!(
+            proposals,
+            [
+                ChapterProposal {
+                    time: 0.5,
+                    title: "Intro".to_string()
+                },
+                ChapterProposal {
+                    time: 95.0,
+                    title: "Demo".to_string()
+                },
+            ]
+        );
+        assert_eq!(parse_proposals(raw, &blocks, 1).unwrap().len(), 1);
+        assert!(parse_proposals("{\"chapters\":[]}", &blocks, 10).is_err());
+    }
+}
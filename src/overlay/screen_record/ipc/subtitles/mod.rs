@@ -1,4 +1,5 @@
 pub(crate) mod audio;
+mod chapters;
 mod job;
 pub(crate) mod media;
 mod parakeet_tdt;
@@ -10,6 +11,7 @@ mod translation_openai;
 mod translation_providers;
 pub(crate) mod types;
 
+pub use chapters::handle_propose_subtitle_chapters;
 pub use job::{
     handle_cancel_subtitle_generation, handle_get_subtitle_generation_capabilities,
     handle_get_subtitle_generation_status, handle_start_subtitle_generation,
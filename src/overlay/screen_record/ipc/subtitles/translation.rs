@@ -14,10 +14,8 @@ use crate::model_config::{
 
 use self::chunking::{initial_translation_chunk_count, split_translation_items};
 use self::diagnostics::{TranslationDiagnostics, TranslationStartLog};
-use self::models::{
-    collect_prioritized_translation_models, collect_translation_models, current_config,
-    localized_model_label,
-};
+use self::models::{collect_prioritized_translation_models, localized_model_label};
+pub(super) use self::models::{collect_translation_models, current_config};
 use self::retry::{AttemptModelArgs, AttemptOutcome, attempt_model};
 use super::super::job_registry::{self, JobHandle, JobState};
 use super::translation_providers::{
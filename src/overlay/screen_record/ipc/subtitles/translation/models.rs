@@ -12,7 +12,8 @@ use crate::retry_model_chain::{
 
 use super::GTX_TRANSLATION_MODEL_ID;
 
-pub(super) fn current_config() -> Result<Config, String> {
+pub(in crate::overlay::screen_record::ipc::subtitles) fn current_config() -> Result<Config, String>
+{
     APP.lock()
         .map(|app| app.config.clone())
         .map_err(|_| "App lock poisoned".to_string())
@@ -22,7 +23,9 @@ pub(super) fn localized_model_label(model: &ModelConfig, ui_language: &str) -> S
     model.localized_name(ui_language).to_string()
 }
 
-pub(super) fn collect_translation_models(config: &Config) -> Vec<ModelConfig> {
+pub(in crate::overlay::screen_record::ipc::subtitles) fn collect_translation_models(
+    config: &Config,
+) -> Vec<ModelConfig> {
     let blocked_providers = HashSet::new();
     let mut models = Vec::new();
     let mut seen_model_ids = HashSet::new();
@@ -131,5 +134,6 @@ fn is_compatible_translation_model(
         && !model_is_non_llm(&model.id)
         && !blocked_providers.contains(&model.provider)
         && provider_is_available(&model.provider, config)
-        && preflight_skip_reason(&model.id, &model.provider, config, blocked_providers, None).is_none()
+        && preflight_skip_reason(&model.id, &model.provider, config, blocked_providers, None)
+            .is_none()
 }
@@ -435,6 +435,31 @@ pub fn probe_video_metadata(file_path: &str) -> Result<VideoMetadataProbe, Strin
     })
 }
 
+/// Probe a media file's duration in seconds from its container (no decode).
+pub fn probe_media_duration_seconds(file_path: &str) -> Result<f64, String> {
+    mf_startup()?;
+
+    let wide_path: Vec<u16> = file_path.encode_utf16().chain(std::iter::once(0)).collect();
+    let reader = unsafe {
+        MFCreateSourceReaderFromURL(
+            windows::core::PCWSTR(wide_path.as_ptr()),
+            None::<&IMFAttributes>,
+        )
+        .map_err(|e| format!("MFCreateSourceReaderFromURL probe: {e}"))?
+    };
+    let duration = unsafe {
+        reader
+            .GetPresentationAttribute(MF_SOURCE_READER_MEDIASOURCE.0 as u32, &MF_PD_DURATION)
+            .map_err(|e| format!("GetPresentationAttribute DURATION: {e}"))?
+    };
+    // MF_PD_DURATION is a VT_UI8 count of 100ns units.
+    let duration_100ns = unsafe { duration.Anonymous.Anonymous.Anonymous.uhVal };
+    if duration_100ns == 0 {
+        return Err("Media source reports no duration".to_string());
+    }
+    Ok(duration_100ns as f64 / 10_000_000.0)
+}
+
 /// Create a PROPVARIANT containing an i64 value (VT_I8), used for seeking.
 pub(super) fn make_i64_propvariant(value: i64) -> PROPVARIANT {
     PROPVARIANT {
@@ -24,7 +24,7 @@ fn ease_camera_move(t: f64) -> f64 {
 }
 
 // Convert zoom anchor params → visible viewport center.
-fn to_viewport_center(zoom: f64, pos_x: f64, pos_y: f64) -> (f64, f64) {
+pub(super) fn to_viewport_center(zoom: f64, pos_x: f64, pos_y: f64) -> (f64, f64) {
     if zoom <= 1.0 {
         return (0.5, 0.5);
     }
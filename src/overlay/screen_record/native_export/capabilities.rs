@@ -7,6 +7,7 @@ pub fn get_export_capabilities() -> serde_json::Value {
         "pipeline": if dx12_ok { "zero_copy_gpu" } else { "cpu_fallback" },
         "mf_h264": true,       // MF H.264 software encoder ships with every Win10/11
         "mf_h264_hw": mf_hw,   // hardware-accelerated path (Intel QSV / AMD VCE / NVENC via MF)
+        "presets": super::presets::preset_catalog(),
     })
 }
 
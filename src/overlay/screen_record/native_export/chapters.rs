@@ -0,0 +1,326 @@
+// Chapter markers for composition exports. Markers arrive in compact project
+// time (what the timeline shows), are mapped to output time with the same
+// speed integration the renderer uses, and are written three ways: a `chpl`
+// atom inside the exported MP4, a YouTube description timestamp list and a
+// WebVTT chapters file next to the video.
+
+mod mp4_chpl;
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use serde_json::json;
+
+use super::config::{ChapterMarker, CompositionExportClipJob};
+use super::interchange::time_map::ProjectTimeMap;
+
+// The `chpl` atom stores the chapter count and each title length in one byte.
+const MAX_CHAPTERS: usize = 255;
+const MAX_TITLE_BYTES: usize = 255;
+// Markers closer than this after mapping collapse into the earlier one.
+const MIN_CHAPTER_SEC: f64 = 0.5;
+// YouTube only links chapters when the list has at least three entries that
+// are each ten seconds or longer.
+const YOUTUBE_MIN_CHAPTERS: usize = 3;
+const YOUTUBE_MIN_CHAPTER_SEC: f64 = 10.0;
+// Title of the chapter added at 0 when the first marker starts later.
+const START_CHAPTER_TITLE: &str = "Intro";
+
+#[derive(Debug, Clone, PartialEq)]
+struct OutputChapter {
+    start: f64,
+    end: f64,
+    title: String,
+}
+
+pub(super) fn validate_chapters(chapters: &[ChapterMarker]) -> Result<(), String> {
+    if chapters.len() > MAX_CHAPTERS {
+        return Err(format!("exports support at most {MAX_CHAPTERS} chapters"));
+    }
+    for chapter in chapters {
+        if !chapter.time.is_finite() || chapter.time < 0.0 {
+            return Err("chapter markers contain an invalid time".to_string());
+        }
+        let title = chapter.title.trim();
+        if title.is_empty() || title.len() > MAX_TITLE_BYTES {
+            return Err(format!(
+                "chapter titles must be 1 to {MAX_TITLE_BYTES} bytes long"
+            ));
+        }
+        if title.chars().any(char::is_control) {
+            return Err("chapter titles must be a single line of text".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Writes the chapter atom into `mp4_path` and the two sidecar files next to
+/// it. Returns the sidecar artifacts plus warnings; a failed atom splice is a
+/// warning because the rendered video itself is still good.
+pub(super) fn write_export_chapters(
+    markers: &[ChapterMarker],
+    clips: &[CompositionExportClipJob],
+    mp4_path: &Path,
+) -> Result<(Vec<serde_json::Value>, Vec<String>), String> {
+    if markers.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    let chapters = output_chapters(markers, &ProjectTimeMap::new(clips));
+
+    let mut warnings = Vec::new();
+    if let Err(error) = mp4_chpl::write_chapter_atom(mp4_path, &chapters) {
+        warnings.push(format!("Chapters were not embedded in the MP4: {error}"));
+    }
+
+    let (youtube, youtube_warnings) = youtube_timestamps(&chapters);
+    warnings.extend(youtube_warnings);
+    let mut artifacts = Vec::new();
+    for (format, extension, content) in [
+        ("vtt-chapters", "chapters.vtt", webvtt_chapters(&chapters)),
+        ("youtube-chapters", "chapters.txt", youtube),
+    ] {
+        let path = mp4_path.with_extension(extension);
+        fs::write(&path, content.as_bytes())
+            .map_err(|e| format!("Failed to write chapter file: {e}"))?;
+        artifacts.push(json!({
+            "format": format,
+            "path": path.to_string_lossy(),
+            "bytes": content.len(),
+            "primary": false,
+        }));
+    }
+    Ok((artifacts, warnings))
+}
+
+/// Sidecar paths `write_export_chapters` may create, for cleanup on failure.
+pub(super) fn sidecar_paths(mp4_path: &Path) -> [std::path::PathBuf; 2] {
+    [
+        mp4_path.with_extension("chapters.vtt"),
+        mp4_path.with_extension("chapters.txt"),
+    ]
+}
+
+/// Output-time chapters covering the whole export. The list always starts at
+/// 0 so players and YouTube treat it as complete: a first marker later than
+/// that keeps its time behind an added start chapter, unless the atom has no
+/// room left for one.
+fn output_chapters(markers: &[ChapterMarker], map: &ProjectTimeMap) -> Vec<OutputChapter> {
+    let duration = map.duration();
+    let mut mapped: Vec<(f64, &str)> = markers
+        .iter()
+        .map(|marker| (map.compact_to_record(marker.time), marker.title.trim()))
+        .collect();
+    mapped.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut chapters: Vec<OutputChapter> = Vec::new();
+    let starts_late = mapped
+        .first()
+        .is_some_and(|(start, _)| *start >= MIN_CHAPTER_SEC);
+    if starts_late && mapped.len() < MAX_CHAPTERS {
+        chapters.push(OutputChapter {
+            start: 0.0,
+            end: duration,
+            title: START_CHAPTER_TITLE.to_string(),
+        });
+    }
+    for (start, title) in mapped {
+        let start = if chapters.is_empty() { 0.0 } else { start };
+        let too_close = chapters
+            .last()
+            .is_some_and(|previous| start - previous.start < MIN_CHAPTER_SEC);
+        if too_close || start > duration - MIN_CHAPTER_SEC {
+            continue;
+        }
+        if let Some(previous) = chapters.last_mut() {
+            previous.end = start;
+        }
+        chapters.push(OutputChapter {
+            start,
+            end: duration,
+            title: title.to_string(),
+        });
+    }
+    chapters
+}
+
+fn youtube_timestamps(chapters: &[OutputChapter]) -> (String, Vec<String>) {
+    let with_hours = chapters.last().is_some_and(|last| last.end >= 3600.0);
+    let mut list = String::new();
+    for chapter in chapters {
+        // YouTube reads whole seconds; flooring keeps the first entry at 0:00.
+        let seconds = chapter.start.floor() as u64;
+        let stamp = if with_hours {
+            format!(
+                "{}:{:02}:{:02}",
+                seconds / 3600,
+                (seconds / 60) % 60,
+                seconds % 60
+            )
+        } else {
+            format!("{}:{:02}", seconds / 60, seconds % 60)
+        };
+        let _ = writeln!(list, "{stamp} {}", chapter.title);
+    }
+
+    let mut warnings = Vec::new();
+    if chapters.len() < YOUTUBE_MIN_CHAPTERS {
+        warnings.push(format!(
+            "YouTube needs at least {YOUTUBE_MIN_CHAPTERS} chapters to show them"
+        ));
+    }
+    if chapters
+        .iter()
+        .any(|chapter| chapter.end - chapter.start < YOUTUBE_MIN_CHAPTER_SEC)
+    {
+        warnings.push(format!(
+            "YouTube ignores chapter lists with chapters shorter than {YOUTUBE_MIN_CHAPTER_SEC:.0} seconds"
+        ));
+    }
+    (list, warnings)
+}
+
+fn webvtt_chapters(chapters: &[OutputChapter]) -> String {
+    let mut vtt = String::from("WEBVTT\n");
+    for (index, chapter) in chapters.iter().enumerate() {
+        let _ = write!(
+            vtt,
+            "\n{}\n{} --> {}\n{}\n",
+            index + 1,
+            vtt_timestamp(chapter.start),
+            vtt_timestamp(chapter.end),
+            // "-->" would end the cue text early in some parsers.
+            chapter.title.replace("-->", "->"),
+        );
+    }
+    vtt
+}
+
+fn vtt_timestamp(seconds: f64) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        (millis / 60_000) % 60,
+        (millis / 1000) % 60,
+        millis % 1000
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clips() -> Vec<CompositionExportClipJob> {
+        // 60 s at 1x, then 60 s at 2x: 90 s of output.
+        let clip = |id: &str, speed: f64| {
+            serde_json::from_value(json!({
+                "jobId": id,
+                "clipId": id,
+                "clipName": id,
+                "sourceVideoPath": "C:/rec.mp4",
+                "trimStart": 0.0,
+                "duration": 60.0,
+                "segment": {
+                    "crop": null,
+                    "trimSegments": [{ "startTime": 0.0, "endTime": 60.0 }],
+                    "speedPoints": [
+                        { "time": 0.0, "speed": speed },
+                        { "time": 60.0, "speed": speed }
+                    ]
+                },
+                "backgroundConfig": {
+                    "scale": 100.0,
+                    "borderRadius": 0.0,
+                    "backgroundType": "solid",
+                    "shadow": 0.0,
+                    "cursorScale": 1.0
+                }
+            }))
+            .expect("clip parses")
+        };
+        vec![clip("a", 1.0), clip("b", 2.0)]
+    }
+
+    fn marker(time: f64, title: &str) -> ChapterMarker {
+        ChapterMarker {
+            time,
+            title: title.to_string(),
+        }
+    }
+
+    #[test]
+    fn markers_map_through_speed_and_cover_the_export() {
+        let map = ProjectTimeMap::new(&clips());
+        let chapters = output_chapters(
+            &[
+                marker(80.0, "Faster part"),
+                marker(2.0, " Intro "),
+                marker(80.2, "Duplicate"),
+                marker(30.0, "Setup"),
+            ],
+            &map,
+        );
+        let starts: Vec<f64> = chapters
+            .iter()
+            .map(|c| (c.start * 10.0).round() / 10.0)
+            .collect();
+        assert_eq!(starts, [0.0, 2.0, 30.0, 70.0]);
+        assert_eq!(chapters[0].title, START_CHAPTER_TITLE);
+        assert!((chapters[0].end - 2.0).abs() < 1e-3);
+        assert_eq!(chapters[1].title, "Intro");
+        assert!((chapters[3].end - 90.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn a_first_marker_at_the_start_opens_the_list_itself() {
+        let map = ProjectTimeMap::new(&clips());
+        let chapters = output_chapters(&[marker(0.2, "Opening"), marker(30.0, "Setup")], &map);
+        let titles: Vec<&str> = chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["Opening", "Setup"]);
+        assert_eq!(chapters[0].start, 0.0);
+
+        // A full atom has no room for a start chapter, so the first marker
+        // opens the list instead.
+        let full: Vec<ChapterMarker> = (0..MAX_CHAPTERS)
+            .map(|index| marker(1.0 + index as f64 * 0.3, "Step"))
+            .collect();
+        let chapters = output_chapters(&full, &map);
+        assert_eq!(
+            (chapters[0].start, chapters[0].title.as_str()),
+            (0.0, "Step")
+        );
+    }
+
+    #[test]
+    fn youtube_and_webvtt_lists_follow_their_formats() {
+        let chapters = vec![
+            OutputChapter {
+                start: 0.0,
+                end: 65.5,
+                title: "Intro".to_string(),
+            },
+            OutputChapter {
+                start: 65.5,
+                end: 70.0,
+                title: "A --> B".to_string(),
+            },
+        ];
+        let (youtube, warnings) = youtube_timestamps(&chapters);
+        assert_eq!(youtube, "0:00 Intro\n1:05 A --> B\n");
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            webvtt_chapters(&chapters),
+            "WEBVTT\n\n1\n00:00:00.000 --> 00:01:05.500\nIntro\n\n2\n00:01:05.500 --> 00:01:10.000\nA -> B\n"
+        );
+    }
+
+    #[test]
+    fn chapter_titles_must_fit_the_atom() {
+        assert!(validate_chapters(&[marker(0.0, "Intro")]).is_ok());
+        assert!(validate_chapters(&[marker(0.0, "  ")]).is_err());
+        assert!(validate_chapters(&[marker(0.0, "Two\nlines")]).is_err());
+        assert!(validate_chapters(&[marker(f64::NAN, "Intro")]).is_err());
+        assert!(validate_chapters(&[marker(0.0, &"x".repeat(256))]).is_err());
+    }
+}
@@ -0,0 +1,349 @@
+// Nero-style `moov/udta/chpl` chapter atom, the chapter list VLC, mpv, MPC-HC
+// and ffmpeg read from MP4. Media Foundation's sink writer has no chapter
+// support, so the atom is spliced into the finished file: every box outside
+// `moov` is copied byte for byte, and chunk offsets that point past the old
+// `moov` are shifted by however much it grew.
+
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::OutputChapter;
+
+// Boxes whose payload is a plain list of child boxes on the way to the chunk
+// offset tables.
+const OFFSET_CONTAINERS: [&[u8; 4]; 5] = [b"moov", b"trak", b"mdia", b"minf", b"stbl"];
+// A recorder export's moov is a few MiB at most; refuse to buffer anything
+// that is clearly not one.
+const MAX_MOOV_BYTES: u64 = 256 * 1024 * 1024;
+// `chpl` start times are in 100 ns units.
+const CHPL_TIMESCALE: f64 = 10_000_000.0;
+
+struct BoxHeader {
+    kind: [u8; 4],
+    offset: u64,
+    header_len: u64,
+    size: u64,
+}
+
+pub(super) fn write_chapter_atom(path: &Path, chapters: &[OutputChapter]) -> Result<(), String> {
+    let mut source = File::open(path).map_err(|e| format!("open export: {e}"))?;
+    let file_len = source
+        .metadata()
+        .map_err(|e| format!("stat export: {e}"))?
+        .len();
+    let top_level = read_top_level(&mut source, file_len)?;
+    let moov = top_level
+        .iter()
+        .find(|header| &header.kind == b"moov")
+        .ok_or("export has no moov box")?;
+    let moov_end = moov.offset + moov.size;
+    if top_level
+        .iter()
+        .any(|header| &header.kind == b"moof" && header.offset > moov.offset)
+    {
+        return Err("fragmented MP4 exports are not supported".to_string());
+    }
+    if moov.size > MAX_MOOV_BYTES {
+        return Err("moov box is unexpectedly large".to_string());
+    }
+
+    let mut old_moov = vec![0_u8; moov.size as usize];
+    source
+        .seek(SeekFrom::Start(moov.offset))
+        .and_then(|_| source.read_exact(&mut old_moov))
+        .map_err(|e| format!("read moov: {e}"))?;
+    let mut new_moov = rebuild_moov(&old_moov[moov.header_len as usize..], chapters)?;
+    let delta = new_moov.len() as i64 - moov.size as i64;
+    if delta != 0 {
+        // Header length of the rebuilt box is always 8 (or 16 past 4 GiB).
+        let header_len = if new_moov.len() > u32::MAX as usize {
+            16
+        } else {
+            8
+        };
+        patch_chunk_offsets(&mut new_moov[header_len..], moov_end, delta)?;
+    }
+
+    let temp_path = path.with_extension("chpl.tmp");
+    let result = (|| -> io::Result<()> {
+        let mut target = File::create(&temp_path)?;
+        source.seek(SeekFrom::Start(0))?;
+        io::copy(&mut (&mut source).take(moov.offset), &mut target)?;
+        target.write_all(&new_moov)?;
+        source.seek(SeekFrom::Start(moov_end))?;
+        io::copy(&mut source, &mut target)?;
+        target.sync_all()
+    })();
+    drop(source);
+    if let Err(error) = result.and_then(|_| fs::rename(&temp_path, path)) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("rewrite export: {error}"));
+    }
+    Ok(())
+}
+
+fn read_top_level(file: &mut File, file_len: u64) -> Result<Vec<BoxHeader>, String> {
+    let mut headers = Vec::new();
+    let mut offset = 0;
+    while offset + 8 <= file_len {
+        let mut header = [0_u8; 16];
+        file.seek(SeekFrom::Start(offset))
+            .and_then(|_| file.read_exact(&mut header[..8]))
+            .map_err(|e| format!("read box header: {e}"))?;
+        let mut size = u32::from_be_bytes(header[..4].try_into().unwrap()) as u64;
+        let mut header_len = 8;
+        if size == 1 {
+            file.read_exact(&mut header[8..])
+                .map_err(|e| format!("read box header: {e}"))?;
+            size = u64::from_be_bytes(header[8..].try_into().unwrap());
+            header_len = 16;
+        } else if size == 0 {
+            size = file_len - offset;
+        }
+        if size < header_len || offset + size > file_len {
+            return Err("export has a malformed box layout".to_string());
+        }
+        headers.push(BoxHeader {
+            kind: header[4..8].try_into().unwrap(),
+            offset,
+            header_len,
+            size,
+        });
+        offset += size;
+    }
+    Ok(headers)
+}
+
+/// `(kind, header_len, size)` of the box at the start of `bytes`.
+fn box_at(bytes: &[u8]) -> Result<([u8; 4], usize, usize), String> {
+    let mut size = u32::from_be_bytes(bytes[..4].try_into().unwrap()) as u64;
+    let mut header_len = 8;
+    if size == 1 && bytes.len() >= 16 {
+        size = u64::from_be_bytes(bytes[8..16].try_into().unwrap());
+        header_len = 16;
+    } else if size == 0 {
+        size = bytes.len() as u64;
+    }
+    if size < header_len as u64 || size > bytes.len() as u64 {
+        return Err("moov has a malformed child box".to_string());
+    }
+    Ok((bytes[4..8].try_into().unwrap(), header_len, size as usize))
+}
+
+type ChildBox<'a> = ([u8; 4], usize, &'a [u8]);
+
+/// Child boxes of a container payload as `(kind, header_len, bytes)`, plus
+/// any trailing bytes too short to be a box (QuickTime `udta` may end with a
+/// 32-bit zero terminator).
+fn children(payload: &[u8]) -> Result<(Vec<ChildBox<'_>>, &[u8]), String> {
+    let mut boxes = Vec::new();
+    let mut rest = payload;
+    while rest.len() >= 8 {
+        let (kind, header_len, size) = box_at(rest)?;
+        let (child, tail) = rest.split_at(size);
+        boxes.push((kind, header_len, child));
+        rest = tail;
+    }
+    Ok((boxes, rest))
+}
+
+fn make_box(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(payload.len() + 16);
+    let small = payload.len() + 8;
+    if small <= u32::MAX as usize {
+        out.extend_from_slice(&(small as u32).to_be_bytes());
+        out.extend_from_slice(kind);
+    } else {
+        out.extend_from_slice(&1_u32.to_be_bytes());
+        out.extend_from_slice(kind);
+        out.extend_from_slice(&(payload.len() as u64 + 16).to_be_bytes());
+    }
+    out.extend_from_slice(payload);
+    out
+}
+
+fn chpl_box(chapters: &[OutputChapter]) -> Vec<u8> {
+    // Version 1 carries an extra reserved word before the count.
+    let mut payload = vec![1, 0, 0, 0, 0, 0, 0, 0, chapters.len().min(255) as u8];
+    for chapter in chapters.iter().take(255) {
+        let start = (chapter.start.max(0.0) * CHPL_TIMESCALE).round() as u64;
+        payload.extend_from_slice(&start.to_be_bytes());
+        let mut end = chapter.title.len().min(255);
+        while !chapter.title.is_char_boundary(end) {
+            end -= 1;
+        }
+        payload.push(end as u8);
+        payload.extend_from_slice(&chapter.title.as_bytes()[..end]);
+    }
+    make_box(b"chpl", &payload)
+}
+
+/// New `moov` with any existing `chpl` replaced by one for `chapters`.
+fn rebuild_moov(moov_payload: &[u8], chapters: &[OutputChapter]) -> Result<Vec<u8>, String> {
+    let chpl = chpl_box(chapters);
+    let (boxes, tail) = children(moov_payload)?;
+    let mut payload = Vec::with_capacity(moov_payload.len() + chpl.len() + 8);
+    let mut wrote_udta = false;
+    for (kind, header_len, bytes) in boxes {
+        if &kind != b"udta" || wrote_udta {
+            payload.extend_from_slice(bytes);
+            continue;
+        }
+        let (udta_boxes, udta_tail) = children(&bytes[header_len..])?;
+        let mut udta = Vec::new();
+        for (child_kind, _, child) in udta_boxes {
+            if &child_kind != b"chpl" {
+                udta.extend_from_slice(child);
+            }
+        }
+        udta.extend_from_slice(&chpl);
+        udta.extend_from_slice(udta_tail);
+        payload.extend_from_slice(&make_box(b"udta", &udta));
+        wrote_udta = true;
+    }
+    if !wrote_udta {
+        payload.extend_from_slice(&make_box(b"udta", &chpl));
+    }
+    payload.extend_from_slice(tail);
+    Ok(make_box(b"moov", &payload))
+}
+
+/// Shifts every `stco`/`co64` entry at or past `threshold` by `delta`.
+fn patch_chunk_offsets(payload: &mut [u8], threshold: u64, delta: i64) -> Result<(), String> {
+    let mut cursor = 0;
+    while cursor + 8 <= payload.len() {
+        let (kind, header_len, size) = box_at(&payload[cursor..])?;
+        let body = &mut payload[cursor + header_len..cursor + size];
+        if OFFSET_CONTAINERS.contains(&&kind) {
+            patch_chunk_offsets(body, threshold, delta)?;
+        } else if &kind == b"stco" || &kind == b"co64" {
+            let width = if &kind == b"stco" { 4 } else { 8 };
+            if body.len() < 8 {
+                return Err("chunk offset table is truncated".to_string());
+            }
+            let count = u32::from_be_bytes(body[4..8].try_into().unwrap()) as usize;
+            if body.len() < 8 + count * width {
+                return Err("chunk offset table is truncated".to_string());
+            }
+            for entry in body[8..8 + count * width].chunks_exact_mut(width) {
+                let offset = if width == 4 {
+                    u32::from_be_bytes(entry.try_into().unwrap()) as u64
+                } else {
+                    u64::from_be_bytes(entry.try_into().unwrap())
+                };
+                if offset < threshold {
+                    continue;
+                }
+                let shifted = offset
+                    .checked_add_signed(delta)
+                    .ok_or("chunk offset overflow")?;
+                if width == 4 {
+                    let shifted =
+                        u32::try_from(shifted).map_err(|_| "chunk offset exceeds stco range")?;
+                    entry.copy_from_slice(&shifted.to_be_bytes());
+                } else {
+                    entry.copy_from_slice(&shifted.to_be_bytes());
+                }
+            }
+        }
+        cursor += size;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapters() -> Vec<OutputChapter> {
+        vec![
+            OutputChapter {
+                start: 0.0,
+                end: 1.5,
+                title: "Intro".to_string(),
+            },
+            OutputChapter {
+                start: 1.5,
+                end: 3.0,
+                title: "Démo".to_string(),
+            },
+        ]
+    }
+
+    fn stco(offset: u32) -> Vec<u8> {
+        let mut payload = vec![0, 0, 0, 0, 0, 0, 0, 1];
+        payload.extend_from_slice(&offset.to_be_bytes());
+        make_box(b"stco", &payload)
+    }
+
+    fn moov(offset: u32) -> Vec<u8> {
+        let stbl = make_box(b"stbl", &stco(offset));
+        let minf = make_box(b"minf", &stbl);
+        let mdia = make_box(b"mdia", &minf);
+        let trak = make_box(b"trak", &mdia);
+        let mut payload = make_box(b"mvhd", &[0; 12]);
+        payload.extend_from_slice(&trak);
+        make_box(b"moov", &payload)
+    }
+
+    fn find<'a>(payload: &'a [u8], path: &[&[u8; 4]]) -> Option<&'a [u8]> {
+        let (boxes, _) = children(payload).ok()?;
+        let (_, header_len, bytes) = boxes.into_iter().find(|(kind, _, _)| kind == path[0])?;
+        if path.len() == 1 {
+            return Some(&bytes[header_len..]);
+        }
+        find(&bytes[header_len..], &path[1..])
+    }
+
+    fn splice(name: &str, moov_first: bool) -> Vec<u8> {
+        let ftyp = make_box(b"ftyp", b"isom\0\0\0\0");
+        let mdat = make_box(b"mdat", b"frame-data");
+        let mut file = ftyp.clone();
+        if moov_first {
+            let moov_len = moov(0).len();
+            let data_offset = (ftyp.len() + moov_len + 8) as u32;
+            file.extend_from_slice(&moov(data_offset));
+            file.extend_from_slice(&mdat);
+        } else {
+            file.extend_from_slice(&mdat);
+            file.extend_from_slice(&moov(ftyp.len() as u32 + 8));
+        }
+        let path = std::env::temp_dir().join(format!("sgt-chpl-{}-{name}.mp4", std::process::id()));
+        fs::write(&path, &file).unwrap();
+        write_chapter_atom(&path, &chapters()).expect("chapters are written");
+        let written = fs::read(&path).unwrap();
+        let _ = fs::remove_file(&path);
+        written
+    }
+
+    #[test]
+    fn chpl_atom_is_added_and_chunk_offsets_follow_the_media() {
+        for moov_first in [true, false] {
+            let file = splice(if moov_first { "front" } else { "back" }, moov_first);
+            let chpl = find(&file, &[b"moov", b"udta", b"chpl"]).expect("chpl box");
+            assert_eq!(chpl[8], 2);
+            assert_eq!(&chpl[9..17], &0_u64.to_be_bytes());
+            assert_eq!(&chpl[18..23], b"Intro");
+            assert_eq!(&chpl[23..31], &15_000_000_u64.to_be_bytes());
+            assert_eq!(chpl[31] as usize, "Démo".len());
+
+            let stco = find(
+                &file,
+                &[b"moov", b"trak", b"mdia", b"minf", b"stbl", b"stco"],
+            )
+            .expect("stco box");
+            let offset = u32::from_be_bytes(stco[8..12].try_into().unwrap()) as usize;
+            assert_eq!(&file[offset..offset + 10], b"frame-data");
+        }
+    }
+
+    #[test]
+    fn rewriting_replaces_an_existing_chapter_list() {
+        let once = rebuild_moov(&moov(0)[8..], &chapters()).unwrap();
+        let twice = rebuild_moov(&once[8..], &chapters()[..1]).unwrap();
+        let (udta, _) = children(find(&twice[8..], &[b"udta"]).unwrap()).unwrap();
+        assert_eq!(udta.len(), 1);
+        assert_eq!(udta[0].2[16], 1);
+    }
+}
@@ -92,9 +92,33 @@ fn build_single_clip_config(
             project_clip_start_sec,
             clip.duration,
         ),
+        platform: super::config::PlatformExportOptions {
+            preset: None,
+            auto_reframe: export.platform.auto_reframe,
+        },
     }
 }
 
+/// Sample a sorted track volume envelope at `time` with the mixer's cosine
+/// interpolation between points.
+pub(crate) fn track_volume_at(points: &[super::config::DeviceAudioPoint], time: f64) -> f64 {
+    if points.is_empty() {
+        return 1.0;
+    }
+    let idx = points.partition_point(|point| point.time < time);
+    if idx == 0 {
+        return points[0].volume;
+    }
+    if idx >= points.len() {
+        return points.last().map(|point| point.volume).unwrap_or(1.0);
+    }
+    let left = &points[idx - 1];
+    let right = &points[idx];
+    let ratio = ((time - left.time) / (right.time - left.time).max(0.0001)).clamp(0.0, 1.0);
+    let cos_t = (1.0 - (ratio * std::f64::consts::PI).cos()) / 2.0;
+    left.volume + (right.volume - left.volume) * cos_t
+}
+
 /// Translate project-relative track volume points into clip-relative ones for
 /// the clip occupying `[clip_start, clip_start + clip_duration]`.
 pub(crate) fn slice_track_volume_points(
@@ -105,23 +129,6 @@ pub(crate) fn slice_track_volume_points(
     if clip_duration <= 0.0 || points.is_empty() {
         return Vec::new();
     }
-    fn volume_at(points: &[super::config::DeviceAudioPoint], time: f64) -> f64 {
-        if points.is_empty() {
-            return 1.0;
-        }
-        let idx = points.partition_point(|point| point.time < time);
-        if idx == 0 {
-            return points[0].volume;
-        }
-        if idx >= points.len() {
-            return points.last().map(|point| point.volume).unwrap_or(1.0);
-        }
-        let left = &points[idx - 1];
-        let right = &points[idx];
-        let ratio = ((time - left.time) / (right.time - left.time).max(0.0001)).clamp(0.0, 1.0);
-        let cos_t = (1.0 - (ratio * std::f64::consts::PI).cos()) / 2.0;
-        left.volume + (right.volume - left.volume) * cos_t
-    }
 
     let mut sorted = points.to_vec();
     sorted.sort_by(|a, b| {
@@ -133,7 +140,7 @@ pub(crate) fn slice_track_volume_points(
     let mut sliced = Vec::new();
     sliced.push(super::config::DeviceAudioPoint {
         time: 0.0,
-        volume: volume_at(&sorted, clip_start),
+        volume: track_volume_at(&sorted, clip_start),
     });
     sliced.extend(sorted.iter().filter_map(|point| {
         let local = point.time - clip_start;
@@ -148,7 +155,7 @@ pub(crate) fn slice_track_volume_points(
     }));
     sliced.push(super::config::DeviceAudioPoint {
         time: clip_duration,
-        volume: volume_at(&sorted, clip_start + clip_duration),
+        volume: track_volume_at(&sorted, clip_start + clip_duration),
     });
     sliced
 }
@@ -210,7 +217,8 @@ pub fn start_composition_export(args: serde_json::Value) -> Result<serde_json::V
     let _active_export_guard = super::ExportActiveGuard::activate()?;
     super::EXPORT_CANCELLED.store(false, Ordering::SeqCst);
 
-    let export: CompositionExportConfig = parse_json_with_path(args)?;
+    let mut export: CompositionExportConfig = parse_json_with_path(args)?;
+    export.apply_platform_preset();
     export.validate()?;
 
     let (temp_owner_root, temp_root) = temp_export_root(&export.session_id)?;
@@ -348,7 +356,15 @@ pub fn start_composition_export(args: serde_json::Value) -> Result<serde_json::V
         }
 
         let mut artifacts = Vec::new();
+        let mut warnings = Vec::new();
         if export.format == "mp4" || export.format == "both" {
+            // Chapters go in before the size is read; the atom grows the file.
+            let (chapter_artifacts, chapter_warnings) = super::chapters::write_export_chapters(
+                &export.chapters,
+                &export.clips,
+                &final_mp4_path,
+            )?;
+            warnings.extend(chapter_warnings);
             let mp4_bytes = fs::metadata(&final_mp4_path).map(|m| m.len()).unwrap_or(0);
             artifacts.push(json!({
                 "format": "mp4",
@@ -356,6 +372,7 @@ pub fn start_composition_export(args: serde_json::Value) -> Result<serde_json::V
                 "bytes": mp4_bytes,
                 "primary": export.format != "gif",
             }));
+            artifacts.extend(chapter_artifacts);
         }
 
         if wants_gif {
@@ -395,6 +412,7 @@ pub fn start_composition_export(args: serde_json::Value) -> Result<serde_json::V
             "status": "success",
             "path": primary_path,
             "artifacts": artifacts,
+            "warnings": warnings,
         }))
     })();
 
@@ -402,6 +420,9 @@ pub fn start_composition_export(args: serde_json::Value) -> Result<serde_json::V
         cleanup_file(&final_mp4_path);
         cleanup_file(&final_gif_path);
         cleanup_file(&merged_temp_mp4);
+        for path in super::chapters::sidecar_paths(&final_mp4_path) {
+            cleanup_file(&path);
+        }
     }
     if let Err(error) = remove_temp_export_tree(&temp_owner_root, &temp_root) {
         eprintln!("[CompositionExport] {error}");
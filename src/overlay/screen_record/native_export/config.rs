@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+pub use super::presets::{ExportPresetId, PlatformExportOptions};
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ExportConfig {
@@ -49,6 +51,8 @@ pub struct ExportConfig {
     /// Track-global volume envelope for the Narration track.
     #[serde(default)]
     pub narration_track_volume_points: Vec<DeviceAudioPoint>,
+    #[serde(flatten)]
+    pub platform: PlatformExportOptions,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -108,6 +112,50 @@ pub struct CompositionExportConfig {
     /// Project-wide track-global Narration track volume envelope.
     #[serde(default)]
     pub narration_track_volume_points: Vec<DeviceAudioPoint>,
+    /// Chapter markers in compact project time.
+    #[serde(default)]
+    pub chapters: Vec<ChapterMarker>,
+    #[serde(flatten)]
+    pub platform: PlatformExportOptions,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ChapterMarker {
+    pub time: f64,
+    pub title: String,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimelineInterchangeFormat {
+    Fcpxml,
+    Otio,
+    Edl,
+}
+
+/// A subtitle cue in its clip's SOURCE time, like zoom blocks.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineSubtitleCue {
+    pub clip_id: String,
+    pub start_time: f64,
+    pub end_time: f64,
+    pub text: String,
+}
+
+/// Edited-timeline export to NLE interchange formats. Carries the same
+/// composition model as a video export; the render-only fields are ignored.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineInterchangeConfig {
+    pub target: TimelineInterchangeFormat,
+    #[serde(default)]
+    pub project_name: String,
+    #[serde(default)]
+    pub subtitle_cues: Vec<TimelineSubtitleCue>,
+    #[serde(flatten)]
+    pub timeline: CompositionExportConfig,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
@@ -0,0 +1,335 @@
+// Timeline interchange export: writes the edited composition as FCPXML,
+// OpenTimelineIO JSON or a CMX3600 EDL that references the raw recordings, so
+// editing can continue in Resolve / Premiere / Final Cut.
+
+mod edl;
+mod fcpxml;
+mod model;
+mod otio;
+pub(super) mod time_map;
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::config::{Config, secrets};
+
+use super::config::{TimelineInterchangeConfig, TimelineInterchangeFormat};
+
+const MAX_SUBTITLE_CUES: usize = 100_000;
+const MAX_TEXT_BYTES: usize = 4 * 1024;
+
+impl TimelineInterchangeConfig {
+    fn validate(&self) -> Result<(), String> {
+        self.timeline.validate()?;
+        if self.project_name.len() > MAX_TEXT_BYTES || self.project_name.contains('\0') {
+            return Err("project name is too long or contains a null byte".to_string());
+        }
+        if self.subtitle_cues.len() > MAX_SUBTITLE_CUES {
+            return Err("subtitle cues contain too many items".to_string());
+        }
+        for cue in &self.subtitle_cues {
+            super::validation::validate_identifier(&cue.clip_id, "subtitle clip id")?;
+            if cue.text.len() > MAX_TEXT_BYTES || cue.text.contains('\0') {
+                return Err("subtitle text is too long or contains a null byte".to_string());
+            }
+            let valid_range =
+                cue.start_time >= 0.0 && cue.end_time >= cue.start_time && cue.end_time.is_finite();
+            if !valid_range {
+                return Err("subtitle cues contain an invalid timeline range".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn start_timeline_interchange_export(
+    args: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let app_config = crate::APP
+        .lock()
+        .map_err(|_| "App lock poisoned".to_string())?
+        .config
+        .clone();
+    export_timeline(args, &app_config)
+}
+
+fn export_timeline(
+    args: serde_json::Value,
+    app_config: &Config,
+) -> Result<serde_json::Value, String> {
+    // Names and captions end up in the file verbatim; no API key may.
+    let args = secrets::without_secrets(&args, app_config)?;
+    let config: TimelineInterchangeConfig = parse_json_with_path(args)?;
+    config.validate()?;
+
+    let timeline = model::build_timeline(&config);
+    let mut warnings = timeline.warnings.clone();
+    let (document, extension) = match config.target {
+        TimelineInterchangeFormat::Fcpxml => (fcpxml::write(&timeline), "fcpxml"),
+        TimelineInterchangeFormat::Otio => (otio::write(&timeline), "otio"),
+        TimelineInterchangeFormat::Edl => {
+            let (document, edl_warnings) = edl::write(&timeline);
+            warnings.extend(edl_warnings);
+            (document, "edl")
+        }
+    };
+
+    let final_dir = if config.timeline.output_dir.trim().is_empty() {
+        dirs::download_dir().unwrap_or_else(|| PathBuf::from("."))
+    } else {
+        PathBuf::from(config.timeline.output_dir.trim())
+    };
+    fs::create_dir_all(&final_dir)
+        .map_err(|e| format!("Failed to create output directory: {e}"))?;
+    let timestamp_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let path = final_dir.join(format!("SGT_Timeline_{timestamp_ms}.{extension}"));
+    fs::write(&path, document.as_bytes())
+        .map_err(|e| format!("Failed to write timeline file: {e}"))?;
+
+    Ok(json!({
+        "status": "success",
+        "path": path.to_string_lossy(),
+        "format": config.target,
+        "warnings": warnings,
+    }))
+}
+
+/// `file://` URL for a local or UNC media path, percent-encoding each segment
+/// but keeping the drive letter readable (`file:///C:/Videos/a%20b.mp4`).
+fn file_url(path: &str) -> String {
+    let normalized = path.replace('\\', "/");
+    let encoded = normalized
+        .split('/')
+        .map(|segment| {
+            let is_drive = segment.len() == 2
+                && segment.ends_with(':')
+                && segment.as_bytes()[0].is_ascii_alphabetic();
+            if is_drive {
+                segment.to_string()
+            } else {
+                urlencoding::encode(segment).into_owned()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    if normalized.starts_with("//") {
+        format!("file:{encoded}")
+    } else if normalized.starts_with('/') {
+        format!("file://{encoded}")
+    } else {
+        format!("file:///{encoded}")
+    }
+}
+
+/// File name without extension for either path separator, so Windows paths
+/// read the same however they were typed.
+fn media_stem(path: &str) -> String {
+    let file_name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    match file_name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem.to_string(),
+        _ => file_name.to_string(),
+    }
+}
+
+fn parse_json_with_path<T: DeserializeOwned>(args: serde_json::Value) -> Result<T, String> {
+    let json =
+        serde_json::to_string(&args).map_err(|e| format!("Serialize interchange args: {e}"))?;
+    let mut deserializer = serde_json::Deserializer::from_str(&json);
+    serde_path_to_error::deserialize(&mut deserializer)
+        .map_err(|error| format!("{} at {}", error.inner(), error.path()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(target: &str) -> TimelineInterchangeConfig {
+        serde_json::from_value(args(target)).expect("interchange config parses")
+    }
+
+    fn args(target: &str) -> serde_json::Value {
+        let background = json!({
+            "scale": 100.0,
+            "borderRadius": 0.0,
+            "backgroundType": "solid",
+            "shadow": 0.0,
+            "cursorScale": 1.0
+        });
+        json!({
+            "target": target,
+            "projectName": "Demo & Test",
+            "sessionId": "session",
+            "width": 1920,
+            "height": 1080,
+            "framerate": 30,
+            "clips": [{
+                "jobId": "job-1",
+                "clipId": "clip-1",
+                "clipName": "Intro",
+                "sourceVideoPath": r"C:\Rec\intro video.mp4",
+                "deviceAudioPath": r"C:\Rec\intro video.mp4",
+                "micAudioPath": r"C:\Rec\intro_mic.wav",
+                "trimStart": 0.0,
+                "duration": 8.0,
+                "segment": {
+                    "crop": null,
+                    "trimSegments": [
+                        { "startTime": 0.0, "endTime": 4.0 },
+                        { "startTime": 6.0, "endTime": 10.0 }
+                    ],
+                    "speedPoints": [
+                        { "time": 0.0, "speed": 1.0 },
+                        { "time": 4.0, "speed": 1.0 },
+                        { "time": 6.0, "speed": 2.0 },
+                        { "time": 10.0, "speed": 2.0 }
+                    ],
+                    "zoomBlocks": [{
+                        "startTime": 1.0,
+                        "endTime": 3.0,
+                        "easeIn": 0.5,
+                        "easeOut": 0.5,
+                        "zoomFactor": 2.0,
+                        "positionX": 0.25,
+                        "positionY": 0.25
+                    }],
+                    "micAudioPoints": [
+                        { "time": 0.0, "volume": 0.5 },
+                        { "time": 10.0, "volume": 0.5 }
+                    ]
+                },
+                "backgroundConfig": background
+            }],
+            "narrationSegments": [{
+                "rawAudioPath": r"C:\Narration\take1.wav",
+                "duration": 3.0,
+                "startTime": 1.0,
+                "inPoint": 0.0,
+                "outPoint": 3.0
+            }],
+            "subtitleCues": [
+                { "clipId": "clip-1", "startTime": 0.5, "endTime": 2.0, "text": "Hello <world>" }
+            ]
+        })
+    }
+
+    #[test]
+    fn exported_timelines_never_carry_an_api_key() {
+        let key = "gsk_timeline_interchange_key";
+        let app_config = Config {
+            api_key: key.to_string(),
+            ..Config::default()
+        };
+        let output_dir =
+            std::env::temp_dir().join(format!("sgt-interchange-secrets-{}", std::process::id()));
+        for target in ["fcpxml", "otio", "edl"] {
+            let mut args = args(target);
+            args["projectName"] = json!(format!("Demo {key}"));
+            args["subtitleCues"][0]["text"] = json!(format!("my key is {key}"));
+            args["outputDir"] = json!(output_dir.to_string_lossy());
+
+            let exported = export_timeline(args, &app_config).unwrap();
+            let document = fs::read_to_string(exported["path"].as_str().unwrap()).unwrap();
+            assert!(
+                document.contains("Demo") && !document.contains(key),
+                "{target}"
+            );
+        }
+        fs::remove_dir_all(output_dir).unwrap();
+    }
+
+    #[test]
+    fn file_urls_keep_drive_letters_and_encode_segments() {
+        assert_eq!(
+            file_url(r"C:\Rec\intro video.mp4"),
+            "file:///C:/Rec/intro%20video.mp4"
+        );
+        assert_eq!(
+            file_url(r"\\nas\share\a#b.wav"),
+            "file://nas/share/a%23b.wav"
+        );
+        assert_eq!(media_stem(r"C:\Rec\intro video.mp4"), "intro video");
+        assert_eq!(media_stem("/tmp/.hidden"), ".hidden");
+    }
+
+    #[test]
+    fn timeline_model_covers_every_track() {
+        let config = config("otio");
+        config.validate().expect("fixture is valid");
+        let timeline = model::build_timeline(&config);
+        // 4 s at 1x plus 4 s at 2x.
+        assert!((timeline.duration - 6.0).abs() < 1e-3);
+        let names: Vec<&str> = timeline.tracks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["Screen", "Device Audio", "Microphone", "Narration"]);
+        let screen = &timeline.tracks[0];
+        assert_eq!(screen.items.len(), 2);
+        assert!(!screen.items[0].transform.is_empty());
+        assert!(screen.items[1].transform.is_empty());
+        assert!((screen.items[1].speed - 2.0).abs() < 1e-3);
+        assert!(!timeline.tracks[2].items[0].volume.is_empty());
+        assert_eq!(timeline.captions.len(), 1);
+    }
+
+    #[test]
+    fn edl_events_use_timecode_and_motion_effects() {
+        let (edl, warnings) = edl::write(&model::build_timeline(&config("edl")));
+        assert!(edl.starts_with("TITLE: Demo & Test\nFCM: NON-DROP FRAME\n"));
+        assert!(edl.contains(
+            "001  SGT00001 V     C        00:00:00:00 00:00:04:00 01:00:00:00 01:00:04:00"
+        ));
+        assert!(edl.contains("M2   SGT00001       060.0                00:00:06:00"));
+        assert!(edl.contains("* SOURCE FILE: C:\\Rec\\intro_mic.wav"));
+        assert!(edl.contains("* SGT ZOOM 01:00:01:15 SCALE 2.000"));
+        assert!(warnings.iter().any(|warning| warning.contains("Narration")));
+        assert!(warnings.iter().any(|warning| warning.contains("subtitles")));
+    }
+
+    #[test]
+    fn otio_document_uses_core_schemas() {
+        let document: serde_json::Value =
+            serde_json::from_str(&otio::write(&model::build_timeline(&config("otio"))))
+                .expect("otio is json");
+        assert_eq!(document["OTIO_SCHEMA"], "Timeline.1");
+        let tracks = document["tracks"]["children"].as_array().unwrap();
+        let screen = tracks[0]["children"].as_array().unwrap();
+        assert_eq!(screen[1]["effects"][0]["OTIO_SCHEMA"], "LinearTimeWarp.1");
+        assert_eq!(
+            screen[0]["media_references"]["DEFAULT_MEDIA"]["target_url"],
+            "file:///C:/Rec/intro%20video.mp4"
+        );
+        let narration = tracks[3]["children"].as_array().unwrap();
+        assert_eq!(narration[0]["OTIO_SCHEMA"], "Gap.1");
+        assert_eq!(
+            document["tracks"]["markers"][0]["metadata"]["sgt"]["kind"],
+            "subtitle"
+        );
+    }
+
+    #[test]
+    fn fcpxml_is_well_formed_and_escaped() {
+        let xml = fcpxml::write(&model::build_timeline(&config("fcpxml")));
+        let mut reader = quick_xml::Reader::from_str(&xml);
+        let mut depth = 0_i32;
+        loop {
+            match reader.read_event().expect("fcpxml parses") {
+                quick_xml::events::Event::Start(_) => depth += 1,
+                quick_xml::events::Event::End(_) => depth -= 1,
+                quick_xml::events::Event::Eof => break,
+                _ => {}
+            }
+        }
+        assert_eq!(depth, 0);
+        assert!(xml.contains("<project name=\"Demo &amp; Test\">"));
+        assert!(xml.contains("Hello &lt;world&gt;"));
+        assert!(xml.contains("<timeMap>"));
+        assert!(xml.contains("<param name=\"scale\">"));
+        assert!(xml.contains("<adjust-volume>"));
+        assert!(xml.contains("srcEnable=\"audio\""));
+    }
+}
@@ -0,0 +1,147 @@
+// CMX3600 EDL writer. An EDL carries one picture channel and two audio
+// channels, so the screen track maps to V, device audio to A and microphone
+// to A2; anything else is reported back as a warning. Zoom keyframes are kept
+// as `* SGT ZOOM` comments under their event.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use super::model::{InterchangeItem, InterchangeTimeline, TrackRole, frames};
+
+// Record timecode starts at the conventional 01:00:00:00.
+const RECORD_START_SECONDS: i64 = 3600;
+
+struct Event<'a> {
+    channel: &'static str,
+    item: &'a InterchangeItem,
+}
+
+pub(super) fn write(timeline: &InterchangeTimeline) -> (String, Vec<String>) {
+    let fps = timeline.fps;
+    let record_offset = RECORD_START_SECONDS * fps as i64;
+    let mut warnings = Vec::new();
+    let mut events: Vec<Event<'_>> = Vec::new();
+    let mut seen_roles: Vec<TrackRole> = Vec::new();
+
+    for track in &timeline.tracks {
+        let first_of_role = !seen_roles.contains(&track.role);
+        seen_roles.push(track.role);
+        let channel = match track.role {
+            TrackRole::Video if first_of_role => "V",
+            TrackRole::DeviceAudio if first_of_role => "A",
+            TrackRole::MicAudio if first_of_role => "A2",
+            _ => {
+                if !track.items.is_empty() {
+                    warnings.push(format!(
+                        "EDL has no channel for the {} track; it was left out",
+                        track.name
+                    ));
+                }
+                continue;
+            }
+        };
+        events.extend(track.items.iter().map(|item| Event { channel, item }));
+    }
+    if !timeline.captions.is_empty() {
+        warnings.push("EDL cannot carry subtitles; export them as SRT instead".to_string());
+    }
+    if timeline
+        .tracks
+        .iter()
+        .any(|track| track.items.iter().any(|item| !item.volume.is_empty()))
+    {
+        warnings.push("EDL cannot carry volume envelopes; levels were left flat".to_string());
+    }
+    events.sort_by(|a, b| {
+        a.item
+            .record_in
+            .total_cmp(&b.item.record_in)
+            .then_with(|| channel_rank(a.channel).cmp(&channel_rank(b.channel)))
+    });
+
+    let mut reels: HashMap<&str, String> = HashMap::new();
+    let mut edl = String::new();
+    let _ = writeln!(edl, "TITLE: {}", sanitize_line(&timeline.name));
+    let _ = writeln!(edl, "FCM: NON-DROP FRAME\n");
+    let mut number = 0;
+    for event in events {
+        let item = event.item;
+        let record_in = frames(item.record_in, fps);
+        let duration = frames(item.record_out, fps) - record_in;
+        if duration <= 0 {
+            continue;
+        }
+        let source_in = frames(item.source_in, fps);
+        let source_out = if (item.speed - 1.0).abs() > 1e-3 {
+            frames(item.source_out(), fps).max(source_in + 1)
+        } else {
+            source_in + duration
+        };
+        let next_reel = format!("SGT{:05}", reels.len() + 1);
+        let reel = reels
+            .entry(item.media_path.as_str())
+            .or_insert(next_reel)
+            .clone();
+        number += 1;
+        let _ = writeln!(
+            edl,
+            "{number:03}  {reel:<8} {:<5} C        {} {} {} {}",
+            event.channel,
+            timecode(source_in, fps),
+            timecode(source_out, fps),
+            timecode(record_offset + record_in, fps),
+            timecode(record_offset + record_in + duration, fps),
+        );
+        if (item.speed - 1.0).abs() > 1e-3 {
+            let _ = writeln!(
+                edl,
+                "M2   {reel:<8}       {:>05.1}                {}",
+                item.speed * fps as f64,
+                timecode(source_in, fps),
+            );
+        }
+        let _ = writeln!(edl, "* FROM CLIP NAME: {}", sanitize_line(&item.name));
+        let _ = writeln!(edl, "* SOURCE FILE: {}", sanitize_line(&item.media_path));
+        for key in &item.transform {
+            let _ = writeln!(
+                edl,
+                "* SGT ZOOM {} SCALE {:.3} CENTER {:.4} {:.4}",
+                timecode(record_offset + frames(key.record_time, fps), fps),
+                key.zoom,
+                key.center_x,
+                key.center_y,
+            );
+        }
+        edl.push('\n');
+    }
+    (edl, warnings)
+}
+
+fn channel_rank(channel: &str) -> u8 {
+    match channel {
+        "V" => 0,
+        "A" => 1,
+        _ => 2,
+    }
+}
+
+fn timecode(frame_count: i64, fps: u32) -> String {
+    let fps = fps.max(1) as i64;
+    let frame_count = frame_count.max(0);
+    let frames = frame_count % fps;
+    let total_seconds = frame_count / fps;
+    format!(
+        "{:02}:{:02}:{:02}:{:02}",
+        total_seconds / 3600,
+        (total_seconds / 60) % 60,
+        total_seconds % 60,
+        frames
+    )
+}
+
+fn sanitize_line(value: &str) -> String {
+    value
+        .chars()
+        .map(|ch| if ch.is_control() { ' ' } else { ch })
+        .collect()
+}
@@ -0,0 +1,285 @@
+// Final Cut Pro XML (FCPXML 1.10) writer.
+//
+// Every item is a connected clip on a single zero-based gap in the primary
+// storyline. Connected-clip offsets are expressed in the parent's local time,
+// so anchoring to a gap that starts at 0s keeps every offset equal to its
+// record time and lets each track map to one lane.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use quick_xml::escape::escape;
+
+use super::model::{InterchangeItem, InterchangeTimeline, TrackRole, frames};
+use super::{file_url, media_stem};
+
+struct Asset {
+    id: String,
+    path: String,
+    has_video: bool,
+    has_audio: bool,
+    duration_frames: i64,
+}
+
+pub(super) fn write(timeline: &InterchangeTimeline) -> String {
+    let fps = timeline.fps;
+    let time = |seconds: f64| rational(frames(seconds, fps), fps);
+
+    // r1 is the sequence format; assets follow in first-use order.
+    let mut assets: Vec<Asset> = Vec::new();
+    let mut asset_by_path: HashMap<String, usize> = HashMap::new();
+    for track in &timeline.tracks {
+        for item in &track.items {
+            let index = *asset_by_path
+                .entry(item.media_path.clone())
+                .or_insert_with(|| {
+                    assets.push(Asset {
+                        id: format!("r{}", assets.len() + 2),
+                        path: item.media_path.clone(),
+                        has_video: false,
+                        has_audio: false,
+                        duration_frames: 0,
+                    });
+                    assets.len() - 1
+                });
+            let asset = &mut assets[index];
+            asset.has_video |= track.role.is_video();
+            asset.has_audio |= !track.role.is_video();
+            asset.duration_frames = asset
+                .duration_frames
+                .max(frames(item.source_out(), fps).saturating_add(1));
+        }
+    }
+
+    let mut xml = String::new();
+    xml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE fcpxml>\n\n");
+    xml.push_str("<fcpxml version=\"1.10\">\n    <resources>\n");
+    let _ = writeln!(
+        xml,
+        "        <format id=\"r1\" name=\"FFVideoFormatRateUndefined\" frameDuration=\"{}\" width=\"{}\" height=\"{}\"/>",
+        rational(1, fps),
+        timeline.width,
+        timeline.height
+    );
+    for asset in &assets {
+        let name = media_stem(&asset.path);
+        let _ = write!(
+            xml,
+            "        <asset id=\"{}\" name=\"{}\" start=\"0s\" duration=\"{}\" hasVideo=\"{}\"",
+            asset.id,
+            escape(name.as_str()),
+            rational(asset.duration_frames, fps),
+            u8::from(asset.has_video),
+        );
+        if asset.has_video {
+            xml.push_str(" format=\"r1\"");
+        }
+        if asset.has_audio {
+            xml.push_str(
+                " hasAudio=\"1\" audioSources=\"1\" audioChannels=\"2\" audioRate=\"48000\"",
+            );
+        }
+        let _ = writeln!(
+            xml,
+            ">\n            <media-rep kind=\"original-media\" src=\"{}\"/>\n        </asset>",
+            escape(file_url(&asset.path).as_str())
+        );
+    }
+    xml.push_str("    </resources>\n    <library>\n");
+    xml.push_str("        <event name=\"Screen Goated Toolbox\">\n");
+    let name = escape(timeline.name.as_str());
+    let duration = time(timeline.duration);
+    let _ = writeln!(xml, "            <project name=\"{name}\">");
+    let _ = writeln!(
+        xml,
+        "                <sequence format=\"r1\" duration=\"{duration}\" tcStart=\"0s\" tcFormat=\"NDF\" audioLayout=\"stereo\" audioRate=\"48k\">"
+    );
+    xml.push_str("                    <spine>\n");
+    let _ = writeln!(
+        xml,
+        "                        <gap name=\"{name}\" offset=\"0s\" start=\"0s\" duration=\"{duration}\">"
+    );
+
+    let indent = "                            ";
+    let mut video_lane = 0;
+    let mut audio_lane = 0;
+    for track in &timeline.tracks {
+        let lane = if track.role.is_video() {
+            video_lane += 1;
+            video_lane
+        } else {
+            audio_lane -= 1;
+            audio_lane
+        };
+        for item in &track.items {
+            let asset = &assets[asset_by_path[&item.media_path]];
+            write_clip(
+                &mut xml, indent, timeline, item, track.role, &asset.id, lane,
+            );
+        }
+    }
+
+    let caption_lane = video_lane + 1;
+    for (index, caption) in timeline.captions.iter().enumerate() {
+        let _ = writeln!(
+            xml,
+            "{indent}<caption lane=\"{caption_lane}\" offset=\"{}\" duration=\"{}\" role=\"SRT?captionFormat=SRT.en\">",
+            time(caption.record_in),
+            rational(
+                frames(caption.record_out, fps) - frames(caption.record_in, fps),
+                fps
+            ),
+        );
+        let _ = writeln!(
+            xml,
+            "{indent}    <text><text-style ref=\"ts1\">{}</text-style></text>",
+            escape(caption.text.as_str())
+        );
+        // A text style is declared once and referenced by every caption.
+        if index == 0 {
+            let _ = writeln!(
+                xml,
+                "{indent}    <text-style-def id=\"ts1\"><text-style font=\"Helvetica\" fontSize=\"13\"/></text-style-def>"
+            );
+        }
+        let _ = writeln!(xml, "{indent}</caption>");
+    }
+
+    xml.push_str("                        </gap>\n");
+    xml.push_str("                    </spine>\n");
+    xml.push_str("                </sequence>\n            </project>\n");
+    xml.push_str("        </event>\n    </library>\n</fcpxml>\n");
+    xml
+}
+
+fn write_clip(
+    xml: &mut String,
+    indent: &str,
+    timeline: &InterchangeTimeline,
+    item: &InterchangeItem,
+    role: TrackRole,
+    asset_id: &str,
+    lane: i32,
+) {
+    let fps = timeline.fps;
+    let record_in = frames(item.record_in, fps);
+    let duration = frames(item.record_out, fps) - record_in;
+    if duration <= 0 {
+        return;
+    }
+    // The clip's local timeline starts at `start`; with a timeMap it runs at
+    // record rate and the map converts it to media time.
+    let start = frames(item.source_in, fps);
+    let local = |record_time: f64| rational(start + frames(record_time, fps) - record_in, fps);
+    let src_enable = if role.is_video() { "video" } else { "audio" };
+    let _ = write!(
+        xml,
+        "{indent}<asset-clip ref=\"{asset_id}\" lane=\"{lane}\" offset=\"{}\" name=\"{}\" start=\"{}\" duration=\"{}\" srcEnable=\"{src_enable}\"",
+        rational(record_in, fps),
+        escape(item.name.as_str()),
+        rational(start, fps),
+        rational(duration, fps),
+    );
+    let inner = format!("{indent}    ");
+    let mut body = String::new();
+    if (item.speed - 1.0).abs() > 1e-3 {
+        let media_end = item.source_in + duration as f64 / fps as f64 * item.speed;
+        let _ = writeln!(
+            body,
+            "{inner}<timeMap>\n{inner}    <timept time=\"{}\" value=\"{}\" interp=\"linear\"/>\n{inner}    <timept time=\"{}\" value=\"{}\" interp=\"linear\"/>\n{inner}</timeMap>",
+            rational(start, fps),
+            rational(start, fps),
+            rational(start + duration, fps),
+            rational(frames(media_end, fps), fps),
+        );
+    }
+    if let Some(crop) = item.crop.as_ref().filter(|_| role == TrackRole::Video) {
+        let _ = writeln!(
+            body,
+            "{inner}<adjust-crop mode=\"trim\">\n{inner}    <trim-rect left=\"{:.4}\" top=\"{:.4}\" right=\"{:.4}\" bottom=\"{:.4}\"/>\n{inner}</adjust-crop>",
+            crop.x * 100.0,
+            crop.y * 100.0,
+            (1.0 - crop.x - crop.width).max(0.0) * 100.0,
+            (1.0 - crop.y - crop.height).max(0.0) * 100.0,
+        );
+    }
+    if !item.transform.is_empty() {
+        // FCP positions are in percent of frame height, y-up, relative to the
+        // frame centre; moving the zoom centre to frame centre needs the
+        // opposite offset scaled by the zoom.
+        let aspect = timeline.width as f64 / timeline.height.max(1) as f64;
+        let _ = writeln!(body, "{inner}<adjust-transform>");
+        let _ = writeln!(
+            body,
+            "{inner}    <param name=\"position\">\n{inner}        <keyframeAnimation>"
+        );
+        for key in &item.transform {
+            let _ = writeln!(
+                body,
+                "{inner}            <keyframe time=\"{}\" value=\"{:.4} {:.4}\"/>",
+                local(key.record_time),
+                (0.5 - key.center_x) * key.zoom * aspect * 100.0,
+                (key.center_y - 0.5) * key.zoom * 100.0,
+            );
+        }
+        let _ = writeln!(
+            body,
+            "{inner}        </keyframeAnimation>\n{inner}    </param>"
+        );
+        let _ = writeln!(
+            body,
+            "{inner}    <param name=\"scale\">\n{inner}        <keyframeAnimation>"
+        );
+        for key in &item.transform {
+            let _ = writeln!(
+                body,
+                "{inner}            <keyframe time=\"{}\" value=\"{:.4} {:.4}\"/>",
+                local(key.record_time),
+                key.zoom,
+                key.zoom,
+            );
+        }
+        let _ = writeln!(
+            body,
+            "{inner}        </keyframeAnimation>\n{inner}    </param>"
+        );
+        let _ = writeln!(body, "{inner}</adjust-transform>");
+    }
+    if !item.volume.is_empty() {
+        let _ = writeln!(
+            body,
+            "{inner}<adjust-volume>\n{inner}    <param name=\"amount\">\n{inner}        <keyframeAnimation>"
+        );
+        for key in &item.volume {
+            let _ = writeln!(
+                body,
+                "{inner}            <keyframe time=\"{}\" value=\"{}\"/>",
+                local(key.record_time),
+                decibels(key.volume),
+            );
+        }
+        let _ = writeln!(
+            body,
+            "{inner}        </keyframeAnimation>\n{inner}    </param>\n{inner}</adjust-volume>"
+        );
+    }
+    if body.is_empty() {
+        xml.push_str("/>\n");
+    } else {
+        let _ = write!(xml, ">\n{body}{indent}</asset-clip>\n");
+    }
+}
+
+fn rational(frame_count: i64, fps: u32) -> String {
+    if frame_count == 0 {
+        return "0s".to_string();
+    }
+    format!("{frame_count}/{fps}s")
+}
+
+fn decibels(volume: f64) -> String {
+    if volume <= 0.0001 {
+        return "-96dB".to_string();
+    }
+    format!("{:.2}dB", 20.0 * volume.log10())
+}
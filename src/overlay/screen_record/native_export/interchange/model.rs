@@ -0,0 +1,521 @@
+// Format-neutral edited timeline shared by the FCPXML, OTIO and EDL writers.
+// All times are seconds on the record (output) timeline unless a field says
+// otherwise; writers quantize to frames themselves.
+
+use std::collections::HashMap;
+
+use super::super::camera_path::to_viewport_center;
+use super::super::composition::track_volume_at;
+use super::super::config::{
+    CropRect, DeviceAudioPoint, ImportedAudioSegmentConfig, TimelineInterchangeConfig, ZoomBlock,
+};
+use super::time_map::{ClipPlacement, ProjectTimeMap, TimelineEdit};
+
+const MIN_ITEM_SEC: f64 = 1e-3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TrackRole {
+    Video,
+    Webcam,
+    DeviceAudio,
+    MicAudio,
+    Audio,
+    Narration,
+}
+
+impl TrackRole {
+    pub(super) fn is_video(self) -> bool {
+        matches!(self, Self::Video | Self::Webcam)
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Video => "Screen",
+            Self::Webcam => "Webcam",
+            Self::DeviceAudio => "Device Audio",
+            Self::MicAudio => "Microphone",
+            Self::Audio => "Audio",
+            Self::Narration => "Narration",
+        }
+    }
+}
+
+/// Zoom state at one record time: scale factor plus the visible viewport
+/// centre in normalized source-frame coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct TransformKeyframe {
+    pub record_time: f64,
+    pub zoom: f64,
+    pub center_x: f64,
+    pub center_y: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct VolumeKeyframe {
+    pub record_time: f64,
+    pub volume: f64,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct InterchangeItem {
+    pub name: String,
+    pub media_path: String,
+    /// Media time at `record_in`.
+    pub source_in: f64,
+    pub record_in: f64,
+    pub record_out: f64,
+    /// Media seconds consumed per record second.
+    pub speed: f64,
+    pub crop: Option<CropRect>,
+    pub transform: Vec<TransformKeyframe>,
+    pub volume: Vec<VolumeKeyframe>,
+}
+
+impl InterchangeItem {
+    pub(super) fn record_duration(&self) -> f64 {
+        self.record_out - self.record_in
+    }
+
+    pub(super) fn source_out(&self) -> f64 {
+        self.source_in + self.record_duration() * self.speed
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct InterchangeTrack {
+    pub name: String,
+    pub role: TrackRole,
+    /// Sorted by `record_in`, never overlapping.
+    pub items: Vec<InterchangeItem>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct InterchangeCaption {
+    pub record_in: f64,
+    pub record_out: f64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct InterchangeTimeline {
+    pub name: String,
+    pub fps: u32,
+    pub width: u32,
+    pub height: u32,
+    pub duration: f64,
+    pub tracks: Vec<InterchangeTrack>,
+    pub captions: Vec<InterchangeCaption>,
+    pub warnings: Vec<String>,
+}
+
+pub(super) fn frames(seconds: f64, fps: u32) -> i64 {
+    (seconds * fps as f64).round() as i64
+}
+
+pub(super) fn build_timeline(config: &TimelineInterchangeConfig) -> InterchangeTimeline {
+    let export = &config.timeline;
+    let map = ProjectTimeMap::new(&export.clips);
+    let mut warnings = Vec::new();
+    let mut items: Vec<(TrackRole, InterchangeItem)> = Vec::new();
+
+    let follows_cursor = export.clips.iter().any(|clip| {
+        clip.segment
+            .zoom_blocks
+            .iter()
+            .any(|block| block.enabled && block.follow_cursor)
+    });
+    if follows_cursor {
+        warnings.push(
+            "Cursor-following zoom blocks were exported at their fixed anchor position".to_string(),
+        );
+    }
+
+    for (clip, placement) in export.clips.iter().zip(&map.clips) {
+        let segment = &clip.segment;
+        let zoom_keys = zoom_keyframes(&segment.zoom_blocks);
+        let device_points = sorted_points(&segment.device_audio_points);
+        let mic_points = sorted_points(&segment.mic_audio_points);
+
+        for edit in &placement.edits {
+            if let Some(mut video) =
+                item_for_edit(&clip.clip_name, &clip.source_video_path, edit, 0.0)
+            {
+                video.crop = segment.crop.clone();
+                video.transform = transform_for_edit(&zoom_keys, placement, edit);
+                items.push((TrackRole::Video, video));
+            }
+            for (role, path, offset, points) in [
+                (
+                    TrackRole::DeviceAudio,
+                    &clip.device_audio_path,
+                    segment.device_audio_offset_sec,
+                    Some(&device_points),
+                ),
+                (
+                    TrackRole::MicAudio,
+                    &clip.mic_audio_path,
+                    segment.mic_audio_offset_sec,
+                    Some(&mic_points),
+                ),
+                (
+                    TrackRole::Webcam,
+                    &clip.webcam_video_path,
+                    segment.webcam_offset_sec,
+                    None,
+                ),
+            ] {
+                if path.trim().is_empty() {
+                    continue;
+                }
+                let Some(mut item) = item_for_edit(&clip.clip_name, path, edit, offset) else {
+                    continue;
+                };
+                if let Some(points) = points {
+                    item.volume =
+                        envelope_for_item(&item, points, |time| placement.source_to_record(time));
+                    let muted = !item.volume.is_empty()
+                        && item.volume.iter().all(|key| key.volume <= 0.0001);
+                    if muted {
+                        continue;
+                    }
+                }
+                items.push((role, item));
+            }
+        }
+    }
+
+    for (role, segments, track_points) in [
+        (
+            TrackRole::Audio,
+            &export.audio_segments,
+            &export.audio_track_volume_points,
+        ),
+        (
+            TrackRole::Narration,
+            &export.narration_segments,
+            &export.narration_track_volume_points,
+        ),
+    ] {
+        let track_points = sorted_points(track_points);
+        for segment in segments {
+            if let Some(item) = item_for_project_audio(&map, segment, &track_points) {
+                items.push((role, item));
+            }
+        }
+    }
+
+    let placements_by_clip: HashMap<&str, &ClipPlacement> = export
+        .clips
+        .iter()
+        .map(|clip| clip.clip_id.as_str())
+        .zip(&map.clips)
+        .collect();
+    let mut captions: Vec<InterchangeCaption> = config
+        .subtitle_cues
+        .iter()
+        .filter_map(|cue| {
+            let placement = placements_by_clip.get(cue.clip_id.as_str())?;
+            let text = cue.text.replace("\r\n", "\n").trim().to_string();
+            let record_in = placement.source_to_record(cue.start_time);
+            let record_out = placement.source_to_record(cue.end_time);
+            (!text.is_empty() && record_out - record_in > MIN_ITEM_SEC).then_some(
+                InterchangeCaption {
+                    record_in,
+                    record_out,
+                    text,
+                },
+            )
+        })
+        .collect();
+    captions.sort_by(|a, b| a.record_in.total_cmp(&b.record_in));
+
+    let name = if config.project_name.trim().is_empty() {
+        "SGT Timeline".to_string()
+    } else {
+        config.project_name.trim().to_string()
+    };
+    InterchangeTimeline {
+        name,
+        fps: export.framerate.max(1),
+        width: export.width,
+        height: export.height,
+        duration: map.duration(),
+        tracks: assign_tracks(items),
+        captions,
+        warnings,
+    }
+}
+
+fn sorted_points(points: &[DeviceAudioPoint]) -> Vec<DeviceAudioPoint> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.time.total_cmp(&b.time));
+    sorted
+}
+
+/// One edit of a clip's media. `media_offset` is the companion-file offset
+/// (`media_time = source_time - offset`); leading media that does not exist
+/// yet is dropped by starting the item later on the record timeline.
+fn item_for_edit(
+    name: &str,
+    path: &str,
+    edit: &TimelineEdit,
+    media_offset: f64,
+) -> Option<InterchangeItem> {
+    let speed = edit.speed();
+    let mut source_in = edit.source_in - media_offset;
+    let mut record_in = edit.record_in;
+    if source_in < 0.0 {
+        record_in -= source_in / speed;
+        source_in = 0.0;
+    }
+    (edit.record_out - record_in > MIN_ITEM_SEC).then(|| InterchangeItem {
+        name: name.to_string(),
+        media_path: path.trim().to_string(),
+        source_in,
+        record_in,
+        record_out: edit.record_out,
+        speed,
+        crop: None,
+        transform: Vec::new(),
+        volume: Vec::new(),
+    })
+}
+
+fn item_for_project_audio(
+    map: &ProjectTimeMap,
+    segment: &ImportedAudioSegmentConfig,
+    track_points: &[DeviceAudioPoint],
+) -> Option<InterchangeItem> {
+    let path = segment.raw_audio_path.trim();
+    let rate = if segment.playback_rate > 0.0001 {
+        segment.playback_rate
+    } else {
+        1.0
+    };
+    let in_point = segment.in_point.max(0.0);
+    let out_point = if segment.out_point > in_point + 0.0001 {
+        segment.out_point
+    } else {
+        segment.duration
+    };
+    if path.is_empty() || out_point <= in_point + 0.0001 {
+        return None;
+    }
+    let compact_end = segment.start_time + (out_point - in_point) / rate;
+    let record_in = map.compact_to_record(segment.start_time);
+    let record_out = map.compact_to_record(compact_end);
+    if record_out - record_in <= MIN_ITEM_SEC {
+        return None;
+    }
+    let mut item = InterchangeItem {
+        name: super::media_stem(path),
+        media_path: path.to_string(),
+        source_in: in_point,
+        record_in,
+        record_out,
+        speed: (out_point - in_point) / (record_out - record_in),
+        crop: None,
+        transform: Vec::new(),
+        volume: Vec::new(),
+    };
+    item.volume = envelope_for_item(&item, track_points, |time| map.compact_to_record(time));
+    Some(item)
+}
+
+/// Volume keyframes covering `item`: the envelope sampled at both edges plus
+/// every control point that lands inside. A flat unity envelope is omitted.
+fn envelope_for_item(
+    item: &InterchangeItem,
+    points: &[DeviceAudioPoint],
+    to_record: impl Fn(f64) -> f64,
+) -> Vec<VolumeKeyframe> {
+    if points.is_empty() {
+        return Vec::new();
+    }
+    let mapped: Vec<DeviceAudioPoint> = points
+        .iter()
+        .map(|point| DeviceAudioPoint {
+            time: to_record(point.time),
+            volume: point.volume,
+        })
+        .collect();
+    let mut keys = vec![VolumeKeyframe {
+        record_time: item.record_in,
+        volume: track_volume_at(&mapped, item.record_in),
+    }];
+    keys.extend(
+        mapped
+            .iter()
+            .filter(|point| {
+                point.time > item.record_in + MIN_ITEM_SEC
+                    && point.time < item.record_out - MIN_ITEM_SEC
+            })
+            .map(|point| VolumeKeyframe {
+                record_time: point.time,
+                volume: point.volume,
+            }),
+    );
+    keys.push(VolumeKeyframe {
+        record_time: item.record_out,
+        volume: track_volume_at(&mapped, item.record_out),
+    });
+    if keys.iter().all(|key| (key.volume - 1.0).abs() < 1e-4) {
+        Vec::new()
+    } else {
+        keys
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ZoomKey {
+    source_time: f64,
+    zoom: f64,
+    center_x: f64,
+    center_y: f64,
+}
+
+const IDENTITY_ZOOM: (f64, f64, f64) = (1.0, 0.5, 0.5);
+
+/// Keyframes in source time for the enabled zoom blocks: ramp in over
+/// `ease_in`, hold, ramp out over `ease_out`. Linked blocks move directly to
+/// the next block instead of returning to the full frame in between.
+fn zoom_keyframes(blocks: &[ZoomBlock]) -> Vec<ZoomKey> {
+    let mut enabled: Vec<&ZoomBlock> = blocks
+        .iter()
+        .filter(|block| block.enabled && block.end_time > block.start_time + 1e-6)
+        .collect();
+    enabled.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+    let key = |source_time: f64, (zoom, center_x, center_y): (f64, f64, f64)| ZoomKey {
+        source_time,
+        zoom,
+        center_x,
+        center_y,
+    };
+    let mut keys: Vec<ZoomKey> = Vec::new();
+    let mut linked_from_previous = false;
+    for (index, block) in enabled.iter().enumerate() {
+        let duration = block.end_time - block.start_time;
+        let mut ease_in = block.ease_in.max(0.0);
+        let mut ease_out = block.ease_out.max(0.0);
+        if ease_in + ease_out > duration {
+            let scale = duration / (ease_in + ease_out);
+            ease_in *= scale;
+            ease_out *= scale;
+        }
+        let (center_x, center_y) =
+            to_viewport_center(block.zoom_factor, block.position_x, block.position_y);
+        let state = (block.zoom_factor, center_x, center_y);
+        let links_to_next = block.direct_transition_to_next && index + 1 < enabled.len();
+
+        if !linked_from_previous {
+            keys.push(key(block.start_time, IDENTITY_ZOOM));
+        }
+        keys.push(key(block.start_time + ease_in, state));
+        keys.push(key(block.end_time - ease_out, state));
+        if !links_to_next {
+            keys.push(key(block.end_time, IDENTITY_ZOOM));
+        }
+        linked_from_previous = links_to_next;
+    }
+    keys.dedup_by(|b, a| (a.source_time - b.source_time).abs() < 1e-6);
+    keys
+}
+
+fn sample_zoom(keys: &[ZoomKey], source_time: f64) -> ZoomKey {
+    let idx = keys.partition_point(|key| key.source_time < source_time);
+    let (zoom, center_x, center_y) = if keys.is_empty() {
+        IDENTITY_ZOOM
+    } else if idx == 0 {
+        (keys[0].zoom, keys[0].center_x, keys[0].center_y)
+    } else if idx >= keys.len() {
+        let last = keys[keys.len() - 1];
+        (last.zoom, last.center_x, last.center_y)
+    } else {
+        let (a, b) = (keys[idx - 1], keys[idx]);
+        let t = ((source_time - a.source_time) / (b.source_time - a.source_time).max(1e-9))
+            .clamp(0.0, 1.0);
+        (
+            a.zoom + (b.zoom - a.zoom) * t,
+            a.center_x + (b.center_x - a.center_x) * t,
+            a.center_y + (b.center_y - a.center_y) * t,
+        )
+    };
+    ZoomKey {
+        source_time,
+        zoom,
+        center_x,
+        center_y,
+    }
+}
+
+fn transform_for_edit(
+    keys: &[ZoomKey],
+    placement: &ClipPlacement,
+    edit: &TimelineEdit,
+) -> Vec<TransformKeyframe> {
+    if keys.is_empty() {
+        return Vec::new();
+    }
+    let mut samples = vec![sample_zoom(keys, edit.source_in)];
+    samples.extend(
+        keys.iter()
+            .copied()
+            .filter(|key| key.source_time > edit.source_in && key.source_time < edit.source_out),
+    );
+    samples.push(sample_zoom(keys, edit.source_out));
+    if samples.iter().all(|key| (key.zoom - 1.0).abs() < 1e-6) {
+        return Vec::new();
+    }
+    samples
+        .into_iter()
+        .map(|key| TransformKeyframe {
+            record_time: placement.source_to_record(key.source_time),
+            zoom: key.zoom,
+            center_x: key.center_x,
+            center_y: key.center_y,
+        })
+        .collect()
+}
+
+/// Group items into tracks by role, spilling overlaps into extra tracks of the
+/// same role because none of the target formats allow overlapping items.
+fn assign_tracks(mut items: Vec<(TrackRole, InterchangeItem)>) -> Vec<InterchangeTrack> {
+    const ROLE_ORDER: [TrackRole; 6] = [
+        TrackRole::Video,
+        TrackRole::Webcam,
+        TrackRole::DeviceAudio,
+        TrackRole::MicAudio,
+        TrackRole::Audio,
+        TrackRole::Narration,
+    ];
+    items.sort_by(|a, b| a.1.record_in.total_cmp(&b.1.record_in));
+    let mut tracks = Vec::new();
+    for role in ROLE_ORDER {
+        let mut lanes: Vec<Vec<InterchangeItem>> = Vec::new();
+        for (_, item) in items.iter().filter(|(item_role, _)| *item_role == role) {
+            let lane = lanes.iter_mut().find(|lane| {
+                lane.last()
+                    .is_none_or(|last| last.record_out <= item.record_in + MIN_ITEM_SEC)
+            });
+            match lane {
+                Some(lane) => lane.push(item.clone()),
+                None => lanes.push(vec![item.clone()]),
+            }
+        }
+        let lane_count = lanes.len();
+        for (index, lane) in lanes.into_iter().enumerate() {
+            let name = if lane_count > 1 {
+                format!("{} {}", role.label(), index + 1)
+            } else {
+                role.label().to_string()
+            };
+            tracks.push(InterchangeTrack {
+                name,
+                role,
+                items: lane,
+            });
+        }
+    }
+    tracks
+}
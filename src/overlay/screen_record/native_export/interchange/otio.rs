@@ -0,0 +1,177 @@
+// OpenTimelineIO JSON writer. Zoom keyframes, crop and volume envelopes have
+// no core OTIO schema, so they ride along as `metadata.sgt` for adapters and
+// pipeline scripts; speed uses the standard LinearTimeWarp effect.
+
+use serde_json::{Value, json};
+
+use super::file_url;
+use super::model::{InterchangeItem, InterchangeTimeline, InterchangeTrack, frames};
+
+pub(super) fn write(timeline: &InterchangeTimeline) -> String {
+    let fps = timeline.fps;
+    let tracks: Vec<Value> = timeline
+        .tracks
+        .iter()
+        .map(|track| track_json(track, fps))
+        .collect();
+    let markers: Vec<Value> = timeline
+        .captions
+        .iter()
+        .map(|caption| {
+            let start = frames(caption.record_in, fps);
+            json!({
+                "OTIO_SCHEMA": "Marker.2",
+                "name": caption.text,
+                "comment": caption.text,
+                "color": "PURPLE",
+                "marked_range": time_range(start, frames(caption.record_out, fps) - start, fps),
+                "metadata": { "sgt": { "kind": "subtitle" } },
+            })
+        })
+        .collect();
+
+    let document = json!({
+        "OTIO_SCHEMA": "Timeline.1",
+        "name": timeline.name,
+        "global_start_time": rational_time(0, fps),
+        "metadata": {
+            "sgt": {
+                "width": timeline.width,
+                "height": timeline.height,
+                "fps": fps,
+            }
+        },
+        "tracks": {
+            "OTIO_SCHEMA": "Stack.1",
+            "name": "tracks",
+            "source_range": null,
+            "effects": [],
+            "markers": markers,
+            "metadata": {},
+            "children": tracks,
+        },
+    });
+    serde_json::to_string_pretty(&document).unwrap_or_default()
+}
+
+fn track_json(track: &InterchangeTrack, fps: u32) -> Value {
+    let mut children = Vec::new();
+    let mut cursor = 0_i64;
+    for item in &track.items {
+        let record_in = frames(item.record_in, fps).max(cursor);
+        let duration = frames(item.record_out, fps) - record_in;
+        if duration <= 0 {
+            continue;
+        }
+        if record_in > cursor {
+            children.push(json!({
+                "OTIO_SCHEMA": "Gap.1",
+                "name": "",
+                "source_range": time_range(0, record_in - cursor, fps),
+                "effects": [],
+                "markers": [],
+                "metadata": {},
+            }));
+        }
+        children.push(clip_json(item, duration, fps));
+        cursor = record_in + duration;
+    }
+    json!({
+        "OTIO_SCHEMA": "Track.1",
+        "name": track.name,
+        "kind": if track.role.is_video() { "Video" } else { "Audio" },
+        "source_range": null,
+        "effects": [],
+        "markers": [],
+        "metadata": {},
+        "children": children,
+    })
+}
+
+fn clip_json(item: &InterchangeItem, duration: i64, fps: u32) -> Value {
+    let mut effects = Vec::new();
+    if (item.speed - 1.0).abs() > 1e-3 {
+        effects.push(json!({
+            "OTIO_SCHEMA": "LinearTimeWarp.1",
+            "name": "",
+            "effect_name": "LinearTimeWarp",
+            "time_scalar": item.speed,
+            "metadata": {},
+        }));
+    }
+    let mut sgt = serde_json::Map::new();
+    if let Some(crop) = &item.crop {
+        sgt.insert(
+            "crop".to_string(),
+            json!({ "x": crop.x, "y": crop.y, "width": crop.width, "height": crop.height }),
+        );
+    }
+    if !item.transform.is_empty() {
+        let keys: Vec<Value> = item
+            .transform
+            .iter()
+            .map(|key| {
+                json!({
+                    "time": rational_time(frames(key.record_time - item.record_in, fps), fps),
+                    "zoom": key.zoom,
+                    "centerX": key.center_x,
+                    "centerY": key.center_y,
+                })
+            })
+            .collect();
+        sgt.insert("transformKeyframes".to_string(), Value::Array(keys));
+    }
+    if !item.volume.is_empty() {
+        let keys: Vec<Value> = item
+            .volume
+            .iter()
+            .map(|key| {
+                json!({
+                    "time": rational_time(frames(key.record_time - item.record_in, fps), fps),
+                    "volume": key.volume,
+                })
+            })
+            .collect();
+        sgt.insert("volumeKeyframes".to_string(), Value::Array(keys));
+    }
+    let metadata = if sgt.is_empty() {
+        json!({})
+    } else {
+        json!({ "sgt": sgt })
+    };
+
+    // OTIO clip durations are in record frames; LinearTimeWarp scales how
+    // much media they consume.
+    json!({
+        "OTIO_SCHEMA": "Clip.2",
+        "name": item.name,
+        "source_range": time_range(frames(item.source_in, fps), duration, fps),
+        "effects": effects,
+        "markers": [],
+        "metadata": metadata,
+        "enabled": true,
+        "active_media_reference_key": "DEFAULT_MEDIA",
+        "media_references": {
+            "DEFAULT_MEDIA": {
+                "OTIO_SCHEMA": "ExternalReference.1",
+                "name": "",
+                "target_url": file_url(&item.media_path),
+                "available_range": null,
+                "available_image_bounds": null,
+                "metadata": {},
+            }
+        },
+    })
+}
+
+fn rational_time(value: i64, fps: u32) -> Value {
+    json!({ "OTIO_SCHEMA": "RationalTime.1", "rate": fps as f64, "value": value as f64 })
+}
+
+fn time_range(start: i64, duration: i64, fps: u32) -> Value {
+    json!({
+        "OTIO_SCHEMA": "TimeRange.1",
+        "start_time": rational_time(start, fps),
+        "duration": rational_time(duration, fps),
+    })
+}
@@ -0,0 +1,259 @@
+// Source → record time mapping for interchange and chapter export.
+//
+// SGT places composition clips back-to-back in COMPACT time (trim segments
+// concatenated, before speed), then the renderer integrates the speed curve
+// to get OUTPUT time. NLE formats want discrete edits with a constant rate, so
+// each trim segment is cut at its speed control points and every piece gets
+// the average rate the renderer would produce over it.
+
+use super::super::config::{CompositionExportClipJob, SpeedPoint, TrimSegment, get_speed};
+
+// Same step as the audio mixer's OutputTimeMapper so edit lengths agree with
+// the rendered export to well under a frame.
+const INTEGRATION_STEP_SEC: f64 = 0.005;
+const MERGE_SPEED_EPSILON: f64 = 1e-3;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct TimelineEdit {
+    pub source_in: f64,
+    pub source_out: f64,
+    pub record_in: f64,
+    pub record_out: f64,
+}
+
+impl TimelineEdit {
+    pub(super) fn speed(&self) -> f64 {
+        (self.source_out - self.source_in) / (self.record_out - self.record_in).max(1e-9)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct ClipPlacement {
+    pub compact_start: f64,
+    pub compact_duration: f64,
+    pub record_end: f64,
+    trims: Vec<TrimSegment>,
+    pub edits: Vec<TimelineEdit>,
+}
+
+impl ClipPlacement {
+    fn build(clip: &CompositionExportClipJob, compact_start: f64, record_start: f64) -> Self {
+        let trims = normalized_trims(clip);
+        let mut speed_points = clip.segment.speed_points.clone();
+        speed_points.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        let mut edits: Vec<TimelineEdit> = Vec::new();
+        let mut record_cursor = record_start;
+        for trim in &trims {
+            let mut cuts = vec![trim.start_time];
+            cuts.extend(
+                speed_points
+                    .iter()
+                    .map(|point| point.time)
+                    .filter(|time| *time > trim.start_time + 1e-6 && *time < trim.end_time - 1e-6),
+            );
+            cuts.push(trim.end_time);
+            for window in cuts.windows(2) {
+                let output_len = integrate_output(window[0], window[1], &speed_points);
+                let edit = TimelineEdit {
+                    source_in: window[0],
+                    source_out: window[1],
+                    record_in: record_cursor,
+                    record_out: record_cursor + output_len,
+                };
+                record_cursor = edit.record_out;
+                match edits.last_mut() {
+                    Some(last)
+                        if (last.source_out - edit.source_in).abs() < 1e-9
+                            && (last.speed() - edit.speed()).abs() < MERGE_SPEED_EPSILON =>
+                    {
+                        last.source_out = edit.source_out;
+                        last.record_out = edit.record_out;
+                    }
+                    _ => edits.push(edit),
+                }
+            }
+        }
+
+        Self {
+            compact_start,
+            compact_duration: clip.duration.max(0.0),
+            record_end: record_cursor,
+            trims,
+            edits,
+        }
+    }
+
+    /// Record time for a source time of this clip. Times inside a cut snap to
+    /// the next kept frame so cues and keyframes straddling a cut stay ordered.
+    pub(super) fn source_to_record(&self, source_time: f64) -> f64 {
+        for edit in &self.edits {
+            if source_time <= edit.source_in {
+                return edit.record_in;
+            }
+            if source_time <= edit.source_out {
+                return edit.record_in + (source_time - edit.source_in) / edit.speed();
+            }
+        }
+        self.record_end
+    }
+
+    /// Mirrors the frontend `toSourceTime` for a clip-local compact time.
+    fn compact_to_source(&self, compact_local: f64) -> f64 {
+        let mut remaining = compact_local.max(0.0);
+        for trim in &self.trims {
+            let len = trim.end_time - trim.start_time;
+            if remaining <= len {
+                return trim.start_time + remaining;
+            }
+            remaining -= len;
+        }
+        self.trims.last().map(|trim| trim.end_time).unwrap_or(0.0)
+    }
+}
+
+fn normalized_trims(clip: &CompositionExportClipJob) -> Vec<TrimSegment> {
+    let mut trims: Vec<TrimSegment> = if clip.segment.trim_segments.is_empty() {
+        vec![TrimSegment {
+            start_time: clip.trim_start,
+            end_time: clip.trim_start + clip.duration.max(0.0),
+        }]
+    } else {
+        clip.segment.trim_segments.clone()
+    };
+    trims.retain(|trim| trim.end_time > trim.start_time + 1e-6);
+    trims.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+    trims
+}
+
+fn integrate_output(start: f64, end: f64, speed_points: &[SpeedPoint]) -> f64 {
+    let mut cursor = start;
+    let mut output = 0.0;
+    while cursor < end - 1e-9 {
+        let step_end = (cursor + INTEGRATION_STEP_SEC).min(end);
+        let speed = get_speed((cursor + step_end) * 0.5, speed_points).clamp(0.1, 16.0);
+        output += (step_end - cursor) / speed;
+        cursor = step_end;
+    }
+    output
+}
+
+/// Every composition clip placed on the record timeline in order.
+pub(in crate::overlay::screen_record::native_export) struct ProjectTimeMap {
+    pub(super) clips: Vec<ClipPlacement>,
+}
+
+impl ProjectTimeMap {
+    pub(in crate::overlay::screen_record::native_export) fn new(
+        clips: &[CompositionExportClipJob],
+    ) -> Self {
+        let mut placements = Vec::with_capacity(clips.len());
+        let mut compact_cursor = 0.0;
+        let mut record_cursor = 0.0;
+        for clip in clips {
+            let placement = ClipPlacement::build(clip, compact_cursor, record_cursor);
+            compact_cursor += placement.compact_duration;
+            record_cursor = placement.record_end;
+            placements.push(placement);
+        }
+        Self { clips: placements }
+    }
+
+    pub(in crate::overlay::screen_record::native_export) fn duration(&self) -> f64 {
+        self.clips.last().map(|clip| clip.record_end).unwrap_or(0.0)
+    }
+
+    /// Record time for a project-wide compact time (audio, narration and their
+    /// track envelopes are positioned in compact time).
+    pub(in crate::overlay::screen_record::native_export) fn compact_to_record(
+        &self,
+        compact_time: f64,
+    ) -> f64 {
+        let Some(clip) = self
+            .clips
+            .iter()
+            .find(|clip| compact_time < clip.compact_start + clip.compact_duration)
+            .or(self.clips.last())
+        else {
+            return 0.0;
+        };
+        let local = (compact_time - clip.compact_start).clamp(0.0, clip.compact_duration);
+        clip.source_to_record(clip.compact_to_source(local))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip(trims: serde_json::Value, speed_points: serde_json::Value) -> CompositionExportClipJob {
+        serde_json::from_value(serde_json::json!({
+            "jobId": "job",
+            "clipId": "clip",
+            "clipName": "Clip",
+            "sourceVideoPath": "C:/rec.mp4",
+            "trimStart": 0.0,
+            "duration": 10.0,
+            "segment": { "crop": null, "trimSegments": trims, "speedPoints": speed_points },
+            "backgroundConfig": {
+                "scale": 100.0,
+                "borderRadius": 0.0,
+                "backgroundType": "solid",
+                "shadow": 0.0,
+                "cursorScale": 1.0
+            }
+        }))
+        .expect("clip parses")
+    }
+
+    #[test]
+    fn trims_become_contiguous_edits_at_unit_speed() {
+        let map = ProjectTimeMap::new(&[clip(
+            serde_json::json!([
+                { "startTime": 1.0, "endTime": 4.0 },
+                { "startTime": 6.0, "endTime": 13.0 }
+            ]),
+            serde_json::json!([{ "time": 0.0, "speed": 1.0 }, { "time": 20.0, "speed": 1.0 }]),
+        )]);
+        let edits = &map.clips[0].edits;
+        assert_eq!(edits.len(), 2);
+        assert!((edits[0].record_out - 3.0).abs() < 1e-6);
+        assert!((edits[1].record_in - 3.0).abs() < 1e-6);
+        assert!((map.duration() - 10.0).abs() < 1e-6);
+        assert!((map.clips[0].source_to_record(5.0) - 3.0).abs() < 1e-6);
+        assert!((map.compact_to_record(4.0) - 4.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn constant_double_speed_halves_record_length() {
+        let map = ProjectTimeMap::new(&[clip(
+            serde_json::json!([{ "startTime": 0.0, "endTime": 10.0 }]),
+            serde_json::json!([{ "time": 0.0, "speed": 2.0 }, { "time": 10.0, "speed": 2.0 }]),
+        )]);
+        let edits = &map.clips[0].edits;
+        assert_eq!(edits.len(), 1);
+        assert!((edits[0].speed() - 2.0).abs() < 1e-6);
+        assert!((map.duration() - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn speed_ramps_split_into_constant_rate_edits() {
+        let map = ProjectTimeMap::new(&[clip(
+            serde_json::json!([{ "startTime": 0.0, "endTime": 10.0 }]),
+            serde_json::json!([
+                { "time": 0.0, "speed": 1.0 },
+                { "time": 4.0, "speed": 1.0 },
+                { "time": 6.0, "speed": 3.0 },
+                { "time": 10.0, "speed": 3.0 }
+            ]),
+        )]);
+        let edits = &map.clips[0].edits;
+        assert_eq!(edits.len(), 3);
+        assert!((edits[0].speed() - 1.0).abs() < 1e-6);
+        assert!(edits[1].speed() > 1.0 && edits[1].speed() < 3.0);
+        assert!((edits[2].speed() - 3.0).abs() < 1e-6);
+        for pair in edits.windows(2) {
+            assert!((pair[0].record_out - pair[1].record_in).abs() < 1e-9);
+        }
+    }
+}
@@ -4,18 +4,23 @@ mod audio_mix;
 mod background_presets;
 mod camera_path;
 mod capabilities;
+mod chapters;
 mod composition;
 pub mod config;
 mod crop_geometry;
 mod cursor;
 mod cursor_path;
 mod gif;
+mod interchange;
+pub(crate) mod native_concat;
 pub(crate) mod native_stitch;
 mod overlay;
 pub mod overlay_frames;
 mod pipeline;
 mod pipeline_build;
+mod presets;
 mod progress;
+mod reframe;
 pub mod sampling;
 pub mod staging;
 mod util;
@@ -32,6 +37,7 @@ use super::mf_encode;
 
 pub use audio_download::start_audio_download;
 pub use composition::start_composition_export;
+pub use interchange::start_timeline_interchange_export;
 pub(crate) use pipeline::run_native_export_with_staged;
 pub use pipeline::start_native_export;
 pub use progress::persist_export_result;
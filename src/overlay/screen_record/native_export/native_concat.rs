@@ -0,0 +1,174 @@
+// Stream-copy join of MP4 clips that share one encoder configuration.
+//
+// Paused recording segments come out of identical encoder settings, so their
+// compressed samples can move into one container unchanged: no decode, no
+// re-encode and no generation loss. Clips whose stream formats differ are
+// refused, and the caller falls back to the re-encoding stitcher.
+
+use std::path::Path;
+
+use windows::Win32::Media::MediaFoundation::*;
+
+use crate::overlay::screen_record::mf_decode;
+
+pub(crate) struct ConcatClip<'a> {
+    pub path: &'a Path,
+    /// Where the next clip starts, relative to this one.
+    pub duration_sec: f64,
+}
+
+/// Every MF_MEDIATYPE_EQUAL_* bit that must hold for samples to be copied.
+const SAME_FORMAT: u32 = MF_MEDIATYPE_EQUAL_MAJOR_TYPES
+    | MF_MEDIATYPE_EQUAL_FORMAT_TYPES
+    | MF_MEDIATYPE_EQUAL_FORMAT_DATA;
+
+pub(crate) fn concat_clips_to_mp4(
+    clips: &[ConcatClip<'_>],
+    output_path: &Path,
+) -> Result<(), String> {
+    let Some(first) = clips.first() else {
+        return Err("No clips to join".to_string());
+    };
+    mf_decode::mf_startup()?;
+
+    let (_, formats) = open_clip(first.path)?;
+    let wide_path: Vec<u16> = output_path
+        .to_string_lossy()
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let writer = unsafe {
+        MFCreateSinkWriterFromURL(
+            windows::core::PCWSTR(wide_path.as_ptr()),
+            None::<&IMFByteStream>,
+            None::<&IMFAttributes>,
+        )
+        .map_err(|e| format!("MFCreateSinkWriterFromURL concat: {e}"))?
+    };
+    // No input type on any stream: the sink writer passes samples through.
+    let sink_streams = formats
+        .iter()
+        .map(|format| unsafe {
+            writer
+                .AddStream(format)
+                .map_err(|e| format!("AddStream concat: {e}"))
+        })
+        .collect::<Result<Vec<u32>, String>>()?;
+    unsafe {
+        writer
+            .BeginWriting()
+            .map_err(|e| format!("BeginWriting concat: {e}"))?;
+    }
+
+    let mut offset_100ns = 0i64;
+    for clip in clips {
+        let (reader, clip_formats) = open_clip(clip.path)?;
+        if !same_formats(&formats, &clip_formats)? {
+            return Err(format!("Stream format differs in {}", clip.path.display()));
+        }
+        copy_samples(&reader, &writer, &sink_streams, offset_100ns)?;
+        offset_100ns += (clip.duration_sec.max(0.0) * 10_000_000.0).round() as i64;
+    }
+
+    unsafe {
+        writer
+            .Finalize()
+            .map_err(|e| format!("Finalize concat: {e}"))?;
+    }
+    Ok(())
+}
+
+/// A reader over every stream of `path`, delivering compressed samples, plus
+/// each stream's native format in stream order.
+fn open_clip(path: &Path) -> Result<(IMFSourceReader, Vec<IMFMediaType>), String> {
+    let wide_path: Vec<u16> = path
+        .to_string_lossy()
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let reader = unsafe {
+        MFCreateSourceReaderFromURL(
+            windows::core::PCWSTR(wide_path.as_ptr()),
+            None::<&IMFAttributes>,
+        )
+        .map_err(|e| format!("MFCreateSourceReaderFromURL concat: {e}"))?
+    };
+    unsafe {
+        reader
+            .SetStreamSelection(MF_SOURCE_READER_ALL_STREAMS.0 as u32, true)
+            .map_err(|e| format!("Select all streams: {e}"))?;
+    }
+    let mut formats = Vec::new();
+    while let Ok(format) = unsafe { reader.GetNativeMediaType(formats.len() as u32, 0) } {
+        formats.push(format);
+    }
+    if formats.is_empty() {
+        return Err(format!("No media streams in {}", path.display()));
+    }
+    Ok((reader, formats))
+}
+
+fn same_formats(expected: &[IMFMediaType], actual: &[IMFMediaType]) -> Result<bool, String> {
+    if expected.len() != actual.len() {
+        return Ok(false);
+    }
+    for (expected, actual) in expected.iter().zip(actual) {
+        let mut flags = 0u32;
+        unsafe {
+            expected
+                .IsEqual(actual, &mut flags)
+                .map_err(|e| format!("IsEqual concat: {e}"))?;
+        }
+        if flags & SAME_FORMAT != SAME_FORMAT {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn copy_samples(
+    reader: &IMFSourceReader,
+    writer: &IMFSinkWriter,
+    sink_streams: &[u32],
+    offset_100ns: i64,
+) -> Result<(), String> {
+    let mut ended = vec![false; sink_streams.len()];
+    while ended.iter().any(|ended| !ended) {
+        let mut stream_index = 0u32;
+        let mut stream_flags = 0u32;
+        let mut timestamp = 0i64;
+        let mut sample: Option<IMFSample> = None;
+        unsafe {
+            reader
+                .ReadSample(
+                    MF_SOURCE_READER_ANY_STREAM.0 as u32,
+                    0,
+                    Some(&mut stream_index),
+                    Some(&mut stream_flags),
+                    Some(&mut timestamp),
+                    Some(&mut sample),
+                )
+                .map_err(|e| format!("ReadSample concat: {e}"))?;
+        }
+        if (stream_flags & MF_SOURCE_READERF_ERROR.0 as u32) != 0 {
+            return Err(format!("Stream {stream_index} failed while joining"));
+        }
+        let Some(&sink_stream) = sink_streams.get(stream_index as usize) else {
+            return Err(format!("Unexpected stream {stream_index} while joining"));
+        };
+        if let Some(sample) = sample {
+            unsafe {
+                sample
+                    .SetSampleTime(timestamp + offset_100ns)
+                    .map_err(|e| format!("SetSampleTime concat: {e}"))?;
+                writer
+                    .WriteSample(sink_stream, &sample)
+                    .map_err(|e| format!("WriteSample concat: {e}"))?;
+            }
+        }
+        if (stream_flags & MF_SOURCE_READERF_ENDOFSTREAM.0 as u32) != 0 {
+            ended[stream_index as usize] = true;
+        }
+    }
+    Ok(())
+}
@@ -26,7 +26,8 @@ pub fn start_native_export(args: serde_json::Value) -> Result<serde_json::Value,
 
     let replay_args = args.clone();
     let parse_start = Instant::now();
-    let config: ExportConfig = parse_json_with_path(args)?;
+    let mut config: ExportConfig = parse_json_with_path(args)?;
+    config.apply_platform_preset();
     config.validate()?;
     super::progress::persist_replay_args(&replay_args);
     let parse_secs = parse_start.elapsed().as_secs_f64();
@@ -170,6 +171,21 @@ pub(crate) fn run_native_export_with_staged(
     let out_w = out_w - (out_w % 2);
     let out_h = out_h - (out_h % 2);
 
+    let baked_path = if config.platform.auto_reframe {
+        let reframed = super::reframe::reframe_camera_path(
+            &baked_path,
+            &baked_cursor,
+            &config,
+            &crop_geometry,
+            out_w,
+            out_h,
+        );
+        println!("[Export] Auto-reframe: {} camera frames", reframed.len());
+        reframed
+    } else {
+        baked_path
+    };
+
     // Setup GPU compositor and uniform builder
     let gpu_result = pipeline_build::setup_gpu_and_uniforms(
         &config,
@@ -0,0 +1,235 @@
+// Named platform export presets. A preset resolves to the same raw
+// width/height/framerate/bitrate/format fields the exporter already takes, so
+// the render path never branches on it; only `auto_reframe` changes rendering
+// (see `reframe`).
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::config::{BackgroundConfig, CompositionExportConfig, ExportConfig};
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportPresetId {
+    #[serde(rename = "youtube-1080p")]
+    Youtube1080p,
+    #[serde(rename = "youtube-4k")]
+    Youtube4k,
+    /// YouTube Shorts, TikTok and Reels share the same 9:16 target.
+    #[serde(rename = "vertical-short")]
+    VerticalShort,
+    #[serde(rename = "twitter")]
+    Twitter,
+    #[serde(rename = "slack-gif")]
+    SlackGif,
+}
+
+/// Preset selection carried by both single-clip and composition exports.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PlatformExportOptions {
+    pub preset: Option<ExportPresetId>,
+    /// Fill the output frame by following the cursor and zoom blocks with a
+    /// moving crop instead of letterboxing the recording.
+    pub auto_reframe: bool,
+}
+
+struct ExportPreset {
+    id: ExportPresetId,
+    label: &'static str,
+    width: u32,
+    height: u32,
+    framerate: u32,
+    bitrate_kbps: u32,
+    format: &'static str,
+    auto_reframe: bool,
+}
+
+// Bitrates follow the platforms' published upload recommendations for SDR
+// video at the preset framerate.
+const PRESETS: [ExportPreset; 5] = [
+    ExportPreset {
+        id: ExportPresetId::Youtube1080p,
+        label: "YouTube 1080p",
+        width: 1920,
+        height: 1080,
+        framerate: 60,
+        bitrate_kbps: 12_000,
+        format: "mp4",
+        auto_reframe: false,
+    },
+    ExportPreset {
+        id: ExportPresetId::Youtube4k,
+        label: "YouTube 4K",
+        width: 3840,
+        height: 2160,
+        framerate: 60,
+        bitrate_kbps: 53_000,
+        format: "mp4",
+        auto_reframe: false,
+    },
+    ExportPreset {
+        id: ExportPresetId::VerticalShort,
+        label: "Shorts / TikTok (9:16)",
+        width: 1080,
+        height: 1920,
+        framerate: 60,
+        bitrate_kbps: 12_000,
+        format: "mp4",
+        auto_reframe: true,
+    },
+    ExportPreset {
+        id: ExportPresetId::Twitter,
+        label: "Twitter / X",
+        width: 1280,
+        height: 720,
+        framerate: 30,
+        bitrate_kbps: 5_000,
+        format: "mp4",
+        auto_reframe: false,
+    },
+    ExportPreset {
+        id: ExportPresetId::SlackGif,
+        label: "Slack GIF",
+        width: 640,
+        height: 360,
+        framerate: 15,
+        bitrate_kbps: 0,
+        format: "gif",
+        auto_reframe: false,
+    },
+];
+
+/// Preset list for the export dialog.
+pub(super) fn preset_catalog() -> serde_json::Value {
+    PRESETS
+        .iter()
+        .map(|preset| {
+            json!({
+                "id": preset.id,
+                "label": preset.label,
+                "width": preset.width,
+                "height": preset.height,
+                "framerate": preset.framerate,
+                "targetVideoBitrateKbps": preset.bitrate_kbps,
+                "format": preset.format,
+                "autoReframe": preset.auto_reframe,
+            })
+        })
+        .collect()
+}
+
+fn preset(id: ExportPresetId) -> &'static ExportPreset {
+    PRESETS
+        .iter()
+        .find(|preset| preset.id == id)
+        .expect("every preset id has a catalog entry")
+}
+
+/// Output fields a preset overrides, shared by both export configs.
+struct OutputFields<'a> {
+    width: &'a mut u32,
+    height: &'a mut u32,
+    framerate: &'a mut u32,
+    bitrate_kbps: &'a mut u32,
+    format: &'a mut String,
+}
+
+impl PlatformExportOptions {
+    fn resolve(&mut self, output: OutputFields<'_>) {
+        if let Some(id) = self.preset {
+            let preset = preset(id);
+            *output.width = preset.width;
+            *output.height = preset.height;
+            *output.framerate = preset.framerate;
+            *output.bitrate_kbps = preset.bitrate_kbps;
+            *output.format = preset.format.to_string();
+            self.auto_reframe |= preset.auto_reframe;
+        }
+    }
+}
+
+/// A reframed export fills the frame edge to edge, so padding, rounded
+/// corners and the drop shadow would only show as artifacts at the crop edge.
+fn fill_frame(background: &mut BackgroundConfig) {
+    background.scale = 100.0;
+    background.border_radius = 0.0;
+    background.shadow = 0.0;
+}
+
+impl ExportConfig {
+    pub(super) fn apply_platform_preset(&mut self) {
+        self.platform.resolve(OutputFields {
+            width: &mut self.width,
+            height: &mut self.height,
+            framerate: &mut self.framerate,
+            bitrate_kbps: &mut self.target_video_bitrate_kbps,
+            format: &mut self.format,
+        });
+        if self.platform.auto_reframe {
+            fill_frame(&mut self.background_config);
+        }
+    }
+}
+
+impl CompositionExportConfig {
+    pub(super) fn apply_platform_preset(&mut self) {
+        self.platform.resolve(OutputFields {
+            width: &mut self.width,
+            height: &mut self.height,
+            framerate: &mut self.framerate,
+            bitrate_kbps: &mut self.target_video_bitrate_kbps,
+            format: &mut self.format,
+        });
+        if self.platform.auto_reframe {
+            for clip in &mut self.clips {
+                fill_frame(&mut clip.background_config);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vertical_preset_overrides_output_and_fills_the_frame() {
+        let mut config: CompositionExportConfig = serde_json::from_value(json!({
+            "sessionId": "session",
+            "width": 1920,
+            "height": 1080,
+            "framerate": 30,
+            "preset": "vertical-short",
+            "clips": [{
+                "jobId": "a",
+                "clipId": "a",
+                "clipName": "a",
+                "sourceVideoPath": "C:/rec.mp4",
+                "trimStart": 0.0,
+                "duration": 10.0,
+                "segment": { "crop": null },
+                "backgroundConfig": {
+                    "scale": 80.0,
+                    "borderRadius": 24.0,
+                    "backgroundType": "solid",
+                    "shadow": 40.0,
+                    "cursorScale": 1.0
+                }
+            }]
+        }))
+        .expect("composition config parses");
+        config.apply_platform_preset();
+        assert_eq!(
+            (config.width, config.height, config.framerate),
+            (1080, 1920, 60)
+        );
+        assert!(config.platform.auto_reframe);
+        assert_eq!(config.clips[0].background_config.scale, 100.0);
+        assert_eq!(config.clips[0].background_config.border_radius, 0.0);
+
+        let catalog = preset_catalog();
+        assert_eq!(catalog.as_array().map(Vec::len), Some(PRESETS.len()));
+        assert_eq!(catalog[4]["id"], "slack-gif");
+        assert_eq!(catalog[4]["format"], "gif");
+    }
+}
@@ -3,6 +3,7 @@ use std::path::PathBuf;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use super::SR_HWND;
+use crate::config::{Config, secrets};
 use serde::Serialize;
 use windows::Win32::Foundation::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
@@ -26,13 +27,31 @@ pub fn export_result_log_path() -> Option<PathBuf> {
     })
 }
 
+/// Debug records go to disk scrubbed of every key the app holds, and are not
+/// written at all when those keys cannot be read.
+fn app_config() -> Option<Config> {
+    crate::APP.lock().ok().map(|app| app.config.clone())
+}
+
 pub fn persist_export_result(result: &Result<serde_json::Value, String>) {
     let Some(path) = export_result_log_path() else {
         return;
     };
+    let Some(record) = app_config().and_then(|config| result_record(result, &config)) else {
+        return;
+    };
     if let Some(parent) = path.parent() {
         let _ = fs::create_dir_all(parent);
     }
+    if let Ok(bytes) = serde_json::to_vec_pretty(&record) {
+        let _ = fs::write(path, bytes);
+    }
+}
+
+fn result_record(
+    result: &Result<serde_json::Value, String>,
+    config: &Config,
+) -> Option<serde_json::Value> {
     let log_value = match result {
         Ok(v) => serde_json::json!({
             "outcome": "ok",
@@ -51,19 +70,26 @@ pub fn persist_export_result(result: &Result<serde_json::Value, String>) {
                 .as_millis() as u64,
         }),
     };
-    if let Ok(bytes) = serde_json::to_vec_pretty(&log_value) {
-        let _ = fs::write(path, bytes);
-    }
+    secrets::without_secrets(&log_value, config).ok()
 }
 
 pub fn persist_replay_args(args: &serde_json::Value) {
     let Some(path) = export_replay_args_path() else {
         return;
     };
-    let Some(obj) = args.as_object() else {
+    let Some(record) = app_config().and_then(|config| replay_record(args, &config)) else {
         return;
     };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    if let Ok(bytes) = serde_json::to_vec_pretty(&record) {
+        let _ = fs::write(path, bytes);
+    }
+}
 
+fn replay_record(args: &serde_json::Value, config: &Config) -> Option<serde_json::Value> {
+    let obj = args.as_object()?;
     let source_video_path = obj
         .get("sourceVideoPath")
         .and_then(|v| v.as_str())
@@ -104,12 +130,7 @@ pub fn persist_replay_args(args: &serde_json::Value) {
         }),
     );
 
-    if let Some(parent) = path.parent() {
-        let _ = fs::create_dir_all(parent);
-    }
-    if let Ok(bytes) = serde_json::to_vec_pretty(&serde_json::Value::Object(replay_obj)) {
-        let _ = fs::write(path, bytes);
-    }
+    secrets::without_secrets(&replay_obj, config).ok()
 }
 
 #[derive(Serialize)]
@@ -168,3 +189,29 @@ pub fn push_export_progress(percent: f64, eta: f64) {
         clip_name: None,
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_debug_records_never_carry_an_api_key() {
+        let key = "AIza_export_debug_key";
+        let config = Config {
+            gemini_api_key: key.to_string(),
+            ..Config::default()
+        };
+        let args = serde_json::json!({
+            "sourceVideoPath": r"C:\Rec\take.mp4",
+            "subtitles": { "provider": "gemini", "apiKey": key },
+        });
+        let replay = replay_record(&args, &config).unwrap();
+        assert!(!replay.to_string().contains(key));
+        assert_eq!(replay["sourceVideoPath"], r"C:\Rec\take.mp4");
+
+        let failed = Err(format!("403 from https://example.test/v1?key={key}"));
+        let result = result_record(&failed, &config).unwrap();
+        assert!(!result.to_string().contains(key));
+        assert_eq!(result["outcome"], "error");
+    }
+}
@@ -0,0 +1,317 @@
+// Auto-reframe for exports whose aspect differs from the recording (16:9 to
+// 9:16 for Shorts/TikTok). The renderer already crops through the camera path,
+// so reframing is a second camera path: the editor's zoom is multiplied by the
+// factor that makes the video cover the output, and the pan follows the
+// cursor, or the zoom block's framing while one is active, through a dead zone
+// and zero-phase smoothing so the crop glides instead of jittering.
+
+use super::camera_path::to_viewport_center;
+use super::config::{BakedCameraFrame, BakedCursorFrame, ExportConfig};
+use super::crop_geometry::ExportCropGeometry;
+
+// Fraction of the reframed window the focus may wander from its centre before
+// the crop starts moving.
+const DEAD_ZONE: f64 = 0.2;
+// Time constant of the forward/backward exponential smoother.
+const SMOOTHING_SEC: f64 = 0.35;
+// Zoom excess over 1x at which an active zoom block fully owns the framing.
+const ZOOM_FOCUS_RAMP: f64 = 0.25;
+
+/// Output size of the video quad at zoom 1, normalized to the output frame,
+/// mirroring the fit in `UniformBuildParams::build_uniforms`.
+fn fitted_video_size(crop_w: f64, crop_h: f64, out_w: f64, out_h: f64, scale: f64) -> (f64, f64) {
+    let crop_aspect = crop_w / crop_h.max(1.0);
+    if crop_aspect > out_w / out_h.max(1.0) {
+        (scale, out_w * scale / crop_aspect / out_h)
+    } else {
+        (out_h * scale * crop_aspect / out_w, scale)
+    }
+}
+
+/// Camera path that fills an `out_w`×`out_h` frame. `base` is the editor's
+/// camera path (may be empty) and `cursor` the baked cursor path, both in
+/// source time and global source pixels.
+pub(super) fn reframe_camera_path(
+    base: &[BakedCameraFrame],
+    cursor: &[BakedCursorFrame],
+    config: &ExportConfig,
+    geometry: &ExportCropGeometry,
+    out_w: u32,
+    out_h: u32,
+) -> Vec<BakedCameraFrame> {
+    let camera_w = f64::from(geometry.camera_width).max(1.0);
+    let camera_h = f64::from(geometry.camera_height).max(1.0);
+    let (video_w, video_h) = fitted_video_size(
+        f64::from(geometry.video_width),
+        f64::from(geometry.video_height),
+        f64::from(out_w),
+        f64::from(out_h),
+        (config.background_config.scale / 100.0).clamp(0.01, 1.0),
+    );
+    let fill = (1.0 / video_w).max(1.0 / video_h);
+
+    let times: Vec<f64> = if base.is_empty() {
+        frame_times(config)
+    } else {
+        base.iter().map(|frame| frame.time).collect()
+    };
+    let mut zooms = Vec::with_capacity(times.len());
+    let mut focus = Vec::with_capacity(times.len());
+    for (index, &time) in times.iter().enumerate() {
+        let (zoom, anchor_x, anchor_y) = base.get(index).map_or((1.0, 0.5, 0.5), |frame| {
+            (
+                frame.zoom.max(1.0),
+                ((frame.x - geometry.x_offset) / camera_w).clamp(0.0, 1.0),
+                ((frame.y - geometry.y_offset) / camera_h).clamp(0.0, 1.0),
+            )
+        });
+        let (view_x, view_y) = to_viewport_center(zoom, anchor_x, anchor_y);
+        let target = match cursor_at(cursor, time) {
+            Some((x, y)) => {
+                // Stay inside what the editor's zoom shows, and hand the
+                // framing to the zoom block as it zooms in.
+                let half = 0.5 / zoom;
+                let cursor_x =
+                    ((x - geometry.x_offset) / camera_w).clamp(view_x - half, view_x + half);
+                let cursor_y =
+                    ((y - geometry.y_offset) / camera_h).clamp(view_y - half, view_y + half);
+                let weight = ((zoom - 1.0) / ZOOM_FOCUS_RAMP).clamp(0.0, 1.0);
+                (
+                    cursor_x + (view_x - cursor_x) * weight,
+                    cursor_y + (view_y - cursor_y) * weight,
+                )
+            }
+            None => (view_x, view_y),
+        };
+        zooms.push(zoom);
+        focus.push(target);
+    }
+
+    let window = |zoom: f64| (1.0 / (zoom * fill * video_w), 1.0 / (zoom * fill * video_h));
+    let mut centers = hold_in_dead_zone(&focus, &zooms, &window);
+    smooth_zero_phase(&mut centers, &times);
+
+    times
+        .iter()
+        .zip(zooms)
+        .zip(centers)
+        .map(|((&time, zoom), (center_x, center_y))| {
+            let total_zoom = zoom * fill;
+            let anchor_x = anchor_for_center(center_x, total_zoom, video_w);
+            let anchor_y = anchor_for_center(center_y, total_zoom, video_h);
+            BakedCameraFrame {
+                time,
+                x: geometry.x_offset + anchor_x * camera_w,
+                y: geometry.y_offset + anchor_y * camera_h,
+                zoom: total_zoom,
+            }
+        })
+        .collect()
+}
+
+/// Source times for the reframe path when the editor produced no camera path,
+/// spaced like `camera_path::generate_camera_path`.
+fn frame_times(config: &ExportConfig) -> Vec<f64> {
+    let (start, end) = match (
+        config.segment.trim_segments.first(),
+        config.segment.trim_segments.last(),
+    ) {
+        (Some(first), Some(last)) => (first.start_time, last.end_time),
+        _ => (config.trim_start, config.trim_start + config.duration),
+    };
+    let step = 1.0 / f64::from(config.framerate.max(1));
+    let count = ((end - start).max(0.0) / step).ceil() as usize;
+    (0..=count)
+        .map(|index| (start + index as f64 * step).min(end))
+        .collect()
+}
+
+fn cursor_at(cursor: &[BakedCursorFrame], time: f64) -> Option<(f64, f64)> {
+    let index = cursor.partition_point(|frame| frame.time < time);
+    let (before, after) = match (
+        index.checked_sub(1).and_then(|i| cursor.get(i)),
+        cursor.get(index),
+    ) {
+        (Some(before), Some(after)) => (before, after),
+        (Some(only), None) | (None, Some(only)) => return Some((only.x, only.y)),
+        (None, None) => return None,
+    };
+    let span = after.time - before.time;
+    let t = if span > 1e-9 {
+        (time - before.time) / span
+    } else {
+        0.0
+    };
+    Some((
+        before.x + (after.x - before.x) * t,
+        before.y + (after.y - before.y) * t,
+    ))
+}
+
+/// Moves the crop centre only as far as needed to keep the focus inside the
+/// dead zone of the reframed window.
+fn hold_in_dead_zone(
+    focus: &[(f64, f64)],
+    zooms: &[f64],
+    window: &dyn Fn(f64) -> (f64, f64),
+) -> Vec<(f64, f64)> {
+    let mut centers = Vec::with_capacity(focus.len());
+    let mut current = focus.first().copied().unwrap_or((0.5, 0.5));
+    for (&(focus_x, focus_y), &zoom) in focus.iter().zip(zooms) {
+        let (window_w, window_h) = window(zoom);
+        let follow = |center: f64, target: f64, size: f64| {
+            let slack = size * DEAD_ZONE;
+            center.clamp(target - slack, target + slack)
+        };
+        current = (
+            follow(current.0, focus_x, window_w),
+            follow(current.1, focus_y, window_h),
+        );
+        centers.push(current);
+    }
+    centers
+}
+
+/// Forward then backward exponential smoothing, so the crop neither lags
+/// behind nor overshoots the focus.
+fn smooth_zero_phase(points: &mut [(f64, f64)], times: &[f64]) {
+    let alpha = |dt: f64| 1.0 - (-dt.abs() / SMOOTHING_SEC).exp();
+    for index in 1..points.len() {
+        let a = alpha(times[index] - times[index - 1]);
+        let previous = points[index - 1];
+        let point = &mut points[index];
+        point.0 += (previous.0 - point.0) * (1.0 - a);
+        point.1 += (previous.1 - point.1) * (1.0 - a);
+    }
+    for index in (0..points.len().saturating_sub(1)).rev() {
+        let a = alpha(times[index + 1] - times[index]);
+        let next = points[index + 1];
+        let point = &mut points[index];
+        point.0 += (next.0 - point.0) * (1.0 - a);
+        point.1 += (next.1 - point.1) * (1.0 - a);
+    }
+}
+
+/// Camera anchor (the renderer's `rx`/`ry`) that puts normalized video
+/// coordinate `center` in the middle of the output at `zoom`, clamped so the
+/// video always covers the frame.
+fn anchor_for_center(center: f64, zoom: f64, video_size: f64) -> f64 {
+    if zoom <= 1.0 + 1e-6 {
+        return 0.5;
+    }
+    let half_window = 0.5 / (zoom * video_size);
+    let center = if half_window >= 0.5 {
+        0.5
+    } else {
+        center.clamp(half_window, 1.0 - half_window)
+    };
+    let anchor =
+        (center * zoom * video_size + (1.0 - video_size) / 2.0 * zoom - 0.5) / (zoom - 1.0);
+    anchor.clamp(0.0, 1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ExportConfig {
+        serde_json::from_value(serde_json::json!({
+            "width": 1080,
+            "height": 1920,
+            "framerate": 30,
+            "sourceVideoPath": "C:/rec.mp4",
+            "trimStart": 0.0,
+            "duration": 6.0,
+            "segment": {
+                "crop": null,
+                "trimSegments": [{ "startTime": 0.0, "endTime": 6.0 }]
+            },
+            "backgroundConfig": {
+                "scale": 100.0,
+                "borderRadius": 0.0,
+                "backgroundType": "solid",
+                "shadow": 0.0,
+                "cursorScale": 1.0
+            },
+            "bakedPath": null,
+            "bakedCursorPath": null
+        }))
+        .expect("export config parses")
+    }
+
+    fn cursor(time: f64, x: f64) -> BakedCursorFrame {
+        BakedCursorFrame {
+            time,
+            x,
+            y: 540.0,
+            scale: 1.0,
+            is_clicked: false,
+            cursor_type: "default".to_string(),
+            opacity: 1.0,
+            rotation: 0.0,
+        }
+    }
+
+    /// Left edge of the visible video window, in normalized video x, for a
+    /// reframed camera frame (inverse of `anchor_for_center`).
+    fn visible_left(frame: &BakedCameraFrame, video_w: f64) -> f64 {
+        let anchor = frame.x / 1920.0;
+        let offset = (1.0 - frame.zoom) * anchor + (1.0 - video_w) / 2.0 * frame.zoom;
+        -offset / (frame.zoom * video_w)
+    }
+
+    #[test]
+    fn landscape_recording_fills_a_vertical_frame_and_follows_the_cursor() {
+        let config = config();
+        let geometry =
+            super::super::crop_geometry::resolve_export_crop_geometry(1920, 1080, None, 0.0);
+        // Cursor idles on the left for two seconds, then jumps to the right.
+        let cursor_path = [
+            cursor(0.0, 200.0),
+            cursor(2.0, 200.0),
+            cursor(2.1, 1700.0),
+            cursor(6.0, 1700.0),
+        ];
+        let frames = reframe_camera_path(&[], &cursor_path, &config, &geometry, 1080, 1920);
+        assert_eq!(frames.len(), 181);
+
+        let fill = 1920.0 / (1080.0 * 1080.0 / 1920.0);
+        assert!(frames.iter().all(|frame| (frame.zoom - fill).abs() < 1e-6));
+        let window = 1.0 / fill;
+        for frame in &frames {
+            let left = visible_left(frame, 1.0);
+            assert!(left >= -1e-9 && left + window <= 1.0 + 1e-9);
+        }
+        assert!(visible_left(&frames[0], 1.0) < 0.05);
+        assert!(visible_left(&frames[180], 1.0) + window > 0.95);
+        // Smoothing spreads the jump over many frames instead of cutting.
+        let largest_step = frames
+            .windows(2)
+            .map(|pair| (pair[1].x - pair[0].x).abs())
+            .fold(0.0, f64::max);
+        assert!(largest_step < 150.0);
+    }
+
+    #[test]
+    fn active_zoom_blocks_own_the_framing() {
+        let config = config();
+        let geometry =
+            super::super::crop_geometry::resolve_export_crop_geometry(1920, 1080, None, 0.0);
+        let base: Vec<BakedCameraFrame> = (0..=60)
+            .map(|index| BakedCameraFrame {
+                time: index as f64 / 10.0,
+                x: 1440.0,
+                y: 540.0,
+                zoom: 2.0,
+            })
+            .collect();
+        let frames =
+            reframe_camera_path(&base, &[cursor(0.0, 100.0)], &config, &geometry, 1080, 1920);
+        let fill = 1920.0 / (1080.0 * 1080.0 / 1920.0);
+        let last = frames.last().unwrap();
+        assert!((last.zoom - 2.0 * fill).abs() < 1e-6);
+        // The zoom block views x in [0.375, 0.875]; the reframe centres on it.
+        let window = 1.0 / (2.0 * fill);
+        assert!((visible_left(last, 1.0) + window / 2.0 - 0.625).abs() < 1e-3);
+    }
+}
@@ -513,7 +513,7 @@ impl CompositionExportConfig {
             &self.narration_track_volume_points,
             "narration track envelope",
         )?;
-        Ok(())
+        super::chapters::validate_chapters(&self.chapters)
     }
 }
 
@@ -140,10 +140,12 @@ where
             let mut parser = TranslationStreamParser::new(&pending);
             let covered_before_attempt = covered.len();
             let attempt_cancel = Arc::clone(&cancel);
+            let (groq_api_key, gemini_api_key) =
+                crate::api::provider_credentials::for_attempt(&current.provider, &config);
             let transport = translate_text_streaming(
                 TranslateTextRequest {
-                    groq_api_key: &config.api_key,
-                    gemini_api_key: &config.gemini_api_key,
+                    groq_api_key: &groq_api_key,
+                    gemini_api_key: &gemini_api_key,
                     text: request_text.clone(),
                     instruction: "Return only the requested structured screen translation."
                         .to_string(),
@@ -239,11 +241,14 @@ where
                 release_model_probe(&current.id);
                 bail!("screen translation was cancelled");
             }
-            record_model_failure(&current.id, &error.to_string());
-            if crate::overlay::utils::should_block_retry_provider(&error.to_string()) {
-                blocked_providers.insert(current.provider.clone());
+            let key_rotated =
+                record_model_failure(&current.id, &current.provider, &error.to_string());
+            if !key_rotated {
+                if crate::overlay::utils::should_block_retry_provider(&error.to_string()) {
+                    blocked_providers.insert(current.provider.clone());
+                }
+                failed.push(current.id.clone());
             }
-            failed.push(current.id.clone());
             crate::log_info!(
                 "[Screen Translate] trace={trace_id} text model failed model={} reason={error}",
                 current.id
@@ -273,6 +278,10 @@ where
             if total_attempts >= MAX_TOTAL_ATTEMPTS {
                 return Err(error).context("translation models did not resolve every text region");
             }
+            // Another pooled key is ready: retry the same model on it.
+            if key_rotated {
+                continue;
+            }
         }
         current = resolve_next_configured_model(
             &current.id,
@@ -0,0 +1,265 @@
+//! Content-addressed cache of completed preset block results.
+//!
+//! Each entry is one JSON file named by the SHA-256 of everything that shapes a
+//! block's answer: block type, provider, model, prompt, target language and the
+//! input itself. Nothing else in the file identifies the request, so an entry
+//! can only ever be found again by sending exactly the same thing.
+//!
+//! Expired entries read as missing and are deleted on sight. After each write
+//! the least recently written entries go until the directory fits the size the
+//! user set, so a long review session cannot grow the cache without bound.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::types::ResponseCacheSettings;
+
+const DIR_NAME: &str = "response-cache";
+const SUPPORTED_VERSION: u32 = 1;
+
+/// Everything a block sends that can change its answer.
+pub struct CacheRequest<'a> {
+    pub block_type: &'a str,
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub prompt: &'a str,
+    pub target_language: Option<&'a str>,
+    /// Input text, or the encoded image for image blocks.
+    pub input: &'a [u8],
+}
+
+impl CacheRequest<'_> {
+    pub fn key(&self) -> String {
+        let mut hasher = Sha256::new();
+        let fields: [&[u8]; 7] = [
+            b"v1",
+            self.block_type.as_bytes(),
+            self.provider.as_bytes(),
+            self.model.as_bytes(),
+            self.prompt.as_bytes(),
+            self.target_language.unwrap_or_default().as_bytes(),
+            self.input,
+        ];
+        // Length prefixes keep "ab" + "c" from hashing like "a" + "bc".
+        for field in fields {
+            hasher.update((field.len() as u64).to_le_bytes());
+            hasher.update(field);
+        }
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CachedResponse {
+    version: u32,
+    created_unix_seconds: u64,
+    pub text: String,
+}
+
+fn cache_dir() -> PathBuf {
+    crate::paths::app_runtime_local_data_dir().join(DIR_NAME)
+}
+
+fn now_unix_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+fn ttl(settings: &ResponseCacheSettings) -> Duration {
+    Duration::from_secs(u64::from(settings.ttl_hours) * 3600)
+}
+
+/// The cached answer for `key`, when caching is on and the entry is fresh.
+pub fn lookup(key: &str, settings: &ResponseCacheSettings) -> Option<CachedResponse> {
+    if !settings.enabled {
+        return None;
+    }
+    lookup_in(&cache_dir(), key, ttl(settings), now_unix_seconds())
+}
+
+/// Saves a completed answer, then trims the cache to its size limit. Failures
+/// only cost a future cache miss, so they are logged and otherwise ignored.
+pub fn store(key: &str, settings: &ResponseCacheSettings, text: &str) {
+    if !settings.enabled || text.trim().is_empty() {
+        return;
+    }
+    let dir = cache_dir();
+    let max_bytes = u64::from(settings.max_size_mb) * 1024 * 1024;
+    if let Err(error) =
+        store_in(&dir, key, text, now_unix_seconds()).and_then(|()| evict_in(&dir, max_bytes))
+    {
+        crate::log_info!("[response-cache] could not save entry: {error}");
+    }
+}
+
+/// Deletes every entry.
+pub fn clear() -> std::io::Result<()> {
+    match std::fs::remove_dir_all(cache_dir()) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error),
+        _ => Ok(()),
+    }
+}
+
+fn entry_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{key}.json"))
+}
+
+fn lookup_in(dir: &Path, key: &str, ttl: Duration, now: u64) -> Option<CachedResponse> {
+    let path = entry_path(dir, key);
+    let bytes = std::fs::read(&path).ok()?;
+    match serde_json::from_slice::<CachedResponse>(&bytes) {
+        Ok(entry)
+            if entry.version == SUPPORTED_VERSION
+                && now.saturating_sub(entry.created_unix_seconds) < ttl.as_secs() =>
+        {
+            Some(entry)
+        }
+        // Expired, foreign or unreadable: it can never be served again.
+        _ => {
+            let _ = std::fs::remove_file(&path);
+            None
+        }
+    }
+}
+
+fn store_in(dir: &Path, key: &str, text: &str, now: u64) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let entry = CachedResponse {
+        version: SUPPORTED_VERSION,
+        created_unix_seconds: now,
+        text: text.to_string(),
+    };
+    crate::atomic_json::write_json_atomic(&entry_path(dir, key), &entry)
+}
+
+struct EntryFile {
+    path: PathBuf,
+    bytes: u64,
+    written: SystemTime,
+}
+
+fn entries(dir: &Path) -> std::io::Result<Vec<EntryFile>> {
+    let mut entries = Vec::new();
+    for item in std::fs::read_dir(dir)? {
+        let item = item?;
+        let path = item.path();
+        if path.extension().is_none_or(|extension| extension != "json") {
+            continue;
+        }
+        let metadata = item.metadata()?;
+        entries.push(EntryFile {
+            path,
+            bytes: metadata.len(),
+            written: metadata.modified().unwrap_or(UNIX_EPOCH),
+        });
+    }
+    Ok(entries)
+}
+
+/// Deletes the least recently written entries until the rest fit `max_bytes`.
+fn evict_in(dir: &Path, max_bytes: u64) -> std::io::Result<()> {
+    let mut entries = entries(dir)?;
+    let mut total: u64 = entries.iter().map(|entry| entry.bytes).sum();
+    entries.sort_by_key(|entry| entry.written);
+    for entry in entries {
+        if total <= max_bytes {
+            break;
+        }
+        std::fs::remove_file(&entry.path)?;
+        total -= entry.bytes;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root(label: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "sgt-response-cache-{label}-{}-{}",
+            std::process::id(),
+            now_unix_seconds()
+        ))
+    }
+
+    fn request<'a>(prompt: &'a str, input: &'a [u8]) -> CacheRequest<'a> {
+        CacheRequest {
+            block_type: "text",
+            provider: "groq",
+            model: "model-a",
+            prompt,
+            target_language: Some("Vietnamese"),
+            input,
+        }
+    }
+
+    #[test]
+    fn keys_change_with_every_field_and_respect_boundaries() {
+        let base = request("Translate", b"hello").key();
+        assert_eq!(base, request("Translate", b"hello").key());
+        assert_eq!(base.len(), 64);
+        assert_ne!(base, request("Translate", b"hello!").key());
+        assert_ne!(base, request("Summarize", b"hello").key());
+        assert_ne!(
+            base,
+            CacheRequest {
+                target_language: None,
+                ..request("Translate", b"hello")
+            }
+            .key()
+        );
+        assert_ne!(
+            request("ab", b"c").key(),
+            request("a", b"bc").key(),
+            "field boundaries must be part of the key"
+        );
+    }
+
+    #[test]
+    fn entries_expire_after_their_ttl() {
+        let dir = root("ttl");
+        let key = request("Translate", b"hello").key();
+        store_in(&dir, &key, "xin chào", 1_000).unwrap();
+
+        let hit = lookup_in(&dir, &key, Duration::from_secs(60), 1_059).unwrap();
+        assert_eq!(hit.text, "xin chào");
+        assert!(lookup_in(&dir, &key, Duration::from_secs(60), 1_060).is_none());
+        assert!(
+            !entry_path(&dir, &key).exists(),
+            "expired entries are removed"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn eviction_keeps_the_newest_entries_within_the_limit() {
+        let dir = root("evict");
+        for (index, key) in ["old", "middle", "new"].iter().enumerate() {
+            store_in(&dir, key, &"x".repeat(200), index as u64).unwrap();
+            let file = std::fs::File::options()
+                .write(true)
+                .open(entry_path(&dir, key))
+                .unwrap();
+            file.set_modified(UNIX_EPOCH + Duration::from_secs(index as u64 + 1))
+                .unwrap();
+        }
+        let entry_bytes = std::fs::metadata(entry_path(&dir, "new")).unwrap().len();
+
+        evict_in(&dir, entry_bytes * 2).unwrap();
+
+        assert!(!entry_path(&dir, "old").exists());
+        assert!(entry_path(&dir, "middle").exists());
+        assert!(entry_path(&dir, "new").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -13,6 +13,18 @@ use std::time::Duration;
 mod budget;
 #[path = "retry_model_chain/cooldown.rs"]
 mod cooldown;
+#[cfg(not(feature = "recorder-worker"))]
+#[path = "retry_model_chain/persist.rs"]
+mod persist;
+#[cfg(not(feature = "recorder-worker"))]
+#[path = "retry_model_chain/routing.rs"]
+mod routing;
+#[cfg(not(feature = "recorder-worker"))]
+pub(crate) use budget::MEASURED_MIN_IMAGE_TOKENS;
+#[cfg(not(feature = "recorder-worker"))]
+pub(crate) use persist::{flush_state, keep_state_in};
+#[cfg(not(feature = "recorder-worker"))]
+pub use routing::resolve_next_routed_model;
 
 #[cfg(not(feature = "recorder-worker"))]
 const INTERACTIVE_TIMEOUT_MULTIPLIER: u64 = 10;
@@ -28,18 +40,23 @@ use cooldown::model_cooldown_remaining;
 use cooldown::model_cooldown_skip_reason;
 #[cfg(not(feature = "recorder-worker"))]
 pub use cooldown::{
-    claim_model_attempt, record_model_failure, record_model_success, release_model_probe,
+    ModelCooldown, ModelCooldownKind, active_model_cooldowns, claim_model_attempt,
+    clear_model_cooldown, record_model_failure, record_model_success, release_model_probe,
 };
 
 /// Stores the token balance a provider reported for one endpoint.
 ///
 /// Only the per-minute token window is tracked; request-count windows are far
-/// larger than a single call and are already handled by the cooldown.
+/// larger than a single call and are already handled by the cooldown, which
+/// takes its length from the reset these headers report.
 #[cfg(not(feature = "recorder-worker"))]
 pub fn record_token_budget(provider: &str, api_model: &str, headers: &ureq::http::HeaderMap) {
     // Counts are plain integers; only the reset carries a duration suffix.
     let count =
         |name: &str| -> Option<u32> { headers.get(name)?.to_str().ok()?.trim().parse().ok() };
+    if let Some(reset) = header_reset(headers, &count) {
+        cooldown::note_header_reset(provider, reset);
+    }
     let reset = headers
         .get("x-ratelimit-reset-tokens")
         .and_then(|value| value.to_str().ok())
@@ -51,18 +68,67 @@ pub fn record_token_budget(provider: &str, api_model: &str, headers: &ureq::http
     ) else {
         return;
     };
+    let label = crate::api::key_pool::leased_label(provider);
     budget::record(
-        &budget_key(provider, api_model),
+        &budget_key(provider, api_model, label.as_deref()),
         limit,
         remaining,
         Duration::from_secs_f64(reset),
     );
 }
 
-/// Key a token budget by the exact provider-qualified endpoint.
+/// The wait a rate-limited response asks for: `retry-after`, or the reset of
+/// whichever request or token window the response shows as spent.
+#[cfg(not(feature = "recorder-worker"))]
+fn header_reset(
+    headers: &ureq::http::HeaderMap,
+    count: impl Fn(&str) -> Option<u32>,
+) -> Option<Duration> {
+    let duration = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| cooldown::parse_duration_seconds(value.trim()))
+            .map(Duration::from_secs_f64)
+    };
+    let spent = [
+        (
+            "x-ratelimit-remaining-requests",
+            "x-ratelimit-reset-requests",
+        ),
+        ("x-ratelimit-remaining-tokens", "x-ratelimit-reset-tokens"),
+    ]
+    .into_iter()
+    .filter(|(remaining, _)| count(remaining) == Some(0))
+    .filter_map(|(_, reset)| duration(reset))
+    .max();
+    duration("retry-after").or(spent)
+}
+
+/// Key a token budget by the exact provider-qualified endpoint and, for a
+/// pooled provider, the API key that spent it.
+#[cfg(not(feature = "recorder-worker"))]
+fn budget_key(provider: &str, api_model: &str, key_label: Option<&str>) -> String {
+    match key_label {
+        Some(label) => format!("{}:{}#{label}", provider.trim(), api_model.trim()),
+        None => format!("{}:{}", provider.trim(), api_model.trim()),
+    }
+}
+
+/// The wait before `minimum` tokens fit this endpoint. A pooled provider is
+/// short only while every one of its keys is.
 #[cfg(not(feature = "recorder-worker"))]
-fn budget_key(provider: &str, api_model: &str) -> String {
-    format!("{}:{}", provider.trim(), api_model.trim())
+fn token_budget_wait(provider: &str, api_model: &str, minimum: u32) -> Option<Duration> {
+    let labels = crate::api::key_pool::labels(provider);
+    if labels.is_empty() {
+        return budget::shortfall(&budget_key(provider, api_model, None), minimum);
+    }
+    labels
+        .iter()
+        .map(|label| budget::shortfall(&budget_key(provider, api_model, Some(label)), minimum))
+        .collect::<Option<Vec<_>>>()?
+        .into_iter()
+        .min()
 }
 
 /// Cheapest total this endpoint could be billed for one vision call, or `None`
@@ -272,7 +338,7 @@ fn provider_preflight_skip_reason(provider: &str, config: &Config) -> Option<Str
 }
 
 fn credential_present(environment: &str, saved: &str) -> bool {
-    !crate::api::provider_credentials::resolve(environment, saved).is_empty()
+    crate::api::provider_credentials::present(environment, saved)
 }
 
 /// Why this model should be passed over for the request about to be made.
@@ -309,8 +375,7 @@ pub fn preflight_skip_reason(
     #[cfg(not(feature = "recorder-worker"))]
     if let Some(model) = get_model_by_id_with_custom(model_id, &config.custom_models)
         && let Some(minimum) = minimum_vision_request_tokens(&model.provider, &model.full_name)
-        && let Some(wait) =
-            budget::shortfall(&budget_key(&model.provider, &model.full_name), minimum)
+        && let Some(wait) = token_budget_wait(&model.provider, &model.full_name, minimum)
     {
         return Some(format!(
             "MODEL_TOKEN_BUDGET:{model_id}:{}s",
@@ -392,12 +457,28 @@ pub fn resolve_next_configured_model(
     blocked_providers: &HashSet<String>,
     chain_kind: RetryChainKind,
     config: &Config,
+) -> Option<ModelConfig> {
+    first_compatible_in_chain(
+        &chain_kind.effective_chain(config),
+        current_model_id,
+        failed_model_ids,
+        blocked_providers,
+        chain_kind,
+        config,
+    )
+}
+
+fn first_compatible_in_chain(
+    chain: &[String],
+    current_model_id: &str,
+    failed_model_ids: &[String],
+    blocked_providers: &HashSet<String>,
+    chain_kind: RetryChainKind,
+    config: &Config,
 ) -> Option<ModelConfig> {
     let must_support_search =
         model_supports_search_by_id_with_custom(current_model_id, &config.custom_models);
-
-    let chain = chain_kind.effective_chain(config);
-    for candidate_id in &chain {
+    for candidate_id in chain {
         if failed_model_ids
             .iter()
             .any(|failed_id| failed_id == candidate_id)
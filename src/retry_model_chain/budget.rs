@@ -15,6 +15,8 @@ use std::collections::HashMap;
 use std::sync::{LazyLock, Mutex};
 use std::time::{Duration, Instant};
 
+use super::persist::{self, SavedBudget, Snapshot};
+
 /// Cheapest image an endpoint can be billed for, measured against the live Groq
 /// endpoint on 2026-08-19 across eleven aspect ratios from 1:4 to 3:1.
 ///
@@ -22,7 +24,7 @@ use std::time::{Duration, Instant};
 /// ratio, so it cannot be predicted from the dimensions alone: 1024x512 billed
 /// 770 while 1024x341 billed 1026 and 1024x682 billed 1794. The floor is stable
 /// though, and a floor is all an admission check needs.
-pub(super) const MEASURED_MIN_IMAGE_TOKENS: u32 = 770;
+pub(crate) const MEASURED_MIN_IMAGE_TOKENS: u32 = 770;
 
 #[derive(Clone, Copy, Debug)]
 struct TokenBudget {
@@ -33,11 +35,56 @@ struct TokenBudget {
 }
 
 static TOKEN_BUDGETS: LazyLock<Mutex<HashMap<String, TokenBudget>>> =
-    LazyLock::new(|| Mutex::new(HashMap::new()));
+    LazyLock::new(|| Mutex::new(restored(persist::previous_run(), Instant::now())));
+
+/// Saved balances whose window has not refilled yet, re-dated to this
+/// process's clock by their age.
+fn restored(snapshot: &Snapshot, now: Instant) -> HashMap<String, TokenBudget> {
+    let elapsed = snapshot.elapsed();
+    snapshot
+        .budgets
+        .iter()
+        .filter_map(|saved| {
+            let reset = Duration::from_millis(saved.reset_ms);
+            let age = Duration::from_millis(saved.age_ms) + elapsed;
+            if saved.limit == 0 || age >= reset {
+                return None;
+            }
+            let budget = TokenBudget {
+                limit: saved.limit,
+                remaining: saved.remaining.min(saved.limit),
+                reset,
+                observed: now.checked_sub(age)?,
+            };
+            Some((saved.endpoint.clone(), budget))
+        })
+        .collect()
+}
+
+/// Every balance still refilling at `now`, for the state file.
+pub(super) fn saved(now: Instant) -> Vec<SavedBudget> {
+    let Ok(budgets) = TOKEN_BUDGETS.lock() else {
+        return Vec::new();
+    };
+    budgets
+        .iter()
+        .filter_map(|(endpoint, budget)| {
+            let age = now.saturating_duration_since(budget.observed);
+            (age < budget.reset).then(|| SavedBudget {
+                endpoint: endpoint.clone(),
+                limit: budget.limit,
+                remaining: budget.remaining,
+                reset_ms: budget.reset.as_millis() as u64,
+                age_ms: age.as_millis() as u64,
+            })
+        })
+        .collect()
+}
 
 /// Stores the token balance a provider reported for one endpoint.
 pub(super) fn record(endpoint: &str, limit: u32, remaining: u32, reset: Duration) {
     record_at(endpoint, limit, remaining, reset, Instant::now());
+    persist::save();
 }
 
 fn record_at(endpoint: &str, limit: u32, remaining: u32, reset: Duration, now: Instant) {
@@ -149,4 +196,31 @@ mod tests {
         assert_eq!(shortfall(&endpoint, 5_000), None);
         forget(&endpoint);
     }
+
+    #[test]
+    fn a_saved_balance_resumes_at_its_age_until_its_window_refills() {
+        let saved = |name: &str, reset_ms, age_ms| SavedBudget {
+            endpoint: key(name),
+            limit: 8_000,
+            remaining: 0,
+            reset_ms,
+            age_ms,
+        };
+        let snapshot = Snapshot::new(
+            Vec::new(),
+            vec![
+                saved("resumed", 60_000, 10_000),
+                saved("refilled", 5_000, 6_000),
+            ],
+        );
+        let now = Instant::now();
+        let budgets = restored(&snapshot, now);
+
+        assert!(!budgets.contains_key(&key("refilled")));
+        let age = now.saturating_duration_since(budgets[&key("resumed")].observed);
+        assert!(
+            age >= Duration::from_secs(10) && age < Duration::from_secs(11),
+            "unexpected age {age:?}"
+        );
+    }
 }
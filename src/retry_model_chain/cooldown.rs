@@ -2,12 +2,18 @@
 //!
 //! A model that rate-limits, times out repeatedly, runs out of credit, or is
 //! withdrawn by its provider is benched here so later chain steps stop paying
-//! for a failure that is already known.
+//! for a failure that is already known. Open circuits are saved through
+//! `persist`, so a restart does not retry a model that is known to be out.
 
+#[cfg(not(feature = "recorder-worker"))]
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::sync::{LazyLock, Mutex};
 use std::time::{Duration, Instant};
 
+#[cfg(not(feature = "recorder-worker"))]
+use super::persist::{self, SavedCooldown, Snapshot};
+
 #[cfg(not(feature = "recorder-worker"))]
 pub(super) const MODEL_RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(300);
 #[cfg(not(feature = "recorder-worker"))]
@@ -20,8 +26,8 @@ pub(super) const MODEL_BILLING_COOLDOWN: Duration = Duration::from_secs(6 * 60 *
 pub(super) const MODEL_TIMEOUT_FAILURE_THRESHOLD: u8 = 2;
 
 #[cfg(not(feature = "recorder-worker"))]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub(super) enum ModelCooldownKind {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ModelCooldownKind {
     RateLimit,
     Timeout,
     Unavailable,
@@ -39,6 +45,14 @@ impl ModelCooldownKind {
         }
     }
 
+    /// The longest this kind can bench a model, reported waits included.
+    fn max_duration(self) -> Duration {
+        match self {
+            Self::RateLimit => MAX_REPORTED_COOLDOWN,
+            other => other.duration(),
+        }
+    }
+
     fn reason(self) -> &'static str {
         match self {
             Self::RateLimit => "MODEL_RATE_LIMIT_COOLDOWN",
@@ -66,7 +80,102 @@ pub(super) enum ModelCircuitState {
 
 #[cfg(not(feature = "recorder-worker"))]
 static MODEL_CIRCUITS: LazyLock<Mutex<HashMap<String, ModelCircuitState>>> =
-    LazyLock::new(|| Mutex::new(HashMap::new()));
+    LazyLock::new(|| Mutex::new(restored(persist::previous_run(), Instant::now())));
+
+/// Open circuits from a saved snapshot, shortened by the wall time since the
+/// save and never longer than their kind allows.
+#[cfg(not(feature = "recorder-worker"))]
+pub(super) fn restored(snapshot: &Snapshot, now: Instant) -> HashMap<String, ModelCircuitState> {
+    let elapsed = snapshot.elapsed();
+    snapshot
+        .cooldowns
+        .iter()
+        .filter_map(|saved| {
+            let remaining = Duration::from_millis(saved.remaining_ms)
+                .min(saved.kind.max_duration())
+                .checked_sub(elapsed)
+                .filter(|remaining| !remaining.is_zero())?;
+            let state = ModelCircuitState::Open {
+                kind: saved.kind,
+                until: now + remaining,
+            };
+            Some((saved.model_id.clone(), state))
+        })
+        .collect()
+}
+
+/// Every circuit still open at `now`, for the state file.
+#[cfg(not(feature = "recorder-worker"))]
+pub(super) fn saved(now: Instant) -> Vec<SavedCooldown> {
+    let Ok(circuits) = MODEL_CIRCUITS.lock() else {
+        return Vec::new();
+    };
+    circuits
+        .iter()
+        .filter_map(|(model_id, state)| match *state {
+            ModelCircuitState::Open { kind, until } if until > now => Some(SavedCooldown {
+                model_id: model_id.clone(),
+                kind,
+                remaining_ms: until.saturating_duration_since(now).as_millis() as u64,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A model currently benched, as shown in settings.
+#[cfg(not(feature = "recorder-worker"))]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModelCooldown {
+    pub model_id: String,
+    pub kind: ModelCooldownKind,
+    pub remaining: Duration,
+}
+
+#[cfg(not(feature = "recorder-worker"))]
+pub fn active_model_cooldowns() -> Vec<ModelCooldown> {
+    let now = Instant::now();
+    let Ok(circuits) = MODEL_CIRCUITS.lock() else {
+        return Vec::new();
+    };
+    let mut active: Vec<ModelCooldown> = circuits
+        .iter()
+        .filter_map(|(model_id, state)| match *state {
+            ModelCircuitState::Open { kind, until } if until > now => Some(ModelCooldown {
+                model_id: model_id.clone(),
+                kind,
+                remaining: until.saturating_duration_since(now),
+            }),
+            _ => None,
+        })
+        .collect();
+    active.sort_by(|left, right| left.model_id.cmp(&right.model_id));
+    active
+}
+
+/// Lifts a model's cooldown by hand, as if its last call had succeeded.
+#[cfg(not(feature = "recorder-worker"))]
+pub fn clear_model_cooldown(model_id: &str) {
+    let removed = MODEL_CIRCUITS
+        .lock()
+        .map(|mut circuits| circuits.remove(model_id).is_some())
+        .unwrap_or(false);
+    if removed {
+        crate::log_info!("[retry-chain] cooldown cleared by hand for {model_id}");
+        persist::save();
+    }
+}
+
+#[cfg(not(feature = "recorder-worker"))]
+thread_local! {
+    /// The wait the last rate-limit headers on this thread asked for, by provider.
+    static HEADER_RESET: RefCell<Option<(String, Duration, Instant)>> =
+        const { RefCell::new(None) };
+}
+
+/// How long a header-reported wait stays attributable to the next failure.
+#[cfg(not(feature = "recorder-worker"))]
+const HEADER_RESET_FRESHNESS: Duration = Duration::from_secs(30);
 
 #[cfg(feature = "recorder-worker")]
 static MODEL_COOLDOWNS: LazyLock<Mutex<HashMap<String, Instant>>> =
@@ -185,19 +294,78 @@ pub(super) fn unavailable_model_error(error: &str) -> bool {
         || lower.contains("was removed")
 }
 
+/// Records a failed attempt on `model_id`. Returns true when the failure was
+/// charged to a pooled API key and another key of `provider` is ready: the
+/// model stays available and the caller can retry it at once.
 #[cfg(not(feature = "recorder-worker"))]
-pub fn record_model_failure(model_id: &str, error: &str) {
-    record_model_failure_at(model_id, error, Instant::now());
+pub fn record_model_failure(model_id: &str, provider: &str, error: &str) -> bool {
+    let now = Instant::now();
+    let header_reset = take_header_reset(provider, now);
+    if let Some(cooldown) = key_cooldown(error, header_reset)
+        && crate::api::key_pool::bench_leased(provider, cooldown)
+    {
+        return true;
+    }
+    if record_model_failure_with(model_id, error, header_reset, now) {
+        persist::save();
+    }
+    false
+}
+
+/// Remembers the reset a provider's rate-limit headers reported on this
+/// thread, for a failure recorded right after the response.
+#[cfg(not(feature = "recorder-worker"))]
+pub(super) fn note_header_reset(provider: &str, reset: Duration) {
+    let provider = crate::api::key_pool::pool_provider(provider).to_string();
+    HEADER_RESET.with_borrow_mut(|slot| *slot = Some((provider, reset, Instant::now())));
+}
+
+#[cfg(not(feature = "recorder-worker"))]
+fn take_header_reset(provider: &str, now: Instant) -> Option<Duration> {
+    let provider = crate::api::key_pool::pool_provider(provider);
+    let (noted, reset, at) = HEADER_RESET.with_borrow_mut(Option::take)?;
+    (noted == provider && now.saturating_duration_since(at) <= HEADER_RESET_FRESHNESS)
+        .then(|| reset.clamp(MIN_REPORTED_COOLDOWN, MAX_REPORTED_COOLDOWN))
+}
+
+/// How long a quota or billing failure benches the one key that hit it.
+#[cfg(not(feature = "recorder-worker"))]
+pub(super) fn key_cooldown(error: &str, header_reset: Option<Duration>) -> Option<Duration> {
+    if billing_error(error) {
+        Some(MODEL_BILLING_COOLDOWN)
+    } else if rate_limit_error(error) {
+        Some(
+            reported_cooldown(error)
+                .or(header_reset)
+                .unwrap_or(MODEL_RATE_LIMIT_COOLDOWN),
+        )
+    } else {
+        None
+    }
 }
 
 #[cfg(not(feature = "recorder-worker"))]
 pub(super) fn record_model_failure_at(model_id: &str, error: &str, now: Instant) {
+    record_model_failure_with(model_id, error, None, now);
+}
+
+/// Returns true when the failure opened a circuit, so the state file is stale.
+#[cfg(not(feature = "recorder-worker"))]
+pub(super) fn record_model_failure_with(
+    model_id: &str,
+    error: &str,
+    header_reset: Option<Duration>,
+    now: Instant,
+) -> bool {
     let is_rate_limit = rate_limit_error(error);
     let is_timeout = timeout_error(error);
     let is_unavailable = unavailable_model_error(error);
     let is_billing = billing_error(error);
-    // A provider that tells us when it reopens is more accurate than any constant.
-    let reported = is_rate_limit.then(|| reported_cooldown(error)).flatten();
+    // A provider that tells us when it reopens is more accurate than any
+    // constant; the message wins over the headers when both carry a wait.
+    let reported = is_rate_limit
+        .then(|| reported_cooldown(error).or(header_reset))
+        .flatten();
     let cooldown_for = |kind: ModelCooldownKind| match kind {
         ModelCooldownKind::RateLimit => reported.unwrap_or_else(|| kind.duration()),
         other => other.duration(),
@@ -213,12 +381,13 @@ pub(super) fn record_model_failure_at(model_id: &str, error: &str, now: Instant)
                     until: now + kind.duration(),
                 },
             );
+            return true;
         }
-        return;
+        return false;
     }
 
     let Ok(mut circuits) = MODEL_CIRCUITS.lock() else {
-        return;
+        return false;
     };
     let state = circuits
         .entry(model_id.to_string())
@@ -232,6 +401,7 @@ pub(super) fn record_model_failure_at(model_id: &str, error: &str, now: Instant)
         };
     }
 
+    let was_open = matches!(*state, ModelCircuitState::Open { .. });
     match *state {
         ModelCircuitState::Open { until, .. } if until > now => {}
         ModelCircuitState::HalfOpen { kind } => {
@@ -286,12 +456,18 @@ pub(super) fn record_model_failure_at(model_id: &str, error: &str, now: Instant)
         }
         _ => {}
     }
+    !was_open && matches!(*state, ModelCircuitState::Open { .. })
 }
 
 #[cfg(not(feature = "recorder-worker"))]
 pub fn record_model_success(model_id: &str) {
-    if let Ok(mut circuits) = MODEL_CIRCUITS.lock() {
-        circuits.remove(model_id);
+    let removed = MODEL_CIRCUITS
+        .lock()
+        .ok()
+        .and_then(|mut circuits| circuits.remove(model_id));
+    // Only a circuit that was open or probing can be in the state file.
+    if removed.is_some_and(|state| !matches!(state, ModelCircuitState::Monitoring { .. })) {
+        persist::save();
     }
 }
 
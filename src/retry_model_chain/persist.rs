@@ -0,0 +1,178 @@
+//! Cooldowns and token budgets that survive a restart.
+//!
+//! Both maps hold `Instant`s, which mean nothing to another process, so the
+//! file stores what was *left* of each entry at save time next to the wall
+//! clock of the save. Restoring subtracts the wall time that passed since; a
+//! clock that went backwards counts as no time passed, so a restart can only
+//! ever shorten an entry, never extend it past what the failure earned.
+//!
+//! Nothing is restored or saved until the app names the file with
+//! [`keep_state_in`]; saves then run on a background thread, at most once per
+//! [`SAVE_INTERVAL`], so a request never waits on the disk.
+
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use super::cooldown::ModelCooldownKind;
+
+const FILE_NAME: &str = "model_cooldowns.json";
+const SUPPORTED_VERSION: u32 = 1;
+/// Budgets change on every response; the file catches up at most this often.
+const SAVE_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub(super) struct Snapshot {
+    version: u32,
+    saved_at_unix_ms: u64,
+    #[serde(default)]
+    pub(super) cooldowns: Vec<SavedCooldown>,
+    #[serde(default)]
+    pub(super) budgets: Vec<SavedBudget>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(super) struct SavedCooldown {
+    pub(super) model_id: String,
+    pub(super) kind: ModelCooldownKind,
+    pub(super) remaining_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub(super) struct SavedBudget {
+    pub(super) endpoint: String,
+    pub(super) limit: u32,
+    pub(super) remaining: u32,
+    pub(super) reset_ms: u64,
+    /// How old the provider's reading already was at save time.
+    pub(super) age_ms: u64,
+}
+
+impl Snapshot {
+    pub(super) fn new(cooldowns: Vec<SavedCooldown>, budgets: Vec<SavedBudget>) -> Self {
+        Self {
+            version: SUPPORTED_VERSION,
+            saved_at_unix_ms: unix_ms(),
+            cooldowns,
+            budgets,
+        }
+    }
+
+    /// Wall time since the save; zero when the clock has gone backwards.
+    pub(super) fn elapsed(&self) -> Duration {
+        Duration::from_millis(unix_ms().saturating_sub(self.saved_at_unix_ms))
+    }
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+struct Writer {
+    path: Option<PathBuf>,
+    /// A save is already waiting out [`SAVE_INTERVAL`].
+    save_scheduled: bool,
+}
+
+impl Writer {
+    /// True when the caller should schedule a save.
+    fn schedule(&mut self) -> bool {
+        self.path.is_some() && !std::mem::replace(&mut self.save_scheduled, true)
+    }
+
+    /// The file to write now, if there is one.
+    fn take_save(&mut self) -> Option<PathBuf> {
+        self.save_scheduled = false;
+        self.path.clone()
+    }
+}
+
+static WRITER: Mutex<Writer> = Mutex::new(Writer {
+    path: None,
+    save_scheduled: false,
+});
+
+/// Restores from, and saves to, the state file in `directory`. Call it before
+/// the first request so the previous run's state is the one restored.
+pub(crate) fn keep_state_in(directory: &Path) {
+    if let Ok(mut writer) = WRITER.lock() {
+        writer.path = Some(directory.join(FILE_NAME));
+    }
+}
+
+/// The state saved by the previous run, read once per process.
+pub(super) fn previous_run() -> &'static Snapshot {
+    static PREVIOUS: LazyLock<Snapshot> = LazyLock::new(|| {
+        let path = WRITER.lock().ok().and_then(|writer| writer.path.clone());
+        path.map(|path| read(&path)).unwrap_or_default()
+    });
+    &PREVIOUS
+}
+
+/// A missing, unreadable, or foreign file restores nothing.
+pub(super) fn read(path: &Path) -> Snapshot {
+    let Ok(bytes) = std::fs::read(path) else {
+        return Snapshot::default();
+    };
+    match serde_json::from_slice::<Snapshot>(&bytes) {
+        Ok(snapshot) if snapshot.version == SUPPORTED_VERSION => snapshot,
+        Ok(snapshot) => {
+            crate::log_info!(
+                "[retry-chain] ignoring cooldown state version {}",
+                snapshot.version
+            );
+            Snapshot::default()
+        }
+        Err(error) => {
+            crate::log_info!("[retry-chain] ignoring unreadable cooldown state: {error}");
+            Snapshot::default()
+        }
+    }
+}
+
+pub(super) fn write(path: &Path, snapshot: &Snapshot) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::atomic_json::write_json_atomic(path, snapshot)
+}
+
+/// Schedules a write of the current cooldowns and budgets after
+/// [`SAVE_INTERVAL`]; changes made meanwhile ride along with it.
+pub(super) fn save() {
+    let schedule = WRITER.lock().is_ok_and(|mut writer| writer.schedule());
+    if !schedule {
+        return;
+    }
+    let spawned = std::thread::Builder::new()
+        .name("sgt-cooldown-save".to_string())
+        .spawn(|| {
+            std::thread::sleep(SAVE_INTERVAL);
+            flush_state();
+        });
+    if let Err(error) = spawned {
+        crate::log_info!("[retry-chain] could not schedule a cooldown save: {error}");
+        flush_state();
+    }
+}
+
+/// Writes the current cooldowns and budgets now, e.g. before the app exits.
+pub(crate) fn flush_state() {
+    let Some(path) = WRITER.lock().ok().and_then(|mut writer| writer.take_save()) else {
+        return;
+    };
+    let now = Instant::now();
+    let snapshot = Snapshot::new(super::cooldown::saved(now), super::budget::saved(now));
+    if let Err(error) = write(&path, &snapshot) {
+        crate::log_info!("[retry-chain] could not save cooldown state: {error}");
+    }
+}
+
+#[cfg(test)]
+#[path = "persist_tests.rs"]
+mod tests;
@@ -0,0 +1,138 @@
+use super::super::cooldown::{ModelCircuitState, restored};
+use super::*;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "sgt-cooldown-state-{}-{name}.json",
+        std::process::id()
+    ))
+}
+
+fn cooldown(model_id: &str, kind: ModelCooldownKind, remaining: Duration) -> SavedCooldown {
+    SavedCooldown {
+        model_id: model_id.to_string(),
+        kind,
+        remaining_ms: remaining.as_millis() as u64,
+    }
+}
+
+fn remaining(
+    circuits: &std::collections::HashMap<String, ModelCircuitState>,
+    model_id: &str,
+    now: Instant,
+) -> Option<Duration> {
+    match circuits.get(model_id)? {
+        ModelCircuitState::Open { until, .. } => Some(until.saturating_duration_since(now)),
+        _ => None,
+    }
+}
+
+#[test]
+fn a_written_snapshot_reads_back_unchanged() {
+    let path = temp_path("round-trip");
+    let snapshot = Snapshot::new(
+        vec![cooldown(
+            "groq-llama",
+            ModelCooldownKind::Billing,
+            Duration::from_secs(600),
+        )],
+        vec![SavedBudget {
+            endpoint: "groq:llama".to_string(),
+            limit: 8_000,
+            remaining: 120,
+            reset_ms: 60_000,
+            age_ms: 2_000,
+        }],
+    );
+    write(&path, &snapshot).unwrap();
+    assert_eq!(read(&path), snapshot);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn missing_foreign_or_corrupt_files_restore_nothing() {
+    let path = temp_path("foreign");
+    assert_eq!(read(&path), Snapshot::default());
+
+    std::fs::write(&path, br#"{"version": 99, "saved_at_unix_ms": 0}"#).unwrap();
+    assert_eq!(read(&path), Snapshot::default());
+
+    std::fs::write(&path, b"{ not json").unwrap();
+    assert_eq!(read(&path), Snapshot::default());
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn restored_cooldowns_lose_the_wall_time_since_the_save() {
+    let mut snapshot = Snapshot::new(
+        vec![
+            cooldown(
+                "billing",
+                ModelCooldownKind::Billing,
+                Duration::from_secs(3600),
+            ),
+            cooldown(
+                "rate-limit",
+                ModelCooldownKind::RateLimit,
+                Duration::from_secs(60),
+            ),
+        ],
+        Vec::new(),
+    );
+    snapshot.saved_at_unix_ms -= 120_000;
+    let now = Instant::now();
+    let circuits = restored(&snapshot, now);
+
+    let billing = remaining(&circuits, "billing", now).unwrap();
+    assert!(billing <= Duration::from_secs(3480) && billing > Duration::from_secs(3470));
+    assert!(!circuits.contains_key("rate-limit"));
+}
+
+#[test]
+fn a_clock_moved_backwards_never_extends_a_cooldown() {
+    let mut snapshot = Snapshot::new(
+        vec![cooldown(
+            "timeout",
+            ModelCooldownKind::Timeout,
+            Duration::from_secs(300),
+        )],
+        Vec::new(),
+    );
+    snapshot.saved_at_unix_ms += 24 * 60 * 60 * 1000;
+    assert_eq!(snapshot.elapsed(), Duration::ZERO);
+    let now = Instant::now();
+    assert_eq!(
+        remaining(&restored(&snapshot, now), "timeout", now),
+        Some(Duration::from_secs(300))
+    );
+}
+
+#[test]
+fn a_tampered_remaining_is_capped_at_what_its_kind_allows() {
+    let snapshot = Snapshot::new(
+        vec![cooldown(
+            "timeout",
+            ModelCooldownKind::Timeout,
+            Duration::from_secs(30 * 24 * 60 * 60),
+        )],
+        Vec::new(),
+    );
+    let now = Instant::now();
+    let left = remaining(&restored(&snapshot, now), "timeout", now).unwrap();
+    assert!(left <= Duration::from_secs(30 * 60));
+}
+
+#[test]
+fn saves_wait_for_a_path_and_a_burst_schedules_one() {
+    let mut writer = Writer {
+        path: None,
+        save_scheduled: false,
+    };
+    assert!(!writer.schedule(), "no file named yet");
+
+    writer.path = Some(temp_path("scheduled"));
+    assert!(writer.schedule());
+    assert!(!writer.schedule(), "the first save is pending");
+    assert_eq!(writer.take_save(), Some(temp_path("scheduled")));
+    assert!(writer.schedule(), "the next change schedules again");
+}
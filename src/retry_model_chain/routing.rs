@@ -0,0 +1,250 @@
+//! Cost-, speed- and quality-aware ordering of a retry chain.
+//!
+//! A preset's [`RoutingObjective`] reorders the effective chain; it never adds a
+//! model the chain does not hold. Each dimension is scaled between the best and
+//! worst candidate, and the sort is stable, so models an objective cannot tell
+//! apart keep the order the user configured. A model without a catalog price or
+//! latency ranks as the worst on that dimension rather than as free or instant.
+//! `Cheapest` keeps models below the preset's quality floor behind every model
+//! that meets it, so a low price alone cannot route a request to a weak model.
+
+use std::collections::HashSet;
+
+use super::{RetryChainKind, UNBENCHMARKED_FEED_QUALITY_TIER};
+use crate::config::Config;
+use crate::config::types::{RoutingObjective, RoutingWeights};
+use crate::model_config::ModelConfig;
+
+/// Size of the request a price comparison assumes. Only the ratio between
+/// models matters for ordering, so a typical short rewrite is enough.
+const TYPICAL_INPUT_TOKENS: u64 = 1_000;
+const TYPICAL_OUTPUT_TOKENS: u64 = 500;
+
+/// Like [`super::resolve_next_retry_model`], taking the first compatible model
+/// from the chain as ordered for `objective`. The reordered chain holds the same
+/// models, so when none of them fits, the unrouted resolver reaches the same
+/// automatic fallback.
+pub fn resolve_next_routed_model(
+    current_model_id: &str,
+    failed_model_ids: &[String],
+    blocked_providers: &HashSet<String>,
+    chain_kind: RetryChainKind,
+    config: &Config,
+    objective: RoutingObjective,
+    quality_floor: u8,
+) -> Option<ModelConfig> {
+    let chain = order(
+        chain_kind.effective_chain(config),
+        objective,
+        quality_floor,
+        |id| metrics(id, chain_kind, config),
+    );
+    super::first_compatible_in_chain(
+        &chain,
+        current_model_id,
+        failed_model_ids,
+        blocked_providers,
+        chain_kind,
+        config,
+    )
+    .or_else(|| {
+        super::resolve_next_retry_model(
+            current_model_id,
+            failed_model_ids,
+            blocked_providers,
+            chain_kind,
+            config,
+        )
+    })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) struct RouteMetrics {
+    /// List price of a typical request in USD.
+    pub(super) cost_usd: Option<f64>,
+    pub(super) latency_ms: Option<u32>,
+    pub(super) quality_tier: u8,
+}
+
+/// What the catalog knows about one chain entry.
+pub(super) fn metrics(id: &str, chain_kind: RetryChainKind, config: &Config) -> RouteMetrics {
+    let model = crate::model_config::get_model_by_id_with_custom(id, &config.custom_models);
+    let image_tokens = match chain_kind {
+        RetryChainKind::ImageToText => u64::from(super::budget::MEASURED_MIN_IMAGE_TOKENS),
+        RetryChainKind::TextToText => 0,
+    };
+    RouteMetrics {
+        cost_usd: model
+            .as_ref()
+            .and_then(|model| crate::model_config::model_pricing(&model.provider, &model.full_name))
+            .map(|pricing| {
+                pricing.text_request_usd(TYPICAL_INPUT_TOKENS, image_tokens, TYPICAL_OUTPUT_TOKENS)
+            }),
+        latency_ms: model.as_ref().and_then(|model| model.typical_latency_ms),
+        quality_tier: model
+            .and_then(|model| model.intelligence_tier)
+            .unwrap_or(UNBENCHMARKED_FEED_QUALITY_TIER),
+    }
+}
+
+/// Reorders `chain` for `objective`; `Priority` and an all-zero mix keep it.
+/// `quality_floor` applies to `Cheapest` only.
+pub(super) fn order(
+    chain: Vec<String>,
+    objective: RoutingObjective,
+    quality_floor: u8,
+    metrics: impl Fn(&str) -> RouteMetrics,
+) -> Vec<String> {
+    let (weights, floor) = match objective {
+        RoutingObjective::Priority => return chain,
+        RoutingObjective::Cheapest => (weights(1, 0, 0), quality_floor),
+        RoutingObjective::Fastest => (weights(0, 1, 0), 0),
+        RoutingObjective::BestQuality => (weights(0, 0, 1), 0),
+        RoutingObjective::Weighted(mix) => (mix, 0),
+    };
+    let total = u32::from(weights.cost) + u32::from(weights.speed) + u32::from(weights.quality);
+    if total == 0 || chain.len() < 2 {
+        return chain;
+    }
+    let measured: Vec<RouteMetrics> = chain.iter().map(|id| metrics(id)).collect();
+    let cost = scaled(measured.iter().map(|metrics| metrics.cost_usd));
+    let speed = scaled(
+        measured
+            .iter()
+            .map(|metrics| metrics.latency_ms.map(f64::from)),
+    );
+    // Higher tiers are better, so negate them to share the lower-is-better scale.
+    let quality = scaled(
+        measured
+            .iter()
+            .map(|metrics| Some(-f64::from(metrics.quality_tier))),
+    );
+    let mut scored: Vec<(bool, f64, String)> = chain
+        .into_iter()
+        .enumerate()
+        .map(|(index, id)| {
+            let score = f64::from(weights.cost) * cost[index]
+                + f64::from(weights.speed) * speed[index]
+                + f64::from(weights.quality) * quality[index];
+            let below_floor = measured[index].quality_tier < floor;
+            (below_floor, score / f64::from(total), id)
+        })
+        .collect();
+    scored.sort_by(|left, right| left.0.cmp(&right.0).then(left.1.total_cmp(&right.1)));
+    scored.into_iter().map(|(_, _, id)| id).collect()
+}
+
+fn weights(cost: u8, speed: u8, quality: u8) -> RoutingWeights {
+    RoutingWeights {
+        cost,
+        speed,
+        quality,
+    }
+}
+
+/// Maps known values onto 0 (best) through 1 (worst); unknown values are 1.
+fn scaled(values: impl Iterator<Item = Option<f64>>) -> Vec<f64> {
+    let values: Vec<Option<f64>> = values.collect();
+    let known = values.iter().flatten().copied();
+    let (best, worst) = known.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+        (min.min(value), max.max(value))
+    });
+    values
+        .into_iter()
+        .map(|value| match value {
+            Some(value) if worst > best => (value - best) / (worst - best),
+            Some(_) => 0.0,
+            None => 1.0,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain() -> Vec<String> {
+        ["slow-smart-paid", "fast-free", "mid-unpriced"]
+            .map(str::to_string)
+            .to_vec()
+    }
+
+    fn metrics(id: &str) -> RouteMetrics {
+        match id {
+            "slow-smart-paid" => RouteMetrics {
+                cost_usd: Some(0.004),
+                latency_ms: Some(4_000),
+                quality_tier: 6,
+            },
+            "fast-free" => RouteMetrics {
+                cost_usd: Some(0.0),
+                latency_ms: Some(400),
+                quality_tier: 3,
+            },
+            _ => RouteMetrics {
+                cost_usd: None,
+                latency_ms: Some(1_500),
+                quality_tier: 4,
+            },
+        }
+    }
+
+    #[test]
+    fn single_objectives_reorder_the_same_candidates() {
+        assert_eq!(
+            order(chain(), RoutingObjective::Priority, 1, metrics),
+            chain()
+        );
+        assert_eq!(
+            order(chain(), RoutingObjective::Cheapest, 1, metrics),
+            ["fast-free", "slow-smart-paid", "mid-unpriced"]
+        );
+        assert_eq!(
+            order(chain(), RoutingObjective::Fastest, 6, metrics),
+            ["fast-free", "mid-unpriced", "slow-smart-paid"]
+        );
+        assert_eq!(
+            order(chain(), RoutingObjective::BestQuality, 6, metrics),
+            ["slow-smart-paid", "mid-unpriced", "fast-free"]
+        );
+    }
+
+    #[test]
+    fn weighted_mix_trades_dimensions_and_ties_keep_chain_order() {
+        let quality_first = RoutingObjective::Weighted(weights(1, 1, 8));
+        assert_eq!(
+            order(chain(), quality_first, 6, metrics)[0],
+            "slow-smart-paid"
+        );
+        let balanced = RoutingObjective::Weighted(RoutingWeights::default());
+        assert_eq!(order(chain(), balanced, 6, metrics)[0], "fast-free");
+        let unmeasured = |_: &str| RouteMetrics {
+            cost_usd: None,
+            latency_ms: None,
+            quality_tier: 4,
+        };
+        assert_eq!(order(chain(), balanced, 6, unmeasured), chain());
+        assert_eq!(
+            order(
+                chain(),
+                RoutingObjective::Weighted(weights(0, 0, 0)),
+                6,
+                metrics
+            ),
+            chain()
+        );
+    }
+
+    #[test]
+    fn cheapest_tries_models_below_the_quality_floor_last() {
+        assert_eq!(
+            order(chain(), RoutingObjective::Cheapest, 4, metrics),
+            ["slow-smart-paid", "mid-unpriced", "fast-free"]
+        );
+        assert_eq!(
+            order(chain(), RoutingObjective::Cheapest, 7, metrics),
+            order(chain(), RoutingObjective::Cheapest, 1, metrics),
+            "a floor no model meets leaves the price order"
+        );
+    }
+}
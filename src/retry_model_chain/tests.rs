@@ -1,6 +1,7 @@
 use super::cooldown::{
-    MODEL_TIMEOUT_COOLDOWN, claim_model_attempt_at, model_cooldown_skip_reason_at,
-    rate_limit_error, record_model_failure_at, unavailable_model_error,
+    MODEL_BILLING_COOLDOWN, MODEL_RATE_LIMIT_COOLDOWN, MODEL_TIMEOUT_COOLDOWN,
+    claim_model_attempt_at, key_cooldown, model_cooldown_skip_reason_at, rate_limit_error,
+    record_model_failure_at, record_model_failure_with, unavailable_model_error,
 };
 use super::{
     RetryChainKind, UNBENCHMARKED_FEED_QUALITY_TIER, benchmark_derived_timeout,
@@ -166,6 +167,76 @@ fn distinguishes_rate_limits_from_transient_server_errors() {
     assert!(!rate_limit_error("vision API HTTP 503"));
 }
 
+#[test]
+fn only_quota_and_billing_failures_bench_a_pooled_key() {
+    assert_eq!(
+        key_cooldown("HTTP 429: quota exceeded", None),
+        Some(MODEL_RATE_LIMIT_COOLDOWN)
+    );
+    assert_eq!(
+        key_cooldown("HTTP 402: Payment Required", None),
+        Some(MODEL_BILLING_COOLDOWN)
+    );
+    assert_eq!(key_cooldown("vision API HTTP 503", None), None);
+    assert_eq!(key_cooldown("request timed out", None), None);
+    assert_eq!(
+        key_cooldown("HTTP 429: quota exceeded", Some(Duration::from_secs(90))),
+        Some(Duration::from_secs(90))
+    );
+}
+
+#[test]
+fn a_header_reported_reset_sets_the_rate_limit_cooldown() {
+    let model_id = "test-header-reset-cooldown-text";
+    let started = Instant::now();
+    record_model_success(model_id);
+    record_model_failure_with(
+        model_id,
+        "HTTP 429: too many requests",
+        Some(Duration::from_secs(3600)),
+        started,
+    );
+
+    let reason = model_cooldown_skip_reason_at(model_id, started + Duration::from_secs(1200))
+        .expect("the reported hour should outlast the fixed five minutes");
+    assert!(reason.starts_with("MODEL_RATE_LIMIT_COOLDOWN:"));
+    record_model_success(model_id);
+}
+
+#[test]
+fn spent_windows_and_retry_after_report_their_reset() {
+    use crate::retry_model_chain::header_reset;
+
+    let reset_of = |pairs: &[(&'static str, &str)]| {
+        let mut headers = ureq::http::HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        let count = |name: &str| -> Option<u32> { headers.get(name)?.to_str().ok()?.parse().ok() };
+        header_reset(&headers, count)
+    };
+    assert_eq!(
+        reset_of(&[
+            ("x-ratelimit-remaining-requests", "0"),
+            ("x-ratelimit-reset-requests", "2m59.5s"),
+            ("x-ratelimit-remaining-tokens", "1200"),
+            ("x-ratelimit-reset-tokens", "7.6s"),
+        ]),
+        Some(Duration::from_secs_f64(179.5))
+    );
+    assert_eq!(
+        reset_of(&[("retry-after", "14"), ("x-ratelimit-reset-tokens", "7.6s")]),
+        Some(Duration::from_secs(14))
+    );
+    assert_eq!(
+        reset_of(&[
+            ("x-ratelimit-remaining-requests", "12"),
+            ("x-ratelimit-reset-requests", "2m59.5s"),
+        ]),
+        None
+    );
+}
+
 #[test]
 fn search_capable_retry_skips_incompatible_priority_candidates() {
     let config = Config {
@@ -325,7 +396,7 @@ fn real_groq_rate_headers_populate_the_token_budget() {
     headers.insert("x-ratelimit-reset-tokens", "22.012s".parse().unwrap());
     record_token_budget("groq", "qwen/qwen3.6-27b", &headers);
 
-    let key = budget_key("groq", "qwen/qwen3.6-27b");
+    let key = budget_key("groq", "qwen/qwen3.6-27b", None);
     // 1000 left cannot cover the cheapest measured call plus its output reserve.
     assert!(budget::shortfall(&key, budget::MEASURED_MIN_IMAGE_TOKENS + 512).is_some());
     // ... but it comfortably covers a hypothetical tiny one, so nothing is blocked.
@@ -334,7 +405,7 @@ fn real_groq_rate_headers_populate_the_token_budget() {
     // A response without the headers must leave admission untouched.
     record_token_budget("groq", "unmetered-model", &ureq::http::HeaderMap::new());
     assert_eq!(
-        budget::shortfall(&budget_key("groq", "unmetered-model"), 9_000),
+        budget::shortfall(&budget_key("groq", "unmetered-model", None), 9_000),
         None
     );
 }
@@ -0,0 +1,143 @@
+//! Text comparison metrics shared by the catalog benchmark and golden-set
+//! evaluation.
+//!
+//! Similarities are `1 - edit distance / longer length`, so they stay within
+//! `0..=1`. Error rates divide by the expected length only, as CER and WER are
+//! conventionally defined, and exceed 1 when the output adds more than the
+//! reference holds.
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Case-, punctuation- and Unicode-form-insensitive character similarity.
+pub fn text_similarity(actual: &str, expected: &str) -> f64 {
+    let actual: Vec<char> = normalize(actual).chars().collect();
+    let expected: Vec<char> = normalize(expected).chars().collect();
+    character_similarity(&actual, &expected)
+}
+
+/// Character similarity that keeps case, punctuation and diacritics, which an
+/// OCR result is expected to reproduce.
+pub fn ocr_similarity(actual: &str, expected: &str) -> f64 {
+    let actual: Vec<char> = normalize_ocr(actual).chars().collect();
+    let expected: Vec<char> = normalize_ocr(expected).chars().collect();
+    character_similarity(&actual, &expected)
+}
+
+fn character_similarity(actual: &[char], expected: &[char]) -> f64 {
+    if actual.is_empty() && expected.is_empty() {
+        return 1.0;
+    }
+    let distance = levenshtein(actual, expected);
+    1.0 - distance as f64 / actual.len().max(expected.len()) as f64
+}
+
+/// Character error rate over OCR-normalized text (whitespace runs collapsed).
+pub fn character_error_rate(actual: &str, expected: &str) -> f64 {
+    let actual: Vec<char> = normalize_ocr(actual).chars().collect();
+    let expected: Vec<char> = normalize_ocr(expected).chars().collect();
+    error_rate(&actual, &expected)
+}
+
+/// Word error rate over whitespace-separated, OCR-normalized words.
+pub fn word_error_rate(actual: &str, expected: &str) -> f64 {
+    let actual = normalize_ocr(actual);
+    let expected = normalize_ocr(expected);
+    let actual: Vec<&str> = actual.split_whitespace().collect();
+    let expected: Vec<&str> = expected.split_whitespace().collect();
+    error_rate(&actual, &expected)
+}
+
+fn error_rate<T: PartialEq>(actual: &[T], expected: &[T]) -> f64 {
+    if expected.is_empty() {
+        return if actual.is_empty() { 0.0 } else { 1.0 };
+    }
+    levenshtein(actual, expected) as f64 / expected.len() as f64
+}
+
+/// Share of `terms` found in `actual`, compared after [`normalize`].
+pub fn term_coverage(actual: &str, terms: &[String]) -> f64 {
+    if terms.is_empty() {
+        return 1.0;
+    }
+    let actual = normalize(actual);
+    let found = terms
+        .iter()
+        .filter(|term| actual.contains(&normalize(term)))
+        .count();
+    found as f64 / terms.len() as f64
+}
+
+/// NFKC, lowercase, and every run of non-alphanumerics as one space.
+pub fn normalize(value: &str) -> String {
+    let mut normalized = String::new();
+    let mut pending_space = false;
+    for character in value.nfkc().flat_map(char::to_lowercase) {
+        if character.is_alphanumeric() {
+            if pending_space && !normalized.is_empty() {
+                normalized.push(' ');
+            }
+            normalized.push(character);
+            pending_space = false;
+        } else {
+            pending_space = true;
+        }
+    }
+    normalized
+}
+
+fn normalize_ocr(value: &str) -> String {
+    let mut normalized = String::new();
+    let mut pending_space = false;
+    for character in value.nfkc() {
+        if character.is_whitespace() {
+            pending_space = true;
+            continue;
+        }
+        if pending_space && !normalized.is_empty() {
+            normalized.push(' ');
+        }
+        normalized.push(match character {
+            '‘' | '’' => '\'',
+            '“' | '”' => '"',
+            other => other,
+        });
+        pending_space = false;
+    }
+    normalized
+}
+
+fn levenshtein<T: PartialEq>(left: &[T], right: &[T]) -> usize {
+    let mut previous: Vec<usize> = (0..=right.len()).collect();
+    let mut current = vec![0; right.len() + 1];
+    for (left_index, left_item) in left.iter().enumerate() {
+        current[0] = left_index + 1;
+        for (right_index, right_item) in right.iter().enumerate() {
+            current[right_index + 1] = (previous[right_index + 1] + 1)
+                .min(current[right_index] + 1)
+                .min(previous[right_index] + usize::from(left_item != right_item));
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[right.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{character_error_rate, word_error_rate};
+
+    #[test]
+    fn error_rates_count_edits_against_the_reference_length() {
+        assert_eq!(character_error_rate("The  Sun.", "The Sun."), 0.0);
+        assert_eq!(character_error_rate("Tho Sun.", "The Sun."), 1.0 / 8.0);
+        assert_eq!(word_error_rate("the cat sat", "the cat sat down"), 0.25);
+        assert_eq!(word_error_rate("a b c d e f", "x"), 6.0);
+        assert_eq!(word_error_rate("", ""), 0.0);
+        assert_eq!(character_error_rate("extra", ""), 1.0);
+    }
+
+    #[test]
+    fn error_rates_keep_case_and_diacritics() {
+        assert!(character_error_rate("PRIBRAM", "PŘÍBRAM") > 0.0);
+        assert_eq!(word_error_rate("hello World", "hello world"), 0.5);
+    }
+}
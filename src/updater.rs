@@ -1,6 +1,8 @@
 mod github;
 mod manifest;
+mod rollout;
 
+use crate::config::{UpdateChannel, UpdatePreferences};
 use anyhow::{Context, Result, bail};
 use sha2::{Digest, Sha256};
 use std::fs::OpenOptions;
@@ -20,6 +22,8 @@ struct UpdateCandidate {
     download_url: String,
     size_bytes: u64,
     sha256: String,
+    /// Feed the release came from; only beta feeds may carry prereleases.
+    channel: UpdateChannel,
 }
 
 #[derive(Default)]
@@ -81,7 +85,9 @@ impl UpdateCandidate {
         if self.size_bytes == 0 || self.size_bytes > MAX_INSTALLER_BYTES {
             bail!("update installer size is outside the accepted range");
         }
-        if !self.version.pre.is_empty() || !self.version.build.is_empty() {
+        if !self.version.build.is_empty()
+            || (!self.version.pre.is_empty() && self.channel != UpdateChannel::Beta)
+        {
             bail!("update version is not a stable release");
         }
         if self.sha256.len() != 64 || !self.sha256.bytes().all(|byte| byte.is_ascii_hexdigit()) {
@@ -91,12 +97,38 @@ impl UpdateCandidate {
     }
 }
 
-fn latest_candidate() -> Result<UpdateCandidate> {
-    match manifest::fetch() {
-        Ok(Some(candidate)) => Ok(candidate),
-        Ok(None) => github::fetch_latest().context("stable update feed is not published"),
-        Err(error) => Err(error).context("stable update feed was rejected"),
+/// Releases the channel may offer. Beta installs also follow the stable feed,
+/// so a stable release newer than the last beta still reaches them, and a beta
+/// feed that cannot be read leaves them on stable rather than failing the check.
+fn available_releases(channel: UpdateChannel) -> Result<Vec<rollout::Release>> {
+    let stable = match manifest::fetch(UpdateChannel::Stable) {
+        Ok(Some(release)) => release,
+        Ok(None) => rollout::Release::unstaged(
+            github::fetch_latest().context("stable update feed is not published")?,
+        ),
+        Err(error) => return Err(error).context("stable update feed was rejected"),
+    };
+    let mut releases = vec![stable];
+    if channel == UpdateChannel::Beta {
+        match manifest::fetch(UpdateChannel::Beta) {
+            Ok(Some(beta)) => releases.push(beta),
+            Ok(None) => {}
+            Err(error) => {
+                crate::log_info!("[updater] beta update feed was rejected: {error:#}");
+            }
+        }
     }
+    Ok(releases)
+}
+
+fn choose_release(preferences: &UpdatePreferences) -> Result<rollout::Choice> {
+    let current = semver::Version::parse(env!("CARGO_PKG_VERSION"))?;
+    rollout::choose(
+        &current,
+        available_releases(preferences.channel)?,
+        rollout::install_bucket(),
+        preferences.deferred_version.as_deref(),
+    )
 }
 
 fn bump_is_greater(current: &str, new: &semver::Version) -> Result<bool> {
@@ -108,7 +140,12 @@ pub enum UpdateStatus {
     Idle,
     Checking,
     UpToDate(String),
-    UpdateAvailable { version: String, body: String },
+    UpdateAvailable {
+        version: String,
+        body: String,
+    },
+    /// The newest eligible release is one the user chose to skip.
+    Deferred(String),
     Downloading,
     Error(String),
     UpdatedAndRestartRequired(StagedUpdate),
@@ -127,7 +164,7 @@ impl Updater {
         }
     }
 
-    pub fn check_for_updates(&self) {
+    pub fn check_for_updates(&self, preferences: &UpdatePreferences) {
         let tx = self.tx.clone();
         let selection = Arc::clone(&self.selection);
         let generation = {
@@ -139,45 +176,38 @@ impl Updater {
             let _ = tx.send(UpdateStatus::Checking);
             generation
         };
-        thread::spawn(move || match latest_candidate() {
-            Ok(candidate) => {
-                let current = env!("CARGO_PKG_VERSION");
-                match bump_is_greater(current, &candidate.version) {
-                    Ok(true) => {
-                        let status = UpdateStatus::UpdateAvailable {
-                            version: candidate.version.to_string(),
-                            body: candidate.body.clone(),
-                        };
-                        publish_check_result(&selection, generation, &tx, status, Some(candidate));
-                    }
-                    Ok(false) => {
-                        publish_check_result(
-                            &selection,
-                            generation,
-                            &tx,
-                            UpdateStatus::UpToDate(current.to_string()),
-                            None,
-                        );
-                    }
-                    Err(error) => publish_check_result(
-                        &selection,
-                        generation,
-                        &tx,
-                        UpdateStatus::Error(error.to_string()),
-                        None,
-                    ),
+        let preferences = preferences.clone();
+        thread::spawn(move || {
+            let (status, candidate) = match choose_release(&preferences) {
+                Ok(rollout::Choice::Offer(candidate)) => {
+                    let status = UpdateStatus::UpdateAvailable {
+                        version: candidate.version.to_string(),
+                        body: candidate.body.clone(),
+                    };
+                    (status, Some(candidate))
                 }
-            }
-            Err(error) => publish_check_result(
-                &selection,
-                generation,
-                &tx,
-                UpdateStatus::Error(error.to_string()),
-                None,
-            ),
+                Ok(rollout::Choice::Deferred(version)) => {
+                    (UpdateStatus::Deferred(version.to_string()), None)
+                }
+                Ok(rollout::Choice::UpToDate) => (
+                    UpdateStatus::UpToDate(env!("CARGO_PKG_VERSION").to_string()),
+                    None,
+                ),
+                Err(error) => (UpdateStatus::Error(error.to_string()), None),
+            };
+            publish_check_result(&selection, generation, &tx, status, candidate);
         });
     }
 
+    /// Drops the offered release and any check still in flight. The caller
+    /// stores `version` in the update preferences so later checks skip it.
+    pub fn defer(&self, version: &str) {
+        if let Ok(mut state) = self.selection.lock() {
+            state.begin_check();
+        }
+        let _ = self.tx.send(UpdateStatus::Deferred(version.to_string()));
+    }
+
     pub fn perform_update(&self) {
         let tx = self.tx.clone();
         let candidate = self
@@ -346,6 +376,7 @@ mod tests {
             download_url: "https://github.com/nganlinh4/screen-goated-toolbox/releases/download/v5.5.0/ScreenGoatedToolbox_v5.5.0.exe".into(),
             size_bytes: bytes.len() as u64,
             sha256: format!("{:x}", Sha256::digest(bytes)),
+            channel: UpdateChannel::Stable,
         }
     }
 
@@ -358,8 +389,25 @@ mod tests {
             download_url: "https://example.com/update.exe".into(),
             size_bytes: 100,
             sha256: "a".repeat(64),
+            channel: UpdateChannel::Stable,
+        };
+        assert!(candidate.validate().is_err());
+    }
+
+    #[test]
+    fn only_beta_candidates_may_be_prereleases() {
+        let version = semver::Version::parse("5.6.0-beta.1").unwrap();
+        let mut candidate = UpdateCandidate {
+            asset_name: format!("ScreenGoatedToolbox_v{version}.exe"),
+            download_url: format!(
+                "https://github.com/nganlinh4/screen-goated-toolbox/releases/download/v{version}/ScreenGoatedToolbox_v{version}.exe"
+            ),
+            version,
+            ..valid_candidate(b"installer")
         };
         assert!(candidate.validate().is_err());
+        candidate.channel = UpdateChannel::Beta;
+        candidate.validate().unwrap();
     }
 
     #[test]
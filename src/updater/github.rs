@@ -97,6 +97,7 @@ fn candidate_from_release(release: GithubRelease) -> Option<UpdateCandidate> {
         download_url: asset.browser_download_url,
         size_bytes: asset.size,
         sha256,
+        channel: crate::config::UpdateChannel::Stable,
     };
     candidate.validate().ok().map(|()| candidate)
 }
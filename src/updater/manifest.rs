@@ -1,11 +1,13 @@
 use super::UpdateCandidate;
+use super::rollout::Release;
+use crate::config::UpdateChannel;
 use anyhow::{Context, Result, bail};
 use serde::Deserialize;
 use std::io::Read;
 use std::time::Duration;
 
-const MANIFEST_URL: &str = "https://raw.githubusercontent.com/nganlinh4/screen-goated-toolbox/app-update-feed/stable-v1.json";
-const SIGNATURE_URL: &str = "https://raw.githubusercontent.com/nganlinh4/screen-goated-toolbox/app-update-feed/stable-v1.sig";
+const FEED_BASE_URL: &str =
+    "https://raw.githubusercontent.com/nganlinh4/screen-goated-toolbox/app-update-feed";
 const MAX_MANIFEST_BYTES: u64 = 64 * 1024;
 const PUBLIC_KEY_HEX: &str = include_str!(concat!(
     env!("CARGO_MANIFEST_DIR"),
@@ -14,15 +16,24 @@ const PUBLIC_KEY_HEX: &str = include_str!(concat!(
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct StableManifest {
+struct ChannelManifest {
     schema_version: u32,
     channel: String,
     version: String,
     #[serde(default)]
     release_notes: String,
+    /// Share of installs offered this release; omitted means everyone.
+    #[serde(default = "full_rollout")]
+    rollout_percent: u8,
+    #[serde(default)]
+    minimum_version: Option<String>,
     installer: Installer,
 }
 
+fn full_rollout() -> u8 {
+    100
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct Installer {
@@ -32,22 +43,34 @@ struct Installer {
     sha256: String,
 }
 
-pub(super) fn fetch() -> Result<Option<UpdateCandidate>> {
-    let Some(payload) = fetch_optional(MANIFEST_URL, MAX_MANIFEST_BYTES)? else {
+/// Fetches and verifies one channel's signed manifest. Each channel is signed
+/// separately, so a stable install never trusts a beta payload.
+pub(super) fn fetch(channel: UpdateChannel) -> Result<Option<Release>> {
+    let manifest_url = format!("{FEED_BASE_URL}/{}-v1.json", channel.id());
+    let Some(payload) = fetch_optional(&manifest_url, MAX_MANIFEST_BYTES)? else {
         return Ok(None);
     };
-    let signature = fetch_required(SIGNATURE_URL, 64)?;
+    let signature = fetch_required(&format!("{FEED_BASE_URL}/{}-v1.sig", channel.id()), 64)?;
     let public_key = crate::crypto::decode_hex(PUBLIC_KEY_HEX.trim(), "app update manifest")?;
     crate::crypto::verify_p256_sha256(&public_key, &payload, &signature, "app update manifest")?;
-    parse(&payload).map(Some)
+    parse(&payload, channel).map(Some)
 }
 
-fn parse(payload: &[u8]) -> Result<UpdateCandidate> {
-    let manifest: StableManifest =
+fn parse(payload: &[u8], channel: UpdateChannel) -> Result<Release> {
+    let manifest: ChannelManifest =
         serde_json::from_slice(payload).context("app update manifest JSON is invalid")?;
-    if manifest.schema_version != 1 || manifest.channel != "stable" {
+    if manifest.schema_version != 1 || manifest.channel != channel.id() {
         bail!("app update manifest contract is unsupported");
     }
+    if manifest.rollout_percent > 100 {
+        bail!("app update manifest rollout is outside 0-100");
+    }
+    let minimum_version = manifest
+        .minimum_version
+        .as_deref()
+        .map(semver::Version::parse)
+        .transpose()
+        .context("app update manifest minimum version is invalid")?;
     let candidate = UpdateCandidate {
         version: semver::Version::parse(&manifest.version)
             .context("app update manifest version is invalid")?,
@@ -56,9 +79,14 @@ fn parse(payload: &[u8]) -> Result<UpdateCandidate> {
         download_url: manifest.installer.url,
         size_bytes: manifest.installer.size_bytes,
         sha256: manifest.installer.sha256,
+        channel,
     };
     candidate.validate()?;
-    Ok(candidate)
+    Ok(Release {
+        candidate,
+        rollout_percent: manifest.rollout_percent,
+        minimum_version,
+    })
 }
 
 fn fetch_optional(url: &str, limit: u64) -> Result<Option<Vec<u8>>> {
@@ -107,14 +135,31 @@ mod tests {
     #[test]
     fn parses_a_valid_stable_contract() {
         let payload = br#"{"schemaVersion":1,"channel":"stable","version":"5.5.0","releaseNotes":"Notes","installer":{"name":"ScreenGoatedToolbox_v5.5.0.exe","url":"https://github.com/nganlinh4/screen-goated-toolbox/releases/download/v5.5.0/ScreenGoatedToolbox_v5.5.0.exe","sizeBytes":123,"sha256":"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"}}"#;
-        let candidate = parse(payload).unwrap();
-        assert_eq!(candidate.version, semver::Version::new(5, 5, 0));
-        assert_eq!(candidate.body, "Notes");
+        let release = parse(payload, UpdateChannel::Stable).unwrap();
+        assert_eq!(release.candidate.version, semver::Version::new(5, 5, 0));
+        assert_eq!(release.candidate.body, "Notes");
+        assert_eq!(release.rollout_percent, 100);
+        assert!(release.minimum_version.is_none());
     }
 
     #[test]
     fn rejects_a_non_stable_channel() {
         let payload = br#"{"schemaVersion":1,"channel":"staging","version":"5.5.0","installer":{"name":"ScreenGoatedToolbox_v5.5.0.exe","url":"https://github.com/nganlinh4/screen-goated-toolbox/releases/download/v5.5.0/ScreenGoatedToolbox_v5.5.0.exe","sizeBytes":123,"sha256":"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"}}"#;
-        assert!(parse(payload).is_err());
+        assert!(parse(payload, UpdateChannel::Stable).is_err());
+    }
+
+    #[test]
+    fn parses_a_staged_beta_prerelease_only_from_the_beta_feed() {
+        let payload = br#"{"schemaVersion":1,"channel":"beta","version":"5.6.0-beta.1","rolloutPercent":25,"minimumVersion":"5.4.0","installer":{"name":"ScreenGoatedToolbox_v5.6.0-beta.1.exe","url":"https://github.com/nganlinh4/screen-goated-toolbox/releases/download/v5.6.0-beta.1/ScreenGoatedToolbox_v5.6.0-beta.1.exe","sizeBytes":123,"sha256":"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"}}"#;
+        let release = parse(payload, UpdateChannel::Beta).unwrap();
+        assert_eq!(release.rollout_percent, 25);
+        assert_eq!(release.minimum_version, Some(semver::Version::new(5, 4, 0)));
+        assert!(parse(payload, UpdateChannel::Stable).is_err());
+    }
+
+    #[test]
+    fn rejects_a_rollout_above_one_hundred_percent() {
+        let payload = br#"{"schemaVersion":1,"channel":"stable","version":"5.5.0","rolloutPercent":101,"installer":{"name":"ScreenGoatedToolbox_v5.5.0.exe","url":"https://github.com/nganlinh4/screen-goated-toolbox/releases/download/v5.5.0/ScreenGoatedToolbox_v5.5.0.exe","sizeBytes":123,"sha256":"aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"}}"#;
+        assert!(parse(payload, UpdateChannel::Stable).is_err());
     }
 }
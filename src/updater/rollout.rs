@@ -0,0 +1,248 @@
+//! Staged rollout, minimum-version gating and deferral of signed releases.
+//!
+//! Each install keeps a random id on disk and hashes it into a bucket from 0
+//! to 99; a release with `rolloutPercent: 20` reaches buckets 0 through 19.
+//! The bucket never changes, so widening a rollout only adds installs.
+
+use anyhow::{Result, bail};
+use sha2::{Digest, Sha256};
+
+use super::UpdateCandidate;
+
+const INSTALL_ID_BYTES: usize = 16;
+
+/// One signed release and the conditions it is offered under.
+#[derive(Debug, Clone)]
+pub(super) struct Release {
+    pub(super) candidate: UpdateCandidate,
+    /// Share of installs, by bucket, that see this release.
+    pub(super) rollout_percent: u8,
+    /// Installs older than this must update by other means first.
+    pub(super) minimum_version: Option<semver::Version>,
+}
+
+impl Release {
+    /// A release that is offered to every install.
+    pub(super) fn unstaged(candidate: UpdateCandidate) -> Self {
+        Self {
+            candidate,
+            rollout_percent: 100,
+            minimum_version: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(super) enum Choice {
+    Offer(UpdateCandidate),
+    Deferred(semver::Version),
+    UpToDate,
+}
+
+/// Picks the newest release this install may take from the channel's feeds.
+pub(super) fn choose(
+    current: &semver::Version,
+    releases: Vec<Release>,
+    bucket: u8,
+    deferred: Option<&str>,
+) -> Result<Choice> {
+    let mut gated = None;
+    let mut best: Option<UpdateCandidate> = None;
+    for release in releases {
+        if release.candidate.version <= *current || bucket >= release.rollout_percent {
+            continue;
+        }
+        if let Some(minimum) = release.minimum_version
+            && *current < minimum
+        {
+            gated = Some((release.candidate.version, minimum));
+            continue;
+        }
+        if best
+            .as_ref()
+            .is_none_or(|best| release.candidate.version > best.version)
+        {
+            best = Some(release.candidate);
+        }
+    }
+    match (best, gated) {
+        (Some(candidate), _) => {
+            let deferred = deferred.and_then(|version| semver::Version::parse(version).ok());
+            if deferred.as_ref() == Some(&candidate.version) {
+                Ok(Choice::Deferred(candidate.version))
+            } else {
+                Ok(Choice::Offer(candidate))
+            }
+        }
+        (None, Some((version, minimum))) => bail!(
+            "version {version} requires version {minimum} or newer; install it from the releases page"
+        ),
+        (None, None) => Ok(Choice::UpToDate),
+    }
+}
+
+/// This install's rollout bucket. An unreadable id file is replaced, so a
+/// broken file costs at most one re-roll of the bucket.
+pub(super) fn install_bucket() -> u8 {
+    let path = crate::paths::app_runtime_local_data_dir().join("update-rollout-id");
+    let stored = std::fs::read_to_string(&path)
+        .ok()
+        .map(|id| id.trim().to_string())
+        .filter(|id| valid_install_id(id));
+    let id = match stored {
+        Some(id) => id,
+        None => match new_install_id(&path) {
+            Ok(id) => id,
+            Err(error) => {
+                // Without an id this install joins only full rollouts.
+                crate::log_info!("[updater] rollout id is unavailable: {error:#}");
+                return 99;
+            }
+        },
+    };
+    bucket_for(&id)
+}
+
+fn new_install_id(path: &std::path::Path) -> Result<String> {
+    let mut random = [0_u8; INSTALL_ID_BYTES];
+    getrandom::fill(&mut random).map_err(|error| anyhow::anyhow!("{error}"))?;
+    let id = random
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::atomic_json::write_bytes_atomic(path, id.as_bytes())?;
+    Ok(id)
+}
+
+fn valid_install_id(id: &str) -> bool {
+    id.len() == INSTALL_ID_BYTES * 2 && id.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+fn bucket_for(id: &str) -> u8 {
+    let digest = Sha256::digest(id.as_bytes());
+    let value = u64::from_be_bytes(digest[..8].try_into().expect("digest has eight bytes"));
+    (value % 100) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(version: &str, rollout_percent: u8, minimum: Option<&str>) -> Release {
+        let version = semver::Version::parse(version).unwrap();
+        Release {
+            candidate: UpdateCandidate {
+                asset_name: format!("ScreenGoatedToolbox_v{version}.exe"),
+                download_url: String::new(),
+                version,
+                body: String::new(),
+                size_bytes: 1,
+                sha256: "a".repeat(64),
+                channel: crate::config::UpdateChannel::Stable,
+            },
+            rollout_percent,
+            minimum_version: minimum.map(|minimum| semver::Version::parse(minimum).unwrap()),
+        }
+    }
+
+    fn offered(choice: Choice) -> String {
+        match choice {
+            Choice::Offer(candidate) => candidate.version.to_string(),
+            other => panic!("expected an offer, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn buckets_are_stable_and_spread() {
+        assert_eq!(bucket_for("00"), bucket_for("00"));
+        let buckets = (0..1000)
+            .map(|index| bucket_for(&format!("{index:032x}")))
+            .collect::<Vec<_>>();
+        assert!(buckets.iter().all(|bucket| *bucket < 100));
+        let below_half = buckets.iter().filter(|bucket| **bucket < 50).count();
+        assert!((400..600).contains(&below_half), "{below_half}");
+        assert!(valid_install_id(&"0f".repeat(16)));
+        assert!(!valid_install_id("0f"));
+    }
+
+    #[test]
+    fn staged_releases_reach_only_buckets_inside_the_rollout() {
+        let current = semver::Version::new(5, 5, 0);
+        let releases = || vec![release("5.5.1", 100, None), release("5.6.0", 20, None)];
+        assert_eq!(
+            offered(choose(&current, releases(), 19, None).unwrap()),
+            "5.6.0"
+        );
+        assert_eq!(
+            offered(choose(&current, releases(), 20, None).unwrap()),
+            "5.5.1"
+        );
+        assert!(matches!(
+            choose(&current, vec![release("5.6.0", 0, None)], 0, None).unwrap(),
+            Choice::UpToDate
+        ));
+    }
+
+    #[test]
+    fn beta_prereleases_compete_with_stable_by_version() {
+        let current = semver::Version::new(5, 5, 0);
+        let beta = release("5.6.0-beta.1", 100, None);
+        assert_eq!(
+            offered(
+                choose(
+                    &current,
+                    vec![release("5.5.1", 100, None), beta.clone()],
+                    50,
+                    None
+                )
+                .unwrap()
+            ),
+            "5.6.0-beta.1"
+        );
+        assert_eq!(
+            offered(choose(&current, vec![release("5.6.0", 100, None), beta], 50, None).unwrap()),
+            "5.6.0"
+        );
+    }
+
+    #[test]
+    fn minimum_version_gates_older_installs() {
+        let gated = vec![release("6.0.0", 100, Some("5.5.0"))];
+        let error = choose(&semver::Version::new(5, 4, 0), gated.clone(), 0, None).unwrap_err();
+        assert!(error.to_string().contains("5.5.0"), "{error}");
+        assert_eq!(
+            offered(choose(&semver::Version::new(5, 5, 0), gated, 0, None).unwrap()),
+            "6.0.0"
+        );
+    }
+
+    #[test]
+    fn a_deferred_release_is_held_back_until_a_newer_one_ships() {
+        let current = semver::Version::new(5, 5, 0);
+        assert!(matches!(
+            choose(
+                &current,
+                vec![release("5.6.0", 100, None)],
+                0,
+                Some("5.6.0")
+            )
+            .unwrap(),
+            Choice::Deferred(_)
+        ));
+        assert_eq!(
+            offered(
+                choose(
+                    &current,
+                    vec![release("5.6.1", 100, None)],
+                    0,
+                    Some("5.6.0")
+                )
+                .unwrap()
+            ),
+            "5.6.1"
+        );
+    }
+}
@@ -3,6 +3,10 @@ use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 use ureq::http::HeaderMap;
 
+#[cfg(not(feature = "recorder-worker"))]
+#[path = "usage_stats/spend.rs"]
+pub mod spend;
+
 pub const FRESH_THROUGH_SECONDS: u64 = 300;
 pub const AGING_THROUGH_SECONDS: u64 = 900;
 pub const LOCAL_RUNTIME_PROVIDERS: &[&str] = &["ollama", "parakeet", "qwen3", "moonshine"];
@@ -0,0 +1,233 @@
+//! Estimated model spend for the current calendar month.
+//!
+//! Providers do not report what a request cost, so each successful request is
+//! priced from the catalog's list rates with tokens estimated from characters.
+//! Images count at the measured billing floor, which makes the total a lower
+//! bound rather than an invoice. Requests to endpoints without catalog pricing
+//! are counted but add nothing, and the UI says how many there were.
+
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+const FILE_NAME: &str = "model-spend.json";
+const SUPPORTED_VERSION: u32 = 1;
+/// Rough English average; close enough for an estimate shown as approximate.
+const CHARS_PER_TOKEN: u64 = 4;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct MonthlySpend {
+    version: u32,
+    /// Local calendar month as `YYYY-MM`.
+    pub month: String,
+    pub requests: u64,
+    pub priced_requests: u64,
+    pub total_usd: f64,
+}
+
+impl MonthlySpend {
+    fn starting(month: &str) -> Self {
+        Self {
+            version: SUPPORTED_VERSION,
+            month: month.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Adds one request, starting over when the month has turned.
+    fn add(&mut self, month: &str, usd: Option<f64>) {
+        if self.month != month {
+            *self = Self::starting(month);
+        }
+        self.requests += 1;
+        if let Some(usd) = usd {
+            self.priced_requests += 1;
+            self.total_usd += usd;
+        }
+    }
+}
+
+/// Requests come in bursts, so the file is written off the request thread at
+/// most this often. Exiting between saves loses that stretch of an estimate
+/// unless [`flush`] runs first.
+const SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+struct SpendStore {
+    spend: MonthlySpend,
+    path: PathBuf,
+    /// A save is already waiting out [`SAVE_INTERVAL`].
+    save_scheduled: bool,
+}
+
+impl SpendStore {
+    /// A missing, unreadable or foreign file starts the month at zero.
+    fn load(path: PathBuf) -> Self {
+        let spend = match std::fs::read(&path) {
+            Ok(bytes) => match serde_json::from_slice::<MonthlySpend>(&bytes) {
+                Ok(spend) if spend.version == SUPPORTED_VERSION => spend,
+                Ok(_) | Err(_) => {
+                    crate::log_info!("[spend] ignoring unreadable spend estimate");
+                    MonthlySpend::default()
+                }
+            },
+            Err(_) => MonthlySpend::default(),
+        };
+        Self {
+            spend,
+            path,
+            save_scheduled: false,
+        }
+    }
+
+    /// Adds one request. True when the caller should schedule a save.
+    fn add(&mut self, month: &str, usd: Option<f64>) -> bool {
+        self.spend.add(month, usd);
+        !std::mem::replace(&mut self.save_scheduled, true)
+    }
+
+    /// What to write now.
+    fn take_save(&mut self) -> (PathBuf, MonthlySpend) {
+        self.save_scheduled = false;
+        (self.path.clone(), self.spend.clone())
+    }
+}
+
+static SPEND: LazyLock<Mutex<SpendStore>> = LazyLock::new(|| {
+    Mutex::new(SpendStore::load(
+        crate::paths::app_runtime_local_data_dir().join(FILE_NAME),
+    ))
+});
+
+fn current_month() -> String {
+    chrono::Local::now().format("%Y-%m").to_string()
+}
+
+/// This month's estimate; a total saved in an earlier month reads as zero.
+pub fn current() -> MonthlySpend {
+    let month = current_month();
+    match SPEND.lock() {
+        Ok(store) if store.spend.month == month => store.spend.clone(),
+        _ => MonthlySpend::starting(&month),
+    }
+}
+
+/// Estimated list price of one request, or `None` without catalog pricing.
+fn estimate_usd(
+    provider: &str,
+    api_model: &str,
+    input_chars: usize,
+    images: u32,
+    output_chars: usize,
+) -> Option<f64> {
+    let tokens = |chars: usize| (chars as u64).div_ceil(CHARS_PER_TOKEN);
+    let image_tokens =
+        u64::from(images) * u64::from(crate::retry_model_chain::MEASURED_MIN_IMAGE_TOKENS);
+    crate::model_config::model_pricing(provider, api_model).map(|pricing| {
+        pricing.text_request_usd(tokens(input_chars), image_tokens, tokens(output_chars))
+    })
+}
+
+/// Adds a completed request to this month's estimate. The file catches up on
+/// a background thread after [`SAVE_INTERVAL`].
+pub fn record_request(
+    provider: &str,
+    api_model: &str,
+    input_chars: usize,
+    images: u32,
+    output_chars: usize,
+) {
+    let usd = estimate_usd(provider, api_model, input_chars, images, output_chars);
+    let schedule = match SPEND.lock() {
+        Ok(mut store) => store.add(&current_month(), usd),
+        Err(_) => return,
+    };
+    if !schedule {
+        return;
+    }
+    let spawned = std::thread::Builder::new()
+        .name("sgt-spend-save".to_string())
+        .spawn(|| {
+            std::thread::sleep(SAVE_INTERVAL);
+            flush();
+        });
+    if let Err(error) = spawned {
+        crate::log_info!("[spend] could not schedule a save: {error}");
+        flush();
+    }
+}
+
+/// Writes the estimate now, e.g. before the app exits.
+pub fn flush() {
+    let Ok((path, snapshot)) = SPEND.lock().map(|mut store| store.take_save()) else {
+        return;
+    };
+    if let Err(error) = write(&path, &snapshot) {
+        crate::log_info!("[spend] could not save spend estimate: {error}");
+    }
+}
+
+fn write(path: &Path, spend: &MonthlySpend) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::atomic_json::write_json_atomic(path, spend)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn months_accumulate_and_roll_over() {
+        let mut spend = MonthlySpend::starting("2026-09");
+        spend.add("2026-09", Some(0.25));
+        spend.add("2026-09", None);
+        spend.add("2026-09", Some(0.5));
+        assert_eq!((spend.requests, spend.priced_requests), (3, 2));
+        assert!((spend.total_usd - 0.75).abs() < 1e-9);
+
+        spend.add("2026-10", Some(0.1));
+        assert_eq!(spend.month, "2026-10");
+        assert_eq!((spend.requests, spend.priced_requests), (1, 1));
+        assert!((spend.total_usd - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_burst_of_requests_schedules_one_save_that_reads_back() {
+        let path =
+            std::env::temp_dir().join(format!("sgt-model-spend-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut store = SpendStore::load(path.clone());
+        assert!(store.add("2026-10", Some(0.25)));
+        assert!(
+            !store.add("2026-10", Some(0.5)),
+            "the first save is pending"
+        );
+
+        let (save_path, snapshot) = store.take_save();
+        write(&save_path, &snapshot).unwrap();
+        assert!(
+            store.add("2026-10", None),
+            "the next change schedules again"
+        );
+        assert_eq!(SpendStore::load(path.clone()).spend, snapshot);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn free_endpoints_are_priced_and_unknown_ones_are_not() {
+        assert_eq!(
+            estimate_usd(
+                "google-gtx",
+                "translate.googleapis.com/gtx",
+                4_000,
+                0,
+                4_000
+            ),
+            Some(0.0)
+        );
+        assert_eq!(estimate_usd("nobody", "no-such-model", 4_000, 1, 400), None);
+    }
+}